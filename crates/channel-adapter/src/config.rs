@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 
+use crate::dlp::DlpPolicy;
 use crate::types::ChatPlatform;
 
 /// Top-level configuration for a channel adapter instance.
@@ -11,6 +12,10 @@ pub struct ChannelConfig {
     pub platform: ChatPlatform,
     /// Platform-specific configuration.
     pub settings: PlatformSettings,
+    /// Data classification applied to inbound messages and agent responses.
+    /// `None` passes traffic through unchanged.
+    #[serde(default)]
+    pub dlp: Option<DlpPolicy>,
 }
 
 /// Platform-specific settings, determined by the `platform` field.
@@ -245,6 +250,7 @@ mod tests {
                 signing_secret: Some("secret123".to_string()),
                 ..SlackConfig::default()
             }),
+            dlp: None,
         };
         let json = serde_json::to_string_pretty(&cfg).unwrap();
         assert!(json.contains("ops-slack"));
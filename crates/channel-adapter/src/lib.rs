@@ -7,6 +7,7 @@
 //! # Community (default)
 //! - Slack adapter with Events API + slash commands
 //! - Basic structured logging
+//! - Built-in data classification (redact/block) for channel `data_classification` rules
 //! - `ChannelAdapter` trait for building custom adapters
 //!
 //! # Enterprise
@@ -15,6 +16,7 @@
 //! - `EnterpriseChannelHooks` trait for policy, DLP, and crypto audit (feature: `enterprise-hooks`)
 
 pub mod config;
+pub mod dlp;
 pub mod error;
 pub mod logging;
 pub mod manager;
@@ -25,6 +27,7 @@ pub mod adapters;
 
 // Re-export core types
 pub use config::{ChannelConfig, PlatformSettings, SlackConfig};
+pub use dlp::{DlpAction, DlpCategory, DlpPolicy, DlpScan};
 pub use error::ChannelAdapterError;
 pub use logging::BasicInteractionLogger;
 pub use manager::{AgentInvoker, ChannelAdapterManager};
@@ -75,6 +75,9 @@ impl BasicInteractionLogger {
             success,
             duration_ms,
             error,
+            redacted: false,
+            redaction_count: 0,
+            blocked: Vec::new(),
         }
     }
 
@@ -1,6 +1,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::dlp::DlpCategory;
+
 /// Supported chat platforms.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -97,6 +99,16 @@ pub struct InteractionLog {
     pub success: bool,
     pub duration_ms: Option<u64>,
     pub error: Option<String>,
+    /// Whether data classification redacted anything in the message or
+    /// the response.
+    #[serde(default)]
+    pub redacted: bool,
+    /// Number of spans redacted across the message and the response.
+    #[serde(default)]
+    pub redaction_count: usize,
+    /// Categories that caused the message or response to be blocked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked: Vec<DlpCategory>,
 }
 
 /// Types of interaction actions logged.
@@ -191,6 +203,9 @@ mod tests {
             success: true,
             duration_ms: Some(150),
             error: None,
+            redacted: false,
+            redaction_count: 0,
+            blocked: Vec::new(),
         };
         let json = serde_json::to_string(&log).unwrap();
         assert!(json.contains("\"action\":\"invoke\""));
@@ -9,6 +9,7 @@ use std::time::Duration;
 use tree_sitter::{Language, Node, Parser, Tree};
 
 pub mod format;
+pub mod semantic;
 
 /// Canonical file extension for Symbiont agent definitions.
 pub const SYMBI_EXTENSION: &str = "symbi";
@@ -53,6 +54,10 @@ pub enum SandboxTier {
     Firecracker,
     /// E2B.dev cloud sandbox
     E2B,
+    /// Rootless Linux namespace sandbox
+    Namespace,
+    /// Embedded WebAssembly (WASI) sandbox
+    Wasm,
 }
 
 impl std::fmt::Display for SandboxTier {
@@ -62,6 +67,8 @@ impl std::fmt::Display for SandboxTier {
             SandboxTier::GVisor => write!(f, "gvisor"),
             SandboxTier::Firecracker => write!(f, "firecracker"),
             SandboxTier::E2B => write!(f, "e2b"),
+            SandboxTier::Namespace => write!(f, "namespace"),
+            SandboxTier::Wasm => write!(f, "wasm"),
         }
     }
 }
@@ -179,9 +186,11 @@ impl WithBlock {
             "gvisor" | "tier2" => Ok(SandboxTier::GVisor),
             "firecracker" | "tier3" => Ok(SandboxTier::Firecracker),
             "e2b" => Ok(SandboxTier::E2B),
+            "namespace" => Ok(SandboxTier::Namespace),
+            "wasm" | "wasi" => Ok(SandboxTier::Wasm),
             _ => Err(format!(
                 "Invalid sandbox tier: {}. Valid options are: docker (tier1), \
-                 gvisor (tier2), firecracker (tier3), e2b",
+                 gvisor (tier2), firecracker (tier3), e2b, namespace, wasm",
                 value
             )),
         }
@@ -552,123 +561,125 @@ pub fn extract_memory_definitions(
             ));
         }
         if node.kind() == "memory_definition" {
-            // Child 0 = "memory" keyword, Child 1 = identifier, then "{", properties, "}"
-            let name_node = node
-                .child(1u32)
-                .ok_or_else(|| "memory_definition missing name".to_string())?;
-            let name = source[name_node.start_byte()..name_node.end_byte()].to_string();
-            let mut mem = MemoryDefinition::new(name);
+            memories.push(parse_memory_definition(node, source)?);
+        }
 
-            for i in 0u32..node.child_count() as u32 {
-                if let Some(child) = node.child(i) {
-                    match child.kind() {
-                        "memory_property" => {
-                            // memory_property: identifier value (space-separated, NO colon)
-                            // child(0) = key, child(1) = value
-                            if let (Some(key_node), Some(val_node)) =
-                                (child.child(0u32), child.child(1u32))
-                            {
-                                let key =
-                                    source[key_node.start_byte()..key_node.end_byte()].to_string();
-                                let raw_value =
-                                    source[val_node.start_byte()..val_node.end_byte()].to_string();
-                                let value = raw_value.trim_matches('"').to_string();
+        // Recurse into children.
+        for i in 0u32..node.child_count() as u32 {
+            if let Some(child) = node.child(i) {
+                traverse_for_memories(child, source, memories, depth + 1)?;
+            }
+        }
 
-                                match key.as_str() {
-                                    "store" => match value.to_lowercase().as_str() {
-                                        "markdown" => mem.store = MemoryStoreType::Markdown,
-                                        _ => {
-                                            return Err(format!(
-                                                "memory '{}': unknown store type '{}'",
-                                                mem.name, value
-                                            ));
-                                        }
-                                    },
-                                    "path" => mem.path = PathBuf::from(value),
-                                    "retention" => {
-                                        mem.retention =
-                                            humantime::parse_duration(&value).map_err(|e| {
-                                                format!(
-                                                    "memory '{}': invalid retention '{}': {}",
-                                                    mem.name, value, e
-                                                )
-                                            })?;
-                                    }
-                                    _ => {
-                                        // Unknown properties are silently ignored for forward compat.
-                                    }
+        Ok(())
+    }
+
+    traverse_for_memories(root_node, source, &mut memories, 0)?;
+    Ok(memories)
+}
+
+/// Build a `MemoryDefinition` from a single `memory_definition` node.
+pub(crate) fn parse_memory_definition(
+    node: Node,
+    source: &str,
+) -> Result<MemoryDefinition, String> {
+    // Child 0 = "memory" keyword, Child 1 = identifier, then "{", properties, "}"
+    let name_node = node
+        .child(1u32)
+        .ok_or_else(|| "memory_definition missing name".to_string())?;
+    let name = source[name_node.start_byte()..name_node.end_byte()].to_string();
+    let mut mem = MemoryDefinition::new(name);
+
+    for i in 0u32..node.child_count() as u32 {
+        if let Some(child) = node.child(i) {
+            match child.kind() {
+                "memory_property" => {
+                    // memory_property: identifier value (space-separated, NO colon)
+                    // child(0) = key, child(1) = value
+                    if let (Some(key_node), Some(val_node)) = (child.child(0u32), child.child(1u32))
+                    {
+                        let key = source[key_node.start_byte()..key_node.end_byte()].to_string();
+                        let raw_value =
+                            source[val_node.start_byte()..val_node.end_byte()].to_string();
+                        let value = raw_value.trim_matches('"').to_string();
+
+                        match key.as_str() {
+                            "store" => match value.to_lowercase().as_str() {
+                                "markdown" => mem.store = MemoryStoreType::Markdown,
+                                _ => {
+                                    return Err(format!(
+                                        "memory '{}': unknown store type '{}'",
+                                        mem.name, value
+                                    ));
                                 }
+                            },
+                            "path" => mem.path = PathBuf::from(value),
+                            "retention" => {
+                                mem.retention = humantime::parse_duration(&value).map_err(|e| {
+                                    format!(
+                                        "memory '{}': invalid retention '{}': {}",
+                                        mem.name, value, e
+                                    )
+                                })?;
+                            }
+                            _ => {
+                                // Unknown properties are silently ignored for forward compat.
                             }
                         }
-                        "memory_search_block" => {
-                            // memory_search_block: 'search' '{' repeat(memory_search_property) '}'
-                            let mut search = MemorySearchConfig::default();
-                            for j in 0u32..child.child_count() as u32 {
-                                if let Some(prop_node) = child.child(j) {
-                                    if prop_node.kind() == "memory_search_property" {
-                                        // memory_search_property: identifier value (space-separated)
-                                        // child(0) = key, child(1) = value
-                                        if let (Some(key_node), Some(val_node)) =
-                                            (prop_node.child(0u32), prop_node.child(1u32))
-                                        {
-                                            let key = source
-                                                [key_node.start_byte()..key_node.end_byte()]
-                                                .to_string();
-                                            let raw_value = source
-                                                [val_node.start_byte()..val_node.end_byte()]
-                                                .to_string();
+                    }
+                }
+                "memory_search_block" => {
+                    // memory_search_block: 'search' '{' repeat(memory_search_property) '}'
+                    let mut search = MemorySearchConfig::default();
+                    for j in 0u32..child.child_count() as u32 {
+                        if let Some(prop_node) = child.child(j) {
+                            if prop_node.kind() == "memory_search_property" {
+                                // memory_search_property: identifier value (space-separated)
+                                // child(0) = key, child(1) = value
+                                if let (Some(key_node), Some(val_node)) =
+                                    (prop_node.child(0u32), prop_node.child(1u32))
+                                {
+                                    let key = source[key_node.start_byte()..key_node.end_byte()]
+                                        .to_string();
+                                    let raw_value = source
+                                        [val_node.start_byte()..val_node.end_byte()]
+                                        .to_string();
 
-                                            match key.as_str() {
-                                                "vector_weight" => {
-                                                    search.vector_weight = raw_value
-                                                        .parse::<f64>()
-                                                        .map_err(|e| {
-                                                            format!(
-                                                                "memory: invalid vector_weight '{}': {}",
-                                                                raw_value, e
-                                                            )
-                                                        })?;
-                                                }
-                                                "keyword_weight" => {
-                                                    search.keyword_weight = raw_value
-                                                        .parse::<f64>()
-                                                        .map_err(|e| {
-                                                            format!(
-                                                                "memory: invalid keyword_weight '{}': {}",
-                                                                raw_value, e
-                                                            )
-                                                        })?;
-                                                }
-                                                _ => {
-                                                    // Unknown search properties ignored.
-                                                }
-                                            }
+                                    match key.as_str() {
+                                        "vector_weight" => {
+                                            search.vector_weight =
+                                                raw_value.parse::<f64>().map_err(|e| {
+                                                    format!(
+                                                        "memory: invalid vector_weight '{}': {}",
+                                                        raw_value, e
+                                                    )
+                                                })?;
+                                        }
+                                        "keyword_weight" => {
+                                            search.keyword_weight =
+                                                raw_value.parse::<f64>().map_err(|e| {
+                                                    format!(
+                                                        "memory: invalid keyword_weight '{}': {}",
+                                                        raw_value, e
+                                                    )
+                                                })?;
+                                        }
+                                        _ => {
+                                            // Unknown search properties ignored.
                                         }
                                     }
                                 }
                             }
-                            mem.search = Some(search);
                         }
-                        _ => {}
                     }
+                    mem.search = Some(search);
                 }
-            }
-
-            memories.push(mem);
-        }
-
-        // Recurse into children.
-        for i in 0u32..node.child_count() as u32 {
-            if let Some(child) = node.child(i) {
-                traverse_for_memories(child, source, memories, depth + 1)?;
+                _ => {}
             }
         }
-
-        Ok(())
     }
 
-    traverse_for_memories(root_node, source, &mut memories, 0)?;
-    Ok(memories)
+    Ok(mem)
 }
 
 /// Webhook provider type.
@@ -1226,15 +1237,43 @@ pub struct DslDiagnostic {
     pub snippet: String,
     /// Nesting depth at which the error was found
     pub depth: usize,
+    /// Explanation for semantic errors. Syntax errors leave this unset and
+    /// are described by their snippet alone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl DslDiagnostic {
+    /// Diagnostic spanning `node`, with an explanatory message.
+    pub fn at_node(node: Node, source: &str, depth: usize, message: impl Into<String>) -> Self {
+        let start = node.start_position();
+        let end = node.end_position();
+        Self {
+            start_line: start.row + 1,
+            start_col: start.column + 1,
+            end_line: end.row + 1,
+            end_col: end.column + 1,
+            snippet: source[node.start_byte()..node.end_byte()].to_string(),
+            depth,
+            message: Some(message.into()),
+        }
+    }
 }
 
 impl std::fmt::Display for DslDiagnostic {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "ERROR at {}:{}-{}:{}: '{}'",
-            self.start_line, self.start_col, self.end_line, self.end_col, self.snippet
-        )
+        match &self.message {
+            Some(message) => write!(
+                f,
+                "ERROR at {}:{}-{}:{}: {}",
+                self.start_line, self.start_col, self.end_line, self.end_col, message
+            ),
+            None => write!(
+                f,
+                "ERROR at {}:{}-{}:{}: '{}'",
+                self.start_line, self.start_col, self.end_line, self.end_col, self.snippet
+            ),
+        }
     }
 }
 
@@ -1257,6 +1296,7 @@ fn collect_errors(node: Node, source: &str, depth: usize, diagnostics: &mut Vec<
             end_col: end.column + 1,
             snippet: text.to_string(),
             depth,
+            message: None,
         });
     }
 
@@ -1348,6 +1388,12 @@ mod tests {
             Ok(SandboxTier::Firecracker)
         );
         assert_eq!(WithBlock::parse_sandbox_tier("e2b"), Ok(SandboxTier::E2B));
+        assert_eq!(
+            WithBlock::parse_sandbox_tier("namespace"),
+            Ok(SandboxTier::Namespace)
+        );
+        assert_eq!(WithBlock::parse_sandbox_tier("wasm"), Ok(SandboxTier::Wasm));
+        assert_eq!(WithBlock::parse_sandbox_tier("WASI"), Ok(SandboxTier::Wasm));
 
         // Tier-number aliases (case-insensitive) used by docs + example agents.
         assert_eq!(
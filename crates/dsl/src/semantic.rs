@@ -0,0 +1,1254 @@
+//! Semantic analysis across a set of Symbi files.
+//!
+//! The extractors in the crate root read each block in isolation. This module
+//! builds a [`SymbolTable`] over every file in a [`Workspace`] (normally the
+//! project's `agents/` directory) and reports what the parser cannot see:
+//!
+//! - schedule `agent`, channel `default_agent`, and webhook `agent` must name
+//!   an agent, either by its declared name or by its file stem;
+//! - schedule `policy` must name a policy declared somewhere in the workspace;
+//! - schedule `deliver` must be a well-formed delivery target;
+//! - calls to declared functions must match their parameter count, names,
+//!   and types;
+//! - `return` values must match the enclosing function's or agent's `-> type`;
+//! - `memory` blocks must have a valid store, path, retention, and search
+//!   weights, belong to a file that declares an agent, and agree with any
+//!   same-named block elsewhere in the workspace.
+//!
+//! Types are only compared when both sides are known: a built-in, or a type
+//! declared with `type` in the workspace. Agents routinely name result types
+//! they never declare (`-> ReviewDecision`), and those are treated as opaque
+//! rather than reported.
+
+use crate::{
+    is_symbi_file, parse_dsl, parse_memory_definition, strip_symbi_extension, DslDiagnostic,
+    MemoryDefinition, MAX_AST_DEPTH,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Tree};
+
+/// Built-in scalar types.
+const BUILTIN_TYPES: &[&str] = &["String", "int", "float", "bool"];
+
+/// Upper bound on `type A = B` alias chains, so alias cycles terminate.
+const MAX_ALIAS_DEPTH: usize = 16;
+
+/// Operators whose result is always `bool`.
+const BOOLEAN_OPERATORS: &[&str] = &[
+    "||", "&&", "==", "!=", "in", "<", ">", "<=", ">=", "!", "not",
+];
+
+/// Arithmetic operators; the result type follows the operands.
+const ARITHMETIC_OPERATORS: &[&str] = &["+", "-", "*", "/", "%"];
+
+/// A declared function or agent parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: String,
+    /// Declared type, with whitespace removed (`Map<String,int>`).
+    pub ty: String,
+}
+
+/// Signature of a `function` definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSignature {
+    pub name: String,
+    /// File the function is declared in.
+    pub path: PathBuf,
+    /// Agent the function is declared inside, or `None` at the top level.
+    pub agent: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<String>,
+}
+
+/// A `type` definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDeclaration {
+    pub name: String,
+    pub path: PathBuf,
+    /// Target of `type A = B`; `None` for struct types.
+    pub alias_of: Option<String>,
+}
+
+/// A `memory` block and the file that declares it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryDeclaration {
+    pub path: PathBuf,
+    pub definition: MemoryDefinition,
+}
+
+/// Everything declared across a workspace.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    /// Agents by declared name and by file stem, mapped to their file.
+    pub agents: BTreeMap<String, PathBuf>,
+    /// Policy names, including agent-level and channel policies.
+    pub policies: BTreeSet<String>,
+    /// Functions by name. Agent-scoped functions may share a name.
+    pub functions: BTreeMap<String, Vec<FunctionSignature>>,
+    pub types: BTreeMap<String, TypeDeclaration>,
+    /// Memory blocks by name. Blocks that fail to parse are left out.
+    pub memories: BTreeMap<String, Vec<MemoryDeclaration>>,
+}
+
+impl SymbolTable {
+    /// Resolve aliases and return the type if it is known: a built-in or a
+    /// declared struct type. Undeclared and generic types resolve to `None`.
+    pub fn resolve_type(&self, ty: &str) -> Option<String> {
+        let mut current = normalize_type(ty);
+        for _ in 0..MAX_ALIAS_DEPTH {
+            if BUILTIN_TYPES.contains(&current.as_str()) {
+                return Some(current);
+            }
+            match self.types.get(&current)?.alias_of.as_deref() {
+                Some(target) => current = normalize_type(target),
+                None => return Some(current),
+            }
+        }
+        None
+    }
+
+    /// Whether a value of type `actual` may be used where `expected` is
+    /// declared. Unknown types are always compatible; `int` widens to `float`.
+    pub fn is_compatible(&self, expected: &str, actual: &str) -> bool {
+        match (self.resolve_type(expected), self.resolve_type(actual)) {
+            (Some(expected), Some(actual)) => {
+                expected == actual || (expected == "float" && actual == "int")
+            }
+            _ => true,
+        }
+    }
+
+    /// The function a call to `name` resolves to from inside `agent` in
+    /// `path`: a function declared in that agent first, otherwise the only
+    /// top-level function of that name. Ambiguous names resolve to `None`.
+    pub fn resolve_function(
+        &self,
+        name: &str,
+        path: &Path,
+        agent: Option<&str>,
+    ) -> Option<&FunctionSignature> {
+        let candidates = self.functions.get(name)?;
+        if let Some(agent) = agent {
+            if let Some(local) = candidates
+                .iter()
+                .find(|f| f.path == path && f.agent.as_deref() == Some(agent))
+            {
+                return Some(local);
+            }
+        }
+        let mut top_level = candidates.iter().filter(|f| f.agent.is_none());
+        match (top_level.next(), top_level.next()) {
+            (Some(only), None) => Some(only),
+            _ => None,
+        }
+    }
+}
+
+/// Check a schedule `deliver` target.
+///
+/// Accepted forms mirror the scheduler's delivery channels: `stdout`,
+/// `log_file://<path>`, `http(s)://<host>/...`, `slack://<channel>`,
+/// `email://<address>[,<address>]` or `mailto:<address>`,
+/// `channel://<adapter>/<channel_id>`, and `custom://<handler>`.
+pub fn validate_deliver_target(target: &str) -> Result<(), String> {
+    if target == "stdout" {
+        return Ok(());
+    }
+    if let Some(addresses) = target.strip_prefix("mailto:") {
+        return validate_addresses(addresses);
+    }
+    let (scheme, rest) = target
+        .split_once("://")
+        .ok_or_else(|| format!("expected `<scheme>://<target>`, got `{}`", target))?;
+    match scheme {
+        "stdout" if rest.is_empty() => Ok(()),
+        "stdout" => Err("`stdout://` takes no target".to_string()),
+        "http" | "https" => match rest.split('/').next() {
+            Some(host) if !host.is_empty() => Ok(()),
+            _ => Err(format!("`{}` is missing a host", target)),
+        },
+        "email" => validate_addresses(rest),
+        "channel" => match rest.split_once('/') {
+            Some((adapter, channel)) if !adapter.is_empty() && !channel.is_empty() => Ok(()),
+            _ => Err("expected `channel://<adapter>/<channel_id>`".to_string()),
+        },
+        "log_file" | "slack" | "custom" if !rest.is_empty() => Ok(()),
+        "log_file" | "slack" | "custom" => Err(format!("`{}://` is missing a target", scheme)),
+        other => Err(format!(
+            "unknown delivery scheme `{}` (expected stdout, log_file, http, https, slack, \
+             email, mailto, channel, or custom)",
+            other
+        )),
+    }
+}
+
+/// Check a memory `path`: a non-empty directory path without `..`
+/// components, so a memory block cannot reach outside its data directory.
+pub fn validate_memory_path(path: &str) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("path is empty".to_string());
+    }
+    if path.contains('\0') {
+        return Err("path contains a NUL byte".to_string());
+    }
+    if Path::new(path)
+        .components()
+        .any(|c| c == std::path::Component::ParentDir)
+    {
+        return Err(format!("`{}` must not contain `..` components", path));
+    }
+    Ok(())
+}
+
+fn validate_addresses(addresses: &str) -> Result<(), String> {
+    for address in addresses.split(',').map(str::trim) {
+        match address.split_once('@') {
+            Some((user, domain)) if !user.is_empty() && !domain.is_empty() => {}
+            _ => return Err(format!("`{}` is not an email address", address)),
+        }
+    }
+    Ok(())
+}
+
+/// A parsed source file in a workspace.
+struct WorkspaceFile {
+    path: PathBuf,
+    source: String,
+    tree: Option<Tree>,
+}
+
+/// A set of Symbi files analysed together.
+#[derive(Default)]
+pub struct Workspace {
+    files: Vec<WorkspaceFile>,
+}
+
+impl Workspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every `.symbi` / `.dsl` file directly inside `dir`.
+    pub fn from_dir(dir: &Path) -> std::io::Result<Self> {
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| is_symbi_file(path))
+            .collect();
+        paths.sort();
+
+        let mut workspace = Self::new();
+        for path in paths {
+            let source = std::fs::read_to_string(&path)?;
+            workspace.add_file(path, source);
+        }
+        Ok(workspace)
+    }
+
+    /// Add a file, replacing any earlier file with the same path.
+    pub fn add_file(&mut self, path: impl Into<PathBuf>, source: impl Into<String>) {
+        let path = path.into();
+        let source = source.into();
+        let tree = parse_dsl(&source).ok();
+        let file = WorkspaceFile { path, source, tree };
+        match self.files.iter_mut().find(|f| f.path == file.path) {
+            Some(existing) => *existing = file,
+            None => self.files.push(file),
+        }
+    }
+
+    /// Paths of all files, in insertion order.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(|f| f.path.as_path())
+    }
+
+    /// Collect declarations from every file.
+    pub fn symbols(&self) -> SymbolTable {
+        let mut table = SymbolTable::default();
+        for file in &self.files {
+            if let Some(tree) = &file.tree {
+                collect_symbols(tree.root_node(), file, None, &mut table, 0);
+            }
+        }
+        table
+    }
+
+    /// Semantic diagnostics for one file, resolved against the whole
+    /// workspace. Returns nothing for paths not in the workspace.
+    pub fn check_file(&self, path: &Path) -> Vec<DslDiagnostic> {
+        let table = self.symbols();
+        self.files
+            .iter()
+            .find(|f| f.path == path)
+            .map(|file| check(file, &table))
+            .unwrap_or_default()
+    }
+
+    /// Semantic diagnostics for every file that has any.
+    pub fn check(&self) -> BTreeMap<PathBuf, Vec<DslDiagnostic>> {
+        let table = self.symbols();
+        self.files
+            .iter()
+            .map(|file| (file.path.clone(), check(file, &table)))
+            .filter(|(_, diagnostics)| !diagnostics.is_empty())
+            .collect()
+    }
+}
+
+fn collect_symbols(
+    node: Node,
+    file: &WorkspaceFile,
+    agent: Option<&str>,
+    table: &mut SymbolTable,
+    depth: usize,
+) {
+    if depth > MAX_AST_DEPTH {
+        tracing::warn!(
+            "DSL symbol collection aborted: depth {} exceeds MAX_AST_DEPTH {}",
+            depth,
+            MAX_AST_DEPTH
+        );
+        return;
+    }
+    let source = file.source.as_str();
+    let name = name_of(node, source);
+    let mut agent = agent;
+
+    match (node.kind(), name) {
+        ("agent_definition", Some(name)) => {
+            table.agents.insert(name.to_string(), file.path.clone());
+            if let Some(stem) = file
+                .path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(strip_symbi_extension)
+            {
+                table
+                    .agents
+                    .entry(stem.to_string())
+                    .or_insert_with(|| file.path.clone());
+            }
+            agent = Some(name);
+        }
+        ("policy_definition" | "channel_policy_block", Some(name)) => {
+            table.policies.insert(name.to_string());
+        }
+        ("function_definition", Some(name)) => {
+            table
+                .functions
+                .entry(name.to_string())
+                .or_default()
+                .push(FunctionSignature {
+                    name: name.to_string(),
+                    path: file.path.clone(),
+                    agent: agent.map(str::to_string),
+                    parameters: parameters(node, source),
+                    return_type: return_type(node, source),
+                });
+        }
+        ("type_definition", Some(name)) => {
+            let alias_of = child_of_kind(node, "type_spec")
+                .and_then(|spec| spec.child(0))
+                .filter(|target| target.kind() == "identifier")
+                .map(|target| text(target, source).to_string());
+            table.types.insert(
+                name.to_string(),
+                TypeDeclaration {
+                    name: name.to_string(),
+                    path: file.path.clone(),
+                    alias_of,
+                },
+            );
+        }
+        ("memory_definition", Some(name)) => {
+            if let Ok(definition) = parse_memory_definition(node, source) {
+                table
+                    .memories
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(MemoryDeclaration {
+                        path: file.path.clone(),
+                        definition,
+                    });
+            }
+        }
+        _ => {}
+    }
+
+    for child in children(node) {
+        collect_symbols(child, file, agent, table, depth + 1);
+    }
+}
+
+/// The function or agent whose body is being checked.
+#[derive(Clone, Default)]
+struct Scope {
+    agent: Option<String>,
+    /// `("function" | "agent", name, declared return type)`.
+    owner: Option<(&'static str, String, String)>,
+    /// Known types of parameters and `let` bindings.
+    locals: HashMap<String, String>,
+}
+
+struct Checker<'a> {
+    file: &'a WorkspaceFile,
+    table: &'a SymbolTable,
+    /// Name of the first `memory` block seen in this file.
+    memory: Option<String>,
+    diagnostics: Vec<DslDiagnostic>,
+}
+
+fn check(file: &WorkspaceFile, table: &SymbolTable) -> Vec<DslDiagnostic> {
+    let Some(tree) = &file.tree else {
+        return Vec::new();
+    };
+    let mut checker = Checker {
+        file,
+        table,
+        memory: None,
+        diagnostics: Vec::new(),
+    };
+    checker.visit(tree.root_node(), &Scope::default(), 0);
+    checker.diagnostics
+}
+
+impl Checker<'_> {
+    fn source(&self) -> &str {
+        &self.file.source
+    }
+
+    fn report(&mut self, node: Node, depth: usize, message: String) {
+        self.diagnostics
+            .push(DslDiagnostic::at_node(node, self.source(), depth, message));
+    }
+
+    fn visit(&mut self, node: Node, scope: &Scope, depth: usize) {
+        if depth > MAX_AST_DEPTH {
+            tracing::warn!(
+                "DSL semantic check aborted: depth {} exceeds MAX_AST_DEPTH {}",
+                depth,
+                MAX_AST_DEPTH
+            );
+            return;
+        }
+        let entered;
+        let scope = match node.kind() {
+            "agent_definition" | "function_definition" => {
+                entered = self.enter(node, scope);
+                &entered
+            }
+            "schedule_definition" => {
+                self.check_schedule(node, depth);
+                scope
+            }
+            "channel_definition" => {
+                self.check_agent_reference(node, "channel", "default_agent", depth);
+                scope
+            }
+            "webhook_definition" => {
+                self.check_agent_reference(node, "webhook", "agent", depth);
+                scope
+            }
+            "memory_definition" => {
+                self.check_memory(node, depth);
+                scope
+            }
+            "call_expression" => {
+                self.check_call(node, scope, depth);
+                scope
+            }
+            "return_statement" => {
+                self.check_return(node, scope, depth);
+                scope
+            }
+            _ => scope,
+        };
+        for child in children(node) {
+            self.visit(child, scope, depth + 1);
+        }
+    }
+
+    /// Scope for the body of an agent or function: its parameters plus the
+    /// `let` bindings whose type can be inferred.
+    fn enter(&self, node: Node, outer: &Scope) -> Scope {
+        let source = self.source();
+        let name = name_of(node, source).unwrap_or_default().to_string();
+        let is_agent = node.kind() == "agent_definition";
+
+        let mut scope = Scope {
+            agent: if is_agent {
+                Some(name.clone())
+            } else {
+                outer.agent.clone()
+            },
+            owner: return_type(node, source).map(|ty| {
+                let kind = if is_agent { "agent" } else { "function" };
+                (kind, name, ty)
+            }),
+            locals: HashMap::new(),
+        };
+        for param in parameters(node, source) {
+            scope.locals.insert(param.name, param.ty);
+        }
+        for child in children(node) {
+            self.bind_lets(child, &mut scope, 0);
+        }
+        scope
+    }
+
+    fn bind_lets(&self, node: Node, scope: &mut Scope, depth: usize) {
+        if depth > MAX_AST_DEPTH || node.kind() == "function_definition" {
+            return;
+        }
+        if node.kind() == "let_statement" {
+            if let (Some(name), Some(value)) = (node.child(1), child_of_kind(node, "expression")) {
+                if let Some(ty) = self.infer(value, scope) {
+                    scope
+                        .locals
+                        .insert(text(name, self.source()).to_string(), ty);
+                }
+            }
+        }
+        for child in children(node) {
+            self.bind_lets(child, scope, depth + 1);
+        }
+    }
+
+    fn check_schedule(&mut self, node: Node, depth: usize) {
+        let source = self.file.source.as_str();
+        let schedule = name_of(node, source).unwrap_or_default();
+        for (key, value) in properties(node, "schedule_property", source) {
+            let target = unquote(text(value, source));
+            match key {
+                "agent" if !self.table.agents.contains_key(target) => self.report(
+                    value,
+                    depth + 1,
+                    format!(
+                        "schedule `{}` references unknown agent `{}`",
+                        schedule, target
+                    ),
+                ),
+                "policy" if !self.table.policies.contains(target) => self.report(
+                    value,
+                    depth + 1,
+                    format!(
+                        "schedule `{}` references unknown policy `{}`",
+                        schedule, target
+                    ),
+                ),
+                "deliver" => {
+                    if let Err(reason) = validate_deliver_target(target) {
+                        self.report(
+                            value,
+                            depth + 1,
+                            format!(
+                                "schedule `{}` has an invalid deliver target: {}",
+                                schedule, reason
+                            ),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn check_agent_reference(&mut self, node: Node, block: &str, key: &str, depth: usize) {
+        let source = self.file.source.as_str();
+        let name = name_of(node, source).unwrap_or_default();
+        let property = format!("{}_property", block);
+        for (k, value) in properties(node, &property, source) {
+            let target = unquote(text(value, source));
+            if k == key && !self.table.agents.contains_key(target) {
+                self.report(
+                    value,
+                    depth + 1,
+                    format!("{} `{}` references unknown agent `{}`", block, name, target),
+                );
+            }
+        }
+    }
+
+    fn check_memory(&mut self, node: Node, depth: usize) {
+        let source = self.file.source.as_str();
+        let Some(name) = name_of(node, source) else {
+            return;
+        };
+
+        let mut seen = BTreeSet::new();
+        for (key, value) in properties(node, "memory_property", source) {
+            if !seen.insert(key) {
+                self.report(
+                    value,
+                    depth + 1,
+                    format!("memory `{}` sets `{}` more than once", name, key),
+                );
+                continue;
+            }
+            let raw = unquote(text(value, source));
+            let problem = match key {
+                "store" if !raw.eq_ignore_ascii_case("markdown") => Some(format!(
+                    "memory `{}` uses unknown store `{}` (expected markdown)",
+                    name, raw
+                )),
+                "path" => validate_memory_path(raw)
+                    .err()
+                    .map(|reason| format!("memory `{}` has an invalid path: {}", name, reason)),
+                "retention" => humantime::parse_duration(raw).err().map(|_| {
+                    format!(
+                        "memory `{}` has an invalid retention `{}` (expected a duration \
+                         such as `90d`)",
+                        name, raw
+                    )
+                }),
+                _ => None,
+            };
+            if let Some(message) = problem {
+                self.report(value, depth + 1, message);
+            }
+        }
+
+        if let Some(search) = child_of_kind(node, "memory_search_block") {
+            let mut weights = Vec::new();
+            for (key, value) in properties(search, "memory_search_property", source) {
+                if key != "vector_weight" && key != "keyword_weight" {
+                    continue;
+                }
+                let raw = text(value, source);
+                match raw.parse::<f64>() {
+                    Ok(weight) if (0.0..=1.0).contains(&weight) => weights.push(weight),
+                    _ => self.report(
+                        value,
+                        depth + 2,
+                        format!(
+                            "memory `{}` has an invalid `{}` `{}`: expected a number \
+                             between 0 and 1",
+                            name, key, raw
+                        ),
+                    ),
+                }
+            }
+            if !weights.is_empty() && weights.iter().all(|w| *w == 0.0) {
+                self.report(
+                    search,
+                    depth + 1,
+                    format!("memory `{}` search weights are all zero", name),
+                );
+            }
+        }
+
+        let path = &self.file.path;
+        if let Some(first) = &self.memory {
+            let message = format!(
+                "memory `{}` conflicts with memory `{}`: a file attaches at most one \
+                 memory block to its agent",
+                name, first
+            );
+            self.report(node, depth, message);
+        } else {
+            self.memory = Some(name.to_string());
+            if !self.table.agents.values().any(|p| p == path) {
+                self.report(
+                    node,
+                    depth,
+                    format!(
+                        "memory `{}` is not attached to an agent: declare an agent in this file",
+                        name
+                    ),
+                );
+            }
+        }
+
+        // Same-named blocks elsewhere must describe the same store.
+        let Ok(definition) = parse_memory_definition(node, source) else {
+            return;
+        };
+        let conflict = self.table.memories.get(name).and_then(|declarations| {
+            declarations
+                .iter()
+                .find(|d| &d.path != path && d.definition != definition)
+        });
+        if let Some(other) = conflict {
+            let message = format!(
+                "memory `{}` conflicts with its definition in {}",
+                name,
+                other.path.display()
+            );
+            self.report(node, depth, message);
+        }
+    }
+
+    fn check_call(&mut self, node: Node, scope: &Scope, depth: usize) {
+        let source = self.file.source.as_str();
+        let table = self.table;
+        let Some(callee) = node
+            .child(0)
+            .filter(|c| c.kind() == "value")
+            .and_then(|c| c.child(0))
+            .filter(|c| c.kind() == "identifier")
+        else {
+            return;
+        };
+        let name = text(callee, source).to_string();
+        let Some(function) = table.resolve_function(&name, &self.file.path, scope.agent.as_deref())
+        else {
+            return;
+        };
+
+        let arguments: Vec<Node> = children(node)
+            .into_iter()
+            .filter(|c| matches!(c.kind(), "expression" | "named_argument"))
+            .collect();
+        if arguments.len() != function.parameters.len() {
+            self.report(
+                node,
+                depth,
+                format!(
+                    "`{}` expects {} argument(s), found {}",
+                    name,
+                    function.parameters.len(),
+                    arguments.len()
+                ),
+            );
+            return;
+        }
+
+        let mut bound = vec![false; function.parameters.len()];
+        let mut next_positional = 0;
+        for argument in arguments {
+            let (index, value) = if argument.kind() == "named_argument" {
+                let arg_name = argument
+                    .child(0)
+                    .map(|n| text(n, source))
+                    .unwrap_or_default();
+                let Some(index) = function.parameters.iter().position(|p| p.name == arg_name)
+                else {
+                    self.report(
+                        argument,
+                        depth + 1,
+                        format!("`{}` has no parameter named `{}`", name, arg_name),
+                    );
+                    continue;
+                };
+                (index, child_of_kind(argument, "expression"))
+            } else {
+                while next_positional < bound.len() && bound[next_positional] {
+                    next_positional += 1;
+                }
+                (next_positional, Some(argument))
+            };
+            if index >= bound.len() {
+                continue;
+            }
+            let parameter = &function.parameters[index];
+            if bound[index] {
+                self.report(
+                    argument,
+                    depth + 1,
+                    format!(
+                        "parameter `{}` of `{}` is given more than once",
+                        parameter.name, name
+                    ),
+                );
+                continue;
+            }
+            bound[index] = true;
+
+            let Some(value) = value else { continue };
+            if let Some(actual) = self.infer(value, scope) {
+                if !table.is_compatible(&parameter.ty, &actual) {
+                    let message = format!(
+                        "argument `{}` of `{}` expects `{}`, found `{}`",
+                        parameter.name, name, parameter.ty, actual
+                    );
+                    self.report(value, depth + 1, message);
+                }
+            }
+        }
+    }
+
+    fn check_return(&mut self, node: Node, scope: &Scope, depth: usize) {
+        let Some((kind, owner, expected)) = &scope.owner else {
+            return;
+        };
+        let Some(value) = child_of_kind(node, "expression") else {
+            return;
+        };
+        if let Some(actual) = self.infer(value, scope) {
+            if !self.table.is_compatible(expected, &actual) {
+                let message = format!(
+                    "{} `{}` returns `{}`, found `{}`",
+                    kind, owner, expected, actual
+                );
+                self.report(value, depth + 1, message);
+            }
+        }
+    }
+
+    /// Best-effort static type of an expression, or `None` when unknown.
+    fn infer(&self, node: Node, scope: &Scope) -> Option<String> {
+        let source = self.source();
+        match node.kind() {
+            // Binary and unary operators are flattened into the expression
+            // node by the grammar's hidden precedence rules.
+            "expression" => {
+                let nodes = children(node);
+                let operators: Vec<&str> = nodes
+                    .iter()
+                    .filter(|n| !n.is_named())
+                    .map(|n| n.kind())
+                    .collect();
+                let operands: Vec<Node> = nodes
+                    .into_iter()
+                    .filter(|n| n.is_named() && n.kind() != "comment")
+                    .collect();
+
+                if operators.iter().any(|op| BOOLEAN_OPERATORS.contains(op)) {
+                    return Some("bool".to_string());
+                }
+                if !operators.iter().any(|op| ARITHMETIC_OPERATORS.contains(op)) {
+                    return match operands.as_slice() {
+                        [only] => self.infer(*only, scope),
+                        _ => None,
+                    };
+                }
+                let types = operands
+                    .iter()
+                    .map(|n| {
+                        self.infer(*n, scope)
+                            .and_then(|t| self.table.resolve_type(&t))
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                if operators.contains(&"+") && types.iter().any(|t| t == "String") {
+                    Some("String".to_string())
+                } else if types.iter().all(|t| t == "int") {
+                    Some("int".to_string())
+                } else if types.iter().all(|t| t == "int" || t == "float") {
+                    Some("float".to_string())
+                } else {
+                    None
+                }
+            }
+            "value" => {
+                let inner = node.child(0)?;
+                match inner.kind() {
+                    "string" => Some("String".to_string()),
+                    "number" if text(inner, source).contains('.') => Some("float".to_string()),
+                    "number" => Some("int".to_string()),
+                    "boolean" => Some("bool".to_string()),
+                    "identifier" => scope.locals.get(text(inner, source)).cloned(),
+                    _ => None,
+                }
+            }
+            "call_expression" => {
+                let callee = node.child(0)?.child(0)?;
+                if callee.kind() != "identifier" {
+                    return None;
+                }
+                self.table
+                    .resolve_function(
+                        text(callee, source),
+                        &self.file.path,
+                        scope.agent.as_deref(),
+                    )?
+                    .return_type
+                    .clone()
+            }
+            "type_literal" => node.child(0).map(|n| text(n, source).to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// `(key, value)` pairs of the `kind` property children of `node`. The value
+/// is the first named node after the key, so both `key: value` and
+/// `key value` forms are handled.
+fn properties<'t, 's>(node: Node<'t>, kind: &str, source: &'s str) -> Vec<(&'s str, Node<'t>)> {
+    children(node)
+        .into_iter()
+        .filter(|c| c.kind() == kind)
+        .filter_map(|property| {
+            let mut named = children(property).into_iter().filter(|n| n.is_named());
+            let key = named.next()?;
+            let value = named.next()?;
+            Some((text(key, source), value))
+        })
+        .collect()
+}
+
+fn parameters(node: Node, source: &str) -> Vec<Parameter> {
+    children(node)
+        .into_iter()
+        .filter(|c| c.kind() == "parameter")
+        .filter_map(|p| {
+            Some(Parameter {
+                name: text(p.child(0)?, source).to_string(),
+                ty: normalize_type(text(p.child(2)?, source)),
+            })
+        })
+        .collect()
+}
+
+/// The `-> type` of a function or agent. Parameter types are nested in
+/// `parameter` nodes, so the only direct `type` child is the return type.
+fn return_type(node: Node, source: &str) -> Option<String> {
+    child_of_kind(node, "type").map(|t| normalize_type(text(t, source)))
+}
+
+fn normalize_type(ty: &str) -> String {
+    ty.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+fn name_of<'s>(node: Node, source: &'s str) -> Option<&'s str> {
+    child_of_kind(node, "identifier").map(|n| text(n, source))
+}
+
+fn child_of_kind<'t>(node: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    children(node).into_iter().find(|c| c.kind() == kind)
+}
+
+fn children(node: Node) -> Vec<Node> {
+    (0u32..node.child_count() as u32)
+        .filter_map(|i| node.child(i))
+        .collect()
+}
+
+fn text<'s>(node: Node, source: &'s str) -> &'s str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REVIEWER: &str = r#"
+type Score = int
+type Report = {
+    score: int,
+}
+
+function weigh(value: int, factor: float) -> Score {
+    return value;
+}
+
+agent reviewer(input: String) -> Report {
+    policy review_policy {
+        allow: read(input) if true
+    }
+
+    with sandbox = "docker" {
+        let base = weigh(1, 2.0);
+        return Report { score: base };
+    }
+}
+"#;
+
+    fn workspace(files: &[(&str, &str)]) -> Workspace {
+        let mut workspace = Workspace::new();
+        for (path, source) in files {
+            workspace.add_file(*path, *source);
+        }
+        workspace
+    }
+
+    fn messages(workspace: &Workspace, path: &str) -> Vec<String> {
+        workspace
+            .check_file(Path::new(path))
+            .into_iter()
+            .map(|d| d.message.unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn test_symbol_table_spans_files() {
+        let ws = workspace(&[
+            ("agents/review.symbi", REVIEWER),
+            ("agents/other.symbi", "function helper(x: String) {\n}\n"),
+        ]);
+        let table = ws.symbols();
+        assert!(table.agents.contains_key("reviewer"));
+        assert!(table.agents.contains_key("review"), "file stem alias");
+        assert!(table.policies.contains("review_policy"));
+        assert_eq!(table.functions["weigh"][0].parameters.len(), 2);
+        assert_eq!(
+            table.functions["helper"][0].path,
+            Path::new("agents/other.symbi")
+        );
+        assert_eq!(table.resolve_type("Score").as_deref(), Some("int"));
+        assert_eq!(table.resolve_type("Report").as_deref(), Some("Report"));
+        assert_eq!(table.resolve_type("Undeclared"), None);
+        assert!(ws.check().is_empty(), "{:?}", ws.check());
+    }
+
+    #[test]
+    fn test_schedule_references_resolve_across_files() {
+        let schedules = r#"
+schedule nightly {
+    cron: "0 0 2 * * *",
+    agent: "reviewer",
+    policy: "review_policy",
+    deliver: "slack://reviews",
+}
+
+schedule broken {
+    cron: "0 0 3 * * *",
+    agent: "ghost",
+    policy: "missing",
+    deliver: "pager://oncall",
+}
+"#;
+        let ws = workspace(&[
+            ("agents/review.symbi", REVIEWER),
+            ("agents/schedules.symbi", schedules),
+        ]);
+        let found = ws.check_file(Path::new("agents/schedules.symbi"));
+        let messages: Vec<&str> = found.iter().filter_map(|d| d.message.as_deref()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "schedule `broken` references unknown agent `ghost`",
+                "schedule `broken` references unknown policy `missing`",
+                "schedule `broken` has an invalid deliver target: unknown delivery scheme \
+                 `pager` (expected stdout, log_file, http, https, slack, email, mailto, \
+                 channel, or custom)",
+            ]
+        );
+        assert_eq!(found[0].snippet, "\"ghost\"");
+        assert_eq!(found[0].start_line, 11);
+        assert_eq!(found[0].start_col, 12);
+    }
+
+    #[test]
+    fn test_channel_and_webhook_agents_must_exist() {
+        let source = r#"
+channel support {
+    platform: "slack",
+    default_agent: "reviewer",
+}
+
+webhook deploys {
+    path "/hooks/deploy"
+    agent nobody
+}
+"#;
+        let ws = workspace(&[
+            ("agents/review.symbi", REVIEWER),
+            ("agents/io.symbi", source),
+        ]);
+        assert_eq!(
+            messages(&ws, "agents/io.symbi"),
+            vec!["webhook `deploys` references unknown agent `nobody`"]
+        );
+    }
+
+    #[test]
+    fn test_call_arity_names_and_types() {
+        let source = r#"
+function weigh(value: int, factor: float) -> int {
+    return value;
+}
+
+function caller(text: String) -> int {
+    let a = weigh(1);
+    let b = weigh(text, 2);
+    let c = weigh(1, scale: 2.0);
+    let d = weigh(factor: 1.5, value: 3);
+    let e = weigh(1, value: 2);
+    return d;
+}
+"#;
+        let ws = workspace(&[("a.symbi", source)]);
+        assert_eq!(
+            messages(&ws, "a.symbi"),
+            vec![
+                "`weigh` expects 2 argument(s), found 1",
+                "argument `value` of `weigh` expects `int`, found `String`",
+                "`weigh` has no parameter named `scale`",
+                "parameter `value` of `weigh` is given more than once",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_return_types_checked_against_declarations() {
+        let source = r#"
+type Report = { score: int }
+
+function ratio() -> float {
+    return 1;
+}
+
+function label(n: int) -> String {
+    return n > 2;
+}
+
+agent reviewer() -> Report {
+    with sandbox = "docker" {
+        return "done";
+    }
+}
+
+agent opaque() -> ReviewDecision {
+    with sandbox = "docker" {
+        return "anything";
+    }
+}
+"#;
+        let ws = workspace(&[("a.symbi", source)]);
+        assert_eq!(
+            messages(&ws, "a.symbi"),
+            vec![
+                "function `label` returns `String`, found `bool`",
+                "agent `reviewer` returns `Report`, found `String`",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_agent_scoped_functions_shadow_top_level() {
+        let source = r#"
+function score(x: String) -> int {
+    return 1;
+}
+
+agent reviewer() {
+    function score(x: int) -> int {
+        return x;
+    }
+
+    with sandbox = "docker" {
+        let s = score(3);
+    }
+}
+"#;
+        let ws = workspace(&[("a.symbi", source)]);
+        assert!(messages(&ws, "a.symbi").is_empty());
+    }
+
+    #[test]
+    fn test_deliver_target_forms() {
+        for ok in [
+            "stdout",
+            "log_file://reports/nightly.log",
+            "https://hooks.example.com/run",
+            "slack://#alerts",
+            "email://ops@example.com,oncall@example.com",
+            "mailto:ops@example.com",
+            "channel://teams/general",
+            "custom://archiver",
+        ] {
+            assert!(validate_deliver_target(ok).is_ok(), "{}", ok);
+        }
+        for bad in [
+            "slack",
+            "slack://",
+            "https:///path",
+            "email://nobody",
+            "channel://teams",
+            "ftp://host",
+        ] {
+            assert!(validate_deliver_target(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_memory_blocks_are_validated() {
+        let source = r#"
+agent reviewer() {
+}
+
+memory notes {
+    store     sqlite
+    path      "../outside"
+    retention soon
+    retention 30d
+    search {
+        vector_weight  1.5
+        keyword_weight 0.0
+    }
+}
+
+memory scratch {
+    store markdown
+}
+"#;
+        let ws = workspace(&[("a.symbi", source)]);
+        assert_eq!(
+            messages(&ws, "a.symbi"),
+            vec![
+                "memory `notes` uses unknown store `sqlite` (expected markdown)",
+                "memory `notes` has an invalid path: `../outside` must not contain `..` components",
+                "memory `notes` has an invalid retention `soon` (expected a duration such as \
+                 `90d`)",
+                "memory `notes` sets `retention` more than once",
+                "memory `notes` has an invalid `vector_weight` `1.5`: expected a number \
+                 between 0 and 1",
+                "memory `notes` search weights are all zero",
+                "memory `scratch` conflicts with memory `notes`: a file attaches at most one \
+                 memory block to its agent",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_memory_conflicts_across_files() {
+        let first = r#"
+agent reviewer() {
+}
+
+memory shared {
+    store     markdown
+    path      "data/agents"
+    retention 30d
+}
+"#;
+        let second = r#"
+agent triage() {
+}
+
+memory shared {
+    store     markdown
+    path      "data/agents"
+    retention 7d
+}
+"#;
+        let orphan = "memory loose {\n    store markdown\n}\n";
+        let ws = workspace(&[("a.symbi", first), ("b.symbi", second), ("c.symbi", orphan)]);
+        assert_eq!(
+            messages(&ws, "a.symbi"),
+            vec!["memory `shared` conflicts with its definition in b.symbi"]
+        );
+        assert_eq!(
+            messages(&ws, "c.symbi"),
+            vec!["memory `loose` is not attached to an agent: declare an agent in this file"]
+        );
+
+        // Identical definitions in several files are not a conflict.
+        let ws = workspace(&[("a.symbi", first), ("b.symbi", first)]);
+        assert!(ws.check().is_empty(), "{:?}", ws.check());
+    }
+
+    #[test]
+    fn test_memory_path_forms() {
+        for ok in ["data/agents", "/var/lib/symbi/memory", "./memory"] {
+            assert!(validate_memory_path(ok).is_ok(), "{}", ok);
+        }
+        for bad in ["", "  ", "data/../../etc", "..", "a\0b"] {
+            assert!(validate_memory_path(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_shipped_agents_are_clean() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../agents");
+        let ws = Workspace::from_dir(&dir).unwrap();
+        assert!(ws.paths().count() > 0);
+        assert!(ws.check().is_empty(), "{:#?}", ws.check());
+    }
+}
@@ -408,6 +408,8 @@ mod parser_tests {
             ("gvisor", SandboxTier::GVisor),
             ("firecracker", SandboxTier::Firecracker),
             ("e2b", SandboxTier::E2B),
+            ("namespace", SandboxTier::Namespace),
+            ("wasm", SandboxTier::Wasm),
         ];
 
         for (tier_str, expected_tier) in test_cases {
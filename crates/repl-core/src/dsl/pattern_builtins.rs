@@ -750,6 +750,7 @@ mod tests {
                     prompt_tokens: 1,
                     completion_tokens: 1,
                     total_tokens: 2,
+                    cached_prompt_tokens: 0,
                 },
                 model: "mock".to_string(),
             })
@@ -89,6 +89,8 @@ pub async fn builtin_reason(args: &[DslValue], ctx: &ReasoningBuiltinContext) ->
         circuit_breakers: Arc::new(CircuitBreakerRegistry::default()),
         journal: Arc::new(BufferedJournal::new(1000)),
         knowledge_bridge: None,
+        sandbox_sessions: None,
+        spend: None,
         delegation: None,
     };
 
@@ -0,0 +1,946 @@
+//! Language features computed from a parsed [`Document`].
+//!
+//! Everything here is synchronous and side-effect free so the backend can call
+//! it while holding a read lock on the document store.
+
+use crate::docs;
+use crate::document::Document;
+use tower_lsp::lsp_types::*;
+use tree_sitter::Node;
+
+/// Diagnostic source reported to the client.
+const SOURCE: &str = "symbi";
+
+/// Longest source snippet quoted in a syntax error message.
+const MAX_SNIPPET_CHARS: usize = 40;
+
+/// Built-in type names offered in type positions.
+const BUILTIN_TYPES: &[&str] = &["String", "int", "float", "bool"];
+
+/// What a named top-level or agent-level item declares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeclarationKind {
+    Agent,
+    Function,
+    Type,
+    Policy,
+}
+
+/// A named item that go-to-definition and hover can resolve to.
+#[derive(Debug, Clone)]
+pub struct Declaration {
+    pub name: String,
+    pub kind: DeclarationKind,
+    /// Range of the name identifier.
+    pub name_range: Range,
+    /// Header text shown on hover, e.g. `function f(x: int) -> bool`.
+    pub signature: String,
+}
+
+/// Syntax errors, missing tokens, and invalid sandbox tiers.
+pub fn diagnostics(doc: &Document) -> Vec<Diagnostic> {
+    let Some(tree) = doc.tree() else {
+        return Vec::new();
+    };
+    let root = tree.root_node();
+    let mut out: Vec<Diagnostic> = Vec::new();
+
+    // `find_errors` reports nested ERROR nodes too; only keep the outermost.
+    let mut reported: Vec<(usize, usize)> = Vec::new();
+    for err in dsl::find_errors(root, doc.text(), 0) {
+        let start = doc.line_col_offset(err.start_line - 1, err.start_col - 1);
+        let end = doc.line_col_offset(err.end_line - 1, err.end_col - 1);
+        if reported.iter().any(|&(s, e)| s <= start && end <= e) {
+            continue;
+        }
+        reported.push((start, end));
+        out.push(error(
+            doc.byte_range(start, end),
+            syntax_message(&err.snippet),
+        ));
+    }
+
+    walk(root, &mut |node| {
+        if node.is_missing() {
+            out.push(error(
+                doc.node_range(node),
+                format!("Syntax error: missing `{}`", node.kind()),
+            ));
+        } else if node.kind() == "with_attribute" {
+            if let (Some(name), Some(value)) = (node.child(0), node.child(2)) {
+                if doc.node_text(name) == "sandbox" && value.kind() != "array" {
+                    if let Err(message) = dsl::WithBlock::parse_sandbox_tier(doc.node_text(value)) {
+                        out.push(error(doc.node_range(value), message));
+                    }
+                }
+            }
+        }
+    });
+
+    out
+}
+
+/// Convert diagnostics from `dsl::semantic` for this document.
+pub fn semantic_diagnostics(doc: &Document, found: &[dsl::DslDiagnostic]) -> Vec<Diagnostic> {
+    found
+        .iter()
+        .map(|d| {
+            // `DslDiagnostic` positions are 1-based and count bytes.
+            let start = doc.line_col_offset(d.start_line - 1, d.start_col - 1);
+            let end = doc.line_col_offset(d.end_line - 1, d.end_col - 1);
+            let message = d
+                .message
+                .clone()
+                .unwrap_or_else(|| syntax_message(&d.snippet));
+            error(doc.byte_range(start, end), message)
+        })
+        .collect()
+}
+
+/// Outline of agents, policies, types, functions, and the schedule, channel,
+/// memory, and webhook blocks.
+#[allow(deprecated)] // `DocumentSymbol::deprecated` must still be populated.
+pub fn document_symbols(doc: &Document) -> Vec<DocumentSymbol> {
+    fn symbol(
+        doc: &Document,
+        node: Node,
+        name: String,
+        kind: SymbolKind,
+        detail: Option<String>,
+        children: Vec<DocumentSymbol>,
+    ) -> DocumentSymbol {
+        let selection_range = name_node(node)
+            .map(|n| doc.node_range(n))
+            .unwrap_or_else(|| doc.node_range(node));
+        DocumentSymbol {
+            name,
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range: doc.node_range(node),
+            selection_range,
+            children: (!children.is_empty()).then_some(children),
+        }
+    }
+
+    fn collect(doc: &Document, parent: Node) -> Vec<DocumentSymbol> {
+        let mut symbols = Vec::new();
+        for node in children(parent) {
+            let name = name_node(node)
+                .map(|n| doc.node_text(n).to_string())
+                .unwrap_or_default();
+            let (kind, detail, nested) = match node.kind() {
+                "metadata_block" => {
+                    symbols.push(symbol(
+                        doc,
+                        node,
+                        "metadata".to_string(),
+                        SymbolKind::NAMESPACE,
+                        None,
+                        Vec::new(),
+                    ));
+                    continue;
+                }
+                "agent_definition" => (SymbolKind::CLASS, "agent", collect(doc, node)),
+                "function_definition" => (SymbolKind::FUNCTION, "function", Vec::new()),
+                "type_definition" => (SymbolKind::STRUCT, "type", Vec::new()),
+                "policy_definition" | "channel_policy_block" => {
+                    (SymbolKind::OBJECT, "policy", Vec::new())
+                }
+                "schedule_definition" => (SymbolKind::EVENT, "schedule", Vec::new()),
+                "channel_definition" => (SymbolKind::INTERFACE, "channel", collect(doc, node)),
+                "memory_definition" => (SymbolKind::MODULE, "memory", Vec::new()),
+                "webhook_definition" => (SymbolKind::EVENT, "webhook", Vec::new()),
+                "capabilities_declaration" => {
+                    let caps = dsl_strings(doc, node).join(", ");
+                    symbols.push(symbol(
+                        doc,
+                        node,
+                        "capabilities".to_string(),
+                        SymbolKind::ARRAY,
+                        Some(caps),
+                        Vec::new(),
+                    ));
+                    continue;
+                }
+                "with_block" => {
+                    let attrs = children(node)
+                        .into_iter()
+                        .filter(|c| c.kind() == "with_attribute")
+                        .map(|c| doc.node_text(c).to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    symbols.push(symbol(
+                        doc,
+                        node,
+                        "with".to_string(),
+                        SymbolKind::NAMESPACE,
+                        (!attrs.is_empty()).then_some(attrs),
+                        Vec::new(),
+                    ));
+                    continue;
+                }
+                _ => continue,
+            };
+            if name.is_empty() {
+                continue;
+            }
+            symbols.push(symbol(
+                doc,
+                node,
+                name,
+                kind,
+                Some(detail.to_string()),
+                nested,
+            ));
+        }
+        symbols
+    }
+
+    match doc.tree() {
+        Some(tree) => collect(doc, tree.root_node()),
+        None => Vec::new(),
+    }
+}
+
+/// Agents, functions, types, and policies declared in the document.
+pub fn declarations(doc: &Document) -> Vec<Declaration> {
+    let Some(tree) = doc.tree() else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    walk(tree.root_node(), &mut |node| {
+        let kind = match node.kind() {
+            "agent_definition" => DeclarationKind::Agent,
+            "function_definition" => DeclarationKind::Function,
+            "type_definition" => DeclarationKind::Type,
+            "policy_definition" => DeclarationKind::Policy,
+            _ => return,
+        };
+        let Some(name) = name_node(node) else {
+            return;
+        };
+        out.push(Declaration {
+            name: doc.node_text(name).to_string(),
+            kind,
+            name_range: doc.node_range(name),
+            signature: signature(doc, node),
+        });
+    });
+    out
+}
+
+/// The name under the cursor that go-to-definition should resolve: an
+/// identifier, or a quoted string such as `agent: "reviewer"` in a schedule.
+pub fn reference_at(doc: &Document, position: Position) -> Option<String> {
+    let node = node_at(doc, position)?;
+    match node.kind() {
+        "identifier" => Some(doc.node_text(node).to_string()),
+        "string" => Some(doc.node_text(node).trim_matches('"').to_string()),
+        _ => None,
+    }
+}
+
+/// Hover text for `with` attributes and sandbox tiers, capabilities, block
+/// properties, keywords, and references to declared items.
+pub fn hover(doc: &Document, position: Position) -> Option<Hover> {
+    let node = node_at(doc, position)?;
+    let text = doc.node_text(node);
+    let parent = node.parent();
+    let parent_kind = parent.map(|p| p.kind()).unwrap_or_default();
+
+    let contents = if !node.is_named() {
+        let d = docs::lookup(docs::TOP_LEVEL_KEYWORDS, text)
+            .or_else(|| docs::lookup(docs::AGENT_KEYWORDS, text))?;
+        format!("**{}**\n\n{}", text, d)
+    } else if let Some(attr) = ancestor(node, "with_attribute") {
+        let name = attr.child(0)?;
+        let attr_name = doc.node_text(name);
+        if name.id() == node.id() {
+            match docs::lookup(docs::WITH_ATTRIBUTES, attr_name) {
+                Some(d) => format!("**with** `{}`\n\n{}", attr_name, d),
+                None => format!(
+                    "**with** `{}`\n\nNot recognised by the runtime; the attribute is ignored.",
+                    attr_name
+                ),
+            }
+        } else if attr_name == "sandbox" {
+            let tier = text.trim_matches('"');
+            let d = docs::lookup(docs::SANDBOX_TIERS, &tier.to_lowercase())?;
+            format!("**sandbox** `{}`\n\n{}", tier, d)
+        } else {
+            return None;
+        }
+    } else if node.kind() == "string" && ancestor(node, "capabilities_declaration").is_some() {
+        let name = text.trim_matches('"');
+        match docs::lookup(docs::CAPABILITIES, name) {
+            Some(d) => format!("**capability** `{}`\n\n{}", name, d),
+            None => format!("**capability** `{}`\n\nCustom capability.", name),
+        }
+    } else if node.kind() == "identifier"
+        && parent.and_then(|p| p.child(0)).map(|c| c.id()) == Some(node.id())
+        && property_table(parent_kind).is_some()
+    {
+        let table = property_table(parent_kind)?;
+        let d = docs::lookup(table, text)?;
+        format!("**{}**\n\n{}", text, d)
+    } else if node.kind() == "identifier" {
+        let decl = declarations(doc).into_iter().find(|d| d.name == text)?;
+        format!("```symbi\n{}\n```", decl.signature)
+    } else {
+        return None;
+    };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: contents,
+        }),
+        range: Some(doc.node_range(node)),
+    })
+}
+
+/// Context-sensitive completion items at `position`.
+///
+/// Incomplete input rarely parses into the node we want, so the context is
+/// recovered by scanning the text before the cursor for open blocks rather
+/// than by walking the syntax tree.
+pub fn completions(doc: &Document, position: Position) -> Vec<CompletionItem> {
+    let before = &doc.text()[..doc.offset_at(position)];
+    let scan = scan_context(before);
+    let segment = scan.segment.as_str();
+    let word_start = segment.len()
+        - segment
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphanumeric() || *c == '_')
+            .map(char::len_utf8)
+            .sum::<usize>();
+    let lead = segment[..word_start].trim_end();
+    let line_lead = segment[..word_start]
+        .rsplit(['\n', ','])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    let frame = scan.frames.last().map(String::as_str);
+
+    if frame == Some("[capabilities") {
+        let quoted = segment.matches('"').count() % 2 == 1;
+        return table_items(docs::CAPABILITIES, CompletionItemKind::VALUE, |name| {
+            if quoted {
+                name.to_string()
+            } else {
+                format!("\"{}\"", name)
+            }
+        });
+    }
+
+    if block_keyword(segment) == "with" {
+        if let Some(idx) = lead.rfind("sandbox") {
+            let rest = lead[idx + "sandbox".len()..].trim_start();
+            if rest.strip_prefix('=').is_some_and(|v| {
+                let v = v.trim_start();
+                v.is_empty() || v == "\""
+            }) {
+                let quoted = lead.ends_with('"');
+                return table_items(docs::SANDBOX_TIERS, CompletionItemKind::ENUM_MEMBER, |t| {
+                    if quoted {
+                        t.to_string()
+                    } else {
+                        format!("\"{}\"", t)
+                    }
+                });
+            }
+        }
+        if lead.ends_with("with") || lead.ends_with(',') {
+            return table_items(docs::WITH_ATTRIBUTES, CompletionItemKind::PROPERTY, |a| {
+                a.to_string()
+            });
+        }
+        return Vec::new();
+    }
+
+    if lead.ends_with("->") || (lead.ends_with(':') && scan.open_parens > 0) {
+        return type_items(doc);
+    }
+
+    if !line_lead.is_empty() {
+        return match frame {
+            Some(f) if is_statement_frame(f) => statement_items(doc),
+            _ => Vec::new(),
+        };
+    }
+
+    match frame {
+        None => table_items(docs::TOP_LEVEL_KEYWORDS, CompletionItemKind::KEYWORD, |k| {
+            k.to_string()
+        }),
+        Some("agent") => table_items(docs::AGENT_KEYWORDS, CompletionItemKind::KEYWORD, |k| {
+            k.to_string()
+        }),
+        Some(f) => match frame_table(f) {
+            Some(table) => table_items(table, CompletionItemKind::PROPERTY, |k| k.to_string()),
+            None if is_statement_frame(f) => statement_items(doc),
+            None => Vec::new(),
+        },
+    }
+}
+
+/// A single whole-document edit produced by `dsl::format`, or nothing when
+/// the source is already canonical or fails to parse.
+pub fn formatting(doc: &Document) -> Vec<TextEdit> {
+    match dsl::format::format_source(doc.text()) {
+        Ok(formatted) if formatted != doc.text() => {
+            vec![TextEdit::new(doc.full_range(), formatted)]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Open blocks and the text of the statement being typed.
+struct ScanContext {
+    /// Keyword that opened each enclosing `{` (e.g. `agent`, `schedule`), or
+    /// `[` followed by the keyword for arrays.
+    frames: Vec<String>,
+    /// Text since the last block or statement delimiter, comments removed.
+    segment: String,
+    /// Unclosed `(` in the segment.
+    open_parens: usize,
+}
+
+fn scan_context(text: &str) -> ScanContext {
+    let mut frames: Vec<String> = Vec::new();
+    let mut segment = String::new();
+    let mut open_parens = 0usize;
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            segment.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        segment.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                segment.push(c);
+            }
+            '#' => skip_line(&mut chars),
+            '/' if chars.peek() == Some(&'/') => skip_line(&mut chars),
+            '{' => {
+                frames.push(block_keyword(&segment).to_string());
+                segment.clear();
+                open_parens = 0;
+            }
+            '[' => {
+                frames.push(format!("[{}", block_keyword(&segment)));
+                segment.clear();
+                open_parens = 0;
+            }
+            '}' | ']' | ';' => {
+                if c != ';' {
+                    frames.pop();
+                }
+                segment.clear();
+                open_parens = 0;
+            }
+            '(' => {
+                open_parens += 1;
+                segment.push(c);
+            }
+            ')' => {
+                open_parens = open_parens.saturating_sub(1);
+                segment.push(c);
+            }
+            _ => segment.push(c),
+        }
+    }
+
+    ScanContext {
+        frames,
+        segment,
+        open_parens,
+    }
+}
+
+/// Keywords that open a `{` block.
+const BLOCK_KEYWORDS: &[&str] = &[
+    "metadata",
+    "agent",
+    "policy",
+    "type",
+    "function",
+    "schedule",
+    "channel",
+    "data_classification",
+    "memory",
+    "search",
+    "webhook",
+    "filter",
+    "with",
+    "if",
+    "else",
+    "for",
+    "match",
+    "try",
+    "catch",
+];
+
+/// The keyword introducing the statement in `segment`.
+///
+/// Headers may span lines (`with a = 1,\n b = 2 {`) and a type alias has no
+/// terminator, so this picks the last line that starts with a block keyword
+/// and falls back to the segment's first word.
+fn block_keyword(segment: &str) -> &str {
+    fn first_word(s: &str) -> &str {
+        s.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .find(|w| !w.is_empty())
+            .unwrap_or_default()
+    }
+    segment
+        .lines()
+        .rev()
+        .map(first_word)
+        .find(|w| BLOCK_KEYWORDS.contains(w))
+        .unwrap_or_else(|| first_word(segment))
+}
+
+fn skip_line(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+    for c in chars.by_ref() {
+        if c == '\n' {
+            break;
+        }
+    }
+}
+
+fn frame_table(frame: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    match frame {
+        "schedule" => Some(docs::SCHEDULE_KEYS),
+        "channel" => Some(docs::CHANNEL_KEYS),
+        "memory" => Some(docs::MEMORY_KEYS),
+        "search" => Some(docs::MEMORY_SEARCH_KEYS),
+        "webhook" => Some(docs::WEBHOOK_KEYS),
+        "filter" => Some(docs::WEBHOOK_FILTER_KEYS),
+        _ => None,
+    }
+}
+
+fn property_table(node_kind: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    match node_kind {
+        "schedule_property" => Some(docs::SCHEDULE_KEYS),
+        "channel_property" => Some(docs::CHANNEL_KEYS),
+        "memory_property" => Some(docs::MEMORY_KEYS),
+        "memory_search_property" => Some(docs::MEMORY_SEARCH_KEYS),
+        "webhook_property" => Some(docs::WEBHOOK_KEYS),
+        "webhook_filter_property" => Some(docs::WEBHOOK_FILTER_KEYS),
+        _ => None,
+    }
+}
+
+/// Frames whose body is a statement block.
+fn is_statement_frame(frame: &str) -> bool {
+    matches!(
+        frame,
+        "function" | "with" | "if" | "else" | "for" | "match" | "try" | "catch" | ""
+    )
+}
+
+fn table_items(
+    table: &[(&str, &str)],
+    kind: CompletionItemKind,
+    insert: impl Fn(&str) -> String,
+) -> Vec<CompletionItem> {
+    table
+        .iter()
+        .map(|(name, doc)| CompletionItem {
+            label: name.to_string(),
+            kind: Some(kind),
+            documentation: Some(Documentation::MarkupContent(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: doc.to_string(),
+            })),
+            insert_text: Some(insert(name)),
+            ..CompletionItem::default()
+        })
+        .collect()
+}
+
+fn type_items(doc: &Document) -> Vec<CompletionItem> {
+    let builtins = BUILTIN_TYPES.iter().map(|t| CompletionItem {
+        label: t.to_string(),
+        kind: Some(CompletionItemKind::STRUCT),
+        detail: Some("built-in".to_string()),
+        ..CompletionItem::default()
+    });
+    let declared = declarations(doc)
+        .into_iter()
+        .filter(|d| d.kind == DeclarationKind::Type)
+        .map(declaration_item);
+    builtins.chain(declared).collect()
+}
+
+fn statement_items(doc: &Document) -> Vec<CompletionItem> {
+    let keywords = docs::STATEMENT_KEYWORDS.iter().map(|k| CompletionItem {
+        label: k.to_string(),
+        kind: Some(CompletionItemKind::KEYWORD),
+        ..CompletionItem::default()
+    });
+    let declared = declarations(doc)
+        .into_iter()
+        .filter(|d| matches!(d.kind, DeclarationKind::Function | DeclarationKind::Type))
+        .map(declaration_item);
+    keywords.chain(declared).collect()
+}
+
+fn declaration_item(decl: Declaration) -> CompletionItem {
+    CompletionItem {
+        label: decl.name,
+        kind: Some(match decl.kind {
+            DeclarationKind::Function => CompletionItemKind::FUNCTION,
+            DeclarationKind::Type => CompletionItemKind::STRUCT,
+            DeclarationKind::Agent => CompletionItemKind::CLASS,
+            DeclarationKind::Policy => CompletionItemKind::MODULE,
+        }),
+        detail: Some(decl.signature),
+        ..CompletionItem::default()
+    }
+}
+
+fn error(range: Range, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some(SOURCE.to_string()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+fn syntax_message(snippet: &str) -> String {
+    let line = snippet.trim().lines().next().unwrap_or_default();
+    if line.is_empty() {
+        return "Syntax error".to_string();
+    }
+    let mut quoted: String = line.chars().take(MAX_SNIPPET_CHARS).collect();
+    if line.chars().count() > MAX_SNIPPET_CHARS {
+        quoted.push('…');
+    }
+    format!("Syntax error: unexpected `{}`", quoted)
+}
+
+/// Header of a declaration: everything before its body, or the whole
+/// definition for types.
+fn signature(doc: &Document, node: Node) -> String {
+    let body = children(node)
+        .into_iter()
+        .find(|c| c.kind() == "{" || c.kind() == "block");
+    let end = match (node.kind(), body) {
+        ("type_definition", _) | (_, None) => node.end_byte(),
+        (_, Some(body)) => body.start_byte(),
+    };
+    doc.text()[node.start_byte()..end].trim().to_string()
+}
+
+/// The token under the cursor. When the cursor sits just after a word (for
+/// example `score|(`), the word wins over the punctuation that follows it.
+fn node_at(doc: &Document, position: Position) -> Option<Node<'_>> {
+    fn leaf_at(root: Node, offset: usize) -> Option<Node> {
+        let mut node = root;
+        while let Some(child) = children(node)
+            .into_iter()
+            .find(|c| c.start_byte() <= offset && offset < c.end_byte())
+        {
+            node = child;
+        }
+        (node.child_count() == 0 && node.id() != root.id()).then_some(node)
+    }
+
+    let root = doc.tree()?.root_node();
+    let offset = doc.offset_at(position);
+    let at = leaf_at(root, offset);
+    if at.is_some_and(|n| n.is_named()) {
+        return at;
+    }
+    offset
+        .checked_sub(1)
+        .and_then(|o| leaf_at(root, o))
+        .filter(|n| n.end_byte() == offset && n.is_named())
+        .or(at)
+}
+
+fn name_node(node: Node) -> Option<Node> {
+    children(node)
+        .into_iter()
+        .find(|c| c.kind() == "identifier")
+}
+
+fn ancestor<'t>(node: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    let mut current = Some(node);
+    while let Some(n) = current {
+        if n.kind() == kind {
+            return Some(n);
+        }
+        current = n.parent();
+    }
+    None
+}
+
+fn children(node: Node) -> Vec<Node> {
+    (0..node.child_count() as u32)
+        .filter_map(|i| node.child(i))
+        .collect()
+}
+
+fn dsl_strings(doc: &Document, node: Node) -> Vec<String> {
+    let mut out = Vec::new();
+    walk(node, &mut |n| {
+        if n.kind() == "string" {
+            out.push(doc.node_text(n).trim_matches('"').to_string());
+        }
+    });
+    out
+}
+
+/// Pre-order traversal without recursion, so adversarial nesting cannot
+/// overflow the stack.
+fn walk<'t>(root: Node<'t>, visit: &mut impl FnMut(Node<'t>)) {
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        visit(node);
+        stack.extend(children(node).into_iter().rev());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AGENT: &str = r#"metadata {
+    version = "1.0.0"
+}
+
+type Report = {
+    score: int,
+}
+
+agent reviewer(input: String) -> Report {
+    capabilities = ["read", "analyze"]
+
+    function score(text: String) -> int {
+        return 1;
+    }
+
+    with sandbox = "docker", timeout = "30s" {
+        return score(input);
+    }
+}
+
+schedule nightly {
+    cron: "0 0 2 * * *",
+    agent: "reviewer",
+}
+
+channel support {
+    platform: "slack"
+}
+
+memory notes {
+    store markdown
+}
+
+webhook deploys {
+    path "/hooks/deploy"
+}
+"#;
+
+    fn position_of(doc: &Document, needle: &str, delta: usize) -> Position {
+        doc.position_at(doc.text().find(needle).unwrap() + delta)
+    }
+
+    fn labels(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|i| i.label.as_str()).collect()
+    }
+
+    fn hover_text(doc: &Document, position: Position) -> String {
+        match hover(doc, position).unwrap().contents {
+            HoverContents::Markup(m) => m.value,
+            other => panic!("unexpected hover contents: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_valid_document_has_no_diagnostics() {
+        let doc = Document::new(AGENT.to_string());
+        assert!(diagnostics(&doc).is_empty(), "{:?}", diagnostics(&doc));
+    }
+
+    #[test]
+    fn test_syntax_error_range_is_precise() {
+        let doc = Document::new("agent a {\n    capabilities = [\"read\"]\n    ??? \n}\n".into());
+        let diags = diagnostics(&doc);
+        assert!(!diags.is_empty());
+        let first = &diags[0];
+        assert_eq!(first.range.start.line, 2);
+        assert_eq!(first.severity, Some(DiagnosticSeverity::ERROR));
+        assert!(
+            first.message.starts_with("Syntax error"),
+            "{}",
+            first.message
+        );
+    }
+
+    #[test]
+    fn test_invalid_sandbox_tier_is_reported_on_the_value() {
+        let doc = Document::new("agent a {\n    with sandbox = \"kvm\" {\n    }\n}\n".into());
+        let diags = diagnostics(&doc);
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("Invalid sandbox tier"));
+        assert_eq!(diags[0].range.start, position_of(&doc, "\"kvm\"", 0));
+        assert_eq!(diags[0].range.end, position_of(&doc, "\"kvm\"", 5));
+    }
+
+    #[test]
+    fn test_semantic_diagnostics_map_to_value_ranges() {
+        let text = "schedule s {\n    cron: \"0 * * * * *\",\n    agent: \"ghöst\",\n}\n";
+        let doc = Document::new(text.to_string());
+        let mut workspace = dsl::semantic::Workspace::new();
+        workspace.add_file("s.symbi", text);
+        let found = workspace.check_file(std::path::Path::new("s.symbi"));
+
+        let diags = semantic_diagnostics(&doc, &found);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].message,
+            "schedule `s` references unknown agent `ghöst`"
+        );
+        assert_eq!(diags[0].range.start, position_of(&doc, "\"ghöst\"", 0));
+        // The end is counted in UTF-16 units, not bytes.
+        assert_eq!(diags[0].range.end, Position::new(2, 18));
+    }
+
+    #[test]
+    fn test_document_symbols_cover_top_level_blocks() {
+        let doc = Document::new(AGENT.to_string());
+        let symbols = document_symbols(&doc);
+        let names: Vec<(&str, SymbolKind)> =
+            symbols.iter().map(|s| (s.name.as_str(), s.kind)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("metadata", SymbolKind::NAMESPACE),
+                ("Report", SymbolKind::STRUCT),
+                ("reviewer", SymbolKind::CLASS),
+                ("nightly", SymbolKind::EVENT),
+                ("support", SymbolKind::INTERFACE),
+                ("notes", SymbolKind::MODULE),
+                ("deploys", SymbolKind::EVENT),
+            ]
+        );
+        let agent_children: Vec<&str> = symbols[2]
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(agent_children, vec!["capabilities", "score", "with"]);
+        assert_eq!(
+            symbols[2].selection_range.start,
+            position_of(&doc, "reviewer", 0)
+        );
+    }
+
+    #[test]
+    fn test_hover_documents_attributes_tiers_and_capabilities() {
+        let doc = Document::new(AGENT.to_string());
+        assert!(hover_text(&doc, position_of(&doc, "sandbox =", 2)).contains("Isolation tier"));
+        assert!(hover_text(&doc, position_of(&doc, "\"docker\"", 3)).contains("Docker container"));
+        assert!(hover_text(&doc, position_of(&doc, "\"analyze\"", 2)).contains("Analyse"));
+        assert!(hover_text(&doc, position_of(&doc, "cron:", 1)).contains("cron expression"));
+        assert!(hover_text(&doc, position_of(&doc, "score(input)", 1))
+            .contains("function score(text: String) -> int"));
+    }
+
+    #[test]
+    fn test_declarations_resolve_functions_types_and_agents() {
+        let doc = Document::new(AGENT.to_string());
+        let decls = declarations(&doc);
+        let find = |name: &str| decls.iter().find(|d| d.name == name).unwrap();
+        assert_eq!(find("Report").kind, DeclarationKind::Type);
+        assert_eq!(find("score").kind, DeclarationKind::Function);
+        assert_eq!(
+            find("reviewer").name_range.start,
+            position_of(&doc, "reviewer", 0)
+        );
+
+        let call = position_of(&doc, "score(input)", 2);
+        assert_eq!(reference_at(&doc, call).as_deref(), Some("score"));
+        let scheduled = position_of(&doc, "\"reviewer\"", 3);
+        assert_eq!(reference_at(&doc, scheduled).as_deref(), Some("reviewer"));
+    }
+
+    #[test]
+    fn test_completion_contexts() {
+        let complete = |text: &str| {
+            let doc = Document::new(text.to_string());
+            let end = doc.position_at(text.len());
+            labels(&completions(&doc, end))
+                .into_iter()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(complete("").contains(&"schedule".to_string()));
+        assert!(complete("agent a {\n    ").contains(&"capabilities".to_string()));
+        assert!(complete("agent a {\n    with sandbox = \"").contains(&"gvisor".to_string()));
+        assert!(complete("agent a {\n    with ").contains(&"timeout".to_string()));
+        assert!(complete("agent a {\n    with memory = \"ephemeral\", ")
+            .contains(&"sandbox".to_string()));
+        assert!(complete("agent a {\n    capabilities = [\"read\", ")
+            .contains(&"memory_write".to_string()));
+        assert!(complete("schedule s {\n    cron: \"* * * * * *\"\n    ")
+            .contains(&"timezone".to_string()));
+        assert!(complete("webhook w {\n    filter {\n        ").contains(&"json_path".to_string()));
+        assert!(complete("type T = String\nfunction f(x: ").contains(&"T".to_string()));
+        assert!(complete("function g() {}\nfunction f() {\n    ").contains(&"g".to_string()));
+        assert!(complete("schedule s {\n    cron: ").is_empty());
+    }
+
+    #[test]
+    fn test_capability_completion_quotes_only_outside_strings() {
+        let doc = Document::new("agent a {\n    capabilities = [".to_string());
+        let items = completions(&doc, doc.position_at(doc.text().len()));
+        let read = items.iter().find(|i| i.label == "read").unwrap();
+        assert_eq!(read.insert_text.as_deref(), Some("\"read\""));
+
+        let doc = Document::new("agent a {\n    capabilities = [\"re".to_string());
+        let items = completions(&doc, doc.position_at(doc.text().len()));
+        let read = items.iter().find(|i| i.label == "read").unwrap();
+        assert_eq!(read.insert_text.as_deref(), Some("read"));
+    }
+
+    #[test]
+    fn test_formatting_returns_whole_document_edit() {
+        let doc = Document::new("agent a{\ncapabilities=[\"read\"]\n}".to_string());
+        let edits = formatting(&doc);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range, doc.full_range());
+        assert_eq!(
+            edits[0].new_text,
+            dsl::format::format_source(doc.text()).unwrap()
+        );
+
+        let canonical = Document::new(edits[0].new_text.clone());
+        assert!(formatting(&canonical).is_empty());
+    }
+}
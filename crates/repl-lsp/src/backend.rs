@@ -1,44 +1,103 @@
-use repl_core::parse_policy;
+use crate::analysis;
+use crate::document::Document;
+use dsl::semantic::Workspace;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
 pub struct Backend {
     client: Client,
+    documents: RwLock<HashMap<Url, Document>>,
+    /// `agents/` under the workspace root, whose files take part in
+    /// cross-file semantic checks even when they are not open.
+    agents_dir: RwLock<Option<PathBuf>>,
 }
 
 impl Backend {
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self {
+            client,
+            documents: RwLock::new(HashMap::new()),
+            agents_dir: RwLock::new(None),
+        }
     }
 
-    async fn on_change(&self, uri: Url, text: String) {
-        let diagnostics = match parse_policy(&text) {
-            Ok(_) => vec![],
-            Err(e) => {
-                // In the future, we'll create a proper span. For now, use a default range.
-                let range = Range::new(Position::new(0, 0), Position::new(0, text.len() as u32));
-                vec![Diagnostic::new_simple(range, e.to_string())]
+    /// Publish diagnostics for every open document. An edit in one file can
+    /// resolve or break references in another, so all of them are refreshed.
+    async fn publish_diagnostics(&self) {
+        let published: Vec<(Url, Vec<Diagnostic>)> = {
+            let documents = self.documents.read().await;
+            let mut workspace = match self.agents_dir.read().await.as_deref() {
+                Some(dir) if dir.is_dir() => Workspace::from_dir(dir).unwrap_or_default(),
+                _ => Workspace::new(),
+            };
+            for (uri, doc) in documents.iter() {
+                workspace.add_file(document_path(uri), doc.text());
             }
+            documents
+                .iter()
+                .map(|(uri, doc)| {
+                    let mut diagnostics = analysis::diagnostics(doc);
+                    let semantic = workspace.check_file(&document_path(uri));
+                    diagnostics.extend(analysis::semantic_diagnostics(doc, &semantic));
+                    (uri.clone(), diagnostics)
+                })
+                .collect()
         };
-        self.client
-            .publish_diagnostics(uri, diagnostics, None)
-            .await;
+        for (uri, diagnostics) in published {
+            self.client
+                .publish_diagnostics(uri, diagnostics, None)
+                .await;
+        }
     }
 }
 
+/// Path used for a document in the semantic workspace. Matches the paths
+/// `Workspace::from_dir` produces for files on disk, so an open file replaces
+/// its saved copy.
+fn document_path(uri: &Url) -> PathBuf {
+    uri.to_file_path()
+        .unwrap_or_else(|_| PathBuf::from(uri.as_str()))
+}
+
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        #[allow(deprecated)] // Older clients only send `root_uri`.
+        let root = params
+            .workspace_folders
+            .as_ref()
+            .and_then(|folders| folders.first())
+            .map(|folder| &folder.uri)
+            .or(params.root_uri.as_ref())
+            .and_then(|uri| uri.to_file_path().ok());
+        *self.agents_dir.write().await = root.map(|root| root.join("agents"));
+
         Ok(InitializeResult {
             server_info: Some(ServerInfo {
                 name: "symbiont-repl-lsp".to_string(),
-                version: Some("0.1.0".to_string()),
+                version: Some(env!("CARGO_PKG_VERSION").to_string()),
             }),
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                    TextDocumentSyncKind::FULL,
+                    TextDocumentSyncKind::INCREMENTAL,
                 )),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                completion_provider: Some(CompletionOptions {
+                    trigger_characters: Some(
+                        ["\"", "=", ",", "["]
+                            .iter()
+                            .map(|c| c.to_string())
+                            .collect(),
+                    ),
+                    ..CompletionOptions::default()
+                }),
+                definition_provider: Some(OneOf::Left(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                document_formatting_provider: Some(OneOf::Left(true)),
                 ..ServerCapabilities::default()
             },
         })
@@ -51,16 +110,115 @@ impl LanguageServer for Backend {
     }
 
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
-        self.on_change(params.text_document.uri, params.text_document.text)
-            .await;
+        let uri = params.text_document.uri;
+        self.documents
+            .write()
+            .await
+            .insert(uri, Document::new(params.text_document.text));
+        self.publish_diagnostics().await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        let uri = params.text_document.uri;
+        {
+            let mut documents = self.documents.write().await;
+            match documents.get_mut(&uri) {
+                Some(doc) => doc.apply_changes(params.content_changes),
+                None => {
+                    // A change for a document we never saw opened: treat the
+                    // last full-text change as its initial contents.
+                    let Some(full) = params
+                        .content_changes
+                        .into_iter()
+                        .rev()
+                        .find(|c| c.range.is_none())
+                    else {
+                        return;
+                    };
+                    documents.insert(uri, Document::new(full.text));
+                }
+            }
+        }
+        self.publish_diagnostics().await;
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let uri = params.text_document.uri;
+        self.documents.write().await.remove(&uri);
+        self.client.publish_diagnostics(uri, Vec::new(), None).await;
+        self.publish_diagnostics().await;
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let documents = self.documents.read().await;
+        Ok(documents
+            .get(&position.text_document.uri)
+            .and_then(|doc| analysis::hover(doc, position.position)))
+    }
+
+    async fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let position = params.text_document_position;
+        let documents = self.documents.read().await;
+        Ok(documents
+            .get(&position.text_document.uri)
+            .map(|doc| analysis::completions(doc, position.position))
+            .filter(|items| !items.is_empty())
+            .map(CompletionResponse::Array))
+    }
+
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let uri = position.text_document.uri;
+        let documents = self.documents.read().await;
+        let Some(name) = documents
+            .get(&uri)
+            .and_then(|doc| analysis::reference_at(doc, position.position))
+        else {
+            return Ok(None);
+        };
+
+        // Prefer declarations in the current file, then other open files.
+        let mut ordered: Vec<(&Url, &Document)> = documents.iter().collect();
+        ordered.sort_by_key(|(u, _)| **u != uri);
+        let locations: Vec<Location> = ordered
+            .into_iter()
+            .flat_map(|(u, doc)| {
+                analysis::declarations(doc)
+                    .into_iter()
+                    .filter(|d| d.name == name)
+                    .map(|d| Location::new(u.clone(), d.name_range))
+            })
+            .collect();
+
+        Ok(match locations.len() {
+            0 => None,
+            1 => locations
+                .into_iter()
+                .next()
+                .map(GotoDefinitionResponse::Scalar),
+            _ => Some(GotoDefinitionResponse::Array(locations)),
+        })
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let documents = self.documents.read().await;
+        Ok(documents
+            .get(&params.text_document.uri)
+            .map(|doc| DocumentSymbolResponse::Nested(analysis::document_symbols(doc))))
     }
 
-    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
-        self.on_change(
-            params.text_document.uri,
-            params.content_changes.remove(0).text,
-        )
-        .await;
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        let documents = self.documents.read().await;
+        Ok(documents
+            .get(&params.text_document.uri)
+            .map(analysis::formatting))
     }
 
     async fn shutdown(&self) -> Result<()> {
@@ -0,0 +1,251 @@
+//! Reference text used for hover and completion.
+//!
+//! Each table pairs a name with a short Markdown description. The key sets
+//! mirror what `dsl::extract_*_definitions` understands, so completions never
+//! suggest a property the runtime would silently ignore.
+
+/// Attributes recognised in `with` block headers.
+pub const WITH_ATTRIBUTES: &[(&str, &str)] = &[
+    (
+        "sandbox",
+        "Isolation tier for the block: `docker` (`tier1`), `gvisor` (`tier2`), \
+         `firecracker` (`tier3`), or `e2b`.",
+    ),
+    (
+        "timeout",
+        "Maximum wall-clock time for the block, e.g. `\"30s\"`, `\"5m\"`, \
+         `30.seconds`. A bare number is read as seconds.",
+    ),
+    (
+        "memory",
+        "Memory lifetime for the block: `\"ephemeral\"` discards state when the \
+         block exits, `\"persistent\"` keeps it in the agent's context store.",
+    ),
+    (
+        "security",
+        "Security profile applied to the block (for example `\"high\"`).",
+    ),
+    (
+        "privacy",
+        "Privacy mode for data handled in the block, e.g. `\"differential\"` \
+         together with `epsilon`.",
+    ),
+    (
+        "communication",
+        "Messaging mode for the block: `\"secure\"`, `\"broadcast\"`, or \
+         `\"subscribe\"`.",
+    ),
+    (
+        "storage",
+        "Storage mode for persistent memory, e.g. `\"encrypted\"`.",
+    ),
+    (
+        "requires",
+        "Precondition for entering the block, e.g. `\"approval\"`.",
+    ),
+];
+
+/// Sandbox tiers accepted by `with sandbox = ...`, including tier aliases.
+pub const SANDBOX_TIERS: &[(&str, &str)] = &[
+    ("docker", "Docker container isolation (tier 1)."),
+    ("tier1", "Alias for `docker`."),
+    ("gvisor", "gVisor user-space kernel isolation (tier 2)."),
+    ("tier2", "Alias for `gvisor`."),
+    ("firecracker", "Firecracker microVM isolation (tier 3)."),
+    ("tier3", "Alias for `firecracker`."),
+    ("e2b", "E2B.dev hosted cloud sandbox."),
+    (
+        "namespace",
+        "Rootless Linux namespaces with seccomp, Landlock and cgroup limits.",
+    ),
+    (
+        "wasm",
+        "Embedded WASI engine with fuel/epoch CPU limits and preopened directories only.",
+    ),
+    ("wasi", "Alias for `wasm`."),
+];
+
+/// Capability names used by the shipped agents. Capabilities are free-form
+/// strings; these are offered as completions, not enforced.
+pub const CAPABILITIES: &[(&str, &str)] = &[
+    ("read", "Read input data and files granted by policy."),
+    ("write", "Write output data and files granted by policy."),
+    ("analyze", "Analyse data passed to the agent."),
+    ("summarize", "Produce summaries of input content."),
+    (
+        "memory_read",
+        "Read from the agent's persistent memory store.",
+    ),
+    (
+        "memory_write",
+        "Write to the agent's persistent memory store.",
+    ),
+    (
+        "http_input",
+        "Receive requests through the HTTP input server.",
+    ),
+    ("web_search", "Query external search services."),
+    (
+        "network_scan",
+        "Perform network discovery against permitted targets.",
+    ),
+    ("execute_tests", "Run test suites inside the sandbox."),
+    ("event_processing", "Consume and react to inbound events."),
+    ("alerting", "Raise alerts to operators or channels."),
+    (
+        "notification_delivery",
+        "Deliver notifications to external channels.",
+    ),
+    ("data_validation", "Validate data against schemas or rules."),
+    (
+        "data_transformation",
+        "Transform data between representations.",
+    ),
+    (
+        "signature_verification",
+        "Verify cryptographic signatures on inputs.",
+    ),
+];
+
+/// Properties of `schedule` blocks.
+pub const SCHEDULE_KEYS: &[(&str, &str)] = &[
+    (
+        "cron",
+        "Six-field cron expression (`sec min hour day month weekday`). \
+         Mutually exclusive with `at`.",
+    ),
+    (
+        "at",
+        "One-shot RFC 3339 timestamp. Mutually exclusive with `cron`.",
+    ),
+    (
+        "timezone",
+        "IANA timezone for the schedule. Defaults to `UTC`.",
+    ),
+    ("agent", "Name of the agent to run."),
+    ("policy", "Policy applied to scheduled runs."),
+    (
+        "audit",
+        "Audit level: `none`, `errors_only`, or `all_operations`.",
+    ),
+    ("one_shot", "Disable the job after its first run."),
+    (
+        "deliver",
+        "Delivery target for run output, e.g. `slack://channel`.",
+    ),
+];
+
+/// Properties of `channel` blocks.
+pub const CHANNEL_KEYS: &[(&str, &str)] = &[
+    (
+        "platform",
+        "Chat platform: `slack`, `teams`, or `mattermost`.",
+    ),
+    (
+        "workspace",
+        "Workspace or tenant identifier on the platform.",
+    ),
+    ("channels", "Array of channel names the adapter listens on."),
+    (
+        "default_agent",
+        "Agent that handles messages without a mention.",
+    ),
+    (
+        "dlp_profile",
+        "Data loss prevention profile applied to messages.",
+    ),
+    ("audit_level", "Audit level for channel traffic."),
+    (
+        "default_deny",
+        "Reject any action not allowed by a channel policy.",
+    ),
+];
+
+/// Properties of `memory` blocks.
+pub const MEMORY_KEYS: &[(&str, &str)] = &[
+    ("store", "Backing store; currently `markdown`."),
+    ("path", "Directory holding the memory files."),
+    ("retention", "How long entries are kept, e.g. `90d`."),
+];
+
+/// Properties of the `search` block inside `memory`.
+pub const MEMORY_SEARCH_KEYS: &[(&str, &str)] = &[
+    (
+        "vector_weight",
+        "Weight of semantic similarity in hybrid search.",
+    ),
+    (
+        "keyword_weight",
+        "Weight of keyword (BM25) matches in hybrid search.",
+    ),
+];
+
+/// Properties of `webhook` blocks.
+pub const WEBHOOK_KEYS: &[(&str, &str)] = &[
+    ("path", "HTTP path the webhook is served on."),
+    (
+        "provider",
+        "Signature scheme: `github`, `stripe`, `slack`, or `custom`.",
+    ),
+    ("secret", "Signing secret, usually a `vault://` reference."),
+    ("agent", "Agent invoked for each accepted request."),
+];
+
+/// Properties of the `filter` block inside `webhook`.
+pub const WEBHOOK_FILTER_KEYS: &[(&str, &str)] = &[
+    ("json_path", "JSON path into the request body to test."),
+    ("equals", "Accept the request when the value equals this."),
+    (
+        "contains",
+        "Accept the request when the value contains this.",
+    ),
+];
+
+/// Top-level item keywords.
+pub const TOP_LEVEL_KEYWORDS: &[(&str, &str)] = &[
+    (
+        "metadata",
+        "File metadata such as `version`, `author`, `description`.",
+    ),
+    (
+        "agent",
+        "Agent definition with capabilities, policies, and `with` blocks.",
+    ),
+    (
+        "policy",
+        "Named set of `allow`/`deny`/`require`/`audit` rules.",
+    ),
+    ("type", "User-defined struct type or alias."),
+    ("function", "Free-standing function."),
+    ("schedule", "Cron or one-shot schedule that runs an agent."),
+    ("channel", "Chat channel adapter binding."),
+    ("memory", "Persistent memory store definition."),
+    ("webhook", "Inbound webhook endpoint."),
+];
+
+/// Items allowed inside an agent body.
+pub const AGENT_KEYWORDS: &[(&str, &str)] = &[
+    (
+        "capabilities",
+        "Array of capability names the agent requires.",
+    ),
+    (
+        "policy",
+        "Named set of `allow`/`deny`/`require`/`audit` rules.",
+    ),
+    ("function", "Function scoped to the agent."),
+    (
+        "with",
+        "Block executed with the given sandbox and resource attributes.",
+    ),
+];
+
+/// Statement keywords available inside blocks.
+pub const STATEMENT_KEYWORDS: &[&str] = &[
+    "let", "if", "else", "for", "in", "match", "try", "catch", "return", "true", "false",
+];
+
+/// Look up a name in one of the tables above.
+pub fn lookup(table: &[(&str, &'static str)], name: &str) -> Option<&'static str> {
+    table.iter().find(|(n, _)| *n == name).map(|(_, doc)| *doc)
+}
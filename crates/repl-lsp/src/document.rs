@@ -0,0 +1,187 @@
+//! In-memory text documents tracked by the language server.
+//!
+//! LSP positions are (line, UTF-16 code unit) pairs while tree-sitter works in
+//! byte offsets, so every document keeps a line index to translate between the
+//! two. The syntax tree is rebuilt after each batch of edits.
+
+use tower_lsp::lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+use tree_sitter::{Node, Tree};
+
+/// An open `.symbi` document and its most recent parse.
+pub struct Document {
+    text: String,
+    line_starts: Vec<usize>,
+    tree: Option<Tree>,
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        let mut doc = Self {
+            text,
+            line_starts: Vec::new(),
+            tree: None,
+        };
+        doc.reparse();
+        doc
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The syntax tree, or `None` if the parser could not be initialised.
+    pub fn tree(&self) -> Option<&Tree> {
+        self.tree.as_ref()
+    }
+
+    /// Apply a batch of content changes in order and reparse once.
+    ///
+    /// A change without a range replaces the whole document (full sync).
+    pub fn apply_changes(&mut self, changes: Vec<TextDocumentContentChangeEvent>) {
+        for change in changes {
+            match change.range {
+                Some(range) => {
+                    let start = self.offset_at(range.start);
+                    let end = self.offset_at(range.end).max(start);
+                    self.text.replace_range(start..end, &change.text);
+                }
+                None => self.text = change.text,
+            }
+            self.index_lines();
+        }
+        self.reparse();
+    }
+
+    /// Convert an LSP position to a byte offset, clamping to the document.
+    pub fn offset_at(&self, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return self.text.len();
+        };
+        let line_end = self
+            .line_starts
+            .get(position.line as usize + 1)
+            .copied()
+            .unwrap_or(self.text.len());
+
+        let mut units = 0u32;
+        for (idx, ch) in self.text[line_start..line_end].char_indices() {
+            if units >= position.character || ch == '\n' || ch == '\r' {
+                return line_start + idx;
+            }
+            units += ch.len_utf16() as u32;
+        }
+        line_end
+    }
+
+    /// Convert a byte offset to an LSP position.
+    pub fn position_at(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self
+            .line_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1);
+        let line_start = self.line_starts[line];
+        let character = self.text[line_start..offset]
+            .chars()
+            .map(|c| c.len_utf16() as u32)
+            .sum();
+        Position::new(line as u32, character)
+    }
+
+    /// Byte offset of a zero-based row and byte column, as reported by
+    /// tree-sitter points.
+    pub fn line_col_offset(&self, line: usize, byte_col: usize) -> usize {
+        match self.line_starts.get(line) {
+            Some(start) => (start + byte_col).min(self.text.len()),
+            None => self.text.len(),
+        }
+    }
+
+    /// The LSP range covering a syntax node.
+    pub fn node_range(&self, node: Node) -> Range {
+        self.byte_range(node.start_byte(), node.end_byte())
+    }
+
+    pub fn byte_range(&self, start: usize, end: usize) -> Range {
+        Range::new(self.position_at(start), self.position_at(end))
+    }
+
+    /// The range spanning the entire document.
+    pub fn full_range(&self) -> Range {
+        self.byte_range(0, self.text.len())
+    }
+
+    /// Source text of a syntax node.
+    pub fn node_text(&self, node: Node) -> &str {
+        &self.text[node.start_byte()..node.end_byte()]
+    }
+
+    fn index_lines(&mut self) {
+        self.line_starts.clear();
+        self.line_starts.push(0);
+        self.line_starts.extend(
+            self.text
+                .bytes()
+                .enumerate()
+                .filter(|(_, b)| *b == b'\n')
+                .map(|(i, _)| i + 1),
+        );
+    }
+
+    fn reparse(&mut self) {
+        self.index_lines();
+        self.tree = dsl::parse_dsl(&self.text).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(range: Option<Range>, text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range,
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_positions_round_trip_with_utf16() {
+        let doc = Document::new("agent a {\n  // héllo 🦀 x\n}\n".to_string());
+        let crab = doc.text().find('🦀').unwrap();
+        let pos = doc.position_at(crab);
+        assert_eq!(pos, Position::new(1, 11));
+        assert_eq!(doc.offset_at(pos), crab);
+        // The crab is two UTF-16 code units wide.
+        let after = doc.offset_at(Position::new(1, 13));
+        assert_eq!(&doc.text()[after..after + 2], " x");
+        // Positions past the end of a line clamp to the newline.
+        assert_eq!(doc.offset_at(Position::new(0, 99)), 9);
+        assert_eq!(doc.offset_at(Position::new(42, 0)), doc.text().len());
+    }
+
+    #[test]
+    fn test_incremental_changes_apply_in_order() {
+        let mut doc = Document::new("agent a {\n}\n".to_string());
+        doc.apply_changes(vec![
+            change(
+                Some(Range::new(Position::new(0, 6), Position::new(0, 7))),
+                "reviewer",
+            ),
+            change(
+                Some(Range::new(Position::new(1, 0), Position::new(1, 0))),
+                "  capabilities = [\"read\"]\n",
+            ),
+        ]);
+        assert_eq!(
+            doc.text(),
+            "agent reviewer {\n  capabilities = [\"read\"]\n}\n"
+        );
+        assert!(!doc.tree().unwrap().root_node().has_error());
+
+        doc.apply_changes(vec![change(None, "agent b {}")]);
+        assert_eq!(doc.text(), "agent b {}");
+        assert_eq!(doc.full_range().end, Position::new(0, 10));
+    }
+}
@@ -1,4 +1,7 @@
+mod analysis;
 mod backend;
+mod docs;
+mod document;
 
 use backend::Backend;
 use tower_lsp::{LspService, Server};
@@ -9,7 +9,9 @@
 
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
+use symbi_runtime::context::embedding::create_embedding_service_from_env;
 use symbi_runtime::context::manager::{ContextManagerConfig, StandardContextManager};
+use symbi_runtime::context::vector_db_lance::{LanceDbBackend, LanceDbConfig};
 use symbi_runtime::rag::*;
 use symbi_runtime::types::AgentId;
 
@@ -26,14 +28,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let context_manager_config = ContextManagerConfig::default();
     let context_manager =
         Arc::new(StandardContextManager::new(context_manager_config, "system").await?);
-    let rag_engine = StandardRAGEngine::new(context_manager);
+
+    // Chunks are embedded with the provider configured via EMBEDDING_* or
+    // OPENAI_API_KEY and stored in a local LanceDB collection.
+    let embedding_service = create_embedding_service_from_env(384)?;
+    let vector_db = LanceDbBackend::new(LanceDbConfig {
+        data_path: std::env::temp_dir().join("symbiont_rag_example"),
+        collection_name: "rag_example".to_string(),
+        vector_dimension: embedding_service.embedding_dimension(),
+        ..Default::default()
+    })
+    .await?;
+    let rag_engine = StandardRAGEngine::new(context_manager)
+        .with_vector_db(Arc::new(vector_db))
+        .with_embedding_service(embedding_service.clone());
 
     // Configure the RAG engine
     let rag_config = RAGConfig {
         embedding_model: EmbeddingModelConfig {
             model_name: "text-embedding-ada-002".to_string(),
             model_type: EmbeddingModelType::OpenAI,
-            dimension: 384,
+            dimension: embedding_service.embedding_dimension(),
             max_tokens: 8192,
             batch_size: 100,
         },
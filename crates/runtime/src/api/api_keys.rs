@@ -48,6 +48,26 @@ pub struct ValidatedKey {
     pub agent_scope: Option<Vec<String>>,
 }
 
+#[cfg(feature = "http-api")]
+impl ValidatedKey {
+    /// Whether this key may act on `agent_id`. Unscoped keys may act on any
+    /// agent; scoped keys only on the agent IDs they list.
+    pub fn can_access(&self, agent_id: &crate::types::AgentId) -> bool {
+        match &self.agent_scope {
+            None => true,
+            Some(scope) => {
+                let target = agent_id.0.to_string();
+                scope.iter().any(|s| s == &target)
+            }
+        }
+    }
+
+    /// Whether this key is unscoped and may use control-plane endpoints.
+    pub fn is_admin(&self) -> bool {
+        self.agent_scope.is_none()
+    }
+}
+
 /// File-backed API key store with O(1) key lookup.
 ///
 /// Keys use the format `keyid.secret`. On validation the key ID prefix is
@@ -378,6 +398,27 @@ mod tests {
         assert!(!store.has_records());
     }
 
+    #[test]
+    fn test_agent_scope_access() {
+        let agent = crate::types::AgentId::new();
+        let other = crate::types::AgentId::new();
+
+        let unscoped = ValidatedKey {
+            key_id: "admin".to_string(),
+            agent_scope: None,
+        };
+        assert!(unscoped.is_admin());
+        assert!(unscoped.can_access(&agent));
+
+        let scoped = ValidatedKey {
+            key_id: "scoped".to_string(),
+            agent_scope: Some(vec![agent.0.to_string()]),
+        };
+        assert!(!scoped.is_admin());
+        assert!(scoped.can_access(&agent));
+        assert!(!scoped.can_access(&other));
+    }
+
     #[test]
     fn test_nonexistent_file() {
         let store =
@@ -21,7 +21,7 @@ use crate::reasoning::context_manager::DefaultContextManager;
 #[cfg(feature = "http-api")]
 use crate::reasoning::conversation::{Conversation, ConversationMessage};
 #[cfg(feature = "http-api")]
-use crate::reasoning::inference::{InferenceProvider, ToolDefinition};
+use crate::reasoning::inference::{InferenceProvider, StreamEvent, ToolDefinition};
 #[cfg(feature = "http-api")]
 use crate::reasoning::loop_types::{
     BufferedJournal, JournalEntry, LoopConfig, LoopEvent, TerminationReason,
@@ -52,6 +52,13 @@ Every action you propose is policy-evaluated before it runs.
 Your own actions are journaled to the operator's session; a delegated agent's \
 internal steps are policy-evaluated but are not journaled to the operator.";
 
+/// The `AgentId` the coordinator runs under, derived from a fixed name so
+/// it is the same in every process.
+#[cfg(feature = "http-api")]
+pub fn coordinator_agent_id() -> AgentId {
+    AgentId(Uuid::new_v5(&Uuid::NAMESPACE_OID, b"symbiont.coordinator"))
+}
+
 /// Shared state across all coordinator WebSocket connections.
 #[cfg(feature = "http-api")]
 pub struct CoordinatorState {
@@ -62,15 +69,17 @@ pub struct CoordinatorState {
     pub loop_config: LoopConfig,
     /// Live RAG retrieval bridge, or `None` when RAG is not configured/available.
     pub knowledge_bridge: Option<Arc<crate::reasoning::knowledge_bridge::KnowledgeBridge>>,
-    /// Stable namespace used for all coordinator knowledge store/recall calls.
-    /// Generated once per process so knowledge persists across turns and
-    /// sessions (single-runtime deployment). Must stay stable across the
-    /// process lifetime; if per-agent search filtering is added later, this
-    /// id is what identifies the coordinator's own knowledge namespace.
+    /// Identity the coordinator's reasoning loop runs under: the namespace
+    /// for its knowledge store/recall calls and the agent its spend is
+    /// billed to. Defaults to [`coordinator_agent_id`], which is the same in
+    /// every process, so knowledge and per-agent budgets survive restarts.
     pub knowledge_agent_id: AgentId,
     /// In-process agent-to-agent delegation handle, or `None` when no `./agents`
     /// registry was configured. Built once at construction via `with_delegation`.
     pub delegation: Option<Arc<dyn crate::reasoning::delegation::DelegationExecutor>>,
+    /// Spend tracker pricing and budgeting each chat turn, or `None` when
+    /// no `[spend]` config is loaded.
+    pub spend: Option<Arc<crate::reasoning::spend::SpendTracker>>,
 }
 
 #[cfg(feature = "http-api")]
@@ -94,11 +103,22 @@ impl CoordinatorState {
                 ..Default::default()
             },
             knowledge_bridge: None,
-            knowledge_agent_id: AgentId::new(),
+            knowledge_agent_id: coordinator_agent_id(),
             delegation: None,
+            spend: None,
         }
     }
 
+    /// Price and budget coordinator turns with `tracker`. Turns are billed
+    /// to the coordinator and to the API key the WebSocket authenticated with.
+    pub fn with_spend_tracker(
+        mut self,
+        tracker: Arc<crate::reasoning::spend::SpendTracker>,
+    ) -> Self {
+        self.spend = Some(tracker);
+        self
+    }
+
     /// Build and attach the live RAG knowledge bridge when RAG is usable
     /// (the `vector-lancedb` feature is built AND an embedding provider is
     /// configured). Otherwise leaves `knowledge_bridge` as `None` after logging
@@ -208,6 +228,8 @@ pub struct CoordinatorSession {
     conversation: Conversation,
     ws_tx: mpsc::Sender<ServerMessage>,
     session_id: String,
+    /// `key_id` of the API key this connection authenticated with, if any.
+    api_key_id: Option<String>,
 }
 
 #[cfg(feature = "http-api")]
@@ -219,9 +241,16 @@ impl CoordinatorSession {
             conversation: Conversation::with_system(COORDINATOR_SYSTEM_PROMPT),
             ws_tx,
             session_id: Uuid::new_v4().to_string(),
+            api_key_id: None,
         }
     }
 
+    /// Bill this session's turns to the API key `key_id`.
+    pub fn with_api_key(mut self, key_id: Option<String>) -> Self {
+        self.api_key_id = key_id;
+        self
+    }
+
     /// Handle a chat message from the user.
     ///
     /// Runs the reasoning loop and streams events to the WebSocket client.
@@ -233,7 +262,9 @@ impl CoordinatorSession {
 
         // Set up streaming journal
         let inner_journal = Arc::new(BufferedJournal::new(500));
-        let (journal_tx, mut journal_rx) = mpsc::channel::<JournalEntry>(64);
+        // Sized for token deltas: the journal drops (never blocks) when full,
+        // and the final `done` chunk always carries the complete output.
+        let (journal_tx, mut journal_rx) = mpsc::channel::<JournalEntry>(1024);
         let streaming_journal = Arc::new(StreamingJournal::new(inner_journal, journal_tx));
 
         // Build executor
@@ -241,9 +272,12 @@ impl CoordinatorSession {
             self.state.runtime_provider.clone(),
         ));
 
-        // Build loop config with tool definitions
+        // Build loop config with tool definitions. Inference is streamed so
+        // the bridge below can push text to the browser as it is generated.
         let mut config = self.state.loop_config.clone();
         config.tool_definitions = self.state.tool_definitions.clone();
+        config.stream_inference = true;
+        config.spend_tags.api_key = self.api_key_id.clone();
 
         // Build the runner
         let runner = ReasoningLoopRunner {
@@ -254,6 +288,8 @@ impl CoordinatorSession {
             circuit_breakers: Arc::new(CircuitBreakerRegistry::default()),
             journal: streaming_journal,
             knowledge_bridge: self.state.knowledge_bridge.clone(),
+            sandbox_sessions: None,
+            spend: self.state.spend.clone(),
             delegation: self.state.delegation.clone(),
         };
 
@@ -263,6 +299,14 @@ impl CoordinatorSession {
         let bridge_handle = tokio::spawn(async move {
             while let Some(entry) = journal_rx.recv().await {
                 let msg = match &entry.event {
+                    LoopEvent::InferenceDelta {
+                        delta: StreamEvent::TextDelta { text },
+                        ..
+                    } => Some(ServerMessage::ChatChunk {
+                        request_id: bridge_request_id.clone(),
+                        content: text.clone(),
+                        done: false,
+                    }),
                     LoopEvent::ReasoningComplete { actions, .. } => {
                         // Report tool call starts
                         for action in actions {
@@ -332,10 +376,9 @@ impl CoordinatorSession {
             }
         });
 
-        // Run the reasoning loop. The coordinator uses one stable knowledge
-        // namespace (set once at process start) so store/recall persist
-        // across turns and sessions in this single-runtime deployment; keep
-        // this stable if per-agent search filtering is added later.
+        // Run the reasoning loop under the coordinator's stable identity so
+        // store/recall and spend budgets carry across turns, sessions and
+        // restarts.
         let agent_id = self.state.knowledge_agent_id;
         tracing::info!(
             session_id = %self.session_id,
@@ -375,13 +418,26 @@ impl CoordinatorSession {
         }
 
         // Check for errors
-        if let TerminationReason::Error { ref message } = result.termination_reason {
+        let failure = match &result.termination_reason {
+            TerminationReason::Error { message } => Some(("LOOP_ERROR", message.clone())),
+            TerminationReason::BudgetExceeded {
+                scope,
+                period,
+                limit_usd,
+                ..
+            } => Some((
+                "BUDGET_EXCEEDED",
+                format!("{scope} reached its per-{period} spend limit of ${limit_usd:.2}"),
+            )),
+            _ => None,
+        };
+        if let Some((code, message)) = failure {
             if let Err(e) = self
                 .ws_tx
                 .send(ServerMessage::Error {
                     request_id: Some(request_id),
-                    code: "LOOP_ERROR".into(),
-                    message: message.clone(),
+                    code: code.into(),
+                    message,
                 })
                 .await
             {
@@ -162,6 +162,13 @@ pub fn extract_traceparent(headers: &axum::http::HeaderMap) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Request extension marking a caller authenticated by the legacy
+/// `SYMBIONT_API_TOKEN`. Such requests carry no `ValidatedKey`; the REST API
+/// treats them as admin.
+#[cfg(feature = "http-api")]
+#[derive(Debug, Clone, Copy)]
+pub struct LegacyTokenAuth;
+
 /// Authentication middleware for bearer token validation.
 ///
 /// Authentication strategy (fail-closed):
@@ -275,6 +282,8 @@ pub async fn auth_middleware(request: Request, next: Next) -> Result<Response, S
          Argon2 hashing, and key rotation. Set SYMBIONT_REFUSE_LEGACY_API_TOKEN=1 \
          once migration is complete to disable the env-var fallback."
     );
+    let mut request = request;
+    request.extensions_mut().insert(LegacyTokenAuth);
     Ok(next.run(request).await)
 }
 
@@ -565,6 +574,30 @@ pub async fn security_headers_middleware(
     Ok(response)
 }
 
+/// Record request latency in the Prometheus registry, labelled by the
+/// matched route template (never the raw path) and status class.
+#[cfg(feature = "http-api")]
+pub async fn http_metrics_middleware(request: Request, next: Next) -> Response {
+    use axum::extract::MatchedPath;
+
+    let method = request.method().as_str().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string());
+    let start = std::time::Instant::now();
+
+    let response = next.run(request).await;
+
+    crate::metrics::prometheus::global().observe_http_request(
+        &method,
+        route.as_deref(),
+        response.status().as_u16(),
+        start.elapsed(),
+    );
+    response
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
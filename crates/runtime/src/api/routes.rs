@@ -60,25 +60,15 @@ fn check_agent_access(
     agent_id: &AgentId,
 ) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
     match validated {
-        Some(key) => match &key.agent_scope {
-            None => Ok(()),
-            Some(scope) => {
-                let target = agent_id.0.to_string();
-                if scope.iter().any(|s| s == &target) {
-                    Ok(())
-                } else {
-                    Err((
-                        StatusCode::FORBIDDEN,
-                        Json(ErrorResponse {
-                            error: "API key is not scoped to this agent".to_string(),
-                            code: "AGENT_SCOPE_DENIED".to_string(),
-                            details: None,
-                        }),
-                    ))
-                }
-            }
-        },
-        None => Ok(()),
+        Some(key) if !key.can_access(agent_id) => Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: "API key is not scoped to this agent".to_string(),
+                code: "AGENT_SCOPE_DENIED".to_string(),
+                details: None,
+            }),
+        )),
+        _ => Ok(()),
     }
 }
 
@@ -93,7 +83,7 @@ pub(super) fn require_admin(
     validated: Option<&ValidatedKey>,
 ) -> Result<(), (StatusCode, Json<ErrorResponse>)> {
     match validated {
-        Some(key) if key.agent_scope.is_some() => Err((
+        Some(key) if !key.is_admin() => Err((
             StatusCode::FORBIDDEN,
             Json(ErrorResponse {
                 error: "Scoped API keys may not access this endpoint".to_string(),
@@ -145,12 +135,12 @@ pub async fn serve_agents_md() -> Result<
     ))
 }
 
-/// Strip sensitive sections from AGENTS.md content (inline helper).
+/// Strip sensitive sections from AGENTS.md content.
 ///
 /// Removes all content between `<!-- agents-md:sensitive-start -->` and
 /// `<!-- agents-md:sensitive-end -->` markers, including the markers themselves.
 #[cfg(feature = "http-api")]
-fn strip_sensitive_sections(content: &str) -> String {
+pub fn strip_sensitive_sections(content: &str) -> String {
     const SENSITIVE_START: &str = "<!-- agents-md:sensitive-start -->";
     const SENSITIVE_END: &str = "<!-- agents-md:sensitive-end -->";
 
@@ -313,11 +303,26 @@ pub async fn list_agents(
 )]
 pub async fn get_metrics(
     State(provider): State<Arc<dyn RuntimeApiProvider>>,
+    spend: Option<Extension<Arc<crate::reasoning::spend::SpendTracker>>>,
     validated: Option<Extension<ValidatedKey>>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<ErrorResponse>)> {
     require_admin(validated.as_ref().map(|Extension(k)| k))?;
     match provider.get_metrics().await {
-        Ok(metrics) => Ok(Json(metrics)),
+        Ok(mut metrics) => {
+            if let Some(Extension(tracker)) = &spend {
+                match (tracker.summary().await, metrics.as_object_mut()) {
+                    (Ok(summary), Some(fields)) => {
+                        fields.insert(
+                            "spend".into(),
+                            serde_json::to_value(summary).unwrap_or_default(),
+                        );
+                    }
+                    (Err(e), _) => tracing::warn!("metrics: spend ledger unavailable: {}", e),
+                    _ => {}
+                }
+            }
+            Ok(Json(metrics))
+        }
         Err(e) => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -329,6 +334,76 @@ pub async fn get_metrics(
     }
 }
 
+/// Prometheus scrape endpoint handler
+///
+/// Serves runtime, scheduler, cron, escalation and reasoning metrics in the
+/// OpenMetrics text format. The runtime provider and escalation queue are
+/// optional request extensions; their sections are omitted when absent or
+/// when reading them fails, so a partial outage still yields a scrape.
+#[cfg(feature = "http-api")]
+pub async fn get_prometheus_metrics(
+    provider: Option<Extension<Arc<dyn RuntimeApiProvider>>>,
+    queue: Option<Extension<Arc<crate::escalation::EscalationQueue>>>,
+    validated: Option<Extension<ValidatedKey>>,
+) -> Result<axum::response::Response, (StatusCode, Json<ErrorResponse>)> {
+    use crate::metrics::prometheus::{self, AgentCounts, CronStats, ScrapeStats};
+    use axum::response::IntoResponse;
+
+    require_admin(validated.as_ref().map(|Extension(k)| k))?;
+
+    let mut stats = ScrapeStats::default();
+    if let Some(Extension(provider)) = &provider {
+        match provider.get_metrics().await {
+            Ok(metrics) => {
+                let count = |pointer: &str| {
+                    metrics
+                        .pointer(pointer)
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0)
+                };
+                stats.agents = Some(AgentCounts {
+                    running: count("/agents/running"),
+                    idle: count("/agents/idle"),
+                    error: count("/agents/error"),
+                    uptime_seconds: count("/system/uptime_seconds"),
+                });
+            }
+            Err(e) => tracing::warn!("metrics scrape: runtime metrics unavailable: {}", e),
+        }
+        match provider.get_scheduler_health().await {
+            Ok(h) => {
+                stats.cron = Some(CronStats {
+                    is_running: h.is_running,
+                    jobs_active: h.jobs_active as u64,
+                    jobs_paused: h.jobs_paused as u64,
+                    jobs_dead_letter: h.jobs_dead_letter as u64,
+                    active_runs: h.global_active_runs as u64,
+                    max_concurrent: h.max_concurrent as u64,
+                    runs_total: h.runs_total,
+                    runs_succeeded: h.runs_succeeded,
+                    runs_failed: h.runs_failed,
+                    average_execution_time_ms: h.average_execution_time_ms,
+                    longest_run_ms: h.longest_run_ms,
+                })
+            }
+            Err(e) => tracing::warn!("metrics scrape: scheduler health unavailable: {}", e),
+        }
+    }
+    if let Some(Extension(queue)) = &queue {
+        stats.escalation_queue_depth = Some(queue.list_pending_async().await.len() as u64);
+    }
+
+    let body = prometheus::global().render(&stats).await;
+    Ok((
+        [(
+            axum::http::header::CONTENT_TYPE,
+            prometheus::OPENMETRICS_CONTENT_TYPE,
+        )],
+        body,
+    )
+        .into_response())
+}
+
 /// Create agent endpoint handler
 #[cfg(feature = "http-api")]
 #[utoipa::path(
@@ -1493,7 +1568,8 @@ pub async fn get_message_status(
 
 /// Aggregated operational status endpoint
 ///
-/// Returns a single-call rollup of runtime health and resource counts.
+/// Returns a single-call rollup of runtime health and resource counts,
+/// plus the status of each webhook route on the HTTP input server.
 /// Requires an admin (unscoped) API key.
 #[cfg(feature = "http-api")]
 #[utoipa::path(
@@ -1557,12 +1633,24 @@ pub async fn get_status(
         )
     })?;
 
+    let webhook_routes = provider.list_webhook_routes().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                code: "STATUS_WEBHOOKS_FAILED".to_string(),
+                details: None,
+            }),
+        )
+    })?;
+
     Ok(Json(StatusResponse {
         version: env!("CARGO_PKG_VERSION").to_string(),
         healthy,
         agent_count: agents.len() as u64,
         schedule_count: schedules.len() as u64,
         channel_count: channels.len() as u64,
+        webhook_routes,
     }))
 }
 
@@ -1624,6 +1712,7 @@ mod scope_tests {
 #[cfg(all(test, feature = "http-api"))]
 mod status_tests {
     use super::*;
+    use crate::api::types::WebhookRouteSummary;
     use crate::types::RuntimeError;
     use async_trait::async_trait;
     use axum::extract::State;
@@ -1632,6 +1721,7 @@ mod status_tests {
         agents: usize,
         schedules: usize,
         channels: usize,
+        webhooks: usize,
         healthy: bool,
     }
 
@@ -1691,6 +1781,9 @@ mod status_tests {
         ) -> Result<GetAgentHistoryResponse, RuntimeError> {
             unimplemented!()
         }
+        async fn record_execution(&self, _: AgentId, _: &str) -> Result<String, RuntimeError> {
+            unimplemented!()
+        }
         async fn list_schedules(&self) -> Result<Vec<ScheduleSummary>, RuntimeError> {
             Ok((0..self.schedules)
                 .map(|i| ScheduleSummary {
@@ -1760,6 +1853,25 @@ mod status_tests {
                 })
                 .collect())
         }
+        async fn list_webhook_routes(&self) -> Result<Vec<WebhookRouteSummary>, RuntimeError> {
+            Ok((0..self.webhooks)
+                .map(|i| WebhookRouteSummary {
+                    name: format!("hook-{i}"),
+                    path: format!("/hooks/{i}"),
+                    provider: "github".to_string(),
+                    agent: AgentId::new().to_string(),
+                    signature_required: true,
+                    state: "active".to_string(),
+                    error: None,
+                    received: i as u64,
+                    accepted: i as u64,
+                    filtered: 0,
+                    rejected: 0,
+                    failed: 0,
+                    last_request_at: None,
+                })
+                .collect())
+        }
         async fn register_channel(
             &self,
             _: RegisterChannelRequest,
@@ -1849,6 +1961,7 @@ mod status_tests {
             agents: 3,
             schedules: 2,
             channels: 1,
+            webhooks: 2,
             healthy: true,
         });
         let result = get_status(State(provider), None).await;
@@ -1857,6 +1970,8 @@ mod status_tests {
         assert_eq!(status.agent_count, 3);
         assert_eq!(status.schedule_count, 2);
         assert_eq!(status.channel_count, 1);
+        assert_eq!(status.webhook_routes.len(), 2);
+        assert_eq!(status.webhook_routes[1].path, "/hooks/1");
         assert!(status.healthy);
         assert_eq!(status.version, env!("CARGO_PKG_VERSION"));
     }
@@ -1867,6 +1982,7 @@ mod status_tests {
             agents: 0,
             schedules: 0,
             channels: 0,
+            webhooks: 0,
             healthy: false,
         });
         let result = get_status(State(provider), None).await;
@@ -1884,6 +2000,7 @@ mod status_tests {
             agents: 0,
             schedules: 0,
             channels: 0,
+            webhooks: 0,
             healthy: true,
         });
         let k = ValidatedKey {
@@ -40,7 +40,7 @@ use super::types::{
     ScheduleActionResponse, ScheduleDetail, ScheduleHistoryResponse, ScheduleRunEntry,
     ScheduleSummary, SchedulerHealthResponse, SendMessageRequest, SendMessageResponse,
     StatusResponse, UpdateAgentRequest, UpdateAgentResponse, UpdateChannelRequest,
-    UpdateScheduleRequest, WorkflowExecutionRequest,
+    UpdateScheduleRequest, WebhookRouteSummary, WorkflowExecutionRequest,
 };
 
 #[cfg(feature = "http-api")]
@@ -144,7 +144,8 @@ use crate::types::RuntimeError;
             ReceiveMessagesResponse,
             MessageEnvelope,
             MessageStatusResponse,
-            StatusResponse
+            StatusResponse,
+            WebhookRouteSummary
         )
     ),
     tags(
@@ -214,6 +215,8 @@ pub struct HttpApiServer {
     api_key_store: Option<Arc<super::api_keys::ApiKeyStore>>,
     coordinator_state: Option<Arc<super::coordinator::CoordinatorState>>,
     escalation_queue: Option<Arc<crate::escalation::EscalationQueue>>,
+    spend_tracker: Option<Arc<crate::reasoning::spend::SpendTracker>>,
+    mcp_router: Option<Router>,
 }
 
 #[cfg(feature = "http-api")]
@@ -227,6 +230,8 @@ impl HttpApiServer {
             api_key_store: None,
             coordinator_state: None,
             escalation_queue: None,
+            spend_tracker: None,
+            mcp_router: None,
         }
     }
 
@@ -251,6 +256,24 @@ impl HttpApiServer {
         self
     }
 
+    /// Attach the spend tracker so `/api/v1/metrics` reports today's and this
+    /// month's spend per agent, cron job and API key.
+    pub fn with_spend_tracker(
+        mut self,
+        tracker: Arc<crate::reasoning::spend::SpendTracker>,
+    ) -> Self {
+        self.spend_tracker = Some(tracker);
+        self
+    }
+
+    /// Mount an MCP Streamable HTTP endpoint. The router's routes are served
+    /// behind the same bearer authentication as the REST API; handlers find
+    /// the caller's `ValidatedKey` in the request extensions.
+    pub fn with_mcp_router(mut self, router: Router) -> Self {
+        self.mcp_router = Some(router);
+        self
+    }
+
     /// Start the HTTP API server
     pub async fn start(&mut self) -> Result<(), RuntimeError> {
         // Initialize trusted proxy configuration from SYMBIONT_TRUSTED_PROXIES
@@ -461,11 +484,15 @@ impl HttpApiServer {
             // load-balancer probes; /api/v1/health/scheduler exposes job counts and
             // run stats so it requires auth. /api/v1/status is an aggregated rollup
             // useful for operators and monitoring.
-            let protected_router = Router::new()
+            let mut protected_router = Router::new()
                 .route("/api/v1/workflows/execute", post(execute_workflow))
                 .route("/api/v1/metrics", get(get_metrics))
                 .route("/api/v1/health/scheduler", get(get_scheduler_health))
-                .route("/api/v1/status", get(get_status))
+                .route("/api/v1/status", get(get_status));
+            if let Some(tracker) = &self.spend_tracker {
+                protected_router = protected_router.layer(axum::Extension(tracker.clone()));
+            }
+            let protected_router = protected_router
                 .layer(middleware::from_fn(auth_middleware))
                 .with_state(provider.clone());
 
@@ -509,6 +536,36 @@ impl HttpApiServer {
             router = router.merge(escalation_router);
         }
 
+        // MCP Streamable HTTP endpoint, authenticated like the REST routes.
+        if let Some(mcp_router) = &self.mcp_router {
+            use super::middleware::auth_middleware;
+            use axum::middleware;
+
+            router = router.merge(
+                mcp_router
+                    .clone()
+                    .layer(middleware::from_fn(auth_middleware)),
+            );
+        }
+
+        // Prometheus scrape endpoint (OpenMetrics text), authenticated like
+        // `/api/v1/metrics`. Runtime and escalation sections are included
+        // when their sources are attached.
+        {
+            use super::middleware::auth_middleware;
+            use axum::middleware;
+
+            let mut metrics_router =
+                Router::new().route("/metrics", get(super::routes::get_prometheus_metrics));
+            if let Some(provider) = &self.runtime_provider {
+                metrics_router = metrics_router.layer(axum::Extension(provider.clone()));
+            }
+            if let Some(queue) = &self.escalation_queue {
+                metrics_router = metrics_router.layer(axum::Extension(queue.clone()));
+            }
+            router = router.merge(metrics_router.layer(middleware::from_fn(auth_middleware)));
+        }
+
         // Mount Swagger UI + OpenAPI spec only if explicitly enabled and not
         // in production. The routes go behind the bearer auth_middleware so
         // an accidentally-set flag in staging still requires a valid token
@@ -548,13 +605,23 @@ impl HttpApiServer {
             router = router.layer(axum::Extension(store.clone()));
         }
 
+        // Record per-route latency for the Prometheus endpoint.
+        router = router.layer(axum::middleware::from_fn(
+            crate::api::middleware::http_metrics_middleware,
+        ));
+
+        // Open a server span per request, continuing the caller's trace.
+        router = router.layer(axum::middleware::from_fn(
+            crate::telemetry::trace_context_middleware,
+        ));
+
         // Add middleware conditionally
         if self.config.enable_tracing {
             router = router.layer(TraceLayer::new_for_http());
         }
 
         if self.config.enable_cors {
-            use axum::http::{header, HeaderValue, Method};
+            use axum::http::{header, HeaderName, HeaderValue, Method};
 
             let allowed_origins: Vec<HeaderValue> = std::env::var("SYMBIONT_CORS_ORIGINS")
                 .unwrap_or_else(|_| "http://localhost:3001,http://localhost:3000".to_string())
@@ -565,7 +632,14 @@ impl HttpApiServer {
             let cors = CorsLayer::new()
                 .allow_origin(allowed_origins)
                 .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-                .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+                .allow_headers([
+                    header::AUTHORIZATION,
+                    header::CONTENT_TYPE,
+                    HeaderName::from_static("mcp-session-id"),
+                    HeaderName::from_static("mcp-protocol-version"),
+                    HeaderName::from_static("last-event-id"),
+                ])
+                .expose_headers([HeaderName::from_static("mcp-session-id")])
                 .allow_credentials(false);
 
             router = router.layer(cors);
@@ -20,7 +20,7 @@ use super::types::{
     RegisterChannelResponse, ScheduleActionResponse, ScheduleDetail, ScheduleHistoryResponse,
     ScheduleSummary, SchedulerHealthResponse, SendMessageRequest, SendMessageResponse,
     UpdateAgentRequest, UpdateAgentResponse, UpdateChannelRequest, UpdateScheduleRequest,
-    WorkflowExecutionRequest,
+    WebhookRouteSummary, WorkflowExecutionRequest,
 };
 
 /// Trait providing API access to core runtime functionalities
@@ -59,6 +59,15 @@ pub trait RuntimeApiProvider: Send + Sync {
             .collect())
     }
 
+    /// The DSL source an agent was registered from.
+    async fn get_agent_dsl(&self, agent_id: AgentId) -> Result<String, RuntimeError> {
+        // Default: no agent records to read it from
+        Err(RuntimeError::Internal(format!(
+            "DSL source for agent {} is not available",
+            agent_id
+        )))
+    }
+
     /// Shutdown an agent gracefully
     async fn shutdown_agent(&self, agent_id: AgentId) -> Result<(), RuntimeError>;
 
@@ -94,6 +103,15 @@ pub trait RuntimeApiProvider: Send + Sync {
         agent_id: AgentId,
     ) -> Result<GetAgentHistoryResponse, RuntimeError>;
 
+    /// Record work done on an agent's behalf outside the scheduler (e.g. a
+    /// single completion answered over MCP) in its execution history,
+    /// without starting the agent. Returns the new execution ID.
+    async fn record_execution(
+        &self,
+        agent_id: AgentId,
+        status: &str,
+    ) -> Result<String, RuntimeError>;
+
     // ── Schedule endpoints ──────────────────────────────────────────
 
     /// List all scheduled jobs.
@@ -168,6 +186,14 @@ pub trait RuntimeApiProvider: Send + Sync {
     /// Delete a channel adapter.
     async fn delete_channel(&self, id: &str) -> Result<DeleteChannelResponse, RuntimeError>;
 
+    // ── Webhook endpoints ──────────────────────────────────────────
+
+    /// List the webhook routes served by the HTTP input server.
+    async fn list_webhook_routes(&self) -> Result<Vec<WebhookRouteSummary>, RuntimeError> {
+        // Default: no HTTP input server attached
+        Ok(vec![])
+    }
+
     /// Start a channel adapter.
     async fn start_channel(&self, id: &str) -> Result<ChannelActionResponse, RuntimeError>;
 
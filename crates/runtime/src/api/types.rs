@@ -677,6 +677,42 @@ pub struct StatusResponse {
     pub schedule_count: u64,
     /// Number of channel adapters currently registered.
     pub channel_count: u64,
+    /// Webhook routes served by the HTTP input server, with request counters.
+    #[serde(default)]
+    pub webhook_routes: Vec<WebhookRouteSummary>,
+}
+
+/// Status of one webhook route on the HTTP input server.
+#[cfg(feature = "http-api")]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct WebhookRouteSummary {
+    /// Route name (the DSL `webhook` block name).
+    pub name: String,
+    /// HTTP path the route is served on.
+    pub path: String,
+    /// Signature provider preset: "github", "stripe", "slack", or "custom".
+    pub provider: String,
+    /// ID of the agent invoked for accepted requests.
+    pub agent: String,
+    /// `true` when requests must carry a valid provider signature.
+    pub signature_required: bool,
+    /// One of: "active", "disabled".
+    pub state: String,
+    /// Why the route is disabled, if it is.
+    pub error: Option<String>,
+    /// Requests received on this route.
+    pub received: u64,
+    /// Requests that reached the agent.
+    pub accepted: u64,
+    /// Requests acknowledged without invoking the agent because the filter
+    /// did not match.
+    pub filtered: u64,
+    /// Requests rejected for a bad signature or malformed body.
+    pub rejected: u64,
+    /// Accepted requests whose agent invocation failed.
+    pub failed: u64,
+    /// Time of the most recent request.
+    pub last_request_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[cfg(test)]
@@ -41,20 +41,24 @@ pub struct WsChatParams {
 /// Validate a bearer token against the API key store or legacy env var.
 ///
 /// Mirrors the logic in `auth_middleware` but works with a raw token string
-/// instead of HTTP headers. Returns `true` if the token is valid.
+/// instead of HTTP headers. Returns `None` if the token is invalid, and
+/// otherwise the matched key's `key_id` (`None` for the legacy token).
 #[cfg(feature = "http-api")]
-fn validate_token(token: &str, key_store: Option<&Arc<super::api_keys::ApiKeyStore>>) -> bool {
+fn validate_token(
+    token: &str,
+    key_store: Option<&Arc<super::api_keys::ApiKeyStore>>,
+) -> Option<Option<String>> {
     // Primary: API key store
     if let Some(store) = key_store {
         if store.has_records() {
-            return store.validate_key(token).is_some();
+            return store.validate_key(token).map(|key| Some(key.key_id));
         }
     }
 
     // Fallback: legacy env var
     match std::env::var("SYMBIONT_API_TOKEN") {
-        Ok(expected) => bool::from(token.as_bytes().ct_eq(expected.as_bytes())),
-        Err(_) => false,
+        Ok(expected) if bool::from(token.as_bytes().ct_eq(expected.as_bytes())) => Some(None),
+        _ => None,
     }
 }
 
@@ -94,23 +98,25 @@ pub async fn ws_chat_handler(
     let token = params.token.as_deref().ok_or(StatusCode::UNAUTHORIZED)?;
     let store_ref = key_store.as_ref().map(|ext| &ext.0);
 
-    if !validate_token(token, store_ref) {
-        return Err(StatusCode::UNAUTHORIZED);
-    }
+    let api_key_id = validate_token(token, store_ref).ok_or(StatusCode::UNAUTHORIZED)?;
 
-    Ok(ws.on_upgrade(move |socket| handle_socket(socket, coordinator_state)))
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, coordinator_state, api_key_id)))
 }
 
 /// Drive a single WebSocket connection.
 #[cfg(feature = "http-api")]
-async fn handle_socket(socket: WebSocket, state: Arc<CoordinatorState>) {
+async fn handle_socket(
+    socket: WebSocket,
+    state: Arc<CoordinatorState>,
+    api_key_id: Option<String>,
+) {
     let (mut ws_writer, mut ws_reader) = socket.split();
 
     // Channel for outbound messages (session → WebSocket writer)
     let (out_tx, mut out_rx) = mpsc::channel::<ServerMessage>(64);
 
     // Create per-connection session
-    let mut session = CoordinatorSession::new(state, out_tx.clone());
+    let mut session = CoordinatorSession::new(state, out_tx.clone()).with_api_key(api_key_id);
 
     // Writer task: forward ServerMessages to the WebSocket
     use axum::extract::ws::Message as WsMessage;
@@ -23,6 +23,17 @@
 //!   export CHAT_MODEL=gemma4:latest
 //!   ```
 //!
+//! ## Cassettes
+//!
+//! `--cassette <path>` (or `SYMBI_CASSETTE`) routes inference through a
+//! record/replay cassette; `--cassette-mode record|replay|lenient` (or
+//! `SYMBI_CASSETTE_MODE`, default `replay`) picks the mode. Strict replay
+//! needs no API key, so recorded evals run offline and deterministically:
+//!   ```bash
+//!   symbi-eval --task-file task.json --cassette task.cassette.json --cassette-mode record
+//!   symbi-eval --task-file task.json --cassette task.cassette.json
+//!   ```
+//!
 //! Build with `--features cloud-llm`.
 
 #![cfg(feature = "cloud-llm")]
@@ -40,6 +51,7 @@ use symbi_runtime::reasoning::inference::{InferenceProvider, ToolDefinition};
 use symbi_runtime::reasoning::loop_types::{
     BufferedJournal, JournalEntry, LoopConfig, LoopResult, Observation, ProposedAction,
 };
+use symbi_runtime::reasoning::providers::cassette::{CassetteConfig, CassetteProvider};
 use symbi_runtime::reasoning::providers::cloud::CloudInferenceProvider;
 use symbi_runtime::reasoning::reasoning_loop::ReasoningLoopRunner;
 use symbi_runtime::types::AgentId;
@@ -612,6 +624,17 @@ impl ActionExecutor for HybridToolExecutor {
 // main
 // ---------------------------------------------------------------------------
 
+/// Value following `name` on the command line.
+fn flag_value<'a>(args: &'a [String], name: &str) -> Result<Option<&'a str>, String> {
+    match args.iter().position(|a| a == name) {
+        Some(idx) => args
+            .get(idx + 1)
+            .map(|v| Some(v.as_str()))
+            .ok_or_else(|| format!("{} requires a value", name)),
+        None => Ok(None),
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Read task JSON. Either --task-file <path> or stdin.
@@ -638,12 +661,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tool.name = sanitize_tool_name(&tool.name);
     }
 
-    // Build inference provider from environment.
-    let provider = CloudInferenceProvider::from_env().ok_or_else(|| {
-        "No LLM provider configured. Set OPENAI_API_KEY (+ OPENAI_BASE_URL for Ollama), \
-         OPENROUTER_API_KEY, or ANTHROPIC_API_KEY"
-            .to_string()
-    })?;
+    // Build inference provider from environment, optionally behind a cassette.
+    let live =
+        CloudInferenceProvider::from_env().map(|p| Arc::new(p) as Arc<dyn InferenceProvider>);
+    let cassette = CassetteConfig::resolve(
+        flag_value(&args, "--cassette")?,
+        flag_value(&args, "--cassette-mode")?,
+    )?;
+    let provider: Arc<dyn InferenceProvider> = match cassette {
+        Some(config) => Arc::new(CassetteProvider::open(&config, live)?),
+        None => live.ok_or_else(|| {
+            "No LLM provider configured. Set OPENAI_API_KEY (+ OPENAI_BASE_URL for Ollama), \
+             OPENROUTER_API_KEY, or ANTHROPIC_API_KEY, or replay a --cassette"
+                .to_string()
+        })?,
+    };
 
     // Build the per-task sandbox if the task declares any non-mock tool.
     // Stays None for the common mock-only case (zero overhead).
@@ -698,7 +730,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .await;
 
     let runner = ReasoningLoopRunner::builder()
-        .provider(Arc::new(provider) as Arc<dyn InferenceProvider>)
+        .provider(provider)
         .executor(executor as Arc<dyn ActionExecutor>)
         .policy_gate(policy_gate)
         .circuit_breakers(circuit_breakers)
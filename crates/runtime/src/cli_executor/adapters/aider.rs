@@ -243,6 +243,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -268,6 +270,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -290,6 +294,8 @@ mod tests {
             success: false,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
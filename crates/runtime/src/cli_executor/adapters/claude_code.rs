@@ -396,6 +396,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -421,6 +423,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -444,6 +448,8 @@ mod tests {
             success: false,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -603,6 +609,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
         let out = adapter.parse_output(&sample_request(), exec);
         let parsed = out.parsed_output.expect("stream-json must still parse");
@@ -277,6 +277,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -304,6 +306,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -327,6 +331,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -349,6 +355,8 @@ mod tests {
             success: false,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
@@ -374,6 +382,8 @@ mod tests {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         };
 
         let codegen = adapter.parse_output(&request, result);
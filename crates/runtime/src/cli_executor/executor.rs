@@ -329,6 +329,8 @@ impl CliExecutor {
                         success: false,
                         stdout_truncated: stdout_out.truncated,
                         stderr_truncated: stderr_out.truncated,
+                        artifacts: Vec::new(),
+                        artifacts_truncated: false,
                     });
                 }
 
@@ -360,6 +362,8 @@ impl CliExecutor {
                     success,
                     stdout_truncated: stdout_out.truncated,
                     stderr_truncated: stderr_out.truncated,
+                    artifacts: Vec::new(),
+                    artifacts_truncated: false,
                 })
             }
             Err(_) => {
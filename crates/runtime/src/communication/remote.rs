@@ -358,6 +358,10 @@ impl RemoteCommunicationBus {
         if let Some(ref token) = self.token {
             req = req.bearer_auth(token);
         }
+        // Carry the caller's trace so the remote runtime's spans join it.
+        for (name, value) in crate::telemetry::current_trace_headers() {
+            req = req.header(name, value);
+        }
         if let Some(body) = body {
             req = req.json(&body);
         }
@@ -54,6 +54,12 @@ pub struct Config {
     pub cli_executor: Option<CliExecutorConfigToml>,
     /// Escalation configuration (optional)
     pub escalation: Option<EscalationConfig>,
+    /// Inference provider failover chain (optional)
+    pub failover: Option<crate::reasoning::providers::failover::FailoverConfig>,
+    /// Token pricing and spend budgets (optional)
+    pub spend: Option<crate::reasoning::spend::SpendConfig>,
+    /// Persistent sandbox sessions for reasoning runs (optional)
+    pub sandbox_sessions: Option<crate::sandbox::SandboxSessionsConfig>,
 }
 
 /// API configuration.
@@ -471,7 +477,7 @@ pub struct NetworkDestination {
 }
 
 /// Network protocol enumeration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NetworkProtocol {
     TCP,
     UDP,
@@ -0,0 +1,199 @@
+//! Per-agent memory stores declared by DSL `memory` blocks.
+//!
+//! An [`AgentMemoryConfig`] is the runtime form of a
+//! [`dsl::MemoryDefinition`] bound to the agent declared in the same file.
+//! [`AgentMemoryConfig::build_context_manager`] turns it into a
+//! [`StandardContextManager`] that persists through the declared store,
+//! archives memory after the declared retention, and ranks hybrid queries
+//! with the declared search weights.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::keyword_index::HybridStrategy;
+use super::manager::{ContextManagerConfig, StandardContextManager};
+use super::markdown_memory::MarkdownMemoryStore;
+use super::types::{ContextError, ContextPersistence};
+use crate::types::AgentId;
+
+/// A memory store attached to one agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentMemoryConfig {
+    /// Memory block name, used in logs.
+    pub name: String,
+    /// Agent whose context is kept in this store.
+    pub agent: AgentId,
+    /// Backend store type.
+    pub store: dsl::MemoryStoreType,
+    /// Root directory of the store; each agent gets a subdirectory.
+    pub path: PathBuf,
+    /// How long memory entries and daily logs are kept.
+    pub retention: Duration,
+    /// How keyword and vector rankings are combined when recalling memory.
+    pub hybrid_search: HybridStrategy,
+}
+
+impl AgentMemoryConfig {
+    /// Build the config for a DSL `memory` block attached to `agent`.
+    /// Blocks without a `search` section use the default hybrid weights.
+    pub fn from_definition(definition: &dsl::MemoryDefinition, agent: AgentId) -> Self {
+        let hybrid_search = match &definition.search {
+            Some(search) => HybridStrategy::Weighted {
+                vector_weight: search.vector_weight as f32,
+                keyword_weight: search.keyword_weight as f32,
+            },
+            None => HybridStrategy::default(),
+        };
+        Self {
+            name: definition.name.clone(),
+            agent,
+            store: definition.store.clone(),
+            path: definition.path.clone(),
+            retention: definition.retention,
+            hybrid_search,
+        }
+    }
+
+    /// Context manager settings for this store: the declared retention for
+    /// memory items and the declared hybrid search weights.
+    pub fn context_manager_config(&self) -> ContextManagerConfig {
+        let mut config = ContextManagerConfig::default();
+        config.default_retention_policy.memory_retention = self.retention;
+        config.hybrid_search = self.hybrid_search;
+        config.enable_persistence = true;
+        config
+    }
+
+    /// Open the declared store, creating its directory if needed.
+    pub fn open_store(&self) -> Result<Arc<dyn ContextPersistence>, ContextError> {
+        let path = self.path.to_string_lossy();
+        dsl::semantic::validate_memory_path(&path).map_err(|reason| {
+            ContextError::InvalidOperation {
+                reason: format!("memory '{}': {}", self.name, reason),
+            }
+        })?;
+        std::fs::create_dir_all(&self.path).map_err(|e| ContextError::StorageError {
+            reason: format!(
+                "memory '{}': cannot create {}: {}",
+                self.name,
+                self.path.display(),
+                e
+            ),
+        })?;
+
+        match self.store {
+            dsl::MemoryStoreType::Markdown => Ok(Arc::new(MarkdownMemoryStore::new(
+                self.path.clone(),
+                self.retention,
+            ))),
+        }
+    }
+
+    /// Build and initialize a context manager backed by this store. Daily
+    /// logs older than the retention are compacted away first.
+    pub async fn build_context_manager(&self) -> Result<StandardContextManager, ContextError> {
+        let store = self.open_store()?;
+        if let Some(markdown) = store.as_any().downcast_ref::<MarkdownMemoryStore>() {
+            markdown.compact(self.agent).await?;
+        }
+
+        let manager =
+            StandardContextManager::new(self.context_manager_config(), &self.agent.to_string())
+                .await?
+                .with_persistence(store);
+        manager.initialize().await?;
+        Ok(manager)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::manager::ContextManager;
+    use crate::context::types::{
+        ContextId, MemoryTarget, MemoryType, MemoryUpdate, UpdateOperation,
+    };
+
+    fn definition(path: PathBuf) -> dsl::MemoryDefinition {
+        let source = format!(
+            "memory notes {{\n    store markdown\n    path \"{}\"\n    retention 30d\n    \
+             search {{\n        vector_weight 0.2\n        keyword_weight 0.8\n    }}\n}}\n",
+            path.display()
+        );
+        let tree = dsl::parse_dsl(&source).unwrap();
+        dsl::extract_memory_definitions(&tree, &source)
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn test_from_definition_maps_weights_and_retention() {
+        let agent = AgentId::new();
+        let config = AgentMemoryConfig::from_definition(&definition("data/mem".into()), agent);
+        assert_eq!(config.agent, agent);
+        assert_eq!(config.path, PathBuf::from("data/mem"));
+        assert_eq!(config.retention, Duration::from_secs(30 * 86400));
+        assert_eq!(
+            config.hybrid_search,
+            HybridStrategy::Weighted {
+                vector_weight: 0.2,
+                keyword_weight: 0.8,
+            }
+        );
+
+        let manager_config = config.context_manager_config();
+        assert_eq!(manager_config.hybrid_search, config.hybrid_search);
+        assert_eq!(
+            manager_config.default_retention_policy.memory_retention,
+            config.retention
+        );
+    }
+
+    #[test]
+    fn test_open_store_rejects_traversal() {
+        let mut config =
+            AgentMemoryConfig::from_definition(&definition("data/mem".into()), AgentId::new());
+        config.path = PathBuf::from("data/../../etc");
+        assert!(matches!(
+            config.open_store(),
+            Err(ContextError::InvalidOperation { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_context_persists_to_markdown_store() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("memory");
+        let agent = AgentId::new();
+        let config = AgentMemoryConfig::from_definition(&definition(root.clone()), agent);
+
+        let manager = config.build_context_manager().await.unwrap();
+        manager.create_session(agent).await.unwrap();
+        manager
+            .update_memory(
+                agent,
+                vec![MemoryUpdate {
+                    operation: UpdateOperation::Add,
+                    target: MemoryTarget::LongTerm(ContextId::new()),
+                    data: serde_json::json!({
+                        "content": "Prefers concise reviews",
+                        "memory_type": MemoryType::Factual,
+                    }),
+                }],
+            )
+            .await
+            .unwrap();
+
+        let memory_file = root.join(agent.to_string()).join("memory.md");
+        let markdown = std::fs::read_to_string(&memory_file).unwrap();
+        assert!(markdown.contains("Prefers concise reviews"), "{}", markdown);
+
+        // A fresh manager over the same store sees the stored memory.
+        let reopened = config.build_context_manager().await.unwrap();
+        let context = reopened.retrieve_context(agent, None).await.unwrap();
+        assert_eq!(context.unwrap().memory.long_term.len(), 1);
+    }
+}
@@ -0,0 +1,656 @@
+//! BM25 keyword index and hybrid rank fusion
+//!
+//! [`Bm25Index`] is an embedded inverted index that sits alongside the vector
+//! store. Documents live in named scopes (an agent's memory, a RAG corpus) so
+//! term statistics are computed per scope. When opened with a path the index
+//! is persisted as JSON; for LanceDB the file lives next to the table
+//! directory (see [`keyword_index_path`](super::vector_db_factory::keyword_index_path)).
+//!
+//! [`HybridStrategy`] fuses a vector ranking with a keyword ranking, either by
+//! reciprocal rank fusion or by a weighted sum of normalised scores.
+
+use super::types::ContextError;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Words too common to carry any ranking signal.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "in",
+    "is", "it", "its", "of", "on", "or", "that", "the", "this", "to", "was", "were", "what",
+    "when", "where", "which", "who", "why", "how", "will", "with",
+];
+
+/// Split text into lowercase index terms.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .filter(|t| t.chars().count() > 1 && !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+/// BM25 tuning parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bm25Params {
+    /// Term frequency saturation.
+    pub k1: f32,
+    /// Document length normalisation (0 = none, 1 = full).
+    pub b: f32,
+}
+
+impl Default for Bm25Params {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+impl Bm25Params {
+    fn idf(&self, document_count: usize, document_frequency: usize) -> f32 {
+        let n = document_count as f32;
+        let df = document_frequency as f32;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    fn term_score(&self, idf: f32, tf: u32, length: u32, avg_length: f32) -> f32 {
+        let tf = tf as f32;
+        let norm = 1.0 - self.b + self.b * length as f32 / avg_length.max(1.0);
+        idf * tf * (self.k1 + 1.0) / (tf + self.k1 * norm)
+    }
+}
+
+/// A document matched by a keyword search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordHit {
+    /// Key the document was indexed under.
+    pub key: String,
+    /// Raw BM25 score (unbounded, higher is better).
+    pub score: f32,
+    /// Fields stored alongside the document at index time.
+    pub stored: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct IndexedDocument {
+    fingerprint: u64,
+    length: u32,
+    terms: HashMap<String, u32>,
+    #[serde(default)]
+    stored: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Scope {
+    documents: HashMap<String, IndexedDocument>,
+    #[serde(skip)]
+    postings: HashMap<String, HashSet<String>>,
+    #[serde(skip)]
+    total_length: u64,
+}
+
+impl Scope {
+    fn rebuild(&mut self) {
+        self.postings.clear();
+        self.total_length = 0;
+        for (key, doc) in &self.documents {
+            self.total_length += doc.length as u64;
+            for term in doc.terms.keys() {
+                self.postings
+                    .entry(term.clone())
+                    .or_default()
+                    .insert(key.clone());
+            }
+        }
+    }
+
+    fn insert(&mut self, key: &str, doc: IndexedDocument) {
+        self.remove(key);
+        self.total_length += doc.length as u64;
+        for term in doc.terms.keys() {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(key.to_string());
+        }
+        self.documents.insert(key.to_string(), doc);
+    }
+
+    fn remove(&mut self, key: &str) -> bool {
+        let Some(doc) = self.documents.remove(key) else {
+            return false;
+        };
+        self.total_length -= doc.length as u64;
+        for term in doc.terms.keys() {
+            if let Some(keys) = self.postings.get_mut(term) {
+                keys.remove(key);
+                if keys.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        true
+    }
+
+    fn search(&self, params: &Bm25Params, query: &str, limit: usize) -> Vec<KeywordHit> {
+        if self.documents.is_empty() {
+            return Vec::new();
+        }
+        let avg_length = self.total_length as f32 / self.documents.len() as f32;
+        let terms: HashSet<String> = tokenize(query).into_iter().collect();
+
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+        for term in &terms {
+            let Some(keys) = self.postings.get(term) else {
+                continue;
+            };
+            let idf = params.idf(self.documents.len(), keys.len());
+            for key in keys {
+                let doc = &self.documents[key];
+                let tf = doc.terms.get(term).copied().unwrap_or(0);
+                *scores.entry(key.as_str()).or_insert(0.0) +=
+                    params.term_score(idf, tf, doc.length, avg_length);
+            }
+        }
+
+        let mut hits: Vec<KeywordHit> = scores
+            .into_iter()
+            .map(|(key, score)| KeywordHit {
+                key: key.to_string(),
+                score,
+                stored: self.documents[key].stored.clone(),
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+fn fingerprint(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn analyze(text: &str, stored: HashMap<String, String>) -> IndexedDocument {
+    let tokens = tokenize(text);
+    let mut terms = HashMap::new();
+    for token in &tokens {
+        *terms.entry(token.clone()).or_insert(0) += 1;
+    }
+    IndexedDocument {
+        fingerprint: fingerprint(text),
+        length: tokens.len() as u32,
+        terms,
+        stored,
+    }
+}
+
+/// Embedded BM25 inverted index, partitioned into scopes.
+pub struct Bm25Index {
+    params: Bm25Params,
+    path: Option<PathBuf>,
+    scopes: RwLock<HashMap<String, Scope>>,
+    dirty: AtomicBool,
+}
+
+impl std::fmt::Debug for Bm25Index {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bm25Index")
+            .field("params", &self.params)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl Bm25Index {
+    /// A non-persistent index.
+    pub fn in_memory() -> Self {
+        Self {
+            params: Bm25Params::default(),
+            path: None,
+            scopes: RwLock::new(HashMap::new()),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Open (or create) an index persisted at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ContextError> {
+        let path = path.as_ref().to_path_buf();
+        let mut scopes: HashMap<String, Scope> = if path.exists() {
+            let data = std::fs::read(&path).map_err(|e| ContextError::StorageError {
+                reason: format!("Failed to read keyword index {:?}: {}", path, e),
+            })?;
+            serde_json::from_slice(&data).map_err(|e| ContextError::SerializationError {
+                reason: format!("Failed to parse keyword index {:?}: {}", path, e),
+            })?
+        } else {
+            HashMap::new()
+        };
+        for scope in scopes.values_mut() {
+            scope.rebuild();
+        }
+
+        Ok(Self {
+            params: Bm25Params::default(),
+            path: Some(path),
+            scopes: RwLock::new(scopes),
+            dirty: AtomicBool::new(false),
+        })
+    }
+
+    /// Override the BM25 parameters.
+    pub fn with_params(mut self, params: Bm25Params) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// File the index is persisted to, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Index `text` under `key`, replacing any previous version.
+    pub fn upsert(&self, scope: &str, key: &str, text: &str, stored: HashMap<String, String>) {
+        let doc = analyze(text, stored);
+        self.scopes
+            .write()
+            .entry(scope.to_string())
+            .or_default()
+            .insert(key, doc);
+        self.dirty.store(true, Ordering::Release);
+    }
+
+    /// Remove a document. Returns whether it was indexed.
+    pub fn remove(&self, scope: &str, key: &str) -> bool {
+        let removed = self
+            .scopes
+            .write()
+            .get_mut(scope)
+            .is_some_and(|s| s.remove(key));
+        if removed {
+            self.dirty.store(true, Ordering::Release);
+        }
+        removed
+    }
+
+    /// Make `scope` contain exactly `documents`: changed texts are
+    /// re-indexed, missing keys removed, unchanged ones left alone.
+    /// Returns whether anything changed.
+    pub fn sync_scope<'a>(
+        &self,
+        scope: &str,
+        documents: impl IntoIterator<Item = (String, &'a str)>,
+    ) -> bool {
+        let mut scopes = self.scopes.write();
+        let index = scopes.entry(scope.to_string()).or_default();
+        let mut seen = HashSet::new();
+        let mut changed = false;
+
+        for (key, text) in documents {
+            let unchanged = index
+                .documents
+                .get(&key)
+                .is_some_and(|d| d.fingerprint == fingerprint(text));
+            if !unchanged {
+                index.insert(&key, analyze(text, HashMap::new()));
+                changed = true;
+            }
+            seen.insert(key);
+        }
+
+        let stale: Vec<String> = index
+            .documents
+            .keys()
+            .filter(|k| !seen.contains(*k))
+            .cloned()
+            .collect();
+        for key in stale {
+            index.remove(&key);
+            changed = true;
+        }
+
+        if changed {
+            self.dirty.store(true, Ordering::Release);
+        }
+        changed
+    }
+
+    /// Drop every document in `scope`.
+    pub fn clear_scope(&self, scope: &str) {
+        if self.scopes.write().remove(scope).is_some() {
+            self.dirty.store(true, Ordering::Release);
+        }
+    }
+
+    /// Top `limit` documents in `scope` for `query`, best first.
+    pub fn search(&self, scope: &str, query: &str, limit: usize) -> Vec<KeywordHit> {
+        self.scopes
+            .read()
+            .get(scope)
+            .map(|s| s.search(&self.params, query, limit))
+            .unwrap_or_default()
+    }
+
+    /// Number of documents indexed in `scope`.
+    pub fn document_count(&self, scope: &str) -> usize {
+        self.scopes
+            .read()
+            .get(scope)
+            .map_or(0, |s| s.documents.len())
+    }
+
+    /// Write pending changes to disk. A no-op for in-memory indexes or when
+    /// nothing changed since the last flush.
+    pub fn flush(&self) -> Result<(), ContextError> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return Ok(());
+        }
+
+        let result = (|| {
+            let data = serde_json::to_vec(&*self.scopes.read()).map_err(|e| {
+                ContextError::SerializationError {
+                    reason: format!("Failed to serialize keyword index: {}", e),
+                }
+            })?;
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| ContextError::StorageError {
+                    reason: format!("Failed to create keyword index dir {:?}: {}", parent, e),
+                })?;
+            }
+            // Write then rename so a crash never leaves a truncated index.
+            let tmp = path.with_extension("json.tmp");
+            std::fs::write(&tmp, data)
+                .and_then(|_| std::fs::rename(&tmp, path))
+                .map_err(|e| ContextError::StorageError {
+                    reason: format!("Failed to write keyword index {:?}: {}", path, e),
+                })
+        })();
+        if result.is_err() {
+            self.dirty.store(true, Ordering::Release);
+        }
+        result
+    }
+}
+
+/// BM25 scores of `query` against an ad-hoc collection of `texts`, using
+/// term statistics of that collection. Useful for re-ranking a candidate
+/// set that is not held in a [`Bm25Index`].
+pub fn bm25_scores(query: &str, texts: &[&str], params: &Bm25Params) -> Vec<f32> {
+    let mut scope = Scope::default();
+    for (i, text) in texts.iter().enumerate() {
+        scope.insert(&i.to_string(), analyze(text, HashMap::new()));
+    }
+    let mut scores = vec![0.0; texts.len()];
+    for hit in scope.search(params, query, texts.len()) {
+        if let Ok(i) = hit.key.parse::<usize>() {
+            scores[i] = hit.score;
+        }
+    }
+    scores
+}
+
+/// How vector and keyword rankings are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HybridStrategy {
+    /// Reciprocal rank fusion: each list contributes `1 / (k + rank)`.
+    ReciprocalRank { k: f32 },
+    /// Weighted sum of scores, each list normalised by its best score.
+    Weighted {
+        vector_weight: f32,
+        keyword_weight: f32,
+    },
+}
+
+impl Default for HybridStrategy {
+    fn default() -> Self {
+        Self::Weighted {
+            vector_weight: 0.7,
+            keyword_weight: 0.3,
+        }
+    }
+}
+
+impl HybridStrategy {
+    /// Reciprocal rank fusion with the conventional `k = 60`.
+    pub fn reciprocal_rank() -> Self {
+        Self::ReciprocalRank { k: 60.0 }
+    }
+
+    /// Fuse two rankings of `(key, score)` pairs, each sorted best first.
+    /// Returned scores are in `[0, 1]`, best first; a key ranked first in
+    /// both lists scores 1.0.
+    pub fn fuse<K: Eq + Hash + Clone>(
+        &self,
+        vector: &[(K, f32)],
+        keyword: &[(K, f32)],
+    ) -> Vec<(K, f32)> {
+        let mut order: Vec<K> = Vec::new();
+        let mut fused: HashMap<K, f32> = HashMap::new();
+        let mut add = |key: &K, score: f32| {
+            if !fused.contains_key(key) {
+                order.push(key.clone());
+            }
+            *fused.entry(key.clone()).or_insert(0.0) += score;
+        };
+
+        match *self {
+            Self::ReciprocalRank { k } => {
+                let max = 2.0 / (k + 1.0);
+                for list in [vector, keyword] {
+                    for (rank, (key, _)) in list.iter().enumerate() {
+                        add(key, 1.0 / (k + rank as f32 + 1.0) / max);
+                    }
+                }
+            }
+            Self::Weighted {
+                vector_weight,
+                keyword_weight,
+            } => {
+                let total = (vector_weight + keyword_weight).max(f32::EPSILON);
+                for (list, weight) in [(vector, vector_weight), (keyword, keyword_weight)] {
+                    let best = list.iter().map(|(_, s)| *s).fold(0.0f32, f32::max);
+                    if best <= 0.0 {
+                        continue;
+                    }
+                    for (key, score) in list {
+                        add(key, weight / total * score.max(0.0) / best);
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<(K, f32)> = order
+            .into_iter()
+            .map(|key| {
+                let score = fused[&key].clamp(0.0, 1.0);
+                (key, score)
+            })
+            .collect();
+        // Stable sort keeps first-seen order for ties.
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn keys(hits: &[KeywordHit]) -> Vec<&str> {
+        hits.iter().map(|h| h.key.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tokenize_drops_stop_words_and_punctuation() {
+        assert_eq!(
+            tokenize("What is the Rust borrow-checker? It's great!"),
+            vec!["rust", "borrow", "checker", "great"]
+        );
+    }
+
+    #[test]
+    fn test_rare_terms_outrank_common_terms() {
+        let index = Bm25Index::in_memory();
+        index.upsert("s", "a", "rust memory safety", HashMap::new());
+        index.upsert("s", "b", "rust compiler speed", HashMap::new());
+        index.upsert("s", "c", "rust async runtime tokio", HashMap::new());
+
+        // "tokio" appears once, "rust" everywhere: only c scores meaningfully
+        let hits = index.search("s", "rust tokio", 10);
+        assert_eq!(hits[0].key, "c");
+        assert!(hits[0].score > hits[1].score * 2.0);
+    }
+
+    #[test]
+    fn test_length_normalisation_and_tf_saturation() {
+        let index = Bm25Index::in_memory();
+        index.upsert("s", "short", "lancedb index", HashMap::new());
+        index.upsert(
+            "s",
+            "long",
+            "lancedb plus many other unrelated words padding this document out",
+            HashMap::new(),
+        );
+        index.upsert("s", "other", "qdrant cluster", HashMap::new());
+        assert_eq!(
+            keys(&index.search("s", "lancedb", 10)),
+            vec!["short", "long"]
+        );
+    }
+
+    #[test]
+    fn test_scopes_are_isolated() {
+        let index = Bm25Index::in_memory();
+        index.upsert("agent-a", "1", "shared secret plan", HashMap::new());
+        index.upsert("agent-b", "2", "another plan", HashMap::new());
+        assert_eq!(keys(&index.search("agent-a", "plan", 10)), vec!["1"]);
+        assert_eq!(index.document_count("agent-b"), 1);
+        assert!(index.search("missing", "plan", 10).is_empty());
+    }
+
+    #[test]
+    fn test_upsert_replaces_and_remove_deletes() {
+        let index = Bm25Index::in_memory();
+        index.upsert("s", "k", "old words", HashMap::new());
+        index.upsert("s", "k", "new words", HashMap::new());
+        assert!(index.search("s", "old", 10).is_empty());
+        assert_eq!(keys(&index.search("s", "new", 10)), vec!["k"]);
+
+        assert!(index.remove("s", "k"));
+        assert!(!index.remove("s", "k"));
+        assert!(index.search("s", "new", 10).is_empty());
+        assert_eq!(index.document_count("s"), 0);
+    }
+
+    #[test]
+    fn test_sync_scope_reindexes_only_changes() {
+        let index = Bm25Index::in_memory();
+        assert!(index.sync_scope(
+            "s",
+            vec![("a".to_string(), "alpha"), ("b".to_string(), "beta")]
+        ));
+        assert!(!index.sync_scope(
+            "s",
+            vec![("a".to_string(), "alpha"), ("b".to_string(), "beta")]
+        ));
+        assert!(index.sync_scope("s", vec![("a".to_string(), "gamma")]));
+        assert_eq!(index.document_count("s"), 1);
+        assert_eq!(keys(&index.search("s", "gamma", 10)), vec!["a"]);
+        assert!(index.search("s", "beta", 10).is_empty());
+    }
+
+    #[test]
+    fn test_persistence_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("ctx.bm25.json");
+
+        let index = Bm25Index::open(&path).unwrap();
+        index.upsert(
+            "s",
+            "k",
+            "persisted keyword text",
+            HashMap::from([("title".to_string(), "Doc".to_string())]),
+        );
+        index.flush().unwrap();
+        assert!(path.exists());
+        drop(index);
+
+        let reopened = Bm25Index::open(&path).unwrap();
+        let hits = reopened.search("s", "keyword", 10);
+        assert_eq!(keys(&hits), vec!["k"]);
+        assert_eq!(hits[0].stored.get("title").map(String::as_str), Some("Doc"));
+
+        reopened.remove("s", "k");
+        reopened.flush().unwrap();
+        assert!(Bm25Index::open(&path)
+            .unwrap()
+            .search("s", "keyword", 10)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_bm25_scores_ad_hoc_collection() {
+        let scores = bm25_scores(
+            "vector search",
+            &["vector search engine", "keyword search", "cooking recipes"],
+            &Bm25Params::default(),
+        );
+        assert!(scores[0] > scores[1]);
+        assert!(scores[1] > 0.0);
+        assert_eq!(scores[2], 0.0);
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion() {
+        let vector = vec![("a", 0.9), ("b", 0.8), ("c", 0.1)];
+        let keyword = vec![("b", 12.0), ("d", 3.0)];
+        let fused = HybridStrategy::reciprocal_rank().fuse(&vector, &keyword);
+
+        let order: Vec<&str> = fused.iter().map(|(k, _)| *k).collect();
+        assert_eq!(order[0], "b"); // ranked in both lists
+        assert_eq!(order.len(), 4);
+        assert!(fused.iter().all(|(_, s)| *s > 0.0 && *s <= 1.0));
+
+        let both_first = HybridStrategy::reciprocal_rank().fuse(&[("x", 1.0)], &[("x", 1.0)]);
+        assert!((both_first[0].1 - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_weighted_fusion_normalises_scores() {
+        let strategy = HybridStrategy::Weighted {
+            vector_weight: 0.5,
+            keyword_weight: 0.5,
+        };
+        // Raw BM25 scores dwarf cosine similarities; normalisation evens them out
+        let vector = vec![("a", 0.9), ("b", 0.45)];
+        let keyword = vec![("b", 20.0), ("c", 10.0)];
+        let fused = strategy.fuse(&vector, &keyword);
+
+        let score = |key: &str| fused.iter().find(|(k, _)| *k == key).unwrap().1;
+        assert!((score("a") - 0.5).abs() < 1e-6);
+        assert!((score("b") - 0.75).abs() < 1e-6);
+        assert!((score("c") - 0.25).abs() < 1e-6);
+        assert_eq!(fused[0].0, "b");
+
+        let keyword_only = HybridStrategy::Weighted {
+            vector_weight: 0.0,
+            keyword_weight: 1.0,
+        }
+        .fuse(&vector, &keyword);
+        assert_eq!(keyword_only[0], ("b", 1.0));
+    }
+}
@@ -12,9 +12,12 @@ use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::RwLock;
 
 use super::embedding::create_embedding_service_from_env;
+use super::keyword_index::{Bm25Index, HybridStrategy};
 use super::types::*;
 use super::vector_db::{EmbeddingService, NoOpVectorDatabase, QdrantConfig};
-use super::vector_db_factory::{create_vector_backend, resolve_vector_config, VectorBackendConfig};
+use super::vector_db_factory::{
+    create_vector_backend, keyword_index_path, resolve_vector_config, VectorBackendConfig,
+};
 use super::vector_db_trait::VectorDb;
 use crate::integrations::policy_engine::{MockPolicyEngine, PolicyEngine};
 use crate::secrets::{SecretStore, SecretsConfig};
@@ -112,6 +115,8 @@ pub struct StandardContextManager {
     vector_db: Arc<dyn VectorDb>,
     /// Embedding service for generating vector embeddings
     embedding_service: Arc<dyn EmbeddingService>,
+    /// BM25 keyword index over memory, one scope per agent
+    keyword_index: Arc<Bm25Index>,
     /// Persistent storage for contexts
     persistence: Arc<dyn ContextPersistence>,
     /// Secrets store for secure secret management
@@ -146,6 +151,8 @@ pub struct ContextManagerConfig {
     pub qdrant_config: QdrantConfig,
     /// Enable vector database integration
     pub enable_vector_db: bool,
+    /// How keyword and vector rankings are combined in hybrid queries
+    pub hybrid_search: HybridStrategy,
     /// File persistence configuration
     pub persistence_config: FilePersistenceConfig,
     /// Enable persistent storage
@@ -168,6 +175,7 @@ impl Default for ContextManagerConfig {
             vector_backend: None,
             qdrant_config: QdrantConfig::default(),
             enable_vector_db: false,
+            hybrid_search: HybridStrategy::default(),
             persistence_config: FilePersistenceConfig::default(),
             enable_persistence: true,
             secrets_config: SecretsConfig::file_json(PathBuf::from("secrets.json")),
@@ -551,21 +559,20 @@ impl ContextPersistence for FilePersistence {
 impl StandardContextManager {
     /// Create a new StandardContextManager
     pub async fn new(config: ContextManagerConfig, agent_id: &str) -> Result<Self, ContextError> {
-        let vector_db: Arc<dyn VectorDb> = if config.enable_vector_db {
-            let db = if let Some(ref backend_config) = config.vector_backend {
-                create_vector_backend(backend_config.clone())
-                    .await
-                    .unwrap_or_else(|e| {
-                        tracing::warn!("Failed to create vector backend: {}, using NoOp", e);
-                        Arc::new(NoOpVectorDatabase)
-                    })
-            } else {
-                let resolved = resolve_vector_config();
-                create_vector_backend(resolved).await.unwrap_or_else(|e| {
+        let backend_config = config.enable_vector_db.then(|| {
+            config
+                .vector_backend
+                .clone()
+                .unwrap_or_else(resolve_vector_config)
+        });
+
+        let vector_db: Arc<dyn VectorDb> = if let Some(ref backend_config) = backend_config {
+            let db = create_vector_backend(backend_config.clone())
+                .await
+                .unwrap_or_else(|e| {
                     tracing::warn!("Failed to create vector backend: {}, using NoOp", e);
                     Arc::new(NoOpVectorDatabase)
-                })
-            };
+                });
             // Initialize the vector DB (creates table/collection if needed)
             if let Err(e) = db.initialize().await {
                 tracing::warn!("Failed to initialize vector DB: {}, queries may fail", e);
@@ -575,6 +582,16 @@ impl StandardContextManager {
             Arc::new(NoOpVectorDatabase)
         };
 
+        // The keyword index is persisted next to the vector store when it has
+        // a local data dir; it is rebuilt from memory on demand either way.
+        let keyword_index = match backend_config.as_ref().and_then(keyword_index_path) {
+            Some(path) => Bm25Index::open(&path).unwrap_or_else(|e| {
+                tracing::warn!("Failed to open keyword index: {}, starting empty", e);
+                Bm25Index::in_memory()
+            }),
+            None => Bm25Index::in_memory(),
+        };
+
         let embedding_service =
             create_embedding_service_from_env(config.qdrant_config.vector_dimension)?;
 
@@ -601,6 +618,7 @@ impl StandardContextManager {
             shared_knowledge: Arc::new(RwLock::new(HashMap::new())),
             vector_db,
             embedding_service,
+            keyword_index: Arc::new(keyword_index),
             persistence,
             secrets,
             policy_engine,
@@ -609,6 +627,19 @@ impl StandardContextManager {
         })
     }
 
+    /// Replace the persistence backend, e.g. with a
+    /// [`MarkdownMemoryStore`](super::markdown_memory::MarkdownMemoryStore).
+    /// Call before `initialize()` so existing contexts load from it.
+    pub fn with_persistence(mut self, persistence: Arc<dyn ContextPersistence>) -> Self {
+        self.persistence = persistence;
+        self
+    }
+
+    /// The BM25 keyword index backing keyword and hybrid memory queries.
+    pub fn keyword_index(&self) -> &Arc<Bm25Index> {
+        &self.keyword_index
+    }
+
     /// Get access to the secrets store
     pub fn secrets(&self) -> &(dyn SecretStore + Send + Sync) {
         self.secrets.as_ref()
@@ -785,12 +816,17 @@ impl StandardContextManager {
         // 2. Save all contexts to persistent storage
         self.save_all_contexts().await?;
 
-        // 3. Close vector database connections (if any cleanup is needed)
+        // 3. Persist the keyword index
+        if let Err(e) = self.keyword_index.flush() {
+            tracing::warn!("Failed to persist keyword index: {}", e);
+        }
+
+        // 4. Close vector database connections (if any cleanup is needed)
         // Note: Vector database connections are typically managed by the client
         // and don't require explicit cleanup, but we log the action
         tracing::info!("Vector database connections will be closed when client is dropped");
 
-        // 4. Flush secrets store if needed
+        // 5. Flush secrets store if needed
         // Note: Secrets store cleanup is typically handled by Drop trait
         tracing::info!("Secrets store cleanup handled by Drop trait");
 
@@ -1486,160 +1522,136 @@ impl StandardContextManager {
         decay_factor.max(0.05)
     }
 
-    /// Perform keyword search on memory items
+    /// Perform BM25 keyword search over memory items, episodes and
+    /// conversation history. The agent's keyword index scope is brought in
+    /// line with its current context before searching.
     async fn keyword_search_memory(
         &self,
         agent_id: AgentId,
         query: &ContextQuery,
     ) -> Result<Vec<ContextItem>, ContextError> {
         let contexts = self.contexts.read().await;
-        if let Some(context) = contexts.get(&agent_id) {
-            let mut results = Vec::new();
+        let Some(context) = contexts.get(&agent_id) else {
+            return Ok(Vec::new());
+        };
+        let query_text = query.search_terms.join(" ");
+        if query_text.trim().is_empty() {
+            return Ok(Vec::new());
+        }
 
-            // Combine all search terms
-            let search_terms: Vec<String> = query
-                .search_terms
-                .iter()
-                .map(|term| term.to_lowercase())
-                .collect();
+        // Candidate items keyed by id, with the importance blended into
+        // their final relevance (None for conversation turns).
+        let mut candidates: HashMap<String, (ContextItem, Option<f32>)> = HashMap::new();
+        let mut documents: Vec<(String, String)> = Vec::new();
 
-            if search_terms.is_empty() {
-                return Ok(results);
-            }
-
-            // Search through memory items
-            for memory_item in context
-                .memory
-                .short_term
-                .iter()
-                .chain(context.memory.long_term.iter())
+        for memory_item in context
+            .memory
+            .short_term
+            .iter()
+            .chain(context.memory.long_term.iter())
+        {
+            let key = memory_item.id.to_string();
+            documents.push((key.clone(), memory_item.content.clone()));
+            // Skip if memory type filter is specified and doesn't match
+            if !query.memory_types.is_empty()
+                && !query.memory_types.contains(&memory_item.memory_type)
             {
-                // Skip if memory type filter is specified and doesn't match
-                if !query.memory_types.is_empty()
-                    && !query.memory_types.contains(&memory_item.memory_type)
-                {
-                    continue;
-                }
-
-                let content_lower = memory_item.content.to_lowercase();
-                let mut match_score = 0.0f32;
-                let mut matched_terms = 0;
-
-                // Calculate match score based on term presence
-                for term in &search_terms {
-                    if content_lower.contains(term) {
-                        matched_terms += 1;
-                        // Boost score for exact matches vs substring matches
-                        if content_lower.split_whitespace().any(|word| word == term) {
-                            match_score += 1.0;
-                        } else {
-                            match_score += 0.5;
-                        }
-                    }
-                }
-
-                if matched_terms > 0 {
-                    // Calculate relevance score combining match score and importance
-                    let importance_score = self.calculate_importance(memory_item);
-                    let term_coverage = matched_terms as f32 / search_terms.len() as f32;
-                    let relevance_score = (match_score * term_coverage + importance_score) / 2.0;
-
-                    if relevance_score >= query.relevance_threshold {
-                        results.push(ContextItem {
-                            id: memory_item.id,
-                            content: memory_item.content.clone(),
-                            item_type: ContextItemType::Memory(memory_item.memory_type.clone()),
-                            relevance_score,
-                            timestamp: memory_item.created_at,
-                            metadata: memory_item.metadata.clone(),
-                        });
-                    }
-                }
-            }
-
-            // Search through episodic memory
-            for episode in &context.memory.episodic_memory {
-                let episode_content = format!("{} {}", episode.title, episode.description);
-                let content_lower = episode_content.to_lowercase();
-                let mut match_score = 0.0f32;
-                let mut matched_terms = 0;
-
-                for term in &search_terms {
-                    if content_lower.contains(term) {
-                        matched_terms += 1;
-                        match_score += if content_lower.split_whitespace().any(|word| word == term)
-                        {
-                            1.0
-                        } else {
-                            0.5
-                        };
-                    }
-                }
-
-                if matched_terms > 0 {
-                    let term_coverage = matched_terms as f32 / search_terms.len() as f32;
-                    let relevance_score = (match_score * term_coverage + episode.importance) / 2.0;
-
-                    if relevance_score >= query.relevance_threshold {
-                        results.push(ContextItem {
-                            id: episode.id,
-                            content: episode_content,
-                            item_type: ContextItemType::Episode,
-                            relevance_score,
-                            timestamp: episode.timestamp,
-                            metadata: HashMap::new(),
-                        });
-                    }
-                }
-            }
+                continue;
+            }
+            candidates.insert(
+                key,
+                (
+                    ContextItem {
+                        id: memory_item.id,
+                        content: memory_item.content.clone(),
+                        item_type: ContextItemType::Memory(memory_item.memory_type.clone()),
+                        relevance_score: 0.0,
+                        timestamp: memory_item.created_at,
+                        metadata: memory_item.metadata.clone(),
+                    },
+                    Some(self.calculate_importance(memory_item)),
+                ),
+            );
+        }
 
-            // Search through conversation history
-            for conv_item in &context.conversation_history {
-                let content_lower = conv_item.content.to_lowercase();
-                let mut match_score = 0.0f32;
-                let mut matched_terms = 0;
-
-                for term in &search_terms {
-                    if content_lower.contains(term) {
-                        matched_terms += 1;
-                        match_score += if content_lower.split_whitespace().any(|word| word == term)
-                        {
-                            1.0
-                        } else {
-                            0.5
-                        };
-                    }
-                }
+        for episode in &context.memory.episodic_memory {
+            let key = episode.id.to_string();
+            let episode_content = format!("{} {}", episode.title, episode.description);
+            documents.push((key.clone(), episode_content.clone()));
+            candidates.insert(
+                key,
+                (
+                    ContextItem {
+                        id: episode.id,
+                        content: episode_content,
+                        item_type: ContextItemType::Episode,
+                        relevance_score: 0.0,
+                        timestamp: episode.timestamp,
+                        metadata: HashMap::new(),
+                    },
+                    Some(episode.importance),
+                ),
+            );
+        }
 
-                if matched_terms > 0 {
-                    let term_coverage = matched_terms as f32 / search_terms.len() as f32;
-                    let relevance_score = match_score * term_coverage;
+        for conv_item in &context.conversation_history {
+            let key = conv_item.id.to_string();
+            documents.push((key.clone(), conv_item.content.clone()));
+            candidates.insert(
+                key,
+                (
+                    ContextItem {
+                        id: conv_item.id,
+                        content: conv_item.content.clone(),
+                        item_type: ContextItemType::Conversation,
+                        relevance_score: 0.0,
+                        timestamp: conv_item.timestamp,
+                        metadata: HashMap::new(),
+                    },
+                    None,
+                ),
+            );
+        }
 
-                    if relevance_score >= query.relevance_threshold {
-                        results.push(ContextItem {
-                            id: conv_item.id,
-                            content: conv_item.content.clone(),
-                            item_type: ContextItemType::Conversation,
-                            relevance_score,
-                            timestamp: conv_item.timestamp,
-                            metadata: HashMap::new(),
-                        });
-                    }
-                }
+        let scope = agent_id.to_string();
+        self.keyword_index.sync_scope(
+            &scope,
+            documents
+                .iter()
+                .map(|(key, text)| (key.clone(), text.as_str())),
+        );
+        let hits = self
+            .keyword_index
+            .search(&scope, &query_text, documents.len());
+        drop(contexts);
+
+        // Normalise BM25 scores against the best match so they blend with
+        // the [0, 1] importance scores.
+        let best = hits.first().map_or(0.0, |h| h.score);
+        let mut results = Vec::new();
+        for hit in hits {
+            let Some((mut item, importance)) = candidates.remove(&hit.key) else {
+                continue;
+            };
+            let keyword_score = if best > 0.0 { hit.score / best } else { 0.0 };
+            item.relevance_score = match importance {
+                Some(importance) => (keyword_score + importance) / 2.0,
+                None => keyword_score,
+            };
+            if item.relevance_score >= query.relevance_threshold {
+                results.push(item);
             }
+        }
 
-            // Sort by relevance score (highest first) and limit results
-            results.sort_by(|a, b| {
-                b.relevance_score
-                    .partial_cmp(&a.relevance_score)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-            results.truncate(query.max_results);
+        // Sort by relevance score (highest first) and limit results
+        results.sort_by(|a, b| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(query.max_results);
 
-            Ok(results)
-        } else {
-            Ok(Vec::new())
-        }
+        Ok(results)
     }
 
     /// Perform temporal search on memory items within a time range
@@ -1871,7 +1883,8 @@ impl StandardContextManager {
         }
     }
 
-    /// Perform hybrid search combining keyword and similarity search
+    /// Perform hybrid search combining BM25 keyword and similarity search,
+    /// fused according to `ContextManagerConfig::hybrid_search`
     async fn hybrid_search_memory(
         &self,
         agent_id: AgentId,
@@ -1881,46 +1894,35 @@ impl StandardContextManager {
         let keyword_results = self.keyword_search_memory(agent_id, query).await?;
         let similarity_results = self.similarity_search_memory(agent_id, query).await?;
 
-        // Combine and deduplicate results
-        let mut combined_results: HashMap<ContextId, ContextItem> = HashMap::new();
-
-        // Weight factors for combining scores (configurable)
-        let keyword_weight = 0.4;
-        let similarity_weight = 0.6;
-
-        // Add keyword results
-        for mut item in keyword_results {
-            item.relevance_score *= keyword_weight;
-            combined_results.insert(item.id, item);
-        }
+        let ranking = |items: &[ContextItem]| -> Vec<(ContextId, f32)> {
+            items.iter().map(|i| (i.id, i.relevance_score)).collect()
+        };
+        let fused = self
+            .config
+            .hybrid_search
+            .fuse(&ranking(&similarity_results), &ranking(&keyword_results));
 
-        // Add similarity results, combining scores for duplicates
-        for mut item in similarity_results {
-            item.relevance_score *= similarity_weight;
+        let mut items: HashMap<ContextId, ContextItem> = keyword_results
+            .into_iter()
+            .chain(similarity_results)
+            .map(|item| (item.id, item))
+            .collect();
 
-            if let Some(existing_item) = combined_results.get_mut(&item.id) {
-                // Combine scores for items found in both searches
-                existing_item.relevance_score += item.relevance_score;
-                // Take the higher of the two relevance scores as the final score
-                existing_item.relevance_score =
-                    existing_item.relevance_score.max(item.relevance_score);
-            } else {
-                combined_results.insert(item.id, item);
+        // Filter by threshold and limit results (fused is sorted best first)
+        let mut final_results = Vec::new();
+        for (id, score) in fused {
+            if score < query.relevance_threshold {
+                continue;
+            }
+            if let Some(mut item) = items.remove(&id) {
+                item.relevance_score = score;
+                final_results.push(item);
+            }
+            if final_results.len() >= query.max_results {
+                break;
             }
         }
 
-        // Convert to vector and sort by combined relevance score
-        let mut final_results: Vec<ContextItem> = combined_results.into_values().collect();
-        final_results.sort_by(|a, b| {
-            b.relevance_score
-                .partial_cmp(&a.relevance_score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        // Filter by threshold and limit results
-        final_results.retain(|item| item.relevance_score >= query.relevance_threshold);
-        final_results.truncate(query.max_results);
-
         Ok(final_results)
     }
 
@@ -3668,4 +3670,147 @@ mod tests {
             "should be no-op when context is nearly empty"
         );
     }
+
+    async fn manager_with_conversation(
+        config: ContextManagerConfig,
+        turns: &[&str],
+    ) -> (StandardContextManager, AgentId) {
+        let agent_id = AgentId::new();
+        let manager = StandardContextManager::new(config, &agent_id.to_string())
+            .await
+            .unwrap();
+        manager.initialize().await.unwrap();
+        manager.create_session(agent_id).await.unwrap();
+
+        let mut context = manager
+            .retrieve_context(agent_id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        for turn in turns {
+            context.conversation_history.push(ConversationItem {
+                id: ContextId::new(),
+                role: ConversationRole::User,
+                content: turn.to_string(),
+                timestamp: SystemTime::now(),
+                context_used: vec![],
+                knowledge_used: vec![],
+            });
+        }
+        manager.store_context(agent_id, context).await.unwrap();
+        (manager, agent_id)
+    }
+
+    fn query(query_type: QueryType, terms: &str) -> ContextQuery {
+        ContextQuery {
+            query_type,
+            search_terms: vec![terms.to_string()],
+            time_range: None,
+            memory_types: vec![],
+            relevance_threshold: 0.0,
+            max_results: 10,
+            include_embeddings: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn keyword_query_ranks_by_bm25() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = ContextManagerConfig::default();
+        config.persistence_config.root_data_dir = tmp.path().to_path_buf();
+        let (manager, agent_id) = manager_with_conversation(
+            config,
+            &[
+                "we deploy the service on kubernetes",
+                "the service uses the tokio runtime for async io",
+                "lunch is at noon",
+            ],
+        )
+        .await;
+
+        let results = manager
+            .query_context(agent_id, query(QueryType::Keyword, "tokio service"))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].content.contains("tokio"));
+        assert!((results[0].relevance_score - 1.0).abs() < 1e-6);
+        assert!(results[1].relevance_score < results[0].relevance_score);
+        assert_eq!(
+            manager
+                .keyword_index()
+                .document_count(&agent_id.to_string()),
+            3
+        );
+
+        // Removed turns drop out of the index on the next query
+        let mut context = manager
+            .retrieve_context(agent_id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        context
+            .conversation_history
+            .retain(|c| !c.content.contains("tokio"));
+        manager.store_context(agent_id, context).await.unwrap();
+        let results = manager
+            .query_context(agent_id, query(QueryType::Keyword, "tokio"))
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn hybrid_query_uses_configured_fusion() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = ContextManagerConfig::default();
+        config.persistence_config.root_data_dir = tmp.path().to_path_buf();
+        config.hybrid_search = HybridStrategy::Weighted {
+            vector_weight: 0.0,
+            keyword_weight: 1.0,
+        };
+        let (manager, agent_id) = manager_with_conversation(
+            config,
+            &["rust ownership and borrowing", "python garbage collection"],
+        )
+        .await;
+
+        let results = manager
+            .query_context(agent_id, query(QueryType::Hybrid, "borrowing"))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content.contains("rust"));
+        assert!((results[0].relevance_score - 1.0).abs() < 1e-6);
+    }
+
+    #[cfg(feature = "vector-lancedb")]
+    #[tokio::test]
+    async fn keyword_index_persists_next_to_lancedb() {
+        use super::super::vector_db_lance::LanceDbConfig;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let lance = LanceDbConfig {
+            data_path: tmp.path().join("vectors"),
+            ..Default::default()
+        };
+        let index_path = lance.keyword_index_path();
+        let mut config = ContextManagerConfig::default();
+        config.persistence_config.root_data_dir = tmp.path().to_path_buf();
+        config.secrets_config = SecretsConfig::file_json(tmp.path().join("secrets.json"));
+        config.enable_vector_db = true;
+        config.vector_backend = Some(VectorBackendConfig::LanceDb(lance));
+
+        let (manager, agent_id) =
+            manager_with_conversation(config, &["persisted keyword memory"]).await;
+        assert_eq!(manager.keyword_index().path(), Some(index_path.as_path()));
+        manager
+            .query_context(agent_id, query(QueryType::Keyword, "keyword"))
+            .await
+            .unwrap();
+        manager.shutdown().await.unwrap();
+
+        let reopened = Bm25Index::open(&index_path).unwrap();
+        assert_eq!(reopened.document_count(&agent_id.to_string()), 1);
+    }
 }
@@ -40,8 +40,10 @@
 //! - **Retention Policies**: Automatic archiving and cleanup of old context data
 //! - **Access Control**: Policy-driven access control for context operations
 
+pub mod agent_memory;
 pub mod compaction;
 pub mod embedding;
+pub mod keyword_index;
 pub mod manager;
 pub mod markdown_memory;
 pub mod token_counter;
@@ -61,8 +63,12 @@ pub use types::{
     VectorContentType, VectorId, VectorMetadata, VectorOperationType, VectorSearchResult,
 };
 
+pub use keyword_index::{Bm25Index, Bm25Params, HybridStrategy, KeywordHit};
+
 pub use manager::{ContextManager, ContextManagerConfig, FilePersistence, StandardContextManager};
 
+pub use agent_memory::AgentMemoryConfig;
+
 pub use markdown_memory::MarkdownMemoryStore;
 
 pub use embedding::{
@@ -83,7 +89,9 @@ pub use token_counter::{
     context_limit_for_model, create_token_counter, HeuristicTokenCounter, TiktokenCounter,
     TokenCounter,
 };
-pub use vector_db_factory::{create_vector_backend, resolve_vector_config, VectorBackendConfig};
+pub use vector_db_factory::{
+    create_vector_backend, keyword_index_path, resolve_vector_config, VectorBackendConfig,
+};
 #[cfg(feature = "vector-lancedb")]
 pub use vector_db_lance::{LanceDbBackend, LanceDbConfig};
 pub use vector_db_trait::{DistanceMetric, VectorDb};
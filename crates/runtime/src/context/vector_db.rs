@@ -6,8 +6,8 @@ use qdrant_client::config::QdrantConfig as ClientConfig;
 #[cfg(feature = "vector-qdrant")]
 use qdrant_client::qdrant::{
     Condition, CreateCollection, DeletePoints, Distance, FieldCondition, Filter, Match, PointId,
-    PointStruct, PointsIdsList, PointsSelector, SearchPoints, UpsertPoints, Value as QdrantValue,
-    VectorParams, VectorsConfig, WithPayloadSelector, WithVectorsSelector,
+    PointStruct, PointsIdsList, PointsSelector, ScrollPoints, SearchPoints, UpsertPoints,
+    Value as QdrantValue, VectorParams, VectorsConfig, WithPayloadSelector, WithVectorsSelector,
 };
 #[cfg(feature = "vector-qdrant")]
 use qdrant_client::Qdrant;
@@ -269,6 +269,62 @@ impl QdrantClientWrapper {
         })
     }
 
+    /// Build a filter matching the agent's points whose payload has every
+    /// `key = value` pair in `filters`.
+    fn metadata_filter(agent_id: AgentId, filters: HashMap<String, String>) -> Filter {
+        let mut conditions = vec![Condition {
+            condition_one_of: Some(qdrant_client::qdrant::condition::ConditionOneOf::Field(
+                FieldCondition {
+                    key: "agent_id".to_string(),
+                    r#match: Some(Match {
+                        match_value: Some(qdrant_client::qdrant::r#match::MatchValue::Keyword(
+                            agent_id.to_string(),
+                        )),
+                    }),
+                    range: None,
+                    geo_bounding_box: None,
+                    geo_radius: None,
+                    values_count: None,
+                    geo_polygon: None,
+                    datetime_range: None,
+                    is_empty: None,
+                    is_null: None,
+                },
+            )),
+        }];
+
+        // Add additional filters
+        for (key, value) in filters {
+            conditions.push(Condition {
+                condition_one_of: Some(qdrant_client::qdrant::condition::ConditionOneOf::Field(
+                    FieldCondition {
+                        key,
+                        r#match: Some(Match {
+                            match_value: Some(qdrant_client::qdrant::r#match::MatchValue::Keyword(
+                                value,
+                            )),
+                        }),
+                        range: None,
+                        geo_bounding_box: None,
+                        geo_radius: None,
+                        values_count: None,
+                        geo_polygon: None,
+                        datetime_range: None,
+                        is_empty: None,
+                        is_null: None,
+                    },
+                )),
+            });
+        }
+
+        Filter {
+            should: vec![],
+            min_should: None,
+            must: conditions,
+            must_not: vec![],
+        }
+    }
+
     /// Extract string value from QdrantValue
     fn extract_string_value(&self, value: &QdrantValue) -> Option<String> {
         match value {
@@ -521,6 +577,10 @@ impl super::vector_db_trait::VectorDb for QdrantClientWrapper {
 
             // Create metadata from VectorMetadata
             let mut metadata = HashMap::new();
+            metadata.insert(
+                "content".to_string(),
+                QdrantValue::from(item.content.clone()),
+            );
             metadata.insert(
                 "agent_id".to_string(),
                 QdrantValue::from(item.metadata.agent_id.to_string()),
@@ -817,58 +877,7 @@ impl super::vector_db_trait::VectorDb for QdrantClientWrapper {
     ) -> Result<Vec<VectorSearchResult>, ContextError> {
         let client = self.get_client().await?;
 
-        // Build complex filter with agent_id and additional filters
-        let mut conditions = vec![Condition {
-            condition_one_of: Some(qdrant_client::qdrant::condition::ConditionOneOf::Field(
-                FieldCondition {
-                    key: "agent_id".to_string(),
-                    r#match: Some(Match {
-                        match_value: Some(qdrant_client::qdrant::r#match::MatchValue::Keyword(
-                            agent_id.to_string(),
-                        )),
-                    }),
-                    range: None,
-                    geo_bounding_box: None,
-                    geo_radius: None,
-                    values_count: None,
-                    geo_polygon: None,
-                    datetime_range: None,
-                    is_empty: None,
-                    is_null: None,
-                },
-            )),
-        }];
-
-        // Add additional filters
-        for (key, value) in filters {
-            conditions.push(Condition {
-                condition_one_of: Some(qdrant_client::qdrant::condition::ConditionOneOf::Field(
-                    FieldCondition {
-                        key,
-                        r#match: Some(Match {
-                            match_value: Some(qdrant_client::qdrant::r#match::MatchValue::Keyword(
-                                value,
-                            )),
-                        }),
-                        range: None,
-                        geo_bounding_box: None,
-                        geo_radius: None,
-                        values_count: None,
-                        geo_polygon: None,
-                        datetime_range: None,
-                        is_empty: None,
-                        is_null: None,
-                    },
-                )),
-            });
-        }
-
-        let filter = Filter {
-            should: vec![],
-            min_should: None,
-            must: conditions,
-            must_not: vec![],
-        };
+        let filter = Self::metadata_filter(agent_id, filters);
 
         let search_points = SearchPoints {
             collection_name: self.config.collection_name.clone(),
@@ -953,6 +962,58 @@ impl super::vector_db_trait::VectorDb for QdrantClientWrapper {
         Ok(results)
     }
 
+    async fn find_by_metadata(
+        &self,
+        agent_id: AgentId,
+        filters: HashMap<String, String>,
+    ) -> Result<Vec<VectorId>, ContextError> {
+        let client = self.get_client().await?;
+        let filter = Self::metadata_filter(agent_id, filters);
+
+        let mut ids = Vec::new();
+        let mut offset = None;
+        loop {
+            let scroll = ScrollPoints {
+                collection_name: self.config.collection_name.clone(),
+                filter: Some(filter.clone()),
+                offset,
+                limit: Some(256),
+                with_payload: Some(WithPayloadSelector {
+                    selector_options: Some(
+                        qdrant_client::qdrant::with_payload_selector::SelectorOptions::Enable(
+                            false,
+                        ),
+                    ),
+                }),
+                with_vectors: Some(WithVectorsSelector {
+                    selector_options: Some(
+                        qdrant_client::qdrant::with_vectors_selector::SelectorOptions::Enable(
+                            false,
+                        ),
+                    ),
+                }),
+                read_consistency: None,
+                shard_key_selector: None,
+                order_by: None,
+                timeout: None,
+            };
+            let page = client.scroll(scroll).await.map_err(map_qdrant_error)?;
+            ids.extend(page.result.into_iter().filter_map(
+                |point| match point.id?.point_id_options? {
+                    qdrant_client::qdrant::point_id::PointIdOptions::Uuid(uuid) => {
+                        uuid::Uuid::parse_str(&uuid).ok().map(VectorId)
+                    }
+                    qdrant_client::qdrant::point_id::PointIdOptions::Num(_) => None,
+                },
+            ));
+            match page.next_page_offset {
+                Some(next) => offset = Some(next),
+                None => break,
+            }
+        }
+        Ok(ids)
+    }
+
     async fn batch_delete(&self, vector_ids: Vec<VectorId>) -> Result<(), ContextError> {
         let client = self.get_client().await?;
 
@@ -1123,6 +1184,14 @@ impl super::vector_db_trait::VectorDb for NoOpVectorDatabase {
         Ok(Vec::new())
     }
 
+    async fn find_by_metadata(
+        &self,
+        _agent_id: AgentId,
+        _filters: HashMap<String, String>,
+    ) -> Result<Vec<VectorId>, ContextError> {
+        Ok(Vec::new())
+    }
+
     async fn delete_knowledge_item(&self, _vector_id: VectorId) -> Result<(), ContextError> {
         Ok(())
     }
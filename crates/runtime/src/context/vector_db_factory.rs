@@ -3,7 +3,6 @@
 //! Resolves which vector backend to use from env vars / config,
 //! then constructs and returns `Arc<dyn VectorDb>`.
 
-#[cfg(feature = "vector-lancedb")]
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -58,6 +57,17 @@ pub async fn create_vector_backend(
     }
 }
 
+/// Where the BM25 keyword index for a backend is persisted: next to the
+/// LanceDB table directory. Backends without a local data dir get `None`
+/// and keep their keyword index in memory.
+pub fn keyword_index_path(config: &VectorBackendConfig) -> Option<PathBuf> {
+    match config {
+        #[cfg(feature = "vector-lancedb")]
+        VectorBackendConfig::LanceDb(cfg) => Some(cfg.keyword_index_path()),
+        _ => None,
+    }
+}
+
 /// Resolve vector backend config from environment variables.
 ///
 /// Resolution order:
@@ -123,6 +133,23 @@ pub fn resolve_vector_config() -> VectorBackendConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_keyword_index_path_next_to_lancedb_table() {
+        assert_eq!(keyword_index_path(&VectorBackendConfig::NoOp), None);
+        #[cfg(feature = "vector-lancedb")]
+        {
+            let config = VectorBackendConfig::LanceDb(LanceDbConfig {
+                data_path: PathBuf::from("/data/vectors"),
+                collection_name: "memories".to_string(),
+                ..Default::default()
+            });
+            assert_eq!(
+                keyword_index_path(&config),
+                Some(PathBuf::from("/data/vectors/memories.bm25.json"))
+            );
+        }
+    }
+
     #[cfg(feature = "vector-lancedb")]
     #[test]
     fn test_resolve_defaults_to_lancedb() {
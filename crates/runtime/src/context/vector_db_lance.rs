@@ -50,6 +50,14 @@ impl Default for LanceDbConfig {
     }
 }
 
+impl LanceDbConfig {
+    /// Path of the BM25 keyword index kept next to this collection's table.
+    pub fn keyword_index_path(&self) -> PathBuf {
+        self.data_path
+            .join(format!("{}.bm25.json", self.collection_name))
+    }
+}
+
 pub struct LanceDbBackend {
     db: lancedb::Connection,
     config: LanceDbConfig,
@@ -214,6 +222,94 @@ impl LanceDbBackend {
             created_at,
         })
     }
+
+    /// Convert a LanceDB distance into a similarity score (higher is better).
+    fn similarity_from_distance(&self, distance: f32) -> f32 {
+        match self.config.distance_metric {
+            DistanceMetric::Cosine | DistanceMetric::DotProduct => 1.0 - distance,
+            DistanceMetric::Euclidean => 1.0 / (1.0 + distance),
+        }
+    }
+
+    /// Build a search result from a row, flattening metadata to the same
+    /// keys the Qdrant backend uses (`source_id`, `tag_N`, `custom_<key>`).
+    fn parse_search_result_from_batch(
+        &self,
+        batch: &RecordBatch,
+        row: usize,
+    ) -> Option<crate::context::types::VectorSearchResult> {
+        let string_col = |name: &str| {
+            batch
+                .column_by_name(name)
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+        };
+        let id_str = string_col("id")?.value(row);
+        let content = string_col("content")?.value(row).to_string();
+
+        let mut metadata = HashMap::new();
+        for column in ["agent_id", "content_type", "source"] {
+            if let Some(col) = string_col(column) {
+                if !col.is_null(row) {
+                    metadata.insert(column.to_string(), col.value(row).to_string());
+                }
+            }
+        }
+        if let Some(json) = string_col("metadata_json").filter(|c| !c.is_null(row)) {
+            if let Ok(Value::Object(map)) = serde_json::from_str::<Value>(json.value(row)) {
+                for (key, value) in map {
+                    match (key.as_str(), value) {
+                        ("tags", Value::Array(tags)) => {
+                            for (i, tag) in tags.iter().enumerate() {
+                                if let Some(tag) = tag.as_str() {
+                                    metadata.insert(format!("tag_{}", i), tag.to_string());
+                                }
+                            }
+                        }
+                        ("custom_fields", Value::Object(fields)) => {
+                            for (k, v) in fields {
+                                if let Some(v) = v.as_str() {
+                                    metadata.insert(format!("custom_{}", k), v.to_string());
+                                }
+                            }
+                        }
+                        (_, Value::String(v)) => {
+                            metadata.insert(key, v);
+                        }
+                        (_, other) => {
+                            metadata.insert(key, other.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        let score = batch
+            .column_by_name("_distance")
+            .and_then(|c| c.as_any().downcast_ref::<arrow_array::Float32Array>())
+            .map(|d| self.similarity_from_distance(d.value(row)))
+            .unwrap_or(0.0);
+
+        let embedding = batch
+            .column_by_name("vector")
+            .and_then(|c| c.as_any().downcast_ref::<FixedSizeListArray>())
+            .and_then(|list| {
+                let values = list.value(row);
+                values
+                    .as_any()
+                    .downcast_ref::<arrow_array::Float32Array>()
+                    .map(|v| v.values().to_vec())
+            });
+
+        Some(crate::context::types::VectorSearchResult {
+            id: uuid::Uuid::parse_str(id_str)
+                .map(VectorId)
+                .unwrap_or_default(),
+            content,
+            score,
+            metadata,
+            embedding,
+        })
+    }
 }
 
 #[async_trait]
@@ -340,7 +436,7 @@ impl VectorDb for LanceDbBackend {
     ) -> Result<Vec<VectorId>, ContextError> {
         let mut ids = Vec::with_capacity(batch.items.len());
         for item in &batch.items {
-            let vector_id = VectorId::new();
+            let vector_id = item.id.unwrap_or_else(VectorId::new);
             let embedding = item.embedding.clone().unwrap_or_default();
             if embedding.is_empty() {
                 ids.push(vector_id);
@@ -352,6 +448,7 @@ impl VectorDb for LanceDbBackend {
             let metadata_json = serde_json::json!({
                 "source_id": item.metadata.source_id,
                 "tags": item.metadata.tags,
+                "custom_fields": item.metadata.custom_fields,
             })
             .to_string();
 
@@ -444,24 +541,98 @@ impl VectorDb for LanceDbBackend {
         &self,
         agent_id: AgentId,
         query_embedding: Vec<f32>,
-        _filters: HashMap<String, String>,
+        filters: HashMap<String, String>,
         limit: usize,
-        _threshold: f32,
+        threshold: f32,
     ) -> Result<Vec<crate::context::types::VectorSearchResult>, ContextError> {
-        let knowledge_items = self
-            .search_knowledge_base(agent_id, query_embedding, limit)
-            .await?;
+        let table = self.get_table().await?;
 
-        Ok(knowledge_items
-            .into_iter()
-            .map(|ki| crate::context::types::VectorSearchResult {
-                id: VectorId::new(),
-                content: ki.content,
-                score: ki.relevance_score,
-                metadata: HashMap::new(),
-                embedding: None,
-            })
-            .collect())
+        // Metadata filters are matched after the search (they live inside
+        // `metadata_json`), so over-fetch when any are present.
+        let fetch = if filters.is_empty() {
+            limit
+        } else {
+            limit.saturating_mul(4)
+        };
+
+        let batches = table
+            .vector_search(query_embedding)
+            .map_err(|e| ContextError::StorageError {
+                reason: format!("Failed to create vector search: {}", e),
+            })?
+            .distance_type(self.distance_type())
+            .only_if(format!("agent_id = '{}'", agent_id))
+            .limit(fetch)
+            .execute()
+            .await
+            .map_err(|e| ContextError::StorageError {
+                reason: format!("Vector search failed: {}", e),
+            })?
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| ContextError::StorageError {
+                reason: format!("Failed to collect search results: {}", e),
+            })?;
+
+        let mut results = Vec::new();
+        for batch in &batches {
+            for row in 0..batch.num_rows() {
+                let Some(result) = self.parse_search_result_from_batch(batch, row) else {
+                    continue;
+                };
+                if result.score < threshold {
+                    continue;
+                }
+                if filters
+                    .iter()
+                    .all(|(k, v)| result.metadata.get(k) == Some(v))
+                {
+                    results.push(result);
+                }
+            }
+        }
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    async fn find_by_metadata(
+        &self,
+        agent_id: AgentId,
+        filters: HashMap<String, String>,
+    ) -> Result<Vec<VectorId>, ContextError> {
+        let table = self.get_table().await?;
+
+        // Metadata lives inside `metadata_json`, so scan the agent's rows and
+        // match the flattened keys here.
+        let batches = table
+            .query()
+            .only_if(format!("agent_id = '{}'", agent_id))
+            .execute()
+            .await
+            .map_err(|e| ContextError::StorageError {
+                reason: format!("Metadata query failed: {}", e),
+            })?
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| ContextError::StorageError {
+                reason: format!("Failed to collect query results: {}", e),
+            })?;
+
+        let mut ids = Vec::new();
+        for batch in &batches {
+            for row in 0..batch.num_rows() {
+                let Some(result) = self.parse_search_result_from_batch(batch, row) else {
+                    continue;
+                };
+                if filters
+                    .iter()
+                    .all(|(k, v)| result.metadata.get(k) == Some(v))
+                {
+                    ids.push(result.id);
+                }
+            }
+        }
+        Ok(ids)
     }
 
     async fn delete_knowledge_item(&self, vector_id: VectorId) -> Result<(), ContextError> {
@@ -627,6 +798,90 @@ mod tests {
         assert_eq!(stats.total_vectors, 0);
     }
 
+    #[tokio::test]
+    async fn test_lance_advanced_search_scopes_and_filters() {
+        use crate::context::types::{
+            VectorBatchItem, VectorContentType, VectorMetadata, VectorOperationType,
+        };
+
+        let tmp = TempDir::new().unwrap();
+        let backend = LanceDbBackend::new(make_test_config(&tmp)).await.unwrap();
+        backend.initialize().await.unwrap();
+
+        let owner = AgentId::new();
+        let item =
+            |agent_id: AgentId, content: &str, doc: &str, embedding: Vec<f32>| VectorBatchItem {
+                id: None,
+                content: content.to_string(),
+                embedding: Some(embedding),
+                metadata: VectorMetadata {
+                    agent_id,
+                    content_type: VectorContentType::Document,
+                    source_id: doc.to_string(),
+                    created_at: std::time::SystemTime::now(),
+                    updated_at: std::time::SystemTime::now(),
+                    tags: vec!["lang".to_string()],
+                    custom_fields: HashMap::from([("doc".to_string(), doc.to_string())]),
+                },
+            };
+        let ids = backend
+            .batch_store(VectorBatchOperation {
+                operation_type: VectorOperationType::Insert,
+                items: vec![
+                    item(owner, "Rust is fast", "a", vec![1.0, 0.0, 0.0, 0.0]),
+                    item(owner, "Python is easy", "b", vec![0.0, 1.0, 0.0, 0.0]),
+                    item(
+                        AgentId::new(),
+                        "Someone else's",
+                        "a",
+                        vec![1.0, 0.0, 0.0, 0.0],
+                    ),
+                ],
+            })
+            .await
+            .unwrap();
+
+        let results = backend
+            .advanced_search(owner, vec![1.0, 0.0, 0.0, 0.0], HashMap::new(), 10, 0.0)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, ids[0]);
+        assert_eq!(results[0].content, "Rust is fast");
+        assert!((results[0].score - 1.0).abs() < 1e-4);
+        assert!(results[0].score > results[1].score);
+        assert_eq!(results[0].metadata.get("custom_doc").unwrap(), "a");
+        assert_eq!(results[0].metadata.get("tag_0").unwrap(), "lang");
+        assert_eq!(results[0].embedding.as_deref().unwrap().len(), 4);
+
+        // Threshold drops the orthogonal vector.
+        let results = backend
+            .advanced_search(owner, vec![1.0, 0.0, 0.0, 0.0], HashMap::new(), 10, 0.5)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        // Metadata filters use the flattened keys.
+        let filters = HashMap::from([("custom_doc".to_string(), "b".to_string())]);
+        let results = backend
+            .advanced_search(owner, vec![1.0, 0.0, 0.0, 0.0], filters, 10, 0.0)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "Python is easy");
+
+        // Lookup and delete by metadata stay scoped to the agent.
+        let filters = HashMap::from([("custom_doc".to_string(), "a".to_string())]);
+        let found = backend
+            .find_by_metadata(owner, filters.clone())
+            .await
+            .unwrap();
+        assert_eq!(found, vec![ids[0]]);
+        assert_eq!(backend.delete_by_metadata(owner, filters).await.unwrap(), 1);
+        let stats = backend.get_stats().await.unwrap();
+        assert_eq!(stats.total_vectors, 2);
+    }
+
     #[tokio::test]
     async fn test_lance_optimize() {
         let tmp = TempDir::new().unwrap();
@@ -80,6 +80,31 @@ pub trait VectorDb: Send + Sync {
         threshold: f32,
     ) -> Result<Vec<super::types::VectorSearchResult>, ContextError>;
 
+    /// Find the IDs of an agent's vectors whose metadata matches every filter.
+    ///
+    /// Filters use the same flattened keys as `advanced_search`
+    /// (`source_id`, `tag_N`, `custom_<key>`).
+    async fn find_by_metadata(
+        &self,
+        agent_id: AgentId,
+        filters: HashMap<String, String>,
+    ) -> Result<Vec<VectorId>, ContextError>;
+
+    /// Delete an agent's vectors whose metadata matches every filter,
+    /// returning how many were removed.
+    async fn delete_by_metadata(
+        &self,
+        agent_id: AgentId,
+        filters: HashMap<String, String>,
+    ) -> Result<usize, ContextError> {
+        let ids = self.find_by_metadata(agent_id, filters).await?;
+        let count = ids.len();
+        if count > 0 {
+            self.batch_delete(ids).await?;
+        }
+        Ok(count)
+    }
+
     /// Delete a knowledge item by vector ID.
     async fn delete_knowledge_item(&self, vector_id: VectorId) -> Result<(), ContextError>;
 
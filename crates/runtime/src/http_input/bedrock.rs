@@ -34,10 +34,6 @@ fn bedrock_content_blocks(content: Option<&Value>) -> Vec<Value> {
 /// than guessed at.
 fn convert_unified_block(block: &Value) -> Option<Value> {
     match block.get("type").and_then(|t| t.as_str()) {
-        Some("text") => {
-            let text = block.get("text").and_then(|t| t.as_str()).unwrap_or("");
-            Some(json!({ "text": text }))
-        }
         Some("tool_use") => {
             let id = block.get("id").and_then(|i| i.as_str()).unwrap_or("");
             let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("");
@@ -52,19 +48,107 @@ fn convert_unified_block(block: &Value) -> Option<Value> {
                 .and_then(|i| i.as_str())
                 .unwrap_or("");
             // The unified tool_result block's own `content` is a plain
-            // string in every producer in this crate; fall back to a
-            // stringified JSON representation for anything else rather
-            // than dropping the result.
-            let text = match block.get("content") {
-                Some(Value::String(s)) => s.clone(),
-                Some(other) => other.to_string(),
-                None => String::new(),
+            // string, or an array of text/image/document blocks when the
+            // tool returned attachments; fall back to a stringified JSON
+            // representation for anything else rather than dropping the
+            // result.
+            let content = match block.get("content") {
+                Some(Value::String(s)) => vec![json!({ "text": s })],
+                Some(Value::Array(blocks)) => {
+                    blocks.iter().filter_map(convert_media_block).collect()
+                }
+                Some(other) => vec![json!({ "text": other.to_string() })],
+                None => vec![json!({ "text": "" })],
             };
             Some(json!({
-                "toolResult": { "toolUseId": tool_use_id, "content": [ { "text": text } ] }
+                "toolResult": { "toolUseId": tool_use_id, "content": content }
             }))
         }
-        _ => None,
+        _ => convert_media_block(block),
+    }
+}
+
+/// Convert a unified text, image or document block into a Converse block.
+///
+/// Converse takes inline bytes (base64 in the JSON API) or S3 locations,
+/// not URLs, so a URL-sourced part is passed on as a text reference.
+fn convert_media_block(block: &Value) -> Option<Value> {
+    let kind = block.get("type").and_then(|t| t.as_str())?;
+    if kind == "text" {
+        let text = block.get("text").and_then(|t| t.as_str()).unwrap_or("");
+        return Some(json!({ "text": text }));
+    }
+    if kind != "image" && kind != "document" {
+        return None;
+    }
+
+    let source = block.get("source")?;
+    let Some(data) = source.get("data").and_then(|d| d.as_str()) else {
+        let url = source.get("url").and_then(|u| u.as_str()).unwrap_or("");
+        return Some(json!({ "text": format!("[{}: {}]", kind, url) }));
+    };
+    let media_type = source
+        .get("media_type")
+        .and_then(|m| m.as_str())
+        .unwrap_or("");
+    let format = converse_format(media_type)?;
+    if kind == "image" {
+        return Some(json!({
+            "image": { "format": format, "source": { "bytes": data } }
+        }));
+    }
+    let title = block
+        .get("title")
+        .and_then(|t| t.as_str())
+        .unwrap_or("document");
+    Some(json!({
+        "document": {
+            "format": format,
+            "name": converse_document_name(title),
+            "source": { "bytes": data },
+        }
+    }))
+}
+
+/// Converse `format` for a media type; `None` for types Converse rejects.
+fn converse_format(media_type: &str) -> Option<&'static str> {
+    Some(match media_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpeg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "application/pdf" => "pdf",
+        "text/csv" => "csv",
+        "text/html" => "html",
+        "text/markdown" => "md",
+        "text/plain" => "txt",
+        "application/msword" => "doc",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.ms-excel" => "xls",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+        _ => return None,
+    })
+}
+
+/// Converse document names allow only alphanumerics, single spaces,
+/// hyphens, parentheses and square brackets.
+fn converse_document_name(title: &str) -> String {
+    let mut name = String::with_capacity(title.len());
+    for c in title.chars() {
+        let c = if c.is_ascii_alphanumeric() || "-()[]".contains(c) {
+            c
+        } else {
+            ' '
+        };
+        if !(c == ' ' && name.ends_with(' ')) {
+            name.push(c);
+        }
+    }
+    let name = name.trim();
+    if name.is_empty() {
+        "document".to_string()
+    } else {
+        name.to_string()
     }
 }
 
@@ -319,6 +403,44 @@ mod tests {
         assert_eq!(tool_use_block["toolUseId"], "call_1");
     }
 
+    #[test]
+    fn maps_images_and_documents_to_converse_blocks() {
+        use crate::reasoning::conversation::{ContentPart, Conversation, ConversationMessage};
+
+        let mut conv = Conversation::new();
+        conv.push(ConversationMessage::user("compare").with_parts([
+            ContentPart::image("image/png", b"png"),
+            ContentPart::document("application/pdf", b"%PDF", Some("Q3 report.v2.pdf".into())),
+            ContentPart::image_url("image/png", "https://x.test/a.png"),
+        ]));
+        conv.push(ConversationMessage::assistant_tool_calls(vec![
+            crate::reasoning::conversation::ToolCall {
+                id: "c1".into(),
+                name: "plot".into(),
+                arguments: "{}".into(),
+            },
+        ]));
+        conv.push(
+            ConversationMessage::tool_result("c1", "plot", "done")
+                .with_parts([ContentPart::image("image/jpeg", b"jpg")]),
+        );
+
+        let (_system, messages) = conv.to_anthropic_messages();
+        let req = build_converse_request("s", &messages, &[], 0.3, 512);
+
+        let user = req["messages"][0]["content"].as_array().unwrap();
+        assert_eq!(user[0]["text"], "compare");
+        assert_eq!(user[1]["image"]["format"], "png");
+        assert_eq!(user[1]["image"]["source"]["bytes"], "cG5n");
+        assert_eq!(user[2]["document"]["format"], "pdf");
+        assert_eq!(user[2]["document"]["name"], "Q3 report v2 pdf");
+        assert_eq!(user[3]["text"], "[image: https://x.test/a.png]");
+
+        let result = &req["messages"][2]["content"][0]["toolResult"]["content"];
+        assert_eq!(result[0]["text"], "done");
+        assert_eq!(result[1]["image"]["format"], "jpeg");
+    }
+
     #[test]
     fn omits_tool_config_when_no_tools() {
         let messages = vec![serde_json::json!({"role": "user", "content": "hi"})];
@@ -53,6 +53,24 @@ pub struct HttpInputConfig {
 
     /// Webhook signature verification configuration.
     pub webhook_verify: Option<WebhookVerifyConfig>,
+
+    /// Additional webhook endpoints, usually generated from DSL `webhook`
+    /// blocks. Each is served on its own path next to `path`.
+    #[serde(default)]
+    pub webhook_routes: Vec<WebhookRouteConfig>,
+
+    /// Per-agent memory stores, usually generated from DSL `memory` blocks.
+    /// Agents listed here recall from and persist to their own store;
+    /// other agents run without a knowledge bridge.
+    #[serde(default)]
+    pub agent_memory: Vec<crate::context::AgentMemoryConfig>,
+
+    /// Resume reasoning runs that a previous process left unfinished in the
+    /// durable journal when the server starts. Off by default: a resumed run
+    /// repeats the tool calls of the iteration it was cut short in, and
+    /// nobody is waiting for its response.
+    #[serde(default)]
+    pub resume_interrupted_runs: bool,
 }
 
 #[cfg(feature = "http-input")]
@@ -73,6 +91,9 @@ impl Default for HttpInputConfig {
             cors_origins: vec![],
             audit_enabled: true,
             webhook_verify: None,
+            webhook_routes: vec![],
+            agent_memory: vec![],
+            resume_interrupted_runs: false,
         }
     }
 }
@@ -121,6 +142,70 @@ pub struct WebhookVerifyConfig {
     pub secret: String,
 }
 
+/// A dedicated webhook endpoint bound to a single agent.
+///
+/// Routes with a `secret` are authenticated by their provider signature
+/// instead of the server-wide `auth_header`/JWT, since webhook senders such
+/// as GitHub or Stripe cannot attach a bearer token. Routes without a secret
+/// fall back to the server-wide authentication.
+#[cfg(feature = "http-input")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRouteConfig {
+    /// Route name, used in logs and status reports.
+    pub name: String,
+    /// HTTP path the route is served on (e.g., "/hooks/github").
+    pub path: String,
+    /// Provider preset (github, stripe, slack, custom).
+    pub provider: String,
+    /// Secret for signature verification (can be a vault:// or file:// reference).
+    pub secret: Option<String>,
+    /// Agent invoked for each accepted request.
+    pub agent: AgentId,
+    /// Optional payload filter; requests that do not match are acknowledged
+    /// without invoking the agent.
+    pub filter: Option<WebhookFilterConfig>,
+}
+
+#[cfg(feature = "http-input")]
+impl WebhookRouteConfig {
+    /// Build a route from a DSL `webhook` block, invoking `agent`.
+    pub fn from_definition(definition: &dsl::WebhookDefinition, agent: AgentId) -> Self {
+        let provider = match definition.provider {
+            dsl::WebhookProvider::GitHub => "github",
+            dsl::WebhookProvider::Stripe => "stripe",
+            dsl::WebhookProvider::Slack => "slack",
+            dsl::WebhookProvider::Custom => "custom",
+        };
+        Self {
+            name: definition.name.clone(),
+            path: definition.path.clone(),
+            provider: provider.to_string(),
+            secret: Some(definition.secret.clone()).filter(|s| !s.is_empty()),
+            agent,
+            filter: definition
+                .filter
+                .as_ref()
+                .map(|filter| WebhookFilterConfig {
+                    json_path: filter.json_path.clone(),
+                    equals: filter.equals.clone(),
+                    contains: filter.contains.clone(),
+                }),
+        }
+    }
+}
+
+/// Payload filter for a webhook route.
+#[cfg(feature = "http-input")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookFilterConfig {
+    /// JSON path into the request body, e.g. `$.action` or `$.items[0].kind`.
+    pub json_path: String,
+    /// Accept only when the value equals this string.
+    pub equals: Option<String>,
+    /// Accept only when the value contains this substring.
+    pub contains: Option<String>,
+}
+
 #[cfg(feature = "http-input")]
 impl Default for ResponseControlConfig {
     fn default() -> Self {
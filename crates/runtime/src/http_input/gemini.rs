@@ -0,0 +1,675 @@
+//! Google Gemini `generateContent` request building + response parsing
+//! (pure, network-free).
+//!
+//! The same body and response shapes serve both the Gemini API
+//! (`generativelanguage.googleapis.com`, API-key auth) and Vertex AI's
+//! publisher-model endpoints (OAuth bearer auth); only the base URL and the
+//! auth header differ.
+
+use crate::reasoning::inference::{
+    FinishReason, InferenceError, InferenceOptions, InferenceResponse, ResponseFormat,
+    ToolCallRequest, ToolChoice, Usage,
+};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+/// `{base_url}/models/{model}:generateContent`.
+pub(crate) fn generate_content_url(base_url: &str, model: &str) -> String {
+    // Accept both bare model ids and fully qualified `models/...` names.
+    let model = model.strip_prefix("models/").unwrap_or(model);
+    format!(
+        "{}/models/{}:generateContent",
+        base_url.trim_end_matches('/'),
+        model
+    )
+}
+
+/// Auth header for a request: `x-goog-api-key` for the Gemini API, an
+/// OAuth bearer token for Vertex AI.
+pub(crate) fn auth_header(vertex: bool, credential: &str) -> (&'static str, String) {
+    if vertex {
+        ("authorization", format!("Bearer {}", credential))
+    } else {
+        ("x-goog-api-key", credential.to_string())
+    }
+}
+
+/// Build a `generateContent` body from the unified (Anthropic-shaped)
+/// message list produced by `Conversation::to_anthropic_messages`.
+///
+/// Tool results name the call they answer only by id, but Gemini's
+/// `functionResponse` wants the function name, so ids are resolved against
+/// the `tool_use` blocks seen earlier in the conversation.
+pub(crate) fn build_generate_content_request(
+    system: Option<&str>,
+    messages: &[Value],
+    options: &InferenceOptions,
+) -> Value {
+    let mut call_names: HashMap<String, String> = HashMap::new();
+    let mut contents: Vec<Value> = Vec::new();
+    for message in messages {
+        let role = match message.get("role").and_then(|r| r.as_str()) {
+            Some("assistant") => "model",
+            _ => "user",
+        };
+        let parts = gemini_parts(message.get("content"), &mut call_names);
+        if parts.is_empty() {
+            continue;
+        }
+        // Gemini wants turns to alternate; fold same-role neighbours.
+        match contents.last_mut() {
+            Some(last) if last["role"] == role => {
+                if let Some(existing) = last["parts"].as_array_mut() {
+                    existing.extend(parts);
+                }
+            }
+            _ => contents.push(json!({ "role": role, "parts": parts })),
+        }
+    }
+
+    let mut generation_config = json!({
+        "temperature": options.temperature,
+        "maxOutputTokens": options.max_tokens,
+    });
+    match &options.response_format {
+        ResponseFormat::Text => {}
+        ResponseFormat::JsonObject => {
+            generation_config["responseMimeType"] = json!("application/json");
+        }
+        ResponseFormat::JsonSchema { schema, .. } => {
+            generation_config["responseMimeType"] = json!("application/json");
+            generation_config["responseSchema"] = to_gemini_schema(schema);
+        }
+    }
+
+    let mut req = json!({
+        "contents": contents,
+        "generationConfig": generation_config,
+    });
+    if let Some(system) = system.filter(|s| !s.is_empty()) {
+        req["systemInstruction"] = json!({ "parts": [ { "text": system } ] });
+    }
+
+    if !options.tool_definitions.is_empty() {
+        let declarations: Vec<Value> = options
+            .tool_definitions
+            .iter()
+            .map(|td| {
+                let mut decl = json!({ "name": td.name, "description": td.description });
+                // Gemini rejects an OBJECT schema with no properties; a
+                // parameterless function simply omits `parameters`.
+                let has_properties = td
+                    .parameters
+                    .get("properties")
+                    .and_then(|p| p.as_object())
+                    .is_some_and(|p| !p.is_empty());
+                if has_properties {
+                    decl["parameters"] = to_gemini_schema(&td.parameters);
+                }
+                decl
+            })
+            .collect();
+        req["tools"] = json!([ { "functionDeclarations": declarations } ]);
+
+        if let Some(choice) = &options.tool_choice {
+            req["toolConfig"] = json!({
+                "functionCallingConfig": match choice {
+                    ToolChoice::Auto => json!({ "mode": "AUTO" }),
+                    ToolChoice::Any => json!({ "mode": "ANY" }),
+                    ToolChoice::Tool { name } => {
+                        json!({ "mode": "ANY", "allowedFunctionNames": [name] })
+                    }
+                }
+            });
+        }
+    }
+
+    // Provider-specific extras (`safetySettings`, `cachedContent`, ...) are
+    // applied last and override same-named keys built above.
+    for (k, v) in &options.extra {
+        req[k] = v.clone();
+    }
+    req
+}
+
+/// Convert one message's unified `content` into Gemini parts.
+fn gemini_parts(content: Option<&Value>, call_names: &mut HashMap<String, String>) -> Vec<Value> {
+    match content {
+        Some(Value::String(s)) if !s.is_empty() => vec![json!({ "text": s })],
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .flat_map(|b| convert_unified_block(b, call_names))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn convert_unified_block(block: &Value, call_names: &mut HashMap<String, String>) -> Vec<Value> {
+    match block.get("type").and_then(|t| t.as_str()) {
+        Some("tool_use") => {
+            let id = block.get("id").and_then(|i| i.as_str()).unwrap_or("");
+            let name = block.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            call_names.insert(id.to_string(), name.to_string());
+            let args = block.get("input").cloned().unwrap_or_else(|| json!({}));
+            vec![json!({ "functionCall": { "name": name, "args": args } })]
+        }
+        Some("tool_result") => {
+            let id = block
+                .get("tool_use_id")
+                .and_then(|i| i.as_str())
+                .unwrap_or("");
+            let name = call_names
+                .get(id)
+                .cloned()
+                .unwrap_or_else(|| id.to_string());
+            // `response` must be a JSON object. Text goes under `content`;
+            // attachments follow as their own parts.
+            let (text, media) = match block.get("content") {
+                Some(Value::String(s)) => (s.clone(), Vec::new()),
+                Some(Value::Array(blocks)) => {
+                    let text: Vec<&str> = blocks
+                        .iter()
+                        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                        .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                        .collect();
+                    let media: Vec<Value> = blocks
+                        .iter()
+                        .filter(|b| b.get("type").and_then(|t| t.as_str()) != Some("text"))
+                        .filter_map(convert_media_block)
+                        .collect();
+                    (text.join("\n"), media)
+                }
+                Some(other) => (other.to_string(), Vec::new()),
+                None => (String::new(), Vec::new()),
+            };
+            let mut parts = vec![json!({
+                "functionResponse": { "name": name, "response": { "content": text } }
+            })];
+            parts.extend(media);
+            parts
+        }
+        _ => convert_media_block(block).into_iter().collect(),
+    }
+}
+
+/// Convert a unified text, image or document block into a Gemini part.
+///
+/// Inline bytes become `inlineData`. URL sources carry no media type in the
+/// unified shape, which `fileData` requires, so they are passed on as a
+/// text reference.
+fn convert_media_block(block: &Value) -> Option<Value> {
+    let kind = block.get("type").and_then(|t| t.as_str())?;
+    if kind == "text" {
+        let text = block.get("text").and_then(|t| t.as_str()).unwrap_or("");
+        return (!text.is_empty()).then(|| json!({ "text": text }));
+    }
+    if kind != "image" && kind != "document" {
+        return None;
+    }
+    let source = block.get("source")?;
+    let Some(data) = source.get("data").and_then(|d| d.as_str()) else {
+        let url = source.get("url").and_then(|u| u.as_str()).unwrap_or("");
+        return Some(json!({ "text": format!("[{}: {}]", kind, url) }));
+    };
+    let media_type = source
+        .get("media_type")
+        .and_then(|m| m.as_str())
+        .unwrap_or("application/octet-stream");
+    Some(json!({ "inlineData": { "mimeType": media_type, "data": data } }))
+}
+
+/// Schema keywords Gemini's OpenAPI-subset `Schema` accepts.
+const SCHEMA_KEYWORDS: &[&str] = &[
+    "type",
+    "format",
+    "title",
+    "description",
+    "nullable",
+    "enum",
+    "items",
+    "minItems",
+    "maxItems",
+    "properties",
+    "required",
+    "minProperties",
+    "maxProperties",
+    "propertyOrdering",
+    "minLength",
+    "maxLength",
+    "pattern",
+    "minimum",
+    "maximum",
+    "anyOf",
+    "default",
+    "example",
+];
+
+/// Translate a JSON Schema into Gemini's `Schema` subset: unsupported
+/// keywords (`$schema`, `additionalProperties`, ...) are dropped, and a
+/// `["string", "null"]` type becomes `STRING` + `nullable`.
+pub(crate) fn to_gemini_schema(schema: &Value) -> Value {
+    let Some(obj) = schema.as_object() else {
+        return schema.clone();
+    };
+    let mut out = Map::new();
+    for (key, value) in obj {
+        if !SCHEMA_KEYWORDS.contains(&key.as_str()) {
+            continue;
+        }
+        let converted = match key.as_str() {
+            "type" => match value {
+                Value::String(t) => json!(t.to_ascii_uppercase()),
+                Value::Array(types) => {
+                    let mut non_null = types
+                        .iter()
+                        .filter_map(|t| t.as_str())
+                        .filter(|t| *t != "null");
+                    if types.iter().any(|t| t == "null") {
+                        out.insert("nullable".into(), json!(true));
+                    }
+                    match non_null.next() {
+                        Some(t) => json!(t.to_ascii_uppercase()),
+                        None => continue,
+                    }
+                }
+                other => other.clone(),
+            },
+            "properties" => match value.as_object() {
+                Some(props) => Value::Object(
+                    props
+                        .iter()
+                        .map(|(name, s)| (name.clone(), to_gemini_schema(s)))
+                        .collect(),
+                ),
+                None => continue,
+            },
+            "items" => to_gemini_schema(value),
+            "anyOf" => match value.as_array() {
+                Some(variants) => Value::Array(variants.iter().map(to_gemini_schema).collect()),
+                None => continue,
+            },
+            _ => value.clone(),
+        };
+        out.insert(key.clone(), converted);
+    }
+    Value::Object(out)
+}
+
+/// Map a candidate `finishReason` to a [`FinishReason`].
+///
+/// Safety and policy blocks (`SAFETY`, `RECITATION`, `BLOCKLIST`,
+/// `PROHIBITED_CONTENT`, `SPII`, `IMAGE_SAFETY`) map to
+/// [`FinishReason::ContentFilter`].
+pub(crate) fn map_finish_reason(reason: &str, has_tool_calls: bool) -> FinishReason {
+    match reason {
+        "MAX_TOKENS" => FinishReason::MaxTokens,
+        "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" | "IMAGE_SAFETY" => {
+            FinishReason::ContentFilter
+        }
+        _ if has_tool_calls => FinishReason::ToolCalls,
+        _ => FinishReason::Stop,
+    }
+}
+
+/// Parse a `generateContent` response.
+///
+/// A prompt blocked before generation (`promptFeedback.blockReason`, no
+/// candidates) is an empty [`FinishReason::ContentFilter`] turn. Gemini
+/// does not always id its function calls, so calls without one get an id
+/// derived from their position in the turn.
+pub(crate) fn parse_generate_content_response(
+    resp: &Value,
+    model: &str,
+) -> Result<InferenceResponse, InferenceError> {
+    let usage = resp
+        .get("usageMetadata")
+        .map(|u| {
+            let field = |key: &str| u.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+            // `promptTokenCount` already includes cached content; thinking
+            // tokens are billed as output.
+            let prompt = field("promptTokenCount");
+            let completion = field("candidatesTokenCount") + field("thoughtsTokenCount");
+            let total = match field("totalTokenCount") {
+                0 => prompt + completion,
+                t => t,
+            };
+            Usage {
+                prompt_tokens: prompt,
+                completion_tokens: completion,
+                total_tokens: total,
+                cached_prompt_tokens: field("cachedContentTokenCount"),
+            }
+        })
+        .unwrap_or_default();
+    let actual_model = resp
+        .get("modelVersion")
+        .and_then(|m| m.as_str())
+        .unwrap_or(model)
+        .to_string();
+
+    let Some(candidate) = resp
+        .get("candidates")
+        .and_then(|c| c.as_array())
+        .and_then(|c| c.first())
+    else {
+        if let Some(reason) = resp
+            .pointer("/promptFeedback/blockReason")
+            .and_then(|r| r.as_str())
+        {
+            tracing::warn!("Gemini blocked the prompt (blockReason={})", reason);
+            return Ok(InferenceResponse {
+                content: String::new(),
+                tool_calls: Vec::new(),
+                finish_reason: FinishReason::ContentFilter,
+                usage,
+                model: actual_model,
+            });
+        }
+        return Err(InferenceError::ParseError(
+            "No candidates in Gemini response".into(),
+        ));
+    };
+
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    let parts = candidate
+        .pointer("/content/parts")
+        .and_then(|p| p.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for part in parts {
+        // Thought summaries are not part of the answer.
+        if part.get("thought").and_then(|t| t.as_bool()) == Some(true) {
+            continue;
+        }
+        if let Some(t) = part.get("text").and_then(|t| t.as_str()) {
+            text.push_str(t);
+        } else if let Some(call) = part.get("functionCall") {
+            let name = call.get("name").and_then(|n| n.as_str()).unwrap_or("");
+            let id = call
+                .get("id")
+                .and_then(|i| i.as_str())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("gemini_call_{}_{}", tool_calls.len(), name));
+            let args = call.get("args").cloned().unwrap_or_else(|| json!({}));
+            tool_calls.push(ToolCallRequest {
+                id,
+                name: name.to_string(),
+                arguments: serde_json::to_string(&args).unwrap_or_else(|_| "{}".into()),
+            });
+        }
+    }
+
+    let reason = candidate
+        .get("finishReason")
+        .and_then(|r| r.as_str())
+        .unwrap_or("STOP");
+    if reason == "MALFORMED_FUNCTION_CALL" {
+        return Err(InferenceError::ParseError(format!(
+            "Gemini produced a malformed function call: {}",
+            candidate
+                .get("finishMessage")
+                .and_then(|m| m.as_str())
+                .unwrap_or("no details")
+        )));
+    }
+    let finish_reason = map_finish_reason(reason, !tool_calls.is_empty());
+    if finish_reason == FinishReason::ContentFilter {
+        tracing::warn!("Gemini response blocked (finishReason={})", reason);
+    }
+
+    Ok(InferenceResponse {
+        content: text,
+        tool_calls,
+        finish_reason,
+        usage,
+        model: actual_model,
+    })
+}
+
+/// Render a parsed response in the unified `{content, stop_reason}` shape
+/// returned by `LlmClient::chat_with_tools`.
+pub(crate) fn to_unified_response(response: &InferenceResponse) -> Value {
+    let mut content = Vec::new();
+    if !response.content.is_empty() {
+        content.push(json!({ "type": "text", "text": response.content }));
+    }
+    for call in &response.tool_calls {
+        let input: Value = serde_json::from_str(&call.arguments).unwrap_or_else(|_| json!({}));
+        content.push(json!({
+            "type": "tool_use",
+            "id": call.id,
+            "name": call.name,
+            "input": input,
+        }));
+    }
+    let stop_reason = match response.finish_reason {
+        FinishReason::ToolCalls => "tool_use",
+        FinishReason::MaxTokens => "max_tokens",
+        _ => "end_turn",
+    };
+    json!({ "content": content, "stop_reason": stop_reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reasoning::conversation::{
+        ContentPart, Conversation, ConversationMessage, ToolCall,
+    };
+    use crate::reasoning::inference::ToolDefinition;
+
+    fn weather_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: "get_weather".into(),
+            description: "Weather for a city".into(),
+            parameters: json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "city": {"type": "string"},
+                    "units": {"type": ["string", "null"], "enum": ["c", "f"]}
+                },
+                "required": ["city"]
+            }),
+        }
+    }
+
+    #[test]
+    fn builds_request_with_system_tools_and_tool_choice() {
+        let messages = vec![json!({"role": "user", "content": "weather in Paris?"})];
+        let options = InferenceOptions {
+            tool_definitions: vec![
+                weather_tool(),
+                ToolDefinition {
+                    name: "now".into(),
+                    description: "Current time".into(),
+                    parameters: json!({"type": "object", "properties": {}}),
+                },
+            ],
+            tool_choice: Some(ToolChoice::Tool {
+                name: "get_weather".into(),
+            }),
+            max_tokens: 512,
+            ..Default::default()
+        };
+        let req = build_generate_content_request(Some("be brief"), &messages, &options);
+
+        assert_eq!(req["systemInstruction"]["parts"][0]["text"], "be brief");
+        assert_eq!(req["contents"][0]["role"], "user");
+        assert_eq!(req["contents"][0]["parts"][0]["text"], "weather in Paris?");
+        assert_eq!(req["generationConfig"]["maxOutputTokens"], 512);
+
+        let decls = &req["tools"][0]["functionDeclarations"];
+        assert_eq!(decls[0]["name"], "get_weather");
+        let params = &decls[0]["parameters"];
+        assert_eq!(params["type"], "OBJECT");
+        assert!(params.get("$schema").is_none());
+        assert!(params.get("additionalProperties").is_none());
+        assert_eq!(params["properties"]["units"]["type"], "STRING");
+        assert_eq!(params["properties"]["units"]["nullable"], true);
+        assert_eq!(params["required"][0], "city");
+        assert!(decls[1].get("parameters").is_none());
+
+        let calling = &req["toolConfig"]["functionCallingConfig"];
+        assert_eq!(calling["mode"], "ANY");
+        assert_eq!(calling["allowedFunctionNames"][0], "get_weather");
+    }
+
+    #[test]
+    fn json_schema_response_format_sets_response_schema() {
+        let options = InferenceOptions {
+            response_format: ResponseFormat::JsonSchema {
+                schema: json!({
+                    "type": "object",
+                    "properties": {"answer": {"type": "string"}},
+                    "additionalProperties": false
+                }),
+                name: Some("answer".into()),
+            },
+            extra: HashMap::from([(
+                "safetySettings".to_string(),
+                json!([{"category": "HARM_CATEGORY_HARASSMENT", "threshold": "BLOCK_NONE"}]),
+            )]),
+            ..Default::default()
+        };
+        let req = build_generate_content_request(None, &[], &options);
+        let config = &req["generationConfig"];
+        assert_eq!(config["responseMimeType"], "application/json");
+        assert_eq!(config["responseSchema"]["type"], "OBJECT");
+        assert_eq!(
+            config["responseSchema"]["properties"]["answer"]["type"],
+            "STRING"
+        );
+        assert!(config["responseSchema"]
+            .get("additionalProperties")
+            .is_none());
+        assert!(req.get("systemInstruction").is_none());
+        assert_eq!(
+            req["safetySettings"][0]["category"],
+            "HARM_CATEGORY_HARASSMENT"
+        );
+    }
+
+    #[test]
+    fn tool_turns_become_function_call_and_named_function_response() {
+        let mut conv = Conversation::with_system("sys");
+        conv.push(ConversationMessage::user("weather?"));
+        conv.push(ConversationMessage::assistant_tool_calls(vec![ToolCall {
+            id: "call_1".into(),
+            name: "get_weather".into(),
+            arguments: r#"{"city":"Paris"}"#.into(),
+        }]));
+        conv.push(
+            ConversationMessage::tool_result("call_1", "get_weather", "sunny, 21C")
+                .with_parts([ContentPart::image("image/png", b"png")]),
+        );
+        conv.push(ConversationMessage::user("thanks"));
+
+        let (system, messages) = conv.to_anthropic_messages();
+        let req = build_generate_content_request(
+            system.as_deref(),
+            &messages,
+            &InferenceOptions::default(),
+        );
+        let contents = req["contents"].as_array().unwrap();
+        assert_eq!(contents.len(), 3, "tool result and next user turn merge");
+
+        assert_eq!(contents[1]["role"], "model");
+        let call = &contents[1]["parts"][0]["functionCall"];
+        assert_eq!(call["name"], "get_weather");
+        assert_eq!(call["args"]["city"], "Paris");
+
+        assert_eq!(contents[2]["role"], "user");
+        let parts = contents[2]["parts"].as_array().unwrap();
+        let response = &parts[0]["functionResponse"];
+        assert_eq!(response["name"], "get_weather");
+        assert_eq!(response["response"]["content"], "sunny, 21C");
+        assert_eq!(parts[1]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(parts[1]["inlineData"]["data"], "cG5n");
+        assert_eq!(parts[2]["text"], "thanks");
+    }
+
+    #[test]
+    fn parses_text_function_calls_and_usage() {
+        let resp = json!({
+            "candidates": [{
+                "content": {"role": "model", "parts": [
+                    {"text": "thinking...", "thought": true},
+                    {"text": "Checking."},
+                    {"functionCall": {"name": "get_weather", "args": {"city": "Paris"}}}
+                ]},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {
+                "promptTokenCount": 100,
+                "candidatesTokenCount": 20,
+                "thoughtsTokenCount": 5,
+                "cachedContentTokenCount": 60,
+                "totalTokenCount": 125
+            },
+            "modelVersion": "gemini-2.5-flash-001"
+        });
+        let parsed = parse_generate_content_response(&resp, "gemini-2.5-flash").unwrap();
+        assert_eq!(parsed.content, "Checking.");
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].name, "get_weather");
+        assert_eq!(parsed.tool_calls[0].id, "gemini_call_0_get_weather");
+        assert_eq!(parsed.tool_calls[0].arguments, r#"{"city":"Paris"}"#);
+        assert_eq!(parsed.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(parsed.usage.prompt_tokens, 100);
+        assert_eq!(parsed.usage.completion_tokens, 25);
+        assert_eq!(parsed.usage.total_tokens, 125);
+        assert_eq!(parsed.usage.cached_prompt_tokens, 60);
+        assert_eq!(parsed.model, "gemini-2.5-flash-001");
+
+        let unified = to_unified_response(&parsed);
+        assert_eq!(unified["stop_reason"], "tool_use");
+        assert_eq!(unified["content"][1]["input"]["city"], "Paris");
+    }
+
+    #[test]
+    fn safety_blocks_map_to_content_filter() {
+        let blocked_candidate = json!({
+            "candidates": [{"content": {"parts": []}, "finishReason": "SAFETY"}]
+        });
+        let parsed = parse_generate_content_response(&blocked_candidate, "m").unwrap();
+        assert_eq!(parsed.finish_reason, FinishReason::ContentFilter);
+        assert_eq!(parsed.model, "m");
+
+        let blocked_prompt = json!({
+            "promptFeedback": {"blockReason": "PROHIBITED_CONTENT"},
+            "usageMetadata": {"promptTokenCount": 7}
+        });
+        let parsed = parse_generate_content_response(&blocked_prompt, "m").unwrap();
+        assert_eq!(parsed.finish_reason, FinishReason::ContentFilter);
+        assert_eq!(parsed.usage.total_tokens, 7);
+
+        assert_eq!(
+            map_finish_reason("MAX_TOKENS", false),
+            FinishReason::MaxTokens
+        );
+        assert_eq!(
+            map_finish_reason("RECITATION", true),
+            FinishReason::ContentFilter
+        );
+        assert!(parse_generate_content_response(&json!({}), "m").is_err());
+    }
+
+    #[test]
+    fn endpoint_and_auth_header_shapes() {
+        assert_eq!(
+            generate_content_url(
+                "https://generativelanguage.googleapis.com/v1beta/",
+                "models/gemini-2.5-pro"
+            ),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-pro:generateContent"
+        );
+        assert_eq!(auth_header(false, "k"), ("x-goog-api-key", "k".to_string()));
+        assert_eq!(
+            auth_header(true, "tok"),
+            ("authorization", "Bearer tok".to_string())
+        );
+    }
+}
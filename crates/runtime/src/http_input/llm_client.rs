@@ -13,6 +13,10 @@ pub enum LlmProvider {
     OpenRouter,
     OpenAI,
     Anthropic,
+    /// Google Gemini API (`generativelanguage.googleapis.com`).
+    Gemini,
+    /// Gemini models served from Vertex AI.
+    Vertex,
     #[cfg(feature = "bedrock")]
     Bedrock,
 }
@@ -24,12 +28,33 @@ impl std::fmt::Display for LlmProvider {
             LlmProvider::OpenRouter => write!(f, "OpenRouter"),
             LlmProvider::OpenAI => write!(f, "OpenAI"),
             LlmProvider::Anthropic => write!(f, "Anthropic"),
+            LlmProvider::Gemini => write!(f, "Gemini"),
+            LlmProvider::Vertex => write!(f, "Vertex"),
             #[cfg(feature = "bedrock")]
             LlmProvider::Bedrock => write!(f, "Bedrock"),
         }
     }
 }
 
+#[cfg(feature = "http-input")]
+impl std::str::FromStr for LlmProvider {
+    type Err = String;
+
+    /// Parse a provider name as written in configuration (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "openrouter" => Ok(LlmProvider::OpenRouter),
+            "openai" => Ok(LlmProvider::OpenAI),
+            "anthropic" => Ok(LlmProvider::Anthropic),
+            "gemini" => Ok(LlmProvider::Gemini),
+            "vertex" => Ok(LlmProvider::Vertex),
+            #[cfg(feature = "bedrock")]
+            "bedrock" => Ok(LlmProvider::Bedrock),
+            other => Err(format!("unknown LLM provider '{other}'")),
+        }
+    }
+}
+
 /// Returns OpenRouter app-attribution headers from env, if the operator opted in.
 ///
 /// Reads `OPENROUTER_REFERER` and `OPENROUTER_TITLE`. Both are optional and
@@ -51,6 +76,32 @@ pub(crate) fn openrouter_attribution_headers() -> Vec<(&'static str, String)> {
     headers
 }
 
+/// Vertex AI publisher-model base URL.
+///
+/// `VERTEX_BASE_URL` wins outright; otherwise the URL is built from
+/// `VERTEX_PROJECT` (or `GOOGLE_CLOUD_PROJECT`) and `VERTEX_LOCATION`
+/// (default `us-central1`). Returns `None` when no project is configured.
+#[cfg(feature = "http-input")]
+fn vertex_base_url() -> Option<String> {
+    if let Ok(url) = std::env::var("VERTEX_BASE_URL") {
+        return Some(url);
+    }
+    let project = std::env::var("VERTEX_PROJECT")
+        .or_else(|_| std::env::var("GOOGLE_CLOUD_PROJECT"))
+        .ok()?;
+    let location = std::env::var("VERTEX_LOCATION").unwrap_or_else(|_| "us-central1".to_string());
+    // The global endpoint has no regional host prefix.
+    let host = if location == "global" {
+        "aiplatform.googleapis.com".to_string()
+    } else {
+        format!("{}-aiplatform.googleapis.com", location)
+    };
+    Some(format!(
+        "https://{}/v1/projects/{}/locations/{}/publishers/google",
+        host, project, location
+    ))
+}
+
 /// OpenAI-compatible chat completions client
 #[cfg(feature = "http-input")]
 pub struct LlmClient {
@@ -75,9 +126,42 @@ impl LlmClient {
     /// 1. `OPENROUTER_API_KEY` → OpenRouter (model from `OPENROUTER_MODEL`)
     /// 2. `OPENAI_API_KEY` → OpenAI (model from `CHAT_MODEL`)
     /// 3. `ANTHROPIC_API_KEY` → Anthropic (model from `ANTHROPIC_MODEL`)
+    /// 4. `GEMINI_API_KEY` → Gemini (model from `GEMINI_MODEL`)
+    /// 5. `VERTEX_ACCESS_TOKEN` → Vertex AI (model from `GEMINI_MODEL`,
+    ///    project from `VERTEX_PROJECT`/`GOOGLE_CLOUD_PROJECT`)
     ///
     /// Returns `None` if no API key is found.
     pub fn from_env() -> Option<Self> {
+        let provider = if std::env::var("OPENROUTER_API_KEY").is_ok() {
+            LlmProvider::OpenRouter
+        } else if std::env::var("OPENAI_API_KEY").is_ok() {
+            LlmProvider::OpenAI
+        } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
+            LlmProvider::Anthropic
+        } else if std::env::var("GEMINI_API_KEY").is_ok() {
+            LlmProvider::Gemini
+        } else if std::env::var("VERTEX_ACCESS_TOKEN").is_ok() {
+            LlmProvider::Vertex
+        } else {
+            #[cfg(feature = "bedrock")]
+            if std::env::var("BEDROCK_MODEL_ID").is_ok() {
+                return Self::from_env_for(LlmProvider::Bedrock);
+            }
+            tracing::info!("No LLM API key found in environment, LLM invocation disabled");
+            return None;
+        };
+        Self::from_env_for(provider)
+    }
+
+    /// Build a client for one specific provider from its environment
+    /// variables, skipping auto-detection.
+    ///
+    /// Reads the same `*_API_KEY` / `*_BASE_URL` / `*_MODEL` variables as
+    /// [`from_env`](Self::from_env). Returns `None` if that provider's key
+    /// is missing or its base URL is refused by the SSRF guard. Used to
+    /// assemble failover chains where several providers are configured at
+    /// once.
+    pub fn from_env_for(provider: LlmProvider) -> Option<Self> {
         // LLM providers legitimately redirect (e.g. `/v1` → `/v1/`) and the
         // LLM hostname comes from env, so we explicitly keep redirect
         // following within reason — but force DNS through the SSRF-safe
@@ -112,87 +196,146 @@ impl LlmClient {
             true
         }
 
-        if let Ok(api_key) = std::env::var("OPENROUTER_API_KEY") {
-            let model = std::env::var("OPENROUTER_MODEL")
-                .unwrap_or_else(|_| "anthropic/claude-sonnet-4".to_string());
-            let base_url = std::env::var("OPENROUTER_BASE_URL")
-                .unwrap_or_else(|_| "https://openrouter.ai/api/v1".to_string());
-            if !validate_base_url("OPENROUTER_BASE_URL", &base_url) {
-                return None;
+        match provider {
+            LlmProvider::OpenRouter => {
+                let api_key = std::env::var("OPENROUTER_API_KEY").ok()?;
+                let model = std::env::var("OPENROUTER_MODEL")
+                    .unwrap_or_else(|_| "anthropic/claude-sonnet-4".to_string());
+                let base_url = std::env::var("OPENROUTER_BASE_URL")
+                    .unwrap_or_else(|_| "https://openrouter.ai/api/v1".to_string());
+                if !validate_base_url("OPENROUTER_BASE_URL", &base_url) {
+                    return None;
+                }
+                tracing::info!(
+                    "LLM client initialized: provider=OpenRouter model={}",
+                    model
+                );
+                Some(Self {
+                    client,
+                    api_key,
+                    base_url,
+                    model,
+                    provider: LlmProvider::OpenRouter,
+                    #[cfg(feature = "bedrock")]
+                    region: String::new(),
+                    #[cfg(feature = "bedrock")]
+                    credentials: None,
+                })
             }
-            tracing::info!(
-                "LLM client initialized: provider=OpenRouter model={}",
-                model
-            );
-            return Some(Self {
-                client,
-                api_key,
-                base_url,
-                model,
-                provider: LlmProvider::OpenRouter,
-                #[cfg(feature = "bedrock")]
-                region: String::new(),
-                #[cfg(feature = "bedrock")]
-                credentials: None,
-            });
-        }
-
-        if let Ok(api_key) = std::env::var("OPENAI_API_KEY") {
-            let model = std::env::var("CHAT_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
-            let base_url = std::env::var("OPENAI_BASE_URL")
-                .unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
-            if !validate_base_url("OPENAI_BASE_URL", &base_url) {
-                return None;
+            LlmProvider::OpenAI => {
+                let api_key = std::env::var("OPENAI_API_KEY").ok()?;
+                let model = std::env::var("CHAT_MODEL").unwrap_or_else(|_| "gpt-4o".to_string());
+                let base_url = std::env::var("OPENAI_BASE_URL")
+                    .unwrap_or_else(|_| "https://api.openai.com/v1".to_string());
+                if !validate_base_url("OPENAI_BASE_URL", &base_url) {
+                    return None;
+                }
+                tracing::info!("LLM client initialized: provider=OpenAI model={}", model);
+                Some(Self {
+                    client,
+                    api_key,
+                    base_url,
+                    model,
+                    provider: LlmProvider::OpenAI,
+                    #[cfg(feature = "bedrock")]
+                    region: String::new(),
+                    #[cfg(feature = "bedrock")]
+                    credentials: None,
+                })
             }
-            tracing::info!("LLM client initialized: provider=OpenAI model={}", model);
-            return Some(Self {
-                client,
-                api_key,
-                base_url,
-                model,
-                provider: LlmProvider::OpenAI,
-                #[cfg(feature = "bedrock")]
-                region: String::new(),
-                #[cfg(feature = "bedrock")]
-                credentials: None,
-            });
-        }
-
-        if let Ok(api_key) = std::env::var("ANTHROPIC_API_KEY") {
-            let model = std::env::var("ANTHROPIC_MODEL")
-                .unwrap_or_else(|_| "claude-sonnet-4-20250514".to_string());
-            let base_url = std::env::var("ANTHROPIC_BASE_URL")
-                .unwrap_or_else(|_| "https://api.anthropic.com/v1".to_string());
-            if !validate_base_url("ANTHROPIC_BASE_URL", &base_url) {
-                return None;
+            LlmProvider::Anthropic => {
+                let api_key = std::env::var("ANTHROPIC_API_KEY").ok()?;
+                let model = std::env::var("ANTHROPIC_MODEL")
+                    .unwrap_or_else(|_| "claude-sonnet-4-20250514".to_string());
+                let base_url = std::env::var("ANTHROPIC_BASE_URL")
+                    .unwrap_or_else(|_| "https://api.anthropic.com/v1".to_string());
+                if !validate_base_url("ANTHROPIC_BASE_URL", &base_url) {
+                    return None;
+                }
+                tracing::info!("LLM client initialized: provider=Anthropic model={}", model);
+                Some(Self {
+                    client,
+                    api_key,
+                    base_url,
+                    model,
+                    provider: LlmProvider::Anthropic,
+                    #[cfg(feature = "bedrock")]
+                    region: String::new(),
+                    #[cfg(feature = "bedrock")]
+                    credentials: None,
+                })
             }
-            tracing::info!("LLM client initialized: provider=Anthropic model={}", model);
-            return Some(Self {
-                client,
-                api_key,
-                base_url,
-                model,
-                provider: LlmProvider::Anthropic,
-                #[cfg(feature = "bedrock")]
-                region: String::new(),
-                #[cfg(feature = "bedrock")]
-                credentials: None,
-            });
-        }
-
-        // Bedrock: no API key — uses AWS credential chain instead.
-        // `from_env` is sync so credentials are resolved lazily at call time
-        // (Task 4 builds the credential chain inside the async dispatch method).
-        #[cfg(feature = "bedrock")]
-        if let Ok(model) = std::env::var("BEDROCK_MODEL_ID") {
-            let region = std::env::var("AWS_REGION")
-                .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
-                .ok();
-            if let Some(region) = region {
+            LlmProvider::Gemini => {
+                let api_key = std::env::var("GEMINI_API_KEY").ok()?;
+                let model = std::env::var("GEMINI_MODEL")
+                    .unwrap_or_else(|_| "gemini-2.5-flash".to_string());
+                let base_url = std::env::var("GEMINI_BASE_URL").unwrap_or_else(|_| {
+                    "https://generativelanguage.googleapis.com/v1beta".to_string()
+                });
+                if !validate_base_url("GEMINI_BASE_URL", &base_url) {
+                    return None;
+                }
+                tracing::info!("LLM client initialized: provider=Gemini model={}", model);
+                Some(Self {
+                    client,
+                    api_key,
+                    base_url,
+                    model,
+                    provider: LlmProvider::Gemini,
+                    #[cfg(feature = "bedrock")]
+                    region: String::new(),
+                    #[cfg(feature = "bedrock")]
+                    credentials: None,
+                })
+            }
+            // Vertex: `api_key` holds a short-lived OAuth access token
+            // (e.g. from `gcloud auth print-access-token`).
+            LlmProvider::Vertex => {
+                let api_key = std::env::var("VERTEX_ACCESS_TOKEN").ok()?;
+                let model = std::env::var("GEMINI_MODEL")
+                    .unwrap_or_else(|_| "gemini-2.5-flash".to_string());
+                let Some(base_url) = vertex_base_url() else {
+                    tracing::warn!(
+                        "VERTEX_ACCESS_TOKEN set but no VERTEX_PROJECT/GOOGLE_CLOUD_PROJECT — skipping Vertex"
+                    );
+                    return None;
+                };
+                if !validate_base_url("VERTEX_BASE_URL", &base_url) {
+                    return None;
+                }
+                tracing::info!("LLM client initialized: provider=Vertex model={}", model);
+                Some(Self {
+                    client,
+                    api_key,
+                    base_url,
+                    model,
+                    provider: LlmProvider::Vertex,
+                    #[cfg(feature = "bedrock")]
+                    region: String::new(),
+                    #[cfg(feature = "bedrock")]
+                    credentials: None,
+                })
+            }
+            // Bedrock: no API key — uses AWS credential chain instead.
+            #[cfg(feature = "bedrock")]
+            LlmProvider::Bedrock => {
+                // `from_env` is sync so credentials are resolved lazily at call
+                // time (Task 4 builds the credential chain inside the async
+                // dispatch method).
+                let model = std::env::var("BEDROCK_MODEL_ID").ok()?;
+                let region = std::env::var("AWS_REGION")
+                    .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+                    .ok();
+                let Some(region) = region else {
+                    tracing::warn!(
+                        "BEDROCK_MODEL_ID set but no AWS_REGION/AWS_DEFAULT_REGION — skipping Bedrock"
+                    );
+                    return None;
+                };
                 tracing::info!(
                     "LLM client initialized: provider=Bedrock model={model} region={region}"
                 );
-                return Some(Self {
+                Some(Self {
                     client,
                     api_key: String::new(),
                     base_url: String::new(),
@@ -202,16 +345,9 @@ impl LlmClient {
                     // `DefaultCredentialsChain::builder().build()` is async;
                     // stored as None here and resolved at call time in Task 4.
                     credentials: None,
-                });
-            } else {
-                tracing::warn!(
-                    "BEDROCK_MODEL_ID set but no AWS_REGION/AWS_DEFAULT_REGION — skipping Bedrock"
-                );
+                })
             }
         }
-
-        tracing::info!("No LLM API key found in environment, LLM invocation disabled");
-        None
     }
 
     /// Like `from_env`, but also accepts an optional `SecretStore` from which
@@ -222,12 +358,14 @@ impl LlmClient {
     /// * `OPENROUTER_API_KEY_REF` — secret-store key for the OpenRouter API key
     /// * `OPENAI_API_KEY_REF` — secret-store key for the OpenAI API key
     /// * `ANTHROPIC_API_KEY_REF` — secret-store key for the Anthropic API key
+    /// * `GEMINI_API_KEY_REF` — secret-store key for the Gemini API key
+    /// * `VERTEX_ACCESS_TOKEN_REF` — secret-store key for the Vertex AI access token
     ///
     /// When a `*_REF` env var is set and `store` is `Some`, the value is
     /// fetched from the store. On store miss / failure, it falls back to the
     /// regular `*_API_KEY` env var. When `*_REF` is unset, the regular env
     /// var is used directly. The provider-detection order matches `from_env`:
-    /// OpenRouter → OpenAI → Anthropic.
+    /// OpenRouter → OpenAI → Anthropic → Gemini → Vertex.
     ///
     /// The `*_BASE_URL` and `*_MODEL` env vars are unchanged from `from_env`.
     pub async fn from_env_or_secrets(
@@ -374,6 +512,86 @@ impl LlmClient {
             });
         }
 
+        // Gemini
+        let gemini_ref = std::env::var("GEMINI_API_KEY_REF").ok();
+        if let Some(api_key) = crate::secrets::resolve_secret_or_env(
+            "GEMINI_API_KEY",
+            gemini_ref.as_deref(),
+            store_ref,
+        )
+        .await
+        {
+            let model =
+                std::env::var("GEMINI_MODEL").unwrap_or_else(|_| "gemini-2.5-flash".to_string());
+            let base_url = std::env::var("GEMINI_BASE_URL")
+                .unwrap_or_else(|_| "https://generativelanguage.googleapis.com/v1beta".to_string());
+            if !validate_base_url("GEMINI_BASE_URL", &base_url) {
+                return None;
+            }
+            tracing::info!(
+                "LLM client initialized: provider=Gemini model={} (key source: {})",
+                model,
+                if gemini_ref.is_some() && store_ref.is_some() {
+                    "secret store (with env fallback)"
+                } else {
+                    "env"
+                }
+            );
+            return Some(Self {
+                client,
+                api_key,
+                base_url,
+                model,
+                provider: LlmProvider::Gemini,
+                #[cfg(feature = "bedrock")]
+                region: String::new(),
+                #[cfg(feature = "bedrock")]
+                credentials: None,
+            });
+        }
+
+        // Vertex AI
+        let vertex_ref = std::env::var("VERTEX_ACCESS_TOKEN_REF").ok();
+        if let Some(api_key) = crate::secrets::resolve_secret_or_env(
+            "VERTEX_ACCESS_TOKEN",
+            vertex_ref.as_deref(),
+            store_ref,
+        )
+        .await
+        {
+            let model =
+                std::env::var("GEMINI_MODEL").unwrap_or_else(|_| "gemini-2.5-flash".to_string());
+            if let Some(base_url) = vertex_base_url() {
+                if !validate_base_url("VERTEX_BASE_URL", &base_url) {
+                    return None;
+                }
+                tracing::info!(
+                    "LLM client initialized: provider=Vertex model={} (key source: {})",
+                    model,
+                    if vertex_ref.is_some() && store_ref.is_some() {
+                        "secret store (with env fallback)"
+                    } else {
+                        "env"
+                    }
+                );
+                return Some(Self {
+                    client,
+                    api_key,
+                    base_url,
+                    model,
+                    provider: LlmProvider::Vertex,
+                    #[cfg(feature = "bedrock")]
+                    region: String::new(),
+                    #[cfg(feature = "bedrock")]
+                    credentials: None,
+                });
+            } else {
+                tracing::warn!(
+                    "VERTEX_ACCESS_TOKEN set but no VERTEX_PROJECT/GOOGLE_CLOUD_PROJECT — skipping Vertex"
+                );
+            }
+        }
+
         // Bedrock: uses AWS credential chain, not a secret-store API key.
         // `from_env_or_secrets` is async so we can build the credentials chain here.
         #[cfg(feature = "bedrock")]
@@ -474,6 +692,20 @@ impl LlmClient {
                     .to_string())
             }
             LlmProvider::Anthropic => self.anthropic_completion(system, user).await,
+            LlmProvider::Gemini | LlmProvider::Vertex => {
+                let messages = vec![serde_json::json!({"role": "user", "content": user})];
+                let resp = self
+                    .gemini_completion_with_tools(system, &messages, &[])
+                    .await?;
+                Ok(resp["content"]
+                    .as_array()
+                    .and_then(|b| {
+                        b.iter()
+                            .find_map(|x| x.get("text").and_then(|t| t.as_str()))
+                    })
+                    .unwrap_or("")
+                    .to_string())
+            }
             _ => self.openai_completion(system, user).await,
         }
     }
@@ -483,8 +715,8 @@ impl LlmClient {
     /// Content blocks are `{"type":"text","text":"..."}` or
     /// `{"type":"tool_use","id":"...","name":"...","input":{...}}`
     ///
-    /// Works with Anthropic (native tool_use), OpenAI/OpenRouter (function calling
-    /// converted to the same normalized format).
+    /// Works with Anthropic (native tool_use), OpenAI/OpenRouter and
+    /// Gemini/Vertex (function calling converted to the same normalized format).
     pub async fn chat_with_tools(
         &self,
         system: &str,
@@ -501,6 +733,10 @@ impl LlmClient {
                 self.anthropic_completion_with_tools(system, messages, tools)
                     .await
             }
+            LlmProvider::Gemini | LlmProvider::Vertex => {
+                self.gemini_completion_with_tools(system, messages, tools)
+                    .await
+            }
             _ => {
                 self.openai_completion_with_tools(system, messages, tools)
                     .await
@@ -780,6 +1016,86 @@ impl LlmClient {
         Ok(resp_json)
     }
 
+    /// Gemini `generateContent` with function declarations, normalized to
+    /// Anthropic format. Serves both the Gemini API and Vertex AI.
+    async fn gemini_completion_with_tools(
+        &self,
+        system: &str,
+        messages: &[serde_json::Value],
+        tools: &[serde_json::Value],
+    ) -> Result<serde_json::Value, RuntimeError> {
+        use crate::http_input::gemini;
+        use crate::reasoning::inference::{InferenceOptions, ToolDefinition};
+
+        let options = InferenceOptions {
+            max_tokens: 4096,
+            temperature: 0.3,
+            tool_definitions: tools
+                .iter()
+                .map(|t| ToolDefinition {
+                    name: t
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or("unknown")
+                        .to_string(),
+                    description: t
+                        .get("description")
+                        .and_then(|d| d.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    parameters: t
+                        .get("input_schema")
+                        .cloned()
+                        .unwrap_or(serde_json::json!({"type": "object", "properties": {}})),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let body = gemini::build_generate_content_request(Some(system), messages, &options);
+        let (auth_name, auth_value) =
+            gemini::auth_header(matches!(self.provider, LlmProvider::Vertex), &self.api_key);
+
+        let response = self
+            .client
+            .post(gemini::generate_content_url(&self.base_url, &self.model))
+            .header(auth_name, auth_value)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| {
+                RuntimeError::Internal(format!("{} request failed: {}", self.provider, e))
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(RuntimeError::Internal(format!(
+                "{} API error ({}): {}",
+                self.provider, status, error_text
+            )));
+        }
+
+        let resp_json: serde_json::Value = response.json().await.map_err(|e| {
+            RuntimeError::Internal(format!("Failed to parse {} response: {}", self.provider, e))
+        })?;
+        let parsed = gemini::parse_generate_content_response(&resp_json, &self.model)
+            .map_err(|e| RuntimeError::Internal(format!("{} response: {}", self.provider, e)))?;
+
+        tracing::info!(
+            "LLM usage: provider={} model={} prompt_tokens={} completion_tokens={}",
+            self.provider,
+            parsed.model,
+            parsed.usage.prompt_tokens,
+            parsed.usage.completion_tokens,
+        );
+
+        Ok(gemini::to_unified_response(&parsed))
+    }
+
     /// OpenAI-compatible chat completion (works for OpenRouter and OpenAI)
     async fn openai_completion(&self, system: &str, user: &str) -> Result<String, RuntimeError> {
         let body = serde_json::json!({
@@ -933,6 +1249,9 @@ mod tests {
         assert_eq!(format!("{}", LlmProvider::OpenRouter), "OpenRouter");
         assert_eq!(format!("{}", LlmProvider::OpenAI), "OpenAI");
         assert_eq!(format!("{}", LlmProvider::Anthropic), "Anthropic");
+        assert_eq!(format!("{}", LlmProvider::Gemini), "Gemini");
+        assert_eq!(format!("{}", LlmProvider::Vertex), "Vertex");
+        assert!(matches!("Vertex".parse(), Ok(LlmProvider::Vertex)));
     }
 
     #[serial]
@@ -942,6 +1261,8 @@ mod tests {
         std::env::remove_var("OPENROUTER_API_KEY");
         std::env::remove_var("OPENAI_API_KEY");
         std::env::remove_var("ANTHROPIC_API_KEY");
+        std::env::remove_var("GEMINI_API_KEY");
+        std::env::remove_var("VERTEX_ACCESS_TOKEN");
         #[cfg(feature = "bedrock")]
         std::env::remove_var("BEDROCK_MODEL_ID");
 
@@ -959,7 +1280,13 @@ mod tests {
         );
         std::env::set_var("AWS_REGION", "us-east-1");
         // Ensure higher-priority providers don't preempt:
-        for k in ["OPENROUTER_API_KEY", "OPENAI_API_KEY", "ANTHROPIC_API_KEY"] {
+        for k in [
+            "OPENROUTER_API_KEY",
+            "OPENAI_API_KEY",
+            "ANTHROPIC_API_KEY",
+            "GEMINI_API_KEY",
+            "VERTEX_ACCESS_TOKEN",
+        ] {
             std::env::remove_var(k);
         }
         let client = LlmClient::from_env().expect("bedrock client");
@@ -969,6 +1296,40 @@ mod tests {
         std::env::remove_var("AWS_REGION");
     }
 
+    #[serial]
+    #[test]
+    fn from_env_selects_vertex_with_project_endpoint() {
+        for k in [
+            "OPENROUTER_API_KEY",
+            "OPENAI_API_KEY",
+            "ANTHROPIC_API_KEY",
+            "GEMINI_API_KEY",
+            "VERTEX_BASE_URL",
+            "VERTEX_LOCATION",
+        ] {
+            std::env::remove_var(k);
+        }
+        std::env::set_var("VERTEX_ACCESS_TOKEN", "ya29.test");
+        std::env::set_var("VERTEX_PROJECT", "acme-prod");
+        std::env::set_var("GEMINI_MODEL", "gemini-2.5-pro");
+
+        let client = LlmClient::from_env().expect("vertex client");
+        assert!(matches!(client.provider(), LlmProvider::Vertex));
+        assert_eq!(client.model(), "gemini-2.5-pro");
+        assert_eq!(
+            client.base_url,
+            "https://us-central1-aiplatform.googleapis.com/v1/projects/acme-prod/locations/us-central1/publishers/google"
+        );
+
+        // Without a project there is no endpoint to call.
+        std::env::remove_var("VERTEX_PROJECT");
+        std::env::remove_var("GOOGLE_CLOUD_PROJECT");
+        assert!(LlmClient::from_env_for(LlmProvider::Vertex).is_none());
+
+        std::env::remove_var("VERTEX_ACCESS_TOKEN");
+        std::env::remove_var("GEMINI_MODEL");
+    }
+
     #[test]
     fn test_tools_to_openai_functions() {
         let tools = vec![serde_json::json!({
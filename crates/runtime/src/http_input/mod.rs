@@ -13,16 +13,26 @@ pub mod llm_client;
 #[cfg(feature = "http-input")]
 pub mod server;
 
+#[cfg(feature = "http-input")]
+pub mod webhook_routes;
+
 #[cfg(feature = "http-input")]
 pub mod webhook_verify;
 
 #[cfg(feature = "http-input")]
 pub use config::{
-    AgentRoutingRule, HttpInputConfig, ResponseControlConfig, RouteMatch, WebhookVerifyConfig,
+    AgentRoutingRule, HttpInputConfig, ResponseControlConfig, RouteMatch, WebhookFilterConfig,
+    WebhookRouteConfig, WebhookVerifyConfig,
 };
 
 #[cfg(feature = "http-input")]
 pub use server::{start_http_input, HttpInputServer};
 
+#[cfg(feature = "http-input")]
+pub use webhook_routes::{WebhookRouteRegistry, WebhookRouteStatus};
+
 #[cfg(feature = "bedrock")]
 pub(crate) mod bedrock;
+
+#[cfg(feature = "http-input")]
+pub(crate) mod gemini;
@@ -3,6 +3,8 @@
 //! This module provides the HTTP input server that receives webhook/HTTP requests
 //! and routes them to appropriate Symbiont agents based on configuration rules.
 
+#[cfg(feature = "http-input")]
+use std::collections::HashMap;
 #[cfg(feature = "http-input")]
 use std::path::Path;
 #[cfg(feature = "http-input")]
@@ -14,22 +16,28 @@ use axum::{
     http::{HeaderMap, StatusCode, Uri},
     middleware,
     response::{IntoResponse, Response},
-    routing::post,
-    Json, Router,
+    routing::{post, MethodRouter},
+    Extension, Json, Router,
 };
 #[cfg(feature = "http-input")]
 use serde_json::Value;
 #[cfg(feature = "http-input")]
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::{mpsc, RwLock, Semaphore};
 #[cfg(feature = "http-input")]
 use tower_http::cors::CorsLayer;
 
 #[cfg(feature = "http-input")]
-use super::config::{HttpInputConfig, ResponseControlConfig, RouteMatch};
+use super::config::{HttpInputConfig, ResponseControlConfig, RouteMatch, WebhookFilterConfig};
+#[cfg(feature = "http-input")]
+use super::webhook_routes::{
+    filter_matches, WebhookOutcome, WebhookRouteRegistry, WebhookRouteStatus, ROUTE_STATE_DISABLED,
+};
+#[cfg(feature = "http-input")]
+use super::webhook_verify::{SignatureVerifier, WebhookProvider};
 #[cfg(feature = "http-input")]
 use crate::reasoning::circuit_breaker::CircuitBreakerRegistry;
 #[cfg(feature = "http-input")]
-use crate::reasoning::context_manager::DefaultContextManager;
+use crate::reasoning::context_manager::{ContextManager, DefaultContextManager};
 #[cfg(feature = "http-input")]
 use crate::reasoning::conversation::{Conversation, ConversationMessage, MessageRole};
 #[cfg(feature = "http-input")]
@@ -37,14 +45,24 @@ use crate::reasoning::executor::ActionExecutor;
 #[cfg(feature = "http-input")]
 use crate::reasoning::inference::InferenceProvider;
 #[cfg(feature = "http-input")]
-use crate::reasoning::loop_types::{BufferedJournal, JournalWriter, LoopConfig};
+use crate::reasoning::journal::{DurableJournal, JournalStorage, RunId};
+#[cfg(feature = "http-input")]
+use crate::reasoning::knowledge_bridge::{KnowledgeBridge, KnowledgeConfig};
+#[cfg(feature = "http-input")]
+use crate::reasoning::loop_types::{BufferedJournal, JournalWriter, LoopConfig, LoopResult};
 #[cfg(feature = "http-input")]
 use crate::reasoning::policy_bridge::{DefaultPolicyGate, ReasoningPolicyGate};
 #[cfg(feature = "http-input")]
 use crate::reasoning::reasoning_loop::ReasoningLoopRunner;
 #[cfg(feature = "http-input")]
+use crate::reasoning::sandbox_executor::SandboxSessionExecutor;
+#[cfg(feature = "http-input")]
+use crate::reasoning::spend::{SpendTags, SpendTracker};
+#[cfg(feature = "http-input")]
 use crate::reasoning::tool_executor_builder::build_tool_executor;
 #[cfg(feature = "http-input")]
+use crate::sandbox::{KernelConfig, SandboxSessionManager};
+#[cfg(feature = "http-input")]
 use crate::secrets::{new_secret_store, SecretStore, SecretsConfig};
 #[cfg(feature = "http-input")]
 use crate::text_util::truncate_utf8;
@@ -60,8 +78,41 @@ pub struct HttpInputServer {
     executor: Option<Arc<dyn ActionExecutor>>,
     inference_provider: Option<Arc<dyn InferenceProvider>>,
     policy_gate: Option<Arc<dyn ReasoningPolicyGate>>,
+    spend: Option<Arc<SpendTracker>>,
+    journal_storage: Option<Arc<dyn JournalStorage>>,
+    context_manager: Option<Arc<dyn ContextManager>>,
+    resumed_run_reports: Option<mpsc::UnboundedSender<Value>>,
+    sandbox_sessions: Option<Arc<SandboxSessionManager>>,
+    sandbox_kernel: Option<KernelConfig>,
     concurrency_limiter: Arc<Semaphore>,
     resolved_auth_header: Arc<RwLock<Option<String>>>,
+    route_registry: Arc<WebhookRouteRegistry>,
+}
+
+/// Where governed reasoning runs are journaled.
+#[cfg(feature = "http-input")]
+#[derive(Clone)]
+enum RunJournal {
+    /// In-memory ring buffer shared by every request; lost on restart.
+    Buffered(Arc<dyn JournalWriter>),
+    /// Durable storage. Each run is journaled under its own `RunId`, so
+    /// concurrent requests to one agent never share a sequence counter.
+    Durable(Arc<dyn JournalStorage>),
+}
+
+#[cfg(feature = "http-input")]
+impl RunJournal {
+    /// The writer for one run, plus the durable journal to compact once
+    /// the run has ended.
+    fn for_run(&self) -> (Arc<dyn JournalWriter>, Option<Arc<DurableJournal>>) {
+        match self {
+            RunJournal::Buffered(journal) => (journal.clone(), None),
+            RunJournal::Durable(storage) => {
+                let journal = Arc::new(DurableJournal::for_run(storage.clone(), RunId::new()));
+                (journal.clone(), Some(journal))
+            }
+        }
+    }
 }
 
 #[cfg(feature = "http-input")]
@@ -77,17 +128,32 @@ impl HttpInputServer {
             executor: None,
             inference_provider: None,
             policy_gate: None,
+            spend: None,
+            journal_storage: None,
+            context_manager: None,
+            resumed_run_reports: None,
+            sandbox_sessions: None,
+            sandbox_kernel: None,
             concurrency_limiter,
             resolved_auth_header: Arc::new(RwLock::new(None)),
+            route_registry: Arc::new(WebhookRouteRegistry::new()),
         }
     }
 
-    /// Set the runtime for agent invocation
+    /// Set the runtime for agent invocation. Webhook route status is
+    /// reported through the runtime's registry from then on, so it shows up
+    /// in `/api/v1/status`.
     pub fn with_runtime(mut self, runtime: Arc<crate::AgentRuntime>) -> Self {
+        self.route_registry = runtime.webhook_routes();
         self.runtime = Some(runtime);
         self
     }
 
+    /// Registry holding the status of the dedicated webhook routes.
+    pub fn route_registry(&self) -> Arc<WebhookRouteRegistry> {
+        self.route_registry.clone()
+    }
+
     /// Set the tool executor used to dispatch model-proposed tool calls.
     /// If unset, `start()` defaults to `build_tool_executor(Path::new("tools"))`:
     /// `ToolCladExecutor` when `tools/*.clad.toml` manifests are present,
@@ -116,6 +182,49 @@ impl HttpInputServer {
         self
     }
 
+    /// Price and budget every governed reasoning loop this server runs.
+    pub fn with_spend_tracker(mut self, tracker: Arc<SpendTracker>) -> Self {
+        self.spend = Some(tracker);
+        self
+    }
+
+    /// Journal reasoning runs to durable storage. Runs a previous process
+    /// left unfinished are resumed when the server starts, but only if
+    /// `resume_interrupted_runs` is set in the config.
+    pub fn with_journal_storage(mut self, storage: Arc<dyn JournalStorage>) -> Self {
+        self.journal_storage = Some(storage);
+        self
+    }
+
+    /// Manage each reasoning run's context window with `manager` instead of
+    /// `DefaultContextManager`.
+    pub fn with_context_manager(mut self, manager: Arc<dyn ContextManager>) -> Self {
+        self.context_manager = Some(manager);
+        self
+    }
+
+    /// Send the response body of every resumed run to `reports`. The HTTP
+    /// callers that started those runs are gone, so without a sink the
+    /// results are only logged.
+    pub fn with_resumed_run_reports(mut self, reports: mpsc::UnboundedSender<Value>) -> Self {
+        self.resumed_run_reports = Some(reports);
+        self
+    }
+
+    /// Give every reasoning run its own sandbox session from `manager` and
+    /// offer the model the `sandbox_exec` tool to run code in it.
+    pub fn with_sandbox_sessions(mut self, manager: Arc<SandboxSessionManager>) -> Self {
+        self.sandbox_sessions = Some(manager);
+        self
+    }
+
+    /// Also offer the `run_cell` tool, backed by a kernel started in the
+    /// run's sandbox session on first use. Needs `with_sandbox_sessions`.
+    pub fn with_sandbox_kernel(mut self, kernel: KernelConfig) -> Self {
+        self.sandbox_kernel = Some(kernel);
+        self
+    }
+
     /// Set the secret store for auth header resolution
     pub fn with_secret_store(mut self, secret_store: Arc<dyn SecretStore + Send + Sync>) -> Self {
         self.secret_store = Some(secret_store);
@@ -213,6 +322,16 @@ impl HttpInputServer {
             .executor
             .clone()
             .unwrap_or_else(|| build_tool_executor(Path::new("tools")));
+        let executor: Arc<dyn ActionExecutor> = match &self.sandbox_sessions {
+            Some(sessions) => {
+                let mut executor = SandboxSessionExecutor::new(executor, sessions.clone());
+                if let Some(kernel) = &self.sandbox_kernel {
+                    executor = executor.with_kernel(kernel.clone());
+                }
+                Arc::new(executor)
+            }
+            None => executor,
+        };
         let executor_tool_count = executor.tool_definitions().len();
         if executor_tool_count > 0 {
             tracing::info!(
@@ -234,7 +353,10 @@ impl HttpInputServer {
         // this server instance handles, so a tool that keeps failing trips
         // its breaker cluster-wide instead of resetting on the next request.
         let circuit_breakers = Arc::new(CircuitBreakerRegistry::default());
-        let journal: Arc<dyn JournalWriter> = Arc::new(BufferedJournal::new(1000));
+        let journal = match &self.journal_storage {
+            Some(storage) => RunJournal::Durable(storage.clone()),
+            None => RunJournal::Buffered(Arc::new(BufferedJournal::new(1000))),
+        };
 
         // Scan agents/ directory for DSL files
         let agent_dsl_sources = scan_agent_dsl_files();
@@ -248,12 +370,7 @@ impl HttpInputServer {
         // Resolve webhook signature verifier if configured
         let webhook_verifier: Option<Arc<dyn super::webhook_verify::SignatureVerifier>> =
             if let Some(ref verify_config) = config.webhook_verify {
-                let provider = match verify_config.provider.to_lowercase().as_str() {
-                    "github" => super::webhook_verify::WebhookProvider::GitHub,
-                    "stripe" => super::webhook_verify::WebhookProvider::Stripe,
-                    "slack" => super::webhook_verify::WebhookProvider::Slack,
-                    _ => super::webhook_verify::WebhookProvider::Custom,
-                };
+                let provider = WebhookProvider::from_name(&verify_config.provider);
                 let secret_value = if let Some(ref store) = self.secret_store {
                     match resolve_secret_reference(store.as_ref(), &verify_config.secret).await {
                         Ok(resolved) => resolved,
@@ -273,6 +390,12 @@ impl HttpInputServer {
                 None
             };
 
+        // Resolve the dedicated webhook routes (usually DSL `webhook` blocks)
+        let webhook_routes = self.resolve_webhook_routes(&config).await?;
+
+        // Open the per-agent memory stores (usually DSL `memory` blocks)
+        let knowledge_bridges = build_knowledge_bridges(&config.agent_memory).await;
+
         // Create shared server state
         let server_state = ServerState {
             config: self.config.clone(),
@@ -285,10 +408,39 @@ impl HttpInputServer {
             policy_gate,
             circuit_breakers,
             journal,
+            context_manager: self
+                .context_manager
+                .clone()
+                .unwrap_or_else(|| Arc::new(DefaultContextManager::default())),
+            knowledge_bridges: Arc::new(knowledge_bridges),
+            spend: self.spend.clone(),
+            sandbox_sessions: self.sandbox_sessions.clone(),
             webhook_verifier,
+            route_registry: self.route_registry.clone(),
             jwt_decoding_key,
         };
 
+        // List leftover runs before serving, so a run started by a new
+        // request is never mistaken for an interrupted one.
+        let resume_storage = self
+            .journal_storage
+            .clone()
+            .filter(|_| config.resume_interrupted_runs);
+        if let Some(storage) = resume_storage {
+            match storage.runs().await {
+                Ok(run_keys) if !run_keys.is_empty() => {
+                    tokio::spawn(resume_interrupted_runs(
+                        storage,
+                        run_keys,
+                        server_state.clone(),
+                        self.resumed_run_reports.clone(),
+                    ));
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Cannot list journaled reasoning runs: {}", e),
+            }
+        }
+
         // Build the router
         let mut app = Router::new();
 
@@ -300,15 +452,32 @@ impl HttpInputServer {
         let wildcard_path = format!("{}/*rest", path.trim_end_matches('/'));
         app = app.route(&wildcard_path, post(webhook_handler));
 
+        // Webhook routes without a signing secret rely on the server-wide
+        // authentication, so they are added before the auth middleware.
+        for route in webhook_routes.iter().filter(|r| r.verifier.is_none()) {
+            app = app.route(&route.path, webhook_route(route.clone()));
+        }
+
         // Add middleware
         app = app.layer(middleware::from_fn_with_state(
             server_state.clone(),
             auth_middleware,
         ));
 
+        // Signed webhook routes are authenticated by their provider
+        // signature: GitHub, Stripe, and Slack cannot send a bearer token.
+        for route in webhook_routes.iter().filter(|r| r.verifier.is_some()) {
+            app = app.route(&route.path, webhook_route(route.clone()));
+        }
+
         // Add body size limit
         app = app.layer(DefaultBodyLimit::max(config.max_body_bytes));
 
+        // Open a server span per request, continuing the caller's trace.
+        app = app.layer(middleware::from_fn(
+            crate::telemetry::trace_context_middleware,
+        ));
+
         // Add CORS if origins are configured. Wildcard origin is rejected
         // at the top of `start` (see SECURITY_AUDIT.md M1), so by the time
         // we get here the list is guaranteed to be an explicit allowlist.
@@ -344,6 +513,126 @@ impl HttpInputServer {
         Ok(())
     }
 
+    /// Validate `config.webhook_routes`, resolve their secrets, and register
+    /// each one with the route registry.
+    ///
+    /// Path conflicts are configuration errors. A route whose secret
+    /// reference cannot be resolved is recorded as disabled and not served,
+    /// rather than falling back to an unverified endpoint.
+    async fn resolve_webhook_routes(
+        &self,
+        config: &HttpInputConfig,
+    ) -> Result<Vec<Arc<WebhookRoute>>, RuntimeError> {
+        self.route_registry.clear();
+
+        let default_path = config.path.trim_end_matches('/');
+        let mut seen_paths = std::collections::HashSet::new();
+        let mut routes = Vec::new();
+
+        for route_config in &config.webhook_routes {
+            let route_path = route_config.path.trim_end_matches('/');
+            if !route_config.path.starts_with('/') || route_path == default_path {
+                return Err(RuntimeError::Configuration(
+                    crate::types::ConfigError::Invalid(format!(
+                        "Webhook '{}' has invalid path '{}': it must start with '/' and \
+                         differ from the default input path '{}'",
+                        route_config.name, route_config.path, config.path
+                    )),
+                ));
+            }
+            if let Some(c) = route_config
+                .path
+                .chars()
+                .find(|c| matches!(c, ':' | '*' | '{' | '}'))
+            {
+                // The router reads these as parameters or wildcards, which
+                // would overlap other routes and make route registration panic.
+                return Err(RuntimeError::Configuration(
+                    crate::types::ConfigError::Invalid(format!(
+                        "Webhook '{}' has invalid path '{}': '{}' is not allowed in a \
+                         webhook path",
+                        route_config.name, route_config.path, c
+                    )),
+                ));
+            }
+            if !seen_paths.insert(route_path.to_string()) {
+                return Err(RuntimeError::Configuration(
+                    crate::types::ConfigError::Invalid(format!(
+                        "Webhook '{}' reuses path '{}', which is already registered",
+                        route_config.name, route_config.path
+                    )),
+                ));
+            }
+
+            let mut status = WebhookRouteStatus::new(
+                &route_config.name,
+                &route_config.path,
+                &route_config.provider,
+                route_config.agent.to_string(),
+            );
+
+            let secret = match (&route_config.secret, &self.secret_store) {
+                (None, _) => None,
+                (Some(secret), Some(store)) => {
+                    match resolve_secret_reference(store.as_ref(), secret).await {
+                        Ok(resolved) => Some(resolved),
+                        Err(e) => {
+                            status.error = Some(format!("secret not resolved: {}", e));
+                            None
+                        }
+                    }
+                }
+                (Some(secret), None) if is_secret_reference(secret) => {
+                    status.error = Some(format!(
+                        "secret reference '{}' requires a secret store",
+                        secret
+                    ));
+                    None
+                }
+                (Some(secret), None) => Some(secret.clone()),
+            };
+
+            if let Some(ref error) = status.error {
+                tracing::warn!(
+                    "Webhook '{}' on {} disabled: {}",
+                    route_config.name,
+                    route_config.path,
+                    error
+                );
+                status.state = ROUTE_STATE_DISABLED.to_string();
+                self.route_registry.register(status);
+                continue;
+            }
+
+            let verifier: Option<Arc<dyn SignatureVerifier>> = secret.map(|secret| {
+                Arc::from(
+                    WebhookProvider::from_name(&route_config.provider).verifier(secret.as_bytes()),
+                )
+            });
+            status.signature_required = verifier.is_some();
+
+            tracing::info!(
+                "Webhook '{}' registered on {} (provider={}, agent={}, signed={})",
+                route_config.name,
+                route_config.path,
+                route_config.provider,
+                route_config.agent,
+                verifier.is_some()
+            );
+            let index = self.route_registry.register(status);
+            routes.push(Arc::new(WebhookRoute {
+                index,
+                name: route_config.name.clone(),
+                path: route_config.path.clone(),
+                agent: route_config.agent,
+                verifier,
+                filter: route_config.filter.clone(),
+            }));
+        }
+
+        Ok(routes)
+    }
+
     /// Stop the HTTP input server gracefully
     pub async fn stop(&self) -> Result<(), RuntimeError> {
         tracing::info!("HTTP Input server stopping");
@@ -382,6 +671,47 @@ fn inference_provider_from_env() -> Option<Arc<dyn InferenceProvider>> {
     None
 }
 
+/// Open each configured agent memory store and wrap it in a knowledge
+/// bridge. A store that cannot be opened is logged and skipped, so that
+/// agent runs without memory recall instead of failing server startup.
+#[cfg(feature = "http-input")]
+async fn build_knowledge_bridges(
+    memories: &[crate::context::AgentMemoryConfig],
+) -> HashMap<AgentId, Arc<KnowledgeBridge>> {
+    let mut bridges = HashMap::new();
+    for memory in memories {
+        if bridges.contains_key(&memory.agent) {
+            tracing::warn!(
+                "Memory '{}' ignored: agent {} already has a memory store",
+                memory.name,
+                memory.agent
+            );
+            continue;
+        }
+        match memory.build_context_manager().await {
+            Ok(manager) => {
+                tracing::info!(
+                    "Memory '{}' for agent {} opened at {}",
+                    memory.name,
+                    memory.agent,
+                    memory.path.display()
+                );
+                let bridge = KnowledgeBridge::new(Arc::new(manager), KnowledgeConfig::default());
+                bridges.insert(memory.agent, Arc::new(bridge));
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Memory '{}' for agent {} disabled: {}",
+                    memory.name,
+                    memory.agent,
+                    e
+                );
+            }
+        }
+    }
+    bridges
+}
+
 /// Shared state for the HTTP server
 #[cfg(feature = "http-input")]
 #[derive(Clone)]
@@ -406,14 +736,39 @@ struct ServerState {
     /// Circuit breaker registry shared across every request this server
     /// instance handles.
     circuit_breakers: Arc<CircuitBreakerRegistry>,
-    /// Journal of governed reasoning-loop events, shared across requests.
-    journal: Arc<dyn JournalWriter>,
+    /// Journal of governed reasoning-loop events.
+    journal: RunJournal,
+    /// Context manager shared by every reasoning run.
+    context_manager: Arc<dyn ContextManager>,
+    /// Knowledge bridges for agents with a configured memory store, keyed
+    /// by agent. Agents without an entry run without memory recall.
+    knowledge_bridges: Arc<HashMap<AgentId, Arc<KnowledgeBridge>>>,
+    /// Spend tracker billing each reasoning loop to its agent, if configured.
+    spend: Option<Arc<SpendTracker>>,
+    /// Sandbox sessions bound to each reasoning run, if configured.
+    sandbox_sessions: Option<Arc<SandboxSessionManager>>,
     /// Optional webhook signature verifier
     webhook_verifier: Option<Arc<dyn super::webhook_verify::SignatureVerifier>>,
+    /// Status registry for the dedicated webhook routes.
+    route_registry: Arc<WebhookRouteRegistry>,
     /// Optional JWT EdDSA verifying key for Bearer token validation
     jwt_decoding_key: Option<Arc<jsonwebtoken::DecodingKey>>,
 }
 
+/// A dedicated webhook endpoint resolved from a `WebhookRouteConfig`.
+#[cfg(feature = "http-input")]
+struct WebhookRoute {
+    /// Index of this route in the `WebhookRouteRegistry`.
+    index: usize,
+    name: String,
+    path: String,
+    agent: AgentId,
+    /// Provider signature verifier; `None` when the route has no secret and
+    /// is protected by the server-wide authentication instead.
+    verifier: Option<Arc<dyn SignatureVerifier>>,
+    filter: Option<WebhookFilterConfig>,
+}
+
 /// JWT claims structure for EdDSA token validation
 #[cfg(feature = "http-input")]
 #[derive(serde::Deserialize)]
@@ -421,8 +776,22 @@ struct JwtClaims {
     /// Expiration time (validated automatically by jsonwebtoken)
     #[allow(dead_code)]
     exp: u64,
+    /// Subject, used to attribute spend when the token has no `kid`
+    #[serde(default)]
+    sub: Option<String>,
 }
 
+/// Key id billed for requests that authenticate with the static
+/// `auth_header`.
+#[cfg(feature = "http-input")]
+const STATIC_AUTH_KEY_ID: &str = "http_input";
+
+/// Credential a request authenticated with, recorded by `auth_middleware`
+/// and billed as the run's API key.
+#[cfg(feature = "http-input")]
+#[derive(Debug, Clone)]
+struct CallerKey(String);
+
 /// Authentication middleware
 ///
 /// Auth flow:
@@ -434,7 +803,7 @@ struct JwtClaims {
 async fn auth_middleware(
     State(state): State<ServerState>,
     headers: HeaderMap,
-    req: axum::extract::Request,
+    mut req: axum::extract::Request,
     next: axum::middleware::Next,
 ) -> Result<Response, StatusCode> {
     let resolved_auth = state.resolved_auth_header.read().await;
@@ -462,6 +831,8 @@ async fn auth_middleware(
             if subtle::ConstantTimeEq::ct_eq(provided_auth.as_bytes(), expected_auth.as_bytes())
                 .into()
             {
+                req.extensions_mut()
+                    .insert(CallerKey(STATIC_AUTH_KEY_ID.to_string()));
                 return Ok(next.run(req).await);
             }
         }
@@ -511,7 +882,10 @@ async fn auth_middleware(
                 validation.leeway = 5;
 
                 match jsonwebtoken::decode::<JwtClaims>(token, decoding_key, &validation) {
-                    Ok(_token_data) => {
+                    Ok(token_data) => {
+                        if let Some(key) = header.kid.or(token_data.claims.sub) {
+                            req.extensions_mut().insert(CallerKey(key));
+                        }
                         return Ok(next.run(req).await);
                     }
                     Err(e) => {
@@ -535,6 +909,7 @@ async fn auth_middleware(
 #[cfg(feature = "http-input")]
 async fn webhook_handler(
     State(state): State<ServerState>,
+    caller: Option<Extension<CallerKey>>,
     uri: Uri,
     headers: HeaderMap,
     body: axum::body::Bytes,
@@ -547,17 +922,7 @@ async fn webhook_handler(
 
     // Verify webhook signature if configured
     if let Some(ref verifier) = state.webhook_verifier {
-        let header_pairs: Vec<(String, String)> = headers
-            .iter()
-            .filter_map(|(name, value)| {
-                value
-                    .to_str()
-                    .ok()
-                    .map(|v| (name.to_string(), v.to_string()))
-            })
-            .collect();
-
-        if let Err(e) = verifier.verify(&header_pairs, &body).await {
+        if let Err(e) = verifier.verify(&header_pairs(&headers), &body).await {
             tracing::warn!("Webhook signature verification failed: {}", e);
             return Err(StatusCode::UNAUTHORIZED);
         }
@@ -581,6 +946,10 @@ async fn webhook_handler(
 
     // Route to appropriate agent
     let agent_id = route_request(&config, uri.path(), &payload, &headers).await;
+    let spend_tags = SpendTags {
+        api_key: caller.map(|Extension(CallerKey(key))| key),
+        ..Default::default()
+    };
 
     // Invoke agent
     match invoke_agent(
@@ -593,6 +962,11 @@ async fn webhook_handler(
         state.policy_gate.clone(),
         state.circuit_breakers.clone(),
         state.journal.clone(),
+        state.context_manager.clone(),
+        state.knowledge_bridges.get(&agent_id).cloned(),
+        state.spend.clone(),
+        spend_tags,
+        state.sandbox_sessions.clone(),
     )
     .await
     {
@@ -608,6 +982,149 @@ async fn webhook_handler(
     }
 }
 
+/// Method router serving one dedicated webhook route.
+#[cfg(feature = "http-input")]
+fn webhook_route(route: Arc<WebhookRoute>) -> MethodRouter<ServerState> {
+    post(
+        move |State(state): State<ServerState>,
+              caller: Option<Extension<CallerKey>>,
+              headers: HeaderMap,
+              body: axum::body::Bytes| {
+            let route = route.clone();
+            let caller = caller.map(|Extension(CallerKey(key))| key);
+            async move { webhook_route_handler(state, &route, caller, headers, body).await }
+        },
+    )
+}
+
+/// Handler for a dedicated webhook route: verify the provider signature,
+/// apply the route filter, then invoke the route's agent.
+///
+/// Requests that fail the filter are acknowledged with `200 OK` and
+/// `"status": "filtered"` so the sender does not retry them. Runs of a
+/// signed route are billed to `webhook:<name>`; other routes are billed to
+/// the credential the request authenticated with.
+#[cfg(feature = "http-input")]
+async fn webhook_route_handler(
+    state: ServerState,
+    route: &WebhookRoute,
+    caller: Option<String>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> Result<Response, StatusCode> {
+    let _permit = state.concurrency_limiter.try_acquire().map_err(|_| {
+        tracing::warn!("Concurrency limit exceeded");
+        StatusCode::TOO_MANY_REQUESTS
+    })?;
+    let registry = state.route_registry.clone();
+
+    if let Some(ref verifier) = route.verifier {
+        if let Err(e) = verifier.verify(&header_pairs(&headers), &body).await {
+            tracing::warn!(
+                "Webhook '{}' signature verification failed: {}",
+                route.name,
+                e
+            );
+            registry.record(route.index, WebhookOutcome::Rejected);
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    let payload: Value = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!("Webhook '{}' received invalid JSON body: {}", route.name, e);
+            registry.record(route.index, WebhookOutcome::Rejected);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    if let Some(ref filter) = route.filter {
+        if !filter_matches(filter, &payload) {
+            tracing::debug!(
+                "Webhook '{}' payload did not match filter on {}",
+                route.name,
+                filter.json_path
+            );
+            registry.record(route.index, WebhookOutcome::Filtered);
+            return Ok((
+                StatusCode::OK,
+                Json(serde_json::json!({
+                    "status": "filtered",
+                    "webhook": route.name,
+                })),
+            )
+                .into_response());
+        }
+    }
+
+    let config = state.config.read().await;
+    if config.audit_enabled {
+        tracing::info!(
+            "HTTP Input: webhook '{}' received request with {} headers, routed to agent {}",
+            route.name,
+            headers.len(),
+            route.agent
+        );
+    }
+
+    let spend_tags = SpendTags {
+        api_key: match route.verifier {
+            Some(_) => Some(format!("webhook:{}", route.name)),
+            None => caller,
+        },
+        ..Default::default()
+    };
+    let response_config = config.response_control.as_ref();
+    match invoke_agent(
+        state.runtime.as_deref(),
+        route.agent,
+        payload,
+        state.inference_provider.clone(),
+        &state.agent_dsl_sources,
+        state.executor.clone(),
+        state.policy_gate.clone(),
+        state.circuit_breakers.clone(),
+        state.journal.clone(),
+        state.context_manager.clone(),
+        state.knowledge_bridges.get(&route.agent).cloned(),
+        state.spend.clone(),
+        spend_tags,
+        state.sandbox_sessions.clone(),
+    )
+    .await
+    {
+        Ok(result) => {
+            registry.record(route.index, WebhookOutcome::Accepted);
+            format_success_response(result, response_config)
+        }
+        Err(e) => {
+            tracing::error!(
+                "Agent invocation for webhook '{}' failed: {:?}",
+                route.name,
+                e
+            );
+            registry.record(route.index, WebhookOutcome::Failed);
+            format_error_response(e, response_config)
+        }
+    }
+}
+
+/// Request headers as `(name, value)` pairs for signature verification.
+/// Headers whose values are not valid UTF-8 are skipped.
+#[cfg(feature = "http-input")]
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|v| (name.to_string(), v.to_string()))
+        })
+        .collect()
+}
+
 /// Route incoming request to appropriate agent
 #[cfg(feature = "http-input")]
 async fn route_request(
@@ -654,6 +1171,82 @@ async fn matches_route_condition(
     }
 }
 
+/// Resume governed runs that a previous process left unfinished in the
+/// durable journal. The HTTP callers that started them are gone, so each
+/// result is sent to `reports` in the same shape a completed request gets
+/// (or logged when there is no sink); each run's journal is compacted once
+/// it has ended.
+#[cfg(feature = "http-input")]
+async fn resume_interrupted_runs(
+    storage: Arc<dyn JournalStorage>,
+    run_keys: Vec<RunId>,
+    state: ServerState,
+    reports: Option<mpsc::UnboundedSender<Value>>,
+) {
+    let Some(provider) = state.inference_provider.clone() else {
+        tracing::warn!(
+            "{} interrupted reasoning run(s) in the journal, but no inference provider is configured to resume them",
+            run_keys.len()
+        );
+        return;
+    };
+
+    for key in run_keys {
+        let start = std::time::Instant::now();
+        let journal = Arc::new(DurableJournal::for_run(storage.clone(), key));
+        // The run's `Started` entry names the agent it belongs to.
+        let agent_id = match journal.replay().await {
+            Ok(entries) => entries.iter().rev().find_map(|e| match e.event {
+                crate::reasoning::loop_types::LoopEvent::Started { agent_id, .. } => Some(agent_id),
+                _ => None,
+            }),
+            Err(e) => {
+                tracing::warn!("Cannot read journaled run {}: {}", key, e);
+                continue;
+            }
+        };
+        let runner = ReasoningLoopRunner {
+            provider: provider.clone(),
+            policy_gate: state.policy_gate.clone(),
+            executor: state.executor.clone(),
+            context_manager: state.context_manager.clone(),
+            circuit_breakers: state.circuit_breakers.clone(),
+            journal: journal.clone(),
+            knowledge_bridge: agent_id.and_then(|id| state.knowledge_bridges.get(&id).cloned()),
+            sandbox_sessions: state.sandbox_sessions.clone(),
+            spend: state.spend.clone(),
+            delegation: None,
+        };
+        match runner.resume(journal.clone()).await {
+            Ok(Some(result)) => {
+                tracing::info!(
+                    "Resumed reasoning run {} for agent {:?}: iterations={} termination={:?}",
+                    key,
+                    agent_id,
+                    result.iterations,
+                    result.termination_reason
+                );
+                let mut body = completed_run_body(agent_id, provider.as_ref(), &result, start);
+                body["status"] = Value::from("resumed");
+                body["run_id"] = Value::from(key.to_string());
+                match &reports {
+                    Some(reports) => {
+                        if reports.send(body).is_err() {
+                            tracing::warn!("Resumed run report for {} was dropped", key);
+                        }
+                    }
+                    None => tracing::info!("Resumed reasoning run {} finished: {}", key, body),
+                }
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Cannot resume reasoning run {}: {}", key, e),
+        }
+        if let Err(e) = journal.compact().await {
+            tracing::warn!("Failed to compact journaled run {}: {}", key, e);
+        }
+    }
+}
+
 /// Invoke an agent with the provided input data, using runtime execution or a
 /// governed reasoning loop.
 ///
@@ -662,7 +1255,8 @@ async fn matches_route_condition(
 /// every model-proposed tool call passes through `policy_gate` before
 /// `executor` ever sees it, tool dispatch goes through the same
 /// circuit-breaker-aware `ActionExecutor` other entry points use, and the run
-/// is recorded to `journal`. There is no path here that acts on model output
+/// is recorded to `journal` (compacted once the run ends, when durable).
+/// There is no path here that acts on model output
 /// without going through the gate.
 #[cfg(feature = "http-input")]
 #[allow(clippy::too_many_arguments)]
@@ -675,7 +1269,12 @@ async fn invoke_agent(
     executor: Arc<dyn ActionExecutor>,
     policy_gate: Arc<dyn ReasoningPolicyGate>,
     circuit_breakers: Arc<CircuitBreakerRegistry>,
-    journal: Arc<dyn JournalWriter>,
+    journal: RunJournal,
+    context_manager: Arc<dyn ContextManager>,
+    knowledge_bridge: Option<Arc<KnowledgeBridge>>,
+    spend: Option<Arc<SpendTracker>>,
+    spend_tags: SpendTags,
+    sandbox_sessions: Option<Arc<SandboxSessionManager>>,
 ) -> Result<Value, RuntimeError> {
     let start = std::time::Instant::now();
 
@@ -833,47 +1432,66 @@ async fn invoke_agent(
     let loop_config = LoopConfig {
         max_iterations: 15,
         tool_timeout: std::time::Duration::from_secs(120),
+        spend_tags,
         ..LoopConfig::default()
     };
 
+    let (journal, durable) = journal.for_run();
     let runner = ReasoningLoopRunner {
         provider: provider.clone(),
         policy_gate,
         executor,
-        context_manager: Arc::new(DefaultContextManager::default()),
+        context_manager,
         circuit_breakers,
         journal,
-        knowledge_bridge: None,
+        knowledge_bridge,
+        sandbox_sessions,
+        spend,
         delegation: None,
     };
 
     let result = runner.run(agent_id, conversation, loop_config).await;
+    if let Some(durable) = durable {
+        if let Err(e) = durable.compact().await {
+            tracing::warn!("Failed to compact journal for agent {}: {}", agent_id, e);
+        }
+    }
 
-    let tool_runs = reconstruct_tool_runs(&result.conversation);
-
-    let latency = start.elapsed();
+    let body = completed_run_body(Some(agent_id), provider.as_ref(), &result, start);
     tracing::info!(
         "Reasoning loop completed for agent {}: latency={:?} iterations={} tool_runs={} response_len={} termination={:?}",
         agent_id,
-        latency,
+        start.elapsed(),
         result.iterations,
-        tool_runs.len(),
+        body["tool_runs"].as_array().map_or(0, Vec::len),
         result.output.len(),
         result.termination_reason,
     );
 
-    Ok(serde_json::json!({
+    Ok(body)
+}
+
+/// The response body for a reasoning run that has ended, whether it was
+/// started by a request or resumed from the journal.
+#[cfg(feature = "http-input")]
+fn completed_run_body(
+    agent_id: Option<AgentId>,
+    provider: &dyn InferenceProvider,
+    result: &LoopResult,
+    start: std::time::Instant,
+) -> Value {
+    serde_json::json!({
         "status": "completed",
-        "agent_id": agent_id.to_string(),
+        "agent_id": agent_id.map(|id| id.to_string()),
         "response": result.output,
-        "tool_runs": tool_runs,
+        "tool_runs": reconstruct_tool_runs(&result.conversation),
         "termination_reason": result.termination_reason,
         "iterations": result.iterations,
         "model": provider.default_model(),
         "provider": provider.provider_name(),
-        "latency_ms": latency.as_millis(),
+        "latency_ms": start.elapsed().as_millis(),
         "timestamp": chrono::Utc::now().to_rfc3339()
-    }))
+    })
 }
 
 /// Rebuild the `tool_runs` response field from the governed run's
@@ -1019,13 +1637,20 @@ fn format_error_response(
     Ok((status, Json(error_body)).into_response())
 }
 
+/// Whether `value` is a secret reference that `resolve_secret_reference`
+/// would look up rather than return as-is.
+#[cfg(feature = "http-input")]
+fn is_secret_reference(value: &str) -> bool {
+    value.starts_with("vault://") || value.starts_with("file://")
+}
+
 /// Resolve a secret reference (vault://, file://, etc.) to its actual value
 #[cfg(feature = "http-input")]
 async fn resolve_secret_reference(
     secret_store: &dyn SecretStore,
     reference: &str,
 ) -> Result<String, RuntimeError> {
-    if reference.starts_with("vault://") || reference.starts_with("file://") {
+    if is_secret_reference(reference) {
         // Extract the key from the reference
         let key = reference.split("://").nth(1).ok_or_else(|| {
             RuntimeError::Configuration(crate::types::ConfigError::Invalid(
@@ -1055,15 +1680,55 @@ async fn resolve_secret_reference(
 /// resolves that to **fail-closed** (`DefaultPolicyGate::new()`), never
 /// permissive, so an embedder that forgets to pass one gets denial for every
 /// tool call rather than free rein.
+///
+/// `inference_provider` overrides the provider auto-detected from the
+/// environment (e.g. a configured failover chain). `spend` prices and
+/// budgets every reasoning loop the server runs. `journal_storage` makes
+/// the reasoning journal durable; with `resume_interrupted_runs` set in the
+/// config, interrupted runs are resumed on start and their results sent to
+/// `resumed_run_reports`. `sandbox_sessions` binds a sandbox session to
+/// every reasoning run, and `sandbox_kernel` adds an interpreter kernel in
+/// it.
 #[cfg(feature = "http-input")]
+#[allow(clippy::too_many_arguments)]
 pub async fn start_http_input(
     config: HttpInputConfig,
     runtime: Option<Arc<crate::AgentRuntime>>,
     secrets_config: Option<SecretsConfig>,
     policy_gate: Option<Arc<dyn ReasoningPolicyGate>>,
+    inference_provider: Option<Arc<dyn InferenceProvider>>,
+    spend: Option<Arc<SpendTracker>>,
+    journal_storage: Option<Arc<dyn JournalStorage>>,
+    sandbox_sessions: Option<Arc<SandboxSessionManager>>,
+    sandbox_kernel: Option<KernelConfig>,
+    resumed_run_reports: Option<mpsc::UnboundedSender<Value>>,
 ) -> Result<(), RuntimeError> {
     let mut server = HttpInputServer::new(config);
 
+    if let Some(kernel) = sandbox_kernel {
+        server = server.with_sandbox_kernel(kernel);
+    }
+
+    if let Some(reports) = resumed_run_reports {
+        server = server.with_resumed_run_reports(reports);
+    }
+
+    if let Some(sessions) = sandbox_sessions {
+        server = server.with_sandbox_sessions(sessions);
+    }
+
+    if let Some(storage) = journal_storage {
+        server = server.with_journal_storage(storage);
+    }
+
+    if let Some(provider) = inference_provider {
+        server = server.with_inference_provider(provider);
+    }
+
+    if let Some(tracker) = spend {
+        server = server.with_spend_tracker(tracker);
+    }
+
     // Add runtime if provided
     if let Some(runtime) = runtime {
         server = server.with_runtime(runtime);
@@ -1073,6 +1738,21 @@ pub async fn start_http_input(
         server = server.with_policy_gate(gate);
     }
 
+    // Add secret store if secrets config is provided
+    let secret_store: Option<Arc<dyn SecretStore + Send + Sync>> = match secrets_config {
+        Some(secrets_config) => Some(Arc::from(
+            new_secret_store(&secrets_config, "http_input")
+                .await
+                .map_err(|e| {
+                    RuntimeError::Internal(format!("Failed to initialize secret store: {}", e))
+                })?,
+        )),
+        None => None,
+    };
+    if let Some(store) = &secret_store {
+        server = server.with_secret_store(store.clone());
+    }
+
     // Load ToolClad manifests and create executor for tool-calling. Also
     // loads `toolclad.toml` custom argument types — `build_tool_executor`'s
     // generic default (used by `HttpInputServer::start()` when no executor
@@ -1090,6 +1770,20 @@ pub async fn start_http_input(
                 manifests.clone(),
                 custom_types,
             );
+            // MCP-backed tools keep one long-lived, health-checked
+            // connection per server; remote servers' credentials come from
+            // the secret store.
+            #[cfg(feature = "mcp-client")]
+            let executor = {
+                use crate::integrations::mcp::pool::{McpConnectionPool, McpPoolConfig};
+                let mut pool = McpConnectionPool::new(McpPoolConfig::default());
+                if let Some(store) = &secret_store {
+                    pool = pool.with_secret_store(store.clone());
+                }
+                let pool = Arc::new(pool);
+                pool.spawn_health_checks();
+                executor.with_mcp_pool(pool)
+            };
             tracing::info!(
                 "HTTP Input: ToolClad executor loaded with {} tool(s)",
                 manifests.len()
@@ -1098,16 +1792,6 @@ pub async fn start_http_input(
         }
     }
 
-    // Add secret store if secrets config is provided
-    if let Some(secrets_config) = secrets_config {
-        let secret_store = new_secret_store(&secrets_config, "http_input")
-            .await
-            .map_err(|e| {
-                RuntimeError::Internal(format!("Failed to initialize secret store: {}", e))
-            })?;
-        server = server.with_secret_store(Arc::from(secret_store));
-    }
-
     server.start().await
 }
 
@@ -1178,12 +1862,15 @@ mod tests {
     /// `reasoning_loop.rs`'s own tests.
     struct ScriptedProvider {
         responses: std::sync::Mutex<std::collections::VecDeque<InferenceResponse>>,
+        /// Names of the tools offered on each call.
+        offered_tools: std::sync::Mutex<Vec<Vec<String>>>,
     }
 
     impl ScriptedProvider {
         fn new(responses: Vec<InferenceResponse>) -> Self {
             Self {
                 responses: std::sync::Mutex::new(responses.into()),
+                offered_tools: std::sync::Mutex::new(Vec::new()),
             }
         }
     }
@@ -1193,8 +1880,15 @@ mod tests {
         async fn complete(
             &self,
             _conversation: &Conversation,
-            _options: &InferenceOptions,
+            options: &InferenceOptions,
         ) -> Result<InferenceResponse, InferenceError> {
+            self.offered_tools.lock().unwrap().push(
+                options
+                    .tool_definitions
+                    .iter()
+                    .map(|d| d.name.clone())
+                    .collect(),
+            );
             self.responses
                 .lock()
                 .unwrap()
@@ -1310,6 +2004,9 @@ type = "object"
             cors_origins: vec![],
             audit_enabled: false,
             webhook_verify: None,
+            webhook_routes: vec![],
+            agent_memory: vec![],
+            resume_interrupted_runs: false,
         }
     }
 
@@ -1453,4 +2150,456 @@ type = "object"
         handle.abort();
         let _ = handle.await;
     }
+
+    // ---- Dedicated webhook route tests ----
+
+    fn github_signature(secret: &[u8], body: &[u8]) -> String {
+        use hmac::{Hmac, Mac};
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn github_route(secret: Option<&str>) -> super::super::config::WebhookRouteConfig {
+        super::super::config::WebhookRouteConfig {
+            name: "github_incidents".to_string(),
+            path: "/hooks/github".to_string(),
+            provider: "github".to_string(),
+            secret: secret.map(str::to_string),
+            agent: AgentId::new(),
+            filter: Some(WebhookFilterConfig {
+                json_path: "$.action".to_string(),
+                equals: Some("created".to_string()),
+                contains: None,
+            }),
+        }
+    }
+
+    /// A signed route is authenticated by its provider signature (no bearer
+    /// token), drops payloads that miss the filter without invoking the
+    /// agent, and records every outcome in the route registry.
+    #[tokio::test]
+    async fn webhook_route_verifies_signature_and_applies_filter() {
+        let mut config = test_config(find_available_port().await);
+        config.webhook_routes = vec![github_route(Some("s3cret"))];
+        let port = config.port;
+
+        // Only one scripted response: a second agent invocation would fail.
+        let provider = Arc::new(ScriptedProvider::new(vec![final_text_response(
+            "incident recorded",
+        )]));
+        let server = HttpInputServer::new(config)
+            .with_executor(build_tool_executor(std::path::Path::new("no-such-tools")))
+            .with_inference_provider(provider)
+            .with_policy_gate(Arc::new(DefaultPolicyGate::new()));
+        let registry = server.route_registry();
+
+        let handle = tokio::spawn(async move {
+            let _ = server.start().await;
+        });
+        wait_for_port(port).await;
+
+        let client = reqwest::Client::new();
+        let url = format!("http://127.0.0.1:{}/hooks/github", port);
+        let post = |body: &'static str, signature: Option<String>| {
+            let mut request = client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .body(body);
+            if let Some(signature) = signature {
+                request = request.header("X-Hub-Signature-256", signature);
+            }
+            request.send()
+        };
+
+        let created = r#"{"action":"created","alert":{"id":7}}"#;
+        let resp = post(created, None).await.expect("request");
+        assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let resp = post(
+            created,
+            Some(github_signature(b"wrong", created.as_bytes())),
+        )
+        .await
+        .expect("request");
+        assert_eq!(resp.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let deleted = r#"{"action":"deleted"}"#;
+        let resp = post(
+            deleted,
+            Some(github_signature(b"s3cret", deleted.as_bytes())),
+        )
+        .await
+        .expect("request");
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        let body: serde_json::Value = resp.json().await.expect("json body");
+        assert_eq!(body["status"], "filtered");
+
+        let resp = post(
+            created,
+            Some(github_signature(b"s3cret", created.as_bytes())),
+        )
+        .await
+        .expect("request");
+        assert!(resp.status().is_success(), "status: {}", resp.status());
+        let body: serde_json::Value = resp.json().await.expect("json body");
+        assert_eq!(body["response"], "incident recorded");
+
+        let routes = registry.snapshot();
+        assert_eq!(routes.len(), 1);
+        let route = &routes[0];
+        assert_eq!(route.state, "active");
+        assert!(route.signature_required);
+        assert_eq!(
+            (
+                route.received,
+                route.rejected,
+                route.filtered,
+                route.accepted
+            ),
+            (4, 2, 1, 1)
+        );
+
+        handle.abort();
+        let _ = handle.await;
+    }
+
+    /// A route whose secret is an unresolvable reference must not be served
+    /// unverified: it is reported as disabled instead.
+    #[tokio::test]
+    async fn webhook_route_with_unresolved_secret_is_disabled() {
+        let mut config = test_config(find_available_port().await);
+        config.webhook_routes = vec![github_route(Some("vault://webhooks/github"))];
+        let port = config.port;
+        let server = HttpInputServer::new(config);
+        let registry = server.route_registry();
+
+        let handle = tokio::spawn(async move {
+            let _ = server.start().await;
+        });
+        wait_for_port(port).await;
+
+        // Authenticate so the server-wide auth middleware lets the request
+        // reach routing; the disabled path itself must not exist.
+        let resp = reqwest::Client::new()
+            .post(format!("http://127.0.0.1:{}/hooks/github", port))
+            .header("Authorization", "Bearer test-token")
+            .json(&serde_json::json!({ "action": "created" }))
+            .send()
+            .await
+            .expect("request");
+        assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+
+        let routes = registry.snapshot();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].state, "disabled");
+        assert!(routes[0].error.as_deref().unwrap().contains("secret store"));
+
+        handle.abort();
+        let _ = handle.await;
+    }
+
+    /// Webhook runs are billed to the credential that started them, so a
+    /// spent key or signed route stops further runs before any inference.
+    #[tokio::test]
+    async fn webhook_runs_are_blocked_by_their_key_budget() {
+        use crate::reasoning::spend::{
+            MemorySpendStore, ModelPrice, PricingTable, SpendLimits, SpendTags, SpendTracker,
+        };
+
+        let mut config = test_config(find_available_port().await);
+        config.webhook_routes = vec![github_route(Some("s3cret"))];
+        let port = config.port;
+
+        // $1 per prompt token, $1 a day per scope.
+        let tracker = Arc::new(
+            SpendTracker::new(Arc::new(MemorySpendStore::new()))
+                .with_pricing(PricingTable::empty().with_price(
+                    "scripted-test-model",
+                    ModelPrice::new(1_000_000.0, 0.0, 0.0),
+                ))
+                .with_limits(SpendLimits {
+                    per_day_usd: Some(1.0),
+                    ..Default::default()
+                }),
+        );
+        let spent = Usage {
+            prompt_tokens: 2,
+            ..Usage::default()
+        };
+        for key in [STATIC_AUTH_KEY_ID, "webhook:github_incidents"] {
+            tracker
+                .record(
+                    AgentId::new(),
+                    &SpendTags::for_api_key(key),
+                    "scripted-test-model",
+                    &spent,
+                )
+                .await;
+        }
+
+        // No scripted responses: reaching the provider would fail the run.
+        let server = HttpInputServer::new(config)
+            .with_executor(build_tool_executor(std::path::Path::new("no-such-tools")))
+            .with_inference_provider(Arc::new(ScriptedProvider::new(Vec::new())))
+            .with_policy_gate(Arc::new(DefaultPolicyGate::new()))
+            .with_spend_tracker(tracker);
+        let handle = tokio::spawn(async move {
+            let _ = server.start().await;
+        });
+        wait_for_port(port).await;
+
+        let client = reqwest::Client::new();
+        let blocked_on = |body: serde_json::Value| {
+            body["termination_reason"]["BudgetExceeded"]["scope"]
+                .as_str()
+                .map(str::to_string)
+        };
+
+        let resp = client
+            .post(format!("http://127.0.0.1:{}/webhook", port))
+            .header("Authorization", "Bearer test-token")
+            .json(&serde_json::json!({"message": "hello"}))
+            .send()
+            .await
+            .expect("request");
+        assert!(resp.status().is_success(), "status: {}", resp.status());
+        let body: serde_json::Value = resp.json().await.expect("json body");
+        assert_eq!(blocked_on(body).as_deref(), Some("api_key:http_input"));
+
+        let created = r#"{"action":"created"}"#;
+        let resp = client
+            .post(format!("http://127.0.0.1:{}/hooks/github", port))
+            .header("Content-Type", "application/json")
+            .header(
+                "X-Hub-Signature-256",
+                github_signature(b"s3cret", created.as_bytes()),
+            )
+            .body(created)
+            .send()
+            .await
+            .expect("request");
+        assert!(resp.status().is_success(), "status: {}", resp.status());
+        let body: serde_json::Value = resp.json().await.expect("json body");
+        assert_eq!(
+            blocked_on(body).as_deref(),
+            Some("api_key:webhook:github_incidents")
+        );
+
+        handle.abort();
+        let _ = handle.await;
+    }
+
+    /// A sandbox kernel adds `run_cell` to the tools offered next to
+    /// `sandbox_exec`; without one, only `sandbox_exec` is offered.
+    #[tokio::test]
+    async fn sandbox_kernel_offers_the_run_cell_tool() {
+        use crate::reasoning::sandbox_executor::{RUN_CELL_TOOL, SANDBOX_EXEC_TOOL};
+        use crate::sandbox::{
+            ExecutionRequest, ExecutionResult, KernelLanguage, SandboxRunner, SandboxSession,
+            SessionConfig,
+        };
+
+        struct EchoSession;
+
+        #[async_trait]
+        impl SandboxSession for EchoSession {
+            async fn execute(
+                &self,
+                request: ExecutionRequest,
+            ) -> Result<ExecutionResult, anyhow::Error> {
+                Ok(ExecutionResult::success(request.code, 0))
+            }
+            async fn close(&self) -> Result<(), anyhow::Error> {
+                Ok(())
+            }
+        }
+
+        struct EchoRunner;
+
+        #[async_trait]
+        impl SandboxRunner for EchoRunner {
+            async fn execute(
+                &self,
+                _code: &str,
+                _env: HashMap<String, String>,
+            ) -> Result<ExecutionResult, anyhow::Error> {
+                unreachable!("sessions only")
+            }
+            async fn open_session(&self) -> Result<Box<dyn SandboxSession>, anyhow::Error> {
+                Ok(Box::new(EchoSession))
+            }
+        }
+
+        for kernel in [None, Some(KernelLanguage::Node)] {
+            let port = find_available_port().await;
+            let provider = Arc::new(ScriptedProvider::new(vec![final_text_response("done")]));
+            let sessions = Arc::new(SandboxSessionManager::new(
+                Arc::new(EchoRunner),
+                SessionConfig::default(),
+            ));
+            let mut server = HttpInputServer::new(test_config(port))
+                .with_executor(build_tool_executor(std::path::Path::new("no-such-tools")))
+                .with_inference_provider(provider.clone())
+                .with_policy_gate(Arc::new(DefaultPolicyGate::new()))
+                .with_sandbox_sessions(sessions);
+            if let Some(language) = kernel {
+                server = server.with_sandbox_kernel(KernelConfig::new(language));
+            }
+            let handle = tokio::spawn(async move {
+                let _ = server.start().await;
+            });
+            wait_for_port(port).await;
+
+            let resp = reqwest::Client::new()
+                .post(format!("http://127.0.0.1:{}/webhook", port))
+                .header("Authorization", "Bearer test-token")
+                .json(&serde_json::json!({ "prompt": "hello" }))
+                .send()
+                .await
+                .expect("request");
+            assert!(resp.status().is_success(), "status: {}", resp.status());
+
+            let offered = provider.offered_tools.lock().unwrap().concat();
+            assert!(
+                offered.iter().any(|t| t == SANDBOX_EXEC_TOOL),
+                "{:?}",
+                offered
+            );
+            assert_eq!(
+                offered.iter().any(|t| t == RUN_CELL_TOOL),
+                kernel.is_some(),
+                "{:?}",
+                offered
+            );
+
+            handle.abort();
+            let _ = handle.await;
+        }
+    }
+
+    /// Runs left unfinished by a previous process are only resumed when the
+    /// config opts in, and a resumed run's result reaches the report sink.
+    #[tokio::test]
+    async fn interrupted_runs_resume_only_when_enabled() {
+        use crate::reasoning::journal::MemoryJournalStorage;
+        use crate::reasoning::loop_types::{JournalEntry, LoopEvent, LoopState};
+
+        let agent = AgentId::new();
+        let run = RunId::new();
+        let storage = Arc::new(MemoryJournalStorage::new());
+        let mut conversation = Conversation::with_system("You are a test agent.");
+        conversation.push(ConversationMessage::user("hello"));
+        for (sequence, event) in [
+            LoopEvent::Started {
+                agent_id: agent,
+                config: Box::default(),
+            },
+            LoopEvent::Checkpoint {
+                iteration: 0,
+                state: Box::new(LoopState::new(agent, conversation)),
+            },
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let entry = JournalEntry {
+                sequence: sequence as u64,
+                timestamp: chrono::Utc::now(),
+                agent_id: agent,
+                iteration: 0,
+                event,
+            };
+            storage.store(&run, &entry).await.unwrap();
+        }
+
+        let start = |resume: bool, port: u16| {
+            let mut config = test_config(port);
+            config.resume_interrupted_runs = resume;
+            let (tx, rx) = mpsc::unbounded_channel();
+            let server = HttpInputServer::new(config)
+                .with_executor(build_tool_executor(std::path::Path::new("no-such-tools")))
+                .with_inference_provider(Arc::new(ScriptedProvider::new(vec![
+                    final_text_response("Picked up where I left off."),
+                ])))
+                .with_policy_gate(Arc::new(DefaultPolicyGate::new()))
+                .with_journal_storage(storage.clone())
+                .with_resumed_run_reports(tx);
+            (server, rx)
+        };
+
+        let port = find_available_port().await;
+        let (server, mut reports) = start(false, port);
+        let handle = tokio::spawn(async move {
+            let _ = server.start().await;
+        });
+        wait_for_port(port).await;
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(reports.try_recv().is_err());
+        assert_eq!(storage.read_entries(&run).await.unwrap().len(), 2);
+        handle.abort();
+        let _ = handle.await;
+
+        let (server, mut reports) = start(true, find_available_port().await);
+        let handle = tokio::spawn(async move {
+            let _ = server.start().await;
+        });
+        let report = tokio::time::timeout(std::time::Duration::from_secs(5), reports.recv())
+            .await
+            .expect("resumed run report")
+            .expect("report channel open");
+        assert_eq!(report["status"], "resumed");
+        assert_eq!(report["run_id"], run.to_string());
+        assert_eq!(report["agent_id"], agent.to_string());
+        assert_eq!(report["response"], "Picked up where I left off.");
+        handle.abort();
+        let _ = handle.await;
+        assert!(storage.runs().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn webhook_route_path_conflicts_are_rejected() {
+        let mut config = test_config(find_available_port().await);
+        let mut clash = github_route(None);
+        clash.path = "/webhook/".to_string();
+        config.webhook_routes = vec![clash];
+        let err = HttpInputServer::new(config.clone()).start().await;
+        assert!(matches!(err, Err(RuntimeError::Configuration(_))));
+
+        config.webhook_routes = vec![github_route(None), github_route(Some("s3cret"))];
+        let err = HttpInputServer::new(config).start().await;
+        assert!(matches!(err, Err(RuntimeError::Configuration(_))));
+    }
+
+    #[tokio::test]
+    async fn webhook_route_paths_with_parameters_are_rejected() {
+        let mut config = test_config(find_available_port().await);
+        for path in [
+            "/hooks/:id",
+            "/hooks/*rest",
+            "/hooks/{id}",
+            "/hooks/}",
+            "/hooks/a:b",
+        ] {
+            let mut route = github_route(None);
+            route.path = path.to_string();
+            config.webhook_routes = vec![route];
+            match HttpInputServer::new(config.clone()).start().await {
+                Err(RuntimeError::Configuration(e)) => {
+                    assert!(e.to_string().contains("not allowed"), "{}: {}", path, e)
+                }
+                other => panic!("{} was accepted: {:?}", path, other.err()),
+            }
+        }
+
+        // Two parameter routes that would overlap are refused before the
+        // router sees them.
+        let mut first = github_route(None);
+        first.path = "/hooks/:id".to_string();
+        let mut second = github_route(None);
+        second.path = "/hooks/:name".to_string();
+        config.webhook_routes = vec![first, second];
+        let err = HttpInputServer::new(config).start().await;
+        assert!(matches!(err, Err(RuntimeError::Configuration(_))));
+    }
 }
@@ -0,0 +1,284 @@
+//! Per-route bookkeeping for dedicated webhook endpoints.
+//!
+//! [`WebhookRouteRegistry`] records every route the HTTP input server
+//! registered from [`WebhookRouteConfig`](super::config::WebhookRouteConfig)
+//! together with request counters, so operators can see through
+//! `/api/v1/status` which webhooks are live and how their traffic is being
+//! handled. This module also evaluates the payload filters attached to a
+//! route.
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::config::WebhookFilterConfig;
+
+/// Route is registered and serving requests.
+pub const ROUTE_STATE_ACTIVE: &str = "active";
+/// Route could not be registered (for example, its secret did not resolve).
+pub const ROUTE_STATE_DISABLED: &str = "disabled";
+
+/// Status and counters for one webhook route.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRouteStatus {
+    /// Route name (the DSL `webhook` block name).
+    pub name: String,
+    /// HTTP path the route is served on.
+    pub path: String,
+    /// Provider preset used for signature verification.
+    pub provider: String,
+    /// Agent invoked for accepted requests.
+    pub agent: String,
+    /// `true` when requests must carry a valid provider signature.
+    pub signature_required: bool,
+    /// `"active"` or `"disabled"`.
+    pub state: String,
+    /// Why the route is disabled, if it is.
+    pub error: Option<String>,
+    /// Requests received on this route.
+    pub received: u64,
+    /// Requests that passed verification and filtering and reached the agent.
+    pub accepted: u64,
+    /// Requests acknowledged without invoking the agent because the filter
+    /// did not match.
+    pub filtered: u64,
+    /// Requests rejected for a bad signature or malformed body.
+    pub rejected: u64,
+    /// Accepted requests whose agent invocation failed.
+    pub failed: u64,
+    /// Time of the most recent request.
+    pub last_request_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookRouteStatus {
+    /// A fresh status entry with zeroed counters.
+    pub fn new(name: &str, path: &str, provider: &str, agent: String) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_string(),
+            provider: provider.to_string(),
+            agent,
+            signature_required: false,
+            state: ROUTE_STATE_ACTIVE.to_string(),
+            error: None,
+            received: 0,
+            accepted: 0,
+            filtered: 0,
+            rejected: 0,
+            failed: 0,
+            last_request_at: None,
+        }
+    }
+}
+
+/// How a request to a webhook route was handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookOutcome {
+    /// The agent was invoked successfully.
+    Accepted,
+    /// The payload did not match the route filter.
+    Filtered,
+    /// Signature verification failed or the body was not valid JSON.
+    Rejected,
+    /// The agent invocation returned an error.
+    Failed,
+}
+
+/// Shared, thread-safe registry of webhook route status.
+///
+/// One registry is owned by each [`AgentRuntime`](crate::AgentRuntime) and
+/// shared with the HTTP input server attached to it, which is how the
+/// runtime API reports route status without holding a handle to the server.
+#[derive(Debug, Default)]
+pub struct WebhookRouteRegistry {
+    routes: RwLock<Vec<WebhookRouteStatus>>,
+}
+
+impl WebhookRouteRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop every registered route. Called when a server (re)starts so stale
+    /// entries from a previous configuration do not linger.
+    pub fn clear(&self) {
+        self.routes.write().clear();
+    }
+
+    /// Register a route and return the index used to record its traffic.
+    pub fn register(&self, status: WebhookRouteStatus) -> usize {
+        let mut routes = self.routes.write();
+        routes.push(status);
+        routes.len() - 1
+    }
+
+    /// Count one request against the route at `index`.
+    pub fn record(&self, index: usize, outcome: WebhookOutcome) {
+        let mut routes = self.routes.write();
+        let Some(route) = routes.get_mut(index) else {
+            return;
+        };
+        route.received += 1;
+        route.last_request_at = Some(Utc::now());
+        match outcome {
+            WebhookOutcome::Accepted => route.accepted += 1,
+            WebhookOutcome::Filtered => route.filtered += 1,
+            WebhookOutcome::Rejected => route.rejected += 1,
+            WebhookOutcome::Failed => route.failed += 1,
+        }
+    }
+
+    /// A copy of every route's current status, in registration order.
+    pub fn snapshot(&self) -> Vec<WebhookRouteStatus> {
+        self.routes.read().clone()
+    }
+}
+
+/// Check whether a JSON payload passes a route filter.
+///
+/// The value at `json_path` must exist. When `equals` is set it must match
+/// exactly; when `contains` is set it must appear as a substring. Non-string
+/// values are compared using their JSON text (`42`, `true`).
+pub fn filter_matches(filter: &WebhookFilterConfig, payload: &Value) -> bool {
+    let Some(value) = lookup_json_path(payload, &filter.json_path) else {
+        return false;
+    };
+    let text = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if let Some(expected) = &filter.equals {
+        if &text != expected {
+            return false;
+        }
+    }
+    if let Some(needle) = &filter.contains {
+        if !text.contains(needle.as_str()) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Resolve a simple JSON path (`$.a.b`, `$.items[0].name`, or `a.b`) against
+/// a value. Wildcards and filter expressions are not supported.
+pub fn lookup_json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim();
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let mut current = value;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (key, mut indexes) = match segment.find('[') {
+            Some(pos) => (&segment[..pos], &segment[pos..]),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            current = current.get(key)?;
+        }
+        while let Some(rest) = indexes.strip_prefix('[') {
+            let end = rest.find(']')?;
+            let index: usize = rest[..end].trim().parse().ok()?;
+            current = current.get(index)?;
+            indexes = &rest[end + 1..];
+        }
+        if !indexes.is_empty() {
+            return None;
+        }
+    }
+    Some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter(path: &str, equals: Option<&str>, contains: Option<&str>) -> WebhookFilterConfig {
+        WebhookFilterConfig {
+            json_path: path.to_string(),
+            equals: equals.map(str::to_string),
+            contains: contains.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_lookup_json_path() {
+        let payload = json!({
+            "action": "created",
+            "issue": { "labels": [{ "name": "bug" }, { "name": "p1" }] },
+            "count": 3
+        });
+        assert_eq!(
+            lookup_json_path(&payload, "$.action"),
+            Some(&json!("created"))
+        );
+        assert_eq!(
+            lookup_json_path(&payload, "action"),
+            Some(&json!("created"))
+        );
+        assert_eq!(
+            lookup_json_path(&payload, "$.issue.labels[1].name"),
+            Some(&json!("p1"))
+        );
+        assert_eq!(lookup_json_path(&payload, "$"), Some(&payload));
+        assert_eq!(lookup_json_path(&payload, "$.issue.labels[5]"), None);
+        assert_eq!(lookup_json_path(&payload, "$.missing"), None);
+        assert_eq!(lookup_json_path(&payload, "$.issue.labels[x]"), None);
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let payload = json!({ "type": "invoice.payment_failed", "attempts": 2 });
+        assert!(filter_matches(
+            &filter("$.type", Some("invoice.payment_failed"), None),
+            &payload
+        ));
+        assert!(!filter_matches(
+            &filter("$.type", Some("invoice"), None),
+            &payload
+        ));
+        assert!(filter_matches(
+            &filter("$.type", None, Some("failed")),
+            &payload
+        ));
+        assert!(!filter_matches(
+            &filter("$.type", Some("invoice.payment_failed"), Some("succeeded")),
+            &payload
+        ));
+        assert!(filter_matches(
+            &filter("$.attempts", Some("2"), None),
+            &payload
+        ));
+        // A filter with no comparison only requires the path to exist.
+        assert!(filter_matches(&filter("$.type", None, None), &payload));
+        assert!(!filter_matches(&filter("$.action", None, None), &payload));
+    }
+
+    #[test]
+    fn test_registry_records_outcomes() {
+        let registry = WebhookRouteRegistry::new();
+        let index = registry.register(WebhookRouteStatus::new(
+            "github",
+            "/hooks/github",
+            "github",
+            "agent-1".to_string(),
+        ));
+        registry.record(index, WebhookOutcome::Accepted);
+        registry.record(index, WebhookOutcome::Filtered);
+        registry.record(index, WebhookOutcome::Rejected);
+        registry.record(99, WebhookOutcome::Accepted);
+
+        let routes = registry.snapshot();
+        assert_eq!(routes.len(), 1);
+        let route = &routes[0];
+        assert_eq!(route.received, 3);
+        assert_eq!(
+            (route.accepted, route.filtered, route.rejected, route.failed),
+            (1, 1, 1, 0)
+        );
+        assert!(route.last_request_at.is_some());
+
+        registry.clear();
+        assert!(registry.snapshot().is_empty());
+    }
+}
@@ -309,6 +309,17 @@ pub enum WebhookProvider {
 }
 
 impl WebhookProvider {
+    /// Look up a provider preset by name (case-insensitive). Unknown names
+    /// map to [`WebhookProvider::Custom`].
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "github" => WebhookProvider::GitHub,
+            "stripe" => WebhookProvider::Stripe,
+            "slack" => WebhookProvider::Slack,
+            _ => WebhookProvider::Custom,
+        }
+    }
+
     /// Build a [`SignatureVerifier`] for this provider using the given secret.
     pub fn verifier(&self, secret: &[u8]) -> Box<dyn SignatureVerifier> {
         match self {
@@ -127,6 +127,33 @@ impl SecureMcpClient {
 
     /// Verify a tool's schema using SchemaPin
     async fn verify_schema(&self, tool: &McpTool) -> Result<VerificationStatus, McpClientError> {
+        // Fetch and pin the provider's public key (TOFU)
+        self.fetch_and_pin_key(&tool.provider).await?;
+        self.check_signature(tool).await
+    }
+
+    /// Re-verify a tool freshly discovered from a live server, ahead of a
+    /// call. Unlike [`McpClient::verify_tool`] this re-fetches and re-pins
+    /// the provider key every time, so a key swapped since it was first
+    /// pinned is rejected (`KeyMismatch`) rather than silently trusted. On
+    /// success the tool is recorded as verified and returned; any other
+    /// outcome is an error.
+    pub async fn reverify_tool(&self, mut tool: McpTool) -> Result<McpTool, McpClientError> {
+        self.pin_provider_key(&tool.provider).await?;
+        tool.verification_status = self.check_signature(&tool).await?;
+        if let VerificationStatus::Failed { reason, .. } = &tool.verification_status {
+            return Err(McpClientError::VerificationFailed {
+                reason: format!("Tool '{}': {}", tool.name, reason),
+            });
+        }
+
+        let mut tools = self.tools.write().await;
+        tools.insert(tool.name.clone(), tool.clone());
+        Ok(tool)
+    }
+
+    /// Check a tool schema's SchemaPin signature against the provider key.
+    async fn check_signature(&self, tool: &McpTool) -> Result<VerificationStatus, McpClientError> {
         // Create a temporary file for the schema
         let mut temp_file =
             NamedTempFile::new().map_err(|e| McpClientError::SerializationError {
@@ -148,9 +175,6 @@ impl SecureMcpClient {
 
         let temp_path = temp_file.path().to_string_lossy().to_string();
 
-        // Fetch and pin the provider's public key (TOFU)
-        self.fetch_and_pin_key(&tool.provider).await?;
-
         // Verify the schema
         let verify_args = VerifyArgs::new(temp_path, tool.provider.public_key_url.clone());
 
@@ -190,6 +214,13 @@ impl SecureMcpClient {
             return Ok(());
         }
 
+        self.pin_provider_key(provider).await
+    }
+
+    /// Fetch a provider's public key over HTTPS and pin it. `pin_key` is
+    /// TOFU: it records the key on first contact, accepts a matching
+    /// re-affirm, and rejects a different key with `KeyMismatch`.
+    async fn pin_provider_key(&self, provider: &ToolProvider) -> Result<(), McpClientError> {
         // Fetch the real public key from the provider's HTTPS endpoint
         tracing::info!(
             provider = %provider.identifier,
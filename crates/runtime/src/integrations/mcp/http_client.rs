@@ -0,0 +1,261 @@
+//! Streamable HTTP MCP client (connect-per-invocation). Connects to a remote
+//! MCP server registered with a `url` in `mcp-config.toml`, authenticating
+//! with a bearer token and headers resolved from the secrets store, and
+//! lists/calls tools over the session. Long-lived sessions live in
+//! [`super::pool::McpConnectionPool`], which reuses
+//! [`RmcpHttpClient::connect`].
+
+use std::collections::HashMap;
+
+use crate::integrations::mcp::registry::HttpServerSpec;
+use crate::integrations::mcp::schema_gate::verify_live_tool;
+use crate::integrations::mcp::stdio_client::tool_output;
+use crate::secrets::{resolve_secret_or_env, SecretStore};
+use reqwest::header::{HeaderName, HeaderValue};
+use rmcp::{
+    model::CallToolRequestParams,
+    service::RunningService,
+    transport::{
+        streamable_http_client::StreamableHttpClientTransportConfig, StreamableHttpClientTransport,
+    },
+    ClientHandler, RoleClient, ServiceExt,
+};
+
+/// Credentials and headers for one connection, resolved from the spec.
+#[derive(Default)]
+pub struct ResolvedHeaders {
+    /// Bearer token (without the `Bearer ` prefix).
+    pub bearer_token: Option<String>,
+    pub headers: HashMap<HeaderName, HeaderValue>,
+}
+
+impl std::fmt::Debug for ResolvedHeaders {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResolvedHeaders")
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+pub struct RmcpHttpClient;
+
+impl RmcpHttpClient {
+    /// Resolve the bearer token and headers for `spec`. The token comes from
+    /// `bearer_token_secret` in `secrets`, falling back to `bearer_token_env`;
+    /// `header_secrets` come from `secrets` only. Fails closed: a configured
+    /// credential that cannot be resolved is an error, never a request sent
+    /// without it.
+    pub async fn resolve_headers(
+        spec: &HttpServerSpec,
+        secrets: Option<&(dyn SecretStore + Send + Sync)>,
+    ) -> Result<ResolvedHeaders, String> {
+        let mut resolved = ResolvedHeaders::default();
+
+        for (name, value) in &spec.headers {
+            resolved
+                .headers
+                .insert(header_name(name)?, header_value(name, value)?);
+        }
+
+        for (name, key) in &spec.header_secrets {
+            let store = secrets.ok_or_else(|| {
+                format!(
+                    "header '{}' for MCP server {} needs secret '{}' but no secret store is configured",
+                    name, spec.url, key
+                )
+            })?;
+            let secret = store.get_secret(key).await.map_err(|e| {
+                format!(
+                    "cannot resolve secret '{}' for header '{}' of MCP server {}: {}",
+                    key, name, spec.url, e
+                )
+            })?;
+            resolved
+                .headers
+                .insert(header_name(name)?, header_value(name, secret.value())?);
+        }
+
+        if spec.bearer_token_secret.is_some() || spec.bearer_token_env.is_some() {
+            let env_var = spec.bearer_token_env.as_deref().unwrap_or_default();
+            let token =
+                match resolve_secret_or_env(env_var, spec.bearer_token_secret.as_deref(), secrets)
+                    .await
+                {
+                    Some(token) if !token.is_empty() => token,
+                    _ => {
+                        return Err(format!(
+                            "no bearer token for MCP server {}: set bearer_token_secret or {}",
+                            spec.url,
+                            if env_var.is_empty() {
+                                "bearer_token_env"
+                            } else {
+                                env_var
+                            }
+                        ))
+                    }
+                };
+            resolved.bearer_token = Some(token);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Open a Streamable HTTP session with the server described by `spec`
+    /// and complete the MCP handshake, with `handler` receiving server
+    /// notifications.
+    pub async fn connect<H: ClientHandler>(
+        spec: &HttpServerSpec,
+        secrets: Option<&(dyn SecretStore + Send + Sync)>,
+        handler: H,
+    ) -> Result<RunningService<RoleClient, H>, String> {
+        let resolved = Self::resolve_headers(spec, secrets).await?;
+        let mut config = StreamableHttpClientTransportConfig::with_uri(spec.url.as_str())
+            .custom_headers(resolved.headers);
+        if let Some(token) = resolved.bearer_token {
+            config = config.auth_header(token);
+        }
+        handler
+            .serve(StreamableHttpClientTransport::from_config(config))
+            .await
+            .map_err(|e| format!("failed to connect to MCP server {}: {}", spec.url, e))
+    }
+
+    /// Connect to the server described by `spec`, list its tools, then
+    /// close the session.
+    pub async fn list_tools(
+        spec: &HttpServerSpec,
+        secrets: Option<&(dyn SecretStore + Send + Sync)>,
+    ) -> Result<Vec<rmcp::model::Tool>, String> {
+        let client = Self::connect(spec, secrets, ()).await?;
+        let tools = client.list_all_tools().await.map_err(|e| e.to_string());
+        let _ = client.cancel().await;
+        tools
+    }
+
+    /// Discover `tool` on the server described by `spec`, gate it behind
+    /// SchemaPin verification when `enforce` is set, then invoke it — all
+    /// over one session, so the verified schema is the one the call runs
+    /// against. Same fail-closed semantics as
+    /// [`super::stdio_client::RmcpStdioClient::verified_invoke`].
+    pub async fn verified_invoke(
+        spec: &HttpServerSpec,
+        secrets: Option<&(dyn SecretStore + Send + Sync)>,
+        tool: &str,
+        args: serde_json::Map<String, serde_json::Value>,
+        enforce: bool,
+    ) -> Result<serde_json::Value, String> {
+        let client = Self::connect(spec, secrets, ()).await?;
+        let out = async {
+            if enforce {
+                let tools = client.list_all_tools().await.map_err(|e| e.to_string())?;
+                let rmcp_tool = tools
+                    .iter()
+                    .find(|t| t.name.as_ref() == tool)
+                    .ok_or_else(|| format!("tool '{}' not found on server", tool))?;
+                verify_live_tool(rmcp_tool, &spec.url, spec.public_key_url.as_deref()).await?;
+            }
+            let params = CallToolRequestParams::new(tool.to_string()).with_arguments(args);
+            client.call_tool(params).await.map_err(|e| e.to_string())
+        }
+        .await;
+        let _ = client.cancel().await;
+        tool_output(tool, out?)
+    }
+}
+
+fn header_name(name: &str) -> Result<HeaderName, String> {
+    HeaderName::from_bytes(name.as_bytes())
+        .map_err(|e| format!("invalid header name '{}': {}", name, e))
+}
+
+fn header_value(name: &str, value: &str) -> Result<HeaderValue, String> {
+    let mut value = HeaderValue::from_str(value)
+        .map_err(|e| format!("invalid value for header '{}': {}", name, e))?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::{Secret, SecretError};
+    use async_trait::async_trait;
+
+    struct StaticSecretStore;
+
+    #[async_trait]
+    impl SecretStore for StaticSecretStore {
+        async fn get_secret(&self, key: &str) -> Result<Secret, SecretError> {
+            match key {
+                "mcp/token" => Ok(Secret::new(key.to_string(), "tok-123".to_string())),
+                "mcp/api_key" => Ok(Secret::new(key.to_string(), "key-456".to_string())),
+                _ => Err(SecretError::NotFound {
+                    key: key.to_string(),
+                }),
+            }
+        }
+
+        async fn list_secrets(&self) -> Result<Vec<String>, SecretError> {
+            Ok(vec!["mcp/token".to_string(), "mcp/api_key".to_string()])
+        }
+    }
+
+    fn spec() -> HttpServerSpec {
+        HttpServerSpec {
+            url: "https://mcp.example.com/mcp".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn resolves_bearer_and_header_secrets_from_store() {
+        let mut spec = spec();
+        spec.bearer_token_secret = Some("mcp/token".to_string());
+        spec.headers.insert("X-Org".to_string(), "acme".to_string());
+        spec.header_secrets
+            .insert("X-Api-Key".to_string(), "mcp/api_key".to_string());
+
+        let resolved = RmcpHttpClient::resolve_headers(&spec, Some(&StaticSecretStore))
+            .await
+            .unwrap();
+        assert_eq!(resolved.bearer_token.as_deref(), Some("tok-123"));
+        assert_eq!(resolved.headers[&HeaderName::from_static("x-org")], "acme");
+        assert_eq!(
+            resolved.headers[&HeaderName::from_static("x-api-key")],
+            "key-456"
+        );
+        assert!(!format!("{resolved:?}").contains("tok-123"));
+    }
+
+    #[tokio::test]
+    async fn unresolvable_credentials_fail_closed() {
+        let mut missing_token = spec();
+        missing_token.bearer_token_secret = Some("mcp/unknown".to_string());
+        missing_token.bearer_token_env = Some("SYMBI_TEST_UNSET_MCP_TOKEN".to_string());
+        let err = RmcpHttpClient::resolve_headers(&missing_token, Some(&StaticSecretStore))
+            .await
+            .unwrap_err();
+        assert!(err.contains("SYMBI_TEST_UNSET_MCP_TOKEN"), "{err}");
+
+        let mut no_store = spec();
+        no_store
+            .header_secrets
+            .insert("X-Api-Key".to_string(), "mcp/api_key".to_string());
+        assert!(RmcpHttpClient::resolve_headers(&no_store, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn no_credentials_configured_sends_none() {
+        let resolved = RmcpHttpClient::resolve_headers(&spec(), None)
+            .await
+            .unwrap();
+        assert!(resolved.bearer_token.is_none());
+        assert!(resolved.headers.is_empty());
+    }
+}
@@ -4,8 +4,14 @@
 
 pub mod client;
 #[cfg(feature = "mcp-client")]
+pub mod http_client;
+#[cfg(feature = "mcp-client")]
+pub mod pool;
+#[cfg(feature = "mcp-client")]
 pub mod registry;
 #[cfg(feature = "mcp-client")]
+mod schema_gate;
+#[cfg(feature = "mcp-client")]
 pub mod stdio_client;
 pub mod types;
 
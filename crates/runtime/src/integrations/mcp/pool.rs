@@ -0,0 +1,333 @@
+//! Pooled long-lived MCP connections.
+//!
+//! [`McpConnectionPool`] keeps one connection per registered server (a stdio
+//! subprocess or a Streamable HTTP session) instead of connecting per call:
+//!
+//! - **Restart on crash.** A connection whose transport has closed is
+//!   replaced on next use, or by the health check, at most once per
+//!   `restart_backoff` so a server that dies on startup is not respawned in
+//!   a tight loop. A call that was in flight when the server died is
+//!   reported as failed, never replayed.
+//! - **`tools/list_changed`.** The tool listing is cached per connection and
+//!   refreshed when the server sends `notifications/tools/list_changed`.
+//!   Servers that do not advertise the `tools.listChanged` capability are
+//!   re-listed on every enforced call.
+//! - **Health checks.** [`McpConnectionPool::spawn_health_checks`] pings
+//!   every pooled connection periodically and restarts those that fail.
+//!
+//! Pooling does not cache trust: under enforcement every call re-verifies
+//! the tool's current schema with SchemaPin through `SecureMcpClient`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+use rmcp::{
+    model::{CallToolRequestParams, ClientRequest, Tool},
+    service::{NotificationContext, Peer, RunningService, ServiceError},
+    ClientHandler, RoleClient,
+};
+
+use crate::integrations::mcp::http_client::RmcpHttpClient;
+use crate::integrations::mcp::registry::McpServerSpec;
+use crate::integrations::mcp::schema_gate::verify_live_tool;
+use crate::integrations::mcp::stdio_client::{tool_output, RmcpStdioClient};
+use crate::secrets::SecretStore;
+
+/// Tuning for [`McpConnectionPool`].
+#[derive(Debug, Clone)]
+pub struct McpPoolConfig {
+    /// Interval between health-check pings.
+    pub health_check_interval: Duration,
+    /// How long a ping may take before the connection is considered dead.
+    pub health_check_timeout: Duration,
+    /// Minimum time between two starts of the same server.
+    pub restart_backoff: Duration,
+}
+
+impl Default for McpPoolConfig {
+    fn default() -> Self {
+        Self {
+            health_check_interval: Duration::from_secs(30),
+            health_check_timeout: Duration::from_secs(5),
+            restart_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Client-side handler for pooled connections: marks the cached tool
+/// listing stale when the server reports that its tools changed.
+struct PoolHandler {
+    tools_stale: Arc<AtomicBool>,
+}
+
+impl ClientHandler for PoolHandler {
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        self.tools_stale.store(true, Ordering::SeqCst);
+    }
+}
+
+struct PooledServer {
+    spec: McpServerSpec,
+    connection: tokio::sync::Mutex<Connection>,
+    tools: tokio::sync::RwLock<Option<Arc<Vec<Tool>>>>,
+    tools_stale: Arc<AtomicBool>,
+    restarts: AtomicU64,
+}
+
+#[derive(Default)]
+struct Connection {
+    service: Option<RunningService<RoleClient, PoolHandler>>,
+    started_at: Option<Instant>,
+}
+
+impl Connection {
+    fn live_peer(&self) -> Option<Peer<RoleClient>> {
+        self.service
+            .as_ref()
+            .filter(|s| !s.is_closed() && !s.peer().is_transport_closed())
+            .map(|s| s.peer().clone())
+    }
+}
+
+/// Long-lived connections to registered MCP servers, keyed by server name.
+pub struct McpConnectionPool {
+    config: McpPoolConfig,
+    secrets: Option<Arc<dyn SecretStore + Send + Sync>>,
+    servers: Mutex<HashMap<String, Arc<PooledServer>>>,
+}
+
+impl McpConnectionPool {
+    pub fn new(config: McpPoolConfig) -> Self {
+        Self {
+            config,
+            secrets: None,
+            servers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Resolve HTTP servers' bearer tokens and secret headers through
+    /// `store`.
+    pub fn with_secret_store(mut self, store: Arc<dyn SecretStore + Send + Sync>) -> Self {
+        self.secrets = Some(store);
+        self
+    }
+
+    /// Ping every pooled connection each `health_check_interval` and restart
+    /// those that are dead or unresponsive. The task ends once the pool is
+    /// dropped.
+    pub fn spawn_health_checks(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let pool: Weak<Self> = Arc::downgrade(self);
+        let interval = self.config.health_check_interval;
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(pool) = pool.upgrade() else { break };
+                pool.check_health().await;
+            }
+        })
+    }
+
+    /// Run one health-check pass over all pooled connections.
+    pub async fn check_health(&self) {
+        let servers: Vec<(String, Arc<PooledServer>)> = self
+            .servers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(name, server)| (name.clone(), server.clone()))
+            .collect();
+
+        for (name, server) in servers {
+            let peer = server.connection.lock().await.live_peer();
+            let healthy = match peer {
+                Some(peer) => tokio::time::timeout(
+                    self.config.health_check_timeout,
+                    peer.send_request(ClientRequest::PingRequest(Default::default())),
+                )
+                .await
+                .is_ok_and(|r| r.is_ok()),
+                None => false,
+            };
+            if !healthy {
+                tracing::warn!(server = %name, "MCP server failed health check; restarting");
+                let mut connection = server.connection.lock().await;
+                connection.service = None;
+                if let Err(e) = self.start(&name, &server, &mut connection).await {
+                    tracing::warn!(server = %name, error = %e, "MCP server restart failed");
+                }
+            }
+        }
+    }
+
+    /// Number of times `server` has been restarted after its connection
+    /// died, or `None` if it has never been pooled.
+    pub fn restarts(&self, server: &str) -> Option<u64> {
+        self.servers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(server)
+            .map(|s| s.restarts.load(Ordering::SeqCst))
+    }
+
+    /// Close every pooled connection (stdio servers are terminated).
+    pub async fn shutdown(&self) {
+        let servers: Vec<Arc<PooledServer>> = self
+            .servers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+            .map(|(_, server)| server)
+            .collect();
+        for server in servers {
+            if let Some(service) = server.connection.lock().await.service.take() {
+                let _ = service.cancel().await;
+            }
+        }
+    }
+
+    /// The tools `server` currently exposes, from the cached listing.
+    pub async fn list_tools(
+        &self,
+        server: &str,
+        spec: &McpServerSpec,
+    ) -> Result<Arc<Vec<Tool>>, String> {
+        let pooled = self.server(server, spec);
+        let peer = self.peer(server, &pooled).await?;
+        self.tools(&pooled, &peer, false).await
+    }
+
+    /// Invoke `tool` on `server` over its pooled connection, gated behind
+    /// SchemaPin verification when `enforce` is set. Same fail-closed
+    /// semantics as [`RmcpStdioClient::verified_invoke`].
+    pub async fn verified_invoke(
+        &self,
+        server: &str,
+        spec: &McpServerSpec,
+        tool: &str,
+        args: serde_json::Map<String, serde_json::Value>,
+        enforce: bool,
+    ) -> Result<serde_json::Value, String> {
+        let pooled = self.server(server, spec);
+        let peer = self.peer(server, &pooled).await?;
+
+        if enforce {
+            let tools = self.tools(&pooled, &peer, true).await?;
+            let rmcp_tool = tools
+                .iter()
+                .find(|t| t.name.as_ref() == tool)
+                .ok_or_else(|| format!("tool '{}' not found on server", tool))?;
+            verify_live_tool(rmcp_tool, spec.provider_identifier(), spec.public_key_url()).await?;
+        }
+
+        let params = CallToolRequestParams::new(tool.to_string()).with_arguments(args);
+        match peer.call_tool(params).await {
+            Ok(result) => tool_output(tool, result),
+            Err(ServiceError::TransportClosed) => {
+                // The server died mid-call. The next use restarts it; this
+                // call is not replayed since it may already have run.
+                Err(format!(
+                    "MCP server '{}' exited during call to '{}'",
+                    server, tool
+                ))
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// The pool entry for `server`, replacing it if the registry spec changed.
+    fn server(&self, server: &str, spec: &McpServerSpec) -> Arc<PooledServer> {
+        let mut servers = self.servers.lock().unwrap_or_else(|e| e.into_inner());
+        match servers.get(server) {
+            Some(existing) if existing.spec == *spec => existing.clone(),
+            _ => {
+                let pooled = Arc::new(PooledServer {
+                    spec: spec.clone(),
+                    connection: tokio::sync::Mutex::new(Connection::default()),
+                    tools: tokio::sync::RwLock::new(None),
+                    tools_stale: Arc::new(AtomicBool::new(true)),
+                    restarts: AtomicU64::new(0),
+                });
+                servers.insert(server.to_string(), pooled.clone());
+                pooled
+            }
+        }
+    }
+
+    /// A live peer for `pooled`, (re)starting the connection if needed.
+    async fn peer(&self, name: &str, pooled: &PooledServer) -> Result<Peer<RoleClient>, String> {
+        let mut connection = pooled.connection.lock().await;
+        if let Some(peer) = connection.live_peer() {
+            return Ok(peer);
+        }
+        if connection.service.take().is_some() {
+            tracing::warn!(server = %name, "MCP server connection closed; restarting");
+        }
+        self.start(name, pooled, &mut connection).await
+    }
+
+    async fn start(
+        &self,
+        name: &str,
+        pooled: &PooledServer,
+        connection: &mut Connection,
+    ) -> Result<Peer<RoleClient>, String> {
+        if let Some(started_at) = connection.started_at {
+            let since = started_at.elapsed();
+            if since < self.config.restart_backoff {
+                tokio::time::sleep(self.config.restart_backoff - since).await;
+            }
+            pooled.restarts.fetch_add(1, Ordering::SeqCst);
+        }
+        connection.started_at = Some(Instant::now());
+
+        let handler = PoolHandler {
+            tools_stale: pooled.tools_stale.clone(),
+        };
+        let service = match &pooled.spec {
+            McpServerSpec::Stdio(spec) => RmcpStdioClient::connect(spec, handler).await?,
+            McpServerSpec::Http(spec) => {
+                RmcpHttpClient::connect(spec, self.secrets.as_deref(), handler).await?
+            }
+        };
+        tracing::debug!(server = %name, "MCP server connected");
+        pooled.tools_stale.store(true, Ordering::SeqCst);
+        let peer = service.peer().clone();
+        connection.service = Some(service);
+        Ok(peer)
+    }
+
+    /// The tool listing for `pooled`, re-listed if the server signalled a
+    /// change, or on every `for_call` use if it never signals changes.
+    async fn tools(
+        &self,
+        pooled: &PooledServer,
+        peer: &Peer<RoleClient>,
+        for_call: bool,
+    ) -> Result<Arc<Vec<Tool>>, String> {
+        let notifies_changes = peer
+            .peer_info()
+            .and_then(|info| info.capabilities.tools.as_ref())
+            .and_then(|tools| tools.list_changed)
+            .unwrap_or(false);
+        let stale = pooled.tools_stale.swap(false, Ordering::SeqCst);
+        if !stale && (notifies_changes || !for_call) {
+            if let Some(tools) = pooled.tools.read().await.as_ref() {
+                return Ok(tools.clone());
+            }
+        }
+
+        let tools = match peer.list_all_tools().await {
+            Ok(tools) => Arc::new(tools),
+            Err(e) => {
+                pooled.tools_stale.store(true, Ordering::SeqCst);
+                return Err(e.to_string());
+            }
+        };
+        *pooled.tools.write().await = Some(tools.clone());
+        Ok(tools)
+    }
+}
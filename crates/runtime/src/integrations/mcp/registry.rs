@@ -1,12 +1,26 @@
 //! Registry mapping MCP server names (referenced from ToolClad `[mcp]`
-//! manifests) to a stdio launch spec. Loaded from `./mcp-config.toml`
-//! (per-project) or `~/.symbiont/mcp-config.toml` (user default).
+//! manifests) to a launch spec: a stdio subprocess or a remote Streamable
+//! HTTP endpoint. Loaded from `./mcp-config.toml` (per-project) or
+//! `~/.symbiont/mcp-config.toml` (user default).
+//!
+//! ```toml
+//! [servers.fs]
+//! command = "mcp-fs"
+//! args = ["--root", "/srv/data"]
+//!
+//! [servers.tickets]
+//! url = "https://mcp.example.com/mcp"
+//! bearer_token_secret = "mcp/tickets/token"
+//! bearer_token_env = "TICKETS_MCP_TOKEN"
+//! header_secrets = { "X-Api-Key" = "mcp/tickets/api_key" }
+//! ```
 
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct StdioServerSpec {
     pub command: String,
     #[serde(default)]
@@ -20,15 +34,88 @@ pub struct StdioServerSpec {
     pub public_key_url: Option<String>,
 }
 
+/// A remote MCP server reached over Streamable HTTP. Credentials are never
+/// written inline: the bearer token (an API key or OAuth access token) and
+/// any secret-valued headers are resolved from the secrets store at connect
+/// time, with an environment-variable fallback for the bearer token.
+#[derive(Debug, Clone, PartialEq, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct HttpServerSpec {
+    /// Streamable HTTP endpoint, e.g. `https://mcp.example.com/mcp`.
+    pub url: String,
+    /// Secret-store key holding the bearer token sent as `Authorization`.
+    #[serde(default)]
+    pub bearer_token_secret: Option<String>,
+    /// Environment variable consulted when the secret store has no token.
+    #[serde(default)]
+    pub bearer_token_env: Option<String>,
+    /// Non-secret headers sent verbatim on every request.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Header name -> secret-store key, for credentials that are not a
+    /// bearer token (e.g. `X-Api-Key`).
+    #[serde(default)]
+    pub header_secrets: HashMap<String, String>,
+    /// URL of the server's SchemaPin public key (PEM); same fail-closed
+    /// semantics as [`StdioServerSpec::public_key_url`].
+    #[serde(default)]
+    pub public_key_url: Option<String>,
+}
+
+/// How to reach a registered MCP server. Entries with `command` are stdio
+/// servers, entries with `url` are Streamable HTTP servers; each spec
+/// rejects the other's fields so an entry cannot silently mean both.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum McpServerSpec {
+    Stdio(StdioServerSpec),
+    Http(HttpServerSpec),
+}
+
+impl McpServerSpec {
+    /// The stdio launch spec, if this is a stdio server.
+    pub fn as_stdio(&self) -> Option<&StdioServerSpec> {
+        match self {
+            Self::Stdio(spec) => Some(spec),
+            Self::Http(_) => None,
+        }
+    }
+
+    /// The HTTP endpoint spec, if this is a remote server.
+    pub fn as_http(&self) -> Option<&HttpServerSpec> {
+        match self {
+            Self::Http(spec) => Some(spec),
+            Self::Stdio(_) => None,
+        }
+    }
+
+    /// SchemaPin public key URL configured for this server, if any.
+    pub fn public_key_url(&self) -> Option<&str> {
+        match self {
+            Self::Stdio(spec) => spec.public_key_url.as_deref(),
+            Self::Http(spec) => spec.public_key_url.as_deref(),
+        }
+    }
+
+    /// Provider identifier used for TOFU key pinning: the command for stdio
+    /// servers, the endpoint URL for remote ones.
+    pub fn provider_identifier(&self) -> &str {
+        match self {
+            Self::Stdio(spec) => &spec.command,
+            Self::Http(spec) => &spec.url,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Deserialize)]
 struct RegistryFile {
     #[serde(default)]
-    servers: HashMap<String, StdioServerSpec>,
+    servers: HashMap<String, McpServerSpec>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct McpServerRegistry {
-    servers: HashMap<String, StdioServerSpec>,
+    servers: HashMap<String, McpServerSpec>,
 }
 
 impl McpServerRegistry {
@@ -65,7 +152,7 @@ impl McpServerRegistry {
         })
     }
 
-    pub fn get(&self, server: &str) -> Option<&StdioServerSpec> {
+    pub fn get(&self, server: &str) -> Option<&McpServerSpec> {
         self.servers.get(server)
     }
 }
@@ -85,11 +172,14 @@ mod tests {
             command = "bare-server"
         "#;
         let reg = McpServerRegistry::from_toml_str(toml).unwrap();
-        let fs = reg.get("fs").expect("fs present");
+        let fs = reg
+            .get("fs")
+            .and_then(|s| s.as_stdio())
+            .expect("fs present");
         assert_eq!(fs.command, "mcp-fs");
         assert_eq!(fs.args, vec!["--root".to_string(), "/tmp".to_string()]);
         assert_eq!(fs.env.get("A").map(String::as_str), Some("1"));
-        let bare = reg.get("bare").unwrap();
+        let bare = reg.get("bare").and_then(|s| s.as_stdio()).unwrap();
         assert!(bare.args.is_empty() && bare.env.is_empty());
         assert!(reg.get("missing").is_none());
     }
@@ -99,4 +189,42 @@ mod tests {
         let reg = McpServerRegistry::from_toml_str("").unwrap();
         assert!(reg.get("anything").is_none());
     }
+
+    #[test]
+    fn parses_http_servers() {
+        let toml = r#"
+            [servers.tickets]
+            url = "https://mcp.example.com/mcp"
+            bearer_token_secret = "mcp/tickets/token"
+            bearer_token_env = "TICKETS_MCP_TOKEN"
+            headers = { "X-Org" = "acme" }
+            header_secrets = { "X-Api-Key" = "mcp/tickets/api_key" }
+            public_key_url = "https://mcp.example.com/.well-known/schemapin.json"
+        "#;
+        let reg = McpServerRegistry::from_toml_str(toml).unwrap();
+        let spec = reg.get("tickets").unwrap();
+        assert!(spec.as_stdio().is_none());
+        assert_eq!(spec.provider_identifier(), "https://mcp.example.com/mcp");
+        let http = spec.as_http().unwrap();
+        assert_eq!(
+            http.bearer_token_secret.as_deref(),
+            Some("mcp/tickets/token")
+        );
+        assert_eq!(http.bearer_token_env.as_deref(), Some("TICKETS_MCP_TOKEN"));
+        assert_eq!(http.headers.get("X-Org").map(String::as_str), Some("acme"));
+        assert_eq!(
+            http.header_secrets.get("X-Api-Key").map(String::as_str),
+            Some("mcp/tickets/api_key")
+        );
+    }
+
+    #[test]
+    fn rejects_entry_with_both_command_and_url() {
+        let toml = r#"
+            [servers.ambiguous]
+            command = "mcp-fs"
+            url = "https://mcp.example.com/mcp"
+        "#;
+        assert!(McpServerRegistry::from_toml_str(toml).is_err());
+    }
 }
@@ -0,0 +1,65 @@
+//! SchemaPin gate for tools discovered from a live MCP server.
+//!
+//! Every transport (one-shot stdio, Streamable HTTP, pooled connections)
+//! routes enforced calls through [`verify_live_tool`] before invoking the
+//! tool, so a schema that changed or a provider key that was swapped since
+//! the last call is caught on the call that would use it.
+
+use super::client::SecureMcpClient;
+use super::types::{McpClientConfig, McpTool, ToolProvider, VerificationStatus};
+
+/// Verify `tool`, as just listed by the server, via [`SecureMcpClient`].
+///
+/// Fails closed: a schema with no embedded SchemaPin signature (a top-level
+/// `signature` field), or a server with no configured public key URL, is
+/// rejected without any network access, since there is nothing to verify
+/// against. Otherwise the provider key is re-pinned (TOFU, rejecting a
+/// swapped key) and the signature checked with
+/// [`SecureMcpClient::reverify_tool`]; any failure blocks the call.
+pub(crate) async fn verify_live_tool(
+    tool: &rmcp::model::Tool,
+    provider_identifier: &str,
+    public_key_url: Option<&str>,
+) -> Result<(), String> {
+    let schema = serde_json::to_value(&*tool.input_schema).map_err(|e| e.to_string())?;
+    let has_signature = schema.get("signature").and_then(|s| s.as_str()).is_some();
+    let public_key_url = public_key_url.unwrap_or_default();
+    if !has_signature || public_key_url.is_empty() {
+        return Err(not_verified(&tool.name));
+    }
+
+    let mcp_tool = McpTool {
+        name: tool.name.to_string(),
+        description: tool
+            .description
+            .clone()
+            .map(|c| c.to_string())
+            .unwrap_or_default(),
+        schema,
+        provider: ToolProvider {
+            identifier: provider_identifier.to_string(),
+            name: provider_identifier.to_string(),
+            public_key_url: public_key_url.to_string(),
+            version: None,
+        },
+        verification_status: VerificationStatus::Pending,
+        metadata: None,
+        sensitive_params: Vec::new(),
+    };
+
+    let client =
+        SecureMcpClient::with_defaults(McpClientConfig::default()).map_err(|e| e.to_string())?;
+    client
+        .reverify_tool(mcp_tool)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("{} ({})", not_verified(&tool.name), e))
+}
+
+fn not_verified(tool: &str) -> String {
+    format!(
+        "tool '{}' is not SchemaPin-verified and enforcement is on (fail-closed). \
+         Sign the tool schema or run with verification disabled for local dev.",
+        tool
+    )
+}
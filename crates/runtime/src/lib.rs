@@ -27,6 +27,7 @@ pub mod secrets;
 #[cfg(feature = "session")]
 pub mod session;
 pub mod skills;
+pub mod telemetry;
 pub mod text_util;
 pub mod toolclad;
 pub mod types;
@@ -43,6 +44,8 @@ pub mod api;
 use api::traits::RuntimeApiProvider;
 #[cfg(all(feature = "http-api", feature = "cron"))]
 use api::types::ScheduleRunEntry;
+#[cfg(all(feature = "http-api", feature = "http-input"))]
+use api::types::WebhookRouteSummary;
 #[cfg(feature = "http-api")]
 use api::types::{
     AddIdentityMappingRequest, AgentExecutionRecord, AgentStatusResponse, ChannelActionResponse,
@@ -83,7 +86,7 @@ pub use scheduler::{
     },
     cron_types::{
         AuditLevel, CronJobDefinition, CronJobId, CronJobStatus, DeliveryChannel, DeliveryConfig,
-        DeliveryReceipt, JobRunRecord, JobRunStatus,
+        DeliveryReceipt, JobRunRecord, JobRunStatus, SmtpTlsMode,
     },
     delivery::{DefaultDeliveryRouter, DeliveryResult, DeliveryRouter},
     heartbeat::{
@@ -188,6 +191,10 @@ pub struct AgentRuntime {
     /// In-memory execution log for agent history tracking.
     #[cfg(feature = "http-api")]
     execution_log: Arc<ExecutionLog>,
+    /// Status of the webhook routes served by the HTTP input server attached
+    /// to this runtime.
+    #[cfg(feature = "http-input")]
+    webhook_routes: Arc<http_input::WebhookRouteRegistry>,
 }
 
 impl AgentRuntime {
@@ -331,6 +338,8 @@ impl AgentRuntime {
             config,
             #[cfg(feature = "http-api")]
             execution_log: Arc::new(ExecutionLog::new(10_000)),
+            #[cfg(feature = "http-input")]
+            webhook_routes: Arc::new(http_input::WebhookRouteRegistry::new()),
         })
     }
 
@@ -344,6 +353,13 @@ impl AgentRuntime {
         self
     }
 
+    /// Registry of webhook routes served by the HTTP input server. Shared
+    /// with every `HttpInputServer` built `with_runtime`.
+    #[cfg(feature = "http-input")]
+    pub fn webhook_routes(&self) -> Arc<http_input::WebhookRouteRegistry> {
+        self.webhook_routes.clone()
+    }
+
     /// Get the current runtime configuration
     pub async fn get_config(&self) -> RuntimeConfig {
         self.config.read().await.clone()
@@ -725,6 +741,15 @@ impl RuntimeApiProvider for AgentRuntime {
         Ok(summaries)
     }
 
+    async fn get_agent_dsl(&self, agent_id: AgentId) -> Result<String, RuntimeError> {
+        self.scheduler
+            .get_agent_config(agent_id)
+            .map(|c| c.dsl_source)
+            .ok_or(RuntimeError::Scheduler(
+                crate::types::SchedulerError::AgentNotFound { agent_id },
+            ))
+    }
+
     async fn shutdown_agent(&self, agent_id: AgentId) -> Result<(), RuntimeError> {
         self.scheduler
             .shutdown_agent(agent_id)
@@ -962,6 +987,22 @@ impl RuntimeApiProvider for AgentRuntime {
         Ok(GetAgentHistoryResponse { history })
     }
 
+    async fn record_execution(
+        &self,
+        agent_id: AgentId,
+        status: &str,
+    ) -> Result<String, RuntimeError> {
+        if !self.scheduler.has_agent(agent_id) {
+            return Err(RuntimeError::Internal(format!(
+                "Agent {} not found",
+                agent_id
+            )));
+        }
+        let execution_id = uuid::Uuid::new_v4().to_string();
+        self.execution_log.record(agent_id, &execution_id, status);
+        Ok(execution_id)
+    }
+
     // ── Schedule endpoints ──────────────────────────────────────────
 
     async fn list_schedules(&self) -> Result<Vec<ScheduleSummary>, RuntimeError> {
@@ -1315,6 +1356,30 @@ impl RuntimeApiProvider for AgentRuntime {
         Ok(vec![])
     }
 
+    #[cfg(feature = "http-input")]
+    async fn list_webhook_routes(&self) -> Result<Vec<WebhookRouteSummary>, RuntimeError> {
+        Ok(self
+            .webhook_routes
+            .snapshot()
+            .into_iter()
+            .map(|route| WebhookRouteSummary {
+                name: route.name,
+                path: route.path,
+                provider: route.provider,
+                agent: route.agent,
+                signature_required: route.signature_required,
+                state: route.state,
+                error: route.error,
+                received: route.received,
+                accepted: route.accepted,
+                filtered: route.filtered,
+                rejected: route.rejected,
+                failed: route.failed,
+                last_request_at: route.last_request_at,
+            })
+            .collect())
+    }
+
     async fn register_channel(
         &self,
         _request: RegisterChannelRequest,
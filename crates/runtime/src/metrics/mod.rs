@@ -4,6 +4,9 @@
 //! - **File**: JSON snapshots written atomically to disk (always available)
 //! - **OTLP**: OpenTelemetry Protocol export via gRPC or HTTP (requires `metrics` feature)
 //!
+//! Runtime and reasoning series are also exposed for scraping in the
+//! OpenMetrics text format by [`prometheus`] (always available).
+//!
 //! Multiple backends can run simultaneously via [`CompositeExporter`].
 
 use async_trait::async_trait;
@@ -14,6 +17,7 @@ use std::sync::Arc;
 use thiserror::Error;
 
 pub mod file;
+pub mod prometheus;
 
 #[cfg(feature = "metrics")]
 pub mod otlp;
@@ -0,0 +1,659 @@
+//! Prometheus exposition in the OpenMetrics text format.
+//!
+//! [`PrometheusRegistry`] holds the series that have no other home —
+//! reasoning loop counters, token usage per agent and model, HTTP request
+//! latency, and the circuit breaker registries in use — and renders them,
+//! together with scheduler, cron and escalation stats gathered at scrape
+//! time ([`ScrapeStats`]), for `GET /metrics`.
+//!
+//! Works without the `metrics` (OTLP) feature. Label cardinality is bounded:
+//! each label dimension admits at most `max_label_values` distinct values
+//! (default [`DEFAULT_MAX_LABEL_VALUES`], overridable with
+//! `SYMBIONT_METRICS_MAX_LABEL_VALUES`); later values are reported as
+//! `other`. HTTP requests are labelled by route template and status class,
+//! never by raw path or status code.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Write as _};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::Duration;
+
+use crate::reasoning::circuit_breaker::{CircuitBreakerRegistry, CircuitState};
+use crate::reasoning::inference::Usage;
+use crate::reasoning::metrics::ReasoningMetrics;
+
+/// `Content-Type` of the rendered exposition.
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Default number of distinct values admitted per label dimension.
+pub const DEFAULT_MAX_LABEL_VALUES: usize = 64;
+
+/// Label value that absorbs everything past the cardinality bound.
+pub const OVERFLOW_LABEL_VALUE: &str = "other";
+
+/// Route label for requests that matched no route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Upper bounds (seconds) of the HTTP request duration histogram buckets.
+const HTTP_DURATION_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+static GLOBAL: OnceLock<PrometheusRegistry> = OnceLock::new();
+
+/// The process-wide registry that reasoning loops, the HTTP API and
+/// `GET /metrics` share.
+pub fn global() -> &'static PrometheusRegistry {
+    GLOBAL.get_or_init(|| {
+        let max_label_values = std::env::var("SYMBIONT_METRICS_MAX_LABEL_VALUES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_LABEL_VALUES);
+        PrometheusRegistry::new(max_label_values)
+    })
+}
+
+/// Agent counts from the scheduler.
+#[derive(Debug, Clone, Default)]
+pub struct AgentCounts {
+    pub running: u64,
+    pub idle: u64,
+    pub error: u64,
+    pub uptime_seconds: u64,
+}
+
+/// Cron scheduler health and run counters (`CronMetrics`).
+#[derive(Debug, Clone, Default)]
+pub struct CronStats {
+    pub is_running: bool,
+    pub jobs_active: u64,
+    pub jobs_paused: u64,
+    pub jobs_dead_letter: u64,
+    pub active_runs: u64,
+    pub max_concurrent: u64,
+    pub runs_total: u64,
+    pub runs_succeeded: u64,
+    pub runs_failed: u64,
+    pub average_execution_time_ms: f64,
+    pub longest_run_ms: u64,
+}
+
+/// Runtime-wide stats read by the caller at scrape time. Sections that are
+/// `None` (no runtime provider, no escalation queue) are left out.
+#[derive(Debug, Clone, Default)]
+pub struct ScrapeStats {
+    pub agents: Option<AgentCounts>,
+    pub cron: Option<CronStats>,
+    pub escalation_queue_depth: Option<u64>,
+}
+
+/// Admits at most `max` distinct values for one label dimension.
+struct BoundedLabel {
+    max: usize,
+    seen: HashSet<String>,
+}
+
+impl BoundedLabel {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            seen: HashSet::new(),
+        }
+    }
+
+    fn admit(&mut self, value: &str) -> String {
+        if self.seen.contains(value) {
+            return value.to_string();
+        }
+        if self.seen.len() < self.max {
+            self.seen.insert(value.to_string());
+            return value.to_string();
+        }
+        OVERFLOW_LABEL_VALUE.to_string()
+    }
+}
+
+#[derive(Default)]
+struct TokenCounts {
+    prompt: u64,
+    completion: u64,
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; HTTP_DURATION_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(HTTP_DURATION_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+struct Series {
+    agents: BoundedLabel,
+    models: BoundedLabel,
+    routes: BoundedLabel,
+    tools: BoundedLabel,
+    /// (agent, model) -> tokens.
+    tokens: BTreeMap<(String, String), TokenCounts>,
+    /// (method, route, status class) -> latency.
+    http: BTreeMap<(&'static str, String, &'static str), Histogram>,
+}
+
+/// In-process store for series rendered at `GET /metrics`.
+pub struct PrometheusRegistry {
+    reasoning: ReasoningMetrics,
+    series: Mutex<Series>,
+    circuit_breakers: Mutex<Vec<Weak<CircuitBreakerRegistry>>>,
+}
+
+impl PrometheusRegistry {
+    /// Create an empty registry admitting `max_label_values` distinct values
+    /// per label dimension.
+    pub fn new(max_label_values: usize) -> Self {
+        Self {
+            reasoning: ReasoningMetrics::new(),
+            series: Mutex::new(Series {
+                agents: BoundedLabel::new(max_label_values),
+                models: BoundedLabel::new(max_label_values),
+                routes: BoundedLabel::new(max_label_values),
+                tools: BoundedLabel::new(max_label_values),
+                tokens: BTreeMap::new(),
+                http: BTreeMap::new(),
+            }),
+            circuit_breakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Reasoning loop counters exported by this registry.
+    pub fn reasoning(&self) -> &ReasoningMetrics {
+        &self.reasoning
+    }
+
+    /// Add one inference call's token usage for `agent` on `model`.
+    pub fn record_token_usage(&self, agent: &str, model: &str, usage: &Usage) {
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (series.agents.admit(agent), series.models.admit(model));
+        let counts = series.tokens.entry(key).or_default();
+        counts.prompt += u64::from(usage.prompt_tokens);
+        counts.completion += u64::from(usage.completion_tokens);
+    }
+
+    /// Record one HTTP request. `route` is the matched route template
+    /// (e.g. `/api/v1/agents/:id`), or `None` if no route matched.
+    pub fn observe_http_request(
+        &self,
+        method: &str,
+        route: Option<&str>,
+        status: u16,
+        elapsed: Duration,
+    ) {
+        let method = match method {
+            "GET" => "GET",
+            "POST" => "POST",
+            "PUT" => "PUT",
+            "DELETE" => "DELETE",
+            "PATCH" => "PATCH",
+            "HEAD" => "HEAD",
+            "OPTIONS" => "OPTIONS",
+            _ => OVERFLOW_LABEL_VALUE,
+        };
+        let status = match status {
+            100..=199 => "1xx",
+            200..=299 => "2xx",
+            300..=399 => "3xx",
+            400..=499 => "4xx",
+            _ => "5xx",
+        };
+        let mut series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+        let route = match route {
+            Some(route) => series.routes.admit(route),
+            None => UNMATCHED_ROUTE.to_string(),
+        };
+        series
+            .http
+            .entry((method, route, status))
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Report the breakers in `registry` while it is alive. Registering the
+    /// same registry again is a no-op.
+    pub fn track_circuit_breakers(&self, registry: &Arc<CircuitBreakerRegistry>) {
+        let mut tracked = self
+            .circuit_breakers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        tracked.retain(|weak| weak.strong_count() > 0);
+        let weak = Arc::downgrade(registry);
+        if !tracked.iter().any(|t| t.ptr_eq(&weak)) {
+            tracked.push(weak);
+        }
+    }
+
+    /// Render every series, plus `stats`, in the OpenMetrics text format.
+    pub async fn render(&self, stats: &ScrapeStats) -> String {
+        let mut out = Exposition::default();
+
+        if let Some(agents) = &stats.agents {
+            out.family("symbi_agents", "gauge", "Agents known to the scheduler.");
+            out.sample("symbi_agents", &[("state", "running")], agents.running);
+            out.sample("symbi_agents", &[("state", "idle")], agents.idle);
+            out.sample("symbi_agents", &[("state", "error")], agents.error);
+            out.family("symbi_uptime_seconds", "gauge", "Runtime uptime.");
+            out.sample("symbi_uptime_seconds", &[], agents.uptime_seconds);
+        }
+
+        if let Some(cron) = &stats.cron {
+            render_cron(&mut out, cron);
+        }
+
+        if let Some(depth) = stats.escalation_queue_depth {
+            out.family(
+                "symbi_escalation_queue_depth",
+                "gauge",
+                "Actions held in the escalation queue awaiting a decision.",
+            );
+            out.sample("symbi_escalation_queue_depth", &[], depth);
+        }
+
+        self.render_reasoning(&mut out);
+        self.render_circuit_breakers(&mut out).await;
+        self.render_series(&mut out);
+
+        out.finish()
+    }
+
+    fn render_reasoning(&self, out: &mut Exposition) {
+        let snap = self.reasoning.snapshot();
+        let counters = [
+            (
+                "symbi_reasoning_loops_started",
+                "Reasoning loops started.",
+                snap.loops_started,
+            ),
+            (
+                "symbi_reasoning_loops_completed",
+                "Reasoning loops that completed normally.",
+                snap.loops_completed,
+            ),
+            (
+                "symbi_reasoning_loops_failed",
+                "Reasoning loops that ended on a limit, denial, timeout or error.",
+                snap.loops_failed,
+            ),
+            (
+                "symbi_reasoning_iterations",
+                "Reasoning loop iterations.",
+                snap.total_iterations,
+            ),
+            (
+                "symbi_reasoning_tool_calls",
+                "Tool calls dispatched by reasoning loops.",
+                snap.tool_calls,
+            ),
+            (
+                "symbi_reasoning_tool_errors",
+                "Tool calls that returned an error.",
+                snap.tool_errors,
+            ),
+            (
+                "symbi_reasoning_policy_denials",
+                "Proposed actions denied by the policy gate.",
+                snap.policy_denials,
+            ),
+            (
+                "symbi_reasoning_inference_attempts",
+                "Calls made to inference providers.",
+                snap.inference_attempts,
+            ),
+            (
+                "symbi_reasoning_inference_retries",
+                "Inference calls retried on the same provider after a transient error.",
+                snap.inference_retries,
+            ),
+            (
+                "symbi_reasoning_provider_failovers",
+                "Switches to the next provider in a failover chain.",
+                snap.provider_failovers,
+            ),
+        ];
+        for (name, help, value) in counters {
+            out.family(name, "counter", help);
+            out.sample(&format!("{name}_total"), &[], value);
+        }
+    }
+
+    async fn render_circuit_breakers(&self, out: &mut Exposition) {
+        let registries: Vec<Arc<CircuitBreakerRegistry>> = self
+            .circuit_breakers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect();
+
+        // Severity per tool; a tool tracked by several registries (or folded
+        // into `other`) reports its worst state.
+        let mut worst: BTreeMap<String, u8> = BTreeMap::new();
+        for registry in registries {
+            for (tool, state) in registry.states().await {
+                let severity = match state {
+                    CircuitState::Closed => 0,
+                    CircuitState::HalfOpen => 1,
+                    CircuitState::Open { .. } => 2,
+                };
+                let tool = self
+                    .series
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .tools
+                    .admit(&tool);
+                let entry = worst.entry(tool).or_default();
+                *entry = (*entry).max(severity);
+            }
+        }
+
+        out.family(
+            "symbi_circuit_breaker_state",
+            "gauge",
+            "Tool circuit breaker state (1 for the current state).",
+        );
+        for (tool, severity) in worst {
+            for (state, value) in [("closed", 0), ("half_open", 1), ("open", 2)] {
+                out.sample(
+                    "symbi_circuit_breaker_state",
+                    &[("tool", &tool), ("state", state)],
+                    u8::from(severity == value),
+                );
+            }
+        }
+    }
+
+    fn render_series(&self, out: &mut Exposition) {
+        let series = self.series.lock().unwrap_or_else(|e| e.into_inner());
+
+        out.family(
+            "symbi_llm_tokens",
+            "counter",
+            "Inference tokens consumed by reasoning loops.",
+        );
+        for ((agent, model), counts) in &series.tokens {
+            for (kind, value) in [("prompt", counts.prompt), ("completion", counts.completion)] {
+                out.sample(
+                    "symbi_llm_tokens_total",
+                    &[("agent", agent), ("model", model), ("kind", kind)],
+                    value,
+                );
+            }
+        }
+
+        out.family(
+            "symbi_http_request_duration_seconds",
+            "histogram",
+            "HTTP API request latency.",
+        );
+        for ((method, route, status), histogram) in &series.http {
+            let labels = [("method", *method), ("route", route), ("status", *status)];
+            for (bound, count) in HTTP_DURATION_BUCKETS.iter().zip(histogram.buckets) {
+                let le = bound.to_string();
+                out.sample(
+                    "symbi_http_request_duration_seconds_bucket",
+                    &[labels[0], labels[1], labels[2], ("le", &le)],
+                    count,
+                );
+            }
+            out.sample(
+                "symbi_http_request_duration_seconds_bucket",
+                &[labels[0], labels[1], labels[2], ("le", "+Inf")],
+                histogram.count,
+            );
+            out.sample(
+                "symbi_http_request_duration_seconds_count",
+                &labels,
+                histogram.count,
+            );
+            out.sample(
+                "symbi_http_request_duration_seconds_sum",
+                &labels,
+                histogram.sum,
+            );
+        }
+    }
+}
+
+fn render_cron(out: &mut Exposition, cron: &CronStats) {
+    out.family(
+        "symbi_cron_running",
+        "gauge",
+        "Whether the cron scheduler is running.",
+    );
+    out.sample("symbi_cron_running", &[], u8::from(cron.is_running));
+
+    out.family("symbi_cron_jobs", "gauge", "Cron jobs by state.");
+    out.sample("symbi_cron_jobs", &[("state", "active")], cron.jobs_active);
+    out.sample("symbi_cron_jobs", &[("state", "paused")], cron.jobs_paused);
+    out.sample(
+        "symbi_cron_jobs",
+        &[("state", "dead_letter")],
+        cron.jobs_dead_letter,
+    );
+
+    out.family(
+        "symbi_cron_active_runs",
+        "gauge",
+        "Cron runs currently executing.",
+    );
+    out.sample("symbi_cron_active_runs", &[], cron.active_runs);
+    out.family(
+        "symbi_cron_max_concurrent",
+        "gauge",
+        "Maximum concurrent cron runs.",
+    );
+    out.sample("symbi_cron_max_concurrent", &[], cron.max_concurrent);
+
+    out.family("symbi_cron_runs", "counter", "Cron runs started.");
+    out.sample("symbi_cron_runs_total", &[], cron.runs_total);
+    out.family(
+        "symbi_cron_runs_succeeded",
+        "counter",
+        "Cron runs that succeeded.",
+    );
+    out.sample("symbi_cron_runs_succeeded_total", &[], cron.runs_succeeded);
+    out.family(
+        "symbi_cron_runs_failed",
+        "counter",
+        "Cron runs that failed.",
+    );
+    out.sample("symbi_cron_runs_failed_total", &[], cron.runs_failed);
+
+    out.family(
+        "symbi_cron_run_duration_average_seconds",
+        "gauge",
+        "Average cron run duration.",
+    );
+    out.sample(
+        "symbi_cron_run_duration_average_seconds",
+        &[],
+        cron.average_execution_time_ms / 1000.0,
+    );
+    out.family(
+        "symbi_cron_run_duration_longest_seconds",
+        "gauge",
+        "Longest cron run duration.",
+    );
+    out.sample(
+        "symbi_cron_run_duration_longest_seconds",
+        &[],
+        cron.longest_run_ms as f64 / 1000.0,
+    );
+}
+
+/// OpenMetrics text writer.
+#[derive(Default)]
+struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# TYPE {name} {kind}");
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            self.out.push('{');
+            for (i, (key, value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.out.push(',');
+                }
+                let _ = write!(self.out, "{key}=\"{}\"", escape_label_value(value));
+            }
+            self.out.push('}');
+        }
+        let _ = writeln!(self.out, " {value}");
+    }
+
+    fn finish(mut self) -> String {
+        self.out.push_str("# EOF\n");
+        self.out
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(prompt: u32, completion: u32) -> Usage {
+        Usage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+            cached_prompt_tokens: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn renders_reasoning_counters_and_token_usage() {
+        let registry = PrometheusRegistry::new(DEFAULT_MAX_LABEL_VALUES);
+        registry.reasoning().record_loop_started();
+        registry.reasoning().record_loop_completed(3, 150);
+        registry.record_token_usage("agent-a", "gpt-4o", &usage(100, 50));
+        registry.record_token_usage("agent-a", "gpt-4o", &usage(10, 5));
+
+        let text = registry.render(&ScrapeStats::default()).await;
+        assert!(text.contains("# TYPE symbi_reasoning_loops_started counter\n"));
+        assert!(text.contains("symbi_reasoning_loops_started_total 1\n"));
+        assert!(text.contains("symbi_reasoning_iterations_total 3\n"));
+        assert!(text.contains(
+            "symbi_llm_tokens_total{agent=\"agent-a\",model=\"gpt-4o\",kind=\"prompt\"} 110\n"
+        ));
+        assert!(text.contains(
+            "symbi_llm_tokens_total{agent=\"agent-a\",model=\"gpt-4o\",kind=\"completion\"} 55\n"
+        ));
+        assert!(text.ends_with("# EOF\n"));
+    }
+
+    #[tokio::test]
+    async fn folds_label_values_past_the_bound_into_other() {
+        let registry = PrometheusRegistry::new(2);
+        for agent in ["a", "b", "c", "d"] {
+            registry.record_token_usage(agent, "m", &usage(1, 0));
+        }
+        registry.record_token_usage("a", "m", &usage(1, 0));
+
+        let text = registry.render(&ScrapeStats::default()).await;
+        assert!(text.contains("{agent=\"a\",model=\"m\",kind=\"prompt\"} 2\n"));
+        assert!(text.contains("{agent=\"b\",model=\"m\",kind=\"prompt\"} 1\n"));
+        assert!(text.contains("{agent=\"other\",model=\"m\",kind=\"prompt\"} 2\n"));
+        assert!(!text.contains("agent=\"c\""));
+    }
+
+    #[tokio::test]
+    async fn http_histogram_is_cumulative_and_labelled_by_template() {
+        let registry = PrometheusRegistry::new(DEFAULT_MAX_LABEL_VALUES);
+        let route = Some("/api/v1/agents/:id");
+        registry.observe_http_request("GET", route, 200, Duration::from_millis(3));
+        registry.observe_http_request("GET", route, 204, Duration::from_millis(300));
+        registry.observe_http_request("BREW", None, 404, Duration::from_millis(1));
+
+        let text = registry.render(&ScrapeStats::default()).await;
+        let labels = "method=\"GET\",route=\"/api/v1/agents/:id\",status=\"2xx\"";
+        assert!(text.contains(&format!(
+            "symbi_http_request_duration_seconds_bucket{{{labels},le=\"0.005\"}} 1\n"
+        )));
+        assert!(text.contains(&format!(
+            "symbi_http_request_duration_seconds_bucket{{{labels},le=\"0.5\"}} 2\n"
+        )));
+        assert!(text.contains(&format!(
+            "symbi_http_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} 2\n"
+        )));
+        assert!(text.contains(&format!(
+            "symbi_http_request_duration_seconds_count{{{labels}}} 2\n"
+        )));
+        assert!(text.contains("method=\"other\",route=\"unmatched\",status=\"4xx\""));
+    }
+
+    #[tokio::test]
+    async fn renders_scrape_stats_and_circuit_breakers() {
+        let registry = PrometheusRegistry::new(DEFAULT_MAX_LABEL_VALUES);
+        let breakers = Arc::new(CircuitBreakerRegistry::default());
+        for _ in 0..5 {
+            breakers.record_failure("flaky").await;
+        }
+        breakers.check("steady").await.unwrap();
+        registry.track_circuit_breakers(&breakers);
+        registry.track_circuit_breakers(&breakers);
+
+        let stats = ScrapeStats {
+            agents: Some(AgentCounts {
+                running: 2,
+                idle: 1,
+                error: 0,
+                uptime_seconds: 60,
+            }),
+            cron: Some(CronStats {
+                is_running: true,
+                runs_total: 4,
+                runs_failed: 1,
+                average_execution_time_ms: 250.0,
+                ..Default::default()
+            }),
+            escalation_queue_depth: Some(3),
+        };
+        let text = registry.render(&stats).await;
+        assert!(text.contains("symbi_agents{state=\"running\"} 2\n"));
+        assert!(text.contains("symbi_cron_running 1\n"));
+        assert!(text.contains("symbi_cron_runs_total 4\n"));
+        assert!(text.contains("symbi_cron_runs_failed_total 1\n"));
+        assert!(text.contains("symbi_cron_run_duration_average_seconds 0.25\n"));
+        assert!(text.contains("symbi_escalation_queue_depth 3\n"));
+        assert!(text.contains("symbi_circuit_breaker_state{tool=\"flaky\",state=\"open\"} 1\n"));
+        assert!(text.contains("symbi_circuit_breaker_state{tool=\"steady\",state=\"closed\"} 1\n"));
+        assert_eq!(text.matches("tool=\"flaky\",state=\"open\"").count(), 1);
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape_label_value("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
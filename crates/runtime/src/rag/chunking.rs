@@ -0,0 +1,329 @@
+//! Document chunking
+//!
+//! Splits document text into chunks according to a [`ChunkingStrategy`].
+//! Offsets in the returned chunks are byte offsets into the original text,
+//! always on `char` boundaries.
+
+use super::types::{ChunkingStrategy, RAGError};
+
+/// A slice of a document produced by a chunking strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChunk {
+    pub content: String,
+    pub start_index: usize,
+    pub end_index: usize,
+}
+
+impl TextChunk {
+    fn from_span(text: &str, start: usize, end: usize) -> Option<Self> {
+        let slice = &text[start..end];
+        let trimmed_start = start + (slice.len() - slice.trim_start().len());
+        let trimmed_end = end - (slice.len() - slice.trim_end().len());
+        if trimmed_start >= trimmed_end {
+            return None;
+        }
+        Some(Self {
+            content: text[trimmed_start..trimmed_end].to_string(),
+            start_index: trimmed_start,
+            end_index: trimmed_end,
+        })
+    }
+}
+
+/// Split `text` into chunks using `strategy`.
+///
+/// Sizes are measured in characters. `Custom` strategies are not
+/// supported by the built-in chunker and return a configuration error.
+pub fn chunk_text(text: &str, strategy: &ChunkingStrategy) -> Result<Vec<TextChunk>, RAGError> {
+    match strategy {
+        ChunkingStrategy::FixedSize { size, overlap } => {
+            if *size == 0 || overlap >= size {
+                return Err(RAGError::ConfigurationError(format!(
+                    "Invalid fixed-size chunking: size {} overlap {}",
+                    size, overlap
+                )));
+            }
+            Ok(fixed_size(text, 0, text.len(), *size, *overlap))
+        }
+        ChunkingStrategy::Paragraph => Ok(paragraph_spans(text)
+            .into_iter()
+            .filter_map(|(s, e)| TextChunk::from_span(text, s, e))
+            .collect()),
+        ChunkingStrategy::Sentence => Ok(sentence_spans(text, 0, text.len())
+            .into_iter()
+            .filter_map(|(s, e)| TextChunk::from_span(text, s, e))
+            .collect()),
+        ChunkingStrategy::Semantic { min_size, max_size } => {
+            if *max_size == 0 || min_size > max_size {
+                return Err(RAGError::ConfigurationError(format!(
+                    "Invalid semantic chunking: min {} max {}",
+                    min_size, max_size
+                )));
+            }
+            Ok(semantic(text, *min_size, *max_size))
+        }
+        ChunkingStrategy::Custom(name) => Err(RAGError::ConfigurationError(format!(
+            "Unsupported chunking strategy: {}",
+            name
+        ))),
+    }
+}
+
+/// Fixed windows of `size` characters over `text[start..end]`, each
+/// starting `size - overlap` characters after the previous one.
+fn fixed_size(text: &str, start: usize, end: usize, size: usize, overlap: usize) -> Vec<TextChunk> {
+    let boundaries: Vec<usize> = text[start..end]
+        .char_indices()
+        .map(|(i, _)| start + i)
+        .chain(std::iter::once(end))
+        .collect();
+    let char_count = boundaries.len() - 1;
+    let step = size - overlap;
+
+    let mut chunks = Vec::new();
+    let mut first = 0;
+    while first < char_count {
+        let last = (first + size).min(char_count);
+        if let Some(chunk) = TextChunk::from_span(text, boundaries[first], boundaries[last]) {
+            chunks.push(chunk);
+        }
+        if last == char_count {
+            break;
+        }
+        first += step;
+    }
+    chunks
+}
+
+/// Byte spans of paragraphs (separated by one or more blank lines).
+fn paragraph_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    let mut blank_run = false;
+    for line in text.split_inclusive('\n') {
+        let is_blank = line.trim().is_empty();
+        if is_blank && !blank_run && offset > start {
+            spans.push((start, offset));
+        }
+        if !is_blank && blank_run {
+            start = offset;
+        }
+        blank_run = is_blank;
+        offset += line.len();
+    }
+    if !blank_run && offset > start {
+        spans.push((start, offset));
+    }
+    spans
+}
+
+/// Byte spans of sentences within `text[start..end]`. A sentence ends at
+/// `.`, `!` or `?` followed by whitespace, or at a line break pair.
+fn sentence_spans(text: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut sentence_start = start;
+    let mut chars = text[start..end].char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let terminal = matches!(c, '.' | '!' | '?')
+            && chars.peek().is_none_or(|(_, next)| next.is_whitespace());
+        let paragraph_break = c == '\n' && chars.peek().is_some_and(|(_, next)| *next == '\n');
+        if terminal || paragraph_break {
+            let sentence_end = start + i + c.len_utf8();
+            spans.push((sentence_start, sentence_end));
+            sentence_start = sentence_end;
+        }
+    }
+    if sentence_start < end {
+        spans.push((sentence_start, end));
+    }
+    spans
+}
+
+/// Pack whole sentences into chunks of at most `max_size` characters,
+/// closing a chunk at a paragraph boundary once it reaches `min_size`.
+/// Sentences longer than `max_size` are split into fixed windows.
+fn semantic(text: &str, min_size: usize, max_size: usize) -> Vec<TextChunk> {
+    let mut chunks = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+
+    let flush = |current: &mut Option<(usize, usize)>, chunks: &mut Vec<TextChunk>| {
+        if let Some((s, e)) = current.take() {
+            chunks.extend(TextChunk::from_span(text, s, e));
+        }
+    };
+
+    for (p_start, p_end) in paragraph_spans(text) {
+        for (s, e) in sentence_spans(text, p_start, p_end) {
+            let sentence_len = text[s..e].trim().chars().count();
+            if sentence_len == 0 {
+                continue;
+            }
+            if sentence_len > max_size {
+                flush(&mut current, &mut chunks);
+                chunks.extend(fixed_size(text, s, e, max_size, 0));
+                continue;
+            }
+            current = match current {
+                Some((cs, _)) if text[cs..e].trim().chars().count() <= max_size => Some((cs, e)),
+                Some(_) => {
+                    flush(&mut current, &mut chunks);
+                    Some((s, e))
+                }
+                None => Some((s, e)),
+            };
+        }
+        if let Some((cs, ce)) = current {
+            if text[cs..ce].trim().chars().count() >= min_size {
+                flush(&mut current, &mut chunks);
+            }
+        }
+    }
+    flush(&mut current, &mut chunks);
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(chunks: &[TextChunk]) -> Vec<&str> {
+        chunks.iter().map(|c| c.content.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fixed_size_with_overlap() {
+        let chunks = chunk_text(
+            "abcdefghij",
+            &ChunkingStrategy::FixedSize {
+                size: 4,
+                overlap: 1,
+            },
+        )
+        .unwrap();
+        assert_eq!(contents(&chunks), vec!["abcd", "defg", "ghij"]);
+        assert_eq!(chunks[1].start_index, 3);
+        assert_eq!(chunks[2].end_index, 10);
+    }
+
+    #[test]
+    fn test_fixed_size_respects_char_boundaries() {
+        let text = "héllo wörld";
+        let chunks = chunk_text(
+            text,
+            &ChunkingStrategy::FixedSize {
+                size: 3,
+                overlap: 0,
+            },
+        )
+        .unwrap();
+        for chunk in &chunks {
+            assert_eq!(&text[chunk.start_index..chunk.end_index], chunk.content);
+        }
+        assert_eq!(chunks[0].content, "hél");
+    }
+
+    #[test]
+    fn test_fixed_size_rejects_overlap_ge_size() {
+        assert!(chunk_text(
+            "text",
+            &ChunkingStrategy::FixedSize {
+                size: 2,
+                overlap: 2
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_paragraph_split() {
+        let text = "First paragraph\nstill first.\n\n\nSecond one.\n   \nThird.";
+        let chunks = chunk_text(text, &ChunkingStrategy::Paragraph).unwrap();
+        assert_eq!(
+            contents(&chunks),
+            vec!["First paragraph\nstill first.", "Second one.", "Third."]
+        );
+        for chunk in &chunks {
+            assert_eq!(&text[chunk.start_index..chunk.end_index], chunk.content);
+        }
+    }
+
+    #[test]
+    fn test_sentence_split() {
+        let text = "Rust is fast. Is it safe? Yes! Version 1.75 shipped";
+        let chunks = chunk_text(text, &ChunkingStrategy::Sentence).unwrap();
+        assert_eq!(
+            contents(&chunks),
+            vec![
+                "Rust is fast.",
+                "Is it safe?",
+                "Yes!",
+                "Version 1.75 shipped"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_semantic_packs_sentences_up_to_max() {
+        let text = "One two. Three four. Five six.\n\nSeven eight. Nine ten.";
+        let chunks = chunk_text(
+            text,
+            &ChunkingStrategy::Semantic {
+                min_size: 5,
+                max_size: 20,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            contents(&chunks),
+            vec![
+                "One two. Three four.",
+                "Five six.",
+                "Seven eight.",
+                "Nine ten."
+            ]
+        );
+        assert!(chunks.iter().all(|c| c.content.chars().count() <= 20));
+    }
+
+    #[test]
+    fn test_semantic_merges_small_paragraphs_until_min() {
+        let text = "Tiny.\n\nAlso tiny.\n\nA much longer closing paragraph.";
+        let chunks = chunk_text(
+            text,
+            &ChunkingStrategy::Semantic {
+                min_size: 12,
+                max_size: 200,
+            },
+        )
+        .unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].content.starts_with("Tiny."));
+        assert!(chunks[0].content.ends_with("Also tiny."));
+    }
+
+    #[test]
+    fn test_semantic_splits_oversized_sentence() {
+        let text = "abcdefghijklmnopqrstuvwxyz";
+        let chunks = chunk_text(
+            text,
+            &ChunkingStrategy::Semantic {
+                min_size: 1,
+                max_size: 10,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            contents(&chunks),
+            vec!["abcdefghij", "klmnopqrst", "uvwxyz"]
+        );
+    }
+
+    #[test]
+    fn test_custom_strategy_unsupported() {
+        assert!(matches!(
+            chunk_text("x", &ChunkingStrategy::Custom("markdown".into())),
+            Err(RAGError::ConfigurationError(_))
+        ));
+    }
+}
@@ -3,11 +3,13 @@
 //! This module provides the RAG engine implementation for the Symbiont Agent Runtime.
 //! It includes query analysis, document retrieval, ranking, and response generation capabilities.
 
+pub mod chunking;
 pub mod engine;
 pub mod types;
 
 #[cfg(test)]
 mod tests;
 
-pub use engine::{RAGEngine, StandardRAGEngine};
+pub use chunking::{chunk_text, TextChunk};
+pub use engine::{default_corpus_id, RAGEngine, StandardRAGEngine};
 pub use types::*;
@@ -4,11 +4,81 @@
 mod rag_tests {
     use super::super::*;
     use crate::context::manager::{ContextManager, ContextManagerConfig, StandardContextManager};
+    use crate::context::types::ContextError;
+    use crate::context::vector_db::EmbeddingService;
     use crate::types::AgentId;
+    use async_trait::async_trait;
     use std::sync::Arc;
-    use std::time::Duration;
+    use std::time::{Duration, SystemTime};
     use tokio;
 
+    const TEST_DIMENSION: usize = 64;
+
+    /// Deterministic bag-of-words embedding: each word is hashed into a
+    /// bucket, so texts sharing words have high cosine similarity.
+    struct WordHashEmbedding;
+
+    #[async_trait]
+    impl EmbeddingService for WordHashEmbedding {
+        async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>, ContextError> {
+            let mut embedding = vec![0.0; TEST_DIMENSION];
+            for word in text
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|w| w.len() > 2)
+            {
+                let bucket = word
+                    .bytes()
+                    .fold(7usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+                embedding[bucket % TEST_DIMENSION] += 1.0;
+            }
+            let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if norm > 0.0 {
+                embedding.iter_mut().for_each(|x| *x /= norm);
+            }
+            Ok(embedding)
+        }
+
+        async fn generate_batch_embeddings(
+            &self,
+            texts: Vec<&str>,
+        ) -> Result<Vec<Vec<f32>>, ContextError> {
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for text in texts {
+                embeddings.push(self.generate_embedding(text).await?);
+            }
+            Ok(embeddings)
+        }
+
+        fn embedding_dimension(&self) -> usize {
+            TEST_DIMENSION
+        }
+
+        fn max_text_length(&self) -> usize {
+            8192
+        }
+    }
+
+    fn document_input(title: &str, content: &str) -> DocumentInput {
+        DocumentInput {
+            title: title.to_string(),
+            content: content.to_string(),
+            metadata: DocumentMetadata {
+                document_type: DocumentType::Text,
+                author: Some("tests".to_string()),
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+                language: "en".to_string(),
+                domain: "testing".to_string(),
+                access_level: AccessLevel::Public,
+                tags: vec![],
+                source_url: None,
+                file_path: None,
+            },
+            chunking_strategy: ChunkingStrategy::Paragraph,
+        }
+    }
+
     async fn create_test_context_manager() -> Arc<dyn ContextManager> {
         let config = ContextManagerConfig::default();
         let manager = StandardContextManager::new(config, "test-agent")
@@ -17,6 +87,7 @@ mod rag_tests {
         Arc::new(manager)
     }
 
+    #[cfg(feature = "vector-lancedb")]
     fn create_test_rag_request() -> RAGRequest {
         RAGRequest {
             agent_id: AgentId::new(),
@@ -61,7 +132,13 @@ mod rag_tests {
         assert_eq!(analyzed.original_query, query);
         assert!(!analyzed.keywords.is_empty());
         assert_eq!(analyzed.intent, QueryIntent::Procedural);
-        assert!(!analyzed.embeddings.is_empty());
+        // No embedding service configured
+        assert!(analyzed.embeddings.is_empty());
+
+        let rag_engine = StandardRAGEngine::new(create_test_context_manager().await)
+            .with_embedding_service(Arc::new(WordHashEmbedding));
+        let analyzed = rag_engine.analyze_query(query, None).await.unwrap();
+        assert_eq!(analyzed.embeddings.len(), TEST_DIMENSION);
     }
 
     #[tokio::test]
@@ -99,154 +176,92 @@ mod rag_tests {
     }
 
     #[tokio::test]
-    async fn test_document_retrieval() {
+    async fn test_retrieval_requires_backends() {
         let context_manager = create_test_context_manager().await;
         let rag_engine = StandardRAGEngine::new(context_manager);
 
-        let analyzed_query = AnalyzedQuery {
-            original_query: "machine learning".to_string(),
-            expanded_terms: vec![
-                "machine".to_string(),
-                "learning".to_string(),
-                "ML".to_string(),
-            ],
-            intent: QueryIntent::Factual,
-            entities: vec![],
-            keywords: vec!["machine".to_string(), "learning".to_string()],
-            embeddings: vec![0.1, 0.2, 0.3],
-            context_keywords: vec!["machine".to_string(), "learning".to_string()],
-        };
-
-        let result = rag_engine.retrieve_documents(&analyzed_query).await;
-        assert!(result.is_ok());
-
-        let documents = result.unwrap();
-        assert!(!documents.is_empty());
-        assert!(documents.len() <= 10); // Should respect max documents limit
-    }
-
-    #[tokio::test]
-    async fn test_document_ranking() {
-        let context_manager = create_test_context_manager().await;
-        let rag_engine = StandardRAGEngine::new(context_manager);
-
-        let analyzed_query = AnalyzedQuery {
-            original_query: "machine learning".to_string(),
-            expanded_terms: vec!["machine".to_string(), "learning".to_string()],
-            intent: QueryIntent::Factual,
-            entities: vec![],
-            keywords: vec!["machine".to_string(), "learning".to_string()],
-            embeddings: vec![0.1, 0.2, 0.3],
-            context_keywords: vec!["machine".to_string(), "learning".to_string()],
-        };
-
-        // First retrieve documents
-        let documents = rag_engine
-            .retrieve_documents(&analyzed_query)
+        let analyzed_query = rag_engine
+            .analyze_query("machine learning", None)
             .await
             .unwrap();
+        let result = rag_engine.retrieve_documents(&analyzed_query).await;
+        assert!(matches!(result, Err(RAGError::ConfigurationError(_))));
 
-        // Then rank them
-        let result = rag_engine.rank_documents(documents, &analyzed_query).await;
-        assert!(result.is_ok());
-
-        let ranked_docs = result.unwrap();
-        assert!(!ranked_docs.is_empty());
-
-        // Check that documents are sorted by relevance (highest first)
-        for i in 1..ranked_docs.len() {
-            assert!(ranked_docs[i - 1].relevance_score >= ranked_docs[i].relevance_score);
-        }
-
-        // Check that ranking factors are present
-        for doc in &ranked_docs {
-            assert!(doc.ranking_factors.semantic_similarity >= 0.0);
-            assert!(doc.ranking_factors.keyword_match >= 0.0);
-            assert!(doc.ranking_factors.recency_score >= 0.0);
-            assert!(doc.ranking_factors.authority_score >= 0.0);
-        }
+        let result = rag_engine
+            .ingest_documents(vec![document_input("ML", "Machine learning.")])
+            .await;
+        assert!(matches!(result, Err(RAGError::ConfigurationError(_))));
     }
 
     #[tokio::test]
-    async fn test_context_augmentation() {
+    async fn test_response_generation_without_documents() {
         let context_manager = create_test_context_manager().await;
         let rag_engine = StandardRAGEngine::new(context_manager);
 
-        let analyzed_query = AnalyzedQuery {
-            original_query: "machine learning".to_string(),
-            expanded_terms: vec!["machine".to_string(), "learning".to_string()],
-            intent: QueryIntent::Factual,
-            entities: vec![],
-            keywords: vec!["machine".to_string(), "learning".to_string()],
-            embeddings: vec![0.1, 0.2, 0.3],
-            context_keywords: vec!["machine".to_string(), "learning".to_string()],
-        };
-
-        // Get documents and rank them
-        let documents = rag_engine
-            .retrieve_documents(&analyzed_query)
+        let analyzed_query = rag_engine
+            .analyze_query("quantum gravity", None)
             .await
             .unwrap();
-        let ranked_docs = rag_engine
-            .rank_documents(documents, &analyzed_query)
+        let augmented = rag_engine
+            .augment_context(&analyzed_query, vec![])
             .await
             .unwrap();
+        let response = rag_engine.generate_response(augmented).await.unwrap();
 
-        // Test context augmentation
-        let result = rag_engine
-            .augment_context(&analyzed_query, ranked_docs)
-            .await;
-        assert!(result.is_ok());
-
-        let augmented = result.unwrap();
-        assert_eq!(augmented.original_query, analyzed_query.original_query);
-        assert!(!augmented.context_summary.is_empty());
-        assert!(!augmented.citations.is_empty());
-        assert_eq!(
-            augmented.retrieved_documents.len(),
-            augmented.citations.len()
-        );
+        assert!(response.content.contains("couldn't find"));
+        assert_eq!(response.confidence, 0.0);
+        assert!(response.citations.is_empty());
     }
 
     #[tokio::test]
-    async fn test_response_generation() {
+    async fn test_initialize_rejects_dimension_mismatch() {
         let context_manager = create_test_context_manager().await;
-        let rag_engine = StandardRAGEngine::new(context_manager);
+        let rag_engine = StandardRAGEngine::new(context_manager)
+            .with_embedding_service(Arc::new(WordHashEmbedding));
 
-        let analyzed_query = AnalyzedQuery {
-            original_query: "machine learning".to_string(),
-            expanded_terms: vec!["machine".to_string(), "learning".to_string()],
-            intent: QueryIntent::Factual,
-            entities: vec![],
-            keywords: vec!["machine".to_string(), "learning".to_string()],
-            embeddings: vec![0.1, 0.2, 0.3],
-            context_keywords: vec!["machine".to_string(), "learning".to_string()],
-        };
-
-        // Create augmented context
-        let documents = rag_engine
-            .retrieve_documents(&analyzed_query)
-            .await
-            .unwrap();
-        let ranked_docs = rag_engine
-            .rank_documents(documents, &analyzed_query)
-            .await
-            .unwrap();
-        let augmented_context = rag_engine
-            .augment_context(&analyzed_query, ranked_docs)
-            .await
-            .unwrap();
-
-        // Test response generation
-        let result = rag_engine.generate_response(augmented_context).await;
-        assert!(result.is_ok());
+        let mut config = test_config();
+        config.embedding_model.dimension = TEST_DIMENSION + 1;
+        let result = rag_engine.initialize(config).await;
+        assert!(matches!(result, Err(RAGError::ConfigurationError(_))));
+    }
 
-        let response = result.unwrap();
-        assert!(!response.content.is_empty());
-        assert!(response.confidence > 0.0);
-        assert!(response.confidence <= 1.0);
-        assert!(!response.citations.is_empty());
-        assert_eq!(response.validation_status, ValidationStatus::Pending);
+    fn test_config() -> RAGConfig {
+        RAGConfig {
+            embedding_model: EmbeddingModelConfig {
+                model_name: "word-hash".to_string(),
+                model_type: EmbeddingModelType::Local,
+                dimension: TEST_DIMENSION,
+                max_tokens: 512,
+                batch_size: 8,
+            },
+            retrieval_config: RetrievalConfig {
+                max_documents: 5,
+                similarity_threshold: 0.0,
+                context_window: 4096,
+                enable_hybrid_search: false,
+                reranking_enabled: false,
+            },
+            ranking_config: RankingConfig {
+                ranking_algorithm: RankingAlgorithm::Hybrid,
+                relevance_weight: 0.4,
+                recency_weight: 0.2,
+                authority_weight: 0.2,
+                diversity_weight: 0.2,
+            },
+            generation_config: GenerationConfig {
+                max_response_length: 512,
+                temperature: 0.2,
+                top_p: 0.9,
+                enable_citations: true,
+                response_format: ResponseFormat::Text,
+            },
+            validation_config: ValidationConfig {
+                enable_policy_check: true,
+                enable_content_filter: true,
+                enable_fact_check: false,
+                confidence_threshold: 0.5,
+            },
+        }
     }
 
     #[tokio::test]
@@ -280,20 +295,42 @@ mod rag_tests {
     }
 
     #[tokio::test]
-    async fn test_full_rag_pipeline() {
+    async fn test_response_validation_reports_content_issues() {
         let context_manager = create_test_context_manager().await;
         let rag_engine = StandardRAGEngine::new(context_manager);
+        rag_engine.initialize(test_config()).await.unwrap();
 
-        let request = create_test_rag_request();
-
-        let result = rag_engine.process_query(request).await;
-        assert!(result.is_ok());
+        let response = GeneratedResponse {
+            content: " ".to_string(),
+            confidence: 0.2,
+            citations: vec![],
+            metadata: ResponseMetadata {
+                generation_time: Duration::from_millis(100),
+                tokens_used: 0,
+                sources_consulted: 0,
+                model_version: "test-v1.0".to_string(),
+            },
+            validation_status: ValidationStatus::Pending,
+        };
 
-        let response = result.unwrap();
-        assert!(!response.response.content.is_empty());
-        assert!(response.confidence_score > 0.0);
-        assert!(response.processing_time > Duration::from_millis(0));
-        assert!(!response.follow_up_suggestions.is_empty());
+        let validation = rag_engine
+            .validate_response(&response, AgentId::new())
+            .await
+            .unwrap();
+        // Content issues are reported without failing the response
+        assert!(validation.is_valid);
+        assert!(validation.policy_violations.is_empty());
+        assert_eq!(validation.content_issues.len(), 2);
+        assert!(matches!(
+            validation.content_issues[0].issue_type,
+            ContentIssueType::Inconsistency
+        ));
+        assert!(matches!(
+            validation.content_issues[1].issue_type,
+            ContentIssueType::Factual
+        ));
+        assert_eq!(validation.confidence_score, 0.2);
+        assert_eq!(validation.recommendations.len(), 1);
     }
 
     #[tokio::test]
@@ -350,21 +387,6 @@ mod rag_tests {
         assert!((sim2 - 0.0).abs() < 0.001);
     }
 
-    #[tokio::test]
-    async fn test_mock_embeddings_generation() {
-        let context_manager = create_test_context_manager().await;
-        let rag_engine = StandardRAGEngine::new(context_manager);
-
-        let text = "test text for embeddings";
-        let embeddings = rag_engine.generate_mock_embeddings(text);
-
-        assert_eq!(embeddings.len(), 384); // Standard embedding dimension
-
-        // Check normalization - vector should have unit length
-        let norm: f32 = embeddings.iter().map(|x| x * x).sum::<f32>().sqrt();
-        assert!((norm - 1.0).abs() < 0.001);
-    }
-
     #[tokio::test]
     async fn test_rag_stats() {
         let context_manager = create_test_context_manager().await;
@@ -379,4 +401,340 @@ mod rag_tests {
         assert_eq!(stats.cache_hit_rate, 0.0);
         assert_eq!(stats.validation_pass_rate, 0.0);
     }
+
+    #[cfg(feature = "vector-lancedb")]
+    mod lancedb_backed {
+        use super::*;
+        use crate::context::vector_db_lance::{LanceDbBackend, LanceDbConfig};
+        use crate::context::vector_db_trait::{DistanceMetric, VectorDb};
+        use crate::reasoning::conversation::Conversation;
+        use crate::reasoning::inference::{
+            FinishReason, InferenceError, InferenceOptions, InferenceProvider, InferenceResponse,
+            Usage,
+        };
+        use std::sync::Mutex;
+        use tempfile::TempDir;
+
+        async fn create_engine(tmp: &TempDir) -> StandardRAGEngine {
+            let vector_db: Arc<dyn VectorDb> = Arc::new(
+                LanceDbBackend::new(LanceDbConfig {
+                    data_path: tmp.path().to_path_buf(),
+                    collection_name: "rag_test".to_string(),
+                    vector_dimension: TEST_DIMENSION,
+                    distance_metric: DistanceMetric::Cosine,
+                })
+                .await
+                .unwrap(),
+            );
+            let engine = StandardRAGEngine::new(create_test_context_manager().await)
+                .with_vector_db(vector_db)
+                .with_embedding_service(Arc::new(WordHashEmbedding));
+            engine.initialize(test_config()).await.unwrap();
+            engine
+        }
+
+        async fn ingest_corpus(engine: &StandardRAGEngine) -> Vec<DocumentId> {
+            engine
+                .ingest_documents(vec![
+                    document_input(
+                        "Rust ownership",
+                        "Rust ownership rules govern memory.\n\n\
+                         Every value in Rust has a single owner.",
+                    ),
+                    document_input(
+                        "Sourdough baking",
+                        "Sourdough bread needs a starter culture.\n\n\
+                         Bake sourdough in a hot oven.",
+                    ),
+                    document_input(
+                        "Tidal energy",
+                        "Tidal turbines convert ocean currents into electricity.",
+                    ),
+                ])
+                .await
+                .unwrap()
+        }
+
+        /// Answers with a fixed string and records the user prompt.
+        struct RecordingProvider {
+            answer: String,
+            prompts: Mutex<Vec<String>>,
+        }
+
+        #[async_trait]
+        impl InferenceProvider for RecordingProvider {
+            async fn complete(
+                &self,
+                conversation: &Conversation,
+                _options: &InferenceOptions,
+            ) -> Result<InferenceResponse, InferenceError> {
+                let prompt = conversation
+                    .messages()
+                    .last()
+                    .map(|m| m.content.clone())
+                    .unwrap_or_default();
+                self.prompts.lock().unwrap().push(prompt);
+                Ok(InferenceResponse {
+                    content: self.answer.clone(),
+                    tool_calls: vec![],
+                    finish_reason: FinishReason::Stop,
+                    usage: Usage {
+                        prompt_tokens: 40,
+                        completion_tokens: 10,
+                        total_tokens: 50,
+                        cached_prompt_tokens: 0,
+                    },
+                    model: "recording".into(),
+                })
+            }
+
+            fn provider_name(&self) -> &str {
+                "recording"
+            }
+            fn default_model(&self) -> &str {
+                "recording"
+            }
+            fn supports_native_tools(&self) -> bool {
+                false
+            }
+            fn supports_structured_output(&self) -> bool {
+                false
+            }
+        }
+
+        #[tokio::test]
+        async fn test_ingest_and_retrieve_relevant_document() {
+            let tmp = TempDir::new().unwrap();
+            let engine = create_engine(&tmp).await;
+            let ids = ingest_corpus(&engine).await;
+            assert_eq!(ids.len(), 3);
+            assert_eq!(engine.get_stats().await.unwrap().total_documents, 3);
+
+            let query = engine
+                .analyze_query("How does sourdough bread baking work?", None)
+                .await
+                .unwrap();
+            let documents = engine.retrieve_documents(&query).await.unwrap();
+            assert!(!documents.is_empty());
+            assert_eq!(documents[0].id, ids[1]);
+            // Both paragraphs of the document are merged back together
+            assert_eq!(documents[0].chunks.len(), 2);
+            assert!(documents[0].content.contains("starter culture"));
+            assert!(documents[0].content.contains("hot oven"));
+            assert_eq!(documents[0].metadata.author.as_deref(), Some("tests"));
+
+            let ranked = engine.rank_documents(documents, &query).await.unwrap();
+            for pair in ranked.windows(2) {
+                assert!(pair[0].relevance_score >= pair[1].relevance_score);
+            }
+            assert_eq!(ranked[0].document.id, ids[1]);
+            assert_eq!(ranked[0].selected_chunks.len(), 2);
+        }
+
+        #[tokio::test]
+        async fn test_extractive_pipeline_cites_sources() {
+            let tmp = TempDir::new().unwrap();
+            let engine = create_engine(&tmp).await;
+            let ids = ingest_corpus(&engine).await;
+
+            let mut request = create_test_rag_request();
+            request.query = "What do tidal turbines convert into electricity?".to_string();
+            request.constraints.max_documents = 2;
+
+            let response = engine.process_query(request).await.unwrap();
+            assert_eq!(response.response.metadata.model_version, "extractive");
+            assert!(response.response.content.contains("Tidal turbines"));
+            assert!(!response.sources_used.is_empty());
+            assert!(response.response.metadata.sources_consulted <= 2);
+            assert_eq!(response.sources_used[0].document_id, ids[2]);
+            assert!(response.confidence_score > 0.0);
+            assert!(response.confidence_score <= 1.0);
+
+            let stats = engine.get_stats().await.unwrap();
+            assert_eq!(stats.total_queries, 1);
+        }
+
+        #[tokio::test]
+        async fn test_inference_provider_grounded_answer() {
+            let tmp = TempDir::new().unwrap();
+            let provider = Arc::new(RecordingProvider {
+                answer: "Every value has a single owner [1].".to_string(),
+                prompts: Mutex::new(vec![]),
+            });
+            let engine = create_engine(&tmp)
+                .await
+                .with_inference_provider(provider.clone());
+            let ids = ingest_corpus(&engine).await;
+
+            let mut request = create_test_rag_request();
+            request.query = "Who owns a value in Rust ownership rules?".to_string();
+            let response = engine.process_query(request).await.unwrap();
+
+            assert_eq!(
+                response.response.content,
+                "Every value has a single owner [1]."
+            );
+            assert_eq!(response.response.metadata.model_version, "recording");
+            assert_eq!(response.response.metadata.tokens_used, 50);
+            // Only the cited source is reported
+            assert_eq!(response.sources_used.len(), 1);
+            assert_eq!(response.sources_used[0].document_id, ids[0]);
+
+            let prompts = provider.prompts.lock().unwrap();
+            assert_eq!(prompts.len(), 1);
+            assert!(prompts[0].contains("[1] Rust ownership"));
+            assert!(prompts[0].contains("Question: Who owns a value"));
+        }
+
+        #[tokio::test]
+        async fn test_context_window_limits_augmented_chunks() {
+            let tmp = TempDir::new().unwrap();
+            let engine = create_engine(&tmp).await;
+            let mut config = test_config();
+            config.retrieval_config.context_window = 1;
+            engine.initialize(config).await.unwrap();
+            ingest_corpus(&engine).await;
+
+            let query = engine
+                .analyze_query("sourdough rust tidal", None)
+                .await
+                .unwrap();
+            let documents = engine.retrieve_documents(&query).await.unwrap();
+            let ranked = engine.rank_documents(documents, &query).await.unwrap();
+            assert!(ranked.len() > 1);
+
+            let augmented = engine.augment_context(&query, ranked).await.unwrap();
+            // The first chunk is always kept, nothing else fits
+            assert_eq!(augmented.retrieved_documents.len(), 1);
+            assert_eq!(augmented.retrieved_documents[0].selected_chunks.len(), 1);
+            assert_eq!(augmented.citations.len(), 1);
+        }
+
+        #[tokio::test]
+        async fn test_update_and_delete_document() {
+            let tmp = TempDir::new().unwrap();
+            let engine = create_engine(&tmp).await;
+            let ids = ingest_corpus(&engine).await;
+
+            engine
+                .update_document(
+                    ids[2],
+                    document_input("Geothermal energy", "Geothermal plants tap volcanic heat."),
+                )
+                .await
+                .unwrap();
+            let query = engine
+                .analyze_query("volcanic geothermal heat", None)
+                .await
+                .unwrap();
+            let documents = engine.retrieve_documents(&query).await.unwrap();
+            assert_eq!(documents[0].id, ids[2]);
+            assert_eq!(documents[0].title, "Geothermal energy");
+            assert!(documents.iter().all(|d| !d.content.contains("Tidal")));
+
+            engine.delete_document(ids[2]).await.unwrap();
+            let documents = engine.retrieve_documents(&query).await.unwrap();
+            assert!(documents.iter().all(|d| d.id != ids[2]));
+            assert_eq!(engine.get_stats().await.unwrap().total_documents, 2);
+
+            assert!(engine.delete_document(ids[2]).await.is_err());
+            assert!(engine
+                .update_document(DocumentId::new(), document_input("x", "y"))
+                .await
+                .is_err());
+        }
+
+        #[tokio::test]
+        async fn test_documents_survive_engine_restart() {
+            let tmp = TempDir::new().unwrap();
+            let ids = ingest_corpus(&create_engine(&tmp).await).await;
+
+            // A fresh engine over the same store finds documents by the id
+            // kept in their vector metadata.
+            let engine = create_engine(&tmp).await;
+            engine
+                .update_document(
+                    ids[0],
+                    document_input("Rust borrowing", "Borrowing lends a reference."),
+                )
+                .await
+                .unwrap();
+            let query = engine
+                .analyze_query("Rust borrowing reference", None)
+                .await
+                .unwrap();
+            let documents = engine.retrieve_documents(&query).await.unwrap();
+            assert_eq!(documents[0].id, ids[0]);
+            assert_eq!(documents[0].title, "Rust borrowing");
+            assert!(documents
+                .iter()
+                .all(|d| !d.content.contains("single owner")));
+
+            engine.delete_document(ids[1]).await.unwrap();
+            let query = engine
+                .analyze_query("sourdough starter", None)
+                .await
+                .unwrap();
+            let documents = engine.retrieve_documents(&query).await.unwrap();
+            assert!(documents.iter().all(|d| d.id != ids[1]));
+            assert!(engine.delete_document(ids[1]).await.is_err());
+        }
+
+        #[tokio::test]
+        async fn test_hybrid_retrieval_surfaces_keyword_matches() {
+            use crate::context::keyword_index::Bm25Index;
+
+            let tmp = TempDir::new().unwrap();
+            let index_path = tmp.path().join("rag.bm25.json");
+            let index = Arc::new(Bm25Index::open(&index_path).unwrap());
+            let engine = create_engine(&tmp).await.with_keyword_index(index.clone());
+            let mut config = test_config();
+            // No vector hit can clear this bar; only BM25 can contribute
+            config.retrieval_config.similarity_threshold = 0.999;
+            config.retrieval_config.enable_hybrid_search = true;
+            engine.initialize(config).await.unwrap();
+            let ids = ingest_corpus(&engine).await;
+            assert_eq!(index.document_count(&default_corpus_id().to_string()), 5);
+            assert!(index_path.exists());
+
+            let query = engine
+                .analyze_query("turbines electricity", None)
+                .await
+                .unwrap();
+            let documents = engine.retrieve_documents(&query).await.unwrap();
+            assert_eq!(documents.len(), 1);
+            assert_eq!(documents[0].id, ids[2]);
+            assert_eq!(documents[0].title, "Tidal energy");
+            assert_eq!(documents[0].metadata.author.as_deref(), Some("tests"));
+
+            let ranked = engine.rank_documents(documents, &query).await.unwrap();
+            assert_eq!(ranked[0].ranking_factors.keyword_match, 1.0);
+
+            engine.delete_document(ids[2]).await.unwrap();
+            assert!(engine.retrieve_documents(&query).await.unwrap().is_empty());
+            let reopened = Bm25Index::open(&index_path).unwrap();
+            assert_eq!(reopened.document_count(&default_corpus_id().to_string()), 4);
+        }
+
+        #[tokio::test]
+        async fn test_bm25_ranking_prefers_rare_terms() {
+            let tmp = TempDir::new().unwrap();
+            let engine = create_engine(&tmp).await;
+            let mut config = test_config();
+            config.ranking_config.ranking_algorithm = RankingAlgorithm::BM25;
+            engine.initialize(config).await.unwrap();
+            ingest_corpus(&engine).await;
+
+            // "rust" appears in one document, "bread" in another; "sourdough"
+            // (three mentions) should dominate
+            let query = engine.analyze_query("sourdough rust", None).await.unwrap();
+            let documents = engine.retrieve_documents(&query).await.unwrap();
+            let ranked = engine.rank_documents(documents, &query).await.unwrap();
+            assert_eq!(ranked[0].document.title, "Sourdough baking");
+            assert_eq!(ranked[0].relevance_score, 1.0);
+            assert!(ranked
+                .iter()
+                .all(|d| d.relevance_score == d.ranking_factors.keyword_match));
+        }
+    }
 }
@@ -240,6 +240,15 @@ impl CircuitBreakerRegistry {
         let breakers = self.breakers.read().await;
         breakers.get(tool_name).map(|b| b.state().clone())
     }
+
+    /// Get the state of every breaker, keyed by tool name.
+    pub async fn states(&self) -> Vec<(String, CircuitState)> {
+        let breakers = self.breakers.read().await;
+        breakers
+            .iter()
+            .map(|(name, b)| (name.clone(), b.state().clone()))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -2,12 +2,23 @@
 //!
 //! Provides a `Conversation` type that manages a sequence of messages
 //! across System, User, Assistant, ToolCall, and ToolResult roles.
-//! Supports serialization to OpenAI and Anthropic API formats and
-//! token estimation for context window management.
+//! Messages may carry typed content parts (text, images, documents) for
+//! vision-capable models. Supports serialization to OpenAI and Anthropic
+//! API formats and token estimation for context window management.
 
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Estimated token cost of one image. Anthropic caps a resized image at
+/// about 1,600 tokens and OpenAI's high-detail tiling lands in the same
+/// range, so budgets assume the ceiling rather than decoding dimensions.
+const IMAGE_TOKEN_ESTIMATE: usize = 1_600;
+
+/// Estimated token cost of one page of a binary document (providers send
+/// each PDF page as extracted text plus a page image).
+const DOCUMENT_PAGE_TOKEN_ESTIMATE: usize = 2_000;
+
 /// Role of a message in a conversation.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -29,6 +40,184 @@ pub struct ToolCall {
     pub arguments: String,
 }
 
+/// Where the bytes of an image or document part come from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MediaSource {
+    /// Inline bytes, base64-encoded.
+    Base64 { data: String },
+    /// A URL the provider fetches itself.
+    Url { url: String },
+}
+
+/// A typed piece of message content following the message's `content` text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    /// Plain text.
+    Text { text: String },
+    /// An image such as a screenshot or diagram (`image/png`, `image/jpeg`,
+    /// `image/gif`, `image/webp`).
+    Image {
+        media_type: String,
+        source: MediaSource,
+    },
+    /// An attached document such as a scanned PDF.
+    Document {
+        media_type: String,
+        source: MediaSource,
+        /// File name shown to the model, if any.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    },
+}
+
+impl ContentPart {
+    /// A text part.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// An image from raw bytes.
+    pub fn image(media_type: impl Into<String>, bytes: &[u8]) -> Self {
+        Self::Image {
+            media_type: media_type.into(),
+            source: MediaSource::Base64 {
+                data: base64::engine::general_purpose::STANDARD.encode(bytes),
+            },
+        }
+    }
+
+    /// An image the provider fetches from `url`.
+    pub fn image_url(media_type: impl Into<String>, url: impl Into<String>) -> Self {
+        Self::Image {
+            media_type: media_type.into(),
+            source: MediaSource::Url { url: url.into() },
+        }
+    }
+
+    /// A document from raw bytes.
+    pub fn document(media_type: impl Into<String>, bytes: &[u8], name: Option<String>) -> Self {
+        Self::Document {
+            media_type: media_type.into(),
+            source: MediaSource::Base64 {
+                data: base64::engine::general_purpose::STANDARD.encode(bytes),
+            },
+            name,
+        }
+    }
+
+    /// Whether this is an image or document rather than text.
+    pub fn is_media(&self) -> bool {
+        !matches!(self, Self::Text { .. })
+    }
+
+    /// Estimate the token cost of this part.
+    ///
+    /// Images count as [`IMAGE_TOKEN_ESTIMATE`]. Text documents are sized
+    /// like text; other documents count [`DOCUMENT_PAGE_TOKEN_ESTIMATE`] per
+    /// page, with PDF pages counted from the inline bytes.
+    pub fn estimate_tokens(&self) -> usize {
+        match self {
+            Self::Text { text } => text.len() * 10 / 33,
+            Self::Image { .. } => IMAGE_TOKEN_ESTIMATE,
+            Self::Document {
+                media_type, source, ..
+            } => match source {
+                MediaSource::Base64 { data } if media_type.starts_with("text/") => {
+                    (data.len() * 3 / 4) * 10 / 33
+                }
+                MediaSource::Base64 { data } => {
+                    let pages = base64::engine::general_purpose::STANDARD
+                        .decode(data)
+                        .map(|bytes| pdf_page_count(&bytes))
+                        .unwrap_or(0);
+                    pages.max(1) * DOCUMENT_PAGE_TOKEN_ESTIMATE
+                }
+                MediaSource::Url { .. } => DOCUMENT_PAGE_TOKEN_ESTIMATE,
+            },
+        }
+    }
+
+    /// Render as an Anthropic content block.
+    fn to_anthropic_block(&self) -> serde_json::Value {
+        let source = |media_type: &str, source: &MediaSource| match source {
+            MediaSource::Base64 { data } => serde_json::json!({
+                "type": "base64",
+                "media_type": media_type,
+                "data": data,
+            }),
+            MediaSource::Url { url } => serde_json::json!({"type": "url", "url": url}),
+        };
+        match self {
+            Self::Text { text } => serde_json::json!({"type": "text", "text": text}),
+            Self::Image {
+                media_type,
+                source: src,
+            } => serde_json::json!({"type": "image", "source": source(media_type, src)}),
+            Self::Document {
+                media_type,
+                source: src,
+                name,
+            } => {
+                let mut block = serde_json::json!({
+                    "type": "document",
+                    "source": source(media_type, src),
+                });
+                if let Some(name) = name {
+                    block["title"] = serde_json::Value::String(name.clone());
+                }
+                block
+            }
+        }
+    }
+
+    /// Render as an OpenAI chat completions content part. OpenAI takes
+    /// documents only as inline file data, so a URL document is referenced
+    /// in text instead.
+    fn to_openai_part(&self) -> serde_json::Value {
+        match self {
+            Self::Text { text } => serde_json::json!({"type": "text", "text": text}),
+            Self::Image { media_type, source } => {
+                let url = match source {
+                    MediaSource::Base64 { data } => format!("data:{};base64,{}", media_type, data),
+                    MediaSource::Url { url } => url.clone(),
+                };
+                serde_json::json!({"type": "image_url", "image_url": {"url": url}})
+            }
+            Self::Document {
+                media_type,
+                source,
+                name,
+            } => match source {
+                MediaSource::Base64 { data } => serde_json::json!({
+                    "type": "file",
+                    "file": {
+                        "filename": name.as_deref().unwrap_or("document"),
+                        "file_data": format!("data:{};base64,{}", media_type, data),
+                    }
+                }),
+                MediaSource::Url { url } => serde_json::json!({
+                    "type": "text",
+                    "text": format!("[Document: {}]", url),
+                }),
+            },
+        }
+    }
+}
+
+/// Count the page objects in a PDF (`/Type /Page`, not `/Type /Pages`).
+fn pdf_page_count(bytes: &[u8]) -> usize {
+    let mut count = 0;
+    for marker in [&b"/Type /Page"[..], &b"/Type/Page"[..]] {
+        count += bytes
+            .windows(marker.len() + 1)
+            .filter(|w| w.starts_with(marker) && w[marker.len()] != b's')
+            .count();
+    }
+    count
+}
+
 /// A single message in a conversation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationMessage {
@@ -45,6 +234,10 @@ pub struct ConversationMessage {
     /// The tool name this result corresponds to (only present when role is Tool).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tool_name: Option<String>,
+    /// Typed parts following `content`: more text, images and documents.
+    /// Empty for plain-text messages.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<ContentPart>,
 }
 
 impl ConversationMessage {
@@ -56,6 +249,7 @@ impl ConversationMessage {
             tool_calls: Vec::new(),
             tool_call_id: None,
             tool_name: None,
+            parts: Vec::new(),
         }
     }
 
@@ -67,6 +261,7 @@ impl ConversationMessage {
             tool_calls: Vec::new(),
             tool_call_id: None,
             tool_name: None,
+            parts: Vec::new(),
         }
     }
 
@@ -78,6 +273,7 @@ impl ConversationMessage {
             tool_calls: Vec::new(),
             tool_call_id: None,
             tool_name: None,
+            parts: Vec::new(),
         }
     }
 
@@ -89,6 +285,7 @@ impl ConversationMessage {
             tool_calls,
             tool_call_id: None,
             tool_name: None,
+            parts: Vec::new(),
         }
     }
 
@@ -104,7 +301,34 @@ impl ConversationMessage {
             tool_calls: Vec::new(),
             tool_call_id: Some(tool_call_id.into()),
             tool_name: Some(tool_name.into()),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Append typed content parts, e.g. images for a vision model or
+    /// screenshots returned by a tool.
+    pub fn with_parts(mut self, parts: impl IntoIterator<Item = ContentPart>) -> Self {
+        self.parts.extend(parts);
+        self
+    }
+
+    /// Whether the message carries any image or document parts.
+    pub fn has_media(&self) -> bool {
+        self.parts.iter().any(ContentPart::is_media)
+    }
+
+    /// The message's text: `content` followed by any text parts.
+    pub fn text(&self) -> String {
+        let mut text = self.content.clone();
+        for part in &self.parts {
+            if let ContentPart::Text { text: t } = part {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(t);
+            }
         }
+        text
     }
 
     /// Estimate token count for this message.
@@ -124,7 +348,8 @@ impl ConversationMessage {
         }
         // ~3.3 chars per token (10 tokens per 33 chars), plus per-message overhead
         // The overhead covers role, JSON structure, content block wrapping
-        (chars * 10 / 33).max(1) + 7
+        let parts: usize = self.parts.iter().map(ContentPart::estimate_tokens).sum();
+        (chars * 10 / 33).max(1) + 7 + parts
     }
 }
 
@@ -231,6 +456,11 @@ impl Conversation {
         self.messages.iter().find(|m| m.role == MessageRole::System)
     }
 
+    /// Whether any message carries image or document parts.
+    pub fn has_media(&self) -> bool {
+        self.messages.iter().any(ConversationMessage::has_media)
+    }
+
     /// Get the last assistant message.
     pub fn last_assistant_message(&self) -> Option<&ConversationMessage> {
         self.messages
@@ -242,67 +472,110 @@ impl Conversation {
     /// Serialize to OpenAI chat completions format.
     ///
     /// Produces a JSON array of message objects with `role`, `content`,
-    /// and optionally `tool_calls` or `tool_call_id` fields.
+    /// and optionally `tool_calls` or `tool_call_id` fields. Messages with
+    /// content parts use the array form of `content`. Tool messages can
+    /// only carry text, so images and documents from tool results follow
+    /// the tool messages in a user message.
     pub fn to_openai_messages(&self) -> Vec<serde_json::Value> {
-        self.messages
-            .iter()
-            .map(|msg| {
-                let mut obj = serde_json::Map::new();
-                let role_str = match msg.role {
-                    MessageRole::System => "system",
-                    MessageRole::User => "user",
-                    MessageRole::Assistant => "assistant",
-                    MessageRole::Tool => "tool",
-                };
-                obj.insert("role".into(), serde_json::Value::String(role_str.into()));
+        let mut out = Vec::with_capacity(self.messages.len());
+        let mut tool_media: Vec<serde_json::Value> = Vec::new();
 
+        for msg in &self.messages {
+            if msg.role != MessageRole::Tool && !tool_media.is_empty() {
+                out.push(serde_json::json!({
+                    "role": "user",
+                    "content": std::mem::take(&mut tool_media),
+                }));
+            }
+
+            let mut obj = serde_json::Map::new();
+            let role_str = match msg.role {
+                MessageRole::System => "system",
+                MessageRole::User => "user",
+                MessageRole::Assistant => "assistant",
+                MessageRole::Tool => "tool",
+            };
+            obj.insert("role".into(), serde_json::Value::String(role_str.into()));
+
+            // Only user messages accept image and file parts; other roles
+            // get their text parts folded into the content string.
+            let content = if msg.role == MessageRole::User && msg.has_media() {
+                let mut parts = Vec::with_capacity(msg.parts.len() + 1);
                 if !msg.content.is_empty() {
-                    obj.insert(
-                        "content".into(),
-                        serde_json::Value::String(msg.content.clone()),
-                    );
-                } else if msg.role != MessageRole::Assistant {
-                    // OpenAI requires content for non-assistant messages
-                    obj.insert("content".into(), serde_json::Value::String(String::new()));
+                    parts.push(serde_json::json!({"type": "text", "text": msg.content}));
                 }
+                parts.extend(msg.parts.iter().map(ContentPart::to_openai_part));
+                serde_json::Value::Array(parts)
+            } else {
+                serde_json::Value::String(msg.text())
+            };
+            if content.as_str() != Some("") {
+                obj.insert("content".into(), content);
+            } else if msg.role != MessageRole::Assistant {
+                // OpenAI requires content for non-assistant messages
+                obj.insert("content".into(), serde_json::Value::String(String::new()));
+            }
 
-                if !msg.tool_calls.is_empty() {
-                    let tool_calls: Vec<serde_json::Value> = msg
-                        .tool_calls
+            if msg.role == MessageRole::Tool && msg.has_media() {
+                tool_media.push(serde_json::json!({
+                    "type": "text",
+                    "text": format!(
+                        "Attachments from tool `{}` (call {}):",
+                        msg.tool_name.as_deref().unwrap_or("unknown"),
+                        msg.tool_call_id.as_deref().unwrap_or("unknown"),
+                    ),
+                }));
+                tool_media.extend(
+                    msg.parts
                         .iter()
-                        .map(|tc| {
-                            serde_json::json!({
-                                "id": tc.id,
-                                "type": "function",
-                                "function": {
-                                    "name": tc.name,
-                                    "arguments": tc.arguments,
-                                }
-                            })
+                        .filter(|p| p.is_media())
+                        .map(ContentPart::to_openai_part),
+                );
+            }
+
+            if !msg.tool_calls.is_empty() {
+                let tool_calls: Vec<serde_json::Value> = msg
+                    .tool_calls
+                    .iter()
+                    .map(|tc| {
+                        serde_json::json!({
+                            "id": tc.id,
+                            "type": "function",
+                            "function": {
+                                "name": tc.name,
+                                "arguments": tc.arguments,
+                            }
                         })
-                        .collect();
-                    obj.insert("tool_calls".into(), serde_json::Value::Array(tool_calls));
-                }
+                    })
+                    .collect();
+                obj.insert("tool_calls".into(), serde_json::Value::Array(tool_calls));
+            }
 
-                if let Some(ref id) = msg.tool_call_id {
-                    obj.insert("tool_call_id".into(), serde_json::Value::String(id.clone()));
-                }
+            if let Some(ref id) = msg.tool_call_id {
+                obj.insert("tool_call_id".into(), serde_json::Value::String(id.clone()));
+            }
+
+            out.push(serde_json::Value::Object(obj));
+        }
 
-                serde_json::Value::Object(obj)
-            })
-            .collect()
+        if !tool_media.is_empty() {
+            out.push(serde_json::json!({"role": "user", "content": tool_media}));
+        }
+        out
     }
 
     /// Serialize to Anthropic Messages API format.
     ///
     /// Returns `(system_prompt, messages)` because Anthropic takes the system
-    /// message as a separate top-level field.
+    /// message as a separate top-level field. Image and document parts
+    /// become content blocks on user turns and inside tool results; the
+    /// API accepts only text from the system prompt and assistant turns.
     pub fn to_anthropic_messages(&self) -> (Option<String>, Vec<serde_json::Value>) {
         let system = self
             .messages
             .iter()
             .find(|m| m.role == MessageRole::System)
-            .map(|m| m.content.clone());
+            .map(|m| m.text());
 
         // Build raw messages first, then merge consecutive same-role messages.
         // Anthropic requires that all tool_result blocks for a given assistant
@@ -322,21 +595,32 @@ impl Conversation {
 
             let serialized = if msg.role == MessageRole::Tool {
                 // Anthropic tool results go as user messages with tool_result content blocks
+                let content = if msg.parts.is_empty() {
+                    serde_json::Value::String(msg.content.clone())
+                } else {
+                    let mut blocks = Vec::with_capacity(msg.parts.len() + 1);
+                    if !msg.content.is_empty() {
+                        blocks.push(serde_json::json!({"type": "text", "text": msg.content}));
+                    }
+                    blocks.extend(msg.parts.iter().map(ContentPart::to_anthropic_block));
+                    serde_json::Value::Array(blocks)
+                };
                 serde_json::json!({
                     "role": "user",
                     "content": [{
                         "type": "tool_result",
                         "tool_use_id": msg.tool_call_id.as_deref().unwrap_or(""),
-                        "content": msg.content,
+                        "content": content,
                     }]
                 })
             } else if !msg.tool_calls.is_empty() {
                 // Assistant message with tool use
                 let mut content_blocks: Vec<serde_json::Value> = Vec::new();
-                if !msg.content.is_empty() {
+                let text = msg.text();
+                if !text.is_empty() {
                     content_blocks.push(serde_json::json!({
                         "type": "text",
-                        "text": msg.content,
+                        "text": text,
                     }));
                 }
                 for tc in &msg.tool_calls {
@@ -353,10 +637,20 @@ impl Conversation {
                     "role": role_str,
                     "content": content_blocks,
                 })
+            } else if msg.role == MessageRole::User && !msg.parts.is_empty() {
+                let mut blocks = Vec::with_capacity(msg.parts.len() + 1);
+                if !msg.content.is_empty() {
+                    blocks.push(serde_json::json!({"type": "text", "text": msg.content}));
+                }
+                blocks.extend(msg.parts.iter().map(ContentPart::to_anthropic_block));
+                serde_json::json!({
+                    "role": role_str,
+                    "content": blocks,
+                })
             } else {
                 serde_json::json!({
                     "role": role_str,
-                    "content": msg.content,
+                    "content": msg.text(),
                 })
             };
 
@@ -926,4 +1220,123 @@ mod tests {
         assert_eq!(restored.len(), conv.len());
         assert_eq!(restored.messages()[2].tool_calls[0].name, "search");
     }
+
+    fn screenshot_conversation() -> Conversation {
+        let mut conv = Conversation::with_system("sys");
+        conv.push(
+            ConversationMessage::user("What does this show?")
+                .with_parts([ContentPart::image("image/png", b"\x89PNG")]),
+        );
+        conv.push(ConversationMessage::assistant_tool_calls(vec![
+            ToolCall {
+                id: "c1".into(),
+                name: "screenshot".into(),
+                arguments: "{}".into(),
+            },
+            ToolCall {
+                id: "c2".into(),
+                name: "search".into(),
+                arguments: "{}".into(),
+            },
+        ]));
+        conv.push(
+            ConversationMessage::tool_result("c1", "screenshot", "captured")
+                .with_parts([ContentPart::image_url("image/jpeg", "https://x.test/a.jpg")]),
+        );
+        conv.push(ConversationMessage::tool_result("c2", "search", "none"));
+        conv
+    }
+
+    #[test]
+    fn test_openai_multimodal_serialization() {
+        let msgs = screenshot_conversation().to_openai_messages();
+        assert_eq!(msgs.len(), 6);
+
+        let user = msgs[1]["content"].as_array().unwrap();
+        assert_eq!(user[0]["text"], "What does this show?");
+        assert_eq!(user[1]["type"], "image_url");
+        assert_eq!(
+            user[1]["image_url"]["url"],
+            "data:image/png;base64,iVBORw=="
+        );
+
+        // Tool messages stay text and contiguous; the image follows them.
+        assert_eq!(msgs[3]["role"], "tool");
+        assert_eq!(msgs[3]["content"], "captured");
+        assert_eq!(msgs[4]["role"], "tool");
+        assert_eq!(msgs[5]["role"], "user");
+        let attachments = msgs[5]["content"].as_array().unwrap();
+        assert!(attachments[0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("screenshot"));
+        assert_eq!(attachments[1]["image_url"]["url"], "https://x.test/a.jpg");
+    }
+
+    #[test]
+    fn test_anthropic_multimodal_serialization() {
+        let mut conv = screenshot_conversation();
+        conv.push(
+            ConversationMessage::user("And this?").with_parts([ContentPart::document(
+                "application/pdf",
+                b"%PDF",
+                Some("scan.pdf".into()),
+            )]),
+        );
+        let (_, msgs) = conv.to_anthropic_messages();
+
+        let user = msgs[0]["content"].as_array().unwrap();
+        assert_eq!(user[1]["type"], "image");
+        assert_eq!(user[1]["source"]["type"], "base64");
+        assert_eq!(user[1]["source"]["media_type"], "image/png");
+
+        let results = msgs[2]["content"].as_array().unwrap();
+        let shot = results[0]["content"].as_array().unwrap();
+        assert_eq!(shot[0]["text"], "captured");
+        assert_eq!(shot[1]["source"]["type"], "url");
+        assert_eq!(results[1]["content"], "none");
+
+        // The follow-up user turn merges into the tool-result message.
+        let doc = results.iter().find(|b| b["type"] == "document").unwrap();
+        assert_eq!(doc["title"], "scan.pdf");
+        assert_eq!(doc["source"]["media_type"], "application/pdf");
+    }
+
+    #[test]
+    fn test_media_token_estimation() {
+        let text = ConversationMessage::user("Hello, world!");
+        let with_image = text
+            .clone()
+            .with_parts([ContentPart::image("image/png", b"png")]);
+        assert_eq!(
+            with_image.estimate_tokens(),
+            text.estimate_tokens() + IMAGE_TOKEN_ESTIMATE
+        );
+
+        let pdf = b"%PDF /Type /Pages /Count 2 /Type /Page x /Type/Page y";
+        let doc = ContentPart::document("application/pdf", pdf, None);
+        assert_eq!(doc.estimate_tokens(), 2 * DOCUMENT_PAGE_TOKEN_ESTIMATE);
+        let notes = ContentPart::document("text/plain", &[b'a'; 330], None);
+        assert_eq!(notes.estimate_tokens(), 100);
+        assert!(screenshot_conversation().has_media());
+    }
+
+    #[test]
+    fn test_parts_serde_roundtrip() {
+        let msg = ConversationMessage::user("look").with_parts([
+            ContentPart::text("more"),
+            ContentPart::image_url("image/png", "https://x.test/p.png"),
+        ]);
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["parts"][1]["type"], "image");
+        assert_eq!(json["parts"][1]["source"]["kind"], "url");
+        let restored: ConversationMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.parts, msg.parts);
+        assert_eq!(restored.text(), "look\nmore");
+
+        // Messages written before content parts existed still load.
+        let old: ConversationMessage =
+            serde_json::from_str(r#"{"role":"user","content":"hi"}"#).unwrap();
+        assert!(old.parts.is_empty());
+    }
 }
@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 
 use async_trait::async_trait;
+use tracing::Instrument;
 
 use crate::reasoning::circuit_breaker::CircuitBreakerRegistry;
 use crate::reasoning::context_manager::ContextManager;
@@ -121,6 +122,8 @@ impl DelegationExecutor for SubLoopDelegationExecutor {
             circuit_breakers: self.circuit_breakers.clone(),
             journal: self.journal.clone(),
             knowledge_bridge: None,
+            sandbox_sessions: None,
+            spend: None,
             delegation: self
                 .self_ref
                 .upgrade()
@@ -147,6 +150,7 @@ impl DelegationExecutor for SubLoopDelegationExecutor {
         // delegated work impossible to attribute or to write policy against.
         let result = sub
             .run(delegated_agent_id(target), conversation, config)
+            .instrument(crate::telemetry::delegation_span(target, ctx.depth + 1))
             .await;
 
         self.delegated_tokens.fetch_add(
@@ -217,6 +221,7 @@ mod tests {
                     prompt_tokens: 10,
                     completion_tokens: 5,
                     total_tokens: 15,
+                    cached_prompt_tokens: 0,
                 },
                 model: "mock".into(),
             })
@@ -262,6 +267,7 @@ mod tests {
                     prompt_tokens: 10,
                     completion_tokens: 5,
                     total_tokens: 15,
+                    cached_prompt_tokens: 0,
                 },
                 model: "mock".into(),
             })
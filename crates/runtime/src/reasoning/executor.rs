@@ -10,6 +10,7 @@ use crate::reasoning::loop_types::{LoopConfig, Observation, ProposedAction};
 use async_trait::async_trait;
 use futures::stream::{FuturesUnordered, StreamExt};
 use std::time::Duration;
+use tracing::Instrument;
 
 /// Trait for executing proposed actions and producing observations.
 #[async_trait]
@@ -87,8 +88,9 @@ impl ActionExecutor for DefaultActionExecutor {
 
                 // Check circuit breaker first
                 let cb_result = circuit_breakers.check(&name).await;
+                let span = crate::telemetry::tool_span(&name, &call_id);
 
-                futures.push(async move {
+                let call = async move {
                     if let Err(cb_err) = cb_result {
                         return Observation {
                             source: name,
@@ -103,6 +105,7 @@ impl ActionExecutor for DefaultActionExecutor {
                                 m.insert("error_type".into(), "circuit_open".into());
                                 m
                             },
+                            outputs: Vec::new(),
                         };
                     }
 
@@ -118,15 +121,10 @@ impl ActionExecutor for DefaultActionExecutor {
                         Ok(Ok(content)) => {
                             Observation::tool_result(&name, content).with_call_id(call_id)
                         }
-                        Ok(Err(err)) => {
-                            Observation::tool_error(&name, err).with_call_id(call_id)
-                        }
+                        Ok(Err(err)) => Observation::tool_error(&name, err).with_call_id(call_id),
                         Err(_) => Observation {
                             source: name.clone(),
-                            content: format!(
-                                "Tool '{}' timed out after {:?}",
-                                name, timeout
-                            ),
+                            content: format!("Tool '{}' timed out after {:?}", name, timeout),
                             is_error: true,
                             call_id: Some(call_id),
                             metadata: {
@@ -134,8 +132,14 @@ impl ActionExecutor for DefaultActionExecutor {
                                 m.insert("error_type".into(), "timeout".into());
                                 m
                             },
+                            outputs: Vec::new(),
                         },
                     }
+                };
+                futures.push(async move {
+                    let observation = call.instrument(span.clone()).await;
+                    crate::telemetry::record_tool_result(&span, &observation);
+                    observation
                 });
             }
         }
@@ -212,6 +216,7 @@ impl ActionExecutor for UnavailableToolExecutor {
                     is_error: true,
                     call_id: Some(call_id.clone()),
                     metadata: Default::default(),
+                    outputs: Vec::new(),
                 }),
                 _ => None,
             })
@@ -6,8 +6,10 @@
 
 use crate::reasoning::conversation::Conversation;
 use async_trait::async_trait;
+use futures::Stream;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::pin::Pin;
 
 /// A tool definition that can be provided to an inference call.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,6 +81,11 @@ pub struct Usage {
     pub completion_tokens: u32,
     /// Total tokens used.
     pub total_tokens: u32,
+    /// Prompt tokens served from the provider's prompt cache. Already
+    /// counted in `prompt_tokens`; reported separately so they can be
+    /// priced at the cached-input rate.
+    #[serde(default)]
+    pub cached_prompt_tokens: u32,
 }
 
 /// Controls whether the model is required to call a tool on this turn.
@@ -179,6 +186,150 @@ impl InferenceResponse {
     pub fn has_tool_calls(&self) -> bool {
         !self.tool_calls.is_empty()
     }
+
+    /// Replay a complete response as the event sequence a streaming call
+    /// would have produced: one text delta, one delta per tool call, then
+    /// the terminal `Finished` event.
+    pub fn into_stream_events(self) -> Vec<StreamEvent> {
+        let mut events = Vec::with_capacity(self.tool_calls.len() + 2);
+        if !self.content.is_empty() {
+            events.push(StreamEvent::TextDelta { text: self.content });
+        }
+        for (index, tc) in self.tool_calls.into_iter().enumerate() {
+            events.push(StreamEvent::ToolCallDelta {
+                index,
+                id: Some(tc.id),
+                name: Some(tc.name),
+                arguments_delta: tc.arguments,
+            });
+        }
+        events.push(StreamEvent::Finished {
+            finish_reason: self.finish_reason,
+            usage: self.usage,
+            model: self.model,
+        });
+        events
+    }
+}
+
+/// An incremental event from a streaming inference call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    /// A fragment of assistant text.
+    TextDelta { text: String },
+    /// A fragment of a tool call. `id` and `name` arrive on the first
+    /// fragment for a given `index`; later fragments usually carry only
+    /// more argument JSON.
+    ToolCallDelta {
+        index: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        arguments_delta: String,
+    },
+    /// The stream finished. Always the last event of a well-formed stream.
+    Finished {
+        finish_reason: FinishReason,
+        usage: Usage,
+        model: String,
+    },
+}
+
+/// A boxed stream of [`StreamEvent`]s returned by
+/// [`InferenceProvider::complete_stream`].
+pub type InferenceStream = Pin<Box<dyn Stream<Item = Result<StreamEvent, InferenceError>> + Send>>;
+
+/// Folds [`StreamEvent`]s back into a complete [`InferenceResponse`].
+///
+/// Tool-call fragments are grouped by `index`, so interleaved deltas for
+/// parallel tool calls reassemble correctly.
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    content: String,
+    tool_calls: BTreeMap<usize, ToolCallRequest>,
+    finish_reason: Option<FinishReason>,
+    usage: Usage,
+    model: Option<String>,
+}
+
+impl StreamAccumulator {
+    /// Create an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply one event.
+    pub fn push(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::TextDelta { text } => self.content.push_str(text),
+            StreamEvent::ToolCallDelta {
+                index,
+                id,
+                name,
+                arguments_delta,
+            } => {
+                let call = self
+                    .tool_calls
+                    .entry(*index)
+                    .or_insert_with(|| ToolCallRequest {
+                        id: String::new(),
+                        name: String::new(),
+                        arguments: String::new(),
+                    });
+                if let Some(id) = id {
+                    call.id.clone_from(id);
+                }
+                if let Some(name) = name {
+                    call.name.clone_from(name);
+                }
+                call.arguments.push_str(arguments_delta);
+            }
+            StreamEvent::Finished {
+                finish_reason,
+                usage,
+                model,
+            } => {
+                self.finish_reason = Some(finish_reason.clone());
+                self.usage = usage.clone();
+                self.model = Some(model.clone());
+            }
+        }
+    }
+
+    /// Whether the terminal `Finished` event has been seen.
+    pub fn is_finished(&self) -> bool {
+        self.finish_reason.is_some()
+    }
+
+    /// Build the final response. `fallback_model` is reported when the
+    /// stream never carried a model id.
+    pub fn finish(self, fallback_model: &str) -> InferenceResponse {
+        let tool_calls: Vec<ToolCallRequest> = self
+            .tool_calls
+            .into_values()
+            .map(|mut tc| {
+                // A tool with no parameters may stream no argument bytes at all.
+                if tc.arguments.trim().is_empty() {
+                    tc.arguments = "{}".into();
+                }
+                tc
+            })
+            .collect();
+        let finish_reason = self.finish_reason.unwrap_or(if tool_calls.is_empty() {
+            FinishReason::Stop
+        } else {
+            FinishReason::ToolCalls
+        });
+        InferenceResponse {
+            content: self.content,
+            tool_calls,
+            finish_reason,
+            usage: self.usage,
+            model: self.model.unwrap_or_else(|| fallback_model.to_string()),
+        }
+    }
 }
 
 /// Errors that can occur during inference.
@@ -222,6 +373,24 @@ pub trait InferenceProvider: Send + Sync {
         options: &InferenceOptions,
     ) -> Result<InferenceResponse, InferenceError>;
 
+    /// Run inference and yield the response incrementally.
+    ///
+    /// The default implementation calls [`complete`](Self::complete) and
+    /// replays the finished response as a single burst of events, so every
+    /// provider can be driven through the streaming path. Providers that
+    /// speak a native streaming protocol override this and report
+    /// [`supports_streaming`](Self::supports_streaming).
+    async fn complete_stream(
+        &self,
+        conversation: &Conversation,
+        options: &InferenceOptions,
+    ) -> Result<InferenceStream, InferenceError> {
+        let response = self.complete(conversation, options).await?;
+        Ok(Box::pin(futures::stream::iter(
+            response.into_stream_events().into_iter().map(Ok),
+        )))
+    }
+
     /// Get the provider's name for logging and routing.
     fn provider_name(&self) -> &str;
 
@@ -233,6 +402,12 @@ pub trait InferenceProvider: Send + Sync {
 
     /// Check if this provider supports structured output natively.
     fn supports_structured_output(&self) -> bool;
+
+    /// Check if [`complete_stream`](Self::complete_stream) delivers tokens
+    /// as they are generated rather than replaying a finished response.
+    fn supports_streaming(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -306,6 +481,83 @@ mod tests {
         assert!(!resp_no_tools.has_tool_calls());
     }
 
+    #[test]
+    fn test_stream_accumulator_reassembles_interleaved_tool_calls() {
+        let mut acc = StreamAccumulator::new();
+        let events = [
+            StreamEvent::TextDelta {
+                text: "Let me ".into(),
+            },
+            StreamEvent::TextDelta {
+                text: "check.".into(),
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: Some("tc_a".into()),
+                name: Some("search".into()),
+                arguments_delta: "{\"q\":".into(),
+            },
+            StreamEvent::ToolCallDelta {
+                index: 1,
+                id: Some("tc_b".into()),
+                name: Some("ping".into()),
+                arguments_delta: String::new(),
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: None,
+                name: None,
+                arguments_delta: "\"rust\"}".into(),
+            },
+        ];
+        for e in &events {
+            acc.push(e);
+        }
+        assert!(!acc.is_finished());
+
+        let resp = acc.finish("fallback");
+        assert_eq!(resp.content, "Let me check.");
+        assert_eq!(resp.tool_calls.len(), 2);
+        assert_eq!(resp.tool_calls[0].id, "tc_a");
+        assert_eq!(resp.tool_calls[0].arguments, r#"{"q":"rust"}"#);
+        assert_eq!(resp.tool_calls[1].arguments, "{}");
+        assert_eq!(resp.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(resp.model, "fallback");
+    }
+
+    #[test]
+    fn test_into_stream_events_round_trips() {
+        let original = InferenceResponse {
+            content: "hi".into(),
+            tool_calls: vec![ToolCallRequest {
+                id: "tc_1".into(),
+                name: "search".into(),
+                arguments: r#"{"q":"x"}"#.into(),
+            }],
+            finish_reason: FinishReason::ToolCalls,
+            usage: Usage {
+                prompt_tokens: 3,
+                completion_tokens: 4,
+                total_tokens: 7,
+                cached_prompt_tokens: 0,
+            },
+            model: "m".into(),
+        };
+        let mut acc = StreamAccumulator::new();
+        for e in original.clone().into_stream_events() {
+            acc.push(&e);
+        }
+        assert!(acc.is_finished());
+        let rebuilt = acc.finish("other");
+        assert_eq!(rebuilt.content, original.content);
+        assert_eq!(
+            rebuilt.tool_calls[0].arguments,
+            original.tool_calls[0].arguments
+        );
+        assert_eq!(rebuilt.usage.total_tokens, 7);
+        assert_eq!(rebuilt.model, "m");
+    }
+
     #[test]
     fn test_finish_reason_serde() {
         let json = serde_json::to_string(&FinishReason::ToolCalls).unwrap();
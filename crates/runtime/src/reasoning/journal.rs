@@ -2,42 +2,104 @@
 //!
 //! Provides append-only, crash-recoverable journal storage for reasoning loops.
 //! Each phase boundary is a checkpoint; crashed loops resume deterministically
-//! by replaying journal entries.
+//! by replaying journal entries. Only the latest `Checkpoint` snapshot per
+//! journal is kept: `DurableJournal` prunes the ones it supersedes.
 //!
-//! Feature-gated behind `cron` (which includes `rusqlite`).
+//! Storage is keyed by [`RunId`]. A journal kept per agent uses the agent's
+//! id as its key; callers that run one agent several times concurrently
+//! give each run its own key.
+//!
+//! `SqliteJournalStorage` is feature-gated behind `cron` (which includes
+//! `rusqlite`); `MemoryJournalStorage` is always available.
 
-use crate::reasoning::loop_types::{JournalEntry, JournalError, JournalWriter};
+use crate::reasoning::loop_types::{JournalEntry, JournalError, JournalWriter, LoopEvent};
 use crate::types::AgentId;
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+/// Key a journal's entries are stored under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RunId(pub uuid::Uuid);
+
+impl RunId {
+    /// A fresh key for one run.
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+}
+
+impl Default for RunId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Journals kept per agent are keyed by the agent's id.
+impl From<AgentId> for RunId {
+    fn from(agent_id: AgentId) -> Self {
+        Self(agent_id.0)
+    }
+}
+
+impl std::fmt::Display for RunId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Trait for durable journal storage backends.
 #[async_trait::async_trait]
 pub trait JournalStorage: Send + Sync {
-    /// Append an entry to persistent storage.
-    async fn store(&self, entry: &JournalEntry) -> Result<(), JournalError>;
+    /// Append an entry to a run's journal in persistent storage.
+    async fn store(&self, run: &RunId, entry: &JournalEntry) -> Result<(), JournalError>;
 
-    /// Read all entries for a given agent, ordered by sequence.
-    async fn read_entries(&self, agent_id: &AgentId) -> Result<Vec<JournalEntry>, JournalError>;
+    /// Read all entries for a given run, ordered by sequence.
+    async fn read_entries(&self, run: &RunId) -> Result<Vec<JournalEntry>, JournalError>;
 
     /// Read entries starting from a given sequence number.
     async fn read_from(
         &self,
-        agent_id: &AgentId,
+        run: &RunId,
         from_sequence: u64,
     ) -> Result<Vec<JournalEntry>, JournalError>;
 
-    /// Get the latest sequence number for an agent (0 if none).
-    async fn latest_sequence(&self, agent_id: &AgentId) -> Result<u64, JournalError>;
+    /// Get the latest sequence number for a run (0 if none).
+    async fn latest_sequence(&self, run: &RunId) -> Result<u64, JournalError>;
+
+    /// Delete all entries for a run (compaction after loop completion).
+    async fn compact(&self, run: &RunId) -> Result<u64, JournalError>;
+
+    /// Delete a run's entries with a sequence below `before_sequence`.
+    /// Backends that cannot delete a range report `Unsupported`.
+    async fn compact_before(&self, run: &RunId, before_sequence: u64) -> Result<u64, JournalError> {
+        let _ = (run, before_sequence);
+        Err(JournalError::Unsupported("compact_before".to_string()))
+    }
+
+    /// Delete a run's `Checkpoint` entries with a sequence below
+    /// `before_sequence`, leaving every other event in place. Pruning only
+    /// saves space, so backends that cannot do it keep every checkpoint.
+    async fn remove_checkpoints_before(
+        &self,
+        run: &RunId,
+        before_sequence: u64,
+    ) -> Result<u64, JournalError> {
+        let _ = (run, before_sequence);
+        Ok(0)
+    }
 
-    /// Delete all entries for an agent (compaction after loop completion).
-    async fn compact(&self, agent_id: &AgentId) -> Result<u64, JournalError>;
+    /// List every run that currently has journal entries. Backends that
+    /// cannot enumerate their runs report `Unsupported`.
+    async fn runs(&self) -> Result<Vec<RunId>, JournalError> {
+        Err(JournalError::Unsupported("runs".to_string()))
+    }
 }
 
 /// In-memory journal storage for testing and lightweight use.
 pub struct MemoryJournalStorage {
-    entries: Mutex<Vec<JournalEntry>>,
+    entries: Mutex<Vec<(RunId, JournalEntry)>>,
 }
 
 impl Default for MemoryJournalStorage {
@@ -56,49 +118,296 @@ impl MemoryJournalStorage {
 
 #[async_trait::async_trait]
 impl JournalStorage for MemoryJournalStorage {
-    async fn store(&self, entry: &JournalEntry) -> Result<(), JournalError> {
-        self.entries.lock().await.push(entry.clone());
+    async fn store(&self, run: &RunId, entry: &JournalEntry) -> Result<(), JournalError> {
+        self.entries.lock().await.push((*run, entry.clone()));
         Ok(())
     }
 
-    async fn read_entries(&self, agent_id: &AgentId) -> Result<Vec<JournalEntry>, JournalError> {
-        let entries = self.entries.lock().await;
-        Ok(entries
-            .iter()
-            .filter(|e| e.agent_id == *agent_id)
-            .cloned()
-            .collect())
+    async fn read_entries(&self, run: &RunId) -> Result<Vec<JournalEntry>, JournalError> {
+        self.read_from(run, 0).await
     }
 
     async fn read_from(
         &self,
-        agent_id: &AgentId,
+        run: &RunId,
         from_sequence: u64,
     ) -> Result<Vec<JournalEntry>, JournalError> {
         let entries = self.entries.lock().await;
         Ok(entries
             .iter()
-            .filter(|e| e.agent_id == *agent_id && e.sequence >= from_sequence)
-            .cloned()
+            .filter(|(r, e)| r == run && e.sequence >= from_sequence)
+            .map(|(_, e)| e.clone())
             .collect())
     }
 
-    async fn latest_sequence(&self, agent_id: &AgentId) -> Result<u64, JournalError> {
+    async fn latest_sequence(&self, run: &RunId) -> Result<u64, JournalError> {
         let entries = self.entries.lock().await;
         Ok(entries
             .iter()
-            .filter(|e| e.agent_id == *agent_id)
-            .map(|e| e.sequence)
+            .filter(|(r, _)| r == run)
+            .map(|(_, e)| e.sequence)
             .max()
             .unwrap_or(0))
     }
 
-    async fn compact(&self, agent_id: &AgentId) -> Result<u64, JournalError> {
+    async fn compact(&self, run: &RunId) -> Result<u64, JournalError> {
+        let mut entries = self.entries.lock().await;
+        let before = entries.len();
+        entries.retain(|(r, _)| r != run);
+        Ok((before - entries.len()) as u64)
+    }
+
+    async fn compact_before(&self, run: &RunId, before_sequence: u64) -> Result<u64, JournalError> {
+        let mut entries = self.entries.lock().await;
+        let before = entries.len();
+        entries.retain(|(r, e)| r != run || e.sequence >= before_sequence);
+        Ok((before - entries.len()) as u64)
+    }
+
+    async fn remove_checkpoints_before(
+        &self,
+        run: &RunId,
+        before_sequence: u64,
+    ) -> Result<u64, JournalError> {
         let mut entries = self.entries.lock().await;
         let before = entries.len();
-        entries.retain(|e| e.agent_id != *agent_id);
+        entries.retain(|(r, e)| {
+            r != run
+                || e.sequence >= before_sequence
+                || !matches!(e.event, LoopEvent::Checkpoint { .. })
+        });
         Ok((before - entries.len()) as u64)
     }
+
+    async fn runs(&self) -> Result<Vec<RunId>, JournalError> {
+        let entries = self.entries.lock().await;
+        let mut runs: Vec<RunId> = Vec::new();
+        for (run, _) in entries.iter() {
+            if !runs.contains(run) {
+                runs.push(*run);
+            }
+        }
+        Ok(runs)
+    }
+}
+
+/// SQLite-backed journal storage.
+///
+/// Entries live in a single `journal_entries` table keyed by
+/// `(agent_id, sequence)`, so per-run reads are index range scans. The
+/// `agent_id` column holds the [`RunId`]; it keeps that name so databases
+/// written by per-agent journals, whose keys are agent ids, still load. The
+/// database runs in WAL mode; `checkpoint_wal` folds the WAL back into the
+/// main file after large compactions.
+#[cfg(feature = "cron")]
+pub struct SqliteJournalStorage {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "cron")]
+impl SqliteJournalStorage {
+    /// Open (or create) the journal database at the given path.
+    pub fn open(path: &std::path::Path) -> Result<Self, JournalError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| JournalError::WriteFailed(format!("create dir: {e}")))?;
+        }
+        let conn = rusqlite::Connection::open(path)
+            .map_err(|e| JournalError::WriteFailed(e.to_string()))?;
+
+        // WAL mode so readers (e.g. `symbi` CLI inspection) don't block the loop.
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| JournalError::WriteFailed(e.to_string()))?;
+
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Open an in-memory journal database (useful for tests).
+    pub fn open_in_memory() -> Result<Self, JournalError> {
+        let conn = rusqlite::Connection::open_in_memory()
+            .map_err(|e| JournalError::WriteFailed(e.to_string()))?;
+
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Default database path: `$XDG_DATA_HOME/symbi/reasoning_journal.db`
+    pub fn default_path() -> std::path::PathBuf {
+        let base = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+        base.join("symbi").join("reasoning_journal.db")
+    }
+
+    fn init_schema(conn: &rusqlite::Connection) -> Result<(), JournalError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS journal_entries (
+                agent_id    TEXT NOT NULL,
+                sequence    INTEGER NOT NULL,
+                iteration   INTEGER NOT NULL,
+                timestamp   TEXT NOT NULL,
+                entry_json  TEXT NOT NULL,
+                PRIMARY KEY (agent_id, sequence)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_journal_entries_iteration
+                ON journal_entries(agent_id, iteration);",
+        )
+        .map_err(|e| JournalError::WriteFailed(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Fold the WAL back into the main database file and truncate it.
+    pub async fn checkpoint_wal(&self) -> Result<(), JournalError> {
+        let conn = self.conn.lock().await;
+        conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+            .map_err(|e| JournalError::WriteFailed(e.to_string()))
+    }
+
+    fn query_entries(
+        conn: &rusqlite::Connection,
+        run: &RunId,
+        from_sequence: u64,
+    ) -> Result<Vec<JournalEntry>, JournalError> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT entry_json FROM journal_entries
+                 WHERE agent_id = ?1 AND sequence >= ?2
+                 ORDER BY sequence",
+            )
+            .map_err(|e| JournalError::ReadFailed(e.to_string()))?;
+        let rows = stmt
+            .query_map(
+                rusqlite::params![run.to_string(), from_sequence as i64],
+                |row| row.get::<_, String>(0),
+            )
+            .map_err(|e| JournalError::ReadFailed(e.to_string()))?;
+
+        let mut entries = Vec::new();
+        for row in rows {
+            let json = row.map_err(|e| JournalError::ReadFailed(e.to_string()))?;
+            let entry = serde_json::from_str(&json).map_err(|e| {
+                JournalError::ReadFailed(format!("Failed to deserialize journal entry: {e}"))
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(feature = "cron")]
+#[async_trait::async_trait]
+impl JournalStorage for SqliteJournalStorage {
+    async fn store(&self, run: &RunId, entry: &JournalEntry) -> Result<(), JournalError> {
+        let json = serde_json::to_string(entry).map_err(|e| {
+            JournalError::WriteFailed(format!("Failed to serialize journal entry: {e}"))
+        })?;
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO journal_entries (agent_id, sequence, iteration, timestamp, entry_json)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                run.to_string(),
+                entry.sequence as i64,
+                entry.iteration,
+                entry.timestamp.to_rfc3339(),
+                json,
+            ],
+        )
+        .map_err(|e| JournalError::WriteFailed(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn read_entries(&self, run: &RunId) -> Result<Vec<JournalEntry>, JournalError> {
+        let conn = self.conn.lock().await;
+        Self::query_entries(&conn, run, 0)
+    }
+
+    async fn read_from(
+        &self,
+        run: &RunId,
+        from_sequence: u64,
+    ) -> Result<Vec<JournalEntry>, JournalError> {
+        let conn = self.conn.lock().await;
+        Self::query_entries(&conn, run, from_sequence)
+    }
+
+    async fn latest_sequence(&self, run: &RunId) -> Result<u64, JournalError> {
+        let conn = self.conn.lock().await;
+        let latest: Option<i64> = conn
+            .query_row(
+                "SELECT MAX(sequence) FROM journal_entries WHERE agent_id = ?1",
+                rusqlite::params![run.to_string()],
+                |row| row.get(0),
+            )
+            .map_err(|e| JournalError::ReadFailed(e.to_string()))?;
+        Ok(latest.unwrap_or(0) as u64)
+    }
+
+    async fn compact(&self, run: &RunId) -> Result<u64, JournalError> {
+        let conn = self.conn.lock().await;
+        let removed = conn
+            .execute(
+                "DELETE FROM journal_entries WHERE agent_id = ?1",
+                rusqlite::params![run.to_string()],
+            )
+            .map_err(|e| JournalError::WriteFailed(e.to_string()))?;
+        Ok(removed as u64)
+    }
+
+    async fn compact_before(&self, run: &RunId, before_sequence: u64) -> Result<u64, JournalError> {
+        let conn = self.conn.lock().await;
+        let removed = conn
+            .execute(
+                "DELETE FROM journal_entries WHERE agent_id = ?1 AND sequence < ?2",
+                rusqlite::params![run.to_string(), before_sequence as i64],
+            )
+            .map_err(|e| JournalError::WriteFailed(e.to_string()))?;
+        Ok(removed as u64)
+    }
+
+    async fn remove_checkpoints_before(
+        &self,
+        run: &RunId,
+        before_sequence: u64,
+    ) -> Result<u64, JournalError> {
+        let conn = self.conn.lock().await;
+        // `LoopEvent` is externally tagged, so a checkpoint serializes as
+        // `{"event": {"Checkpoint": {...}}}`.
+        let removed = conn
+            .execute(
+                "DELETE FROM journal_entries
+                 WHERE agent_id = ?1 AND sequence < ?2
+                   AND json_extract(entry_json, '$.event.Checkpoint') IS NOT NULL",
+                rusqlite::params![run.to_string(), before_sequence as i64],
+            )
+            .map_err(|e| JournalError::WriteFailed(e.to_string()))?;
+        Ok(removed as u64)
+    }
+
+    async fn runs(&self) -> Result<Vec<RunId>, JournalError> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare("SELECT DISTINCT agent_id FROM journal_entries ORDER BY agent_id")
+            .map_err(|e| JournalError::ReadFailed(e.to_string()))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| JournalError::ReadFailed(e.to_string()))?;
+
+        let mut runs = Vec::new();
+        for row in rows {
+            let id = row.map_err(|e| JournalError::ReadFailed(e.to_string()))?;
+            let uuid = uuid::Uuid::parse_str(&id)
+                .map_err(|e| JournalError::ReadFailed(format!("Invalid run id '{id}': {e}")))?;
+            runs.push(RunId(uuid));
+        }
+        Ok(runs)
+    }
 }
 
 /// Durable journal backed by a `JournalStorage` implementation.
@@ -108,46 +417,77 @@ impl JournalStorage for MemoryJournalStorage {
 pub struct DurableJournal {
     storage: Arc<dyn JournalStorage>,
     sequence: AtomicU64,
-    agent_id: AgentId,
+    run: RunId,
+    /// Agent stamped on every entry, for journals kept per agent
+    agent_id: Option<AgentId>,
 }
 
 impl DurableJournal {
-    /// Create a new durable journal for the given agent.
+    /// Create a new durable journal for the given agent, keyed by its id.
     pub fn new(storage: Arc<dyn JournalStorage>, agent_id: AgentId) -> Self {
         Self {
             storage,
             sequence: AtomicU64::new(0),
-            agent_id,
+            run: agent_id.into(),
+            agent_id: Some(agent_id),
+        }
+    }
+
+    /// Create a durable journal for one run. Entries keep the agent the
+    /// loop recorded on them.
+    pub fn for_run(storage: Arc<dyn JournalStorage>, run: RunId) -> Self {
+        Self {
+            storage,
+            sequence: AtomicU64::new(0),
+            run,
+            agent_id: None,
         }
     }
 
-    /// Initialize from storage, resuming the sequence counter.
+    /// Initialize from storage, resuming the sequence counter after the
+    /// last stored entry so appends never reuse a sequence number.
     pub async fn initialize(&self) -> Result<(), JournalError> {
-        let latest = self.storage.latest_sequence(&self.agent_id).await?;
-        self.sequence.store(latest, Ordering::SeqCst);
+        let latest = self.storage.latest_sequence(&self.run).await?;
+        // `latest_sequence` is 0 both for "no entries" and "one entry at 0".
+        let occupied = !self.storage.read_from(&self.run, latest).await?.is_empty();
+        let next = if occupied { latest + 1 } else { latest };
+        self.sequence.store(next, Ordering::SeqCst);
         Ok(())
     }
 
     /// Replay all journal entries for this agent.
     pub async fn replay(&self) -> Result<Vec<JournalEntry>, JournalError> {
-        self.storage.read_entries(&self.agent_id).await
+        self.storage.read_entries(&self.run).await
     }
 
     /// Replay entries starting from a given sequence.
     pub async fn replay_from(&self, from_sequence: u64) -> Result<Vec<JournalEntry>, JournalError> {
-        self.storage.read_from(&self.agent_id, from_sequence).await
+        self.storage.read_from(&self.run, from_sequence).await
     }
 
     /// Compact (remove) all entries for this agent after successful loop completion.
     pub async fn compact(&self) -> Result<u64, JournalError> {
-        let removed = self.storage.compact(&self.agent_id).await?;
+        let removed = self.storage.compact(&self.run).await?;
         self.sequence.store(0, Ordering::SeqCst);
         Ok(removed)
     }
 
+    /// Drop entries older than `before_sequence`, e.g. everything preceding
+    /// the latest checkpoint once the loop no longer needs them to resume.
+    pub async fn compact_before(&self, before_sequence: u64) -> Result<u64, JournalError> {
+        self.storage
+            .compact_before(&self.run, before_sequence)
+            .await
+    }
+
+    /// The key this journal is stored under.
+    pub fn run_id(&self) -> RunId {
+        self.run
+    }
+
     /// Determine the last completed iteration from the journal.
     pub async fn last_completed_iteration(&self) -> Result<u32, JournalError> {
-        let entries = self.storage.read_entries(&self.agent_id).await?;
+        let entries = self.storage.read_entries(&self.run).await?;
         Ok(entries.iter().map(|e| e.iteration).max().unwrap_or(0))
     }
 }
@@ -157,31 +497,52 @@ impl JournalWriter for DurableJournal {
     async fn append(&self, mut entry: JournalEntry) -> Result<(), JournalError> {
         let seq = self.sequence.fetch_add(1, Ordering::SeqCst);
         entry.sequence = seq;
-        entry.agent_id = self.agent_id;
-        self.storage.store(&entry).await
+        if let Some(agent_id) = self.agent_id {
+            entry.agent_id = agent_id;
+        }
+        self.storage.store(&self.run, &entry).await?;
+
+        // A checkpoint holds the full loop state; once a newer one is on
+        // disk the older snapshots can never be resumed from.
+        if matches!(entry.event, LoopEvent::Checkpoint { .. }) {
+            if let Err(e) = self.storage.remove_checkpoints_before(&self.run, seq).await {
+                tracing::warn!(
+                    "Failed to prune superseded checkpoints for {}: {}",
+                    self.run,
+                    e
+                );
+            }
+        }
+        Ok(())
     }
 
     async fn next_sequence(&self) -> u64 {
         self.sequence.load(Ordering::SeqCst)
     }
+
+    fn wants_checkpoints(&self) -> bool {
+        true
+    }
 }
 
-/// Export all journal entries for an agent as a JSON string for backup.
+/// Export all journal entries for a run as a JSON string for backup.
 pub async fn export_entries(
     storage: &dyn JournalStorage,
-    agent_id: &AgentId,
+    run: &RunId,
 ) -> Result<String, JournalError> {
-    let entries = storage.read_entries(agent_id).await?;
+    let entries = storage.read_entries(run).await?;
     serde_json::to_string_pretty(&entries)
         .map_err(|e| JournalError::WriteFailed(format!("Failed to serialize journal entries: {e}")))
 }
 
-/// Import journal entries from a JSON string (restore from backup).
+/// Import journal entries from a JSON string (restore from backup) into
+/// the given run.
 ///
 /// Entries are appended to storage. Callers should compact first if
 /// a clean restore is desired.
 pub async fn import_entries(
     storage: &dyn JournalStorage,
+    run: &RunId,
     json: &str,
 ) -> Result<usize, JournalError> {
     let entries: Vec<JournalEntry> = serde_json::from_str(json).map_err(|e| {
@@ -189,7 +550,7 @@ pub async fn import_entries(
     })?;
     let count = entries.len();
     for entry in &entries {
-        storage.store(entry).await?;
+        storage.store(run, entry).await?;
     }
     Ok(count)
 }
@@ -217,10 +578,16 @@ mod tests {
         let storage = MemoryJournalStorage::new();
         let agent = AgentId::new();
 
-        storage.store(&make_entry(agent, 0, 0)).await.unwrap();
-        storage.store(&make_entry(agent, 1, 1)).await.unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 0, 0))
+            .await
+            .unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 1, 1))
+            .await
+            .unwrap();
 
-        let entries = storage.read_entries(&agent).await.unwrap();
+        let entries = storage.read_entries(&agent.into()).await.unwrap();
         assert_eq!(entries.len(), 2);
         assert_eq!(entries[0].sequence, 0);
         assert_eq!(entries[1].sequence, 1);
@@ -233,12 +600,12 @@ mod tests {
 
         for i in 0..5 {
             storage
-                .store(&make_entry(agent, i, i as u32))
+                .store(&agent.into(), &make_entry(agent, i, i as u32))
                 .await
                 .unwrap();
         }
 
-        let entries = storage.read_from(&agent, 3).await.unwrap();
+        let entries = storage.read_from(&agent.into(), 3).await.unwrap();
         assert_eq!(entries.len(), 2);
         assert_eq!(entries[0].sequence, 3);
         assert_eq!(entries[1].sequence, 4);
@@ -249,12 +616,18 @@ mod tests {
         let storage = MemoryJournalStorage::new();
         let agent = AgentId::new();
 
-        assert_eq!(storage.latest_sequence(&agent).await.unwrap(), 0);
+        assert_eq!(storage.latest_sequence(&agent.into()).await.unwrap(), 0);
 
-        storage.store(&make_entry(agent, 0, 0)).await.unwrap();
-        storage.store(&make_entry(agent, 5, 2)).await.unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 0, 0))
+            .await
+            .unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 5, 2))
+            .await
+            .unwrap();
 
-        assert_eq!(storage.latest_sequence(&agent).await.unwrap(), 5);
+        assert_eq!(storage.latest_sequence(&agent.into()).await.unwrap(), 5);
     }
 
     #[tokio::test]
@@ -262,13 +635,19 @@ mod tests {
         let storage = MemoryJournalStorage::new();
         let agent = AgentId::new();
 
-        storage.store(&make_entry(agent, 0, 0)).await.unwrap();
-        storage.store(&make_entry(agent, 1, 1)).await.unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 0, 0))
+            .await
+            .unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 1, 1))
+            .await
+            .unwrap();
 
-        let removed = storage.compact(&agent).await.unwrap();
+        let removed = storage.compact(&agent.into()).await.unwrap();
         assert_eq!(removed, 2);
 
-        let entries = storage.read_entries(&agent).await.unwrap();
+        let entries = storage.read_entries(&agent.into()).await.unwrap();
         assert!(entries.is_empty());
     }
 
@@ -278,17 +657,38 @@ mod tests {
         let agent_a = AgentId::new();
         let agent_b = AgentId::new();
 
-        storage.store(&make_entry(agent_a, 0, 0)).await.unwrap();
-        storage.store(&make_entry(agent_b, 0, 0)).await.unwrap();
-        storage.store(&make_entry(agent_a, 1, 1)).await.unwrap();
-
-        assert_eq!(storage.read_entries(&agent_a).await.unwrap().len(), 2);
-        assert_eq!(storage.read_entries(&agent_b).await.unwrap().len(), 1);
+        storage
+            .store(&agent_a.into(), &make_entry(agent_a, 0, 0))
+            .await
+            .unwrap();
+        storage
+            .store(&agent_b.into(), &make_entry(agent_b, 0, 0))
+            .await
+            .unwrap();
+        storage
+            .store(&agent_a.into(), &make_entry(agent_a, 1, 1))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            storage.read_entries(&agent_a.into()).await.unwrap().len(),
+            2
+        );
+        assert_eq!(
+            storage.read_entries(&agent_b.into()).await.unwrap().len(),
+            1
+        );
 
         // Compacting agent_a shouldn't affect agent_b
-        storage.compact(&agent_a).await.unwrap();
-        assert_eq!(storage.read_entries(&agent_a).await.unwrap().len(), 0);
-        assert_eq!(storage.read_entries(&agent_b).await.unwrap().len(), 1);
+        storage.compact(&agent_a.into()).await.unwrap();
+        assert_eq!(
+            storage.read_entries(&agent_a.into()).await.unwrap().len(),
+            0
+        );
+        assert_eq!(
+            storage.read_entries(&agent_b.into()).await.unwrap().len(),
+            1
+        );
     }
 
     #[tokio::test]
@@ -331,19 +731,28 @@ mod tests {
         // Write some entries directly to storage
         for i in 0..3 {
             storage
-                .store(&make_entry(agent, i, i as u32))
+                .store(&agent.into(), &make_entry(agent, i, i as u32))
                 .await
                 .unwrap();
         }
 
-        // Create a new journal and initialize — should resume from sequence 2
-        let journal = DurableJournal::new(storage, agent);
+        // Create a new journal and initialize — should resume after sequence 2
+        let journal = DurableJournal::new(storage.clone(), agent);
         journal.initialize().await.unwrap();
-        assert_eq!(journal.next_sequence().await, 2);
+        assert_eq!(journal.next_sequence().await, 3);
 
-        // Next append should get sequence 2
+        // Next append should get sequence 3
         journal.append(make_entry(agent, 0, 3)).await.unwrap();
-        assert_eq!(journal.next_sequence().await, 3);
+        assert_eq!(journal.next_sequence().await, 4);
+        assert_eq!(storage.latest_sequence(&agent.into()).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_durable_journal_initialize_empty_storage() {
+        let storage = Arc::new(MemoryJournalStorage::new());
+        let journal = DurableJournal::new(storage, AgentId::new());
+        journal.initialize().await.unwrap();
+        assert_eq!(journal.next_sequence().await, 0);
     }
 
     #[tokio::test]
@@ -384,10 +793,16 @@ mod tests {
         let storage = MemoryJournalStorage::new();
         let agent = AgentId::new();
 
-        storage.store(&make_entry(agent, 0, 0)).await.unwrap();
-        storage.store(&make_entry(agent, 1, 1)).await.unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 0, 0))
+            .await
+            .unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 1, 1))
+            .await
+            .unwrap();
 
-        let json = export_entries(&storage, &agent).await.unwrap();
+        let json = export_entries(&storage, &agent.into()).await.unwrap();
         assert!(json.contains("sequence"));
 
         // Should be valid JSON array
@@ -401,23 +816,243 @@ mod tests {
         let agent = AgentId::new();
 
         // Create entries, export, then import into a fresh storage
-        storage.store(&make_entry(agent, 0, 0)).await.unwrap();
-        storage.store(&make_entry(agent, 1, 1)).await.unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 0, 0))
+            .await
+            .unwrap();
+        storage
+            .store(&agent.into(), &make_entry(agent, 1, 1))
+            .await
+            .unwrap();
 
-        let json = export_entries(&storage, &agent).await.unwrap();
+        let json = export_entries(&storage, &agent.into()).await.unwrap();
 
         let fresh_storage = MemoryJournalStorage::new();
-        let count = import_entries(&fresh_storage, &json).await.unwrap();
+        let count = import_entries(&fresh_storage, &agent.into(), &json)
+            .await
+            .unwrap();
         assert_eq!(count, 2);
 
-        let entries = fresh_storage.read_entries(&agent).await.unwrap();
+        let entries = fresh_storage.read_entries(&agent.into()).await.unwrap();
         assert_eq!(entries.len(), 2);
     }
 
     #[tokio::test]
     async fn test_import_invalid_json() {
         let storage = MemoryJournalStorage::new();
-        let result = import_entries(&storage, "not valid json").await;
+        let result = import_entries(&storage, &RunId::new(), "not valid json").await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_memory_storage_compact_before_and_runs() {
+        let storage = MemoryJournalStorage::new();
+        let agent1 = AgentId::new();
+        let agent2 = AgentId::new();
+        for i in 0..4 {
+            storage
+                .store(&agent1.into(), &make_entry(agent1, i, 0))
+                .await
+                .unwrap();
+        }
+        storage
+            .store(&agent2.into(), &make_entry(agent2, 0, 0))
+            .await
+            .unwrap();
+
+        assert_eq!(storage.compact_before(&agent1.into(), 2).await.unwrap(), 2);
+        let remaining = storage.read_entries(&agent1.into()).await.unwrap();
+        assert_eq!(remaining[0].sequence, 2);
+        assert_eq!(storage.read_entries(&agent2.into()).await.unwrap().len(), 1);
+
+        let runs = storage.runs().await.unwrap();
+        assert_eq!(runs, vec![agent1.into(), agent2.into()]);
+    }
+
+    #[cfg(feature = "cron")]
+    #[tokio::test]
+    async fn test_sqlite_storage_roundtrip() {
+        let storage = SqliteJournalStorage::open_in_memory().unwrap();
+        let agent1 = AgentId::new();
+        let agent2 = AgentId::new();
+        for i in 0..5 {
+            storage
+                .store(&agent1.into(), &make_entry(agent1, i, i as u32))
+                .await
+                .unwrap();
+        }
+        storage
+            .store(&agent2.into(), &make_entry(agent2, 0, 0))
+            .await
+            .unwrap();
+
+        let entries = storage.read_entries(&agent1.into()).await.unwrap();
+        assert_eq!(entries.len(), 5);
+        assert!(entries.windows(2).all(|w| w[0].sequence < w[1].sequence));
+        assert!(matches!(entries[0].event, LoopEvent::Started { .. }));
+
+        let tail = storage.read_from(&agent1.into(), 3).await.unwrap();
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[0].iteration, 3);
+
+        assert_eq!(storage.latest_sequence(&agent1.into()).await.unwrap(), 4);
+        assert_eq!(storage.latest_sequence(&RunId::new()).await.unwrap(), 0);
+
+        let mut runs = storage.runs().await.unwrap();
+        let mut expected = vec![RunId::from(agent1), RunId::from(agent2)];
+        runs.sort();
+        expected.sort();
+        assert_eq!(runs, expected);
+    }
+
+    #[cfg(feature = "cron")]
+    #[tokio::test]
+    async fn test_sqlite_storage_rejects_duplicate_sequence() {
+        let storage = SqliteJournalStorage::open_in_memory().unwrap();
+        let agent = AgentId::new();
+        storage
+            .store(&agent.into(), &make_entry(agent, 0, 0))
+            .await
+            .unwrap();
+        assert!(matches!(
+            storage.store(&agent.into(), &make_entry(agent, 0, 0)).await,
+            Err(JournalError::WriteFailed(_))
+        ));
+    }
+
+    #[cfg(feature = "cron")]
+    #[tokio::test]
+    async fn test_sqlite_storage_compaction() {
+        let storage = SqliteJournalStorage::open_in_memory().unwrap();
+        let agent1 = AgentId::new();
+        let agent2 = AgentId::new();
+        for i in 0..4 {
+            storage
+                .store(&agent1.into(), &make_entry(agent1, i, 0))
+                .await
+                .unwrap();
+        }
+        storage
+            .store(&agent2.into(), &make_entry(agent2, 0, 0))
+            .await
+            .unwrap();
+
+        assert_eq!(storage.compact_before(&agent1.into(), 3).await.unwrap(), 3);
+        assert_eq!(storage.read_entries(&agent1.into()).await.unwrap().len(), 1);
+
+        assert_eq!(storage.compact(&agent1.into()).await.unwrap(), 1);
+        assert!(storage
+            .read_entries(&agent1.into())
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(storage.runs().await.unwrap(), vec![RunId::from(agent2)]);
+    }
+
+    #[cfg(feature = "cron")]
+    #[tokio::test]
+    async fn test_sqlite_durable_journal_survives_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.db");
+        let agent = AgentId::new();
+
+        {
+            let storage = Arc::new(SqliteJournalStorage::open(&path).unwrap());
+            let journal = DurableJournal::new(storage.clone(), agent);
+            journal.initialize().await.unwrap();
+            for i in 0..3 {
+                journal.append(make_entry(agent, 0, i)).await.unwrap();
+            }
+            storage.checkpoint_wal().await.unwrap();
+        }
+
+        let storage = Arc::new(SqliteJournalStorage::open(&path).unwrap());
+        let journal = DurableJournal::new(storage, agent);
+        journal.initialize().await.unwrap();
+        assert_eq!(journal.next_sequence().await, 3);
+        assert_eq!(journal.last_completed_iteration().await.unwrap(), 2);
+
+        journal.append(make_entry(agent, 0, 3)).await.unwrap();
+        let entries = journal.replay().await.unwrap();
+        let sequences: Vec<u64> = entries.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![0, 1, 2, 3]);
+    }
+
+    /// A run-keyed journal keeps the agent the loop recorded, and its
+    /// storage lists the run rather than a made-up agent.
+    #[tokio::test]
+    async fn test_run_journal_keeps_the_recorded_agent() {
+        let storage = Arc::new(MemoryJournalStorage::new());
+        let agent = AgentId::new();
+        let first = DurableJournal::for_run(storage.clone(), RunId::new());
+        let second = DurableJournal::for_run(storage.clone(), RunId::new());
+
+        first.append(make_entry(agent, 0, 0)).await.unwrap();
+        second.append(make_entry(agent, 0, 0)).await.unwrap();
+        first.append(make_entry(agent, 0, 1)).await.unwrap();
+
+        let entries = first.replay().await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.agent_id == agent));
+        assert_eq!(second.replay().await.unwrap().len(), 1);
+        assert_eq!(
+            storage.runs().await.unwrap(),
+            vec![first.run_id(), second.run_id()]
+        );
+    }
+
+    /// A backend implementing only the required methods still works with
+    /// `DurableJournal`: checkpoints are kept and listing is refused.
+    #[tokio::test]
+    async fn test_storage_defaults_for_minimal_backends() {
+        struct Minimal(MemoryJournalStorage);
+
+        #[async_trait::async_trait]
+        impl JournalStorage for Minimal {
+            async fn store(&self, run: &RunId, entry: &JournalEntry) -> Result<(), JournalError> {
+                self.0.store(run, entry).await
+            }
+            async fn read_entries(&self, run: &RunId) -> Result<Vec<JournalEntry>, JournalError> {
+                self.0.read_entries(run).await
+            }
+            async fn read_from(
+                &self,
+                run: &RunId,
+                from_sequence: u64,
+            ) -> Result<Vec<JournalEntry>, JournalError> {
+                self.0.read_from(run, from_sequence).await
+            }
+            async fn latest_sequence(&self, run: &RunId) -> Result<u64, JournalError> {
+                self.0.latest_sequence(run).await
+            }
+            async fn compact(&self, run: &RunId) -> Result<u64, JournalError> {
+                self.0.compact(run).await
+            }
+        }
+
+        let storage = Arc::new(Minimal(MemoryJournalStorage::new()));
+        let agent = AgentId::new();
+        let journal = DurableJournal::new(storage.clone(), agent);
+        for iteration in 0..2 {
+            let mut entry = make_entry(agent, 0, iteration);
+            entry.event = LoopEvent::Checkpoint {
+                iteration,
+                state: Box::new(crate::reasoning::loop_types::LoopState::new(
+                    agent,
+                    crate::reasoning::conversation::Conversation::new(),
+                )),
+            };
+            journal.append(entry).await.unwrap();
+        }
+
+        assert_eq!(journal.replay().await.unwrap().len(), 2);
+        assert!(matches!(
+            journal.compact_before(1).await,
+            Err(JournalError::Unsupported(_))
+        ));
+        assert!(matches!(
+            storage.runs().await,
+            Err(JournalError::Unsupported(_))
+        ));
+    }
 }
@@ -8,7 +8,7 @@ use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 
 use crate::reasoning::conversation::Conversation;
-use crate::reasoning::inference::{ToolDefinition, Usage};
+use crate::reasoning::inference::{StreamEvent, ToolDefinition, Usage};
 use crate::types::AgentId;
 
 /// An observation that feeds into the reasoning step.
@@ -31,6 +31,10 @@ pub struct Observation {
     /// Metadata for logging and auditing.
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+    /// Structured results (tables, images, JSON) alongside `content`,
+    /// which carries their plain-text rendering.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<RichOutput>,
 }
 
 impl Observation {
@@ -42,6 +46,7 @@ impl Observation {
             is_error: false,
             call_id: None,
             metadata: HashMap::new(),
+            outputs: Vec::new(),
         }
     }
 
@@ -53,6 +58,7 @@ impl Observation {
             is_error: true,
             call_id: None,
             metadata: HashMap::new(),
+            outputs: Vec::new(),
         }
     }
 
@@ -64,6 +70,7 @@ impl Observation {
             is_error: true,
             call_id: None,
             metadata: HashMap::new(),
+            outputs: Vec::new(),
         }
     }
 
@@ -72,6 +79,30 @@ impl Observation {
         self.call_id = Some(call_id.into());
         self
     }
+
+    /// Attach structured results to this observation.
+    pub fn with_outputs(mut self, outputs: Vec<RichOutput>) -> Self {
+        self.outputs = outputs;
+        self
+    }
+}
+
+/// A structured result carried by an observation, such as a table or plot
+/// produced by a code cell.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RichOutput {
+    /// A JSON value.
+    Json { value: serde_json::Value },
+    /// Tabular data, one inner vector per row in `columns` order.
+    Table {
+        columns: Vec<String>,
+        rows: Vec<Vec<serde_json::Value>>,
+    },
+    /// An encoded image; `data` is base64.
+    Image { mime_type: String, data: String },
+    /// An HTML fragment.
+    Html { html: String },
 }
 
 /// An action proposed by the reasoning step, pending policy evaluation.
@@ -176,6 +207,7 @@ impl LoopState {
         self.total_usage.prompt_tokens += usage.prompt_tokens;
         self.total_usage.completion_tokens += usage.completion_tokens;
         self.total_usage.total_tokens += usage.total_tokens;
+        self.total_usage.cached_prompt_tokens += usage.cached_prompt_tokens;
     }
 
     /// Get elapsed time since loop start.
@@ -228,6 +260,27 @@ pub struct LoopConfig {
     /// loop should never terminate on a plain-text response.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tool_choice: Option<crate::reasoning::inference::ToolChoice>,
+    /// Drive inference through `InferenceProvider::complete_stream` and
+    /// journal every delta as a `LoopEvent::InferenceDelta`. Meant for
+    /// interactive callers reading a `StreamingJournal`; leave off when
+    /// journaling to durable storage, which would persist every token.
+    #[serde(default)]
+    pub stream_inference: bool,
+    /// Model requested on every inference call. `None` uses the provider's
+    /// default. A spend budget may switch a running loop to a cheaper one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Cron job and API key this run's spend is billed to, alongside the
+    /// agent. Only consulted when the runner has a spend tracker.
+    #[serde(
+        default,
+        skip_serializing_if = "crate::reasoning::spend::SpendTags::is_empty"
+    )]
+    pub spend_tags: crate::reasoning::spend::SpendTags,
+    /// Sandbox session the runner bound to this run, for executors that run
+    /// code. Runtime-only: never journaled, so a resumed run binds a fresh one.
+    #[serde(skip)]
+    pub sandbox_session: Option<crate::sandbox::SessionBinding>,
     /// Tool profile for filtering tools visible to the LLM.
     #[cfg(feature = "orga-adaptive")]
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -277,6 +330,10 @@ impl Default for LoopConfig {
             delegation_chain: Vec::new(),
             tool_definitions: Vec::new(),
             tool_choice: None,
+            stream_inference: false,
+            model: None,
+            spend_tags: Default::default(),
+            sandbox_session: None,
             #[cfg(feature = "orga-adaptive")]
             tool_profile: None,
             #[cfg(feature = "orga-adaptive")]
@@ -345,6 +402,14 @@ pub enum TerminationReason {
     PolicyDenial { reason: String },
     /// An unrecoverable error occurred.
     Error { message: String },
+    /// A spend limit was reached before the next inference call.
+    BudgetExceeded {
+        /// `run`, or the billed scope (e.g. `api_key:<id>`) whose limit was hit.
+        scope: String,
+        period: crate::reasoning::spend::BudgetPeriod,
+        limit_usd: f64,
+        spent_usd: f64,
+    },
 }
 
 /// Events emitted during loop execution for observability.
@@ -355,6 +420,24 @@ pub enum LoopEvent {
         agent_id: AgentId,
         config: Box<LoopConfig>,
     },
+    /// Snapshot of the loop state at the top of an iteration. Only written
+    /// for journals that opt in via `JournalWriter::wants_checkpoints`;
+    /// `ReasoningLoopRunner::resume` rebuilds the loop from the latest one.
+    Checkpoint {
+        iteration: u32,
+        state: Box<LoopState>,
+    },
+    /// An incremental inference event, emitted while the model is still
+    /// generating (only when `LoopConfig::stream_inference` is set).
+    /// `iteration` matches the `ReasoningComplete` that follows.
+    InferenceDelta { iteration: u32, delta: StreamEvent },
+    /// One call made by a failover provider chain (retry, failover, or the
+    /// final outcome). Written once the inference step returns, before its
+    /// `ReasoningComplete`.
+    InferenceAttempt {
+        iteration: u32,
+        attempt: crate::reasoning::providers::failover::InferenceAttempt,
+    },
     /// Reasoning step completed.
     ReasoningComplete {
         iteration: u32,
@@ -438,6 +521,13 @@ pub trait JournalWriter: Send + Sync {
     async fn append(&self, entry: JournalEntry) -> Result<(), JournalError>;
     /// Get the next sequence number.
     async fn next_sequence(&self) -> u64;
+    /// Whether the loop should journal full `Checkpoint` snapshots.
+    ///
+    /// Only durable journals need them (to resume after a crash), so the
+    /// default is `false` to keep in-memory journals small.
+    fn wants_checkpoints(&self) -> bool {
+        false
+    }
 }
 
 /// In-memory journal that retains entries in a bounded ring buffer.
@@ -505,6 +595,8 @@ pub enum JournalError {
     ReadFailed(String),
     #[error("Journal sequence error: expected {expected}, got {actual}")]
     SequenceError { expected: u64, actual: u64 },
+    #[error("Journal storage does not support {0}")]
+    Unsupported(String),
 }
 
 #[cfg(test)]
@@ -540,11 +632,13 @@ mod tests {
             prompt_tokens: 100,
             completion_tokens: 50,
             total_tokens: 150,
+            cached_prompt_tokens: 0,
         });
         state.add_usage(&Usage {
             prompt_tokens: 200,
             completion_tokens: 80,
             total_tokens: 280,
+            cached_prompt_tokens: 0,
         });
         assert_eq!(state.total_usage.prompt_tokens, 300);
         assert_eq!(state.total_usage.completion_tokens, 130);
@@ -702,6 +796,7 @@ mod tests {
                 prompt_tokens: 1000,
                 completion_tokens: 500,
                 total_tokens: 1500,
+                cached_prompt_tokens: 0,
             },
             duration: Duration::from_secs(10),
         };
@@ -709,6 +804,38 @@ mod tests {
         assert!(json.contains("Terminated"));
     }
 
+    #[test]
+    fn test_inference_delta_event_serde() {
+        let event = LoopEvent::InferenceDelta {
+            iteration: 1,
+            delta: StreamEvent::TextDelta { text: "Hel".into() },
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("InferenceDelta"));
+        assert!(json.contains("text_delta"));
+        let restored: LoopEvent = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            restored,
+            LoopEvent::InferenceDelta {
+                iteration: 1,
+                delta: StreamEvent::TextDelta { .. }
+            }
+        ));
+
+        // Older serialized configs without the flag still load.
+        let config: LoopConfig = serde_json::from_value(serde_json::json!({
+            "max_iterations": 5,
+            "max_total_tokens": 1000,
+            "timeout": {"secs": 10, "nanos": 0},
+            "default_recovery": "DeadLetter",
+            "tool_timeout": {"secs": 5, "nanos": 0},
+            "max_concurrent_tools": 1,
+            "context_token_budget": 1000
+        }))
+        .unwrap();
+        assert!(!config.stream_inference);
+    }
+
     #[test]
     fn loop_config_default_has_delegation_guards() {
         let c = LoopConfig::default();
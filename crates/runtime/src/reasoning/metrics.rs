@@ -24,6 +24,9 @@ struct MetricsInner {
     tool_calls: AtomicU64,
     policy_denials: AtomicU64,
     tool_errors: AtomicU64,
+    inference_attempts: AtomicU64,
+    inference_retries: AtomicU64,
+    provider_failovers: AtomicU64,
 }
 
 impl Default for ReasoningMetrics {
@@ -45,6 +48,9 @@ impl ReasoningMetrics {
                 tool_calls: AtomicU64::new(0),
                 policy_denials: AtomicU64::new(0),
                 tool_errors: AtomicU64::new(0),
+                inference_attempts: AtomicU64::new(0),
+                inference_retries: AtomicU64::new(0),
+                provider_failovers: AtomicU64::new(0),
             }),
         }
     }
@@ -87,6 +93,25 @@ impl ReasoningMetrics {
         self.inner.policy_denials.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record one call to an inference provider.
+    pub fn record_inference_attempt(&self) {
+        self.inner
+            .inference_attempts
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record an inference call being retried on the same provider.
+    pub fn record_inference_retry(&self) {
+        self.inner.inference_retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a switch to the next provider in a failover chain.
+    pub fn record_provider_failover(&self) {
+        self.inner
+            .provider_failovers
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Get a snapshot of all metrics.
     pub fn snapshot(&self) -> MetricsSnapshot {
         MetricsSnapshot {
@@ -98,6 +123,9 @@ impl ReasoningMetrics {
             tool_calls: self.inner.tool_calls.load(Ordering::Relaxed),
             tool_errors: self.inner.tool_errors.load(Ordering::Relaxed),
             policy_denials: self.inner.policy_denials.load(Ordering::Relaxed),
+            inference_attempts: self.inner.inference_attempts.load(Ordering::Relaxed),
+            inference_retries: self.inner.inference_retries.load(Ordering::Relaxed),
+            provider_failovers: self.inner.provider_failovers.load(Ordering::Relaxed),
         }
     }
 
@@ -111,6 +139,9 @@ impl ReasoningMetrics {
         self.inner.tool_calls.store(0, Ordering::Relaxed);
         self.inner.tool_errors.store(0, Ordering::Relaxed);
         self.inner.policy_denials.store(0, Ordering::Relaxed);
+        self.inner.inference_attempts.store(0, Ordering::Relaxed);
+        self.inner.inference_retries.store(0, Ordering::Relaxed);
+        self.inner.provider_failovers.store(0, Ordering::Relaxed);
     }
 }
 
@@ -125,6 +156,9 @@ pub struct MetricsSnapshot {
     pub tool_calls: u64,
     pub tool_errors: u64,
     pub policy_denials: u64,
+    pub inference_attempts: u64,
+    pub inference_retries: u64,
+    pub provider_failovers: u64,
 }
 
 impl MetricsSnapshot {
@@ -24,6 +24,7 @@ pub mod loop_types;
 pub mod phases;
 pub mod policy_bridge;
 pub mod reasoning_loop;
+pub mod sandbox_executor;
 pub mod tool_executor_builder;
 
 // Phase 3 modules
@@ -41,6 +42,7 @@ pub mod cedar_gate;
 pub mod journal;
 pub mod metrics;
 pub mod scheduler;
+pub mod spend;
 pub mod tracing_spans;
 
 #[cfg(feature = "cedar")]
@@ -48,8 +50,8 @@ pub use cedar_gate::{CedarPolicy, CedarPolicyGate};
 pub use conversation::{Conversation, ConversationMessage, MessageRole};
 pub use governed::{governed_gate, GateOptions};
 pub use inference::{
-    InferenceOptions, InferenceProvider, InferenceResponse, ResponseFormat, ToolCallRequest,
-    ToolDefinition, Usage,
+    InferenceOptions, InferenceProvider, InferenceResponse, InferenceStream, ResponseFormat,
+    StreamAccumulator, StreamEvent, ToolCallRequest, ToolDefinition, Usage,
 };
 pub use knowledge_bridge::{KnowledgeBridge, KnowledgeConfig};
 pub use knowledge_executor::KnowledgeAwareExecutor;
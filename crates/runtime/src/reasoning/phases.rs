@@ -23,11 +23,15 @@ use std::marker::PhantomData;
 
 use crate::reasoning::circuit_breaker::CircuitBreakerRegistry;
 use crate::reasoning::context_manager::ContextManager;
-use crate::reasoning::conversation::Conversation;
+use crate::reasoning::conversation::{ContentPart, Conversation, MediaSource};
 use crate::reasoning::executor::ActionExecutor;
-use crate::reasoning::inference::{InferenceProvider, ToolDefinition};
+use crate::reasoning::inference::{
+    InferenceError, InferenceOptions, InferenceProvider, InferenceResponse, StreamAccumulator,
+    ToolDefinition,
+};
 use crate::reasoning::loop_types::*;
 use crate::reasoning::policy_bridge::ReasoningPolicyGate;
+use crate::reasoning::providers::failover::collect_attempts;
 
 // ── Phase markers (zero-sized types) ────────────────────────────────
 
@@ -53,6 +57,8 @@ impl AgentPhase for Observing {}
 pub struct ReasoningOutput {
     /// Actions proposed by the LLM.
     pub proposed_actions: Vec<ProposedAction>,
+    /// Model that served the inference call, as reported by the provider.
+    pub served_model: String,
 }
 
 /// Data produced by the policy check phase, consumed by tool dispatch.
@@ -118,12 +124,15 @@ impl AgentLoop<Reasoning> {
     /// `delegation_available` reflects whether the runner holds a delegation
     /// handle; it gates the `delegate` tool-call conversion (see
     /// [`tool_call_to_action`]) so runners that implement `delegate` in their
-    /// own executor keep receiving it as a tool call.
+    /// own executor keep receiving it as a tool call. When
+    /// `LoopConfig::stream_inference` is set, each streamed delta is written
+    /// to `journal` as it arrives.
     pub async fn produce_output(
         mut self,
         provider: &dyn InferenceProvider,
         context_manager: &dyn ContextManager,
         delegation_available: bool,
+        journal: &dyn JournalWriter,
     ) -> Result<AgentLoop<PolicyCheck>, LoopTermination> {
         self.state.current_phase = "reasoning".into();
 
@@ -190,11 +199,34 @@ impl AgentLoop<Reasoning> {
             // force tool_use on every turn — required for iterate-until-
             // done agents).
             tool_choice: self.config.tool_choice.clone(),
+            model: self.config.model.clone(),
             ..Default::default()
         };
 
-        // Call the inference provider
-        let response = match provider.complete(&self.state.conversation, &options).await {
+        // Call the inference provider. A failover chain reports each
+        // attempt it made; journal them under the iteration this step
+        // completes, whether or not the call ultimately succeeded.
+        let (inference, attempts) = collect_attempts(async {
+            if self.config.stream_inference {
+                self.stream_inference(provider, &options, journal).await
+            } else {
+                provider.complete(&self.state.conversation, &options).await
+            }
+        })
+        .await;
+        let iteration = self.state.iteration + 1;
+        for attempt in attempts {
+            let _ = journal
+                .append(JournalEntry {
+                    sequence: journal.next_sequence().await,
+                    timestamp: chrono::Utc::now(),
+                    agent_id: self.state.agent_id,
+                    iteration,
+                    event: LoopEvent::InferenceAttempt { iteration, attempt },
+                })
+                .await;
+        }
+        let response = match inference {
             Ok(r) => r,
             Err(e) => {
                 return Err(LoopTermination {
@@ -231,6 +263,8 @@ impl AgentLoop<Reasoning> {
             });
         }
 
+        let served_model = response.model.clone();
+
         // Parse the response into proposed actions
         let proposed_actions = if response.has_tool_calls() {
             // Add the assistant message with tool calls to conversation
@@ -270,12 +304,59 @@ impl AgentLoop<Reasoning> {
         Ok(AgentLoop {
             state: self.state,
             config: self.config,
-            phase_data: Some(PhaseData::Reasoning(ReasoningOutput { proposed_actions })),
+            phase_data: Some(PhaseData::Reasoning(ReasoningOutput {
+                proposed_actions,
+                served_model,
+            })),
             _phase: PhantomData,
         })
     }
 }
 
+impl AgentLoop<Reasoning> {
+    /// Drive one inference call through `complete_stream`, journaling each
+    /// event as a `LoopEvent::InferenceDelta` and folding the stream back
+    /// into the response the rest of the phase expects.
+    async fn stream_inference(
+        &self,
+        provider: &dyn InferenceProvider,
+        options: &InferenceOptions,
+        journal: &dyn JournalWriter,
+    ) -> Result<InferenceResponse, InferenceError> {
+        use futures::StreamExt;
+
+        // The iteration counter is bumped once the step completes; tag the
+        // deltas with the value the matching ReasoningComplete will carry.
+        let iteration = self.state.iteration + 1;
+        let mut stream = provider
+            .complete_stream(&self.state.conversation, options)
+            .await?;
+        let mut accumulator = StreamAccumulator::new();
+        while let Some(event) = stream.next().await {
+            let event = event?;
+            accumulator.push(&event);
+            let _ = journal
+                .append(JournalEntry {
+                    sequence: journal.next_sequence().await,
+                    timestamp: chrono::Utc::now(),
+                    agent_id: self.state.agent_id,
+                    iteration,
+                    event: LoopEvent::InferenceDelta {
+                        iteration,
+                        delta: event,
+                    },
+                })
+                .await;
+        }
+        if !accumulator.is_finished() {
+            return Err(InferenceError::ParseError(
+                "stream ended before the model finished".into(),
+            ));
+        }
+        Ok(accumulator.finish(options.model.as_deref().unwrap_or(provider.default_model())))
+    }
+}
+
 impl AgentLoop<PolicyCheck> {
     /// Return a clone of the proposed actions from the reasoning phase.
     /// Used by the loop driver to emit `ReasoningComplete` journal events
@@ -287,6 +368,18 @@ impl AgentLoop<PolicyCheck> {
         }
     }
 
+    /// The model that served the reasoning step, or `None` when the provider
+    /// did not report one. After a failover this differs from the model
+    /// that was requested.
+    pub fn served_model(&self) -> Option<&str> {
+        match &self.phase_data {
+            Some(PhaseData::Reasoning(output)) if !output.served_model.is_empty() => {
+                Some(&output.served_model)
+            }
+            _ => None,
+        }
+    }
+
     /// Evaluate all proposed actions against the policy gate.
     ///
     /// Consumes `self` and produces `AgentLoop<ToolDispatching>`.
@@ -604,12 +697,22 @@ impl AgentLoop<ToolDispatching> {
         for obs in &observations {
             let tool_call_id = obs.call_id.as_deref().unwrap_or(&obs.source);
             if !obs.is_error {
+                // Images a tool returned (plots, screenshots) go to the model
+                // as image parts alongside the text rendering.
+                let images = obs.outputs.iter().filter_map(|output| match output {
+                    RichOutput::Image { mime_type, data } => Some(ContentPart::Image {
+                        media_type: mime_type.clone(),
+                        source: MediaSource::Base64 { data: data.clone() },
+                    }),
+                    _ => None,
+                });
                 self.state.conversation.push(
                     crate::reasoning::conversation::ConversationMessage::tool_result(
                         tool_call_id,
                         &obs.source,
                         &obs.content,
-                    ),
+                    )
+                    .with_parts(images),
                 );
             } else {
                 self.state.conversation.push(
@@ -653,6 +756,16 @@ impl AgentLoop<Observing> {
         }
     }
 
+    /// Return how many observations from the dispatch phase are errors.
+    pub fn error_count(&self) -> usize {
+        match &self.phase_data {
+            Some(PhaseData::Dispatch(output)) => {
+                output.observations.iter().filter(|o| o.is_error).count()
+            }
+            _ => 0,
+        }
+    }
+
     /// Collect observations and decide whether to continue or terminate.
     ///
     /// Consumes `self` and returns either a new Reasoning phase or the final result.
@@ -715,6 +828,7 @@ pub enum LoopTerminationReason {
     MaxTokens { tokens: u32 },
     Timeout,
     Error { message: String },
+    BudgetExceeded(crate::reasoning::spend::BudgetBreach),
 }
 
 impl LoopTermination {
@@ -727,6 +841,12 @@ impl LoopTermination {
             LoopTerminationReason::Error { message } => TerminationReason::Error {
                 message: message.clone(),
             },
+            LoopTerminationReason::BudgetExceeded(breach) => TerminationReason::BudgetExceeded {
+                scope: breach.scope.clone(),
+                period: breach.period,
+                limit_usd: breach.limit_usd,
+                spent_usd: breach.spent_usd,
+            },
         };
         LoopResult {
             output: String::new(),
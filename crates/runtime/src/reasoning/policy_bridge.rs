@@ -6,6 +6,7 @@
 
 use async_trait::async_trait;
 
+use crate::reasoning::conversation::Conversation;
 use crate::reasoning::loop_types::{LoopDecision, LoopState, ProposedAction};
 use crate::types::AgentId;
 
@@ -29,6 +30,41 @@ pub trait ReasoningPolicyGate: Send + Sync {
     ) -> LoopDecision;
 }
 
+/// Evaluate `content` as a `ProposedAction::Respond` from `agent_id` and
+/// return what may be sent back to the caller.
+///
+/// For surfaces that answer with a single completion instead of running a
+/// reasoning loop. `origin` labels the surface the response leaves through
+/// (e.g. `chat-adapter:<agent>`, `mcp:<agent>`).
+pub async fn gate_response(
+    policy_gate: &dyn ReasoningPolicyGate,
+    agent_id: AgentId,
+    origin: &str,
+    content: String,
+) -> Result<String, String> {
+    let proposed = ProposedAction::Respond {
+        content: content.clone(),
+    };
+    let state = LoopState::new(agent_id, Conversation::with_system(origin.to_string()));
+    match policy_gate
+        .evaluate_action(&agent_id, &proposed, &state)
+        .await
+    {
+        LoopDecision::Allow => Ok(content),
+        LoopDecision::Modify {
+            modified_action, ..
+        } => match *modified_action {
+            ProposedAction::Respond { content } => Ok(content),
+            other => Err(format!(
+                "policy gate replaced this response with a non-response action ({other:?}); withholding it"
+            )),
+        },
+        LoopDecision::Deny { reason } => {
+            Err(format!("response withheld by policy gate: {reason}"))
+        }
+    }
+}
+
 /// Default policy gate.
 ///
 /// In its non-permissive mode (`DefaultPolicyGate::new()`) this gate is
@@ -258,8 +294,6 @@ impl ReasoningPolicyGate for ToolFilterPolicyGate {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::reasoning::conversation::Conversation;
-    use crate::reasoning::loop_types::LoopState;
 
     #[tokio::test]
     async fn test_permissive_dev_only_allows_all_actions() {
@@ -409,4 +443,59 @@ mod tests {
         let decision = gate.evaluate_action(&agent_id, &tool_call, &state).await;
         assert!(matches!(decision, LoopDecision::Allow));
     }
+
+    /// Returns the same decision for every action.
+    struct FixedGate(LoopDecision);
+
+    #[async_trait]
+    impl ReasoningPolicyGate for FixedGate {
+        async fn evaluate_action(
+            &self,
+            _agent_id: &AgentId,
+            _action: &ProposedAction,
+            _state: &LoopState,
+        ) -> LoopDecision {
+            self.0.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gate_response_applies_the_decision() {
+        let agent_id = AgentId::new();
+        let allow = FixedGate(LoopDecision::Allow);
+        assert_eq!(
+            gate_response(&allow, agent_id, "mcp:a", "hi".into()).await,
+            Ok("hi".to_string())
+        );
+
+        let redact = FixedGate(LoopDecision::Modify {
+            modified_action: Box::new(ProposedAction::Respond {
+                content: "[redacted]".into(),
+            }),
+            reason: "pii".into(),
+        });
+        assert_eq!(
+            gate_response(&redact, agent_id, "mcp:a", "hi".into()).await,
+            Ok("[redacted]".to_string())
+        );
+
+        let swap = FixedGate(LoopDecision::Modify {
+            modified_action: Box::new(ProposedAction::Terminate {
+                reason: "no".into(),
+                output: String::new(),
+            }),
+            reason: "swap".into(),
+        });
+        assert!(gate_response(&swap, agent_id, "mcp:a", "hi".into())
+            .await
+            .is_err());
+
+        let deny = FixedGate(LoopDecision::Deny {
+            reason: "blocked".into(),
+        });
+        let err = gate_response(&deny, agent_id, "mcp:a", "hi".into())
+            .await
+            .unwrap_err();
+        assert!(err.contains("blocked"));
+    }
 }
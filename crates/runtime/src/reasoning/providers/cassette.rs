@@ -0,0 +1,715 @@
+//! Record/replay cassette provider
+//!
+//! Wraps an `InferenceProvider` so that every `complete()` call can be
+//! captured to, and later served from, a JSON "cassette" file. Requests are
+//! keyed by a SHA-256 hash of their normalized JSON form, so a replayed run
+//! gets exactly the responses the recorded run did — no network, no API
+//! key, no sampling noise.
+//!
+//! Three modes:
+//! - `record`: call the live provider and write every response to the
+//!   cassette (the file is replaced, and rewritten after each call).
+//! - `replay`: serve from the cassette; a request with no recording is an
+//!   `InferenceError::InvalidRequest`.
+//! - `lenient`: serve from the cassette, falling through to the live
+//!   provider for requests it has no recording of. Live responses are not
+//!   written back.
+//!
+//! Select a cassette with `SYMBI_CASSETTE=<path>` and
+//! `SYMBI_CASSETTE_MODE=record|replay|lenient` (default `replay`).
+
+use crate::reasoning::conversation::Conversation;
+use crate::reasoning::inference::*;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Env var naming the cassette file.
+pub const CASSETTE_ENV: &str = "SYMBI_CASSETTE";
+/// Env var selecting the cassette mode.
+pub const CASSETTE_MODE_ENV: &str = "SYMBI_CASSETTE_MODE";
+
+/// Cassette format version written by this build.
+const CASSETTE_VERSION: u32 = 1;
+
+/// Errors raised while opening or writing a cassette.
+#[derive(Debug, thiserror::Error)]
+pub enum CassetteError {
+    #[error("Cassette configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("Cassette I/O error on {path}: {message}")]
+    Io { path: PathBuf, message: String },
+
+    #[error("Invalid cassette {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+}
+
+/// How a [`CassetteProvider`] uses its cassette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CassetteMode {
+    /// Call the live provider and record every response.
+    Record,
+    /// Serve only from the cassette; unknown requests fail.
+    Replay,
+    /// Serve from the cassette, falling through to the live provider.
+    Lenient,
+}
+
+impl CassetteMode {
+    /// Whether this mode needs a live provider to wrap.
+    pub fn needs_live(self) -> bool {
+        matches!(self, CassetteMode::Record | CassetteMode::Lenient)
+    }
+}
+
+impl std::fmt::Display for CassetteMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CassetteMode::Record => write!(f, "record"),
+            CassetteMode::Replay => write!(f, "replay"),
+            CassetteMode::Lenient => write!(f, "lenient"),
+        }
+    }
+}
+
+impl std::str::FromStr for CassetteMode {
+    type Err = String;
+
+    /// Parse a mode as written on the command line (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "record" => Ok(CassetteMode::Record),
+            "replay" | "strict" => Ok(CassetteMode::Replay),
+            "lenient" => Ok(CassetteMode::Lenient),
+            other => Err(format!(
+                "unknown cassette mode '{other}' (expected record, replay or lenient)"
+            )),
+        }
+    }
+}
+
+/// Where a cassette lives and how to use it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CassetteConfig {
+    pub path: PathBuf,
+    pub mode: CassetteMode,
+}
+
+impl CassetteConfig {
+    pub fn new(path: impl Into<PathBuf>, mode: CassetteMode) -> Self {
+        Self {
+            path: path.into(),
+            mode,
+        }
+    }
+
+    /// Read `SYMBI_CASSETTE` / `SYMBI_CASSETTE_MODE`. `Ok(None)` when no
+    /// cassette is configured.
+    pub fn from_env() -> Result<Option<Self>, CassetteError> {
+        let path = match std::env::var(CASSETTE_ENV) {
+            Ok(p) if !p.trim().is_empty() => PathBuf::from(p),
+            _ => return Ok(None),
+        };
+        let mode = match std::env::var(CASSETTE_MODE_ENV) {
+            Ok(m) if !m.trim().is_empty() => m
+                .trim()
+                .parse()
+                .map_err(|e| CassetteError::ConfigError(format!("{CASSETTE_MODE_ENV}: {e}")))?,
+            _ => CassetteMode::Replay,
+        };
+        Ok(Some(Self { path, mode }))
+    }
+
+    /// Cassette selection for a command line: `--cassette` and
+    /// `--cassette-mode` values override `SYMBI_CASSETTE` /
+    /// `SYMBI_CASSETTE_MODE`. A mode alone re-modes the environment's
+    /// cassette.
+    pub fn resolve(path: Option<&str>, mode: Option<&str>) -> Result<Option<Self>, CassetteError> {
+        let mode = mode
+            .map(|m| {
+                m.parse::<CassetteMode>()
+                    .map_err(|e| CassetteError::ConfigError(format!("--cassette-mode: {e}")))
+            })
+            .transpose()?;
+        let env = Self::from_env()?;
+        Ok(match (path, env) {
+            (Some(path), env) => Some(Self::new(
+                path,
+                mode.or(env.map(|e| e.mode)).unwrap_or(CassetteMode::Replay),
+            )),
+            (None, Some(env)) => Some(Self {
+                mode: mode.unwrap_or(env.mode),
+                ..env
+            }),
+            (None, None) => None,
+        })
+    }
+}
+
+/// One recorded `complete()` call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// [`request_key`] of the request.
+    pub key: String,
+    /// The normalized request, kept for humans diffing cassettes.
+    pub request: serde_json::Value,
+    pub response: InferenceResponse,
+}
+
+/// The on-disk cassette: recorded interactions in call order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Cassette {
+    pub version: u32,
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
+impl Default for Cassette {
+    fn default() -> Self {
+        Self {
+            version: CASSETTE_VERSION,
+            interactions: Vec::new(),
+        }
+    }
+}
+
+impl Cassette {
+    /// Load a cassette from `path`.
+    pub fn load(path: &Path) -> Result<Self, CassetteError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| CassetteError::Io {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        let cassette: Cassette = serde_json::from_str(&raw).map_err(|e| CassetteError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        if cassette.version > CASSETTE_VERSION {
+            return Err(CassetteError::Parse {
+                path: path.to_path_buf(),
+                message: format!(
+                    "cassette version {} is newer than supported version {}",
+                    cassette.version, CASSETTE_VERSION
+                ),
+            });
+        }
+        Ok(cassette)
+    }
+
+    /// Write the cassette to `path` atomically (temp file + rename).
+    pub fn save(&self, path: &Path) -> Result<(), CassetteError> {
+        use std::io::Write;
+
+        let io_err = |e: std::io::Error| CassetteError::Io {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        };
+        let json = serde_json::to_string_pretty(self).map_err(|e| CassetteError::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        let parent = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+        let mut tmp = tempfile::NamedTempFile::new_in(parent).map_err(io_err)?;
+        tmp.write_all(json.as_bytes()).map_err(io_err)?;
+        tmp.flush().map_err(io_err)?;
+        tmp.persist(path).map_err(|e| io_err(e.error))?;
+        Ok(())
+    }
+}
+
+/// Normalized JSON form of a request: keys sorted, and nulls, empty
+/// strings, arrays and objects dropped, so adding an optional field with
+/// an empty default does not invalidate existing cassettes.
+pub fn normalize_request(
+    conversation: &Conversation,
+    options: &InferenceOptions,
+) -> serde_json::Value {
+    let value = serde_json::json!({
+        "messages": conversation.messages(),
+        "options": options,
+    });
+    prune(value).unwrap_or(serde_json::Value::Null)
+}
+
+/// Cassette key of a request: hex SHA-256 of its normalized JSON.
+pub fn request_key(conversation: &Conversation, options: &InferenceOptions) -> String {
+    key_of(&normalize_request(conversation, options))
+}
+
+fn key_of(normalized: &serde_json::Value) -> String {
+    // serde_json's map is ordered by key, so this serialization is canonical.
+    hex::encode(Sha256::digest(normalized.to_string().as_bytes()))
+}
+
+fn prune(value: serde_json::Value) -> Option<serde_json::Value> {
+    use serde_json::Value;
+    match value {
+        Value::Null => None,
+        Value::String(s) if s.is_empty() => None,
+        Value::Array(items) => {
+            // Array elements are positional; keep them even when empty.
+            let items: Vec<Value> = items
+                .into_iter()
+                .map(|v| prune(v).unwrap_or(Value::Null))
+                .collect();
+            (!items.is_empty()).then_some(Value::Array(items))
+        }
+        Value::Object(map) => {
+            let map: serde_json::Map<String, Value> = map
+                .into_iter()
+                .filter_map(|(k, v)| prune(v).map(|v| (k, v)))
+                .collect();
+            (!map.is_empty()).then_some(Value::Object(map))
+        }
+        other => Some(other),
+    }
+}
+
+struct CassetteState {
+    cassette: Cassette,
+    /// Interaction indices per key, in recorded order.
+    index: HashMap<String, Vec<usize>>,
+    /// How many of each key's interactions have been served.
+    served: HashMap<String, usize>,
+}
+
+impl CassetteState {
+    fn new(cassette: Cassette) -> Self {
+        let mut index: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, interaction) in cassette.interactions.iter().enumerate() {
+            index.entry(interaction.key.clone()).or_default().push(i);
+        }
+        Self {
+            cassette,
+            index,
+            served: HashMap::new(),
+        }
+    }
+
+    /// Next recorded response for `key`. A key recorded N times is served
+    /// in recorded order; once exhausted, its last response repeats.
+    fn next(&mut self, key: &str) -> Option<InferenceResponse> {
+        let positions = self.index.get(key)?;
+        let served = self.served.entry(key.to_string()).or_insert(0);
+        let pos = positions[(*served).min(positions.len() - 1)];
+        *served += 1;
+        Some(self.cassette.interactions[pos].response.clone())
+    }
+}
+
+/// An `InferenceProvider` that records to, or replays from, a cassette.
+pub struct CassetteProvider {
+    path: PathBuf,
+    mode: CassetteMode,
+    live: Option<Arc<dyn InferenceProvider>>,
+    state: Mutex<CassetteState>,
+    default_model: String,
+}
+
+impl CassetteProvider {
+    /// Open a cassette. `record` starts an empty cassette at `config.path`;
+    /// `replay` and `lenient` load it. `live` is required by `record` and
+    /// `lenient`, and ignored by `replay`.
+    pub fn open(
+        config: &CassetteConfig,
+        live: Option<Arc<dyn InferenceProvider>>,
+    ) -> Result<Self, CassetteError> {
+        if config.mode.needs_live() && live.is_none() {
+            return Err(CassetteError::ConfigError(format!(
+                "cassette mode '{}' needs a live inference provider",
+                config.mode
+            )));
+        }
+        let cassette = match config.mode {
+            CassetteMode::Record => Cassette::default(),
+            CassetteMode::Replay | CassetteMode::Lenient => Cassette::load(&config.path)?,
+        };
+        let default_model = match &live {
+            Some(p) => p.default_model().to_string(),
+            None => cassette
+                .interactions
+                .first()
+                .map(|i| i.response.model.clone())
+                .unwrap_or_else(|| "cassette".to_string()),
+        };
+        let live = match config.mode {
+            CassetteMode::Replay => None,
+            CassetteMode::Record | CassetteMode::Lenient => live,
+        };
+        tracing::info!(
+            path = %config.path.display(),
+            mode = %config.mode,
+            interactions = cassette.interactions.len(),
+            "inference cassette opened"
+        );
+        Ok(Self {
+            path: config.path.clone(),
+            mode: config.mode,
+            live,
+            state: Mutex::new(CassetteState::new(cassette)),
+            default_model,
+        })
+    }
+
+    /// Wrap `live` as configured by `SYMBI_CASSETTE` /
+    /// `SYMBI_CASSETTE_MODE`. Returns `live` unchanged when no cassette is
+    /// configured.
+    pub fn wrap_from_env(
+        live: Option<Arc<dyn InferenceProvider>>,
+    ) -> Result<Option<Arc<dyn InferenceProvider>>, CassetteError> {
+        match CassetteConfig::from_env()? {
+            Some(config) => Ok(Some(Arc::new(Self::open(&config, live)?))),
+            None => Ok(live),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Number of interactions currently in the cassette.
+    pub async fn len(&self) -> usize {
+        self.state.lock().await.cassette.interactions.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    fn live(&self) -> Result<&Arc<dyn InferenceProvider>, InferenceError> {
+        self.live
+            .as_ref()
+            .ok_or_else(|| InferenceError::InvalidRequest("cassette has no live provider".into()))
+    }
+
+    async fn record(
+        &self,
+        key: String,
+        request: serde_json::Value,
+        response: &InferenceResponse,
+    ) -> Result<(), InferenceError> {
+        let mut state = self.state.lock().await;
+        let pos = state.cassette.interactions.len();
+        state.cassette.interactions.push(Interaction {
+            key: key.clone(),
+            request,
+            response: response.clone(),
+        });
+        state.index.entry(key).or_default().push(pos);
+        let snapshot = state.cassette.clone();
+        let path = self.path.clone();
+        // Rewrite under the lock so concurrent calls land in call order.
+        tokio::task::spawn_blocking(move || snapshot.save(&path))
+            .await
+            .map_err(|e| InferenceError::Provider(format!("cassette write panicked: {e}")))?
+            .map_err(|e| InferenceError::Provider(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl InferenceProvider for CassetteProvider {
+    async fn complete(
+        &self,
+        conversation: &Conversation,
+        options: &InferenceOptions,
+    ) -> Result<InferenceResponse, InferenceError> {
+        let request = normalize_request(conversation, options);
+        let key = key_of(&request);
+
+        if self.mode == CassetteMode::Record {
+            let response = self.live()?.complete(conversation, options).await?;
+            self.record(key, request, &response).await?;
+            return Ok(response);
+        }
+
+        if let Some(response) = self.state.lock().await.next(&key) {
+            tracing::debug!(key = %key, "cassette hit");
+            return Ok(response);
+        }
+        match self.mode {
+            CassetteMode::Lenient => {
+                tracing::info!(key = %key, "cassette miss; calling live provider");
+                self.live()?.complete(conversation, options).await
+            }
+            _ => Err(InferenceError::InvalidRequest(format!(
+                "no recording for request {} in cassette {} (re-record with {}=record)",
+                key,
+                self.path.display(),
+                CASSETTE_MODE_ENV
+            ))),
+        }
+    }
+
+    fn provider_name(&self) -> &str {
+        match &self.live {
+            Some(p) => p.provider_name(),
+            None => "cassette",
+        }
+    }
+
+    fn default_model(&self) -> &str {
+        &self.default_model
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.live.as_ref().is_none_or(|p| p.supports_native_tools())
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        self.live
+            .as_ref()
+            .is_none_or(|p| p.supports_structured_output())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reasoning::conversation::ConversationMessage;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Provider answering "reply N" to its Nth call.
+    struct CountingProvider {
+        calls: AtomicUsize,
+    }
+
+    impl CountingProvider {
+        fn new() -> Arc<Self> {
+            Arc::new(Self {
+                calls: AtomicUsize::new(0),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl InferenceProvider for CountingProvider {
+        async fn complete(
+            &self,
+            _conversation: &Conversation,
+            _options: &InferenceOptions,
+        ) -> Result<InferenceResponse, InferenceError> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(InferenceResponse {
+                content: format!("reply {n}"),
+                tool_calls: vec![],
+                finish_reason: FinishReason::Stop,
+                usage: Usage {
+                    prompt_tokens: 10,
+                    completion_tokens: 2,
+                    total_tokens: 12,
+                    cached_prompt_tokens: 0,
+                },
+                model: "live-model".into(),
+            })
+        }
+
+        fn provider_name(&self) -> &str {
+            "counting"
+        }
+
+        fn default_model(&self) -> &str {
+            "live-model"
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+
+        fn supports_structured_output(&self) -> bool {
+            false
+        }
+    }
+
+    fn conv(prompt: &str) -> Conversation {
+        let mut c = Conversation::with_system("be brief");
+        c.push(ConversationMessage::user(prompt));
+        c
+    }
+
+    #[tokio::test]
+    async fn records_then_replays_without_the_live_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("run.json");
+        let live = CountingProvider::new();
+        let opts = InferenceOptions::default();
+
+        let recorder = CassetteProvider::open(
+            &CassetteConfig::new(&path, CassetteMode::Record),
+            Some(live.clone() as Arc<dyn InferenceProvider>),
+        )
+        .unwrap();
+        assert_eq!(recorder.provider_name(), "counting");
+        let first = recorder.complete(&conv("hi"), &opts).await.unwrap();
+        let second = recorder.complete(&conv("bye"), &opts).await.unwrap();
+        assert_eq!(recorder.len().await, 2);
+        assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 2);
+
+        let player =
+            CassetteProvider::open(&CassetteConfig::new(&path, CassetteMode::Replay), None)
+                .unwrap();
+        assert_eq!(player.provider_name(), "cassette");
+        assert_eq!(player.default_model(), "live-model");
+        let replayed = player.complete(&conv("bye"), &opts).await.unwrap();
+        assert_eq!(replayed.content, second.content);
+        let replayed = player.complete(&conv("hi"), &opts).await.unwrap();
+        assert_eq!(replayed.content, first.content);
+        assert_eq!(replayed.usage.total_tokens, 12);
+        assert_eq!(live.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn strict_replay_rejects_unknown_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.json");
+        Cassette::default().save(&path).unwrap();
+
+        let player =
+            CassetteProvider::open(&CassetteConfig::new(&path, CassetteMode::Replay), None)
+                .unwrap();
+        let err = player
+            .complete(&conv("hi"), &InferenceOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, InferenceError::InvalidRequest(ref m) if m.contains("no recording")));
+    }
+
+    #[tokio::test]
+    async fn lenient_replay_falls_through_to_live() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.json");
+        let live = CountingProvider::new();
+        let opts = InferenceOptions::default();
+        {
+            let recorder = CassetteProvider::open(
+                &CassetteConfig::new(&path, CassetteMode::Record),
+                Some(live.clone() as Arc<dyn InferenceProvider>),
+            )
+            .unwrap();
+            recorder.complete(&conv("hi"), &opts).await.unwrap();
+        }
+
+        let player = CassetteProvider::open(
+            &CassetteConfig::new(&path, CassetteMode::Lenient),
+            Some(live.clone() as Arc<dyn InferenceProvider>),
+        )
+        .unwrap();
+        let hit = player.complete(&conv("hi"), &opts).await.unwrap();
+        assert_eq!(hit.content, "reply 1");
+        let miss = player.complete(&conv("new"), &opts).await.unwrap();
+        assert_eq!(miss.content, "reply 2");
+        assert_eq!(live.calls(), 2);
+        // Lenient mode does not write live responses back.
+        assert_eq!(Cassette::load(&path).unwrap().interactions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn repeated_requests_replay_in_recorded_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.json");
+        let opts = InferenceOptions::default();
+        let recorder = CassetteProvider::open(
+            &CassetteConfig::new(&path, CassetteMode::Record),
+            Some(CountingProvider::new() as Arc<dyn InferenceProvider>),
+        )
+        .unwrap();
+        recorder.complete(&conv("again"), &opts).await.unwrap();
+        recorder.complete(&conv("again"), &opts).await.unwrap();
+
+        let player =
+            CassetteProvider::open(&CassetteConfig::new(&path, CassetteMode::Replay), None)
+                .unwrap();
+        let mut replies = Vec::new();
+        for _ in 0..3 {
+            replies.push(
+                player
+                    .complete(&conv("again"), &opts)
+                    .await
+                    .unwrap()
+                    .content,
+            );
+        }
+        assert_eq!(replies, ["reply 1", "reply 2", "reply 2"]);
+    }
+
+    #[test]
+    fn request_key_is_stable_and_ignores_empty_fields() {
+        let opts = InferenceOptions::default();
+        let key = request_key(&conv("hi"), &opts);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, request_key(&conv("hi"), &opts));
+        assert_ne!(key, request_key(&conv("hello"), &opts));
+
+        let mut with_model = opts.clone();
+        with_model.model = Some("other".into());
+        assert_ne!(key, request_key(&conv("hi"), &with_model));
+
+        // Absent, null and empty fields normalize identically; array slots stay.
+        let sparse = serde_json::json!({"b": 1, "a": [{"x": ""}, 2]});
+        let noisy = serde_json::json!({"a": [{"x": null, "y": []}, 2], "b": 1, "c": {}});
+        assert_eq!(prune(sparse.clone()), prune(noisy));
+        assert_eq!(
+            prune(sparse).unwrap(),
+            serde_json::json!({"a": [null, 2], "b": 1})
+        );
+    }
+
+    #[test]
+    fn modes_parse_and_validate() {
+        assert_eq!("record".parse::<CassetteMode>(), Ok(CassetteMode::Record));
+        assert_eq!("STRICT".parse::<CassetteMode>(), Ok(CassetteMode::Replay));
+        assert_eq!("lenient".parse::<CassetteMode>(), Ok(CassetteMode::Lenient));
+        assert!("live".parse::<CassetteMode>().is_err());
+
+        let err = CassetteProvider::open(
+            &CassetteConfig::new("unused.json", CassetteMode::Record),
+            None,
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(err, CassetteError::ConfigError(_)));
+    }
+
+    #[test]
+    #[serial_test::serial(cassette_env)]
+    fn flags_override_the_environment() {
+        std::env::remove_var(CASSETTE_ENV);
+        std::env::remove_var(CASSETTE_MODE_ENV);
+        assert_eq!(CassetteConfig::resolve(None, Some("record")).unwrap(), None);
+        assert_eq!(
+            CassetteConfig::resolve(Some("a.json"), None).unwrap(),
+            Some(CassetteConfig::new("a.json", CassetteMode::Replay))
+        );
+        assert!(CassetteConfig::resolve(Some("a.json"), Some("live")).is_err());
+
+        std::env::set_var(CASSETTE_ENV, "env.json");
+        std::env::set_var(CASSETTE_MODE_ENV, "lenient");
+        assert_eq!(
+            CassetteConfig::resolve(None, Some("record")).unwrap(),
+            Some(CassetteConfig::new("env.json", CassetteMode::Record))
+        );
+        assert_eq!(
+            CassetteConfig::resolve(Some("a.json"), None).unwrap(),
+            Some(CassetteConfig::new("a.json", CassetteMode::Lenient))
+        );
+        std::env::remove_var(CASSETTE_ENV);
+        std::env::remove_var(CASSETTE_MODE_ENV);
+    }
+}
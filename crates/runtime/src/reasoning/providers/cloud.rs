@@ -2,12 +2,15 @@
 //!
 //! Wraps the existing `LlmClient` to implement `InferenceProvider` with
 //! tool calling and structured output support across OpenAI, Anthropic,
-//! and OpenRouter backends.
+//! OpenRouter and Gemini (Gemini API or Vertex AI) backends.
 
+use super::sse::{event_stream, AnthropicStreamParser, OpenAiStreamParser, StreamParser};
+use crate::http_input::gemini;
 use crate::http_input::llm_client::{LlmClient, LlmProvider};
 use crate::reasoning::conversation::Conversation;
 use crate::reasoning::inference::*;
 use async_trait::async_trait;
+use futures::StreamExt;
 
 /// Map an Anthropic `stop_reason` string to a [`FinishReason`].
 ///
@@ -15,7 +18,7 @@ use async_trait::async_trait;
 /// `tool_use` block. A `"refusal"` (safety-classifier decline; Anthropic
 /// returns it with HTTP 200) maps to [`FinishReason::Refusal`] so the loop can
 /// fail over rather than reading a refused turn as an empty, successful stop.
-fn map_anthropic_stop_reason(stop_reason: &str, has_tool_calls: bool) -> FinishReason {
+pub(super) fn map_anthropic_stop_reason(stop_reason: &str, has_tool_calls: bool) -> FinishReason {
     match stop_reason {
         "tool_use" => FinishReason::ToolCalls,
         "max_tokens" => FinishReason::MaxTokens,
@@ -38,6 +41,15 @@ impl CloudInferenceProvider {
         Self { client }
     }
 
+    /// Whether the client talks Gemini `generateContent` (Gemini API or
+    /// Vertex AI) rather than a chat-completions or Messages API.
+    fn is_gemini(&self) -> bool {
+        matches!(
+            self.client.provider(),
+            LlmProvider::Gemini | LlmProvider::Vertex
+        )
+    }
+
     /// Auto-detect from environment, returning None if no API key is set.
     pub fn from_env() -> Option<Self> {
         LlmClient::from_env().map(|c| Self { client: c })
@@ -240,6 +252,153 @@ impl CloudInferenceProvider {
         body
     }
 
+    /// POST a built request body to the provider and map HTTP-level
+    /// failures (timeouts, 429, non-2xx) onto [`InferenceError`].
+    ///
+    /// `streaming` swaps the whole-request timeout for a per-read idle
+    /// timeout, since a long generation legitimately streams for longer
+    /// than any single non-streaming call would take.
+    async fn send(
+        &self,
+        body: &serde_json::Value,
+        model: &str,
+        streaming: bool,
+    ) -> Result<reqwest::Response, InferenceError> {
+        let is_anthropic = matches!(self.client.provider(), LlmProvider::Anthropic);
+
+        // Build and send the HTTP request using reqwest
+        let builder = reqwest::Client::builder();
+        let builder = if streaming {
+            builder
+                .connect_timeout(std::time::Duration::from_secs(30))
+                .read_timeout(std::time::Duration::from_secs(120))
+        } else {
+            builder.timeout(std::time::Duration::from_secs(120))
+        };
+        let http_client = builder
+            .build()
+            .map_err(|e| InferenceError::Provider(format!("HTTP client error: {}", e)))?;
+
+        // The base URL and API key were resolved once at LlmClient
+        // construction (env or secret store) — reuse the cached values
+        // instead of re-reading the env on every request.
+        let base = self.client.base_url();
+        let api_key = self.client.api_key();
+
+        let (url, request_builder) = if is_anthropic {
+            let url = format!("{}/messages", base);
+            let rb = http_client
+                .post(&url)
+                .header("x-api-key", api_key)
+                .header("anthropic-version", "2023-06-01")
+                .header("content-type", "application/json")
+                .json(body);
+            (url, rb)
+        } else if self.is_gemini() {
+            let url = gemini::generate_content_url(base, model);
+            let (auth_name, auth_value) = gemini::auth_header(
+                matches!(self.client.provider(), LlmProvider::Vertex),
+                api_key,
+            );
+            let rb = http_client
+                .post(&url)
+                .header(auth_name, auth_value)
+                .header("content-type", "application/json")
+                .json(body);
+            (url, rb)
+        } else {
+            let url = format!("{}/chat/completions", base);
+            let mut rb = http_client
+                .post(&url)
+                .header("authorization", format!("Bearer {}", api_key))
+                .header("content-type", "application/json");
+            if matches!(self.client.provider(), LlmProvider::OpenRouter) {
+                for (k, v) in crate::http_input::llm_client::openrouter_attribution_headers() {
+                    rb = rb.header(k, v);
+                }
+            }
+            let rb = rb.json(body);
+            (url, rb)
+        };
+
+        tracing::debug!(
+            "Cloud inference: provider={} model={} url={} streaming={}",
+            self.provider_name(),
+            model,
+            url,
+            streaming
+        );
+        // Debug-level fingerprint of the request body. Useful for
+        // diagnosing why an agent terminates early (missing tool_choice,
+        // empty messages array, etc.). Enable with RUST_LOG=symbi_runtime=debug.
+        tracing::debug!(
+            "Cloud request fingerprint: tool_choice={} tools={} system_chars={} msg_count={}",
+            body.get("tool_choice")
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "<absent>".into()),
+            body.get("tools")
+                .and_then(|v| v.as_array())
+                .map(|a| a.len())
+                .unwrap_or(0),
+            body.get("system")
+                .and_then(|v| v.as_str())
+                .map(|s| s.len())
+                .unwrap_or(0),
+            body.get("messages")
+                .and_then(|v| v.as_array())
+                .map(|a| a.len())
+                .unwrap_or(0),
+        );
+
+        let response = request_builder.send().await.map_err(|e| {
+            if e.is_timeout() {
+                InferenceError::Timeout(std::time::Duration::from_secs(120))
+            } else {
+                InferenceError::Provider(format!("Request failed: {}", e))
+            }
+        })?;
+
+        let status = response.status();
+        if status.as_u16() == 429 {
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(1000);
+            return Err(InferenceError::RateLimited {
+                retry_after_ms: retry_after * 1000,
+            });
+        }
+
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".into());
+            tracing::warn!(
+                "Cloud API non-success: status={} body={}",
+                status,
+                error_text.chars().take(400).collect::<String>()
+            );
+            // Both Anthropic and OpenAI-compatible APIs answer an unknown or
+            // retired model with 404; surface it distinctly so a failover
+            // chain can move on to the next provider.
+            if status.as_u16() == 404 {
+                return Err(InferenceError::ModelUnavailable(format!(
+                    "{}: {}",
+                    model, error_text
+                )));
+            }
+            return Err(InferenceError::Provider(format!(
+                "API error ({}): {}",
+                status, error_text
+            )));
+        }
+
+        Ok(response)
+    }
+
     /// Parse an OpenAI-format response into InferenceResponse.
     fn parse_openai_response(
         &self,
@@ -307,6 +466,10 @@ impl CloudInferenceProvider {
                     .and_then(|v| v.as_u64())
                     .unwrap_or(0) as u32,
                 total_tokens: u.get("total_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+                cached_prompt_tokens: u
+                    .pointer("/prompt_tokens_details/cached_tokens")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0) as u32,
             })
             .unwrap_or_default();
 
@@ -392,12 +555,18 @@ impl CloudInferenceProvider {
         let usage = resp
             .get("usage")
             .map(|u| {
-                let input = u.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
-                let output = u.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+                let field = |key: &str| u.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+                // Anthropic reports cache reads and writes apart from
+                // `input_tokens`; fold them back in so `prompt_tokens` is
+                // the full prompt, as it is for OpenAI.
+                let cached = field("cache_read_input_tokens");
+                let input = field("input_tokens") + field("cache_creation_input_tokens") + cached;
+                let output = field("output_tokens");
                 Usage {
                     prompt_tokens: input,
                     completion_tokens: output,
                     total_tokens: input + output,
+                    cached_prompt_tokens: cached,
                 }
             })
             .unwrap_or_default();
@@ -467,113 +636,28 @@ impl InferenceProvider for CloudInferenceProvider {
             return self.parse_anthropic_response(&resp_json, model);
         }
 
+        // Gemini speaks `generateContent`: build from the Anthropic-shaped
+        // message list, which already pairs tool results with their calls.
+        if self.is_gemini() {
+            let (system, messages) = conversation.to_anthropic_messages();
+            let body =
+                gemini::build_generate_content_request(system.as_deref(), &messages, options);
+            let response = self.send(&body, model, false).await?;
+            let resp_json: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| InferenceError::ParseError(format!("JSON parse error: {}", e)))?;
+            return gemini::parse_generate_content_response(&resp_json, model);
+        }
+
         let body = if is_anthropic {
             self.build_anthropic_body(conversation, options)
         } else {
             self.build_openai_body(conversation, options)
         };
 
-        // Build and send the HTTP request using reqwest
-        let http_client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(120))
-            .build()
-            .map_err(|e| InferenceError::Provider(format!("HTTP client error: {}", e)))?;
-
-        // The base URL and API key were resolved once at LlmClient
-        // construction (env or secret store) — reuse the cached values
-        // instead of re-reading the env on every request.
-        let base = self.client.base_url();
-        let api_key = self.client.api_key();
-
-        let (url, request_builder) = if is_anthropic {
-            let url = format!("{}/messages", base);
-            let rb = http_client
-                .post(&url)
-                .header("x-api-key", api_key)
-                .header("anthropic-version", "2023-06-01")
-                .header("content-type", "application/json")
-                .json(&body);
-            (url, rb)
-        } else {
-            let url = format!("{}/chat/completions", base);
-            let mut rb = http_client
-                .post(&url)
-                .header("authorization", format!("Bearer {}", api_key))
-                .header("content-type", "application/json");
-            if matches!(self.client.provider(), LlmProvider::OpenRouter) {
-                for (k, v) in crate::http_input::llm_client::openrouter_attribution_headers() {
-                    rb = rb.header(k, v);
-                }
-            }
-            let rb = rb.json(&body);
-            (url, rb)
-        };
-
-        tracing::debug!(
-            "Cloud inference: provider={} model={} url={}",
-            self.provider_name(),
-            model,
-            url
-        );
-        // Debug-level fingerprint of the request body. Useful for
-        // diagnosing why an agent terminates early (missing tool_choice,
-        // empty messages array, etc.). Enable with RUST_LOG=symbi_runtime=debug.
-        tracing::debug!(
-            "Cloud request fingerprint: tool_choice={} tools={} system_chars={} msg_count={}",
-            body.get("tool_choice")
-                .map(|v| v.to_string())
-                .unwrap_or_else(|| "<absent>".into()),
-            body.get("tools")
-                .and_then(|v| v.as_array())
-                .map(|a| a.len())
-                .unwrap_or(0),
-            body.get("system")
-                .and_then(|v| v.as_str())
-                .map(|s| s.len())
-                .unwrap_or(0),
-            body.get("messages")
-                .and_then(|v| v.as_array())
-                .map(|a| a.len())
-                .unwrap_or(0),
-        );
-
         let start = std::time::Instant::now();
-        let response = request_builder.send().await.map_err(|e| {
-            if e.is_timeout() {
-                InferenceError::Timeout(std::time::Duration::from_secs(120))
-            } else {
-                InferenceError::Provider(format!("Request failed: {}", e))
-            }
-        })?;
-
-        let status = response.status();
-        if status.as_u16() == 429 {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse::<u64>().ok())
-                .unwrap_or(1000);
-            return Err(InferenceError::RateLimited {
-                retry_after_ms: retry_after * 1000,
-            });
-        }
-
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".into());
-            tracing::warn!(
-                "Cloud API non-success: status={} body={}",
-                status,
-                error_text.chars().take(400).collect::<String>()
-            );
-            return Err(InferenceError::Provider(format!(
-                "API error ({}): {}",
-                status, error_text
-            )));
-        }
+        let response = self.send(&body, model, false).await?;
 
         let resp_json: serde_json::Value = response
             .json()
@@ -612,11 +696,69 @@ impl InferenceProvider for CloudInferenceProvider {
         }
     }
 
+    async fn complete_stream(
+        &self,
+        conversation: &Conversation,
+        options: &InferenceOptions,
+    ) -> Result<InferenceStream, InferenceError> {
+        // Bedrock and Gemini go through request/response paths; replay the
+        // finished turn so callers still get a well-formed stream.
+        #[cfg(feature = "bedrock")]
+        if matches!(self.client.provider(), LlmProvider::Bedrock) {
+            let response = self.complete(conversation, options).await?;
+            return Ok(Box::pin(futures::stream::iter(
+                response.into_stream_events().into_iter().map(Ok),
+            )));
+        }
+        if self.is_gemini() {
+            let response = self.complete(conversation, options).await?;
+            return Ok(Box::pin(futures::stream::iter(
+                response.into_stream_events().into_iter().map(Ok),
+            )));
+        }
+
+        let is_anthropic = matches!(self.client.provider(), LlmProvider::Anthropic);
+        let model = options
+            .model
+            .as_deref()
+            .unwrap_or_else(|| self.client.model());
+
+        let mut body = if is_anthropic {
+            self.build_anthropic_body(conversation, options)
+        } else {
+            self.build_openai_body(conversation, options)
+        };
+        body["stream"] = serde_json::json!(true);
+        if !is_anthropic {
+            // Without this OpenAI-compatible streams omit token usage.
+            body["stream_options"] = serde_json::json!({"include_usage": true});
+        }
+
+        let response = self.send(&body, model, true).await?;
+        let parser = if is_anthropic {
+            StreamParser::Anthropic(AnthropicStreamParser::new(model))
+        } else {
+            StreamParser::OpenAi(OpenAiStreamParser::new(model))
+        };
+        let bytes = response.bytes_stream().map(|chunk| {
+            chunk.map_err(|e| {
+                if e.is_timeout() {
+                    InferenceError::Timeout(std::time::Duration::from_secs(120))
+                } else {
+                    InferenceError::Provider(format!("Stream read failed: {}", e))
+                }
+            })
+        });
+        Ok(event_stream(bytes, parser))
+    }
+
     fn provider_name(&self) -> &str {
         match self.client.provider() {
             LlmProvider::OpenRouter => "openrouter",
             LlmProvider::OpenAI => "openai",
             LlmProvider::Anthropic => "anthropic",
+            LlmProvider::Gemini => "gemini",
+            LlmProvider::Vertex => "vertex",
             #[cfg(feature = "bedrock")]
             LlmProvider::Bedrock => "bedrock",
         }
@@ -631,9 +773,19 @@ impl InferenceProvider for CloudInferenceProvider {
     }
 
     fn supports_structured_output(&self) -> bool {
-        // OpenAI and Anthropic both support structured output
+        // OpenAI, Anthropic and Gemini (`responseSchema`) all support
+        // structured output
         true
     }
+
+    fn supports_streaming(&self) -> bool {
+        match self.client.provider() {
+            #[cfg(feature = "bedrock")]
+            LlmProvider::Bedrock => false,
+            LlmProvider::Gemini | LlmProvider::Vertex => false,
+            _ => true,
+        }
+    }
 }
 
 #[cfg(test)]
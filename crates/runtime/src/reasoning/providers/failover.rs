@@ -0,0 +1,816 @@
+//! Failover inference provider
+//!
+//! Wraps an ordered chain of `InferenceProvider`s. Transient failures (rate
+//! limits, timeouts, 5xx / overload responses) are retried on the same
+//! provider with jittered exponential backoff; an unavailable model or a
+//! refused turn moves on to the next provider in the chain, with the model
+//! name translated for that provider.
+//!
+//! Every attempt is counted in the global reasoning metrics. When the call
+//! runs under [`collect_attempts`] (the reasoning loop does this around each
+//! inference step) the attempts are also handed back to the caller, which
+//! journals them as `LoopEvent::InferenceAttempt`.
+
+use crate::reasoning::conversation::Conversation;
+use crate::reasoning::inference::*;
+use async_trait::async_trait;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Errors raised while assembling a failover chain from configuration.
+#[derive(Debug, thiserror::Error)]
+pub enum FailoverError {
+    #[error("Failover configuration error: {0}")]
+    ConfigError(String),
+}
+
+/// `[failover]` section of `symbiont.toml`.
+///
+/// ```toml
+/// [failover]
+/// max_retries = 2
+/// base_delay_ms = 500
+/// max_delay_ms = 30000
+///
+/// [[failover.providers]]
+/// name = "anthropic"
+///
+/// [[failover.providers]]
+/// name = "openai"
+/// model = "gpt-4o"
+/// models = { "claude-sonnet-4-20250514" = "gpt-4o" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailoverConfig {
+    /// Providers in the order they are tried. The first is the primary.
+    #[serde(default)]
+    pub providers: Vec<FailoverProviderConfig>,
+    /// Retries on the same provider after a transient error, before failing
+    /// over to the next one.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Backoff before the first retry; doubles on each further retry.
+    #[serde(default = "default_base_delay_ms")]
+    pub base_delay_ms: u64,
+    /// Upper bound on a single backoff. A provider asking for a longer
+    /// `retry-after` is treated as exhausted.
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+}
+
+fn default_max_retries() -> u32 {
+    2
+}
+
+fn default_base_delay_ms() -> u64 {
+    500
+}
+
+fn default_max_delay_ms() -> u64 {
+    30_000
+}
+
+/// One provider in a `[failover]` chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailoverProviderConfig {
+    /// Provider name: `anthropic`, `openai`, `openrouter`, `gemini`, `vertex`
+    /// or `bedrock`.
+    /// Credentials and base URL come from that provider's usual env vars.
+    pub name: String,
+    /// Model to use on this provider when the request names none, or names
+    /// one missing from `models`.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Requested model name → this provider's equivalent.
+    #[serde(default)]
+    pub models: HashMap<String, String>,
+}
+
+/// Retry and backoff settings for a [`FailoverInferenceProvider`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries on the same provider after a transient error.
+    pub max_retries: u32,
+    /// Backoff before the first retry.
+    pub base_delay: Duration,
+    /// Upper bound on a single backoff.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: default_max_retries(),
+            base_delay: Duration::from_millis(default_base_delay_ms()),
+            max_delay: Duration::from_millis(default_max_delay_ms()),
+        }
+    }
+}
+
+impl From<&FailoverConfig> for RetryPolicy {
+    fn from(config: &FailoverConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base_delay: Duration::from_millis(config.base_delay_ms),
+            max_delay: Duration::from_millis(config.max_delay_ms),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based): exponential backoff
+    /// capped at `max_delay`, jittered into its upper half, and never
+    /// shorter than the `retry_after` the provider asked for. Returns
+    /// `None` when the provider asks to wait longer than `max_delay`.
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if retry_after.is_some_and(|after| after > self.max_delay) {
+            return None;
+        }
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let millis = backoff.as_millis() as u64;
+        let jittered =
+            Duration::from_millis(rand::thread_rng().gen_range(millis / 2..=millis.max(1)));
+        Some(jittered.max(retry_after.unwrap_or_default()))
+    }
+}
+
+/// One call made by a [`FailoverInferenceProvider`], and what it did next.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InferenceAttempt {
+    /// `provider_name()` of the provider called.
+    pub provider: String,
+    /// Model requested from that provider.
+    pub model: String,
+    /// 1-based attempt number on this provider.
+    pub attempt: u32,
+    /// Result of the call.
+    pub outcome: AttemptOutcome,
+    /// Backoff slept before the next attempt (0 unless retrying).
+    pub delay_ms: u64,
+}
+
+/// Result of one [`InferenceAttempt`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AttemptOutcome {
+    /// The provider answered; the response was returned.
+    Success,
+    /// The last provider in the chain refused; the refusal was returned.
+    Refused,
+    /// Transient error; the same provider is retried after `delay_ms`.
+    Retry { error: String },
+    /// The chain moved on to the next provider.
+    Failover { reason: String },
+    /// Non-recoverable error, or the chain was exhausted.
+    Failed { error: String },
+}
+
+tokio::task_local! {
+    static ATTEMPTS: Mutex<Vec<InferenceAttempt>>;
+}
+
+/// Run `future`, returning its output together with every
+/// [`InferenceAttempt`] a failover provider recorded while it ran.
+pub async fn collect_attempts<F: Future>(future: F) -> (F::Output, Vec<InferenceAttempt>) {
+    ATTEMPTS
+        .scope(Mutex::new(Vec::new()), async {
+            let output = future.await;
+            let attempts = ATTEMPTS.with(|a| std::mem::take(&mut *a.lock().unwrap()));
+            (output, attempts)
+        })
+        .await
+}
+
+fn record_attempt(attempt: InferenceAttempt) {
+    tracing::debug!(
+        provider = %attempt.provider,
+        model = %attempt.model,
+        attempt = attempt.attempt,
+        outcome = ?attempt.outcome,
+        delay_ms = attempt.delay_ms,
+        "inference attempt"
+    );
+    let _ = ATTEMPTS.try_with(|a| a.lock().unwrap().push(attempt));
+}
+
+/// How the chain reacts to an inference error.
+enum ErrorAction {
+    /// Retry on the same provider, waiting at least the given time.
+    Retry(Option<Duration>),
+    /// Skip straight to the next provider.
+    Failover,
+    /// Return the error to the caller.
+    Fail,
+}
+
+fn classify(error: &InferenceError) -> ErrorAction {
+    match error {
+        InferenceError::RateLimited { retry_after_ms } => {
+            ErrorAction::Retry(Some(Duration::from_millis(*retry_after_ms)))
+        }
+        InferenceError::Timeout(_) => ErrorAction::Retry(None),
+        InferenceError::Provider(message) if is_transient(message) => ErrorAction::Retry(None),
+        InferenceError::ModelUnavailable(_) => ErrorAction::Failover,
+        _ => ErrorAction::Fail,
+    }
+}
+
+/// Whether a `Provider` error message describes a server-side or network
+/// failure worth retrying (5xx, overload, throttling, connection errors).
+fn is_transient(message: &str) -> bool {
+    if let Some(status) = message
+        .strip_prefix("API error (")
+        .and_then(|rest| rest.get(..3))
+        .and_then(|code| code.parse::<u16>().ok())
+    {
+        return status >= 500 || status == 408;
+    }
+    let lower = message.to_ascii_lowercase();
+    lower.starts_with("request failed")
+        || lower.contains("overloaded")
+        || lower.contains("throttl")
+        || lower.contains("service unavailable")
+}
+
+/// One provider in a failover chain, with its model-name translation.
+pub struct FailoverEntry {
+    provider: Arc<dyn InferenceProvider>,
+    model: Option<String>,
+    models: HashMap<String, String>,
+}
+
+impl FailoverEntry {
+    /// Use `provider` with no model translation.
+    pub fn new(provider: Arc<dyn InferenceProvider>) -> Self {
+        Self {
+            provider,
+            model: None,
+            models: HashMap::new(),
+        }
+    }
+
+    /// Model to use when the request names none, or one with no mapping.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Translate requests for `from` into `to` on this provider.
+    pub fn map_model(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.models.insert(from.into(), to.into());
+        self
+    }
+
+    /// Model to request: a mapped name, else the pinned model, else the
+    /// requested name unchanged (`None` defers to the provider default).
+    fn resolve_model(&self, requested: Option<&str>) -> Option<String> {
+        requested
+            .and_then(|m| self.models.get(m))
+            .or(self.model.as_ref())
+            .cloned()
+            .or_else(|| requested.map(str::to_string))
+    }
+}
+
+/// What a successful attempt produced.
+enum Served {
+    Response(InferenceResponse),
+    Stream(InferenceStream),
+}
+
+/// Inference provider that retries and fails over across a chain of
+/// providers. See the module docs for the policy.
+pub struct FailoverInferenceProvider {
+    entries: Vec<FailoverEntry>,
+    policy: RetryPolicy,
+}
+
+impl FailoverInferenceProvider {
+    /// Start a chain with `primary` and the default [`RetryPolicy`].
+    pub fn new(primary: FailoverEntry) -> Self {
+        Self {
+            entries: vec![primary],
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Append a fallback provider to the chain.
+    pub fn then(mut self, entry: FailoverEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Replace the retry policy.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Build a chain of cloud providers from a `[failover]` section.
+    ///
+    /// Each provider is configured from its usual environment variables;
+    /// one without credentials is skipped with a warning. Fails on an
+    /// unknown provider name or when no provider could be configured.
+    #[cfg(feature = "cloud-llm")]
+    pub fn from_config(config: &FailoverConfig) -> Result<Self, FailoverError> {
+        use crate::http_input::llm_client::{LlmClient, LlmProvider};
+        use crate::reasoning::providers::cloud::CloudInferenceProvider;
+
+        let mut entries = Vec::new();
+        for spec in &config.providers {
+            let kind: LlmProvider = spec.name.parse().map_err(FailoverError::ConfigError)?;
+            let Some(client) = LlmClient::from_env_for(kind) else {
+                tracing::warn!(
+                    "Failover provider '{}' has no credentials configured — skipping",
+                    spec.name
+                );
+                continue;
+            };
+            let mut entry = FailoverEntry::new(Arc::new(CloudInferenceProvider::new(client)));
+            entry.model = spec.model.clone();
+            entry.models = spec.models.clone();
+            entries.push(entry);
+        }
+        if entries.is_empty() {
+            return Err(FailoverError::ConfigError(
+                "no failover provider has credentials configured".into(),
+            ));
+        }
+        Ok(Self {
+            entries,
+            policy: RetryPolicy::from(config),
+        })
+    }
+
+    async fn run(
+        &self,
+        conversation: &Conversation,
+        options: &InferenceOptions,
+        stream: bool,
+    ) -> Result<Served, InferenceError> {
+        let metrics = crate::metrics::prometheus::global().reasoning();
+        let mut last_error = None;
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            let has_next = index + 1 < self.entries.len();
+            let mut options = options.clone();
+            options.model = entry.resolve_model(options.model.as_deref());
+            let provider = entry.provider.provider_name().to_string();
+            let model = options
+                .model
+                .clone()
+                .unwrap_or_else(|| entry.provider.default_model().to_string());
+            let record = |attempt: u32, outcome: AttemptOutcome, delay: Duration| {
+                record_attempt(InferenceAttempt {
+                    provider: provider.clone(),
+                    model: model.clone(),
+                    attempt,
+                    outcome,
+                    delay_ms: delay.as_millis() as u64,
+                })
+            };
+
+            let mut retries = 0;
+            loop {
+                let attempt = retries + 1;
+                metrics.record_inference_attempt();
+                let result = if stream {
+                    entry
+                        .provider
+                        .complete_stream(conversation, &options)
+                        .await
+                        .map(Served::Stream)
+                } else {
+                    entry
+                        .provider
+                        .complete(conversation, &options)
+                        .await
+                        .map(Served::Response)
+                };
+
+                let error = match result {
+                    Ok(Served::Response(response))
+                        if response.finish_reason == FinishReason::Refusal =>
+                    {
+                        if !has_next {
+                            record(attempt, AttemptOutcome::Refused, Duration::ZERO);
+                            return Ok(Served::Response(response));
+                        }
+                        record(
+                            attempt,
+                            AttemptOutcome::Failover {
+                                reason: "model refused the request".into(),
+                            },
+                            Duration::ZERO,
+                        );
+                        metrics.record_provider_failover();
+                        break;
+                    }
+                    Ok(served) => {
+                        record(attempt, AttemptOutcome::Success, Duration::ZERO);
+                        return Ok(served);
+                    }
+                    Err(error) => error,
+                };
+
+                let action = match classify(&error) {
+                    ErrorAction::Retry(retry_after) if retries < self.policy.max_retries => {
+                        match self.policy.delay(retries, retry_after) {
+                            Some(delay) => {
+                                record(
+                                    attempt,
+                                    AttemptOutcome::Retry {
+                                        error: error.to_string(),
+                                    },
+                                    delay,
+                                );
+                                metrics.record_inference_retry();
+                                tokio::time::sleep(delay).await;
+                                retries += 1;
+                                continue;
+                            }
+                            None => ErrorAction::Failover,
+                        }
+                    }
+                    ErrorAction::Retry(_) => ErrorAction::Failover,
+                    other => other,
+                };
+
+                if matches!(action, ErrorAction::Failover) && has_next {
+                    record(
+                        attempt,
+                        AttemptOutcome::Failover {
+                            reason: error.to_string(),
+                        },
+                        Duration::ZERO,
+                    );
+                    metrics.record_provider_failover();
+                    last_error = Some(error);
+                    break;
+                }
+                record(
+                    attempt,
+                    AttemptOutcome::Failed {
+                        error: error.to_string(),
+                    },
+                    Duration::ZERO,
+                );
+                return Err(error);
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| InferenceError::Provider("failover chain exhausted".into())))
+    }
+}
+
+#[async_trait]
+impl InferenceProvider for FailoverInferenceProvider {
+    async fn complete(
+        &self,
+        conversation: &Conversation,
+        options: &InferenceOptions,
+    ) -> Result<InferenceResponse, InferenceError> {
+        match self.run(conversation, options, false).await? {
+            Served::Response(response) => Ok(response),
+            Served::Stream(_) => unreachable!("complete() never opens a stream"),
+        }
+    }
+
+    /// Retries and failover apply until a stream is open. An error raised
+    /// mid-stream is passed through, and a streamed refusal is not detected.
+    async fn complete_stream(
+        &self,
+        conversation: &Conversation,
+        options: &InferenceOptions,
+    ) -> Result<InferenceStream, InferenceError> {
+        match self.run(conversation, options, true).await? {
+            Served::Stream(stream) => Ok(stream),
+            Served::Response(_) => unreachable!("complete_stream() always opens a stream"),
+        }
+    }
+
+    fn provider_name(&self) -> &str {
+        "failover"
+    }
+
+    fn default_model(&self) -> &str {
+        let primary = &self.entries[0];
+        primary
+            .model
+            .as_deref()
+            .unwrap_or_else(|| primary.provider.default_model())
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|e| e.provider.supports_native_tools())
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        self.entries
+            .iter()
+            .all(|e| e.provider.supports_structured_output())
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.entries[0].provider.supports_streaming()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Provider that replays a fixed script of results and records the
+    /// model each call asked for.
+    struct ScriptedProvider {
+        name: &'static str,
+        script: Mutex<VecDeque<Result<InferenceResponse, InferenceError>>>,
+        models: Mutex<Vec<Option<String>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(
+            name: &'static str,
+            script: Vec<Result<InferenceResponse, InferenceError>>,
+        ) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                script: Mutex::new(script.into()),
+                models: Mutex::new(Vec::new()),
+            })
+        }
+
+        fn calls(&self) -> Vec<Option<String>> {
+            self.models.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl InferenceProvider for ScriptedProvider {
+        async fn complete(
+            &self,
+            _conversation: &Conversation,
+            options: &InferenceOptions,
+        ) -> Result<InferenceResponse, InferenceError> {
+            self.models.lock().unwrap().push(options.model.clone());
+            self.script
+                .lock()
+                .unwrap()
+                .pop_front()
+                .expect("script exhausted")
+        }
+
+        fn provider_name(&self) -> &str {
+            self.name
+        }
+
+        fn default_model(&self) -> &str {
+            "default"
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+
+        fn supports_structured_output(&self) -> bool {
+            true
+        }
+    }
+
+    fn reply(text: &str, finish_reason: FinishReason) -> Result<InferenceResponse, InferenceError> {
+        Ok(InferenceResponse {
+            content: text.into(),
+            tool_calls: vec![],
+            finish_reason,
+            usage: Usage::default(),
+            model: "scripted".into(),
+        })
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 2,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(50),
+        }
+    }
+
+    async fn complete(
+        chain: &FailoverInferenceProvider,
+    ) -> (
+        Result<InferenceResponse, InferenceError>,
+        Vec<InferenceAttempt>,
+    ) {
+        collect_attempts(chain.complete(&Conversation::new(), &InferenceOptions::default())).await
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limit_on_same_provider() {
+        let primary = ScriptedProvider::new(
+            "primary",
+            vec![
+                Err(InferenceError::RateLimited { retry_after_ms: 20 }),
+                Err(InferenceError::Provider(
+                    "API error (529): overloaded".into(),
+                )),
+                reply("ok", FinishReason::Stop),
+            ],
+        );
+        let chain = FailoverInferenceProvider::new(FailoverEntry::new(primary.clone()))
+            .with_retry_policy(fast_policy());
+
+        let started = std::time::Instant::now();
+        let (result, attempts) = complete(&chain).await;
+        assert_eq!(result.unwrap().content, "ok");
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert_eq!(primary.calls().len(), 3);
+
+        assert_eq!(attempts.len(), 3);
+        assert!(matches!(attempts[0].outcome, AttemptOutcome::Retry { .. }));
+        assert!(attempts[0].delay_ms >= 20, "retry-after is honoured");
+        assert!(matches!(attempts[1].outcome, AttemptOutcome::Retry { .. }));
+        assert_eq!(attempts[2].outcome, AttemptOutcome::Success);
+        assert_eq!(attempts[2].attempt, 3);
+    }
+
+    #[tokio::test]
+    async fn model_unavailable_fails_over_with_translated_model() {
+        let primary = ScriptedProvider::new(
+            "anthropic",
+            vec![Err(InferenceError::ModelUnavailable("retired".into()))],
+        );
+        let fallback = ScriptedProvider::new("openai", vec![reply("ok", FinishReason::Stop)]);
+        let chain = FailoverInferenceProvider::new(FailoverEntry::new(primary.clone()))
+            .then(FailoverEntry::new(fallback.clone()).map_model("claude-sonnet", "gpt-4o"))
+            .with_retry_policy(fast_policy());
+
+        let options = InferenceOptions {
+            model: Some("claude-sonnet".into()),
+            ..Default::default()
+        };
+        let (result, attempts) =
+            collect_attempts(chain.complete(&Conversation::new(), &options)).await;
+        assert_eq!(result.unwrap().content, "ok");
+        assert_eq!(primary.calls(), vec![Some("claude-sonnet".into())]);
+        assert_eq!(fallback.calls(), vec![Some("gpt-4o".into())]);
+
+        assert_eq!(attempts.len(), 2);
+        assert_eq!(attempts[0].provider, "anthropic");
+        assert!(matches!(
+            attempts[0].outcome,
+            AttemptOutcome::Failover { .. }
+        ));
+        assert_eq!(attempts[1].provider, "openai");
+        assert_eq!(attempts[1].model, "gpt-4o");
+    }
+
+    #[tokio::test]
+    async fn refusal_fails_over_until_last_provider() {
+        let primary = ScriptedProvider::new("primary", vec![reply("no", FinishReason::Refusal)]);
+        let fallback =
+            ScriptedProvider::new("fallback", vec![reply("still no", FinishReason::Refusal)]);
+        let chain = FailoverInferenceProvider::new(FailoverEntry::new(primary))
+            .then(FailoverEntry::new(fallback).with_model("pinned"))
+            .with_retry_policy(fast_policy());
+
+        let (result, attempts) = complete(&chain).await;
+        let response = result.unwrap();
+        assert_eq!(response.finish_reason, FinishReason::Refusal);
+        assert_eq!(response.content, "still no");
+        assert!(matches!(
+            attempts[0].outcome,
+            AttemptOutcome::Failover { .. }
+        ));
+        assert_eq!(attempts[1].outcome, AttemptOutcome::Refused);
+        assert_eq!(attempts[1].model, "pinned");
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_fail_over_and_last_error_is_returned() {
+        let primary = ScriptedProvider::new(
+            "primary",
+            vec![
+                Err(InferenceError::Timeout(Duration::from_secs(1))),
+                Err(InferenceError::Timeout(Duration::from_secs(1))),
+                Err(InferenceError::Timeout(Duration::from_secs(1))),
+            ],
+        );
+        let fallback = ScriptedProvider::new(
+            "fallback",
+            vec![Err(InferenceError::Provider(
+                "API error (503 Service Unavailable): down".into(),
+            ))],
+        );
+        let chain = FailoverInferenceProvider::new(FailoverEntry::new(primary.clone()))
+            .then(FailoverEntry::new(fallback.clone()))
+            .with_retry_policy(RetryPolicy {
+                max_retries: 0,
+                ..fast_policy()
+            });
+
+        let (result, attempts) = complete(&chain).await;
+        assert!(matches!(result, Err(InferenceError::Provider(_))));
+        assert_eq!(primary.calls().len(), 1);
+        assert_eq!(fallback.calls().len(), 1);
+        assert!(matches!(
+            attempts[0].outcome,
+            AttemptOutcome::Failover { .. }
+        ));
+        assert!(matches!(attempts[1].outcome, AttemptOutcome::Failed { .. }));
+    }
+
+    #[tokio::test]
+    async fn retry_after_beyond_cap_fails_over_without_waiting() {
+        let primary = ScriptedProvider::new(
+            "primary",
+            vec![Err(InferenceError::RateLimited {
+                retry_after_ms: 60_000,
+            })],
+        );
+        let fallback = ScriptedProvider::new("fallback", vec![reply("ok", FinishReason::Stop)]);
+        let chain = FailoverInferenceProvider::new(FailoverEntry::new(primary))
+            .then(FailoverEntry::new(fallback))
+            .with_retry_policy(fast_policy());
+
+        let (result, attempts) = complete(&chain).await;
+        assert_eq!(result.unwrap().content, "ok");
+        assert_eq!(attempts[0].delay_ms, 0);
+        assert!(matches!(
+            attempts[0].outcome,
+            AttemptOutcome::Failover { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn non_recoverable_error_is_returned_immediately() {
+        let primary = ScriptedProvider::new(
+            "primary",
+            vec![Err(InferenceError::InvalidRequest("bad schema".into()))],
+        );
+        let fallback = ScriptedProvider::new("fallback", vec![]);
+        let chain = FailoverInferenceProvider::new(FailoverEntry::new(primary))
+            .then(FailoverEntry::new(fallback.clone()));
+
+        let (result, attempts) = complete(&chain).await;
+        assert!(matches!(result, Err(InferenceError::InvalidRequest(_))));
+        assert!(fallback.calls().is_empty());
+        assert_eq!(attempts.len(), 1);
+        assert!(matches!(attempts[0].outcome, AttemptOutcome::Failed { .. }));
+    }
+
+    #[test]
+    fn backoff_grows_and_stays_within_bounds() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1_000),
+        };
+        for retry in 0..6 {
+            let cap = (100u64 << retry).min(1_000);
+            let delay = policy.delay(retry, None).unwrap().as_millis() as u64;
+            assert!((cap / 2..=cap).contains(&delay), "retry {retry}: {delay}ms");
+        }
+        assert!(policy
+            .delay(0, Some(Duration::from_millis(2_000)))
+            .is_none());
+        assert!(is_transient(
+            "API error (529 <unknown status code>): overloaded"
+        ));
+        assert!(!is_transient("API error (401 Unauthorized): bad key"));
+    }
+
+    #[test]
+    fn parses_failover_section() {
+        let toml = r#"
+max_retries = 4
+
+[[providers]]
+name = "anthropic"
+
+[[providers]]
+name = "openai"
+model = "gpt-4o"
+models = { "claude-sonnet-4-20250514" = "gpt-4o-mini" }
+"#;
+        let config: FailoverConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.providers.len(), 2);
+        assert_eq!(config.base_delay_ms, 500);
+        let policy = RetryPolicy::from(&config);
+        assert_eq!(policy.max_retries, 4);
+        assert_eq!(
+            config.providers[1].models["claude-sonnet-4-20250514"],
+            "gpt-4o-mini"
+        );
+    }
+}
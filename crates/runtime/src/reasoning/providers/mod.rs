@@ -1,8 +1,14 @@
 //! Inference provider implementations
 //!
-//! Wraps existing `LlmClient` and `SlmRunner` with the unified `InferenceProvider` trait.
+//! Wraps existing `LlmClient` and `SlmRunner` with the unified `InferenceProvider` trait,
+//! composes providers into retrying failover chains, and records/replays
+//! inference calls to cassette files for deterministic tests.
 
 #[cfg(feature = "cloud-llm")]
 pub mod cloud;
+#[cfg(feature = "cloud-llm")]
+mod sse;
 
+pub mod cassette;
+pub mod failover;
 pub mod slm;
@@ -33,7 +33,7 @@ impl SlmInferenceProvider {
 
         // Start with system message, augmented with tool/format instructions
         if let Some(sys) = conversation.system_message() {
-            parts.push(format!("### System\n{}", sys.content));
+            parts.push(format!("### System\n{}", sys.text()));
         }
 
         // Inject tool definitions into the prompt
@@ -73,7 +73,7 @@ impl SlmInferenceProvider {
             match msg.role {
                 crate::reasoning::conversation::MessageRole::System => continue, // Already handled
                 crate::reasoning::conversation::MessageRole::User => {
-                    parts.push(format!("\n### User\n{}", msg.content));
+                    parts.push(format!("\n### User\n{}", msg.text()));
                 }
                 crate::reasoning::conversation::MessageRole::Assistant => {
                     if !msg.tool_calls.is_empty() {
@@ -92,15 +92,12 @@ impl SlmInferenceProvider {
                             serde_json::to_string(&tc_json).unwrap_or_default()
                         ));
                     } else {
-                        parts.push(format!("\n### Assistant\n{}", msg.content));
+                        parts.push(format!("\n### Assistant\n{}", msg.text()));
                     }
                 }
                 crate::reasoning::conversation::MessageRole::Tool => {
                     let tool_name = msg.tool_name.as_deref().unwrap_or("unknown");
-                    parts.push(format!(
-                        "\n### Tool Result ({})\n{}",
-                        tool_name, msg.content
-                    ));
+                    parts.push(format!("\n### Tool Result ({})\n{}", tool_name, msg.text()));
                 }
             }
         }
@@ -170,6 +167,16 @@ impl InferenceProvider for SlmInferenceProvider {
         conversation: &Conversation,
         options: &InferenceOptions,
     ) -> Result<InferenceResponse, InferenceError> {
+        // The runner takes a single text prompt; dropping attachments would
+        // have the model answer about images it never saw.
+        if conversation.has_media() {
+            return Err(InferenceError::InvalidRequest(format!(
+                "SLM model '{}' accepts text only; the conversation contains image or document \
+                 content. Route multimodal turns to a vision-capable cloud provider.",
+                self.model_name
+            )));
+        }
+
         let prompt = Self::build_prompt(conversation, options);
 
         let exec_options = ExecutionOptions {
@@ -210,6 +217,7 @@ impl InferenceProvider for SlmInferenceProvider {
                 .input_tokens
                 .unwrap_or(0)
                 .saturating_add(result.metadata.output_tokens.unwrap_or(0)),
+            cached_prompt_tokens: 0,
         };
 
         Ok(InferenceResponse {
@@ -241,7 +249,71 @@ impl InferenceProvider for SlmInferenceProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::reasoning::conversation::ConversationMessage;
+    use crate::config::{ModelResourceRequirements, SandboxProfile};
+    use crate::models::runners::{ExecutionResult, RunnerInfo};
+    use crate::models::SlmRunnerError;
+    use crate::reasoning::conversation::{ContentPart, ConversationMessage};
+
+    /// A runner that must never be reached.
+    struct UnreachableRunner {
+        profile: SandboxProfile,
+        requirements: ModelResourceRequirements,
+    }
+
+    #[async_trait]
+    impl SlmRunner for UnreachableRunner {
+        async fn execute(
+            &self,
+            _prompt: &str,
+            _options: Option<ExecutionOptions>,
+        ) -> Result<ExecutionResult, SlmRunnerError> {
+            unreachable!("multimodal requests are rejected before execution")
+        }
+        fn get_sandbox_profile(&self) -> &SandboxProfile {
+            &self.profile
+        }
+        fn get_resource_requirements(&self) -> &ModelResourceRequirements {
+            &self.requirements
+        }
+        async fn health_check(&self) -> Result<(), SlmRunnerError> {
+            Ok(())
+        }
+        fn get_info(&self) -> RunnerInfo {
+            RunnerInfo {
+                runner_type: "unreachable".into(),
+                model_path: String::new(),
+                capabilities: Vec::new(),
+                version: None,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_image_content() {
+        let provider = SlmInferenceProvider::new(
+            Arc::new(UnreachableRunner {
+                profile: SandboxProfile::secure_default(),
+                requirements: ModelResourceRequirements {
+                    min_memory_mb: 512,
+                    preferred_cpu_cores: 1.0,
+                    gpu_requirements: None,
+                },
+            }),
+            "tiny",
+        );
+        let mut conv = Conversation::new();
+        conv.push(
+            ConversationMessage::user("describe")
+                .with_parts([ContentPart::image("image/png", b"png")]),
+        );
+
+        let err = provider
+            .complete(&conv, &InferenceOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, InferenceError::InvalidRequest(_)));
+        assert!(err.to_string().contains("'tiny' accepts text only"));
+    }
 
     #[test]
     fn test_strip_markdown_fences_json() {
@@ -0,0 +1,709 @@
+//! Server-sent event decoding for streaming cloud inference
+//!
+//! Splits a raw `text/event-stream` byte stream into frames and maps the
+//! OpenAI-compatible (OpenAI, OpenRouter) and Anthropic Messages streaming
+//! formats onto [`StreamEvent`]s. Everything here is pure and network-free
+//! so the wire formats can be unit-tested from captured transcripts.
+
+use std::collections::{HashMap, VecDeque};
+
+use futures::{Stream, StreamExt};
+
+use super::cloud::map_anthropic_stop_reason;
+use crate::reasoning::inference::{
+    FinishReason, InferenceError, InferenceStream, StreamEvent, Usage,
+};
+
+/// One dispatched SSE frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SseFrame {
+    /// The `event:` field, if the server sent one.
+    pub event: Option<String>,
+    /// The `data:` payload; multiple data lines are joined with `\n`.
+    pub data: String,
+}
+
+/// Incremental SSE decoder.
+///
+/// Bytes are buffered until a full line is available, so chunk boundaries
+/// that split a line (or a multi-byte UTF-8 sequence) are handled.
+#[derive(Debug, Default)]
+pub(crate) struct SseDecoder {
+    buf: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk of bytes and return every frame it completed.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseFrame> {
+        self.buf.extend_from_slice(chunk);
+        let mut frames = Vec::new();
+        while let Some(pos) = self.buf.iter().position(|b| *b == b'\n') {
+            let mut line: Vec<u8> = self.buf.drain(..=pos).collect();
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            let line = String::from_utf8_lossy(&line);
+            if let Some(frame) = self.process_line(&line) {
+                frames.push(frame);
+            }
+        }
+        frames
+    }
+
+    /// Flush a trailing frame when the stream ends without a blank line.
+    pub fn finish(&mut self) -> Option<SseFrame> {
+        if !self.buf.is_empty() {
+            let rest = std::mem::take(&mut self.buf);
+            let line = String::from_utf8_lossy(&rest).into_owned();
+            if let Some(frame) = self.process_line(line.trim_end_matches('\r')) {
+                return Some(frame);
+            }
+        }
+        self.dispatch()
+    }
+
+    fn process_line(&mut self, line: &str) -> Option<SseFrame> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((f, v)) => (f, v.strip_prefix(' ').unwrap_or(v)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseFrame> {
+        if self.data.is_empty() && self.event.is_none() {
+            return None;
+        }
+        let frame = SseFrame {
+            event: self.event.take(),
+            data: std::mem::take(&mut self.data).join("\n"),
+        };
+        Some(frame)
+    }
+}
+
+fn parse_frame_json(frame: &SseFrame) -> Result<serde_json::Value, InferenceError> {
+    serde_json::from_str(&frame.data)
+        .map_err(|e| InferenceError::ParseError(format!("Invalid stream chunk: {e}")))
+}
+
+fn u32_field(v: &serde_json::Value, key: &str) -> Option<u32> {
+    v.get(key).and_then(|x| x.as_u64()).map(|x| x as u32)
+}
+
+/// Maps OpenAI Chat Completions stream chunks (also used by OpenRouter).
+#[derive(Debug)]
+pub(crate) struct OpenAiStreamParser {
+    model: String,
+    finish_reason: Option<FinishReason>,
+    usage: Usage,
+    saw_tool_calls: bool,
+    finished: bool,
+}
+
+impl OpenAiStreamParser {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            finish_reason: None,
+            usage: Usage::default(),
+            saw_tool_calls: false,
+            finished: false,
+        }
+    }
+
+    pub fn on_frame(&mut self, frame: &SseFrame) -> Result<Vec<StreamEvent>, InferenceError> {
+        if self.finished {
+            return Ok(Vec::new());
+        }
+        if frame.data.trim() == "[DONE]" {
+            return Ok(self.finish().into_iter().collect());
+        }
+        let chunk = parse_frame_json(frame)?;
+        if let Some(err) = chunk.get("error") {
+            let message = err
+                .get("message")
+                .and_then(|m| m.as_str())
+                .unwrap_or("unknown stream error");
+            return Err(InferenceError::Provider(format!("Stream error: {message}")));
+        }
+        if let Some(model) = chunk.get("model").and_then(|m| m.as_str()) {
+            self.model = model.to_string();
+        }
+        if let Some(u) = chunk.get("usage").filter(|u| !u.is_null()) {
+            self.usage = Usage {
+                prompt_tokens: u32_field(u, "prompt_tokens").unwrap_or(0),
+                completion_tokens: u32_field(u, "completion_tokens").unwrap_or(0),
+                total_tokens: u32_field(u, "total_tokens").unwrap_or(0),
+                cached_prompt_tokens: u
+                    .get("prompt_tokens_details")
+                    .and_then(|d| u32_field(d, "cached_tokens"))
+                    .unwrap_or(0),
+            };
+        }
+
+        let mut events = Vec::new();
+        let Some(choice) = chunk.get("choices").and_then(|c| c.get(0)) else {
+            return Ok(events);
+        };
+        if let Some(delta) = choice.get("delta") {
+            if let Some(text) = delta.get("content").and_then(|c| c.as_str()) {
+                if !text.is_empty() {
+                    events.push(StreamEvent::TextDelta {
+                        text: text.to_string(),
+                    });
+                }
+            }
+            if let Some(calls) = delta.get("tool_calls").and_then(|t| t.as_array()) {
+                for (pos, call) in calls.iter().enumerate() {
+                    self.saw_tool_calls = true;
+                    let function = call.get("function");
+                    events.push(StreamEvent::ToolCallDelta {
+                        index: call
+                            .get("index")
+                            .and_then(|i| i.as_u64())
+                            .map(|i| i as usize)
+                            .unwrap_or(pos),
+                        id: call.get("id").and_then(|v| v.as_str()).map(String::from),
+                        name: function
+                            .and_then(|f| f.get("name"))
+                            .and_then(|v| v.as_str())
+                            .map(String::from),
+                        arguments_delta: function
+                            .and_then(|f| f.get("arguments"))
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("")
+                            .to_string(),
+                    });
+                }
+            }
+        }
+        if let Some(reason) = choice.get("finish_reason").and_then(|f| f.as_str()) {
+            self.finish_reason = Some(match reason {
+                "tool_calls" | "function_call" => FinishReason::ToolCalls,
+                "length" => FinishReason::MaxTokens,
+                "content_filter" => FinishReason::ContentFilter,
+                _ if self.saw_tool_calls => FinishReason::ToolCalls,
+                _ => FinishReason::Stop,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Emit the terminal event. Returns `None` if it was already emitted.
+    pub fn finish(&mut self) -> Option<StreamEvent> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        let finish_reason = self
+            .finish_reason
+            .clone()
+            .unwrap_or(if self.saw_tool_calls {
+                FinishReason::ToolCalls
+            } else {
+                FinishReason::Stop
+            });
+        Some(StreamEvent::Finished {
+            finish_reason,
+            usage: self.usage.clone(),
+            model: self.model.clone(),
+        })
+    }
+}
+
+/// Maps Anthropic Messages API stream events.
+#[derive(Debug)]
+pub(crate) struct AnthropicStreamParser {
+    model: String,
+    usage: Usage,
+    stop_reason: Option<String>,
+    /// Content-block index -> tool-call ordinal.
+    tool_blocks: HashMap<usize, usize>,
+    emitted_text: bool,
+    separator_pending: bool,
+    finished: bool,
+}
+
+impl AnthropicStreamParser {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            usage: Usage::default(),
+            stop_reason: None,
+            tool_blocks: HashMap::new(),
+            emitted_text: false,
+            separator_pending: false,
+            finished: false,
+        }
+    }
+
+    pub fn on_frame(&mut self, frame: &SseFrame) -> Result<Vec<StreamEvent>, InferenceError> {
+        if self.finished {
+            return Ok(Vec::new());
+        }
+        let data = parse_frame_json(frame)?;
+        let kind = frame
+            .event
+            .as_deref()
+            .or_else(|| data.get("type").and_then(|t| t.as_str()))
+            .unwrap_or("");
+        let mut events = Vec::new();
+        match kind {
+            "message_start" => {
+                let message = data.get("message");
+                if let Some(model) = message
+                    .and_then(|m| m.get("model"))
+                    .and_then(|m| m.as_str())
+                {
+                    self.model = model.to_string();
+                }
+                if let Some(u) = message.and_then(|m| m.get("usage")) {
+                    let cached = u32_field(u, "cache_read_input_tokens").unwrap_or(0);
+                    self.usage.prompt_tokens = u32_field(u, "input_tokens").unwrap_or(0)
+                        + u32_field(u, "cache_creation_input_tokens").unwrap_or(0)
+                        + cached;
+                    self.usage.cached_prompt_tokens = cached;
+                    self.usage.completion_tokens = u32_field(u, "output_tokens").unwrap_or(0);
+                }
+            }
+            "content_block_start" => {
+                let index = data.get("index").and_then(|i| i.as_u64()).unwrap_or(0) as usize;
+                let block = data.get("content_block");
+                match block.and_then(|b| b.get("type")).and_then(|t| t.as_str()) {
+                    Some("text") => {
+                        // The non-streaming parser joins text blocks with a
+                        // newline; keep the streamed text identical.
+                        self.separator_pending = self.emitted_text;
+                    }
+                    Some("tool_use") => {
+                        let ordinal = self.tool_blocks.len();
+                        self.tool_blocks.insert(index, ordinal);
+                        events.push(StreamEvent::ToolCallDelta {
+                            index: ordinal,
+                            id: block
+                                .and_then(|b| b.get("id"))
+                                .and_then(|v| v.as_str())
+                                .map(String::from),
+                            name: block
+                                .and_then(|b| b.get("name"))
+                                .and_then(|v| v.as_str())
+                                .map(String::from),
+                            arguments_delta: String::new(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            "content_block_delta" => {
+                let index = data.get("index").and_then(|i| i.as_u64()).unwrap_or(0) as usize;
+                let delta = data.get("delta");
+                match delta.and_then(|d| d.get("type")).and_then(|t| t.as_str()) {
+                    Some("text_delta") => {
+                        let text = delta
+                            .and_then(|d| d.get("text"))
+                            .and_then(|t| t.as_str())
+                            .unwrap_or("");
+                        if !text.is_empty() {
+                            let mut out = String::new();
+                            if self.separator_pending {
+                                out.push('\n');
+                                self.separator_pending = false;
+                            }
+                            out.push_str(text);
+                            self.emitted_text = true;
+                            events.push(StreamEvent::TextDelta { text: out });
+                        }
+                    }
+                    Some("input_json_delta") => {
+                        if let Some(ordinal) = self.tool_blocks.get(&index) {
+                            events.push(StreamEvent::ToolCallDelta {
+                                index: *ordinal,
+                                id: None,
+                                name: None,
+                                arguments_delta: delta
+                                    .and_then(|d| d.get("partial_json"))
+                                    .and_then(|t| t.as_str())
+                                    .unwrap_or("")
+                                    .to_string(),
+                            });
+                        }
+                    }
+                    // thinking_delta / signature_delta are not surfaced.
+                    _ => {}
+                }
+            }
+            "message_delta" => {
+                if let Some(reason) = data
+                    .get("delta")
+                    .and_then(|d| d.get("stop_reason"))
+                    .and_then(|s| s.as_str())
+                {
+                    self.stop_reason = Some(reason.to_string());
+                }
+                if let Some(output) = data
+                    .get("usage")
+                    .and_then(|u| u32_field(u, "output_tokens"))
+                {
+                    self.usage.completion_tokens = output;
+                }
+            }
+            "message_stop" => {
+                events.extend(self.finish());
+            }
+            "error" => {
+                let err = data.get("error");
+                let err_type = err
+                    .and_then(|e| e.get("type"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("error");
+                let message = err
+                    .and_then(|e| e.get("message"))
+                    .and_then(|m| m.as_str())
+                    .unwrap_or("unknown stream error");
+                return Err(match err_type {
+                    "rate_limit_error" => InferenceError::RateLimited {
+                        retry_after_ms: 1000,
+                    },
+                    _ => InferenceError::Provider(format!("Stream error ({err_type}): {message}")),
+                });
+            }
+            // ping, content_block_stop, and unknown future events.
+            _ => {}
+        }
+        Ok(events)
+    }
+
+    /// Emit the terminal event. Returns `None` if it was already emitted.
+    pub fn finish(&mut self) -> Option<StreamEvent> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        let stop_reason = self.stop_reason.as_deref().unwrap_or("end_turn");
+        let mut usage = self.usage.clone();
+        usage.total_tokens = usage.prompt_tokens + usage.completion_tokens;
+        Some(StreamEvent::Finished {
+            finish_reason: map_anthropic_stop_reason(stop_reason, !self.tool_blocks.is_empty()),
+            usage,
+            model: self.model.clone(),
+        })
+    }
+}
+
+/// A stream parser for whichever wire format the provider speaks.
+#[derive(Debug)]
+pub(crate) enum StreamParser {
+    OpenAi(OpenAiStreamParser),
+    Anthropic(AnthropicStreamParser),
+}
+
+impl StreamParser {
+    pub fn on_frame(&mut self, frame: &SseFrame) -> Result<Vec<StreamEvent>, InferenceError> {
+        match self {
+            Self::OpenAi(p) => p.on_frame(frame),
+            Self::Anthropic(p) => p.on_frame(frame),
+        }
+    }
+
+    pub fn finish(&mut self) -> Option<StreamEvent> {
+        match self {
+            Self::OpenAi(p) => p.finish(),
+            Self::Anthropic(p) => p.finish(),
+        }
+    }
+}
+
+/// Turn a response body byte stream into an [`InferenceStream`].
+///
+/// The stream ends after the parser's terminal `Finished` event, or right
+/// after the first error.
+pub(crate) fn event_stream<S, B>(bytes: S, parser: StreamParser) -> InferenceStream
+where
+    S: Stream<Item = Result<B, InferenceError>> + Send + 'static,
+    B: AsRef<[u8]>,
+{
+    type Pending = VecDeque<Result<StreamEvent, InferenceError>>;
+    let state = (
+        Box::pin(bytes),
+        SseDecoder::new(),
+        parser,
+        Pending::new(),
+        false,
+    );
+    Box::pin(futures::stream::unfold(
+        state,
+        |(mut bytes, mut decoder, mut parser, mut pending, mut done)| async move {
+            loop {
+                if let Some(item) = pending.pop_front() {
+                    return Some((item, (bytes, decoder, parser, pending, done)));
+                }
+                if done {
+                    return None;
+                }
+                let frames = match bytes.next().await {
+                    Some(Ok(chunk)) => decoder.push(chunk.as_ref()),
+                    Some(Err(e)) => {
+                        pending.push_back(Err(e));
+                        done = true;
+                        continue;
+                    }
+                    None => {
+                        done = true;
+                        decoder.finish().into_iter().collect()
+                    }
+                };
+                for frame in frames {
+                    match parser.on_frame(&frame) {
+                        Ok(events) => pending.extend(events.into_iter().map(Ok)),
+                        Err(e) => {
+                            pending.push_back(Err(e));
+                            done = true;
+                            break;
+                        }
+                    }
+                }
+                if done && !matches!(pending.back(), Some(Err(_))) {
+                    pending.extend(parser.finish().map(Ok));
+                }
+            }
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reasoning::inference::StreamAccumulator;
+
+    fn run(parser: &mut StreamParser, transcript: &str, chunk_size: usize) -> Vec<StreamEvent> {
+        let mut decoder = SseDecoder::new();
+        let mut events = Vec::new();
+        for chunk in transcript.as_bytes().chunks(chunk_size) {
+            for frame in decoder.push(chunk) {
+                events.extend(parser.on_frame(&frame).unwrap());
+            }
+        }
+        if let Some(frame) = decoder.finish() {
+            events.extend(parser.on_frame(&frame).unwrap());
+        }
+        events.extend(parser.finish());
+        events
+    }
+
+    #[test]
+    fn decoder_handles_split_lines_and_comments() {
+        let mut d = SseDecoder::new();
+        assert!(d.push(b": keepalive\n\nevent: ping\r\nda").is_empty());
+        let frames = d.push(b"ta: {}\r\n\r\ndata: a\ndata: b\n\n");
+        assert_eq!(
+            frames,
+            vec![
+                SseFrame {
+                    event: Some("ping".into()),
+                    data: "{}".into()
+                },
+                SseFrame {
+                    event: None,
+                    data: "a\nb".into()
+                },
+            ]
+        );
+        assert!(d.finish().is_none());
+    }
+
+    #[test]
+    fn openai_transcript_with_text_tool_calls_and_usage() {
+        let transcript = concat!(
+            "data: {\"model\":\"gpt-4o-2024\",\"choices\":[{\"delta\":{\"role\":\"assistant\",\"content\":\"Hel\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"lo\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"type\":\"function\",\"function\":{\"name\":\"search\",\"arguments\":\"\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"{\\\"q\\\":\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"\\\"rust\\\"}\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"tool_calls\"}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":8,\"total_tokens\":20,\"prompt_tokens_details\":{\"cached_tokens\":4}}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        for chunk_size in [1, 7, 4096] {
+            let mut parser = StreamParser::OpenAi(OpenAiStreamParser::new("gpt-4o"));
+            let events = run(&mut parser, transcript, chunk_size);
+            assert!(matches!(events.last(), Some(StreamEvent::Finished { .. })));
+
+            let mut acc = StreamAccumulator::new();
+            events.iter().for_each(|e| acc.push(e));
+            let resp = acc.finish("unused");
+            assert_eq!(resp.content, "Hello");
+            assert_eq!(resp.tool_calls.len(), 1);
+            assert_eq!(resp.tool_calls[0].id, "call_1");
+            assert_eq!(resp.tool_calls[0].name, "search");
+            assert_eq!(resp.tool_calls[0].arguments, r#"{"q":"rust"}"#);
+            assert_eq!(resp.finish_reason, FinishReason::ToolCalls);
+            assert_eq!(resp.usage.total_tokens, 20);
+            assert_eq!(resp.usage.cached_prompt_tokens, 4);
+            assert_eq!(resp.model, "gpt-4o-2024");
+        }
+    }
+
+    #[test]
+    fn openai_stream_without_done_still_finishes() {
+        let transcript =
+            "data: {\"choices\":[{\"delta\":{\"content\":\"hi\"},\"finish_reason\":\"length\"}]}\n\n";
+        let mut parser = StreamParser::OpenAi(OpenAiStreamParser::new("gpt-4o"));
+        let events = run(&mut parser, transcript, 64);
+        match events.last() {
+            Some(StreamEvent::Finished { finish_reason, .. }) => {
+                assert_eq!(*finish_reason, FinishReason::MaxTokens)
+            }
+            other => panic!("expected Finished, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn openai_error_chunk_is_an_error() {
+        let mut parser = OpenAiStreamParser::new("m");
+        let frame = SseFrame {
+            event: None,
+            data: r#"{"error":{"message":"upstream overloaded"}}"#.into(),
+        };
+        let err = parser.on_frame(&frame).unwrap_err();
+        assert!(err.to_string().contains("upstream overloaded"));
+    }
+
+    #[test]
+    fn anthropic_transcript_with_text_and_tool_use() {
+        let transcript = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"model\":\"claude-sonnet-4-5\",\"usage\":{\"input_tokens\":5,\"cache_creation_input_tokens\":8,\"cache_read_input_tokens\":12,\"output_tokens\":1}}}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+            "event: ping\n",
+            "data: {\"type\":\"ping\"}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"Searching\"}}\n\n",
+            "event: content_block_stop\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"web_search\",\"input\":{}}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"query\\\": \"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"\\\"rust\\\"}\"}}\n\n",
+            "event: content_block_stop\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":1}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":30}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        );
+        for chunk_size in [3, 4096] {
+            let mut parser =
+                StreamParser::Anthropic(AnthropicStreamParser::new("claude-requested"));
+            let events = run(&mut parser, transcript, chunk_size);
+            let finished = events
+                .iter()
+                .filter(|e| matches!(e, StreamEvent::Finished { .. }))
+                .count();
+            assert_eq!(finished, 1, "exactly one Finished event");
+
+            let mut acc = StreamAccumulator::new();
+            events.iter().for_each(|e| acc.push(e));
+            let resp = acc.finish("unused");
+            assert_eq!(resp.content, "Searching");
+            assert_eq!(resp.tool_calls.len(), 1);
+            assert_eq!(resp.tool_calls[0].id, "toolu_1");
+            assert_eq!(resp.tool_calls[0].arguments, r#"{"query": "rust"}"#);
+            assert_eq!(resp.finish_reason, FinishReason::ToolCalls);
+            assert_eq!(resp.usage.prompt_tokens, 25);
+            assert_eq!(resp.usage.cached_prompt_tokens, 12);
+            assert_eq!(resp.usage.completion_tokens, 30);
+            assert_eq!(resp.usage.total_tokens, 55);
+            assert_eq!(resp.model, "claude-sonnet-4-5");
+        }
+    }
+
+    #[test]
+    fn anthropic_multiple_text_blocks_are_newline_joined() {
+        let transcript = concat!(
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"text\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"one\"}}\n\n",
+            "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"text\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"two\"}}\n\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"refusal\"}}\n\n",
+        );
+        let mut parser = StreamParser::Anthropic(AnthropicStreamParser::new("m"));
+        let events = run(&mut parser, transcript, 4096);
+        let mut acc = StreamAccumulator::new();
+        events.iter().for_each(|e| acc.push(e));
+        let resp = acc.finish("m");
+        assert_eq!(resp.content, "one\ntwo");
+        assert_eq!(resp.finish_reason, FinishReason::Refusal);
+    }
+
+    #[tokio::test]
+    async fn event_stream_yields_events_then_ends() {
+        let body = concat!(
+            "data: {\"choices\":[{\"delta\":{\"content\":\"a\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"b\"}}]}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let chunks: Vec<Result<Vec<u8>, InferenceError>> =
+            body.as_bytes().chunks(5).map(|c| Ok(c.to_vec())).collect();
+        let stream = event_stream(
+            futures::stream::iter(chunks),
+            StreamParser::OpenAi(OpenAiStreamParser::new("m")),
+        );
+        let events: Vec<_> = stream.collect().await;
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[0], Ok(StreamEvent::TextDelta { text }) if text == "a"));
+        assert!(matches!(&events[2], Ok(StreamEvent::Finished { .. })));
+    }
+
+    #[tokio::test]
+    async fn event_stream_stops_after_transport_error() {
+        let chunks: Vec<Result<&'static [u8], InferenceError>> = vec![
+            Ok(b"data: {\"choices\":[{\"delta\":{\"content\":\"a\"}}]}\n\n"),
+            Err(InferenceError::Timeout(std::time::Duration::from_secs(1))),
+            Ok(b"data: [DONE]\n\n"),
+        ];
+        let stream = event_stream(
+            futures::stream::iter(chunks),
+            StreamParser::OpenAi(OpenAiStreamParser::new("m")),
+        );
+        let events: Vec<_> = stream.collect().await;
+        assert_eq!(events.len(), 2);
+        assert!(events[0].is_ok());
+        assert!(matches!(events[1], Err(InferenceError::Timeout(_))));
+    }
+
+    #[test]
+    fn anthropic_error_event_maps_rate_limit() {
+        let mut parser = AnthropicStreamParser::new("m");
+        let frame = SseFrame {
+            event: Some("error".into()),
+            data: r#"{"type":"error","error":{"type":"rate_limit_error","message":"slow down"}}"#
+                .into(),
+        };
+        assert!(matches!(
+            parser.on_frame(&frame),
+            Err(InferenceError::RateLimited { .. })
+        ));
+    }
+}
@@ -6,6 +6,8 @@
 
 use std::sync::Arc;
 
+use tracing::Instrument;
+
 use crate::reasoning::circuit_breaker::CircuitBreakerRegistry;
 use crate::reasoning::context_manager::{ContextManager, DefaultContextManager};
 use crate::reasoning::conversation::Conversation;
@@ -14,7 +16,9 @@ use crate::reasoning::inference::InferenceProvider;
 use crate::reasoning::knowledge_bridge::KnowledgeBridge;
 use crate::reasoning::knowledge_executor::KnowledgeAwareExecutor;
 use crate::reasoning::loop_types::*;
-use crate::reasoning::phases::{AgentLoop, LoopContinuation, Reasoning};
+use crate::reasoning::phases::{
+    AgentLoop, LoopContinuation, LoopTermination, LoopTerminationReason, Reasoning,
+};
 use crate::reasoning::policy_bridge::{DefaultPolicyGate, ReasoningPolicyGate};
 use crate::types::AgentId;
 
@@ -37,6 +41,14 @@ pub struct ReasoningLoopRunner {
     /// Optional agent-to-agent delegation handle. `None` → an approved
     /// `Delegate` action surfaces an honest error instead of running.
     pub delegation: Option<Arc<dyn crate::reasoning::delegation::DelegationExecutor>>,
+    /// Optional sandbox session manager. When set, each run opens one
+    /// session, exposes it to executors as `LoopConfig::sandbox_session`,
+    /// and closes it when the run ends.
+    pub sandbox_sessions: Option<Arc<crate::sandbox::SandboxSessionManager>>,
+    /// Optional spend tracker. When set, every inference step is priced and
+    /// billed to the run's scopes, and the tracker's limits are checked
+    /// before each inference call.
+    pub spend: Option<Arc<crate::reasoning::spend::SpendTracker>>,
 }
 
 /// Builder for `ReasoningLoopRunner` with typestate enforcement.
@@ -59,6 +71,8 @@ pub struct ReasoningLoopRunnerBuilder<P, E> {
     journal: Option<Arc<dyn JournalWriter>>,
     knowledge_bridge: Option<Arc<KnowledgeBridge>>,
     delegation: Option<Arc<dyn crate::reasoning::delegation::DelegationExecutor>>,
+    sandbox_sessions: Option<Arc<crate::sandbox::SandboxSessionManager>>,
+    spend: Option<Arc<crate::reasoning::spend::SpendTracker>>,
 }
 
 impl ReasoningLoopRunner {
@@ -73,6 +87,8 @@ impl ReasoningLoopRunner {
             journal: None,
             knowledge_bridge: None,
             delegation: None,
+            sandbox_sessions: None,
+            spend: None,
         }
     }
 }
@@ -117,6 +133,18 @@ impl<P, E> ReasoningLoopRunnerBuilder<P, E> {
         self.delegation = Some(delegation);
         self
     }
+
+    /// Bind one sandbox session from `manager` to each run.
+    pub fn sandbox_sessions(mut self, manager: Arc<crate::sandbox::SandboxSessionManager>) -> Self {
+        self.sandbox_sessions = Some(manager);
+        self
+    }
+
+    /// Price and budget every run with `tracker`.
+    pub fn spend_tracker(mut self, tracker: Arc<crate::reasoning::spend::SpendTracker>) -> Self {
+        self.spend = Some(tracker);
+        self
+    }
 }
 
 // Set provider (transitions from () to Arc<dyn InferenceProvider>)
@@ -135,6 +163,8 @@ impl<E> ReasoningLoopRunnerBuilder<(), E> {
             journal: self.journal,
             knowledge_bridge: self.knowledge_bridge,
             delegation: self.delegation,
+            sandbox_sessions: self.sandbox_sessions,
+            spend: self.spend,
         }
     }
 }
@@ -155,6 +185,8 @@ impl<P> ReasoningLoopRunnerBuilder<P, ()> {
             journal: self.journal,
             knowledge_bridge: self.knowledge_bridge,
             delegation: self.delegation,
+            sandbox_sessions: self.sandbox_sessions,
+            spend: self.spend,
         }
     }
 }
@@ -180,6 +212,8 @@ impl ReasoningLoopRunnerBuilder<Arc<dyn InferenceProvider>, Arc<dyn ActionExecut
                 .unwrap_or_else(|| Arc::new(BufferedJournal::new(1000))),
             knowledge_bridge: self.knowledge_bridge,
             delegation: self.delegation,
+            sandbox_sessions: self.sandbox_sessions,
+            spend: self.spend,
         }
     }
 }
@@ -234,6 +268,147 @@ impl ReasoningLoopRunner {
             })
             .await;
 
+        self.run_with_timeout(state, config).await
+    }
+
+    /// Resume an interrupted loop from a durable journal.
+    ///
+    /// Looks for the agent's most recent `Started` entry; if no `Terminated`
+    /// follows it, the loop continues from the iteration the journal last
+    /// recorded, appending to the same journal. Returns `Ok(None)` when
+    /// there is nothing to resume.
+    ///
+    /// If the recorded iteration finished, its closing `Checkpoint` is
+    /// resumed as-is. If it was cut short, the loop rewinds to the
+    /// checkpoint before it and that iteration runs again (including its
+    /// tool calls), so resumption is at-least-once per iteration. The
+    /// resumed segment gets a fresh `config.timeout`.
+    pub async fn resume(
+        &self,
+        journal: Arc<crate::reasoning::journal::DurableJournal>,
+    ) -> Result<Option<LoopResult>, JournalError> {
+        journal.initialize().await?;
+
+        let entries = journal.replay().await?;
+        let Some(start) = entries
+            .iter()
+            .rposition(|e| matches!(e.event, LoopEvent::Started { .. }))
+        else {
+            return Ok(None);
+        };
+        let run = &entries[start..];
+        if run
+            .iter()
+            .any(|e| matches!(e.event, LoopEvent::Terminated { .. }))
+        {
+            return Ok(None);
+        }
+
+        let config = match &run[0].event {
+            LoopEvent::Started { config, .. } => (**config).clone(),
+            _ => unreachable!("run begins at a Started entry"),
+        };
+        let recorded = run.iter().map(|e| e.iteration).max().unwrap_or(0);
+        let checkpoint = |iteration: u32| {
+            run.iter().rev().find_map(|e| match &e.event {
+                LoopEvent::Checkpoint {
+                    iteration: i,
+                    state,
+                } if *i == iteration => Some((**state).clone()),
+                _ => None,
+            })
+        };
+        let state = checkpoint(recorded)
+            .or_else(|| recorded.checked_sub(1).and_then(checkpoint))
+            .ok_or_else(|| {
+                JournalError::ReadFailed(format!(
+                    "No checkpoint for iteration {} recorded for run {} since its last start",
+                    recorded,
+                    journal.run_id()
+                ))
+            })?;
+
+        tracing::info!(
+            "Resuming reasoning loop for agent {} at iteration {} (journal reached iteration {})",
+            state.agent_id,
+            state.iteration,
+            recorded
+        );
+
+        let runner = ReasoningLoopRunner {
+            provider: self.provider.clone(),
+            policy_gate: self.policy_gate.clone(),
+            executor: self.executor.clone(),
+            context_manager: self.context_manager.clone(),
+            circuit_breakers: self.circuit_breakers.clone(),
+            journal,
+            knowledge_bridge: self.knowledge_bridge.clone(),
+            delegation: self.delegation.clone(),
+            sandbox_sessions: self.sandbox_sessions.clone(),
+            spend: self.spend.clone(),
+        };
+        Ok(Some(runner.run_with_timeout(state, config).await))
+    }
+
+    async fn run_with_timeout(&self, state: LoopState, mut config: LoopConfig) -> LoopResult {
+        // Bind a sandbox session for this run. A run that cannot get one
+        // still proceeds; sandbox tools report the missing session.
+        let session = match &self.sandbox_sessions {
+            Some(manager) => match manager.open(state.agent_id).await {
+                Ok(id) => Some((manager, id)),
+                Err(e) => {
+                    tracing::warn!("No sandbox session for agent {}: {}", state.agent_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        config.sandbox_session = session
+            .as_ref()
+            .map(|(_, id)| crate::sandbox::SessionBinding {
+                id: *id,
+                owner: state.agent_id,
+            });
+
+        let metrics = crate::metrics::prometheus::global();
+        metrics.track_circuit_breakers(&self.circuit_breakers);
+        metrics.reasoning().record_loop_started();
+
+        let span = crate::telemetry::agent_span(
+            &state.agent_id,
+            self.provider.provider_name(),
+            self.provider.default_model(),
+        );
+        let result = self
+            .run_bounded(state, config)
+            .instrument(span.clone())
+            .await;
+        crate::telemetry::record_agent_result(
+            &span,
+            result.iterations,
+            &result.total_usage,
+            &result.termination_reason,
+        );
+
+        let tokens = u64::from(result.total_usage.total_tokens);
+        match result.termination_reason {
+            TerminationReason::Completed => metrics
+                .reasoning()
+                .record_loop_completed(result.iterations, tokens),
+            _ => metrics
+                .reasoning()
+                .record_loop_failed(result.iterations, tokens),
+        }
+
+        if let Some((manager, id)) = session {
+            if let Err(e) = manager.close(id).await {
+                tracing::warn!("Failed to close sandbox session {}: {}", id, e);
+            }
+        }
+        result
+    }
+
+    async fn run_bounded(&self, state: LoopState, config: LoopConfig) -> LoopResult {
         // Wrap the entire loop in a timeout
         let timeout = config.timeout;
         match tokio::time::timeout(timeout, self.run_inner(state, config)).await {
@@ -255,6 +430,7 @@ impl ReasoningLoopRunner {
     async fn run_inner(&self, state: LoopState, config: LoopConfig) -> LoopResult {
         let agent_id = state.agent_id;
         let mut current_loop = AgentLoop::<Reasoning>::new(state, config);
+        let mut run_spent_usd = 0.0;
 
         // Build the effective executor: wrap with KnowledgeAwareExecutor if bridge is present
         let effective_executor: Arc<dyn ActionExecutor> =
@@ -333,6 +509,23 @@ impl ReasoningLoopRunner {
         }
 
         loop {
+            // Snapshot state for durable journals so a crashed loop can resume here
+            if self.journal.wants_checkpoints() {
+                let _ = self
+                    .journal
+                    .append(JournalEntry {
+                        sequence: self.journal.next_sequence().await,
+                        timestamp: chrono::Utc::now(),
+                        agent_id,
+                        iteration: current_loop.state.iteration,
+                        event: LoopEvent::Checkpoint {
+                            iteration: current_loop.state.iteration,
+                            state: Box::new(current_loop.state.clone()),
+                        },
+                    })
+                    .await;
+            }
+
             // Inject knowledge context before reasoning if bridge is present
             if let Some(ref bridge) = self.knowledge_bridge {
                 if let Err(e) = bridge
@@ -343,16 +536,64 @@ impl ReasoningLoopRunner {
                 }
             }
 
+            // Enforce spend limits before paying for another inference call
+            if let Some(ref spend) = self.spend {
+                use crate::reasoning::spend::BudgetStatus;
+                match spend
+                    .check(agent_id, &current_loop.config.spend_tags, run_spent_usd)
+                    .await
+                {
+                    BudgetStatus::WithinBudget => {}
+                    BudgetStatus::Downgrade { model } => {
+                        if current_loop.config.model.as_ref() != Some(&model) {
+                            tracing::info!(
+                                "Agent {} is near its spend limit; routing inference to {}",
+                                agent_id,
+                                model
+                            );
+                            current_loop.config.model = Some(model);
+                        }
+                    }
+                    BudgetStatus::Exceeded(breach) => {
+                        tracing::warn!(
+                            "Agent {} stopped: {} per-{} spend limit ${:.2} reached",
+                            agent_id,
+                            breach.scope,
+                            breach.period,
+                            breach.limit_usd
+                        );
+                        let termination = LoopTermination {
+                            reason: LoopTerminationReason::BudgetExceeded(breach),
+                            state: current_loop.state,
+                        };
+                        return termination.into_result();
+                    }
+                }
+            }
+
             // Snapshot usage before reasoning to compute per-step delta
             let usage_before = current_loop.state.total_usage.clone();
+            let model = current_loop
+                .config
+                .model
+                .clone()
+                .unwrap_or_else(|| self.provider.default_model().to_string());
+            let spend_tags = current_loop.config.spend_tags.clone();
 
             // Phase 1: Reasoning
+            let chat_span = crate::telemetry::chat_span(
+                self.provider.provider_name(),
+                &model,
+                current_loop.state.iteration,
+            );
             let policy_phase = match current_loop
                 .produce_output(
                     self.provider.as_ref(),
                     self.context_manager.as_ref(),
                     self.delegation.is_some(),
+                    self.journal.as_ref(),
                 )
+                .instrument(chat_span.clone())
                 .await
             {
                 Ok(phase) => phase,
@@ -377,7 +618,26 @@ impl ReasoningLoopRunner {
                     .total_usage
                     .total_tokens
                     .saturating_sub(usage_before.total_tokens),
+                cached_prompt_tokens: policy_phase
+                    .state
+                    .total_usage
+                    .cached_prompt_tokens
+                    .saturating_sub(usage_before.cached_prompt_tokens),
             };
+            crate::telemetry::record_usage(&chat_span, &step_usage);
+            // Bill the model that answered, which after a failover is not
+            // the one requested.
+            let served_model = policy_phase.served_model().unwrap_or(&model).to_string();
+            crate::metrics::prometheus::global().record_token_usage(
+                &agent_id.to_string(),
+                &served_model,
+                &step_usage,
+            );
+            if let Some(ref spend) = self.spend {
+                run_spent_usd += spend
+                    .record(agent_id, &spend_tags, &served_model, &step_usage)
+                    .await;
+            }
             let proposed_actions = policy_phase.proposed_actions();
             let _ = self
                 .journal
@@ -395,13 +655,23 @@ impl ReasoningLoopRunner {
                 .await;
 
             // Phase 2: Policy Check
-            let dispatch_phase = match policy_phase.check_policy(self.policy_gate.as_ref()).await {
+            let policy_span = crate::telemetry::policy_span(policy_phase.state.iteration);
+            let dispatch_phase = match policy_phase
+                .check_policy(self.policy_gate.as_ref())
+                .instrument(policy_span.clone())
+                .await
+            {
                 Ok(phase) => phase,
                 Err(termination) => return termination.into_result(),
             };
 
             // Emit PolicyEvaluated journal event
             let (action_count, denied_count) = dispatch_phase.policy_summary();
+            crate::telemetry::record_policy_decision(&policy_span, action_count, denied_count);
+            let reasoning_metrics = crate::metrics::prometheus::global().reasoning();
+            for _ in 0..denied_count {
+                reasoning_metrics.record_policy_denial();
+            }
             let _ = self
                 .journal
                 .append(JournalEntry {
@@ -434,6 +704,12 @@ impl ReasoningLoopRunner {
 
             // Emit ToolsDispatched journal event
             let observation_count = observe_phase.observation_count();
+            for _ in 0..observation_count {
+                reasoning_metrics.record_tool_call();
+            }
+            for _ in 0..observe_phase.error_count() {
+                reasoning_metrics.record_tool_error();
+            }
             let _ = self
                 .journal
                 .append(JournalEntry {
@@ -553,6 +829,7 @@ mod tests {
                         prompt_tokens: 10,
                         completion_tokens: 5,
                         total_tokens: 15,
+                        cached_prompt_tokens: 0,
                     },
                     model: "mock".into(),
                 })
@@ -585,6 +862,8 @@ mod tests {
             journal: Arc::new(BufferedJournal::new(1000)),
             knowledge_bridge: None,
             delegation: None,
+            sandbox_sessions: None,
+            spend: None,
         }
     }
 
@@ -598,6 +877,7 @@ mod tests {
                 prompt_tokens: 20,
                 completion_tokens: 10,
                 total_tokens: 30,
+                cached_prompt_tokens: 0,
             },
             model: "mock".into(),
         }]));
@@ -619,6 +899,63 @@ mod tests {
         assert_eq!(result.total_usage.total_tokens, 30);
     }
 
+    #[tokio::test]
+    async fn test_stream_inference_journals_deltas_before_reasoning_complete() {
+        let provider = Arc::new(MockProvider::new(vec![InferenceResponse {
+            content: "The answer is 42.".into(),
+            tool_calls: vec![],
+            finish_reason: FinishReason::Stop,
+            usage: Usage {
+                prompt_tokens: 20,
+                completion_tokens: 10,
+                total_tokens: 30,
+                cached_prompt_tokens: 0,
+            },
+            model: "mock".into(),
+        }]));
+        let journal = Arc::new(BufferedJournal::new(1000));
+        let mut runner = make_runner(provider);
+        runner.journal = journal.clone();
+
+        let mut conv = Conversation::with_system("You are a test agent.");
+        conv.push(ConversationMessage::user("What is 6 * 7?"));
+        let config = LoopConfig {
+            stream_inference: true,
+            ..LoopConfig::default()
+        };
+
+        let result = runner.run(AgentId::new(), conv, config).await;
+        assert!(matches!(
+            result.termination_reason,
+            TerminationReason::Completed
+        ));
+        assert_eq!(result.output, "The answer is 42.");
+        assert_eq!(result.total_usage.total_tokens, 30);
+
+        let events: Vec<LoopEvent> = journal
+            .entries()
+            .await
+            .into_iter()
+            .map(|e| e.event)
+            .collect();
+        let first_delta = events
+            .iter()
+            .position(|e| matches!(e, LoopEvent::InferenceDelta { .. }))
+            .expect("deltas journaled");
+        let reasoning = events
+            .iter()
+            .position(|e| matches!(e, LoopEvent::ReasoningComplete { .. }))
+            .expect("reasoning journaled");
+        assert!(first_delta < reasoning);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            LoopEvent::InferenceDelta {
+                iteration: 1,
+                delta: StreamEvent::TextDelta { text },
+            } if text == "The answer is 42."
+        )));
+    }
+
     #[tokio::test]
     async fn test_refusal_terminates_with_error_not_empty_respond() {
         // A model refusal (e.g. Anthropic stop_reason=refusal) must not be
@@ -632,6 +969,7 @@ mod tests {
                 prompt_tokens: 20,
                 completion_tokens: 0,
                 total_tokens: 20,
+                cached_prompt_tokens: 0,
             },
             model: "mock".into(),
         }]));
@@ -651,6 +989,218 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_failover_chain_recovers_refusal_and_journals_attempts() {
+        use crate::reasoning::providers::failover::{
+            AttemptOutcome, FailoverEntry, FailoverInferenceProvider,
+        };
+
+        let refusing = Arc::new(MockProvider::new(vec![InferenceResponse {
+            content: String::new(),
+            tool_calls: vec![],
+            finish_reason: FinishReason::Refusal,
+            usage: Usage::default(),
+            model: "mock".into(),
+        }]));
+        let answering = Arc::new(MockProvider::new(vec![]));
+        let chain = FailoverInferenceProvider::new(FailoverEntry::new(refusing))
+            .then(FailoverEntry::new(answering).with_model("fallback-model"));
+        let journal = Arc::new(BufferedJournal::new(1000));
+        let mut runner = make_runner(Arc::new(chain));
+        runner.journal = journal.clone();
+
+        let mut conv = Conversation::with_system("You are a test agent.");
+        conv.push(ConversationMessage::user("What is 6 * 7?"));
+        let result = runner
+            .run(AgentId::new(), conv, LoopConfig::default())
+            .await;
+        assert!(matches!(
+            result.termination_reason,
+            TerminationReason::Completed
+        ));
+        assert_eq!(result.output, "I'm done.");
+
+        let events: Vec<LoopEvent> = journal
+            .entries()
+            .await
+            .into_iter()
+            .map(|e| e.event)
+            .collect();
+        let attempts: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                LoopEvent::InferenceAttempt {
+                    iteration: 1,
+                    attempt,
+                } => Some(attempt),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(attempts.len(), 2);
+        assert!(matches!(
+            attempts[0].outcome,
+            AttemptOutcome::Failover { .. }
+        ));
+        assert_eq!(attempts[1].outcome, AttemptOutcome::Success);
+        assert_eq!(attempts[1].model, "fallback-model");
+    }
+
+    #[tokio::test]
+    async fn test_spend_budget_downgrades_then_terminates() {
+        use crate::reasoning::spend::{
+            BudgetPeriod, MemorySpendStore, ModelPrice, PricingTable, SpendLimits, SpendScope,
+            SpendTags, SpendTracker,
+        };
+
+        /// Always proposes a tool call, recording the model each call asked for.
+        struct ModelRecorder {
+            models: std::sync::Mutex<Vec<Option<String>>>,
+        }
+
+        #[async_trait::async_trait]
+        impl InferenceProvider for ModelRecorder {
+            async fn complete(
+                &self,
+                _conversation: &Conversation,
+                options: &InferenceOptions,
+            ) -> Result<InferenceResponse, InferenceError> {
+                self.models.lock().unwrap().push(options.model.clone());
+                Ok(InferenceResponse {
+                    content: String::new(),
+                    tool_calls: vec![ToolCallRequest {
+                        id: "call_1".into(),
+                        name: "search".into(),
+                        arguments: "{}".into(),
+                    }],
+                    finish_reason: FinishReason::ToolCalls,
+                    usage: Usage {
+                        prompt_tokens: 10,
+                        completion_tokens: 0,
+                        total_tokens: 10,
+                        cached_prompt_tokens: 0,
+                    },
+                    model: options.model.clone().unwrap_or_else(|| "mock-model".into()),
+                })
+            }
+            fn provider_name(&self) -> &str {
+                "recorder"
+            }
+            fn default_model(&self) -> &str {
+                "mock-model"
+            }
+            fn supports_native_tools(&self) -> bool {
+                true
+            }
+            fn supports_structured_output(&self) -> bool {
+                true
+            }
+        }
+
+        // $1 per prompt token: every step costs $10 on either model.
+        let price = ModelPrice::new(1_000_000.0, 0.0, 0.0);
+        let tracker = Arc::new(
+            SpendTracker::new(Arc::new(MemorySpendStore::new()))
+                .with_pricing(
+                    PricingTable::empty()
+                        .with_price("mock-model", price)
+                        .with_price("cheap-model", price),
+                )
+                .with_limits(SpendLimits {
+                    per_run_usd: Some(15.0),
+                    ..Default::default()
+                })
+                .with_downgrade("cheap-model", 0.5),
+        );
+        let provider = Arc::new(ModelRecorder {
+            models: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut runner = make_runner(provider.clone());
+        runner.spend = Some(tracker.clone());
+
+        let agent_id = AgentId::new();
+        let config = LoopConfig {
+            spend_tags: SpendTags {
+                api_key: Some("ci".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = runner
+            .run(agent_id, Conversation::with_system("Spend test"), config)
+            .await;
+
+        match result.termination_reason {
+            TerminationReason::BudgetExceeded {
+                scope,
+                period,
+                limit_usd,
+                spent_usd,
+            } => {
+                assert_eq!(scope, "run");
+                assert_eq!(period, BudgetPeriod::Run);
+                assert_eq!(limit_usd, 15.0);
+                assert_eq!(spent_usd, 20.0);
+            }
+            other => panic!("expected BudgetExceeded, got {other:?}"),
+        }
+        assert_eq!(result.iterations, 2);
+        assert_eq!(
+            *provider.models.lock().unwrap(),
+            vec![None, Some("cheap-model".to_string())]
+        );
+
+        let summary = tracker.summary().await.unwrap();
+        let spent = |scope: SpendScope| {
+            summary
+                .day
+                .scopes
+                .iter()
+                .find(|s| s.scope == scope)
+                .map(|s| s.cost_usd)
+        };
+        assert_eq!(spent(SpendScope::Agent(agent_id.to_string())), Some(20.0));
+        assert_eq!(spent(SpendScope::ApiKey("ci".into())), Some(20.0));
+    }
+
+    #[tokio::test]
+    async fn test_spend_is_priced_on_the_model_that_served() {
+        use crate::reasoning::spend::{MemorySpendStore, ModelPrice, PricingTable, SpendTracker};
+
+        // Requested mock-model, but a failover answered on fallback-model.
+        let provider = Arc::new(MockProvider::new(vec![InferenceResponse {
+            content: "done".into(),
+            tool_calls: vec![],
+            finish_reason: FinishReason::Stop,
+            usage: Usage {
+                prompt_tokens: 10,
+                completion_tokens: 0,
+                total_tokens: 10,
+                cached_prompt_tokens: 0,
+            },
+            model: "fallback-model".into(),
+        }]));
+        let tracker = Arc::new(
+            SpendTracker::new(Arc::new(MemorySpendStore::new())).with_pricing(
+                PricingTable::empty()
+                    .with_price("mock-model", ModelPrice::new(1_000_000.0, 0.0, 0.0))
+                    .with_price("fallback-model", ModelPrice::new(100_000.0, 0.0, 0.0)),
+            ),
+        );
+        let mut runner = make_runner(provider);
+        runner.spend = Some(tracker.clone());
+
+        runner
+            .run(
+                AgentId::new(),
+                Conversation::with_system("Spend test"),
+                LoopConfig::default(),
+            )
+            .await;
+
+        let summary = tracker.summary().await.unwrap();
+        assert!((summary.day.total_usd - 1.0).abs() < 1e-9);
+    }
+
     #[tokio::test]
     async fn test_no_progress_turn_terminates_with_error_not_empty_respond() {
         // A turn with no tool calls AND no text (e.g. a thinking-only turn)
@@ -664,6 +1214,7 @@ mod tests {
                 prompt_tokens: 20,
                 completion_tokens: 0,
                 total_tokens: 20,
+                cached_prompt_tokens: 0,
             },
             model: "mock".into(),
         }]));
@@ -699,6 +1250,7 @@ mod tests {
                     prompt_tokens: 20,
                     completion_tokens: 15,
                     total_tokens: 35,
+                    cached_prompt_tokens: 0,
                 },
                 model: "mock".into(),
             },
@@ -711,6 +1263,7 @@ mod tests {
                     prompt_tokens: 40,
                     completion_tokens: 10,
                     total_tokens: 50,
+                    cached_prompt_tokens: 0,
                 },
                 model: "mock".into(),
             },
@@ -733,6 +1286,197 @@ mod tests {
         assert_eq!(result.total_usage.total_tokens, 85);
     }
 
+    #[tokio::test]
+    async fn test_resume_continues_from_latest_checkpoint() {
+        use crate::reasoning::journal::{DurableJournal, JournalStorage, MemoryJournalStorage};
+
+        let tool_call = InferenceResponse {
+            content: String::new(),
+            tool_calls: vec![ToolCallRequest {
+                id: "call_1".into(),
+                name: "search".into(),
+                arguments: r#"{"q": "weather"}"#.into(),
+            }],
+            finish_reason: FinishReason::ToolCalls,
+            usage: Usage {
+                prompt_tokens: 20,
+                completion_tokens: 15,
+                total_tokens: 35,
+                cached_prompt_tokens: 0,
+            },
+            model: "mock".into(),
+        };
+        let agent = AgentId::new();
+        let storage = Arc::new(MemoryJournalStorage::new());
+        let mut runner = make_runner(Arc::new(MockProvider::new(vec![tool_call])));
+        runner.journal = Arc::new(DurableJournal::new(storage.clone(), agent));
+
+        let mut conv = Conversation::with_system("You are a weather agent.");
+        conv.push(ConversationMessage::user("What's the weather?"));
+        runner.run(agent, conv, LoopConfig::default()).await;
+
+        // Simulate a crash right after the second iteration's checkpoint.
+        let entries = storage.read_entries(&agent.into()).await.unwrap();
+        let checkpoint_seq = entries
+            .iter()
+            .filter(|e| matches!(e.event, LoopEvent::Checkpoint { iteration: 1, .. }))
+            .map(|e| e.sequence)
+            .next()
+            .expect("checkpoint for iteration 1");
+        let crashed = Arc::new(MemoryJournalStorage::new());
+        for entry in entries.iter().filter(|e| e.sequence <= checkpoint_seq) {
+            crashed.store(&agent.into(), entry).await.unwrap();
+        }
+
+        let runner = make_runner(Arc::new(MockProvider::new(vec![InferenceResponse {
+            content: "The weather is sunny.".into(),
+            tool_calls: vec![],
+            finish_reason: FinishReason::Stop,
+            usage: Usage {
+                prompt_tokens: 40,
+                completion_tokens: 10,
+                total_tokens: 50,
+                cached_prompt_tokens: 0,
+            },
+            model: "mock".into(),
+        }])));
+        let journal = Arc::new(DurableJournal::new(crashed.clone(), agent));
+        let result = runner
+            .resume(journal.clone())
+            .await
+            .unwrap()
+            .expect("interrupted loop should resume");
+
+        assert!(matches!(
+            result.termination_reason,
+            TerminationReason::Completed
+        ));
+        assert_eq!(result.output, "The weather is sunny.");
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.total_usage.total_tokens, 85);
+
+        // The resumed run appended to the same journal without reusing sequences.
+        let resumed = crashed.read_entries(&agent.into()).await.unwrap();
+        assert!(resumed.windows(2).all(|w| w[0].sequence < w[1].sequence));
+        assert!(matches!(
+            resumed.last().unwrap().event,
+            LoopEvent::Terminated { .. }
+        ));
+
+        // A terminated loop has nothing left to resume.
+        assert!(runner.resume(journal).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resume_reruns_an_iteration_cut_short() {
+        use crate::reasoning::journal::{DurableJournal, JournalStorage, MemoryJournalStorage};
+
+        let tool_call = || InferenceResponse {
+            content: String::new(),
+            tool_calls: vec![ToolCallRequest {
+                id: "call_1".into(),
+                name: "search".into(),
+                arguments: r#"{"q": "weather"}"#.into(),
+            }],
+            finish_reason: FinishReason::ToolCalls,
+            usage: Usage {
+                prompt_tokens: 20,
+                completion_tokens: 15,
+                total_tokens: 35,
+                cached_prompt_tokens: 0,
+            },
+            model: "mock".into(),
+        };
+        let agent = AgentId::new();
+        let storage = Arc::new(MemoryJournalStorage::new());
+        let mut runner = make_runner(Arc::new(MockProvider::new(vec![tool_call()])));
+        runner.journal = Arc::new(DurableJournal::new(storage.clone(), agent));
+
+        let mut conv = Conversation::with_system("You are a weather agent.");
+        conv.push(ConversationMessage::user("What's the weather?"));
+        runner.run(agent, conv.clone(), LoopConfig::default()).await;
+
+        // Simulate a crash after the first iteration reasoned but before its
+        // closing checkpoint: the journal records iteration 1 unfinished.
+        // The iteration-0 checkpoint was pruned when iteration 1 closed, so
+        // restore it from the state the run started with.
+        let entries = storage.read_entries(&agent.into()).await.unwrap();
+        let cut = entries
+            .iter()
+            .find(|e| matches!(e.event, LoopEvent::ReasoningComplete { iteration: 1, .. }))
+            .map(|e| e.sequence)
+            .expect("reasoning for iteration 1");
+        let crashed = Arc::new(MemoryJournalStorage::new());
+        for entry in entries.iter().filter(|e| e.sequence <= cut) {
+            crashed.store(&agent.into(), entry).await.unwrap();
+            // The checkpoint was written right after the run started.
+            if matches!(entry.event, LoopEvent::Started { .. }) {
+                crashed
+                    .store(
+                        &agent.into(),
+                        &JournalEntry {
+                            sequence: entry.sequence + 1,
+                            timestamp: chrono::Utc::now(),
+                            agent_id: agent,
+                            iteration: 0,
+                            event: LoopEvent::Checkpoint {
+                                iteration: 0,
+                                state: Box::new(LoopState::new(agent, conv.clone())),
+                            },
+                        },
+                    )
+                    .await
+                    .unwrap();
+            }
+        }
+        assert!(!entries
+            .iter()
+            .any(|e| matches!(e.event, LoopEvent::Checkpoint { iteration: 0, .. })));
+
+        let runner = make_runner(Arc::new(MockProvider::new(vec![
+            tool_call(),
+            InferenceResponse {
+                content: "The weather is sunny.".into(),
+                tool_calls: vec![],
+                finish_reason: FinishReason::Stop,
+                usage: Usage {
+                    prompt_tokens: 40,
+                    completion_tokens: 10,
+                    total_tokens: 50,
+                    cached_prompt_tokens: 0,
+                },
+                model: "mock".into(),
+            },
+        ])));
+        let result = runner
+            .resume(Arc::new(DurableJournal::new(crashed, agent)))
+            .await
+            .unwrap()
+            .expect("interrupted loop should resume");
+
+        // Iteration 1 ran again from the iteration-0 checkpoint.
+        assert_eq!(result.output, "The weather is sunny.");
+        assert_eq!(result.iterations, 2);
+        assert_eq!(result.total_usage.total_tokens, 85);
+    }
+
+    #[tokio::test]
+    async fn test_buffered_journal_skips_checkpoints() {
+        let journal = Arc::new(BufferedJournal::new(1000));
+        let mut runner = make_runner(Arc::new(MockProvider::new(vec![])));
+        runner.journal = journal.clone();
+
+        runner
+            .run(AgentId::new(), Conversation::new(), LoopConfig::default())
+            .await;
+
+        assert!(!journal
+            .entries()
+            .await
+            .iter()
+            .any(|e| matches!(e.event, LoopEvent::Checkpoint { .. })));
+    }
+
     #[tokio::test]
     async fn test_max_iterations_termination() {
         // Provider always returns tool calls → loop should hit max_iterations
@@ -748,6 +1492,7 @@ mod tests {
                 prompt_tokens: 10,
                 completion_tokens: 5,
                 total_tokens: 15,
+                cached_prompt_tokens: 0,
             },
             model: "mock".into(),
         };
@@ -882,6 +1627,8 @@ mod tests {
             journal: Arc::new(BufferedJournal::new(1000)),
             knowledge_bridge: None,
             delegation: None,
+            sandbox_sessions: None,
+            spend: None,
         };
 
         let conv = Conversation::with_system("test");
@@ -931,6 +1678,7 @@ mod tests {
                 prompt_tokens: 10,
                 completion_tokens: 5,
                 total_tokens: 15,
+                cached_prompt_tokens: 0,
             },
             model: "mock".into(),
         }]));
@@ -944,6 +1692,8 @@ mod tests {
             journal: Arc::new(BufferedJournal::new(1000)),
             knowledge_bridge: None,
             delegation: None,
+            sandbox_sessions: None,
+            spend: None,
         };
 
         let config = LoopConfig::default();
@@ -968,6 +1718,7 @@ mod tests {
                     prompt_tokens: 10,
                     completion_tokens: 5,
                     total_tokens: 15,
+                    cached_prompt_tokens: 0,
                 },
                 model: "mock".into(),
             }]));
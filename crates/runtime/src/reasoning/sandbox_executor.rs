@@ -0,0 +1,525 @@
+//! Sandbox-session action executor wrapper.
+//!
+//! `SandboxSessionExecutor` intercepts `sandbox_exec` tool calls and runs
+//! them in the sandbox session the runner bound to the current loop run
+//! (`LoopConfig::sandbox_session`), so files written by one call are there
+//! for the next. With a kernel configured it also offers `run_cell`, which
+//! runs code against a persistent interpreter in that session and returns
+//! tables, images and JSON as `Observation::outputs`. All other tool calls
+//! go to an inner `ActionExecutor`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use tracing::Instrument;
+
+use crate::reasoning::circuit_breaker::CircuitBreakerRegistry;
+use crate::reasoning::executor::ActionExecutor;
+use crate::reasoning::inference::ToolDefinition;
+use crate::reasoning::loop_types::{LoopConfig, Observation, ProposedAction, RichOutput};
+use crate::sandbox::{
+    Artifact, ArtifactSpec, CellOutput, ExecutionRequest, Kernel, KernelConfig, MimeBundle,
+    SandboxSessionId, SandboxSessionManager, SessionBinding,
+};
+
+/// Name of the tool that runs code in the bound session.
+pub const SANDBOX_EXEC_TOOL: &str = "sandbox_exec";
+
+/// Name of the tool that runs a cell in the session's kernel.
+pub const RUN_CELL_TOOL: &str = "run_cell";
+
+/// An `ActionExecutor` wrapper that runs `sandbox_exec` calls in the loop
+/// run's sandbox session and delegates all other calls to an inner executor.
+pub struct SandboxSessionExecutor {
+    inner: Arc<dyn ActionExecutor>,
+    sessions: Arc<SandboxSessionManager>,
+    kernel: Option<KernelConfig>,
+    kernels: Mutex<HashMap<SandboxSessionId, Arc<Kernel>>>,
+}
+
+impl SandboxSessionExecutor {
+    pub fn new(inner: Arc<dyn ActionExecutor>, sessions: Arc<SandboxSessionManager>) -> Self {
+        Self {
+            inner,
+            sessions,
+            kernel: None,
+            kernels: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Offer the `run_cell` tool, backed by a kernel started in each
+    /// session on first use.
+    pub fn with_kernel(mut self, config: KernelConfig) -> Self {
+        self.kernel = Some(config);
+        self
+    }
+
+    async fn kernel_for(&self, session: SessionBinding) -> Result<Arc<Kernel>, String> {
+        let config = self
+            .kernel
+            .clone()
+            .ok_or_else(|| "No kernel is configured for this executor".to_string())?;
+        let mut kernels = self.kernels.lock().await;
+        if let Some(kernel) = kernels.get(&session.id) {
+            return Ok(kernel.clone());
+        }
+
+        // Drop kernels whose sessions have since closed.
+        let mut stale = Vec::new();
+        for id in kernels.keys() {
+            if self.sessions.info(*id).await.is_none() {
+                stale.push(*id);
+            }
+        }
+        for id in stale {
+            kernels.remove(&id);
+        }
+
+        let kernel = Arc::new(
+            Kernel::start(self.sessions.clone(), session, config)
+                .await
+                .map_err(|e| e.to_string())?,
+        );
+        kernels.insert(session.id, kernel.clone());
+        Ok(kernel)
+    }
+
+    async fn handle_cell(&self, config: &LoopConfig, arguments: &str) -> Observation {
+        #[derive(Deserialize)]
+        struct CellArgs {
+            code: String,
+        }
+
+        let args: CellArgs = match serde_json::from_str(arguments) {
+            Ok(args) => args,
+            Err(e) => {
+                return Observation::tool_error(
+                    RUN_CELL_TOOL,
+                    format!("Invalid {} arguments: {}", RUN_CELL_TOOL, e),
+                )
+            }
+        };
+        let Some(session) = config.sandbox_session else {
+            return Observation::tool_error(
+                RUN_CELL_TOOL,
+                "No sandbox session is bound to this run",
+            );
+        };
+        let kernel = match self.kernel_for(session).await {
+            Ok(kernel) => kernel,
+            Err(e) => return Observation::tool_error(RUN_CELL_TOOL, e),
+        };
+        match kernel.run_cell(&args.code).await {
+            Ok(result) => {
+                let outputs = result
+                    .outputs
+                    .iter()
+                    .filter_map(|output| match output {
+                        CellOutput::ExecuteResult { data, .. }
+                        | CellOutput::DisplayData { data } => rich_output(data),
+                        _ => None,
+                    })
+                    .collect();
+                let mut observation = if result.success() {
+                    Observation::tool_result(RUN_CELL_TOOL, result.text())
+                } else {
+                    Observation::tool_error(RUN_CELL_TOOL, result.text())
+                };
+                observation
+                    .metadata
+                    .insert("execution_count".into(), result.execution_count.to_string());
+                observation.with_outputs(outputs)
+            }
+            Err(e) => Observation::tool_error(RUN_CELL_TOOL, e.to_string()),
+        }
+    }
+
+    /// Run a `sandbox_exec` call, returning its output and any images it
+    /// was asked to return.
+    async fn handle(
+        &self,
+        config: &LoopConfig,
+        arguments: &str,
+    ) -> Result<(String, Vec<RichOutput>), String> {
+        #[derive(Deserialize)]
+        struct ExecArgs {
+            code: String,
+            #[serde(default)]
+            env: HashMap<String, String>,
+            #[serde(default)]
+            images: Vec<String>,
+        }
+
+        let args: ExecArgs = serde_json::from_str(arguments)
+            .map_err(|e| format!("Invalid {} arguments: {}", SANDBOX_EXEC_TOOL, e))?;
+        let session = config
+            .sandbox_session
+            .ok_or_else(|| "No sandbox session is bound to this run".to_string())?;
+        let mut request = ExecutionRequest::new(args.code).with_env(args.env);
+        if !args.images.is_empty() {
+            request = request.collect(ArtifactSpec::new(args.images));
+        }
+        let result = self
+            .sessions
+            .execute(session.id, session.owner, request)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let images: Vec<RichOutput> = result.artifacts.iter().filter_map(image_output).collect();
+        let body = serde_json::json!({
+            "exit_code": result.exit_code,
+            "stdout": result.stdout,
+            "stderr": result.stderr,
+            "images": result.artifacts.iter().map(|a| &a.path).collect::<Vec<_>>(),
+        });
+        if result.success {
+            Ok((body.to_string(), images))
+        } else {
+            Err(body.to_string())
+        }
+    }
+}
+
+#[async_trait]
+impl ActionExecutor for SandboxSessionExecutor {
+    async fn execute_actions(
+        &self,
+        actions: &[ProposedAction],
+        config: &LoopConfig,
+        circuit_breakers: &CircuitBreakerRegistry,
+    ) -> Vec<Observation> {
+        let mut sandbox_calls = Vec::new();
+        let mut regular_actions = Vec::new();
+
+        for action in actions {
+            match action {
+                ProposedAction::ToolCall {
+                    name,
+                    call_id,
+                    arguments,
+                } if name == SANDBOX_EXEC_TOOL
+                    || (name == RUN_CELL_TOOL && self.kernel.is_some()) =>
+                {
+                    sandbox_calls.push((name.clone(), call_id.clone(), arguments.clone()));
+                }
+                _ => regular_actions.push(action.clone()),
+            }
+        }
+
+        let mut observations = Vec::new();
+
+        // Calls share one session, so they run in the order proposed.
+        for (name, call_id, arguments) in &sandbox_calls {
+            let span = crate::telemetry::tool_span(name, call_id);
+            let observation = async {
+                if name == RUN_CELL_TOOL {
+                    self.handle_cell(config, arguments).await
+                } else {
+                    match self.handle(config, arguments).await {
+                        Ok((content, images)) => {
+                            Observation::tool_result(SANDBOX_EXEC_TOOL, content)
+                                .with_outputs(images)
+                        }
+                        Err(err) => Observation::tool_error(SANDBOX_EXEC_TOOL, err),
+                    }
+                }
+            }
+            .instrument(span.clone())
+            .await;
+            crate::telemetry::record_tool_result(&span, &observation);
+            observations.push(observation.with_call_id(call_id.clone()));
+        }
+
+        if !regular_actions.is_empty() {
+            let inner_obs = self
+                .inner
+                .execute_actions(&regular_actions, config, circuit_breakers)
+                .await;
+            observations.extend(inner_obs);
+        }
+
+        observations
+    }
+
+    fn tool_definitions(&self) -> Vec<ToolDefinition> {
+        let mut defs = self.inner.tool_definitions();
+        defs.push(sandbox_exec_tool_def());
+        if let Some(kernel) = &self.kernel {
+            defs.push(run_cell_tool_def(kernel));
+        }
+        defs
+    }
+}
+
+/// An image artifact as a rich output; `None` for other files.
+fn image_output(artifact: &Artifact) -> Option<RichOutput> {
+    let extension = artifact.path.rsplit_once('.')?.1.to_ascii_lowercase();
+    let mime_type = match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        _ => return None,
+    };
+    Some(RichOutput::Image {
+        mime_type: mime_type.to_string(),
+        data: base64::engine::general_purpose::STANDARD.encode(&artifact.content),
+    })
+}
+
+/// Pick the richest representation in a cell's MIME bundle.
+fn rich_output(data: &MimeBundle) -> Option<RichOutput> {
+    if let Some(resource) = data.get("application/vnd.dataresource+json") {
+        let columns: Vec<String> = resource["schema"]["fields"]
+            .as_array()
+            .map(|fields| {
+                fields
+                    .iter()
+                    .filter_map(|f| f["name"].as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let rows = resource["data"]
+            .as_array()
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| columns.iter().map(|c| row[c].clone()).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+        return Some(RichOutput::Table { columns, rows });
+    }
+    for mime in ["image/png", "image/jpeg"] {
+        if let Some(data) = data.get(mime).and_then(|v| v.as_str()) {
+            return Some(RichOutput::Image {
+                mime_type: mime.to_string(),
+                data: data.to_string(),
+            });
+        }
+    }
+    if let Some(svg) = data.get("image/svg+xml").and_then(|v| v.as_str()) {
+        return Some(RichOutput::Image {
+            mime_type: "image/svg+xml".to_string(),
+            data: base64::engine::general_purpose::STANDARD.encode(svg),
+        });
+    }
+    if let Some(value) = data.get("application/json") {
+        return Some(RichOutput::Json {
+            value: value.clone(),
+        });
+    }
+    data.get("text/html")
+        .and_then(|v| v.as_str())
+        .map(|html| RichOutput::Html {
+            html: html.to_string(),
+        })
+}
+
+fn sandbox_exec_tool_def() -> ToolDefinition {
+    ToolDefinition {
+        name: SANDBOX_EXEC_TOOL.to_string(),
+        description: "Run a shell command in this task's sandbox. Files written in the working directory persist between calls for the rest of the task.".to_string(),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "Shell code to run"
+                },
+                "env": {
+                    "type": "object",
+                    "description": "Extra environment variables for this call",
+                    "additionalProperties": { "type": "string" }
+                },
+                "images": {
+                    "type": "array",
+                    "description": "Glob patterns (e.g. \"*.png\") of image files the code writes that you want to see",
+                    "items": { "type": "string" }
+                }
+            },
+            "required": ["code"]
+        }),
+    }
+}
+
+fn run_cell_tool_def(kernel: &KernelConfig) -> ToolDefinition {
+    ToolDefinition {
+        name: RUN_CELL_TOOL.to_string(),
+        description: format!(
+            "Run a {} code cell in this task's interpreter. Variables, imports and loaded data persist between cells. The value of the last expression is returned; call display() to show more values.",
+            kernel.language
+        ),
+        parameters: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "string",
+                    "description": "Code to run"
+                }
+            },
+            "required": ["code"]
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reasoning::executor::DefaultActionExecutor;
+    use crate::sandbox::{ExecutionResult, SandboxRunner, SandboxSession, SessionConfig};
+    use crate::types::AgentId;
+    use tokio::sync::Mutex;
+
+    /// Session that appends each command to a shared "file".
+    struct LogSession(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl SandboxSession for LogSession {
+        async fn execute(
+            &self,
+            request: ExecutionRequest,
+        ) -> Result<ExecutionResult, anyhow::Error> {
+            let mut log = self.0.lock().await;
+            log.push(request.code);
+            Ok(ExecutionResult::success(log.join(","), 0))
+        }
+        async fn close(&self) -> Result<(), anyhow::Error> {
+            Ok(())
+        }
+    }
+
+    struct LogRunner;
+
+    #[async_trait]
+    impl SandboxRunner for LogRunner {
+        async fn execute(
+            &self,
+            _code: &str,
+            _env: HashMap<String, String>,
+        ) -> Result<ExecutionResult, anyhow::Error> {
+            unreachable!("sessions only")
+        }
+        async fn open_session(&self) -> Result<Box<dyn SandboxSession>, anyhow::Error> {
+            Ok(Box::new(LogSession(Mutex::new(Vec::new()))))
+        }
+    }
+
+    fn exec(call_id: &str, code: &str) -> ProposedAction {
+        ProposedAction::ToolCall {
+            call_id: call_id.into(),
+            name: SANDBOX_EXEC_TOOL.into(),
+            arguments: serde_json::json!({ "code": code }).to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn calls_in_one_run_share_the_bound_session() {
+        let sessions = Arc::new(SandboxSessionManager::new(
+            Arc::new(LogRunner),
+            SessionConfig::default(),
+        ));
+        let executor = SandboxSessionExecutor::new(
+            Arc::new(DefaultActionExecutor::default()),
+            sessions.clone(),
+        );
+        let breakers = CircuitBreakerRegistry::default();
+        let owner = AgentId::new();
+        let config = LoopConfig {
+            sandbox_session: Some(SessionBinding {
+                id: sessions.open(owner).await.unwrap(),
+                owner,
+            }),
+            ..Default::default()
+        };
+
+        executor
+            .execute_actions(&[exec("c1", "a")], &config, &breakers)
+            .await;
+        let obs = executor
+            .execute_actions(&[exec("c2", "b")], &config, &breakers)
+            .await;
+        assert_eq!(obs.len(), 1);
+        assert!(!obs[0].is_error);
+        assert_eq!(obs[0].call_id.as_deref(), Some("c2"));
+        let body: serde_json::Value = serde_json::from_str(&obs[0].content).unwrap();
+        assert_eq!(body["stdout"], "a,b");
+
+        // Without a bound session the call fails instead of running elsewhere.
+        let obs = executor
+            .execute_actions(&[exec("c3", "c")], &LoopConfig::default(), &breakers)
+            .await;
+        assert!(obs[0].is_error);
+        assert!(obs[0].content.contains("No sandbox session"));
+
+        // A binding that names another agent cannot use the session.
+        let foreign = LoopConfig {
+            sandbox_session: Some(SessionBinding {
+                owner: AgentId::new(),
+                ..config.sandbox_session.unwrap()
+            }),
+            ..Default::default()
+        };
+        let obs = executor
+            .execute_actions(&[exec("c4", "d")], &foreign, &breakers)
+            .await;
+        assert!(obs[0].is_error);
+        assert!(obs[0].content.contains("belongs to another agent"));
+
+        assert!(executor
+            .tool_definitions()
+            .iter()
+            .any(|d| d.name == SANDBOX_EXEC_TOOL));
+    }
+
+    #[test]
+    fn image_artifacts_map_to_rich_outputs() {
+        let plot = Artifact::new("out/plot.PNG", b"png".to_vec());
+        assert_eq!(
+            image_output(&plot),
+            Some(RichOutput::Image {
+                mime_type: "image/png".into(),
+                data: "cG5n".into(),
+            })
+        );
+        assert_eq!(image_output(&Artifact::new("data.csv", vec![])), None);
+        assert_eq!(image_output(&Artifact::new("README", vec![])), None);
+    }
+
+    #[test]
+    fn mime_bundles_map_to_rich_outputs() {
+        let bundle =
+            |pairs: serde_json::Value| -> MimeBundle { serde_json::from_value(pairs).unwrap() };
+
+        let table = bundle(serde_json::json!({
+            "application/vnd.dataresource+json": {
+                "schema": {"fields": [{"name": "a"}, {"name": "b"}]},
+                "data": [{"a": 1, "b": "x"}, {"a": 2}]
+            },
+            "text/html": "<table/>",
+            "text/plain": "frame"
+        }));
+        assert_eq!(
+            rich_output(&table),
+            Some(RichOutput::Table {
+                columns: vec!["a".into(), "b".into()],
+                rows: vec![
+                    vec![1.into(), "x".into()],
+                    vec![2.into(), serde_json::Value::Null]
+                ],
+            })
+        );
+
+        let plot = bundle(serde_json::json!({"image/png": "iVBO", "text/plain": "<Figure 1>"}));
+        assert!(matches!(
+            rich_output(&plot),
+            Some(RichOutput::Image { mime_type, .. }) if mime_type == "image/png"
+        ));
+
+        let plain = bundle(serde_json::json!({"text/plain": "42"}));
+        assert_eq!(rich_output(&plain), None);
+    }
+}
@@ -0,0 +1,1114 @@
+//! Token cost accounting and spend budgets
+//!
+//! Prices each inference step from a per-model [`PricingTable`] and records
+//! the cost against the agent that ran it and, when the run's
+//! `LoopConfig::spend_tags` name them, the cron job and API key that started
+//! it. [`SpendLimits`] cap spend per run, per UTC day and per UTC month; the
+//! reasoning loop checks them before every inference call and terminates
+//! with `TerminationReason::BudgetExceeded` once one is reached. With a
+//! `downgrade_model` configured, a run that nears a limit is first routed
+//! to that cheaper model.
+//!
+//! `SqliteSpendStore` is feature-gated behind `cron` (which includes
+//! `rusqlite`); `MemorySpendStore` is always available.
+
+use crate::reasoning::inference::Usage;
+use crate::types::AgentId;
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Errors raised by spend stores and spend configuration.
+#[derive(Debug, thiserror::Error)]
+pub enum SpendError {
+    #[error("Spend configuration error: {0}")]
+    ConfigError(String),
+    #[error("Spend store error: {0}")]
+    StoreFailed(String),
+}
+
+/// USD rates for one model, per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    /// Uncached prompt tokens.
+    pub input: f64,
+    /// Completion tokens.
+    pub output: f64,
+    /// Prompt tokens served from the provider's prompt cache. Defaults to
+    /// the `input` rate when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input: Option<f64>,
+}
+
+impl ModelPrice {
+    /// A price with a distinct cached-input rate.
+    pub const fn new(input: f64, output: f64, cached_input: f64) -> Self {
+        Self {
+            input,
+            output,
+            cached_input: Some(cached_input),
+        }
+    }
+
+    /// Cost in USD of one call's usage.
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let cached = usage.cached_prompt_tokens.min(usage.prompt_tokens);
+        let uncached = usage.prompt_tokens - cached;
+        (f64::from(uncached) * self.input
+            + f64::from(cached) * self.cached_input.unwrap_or(self.input)
+            + f64::from(usage.completion_tokens) * self.output)
+            / 1_000_000.0
+    }
+}
+
+/// Model name → price. Lookups match the longest configured prefix, so
+/// `claude-sonnet-4` prices every dated `claude-sonnet-4-*` snapshot, and
+/// ignore an OpenRouter-style `vendor/` prefix.
+#[derive(Debug, Clone)]
+pub struct PricingTable {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl Default for PricingTable {
+    /// List prices for the models the cloud providers default to. Override
+    /// or extend them from the `[spend.pricing]` config section.
+    fn default() -> Self {
+        Self::empty()
+            .with_price("claude-opus-4", ModelPrice::new(15.0, 75.0, 1.5))
+            .with_price("claude-opus-4-5", ModelPrice::new(5.0, 25.0, 0.5))
+            .with_price("claude-sonnet-4", ModelPrice::new(3.0, 15.0, 0.3))
+            .with_price("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0, 0.3))
+            .with_price("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0, 0.3))
+            .with_price("claude-haiku-4", ModelPrice::new(1.0, 5.0, 0.1))
+            .with_price("claude-3-5-haiku", ModelPrice::new(0.8, 4.0, 0.08))
+            .with_price("gpt-4o", ModelPrice::new(2.5, 10.0, 1.25))
+            .with_price("gpt-4o-mini", ModelPrice::new(0.15, 0.6, 0.075))
+            .with_price("gpt-4.1", ModelPrice::new(2.0, 8.0, 0.5))
+            .with_price("gpt-4.1-mini", ModelPrice::new(0.4, 1.6, 0.1))
+            .with_price("gpt-4.1-nano", ModelPrice::new(0.1, 0.4, 0.025))
+    }
+}
+
+impl PricingTable {
+    /// A table with no prices; every model costs nothing.
+    pub fn empty() -> Self {
+        Self {
+            prices: HashMap::new(),
+        }
+    }
+
+    /// Add or replace the price for a model name (or name prefix).
+    pub fn with_price(mut self, model: impl Into<String>, price: ModelPrice) -> Self {
+        self.prices.insert(model.into(), price);
+        self
+    }
+
+    /// Price for `model`: an exact entry, else the longest matching prefix.
+    pub fn price_for(&self, model: &str) -> Option<&ModelPrice> {
+        let model = model.rsplit('/').next().unwrap_or(model);
+        self.prices.get(model).or_else(|| {
+            self.prices
+                .iter()
+                .filter(|(name, _)| model.starts_with(name.as_str()))
+                .max_by_key(|(name, _)| name.len())
+                .map(|(_, price)| price)
+        })
+    }
+
+    /// Cost in USD of `usage` on `model`, or `None` when the model is unpriced.
+    pub fn cost(&self, model: &str, usage: &Usage) -> Option<f64> {
+        self.price_for(model).map(|price| price.cost(usage))
+    }
+}
+
+/// Spend caps in USD. Each unset limit is unenforced. Day and month limits
+/// apply separately to the agent, the cron job and the API key a run is
+/// attributed to; days and months are UTC.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpendLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_run_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_day_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_month_usd: Option<f64>,
+}
+
+/// `[spend]` section of `symbiont.toml`.
+///
+/// ```toml
+/// [spend]
+/// downgrade_model = "claude-haiku-4-5"
+/// downgrade_at = 0.8
+///
+/// [spend.limits]
+/// per_run_usd = 0.50
+/// per_day_usd = 10.0
+/// per_month_usd = 200.0
+///
+/// [spend.pricing."my-fine-tune"]
+/// input = 1.0
+/// output = 4.0
+/// cached_input = 0.1
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpendConfig {
+    /// Prices (USD per million tokens) added to, or overriding, the
+    /// built-in table.
+    #[serde(default)]
+    pub pricing: HashMap<String, ModelPrice>,
+    #[serde(default)]
+    pub limits: SpendLimits,
+    /// Model to route a run to once spend reaches `downgrade_at` of any
+    /// limit. Unset: runs keep their model until a limit terminates them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downgrade_model: Option<String>,
+    /// Fraction of a limit at which `downgrade_model` takes over.
+    #[serde(default = "default_downgrade_at")]
+    pub downgrade_at: f64,
+    /// Spend ledger database. Default: `SqliteSpendStore::default_path()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub db_path: Option<std::path::PathBuf>,
+}
+
+fn default_downgrade_at() -> f64 {
+    0.8
+}
+
+impl Default for SpendConfig {
+    fn default() -> Self {
+        Self {
+            pricing: HashMap::new(),
+            limits: SpendLimits::default(),
+            downgrade_model: None,
+            downgrade_at: default_downgrade_at(),
+            db_path: None,
+        }
+    }
+}
+
+/// Who a run's spend is billed to besides the agent itself. Set by the
+/// caller that starts the run; carried in `LoopConfig::spend_tags`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendTags {
+    /// Cron job that triggered the run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cron_job: Option<String>,
+    /// `key_id` of the API key the run was requested with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+/// `AgentConfig::metadata` key carrying the cron job a run is billed to.
+pub const CRON_JOB_TAG_KEY: &str = "spend_cron_job";
+/// `AgentConfig::metadata` key carrying the API key a run is billed to.
+pub const API_KEY_TAG_KEY: &str = "spend_api_key";
+
+impl SpendTags {
+    /// Tags for a run of the given cron job.
+    pub fn for_cron_job(job: impl Into<String>) -> Self {
+        Self {
+            cron_job: Some(job.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Tags for a run requested with the given API key.
+    pub fn for_api_key(key_id: impl Into<String>) -> Self {
+        Self {
+            api_key: Some(key_id.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Read the tags a scheduler stamped onto an agent run's metadata.
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Self {
+        Self {
+            cron_job: metadata.get(CRON_JOB_TAG_KEY).cloned(),
+            api_key: metadata.get(API_KEY_TAG_KEY).cloned(),
+        }
+    }
+
+    /// Stamp the tags onto an agent run's metadata, for whatever runs the
+    /// agent to read back with [`SpendTags::from_metadata`].
+    pub fn write_metadata(&self, metadata: &mut HashMap<String, String>) {
+        if let Some(job) = &self.cron_job {
+            metadata.insert(CRON_JOB_TAG_KEY.to_string(), job.clone());
+        }
+        if let Some(key) = &self.api_key {
+            metadata.insert(API_KEY_TAG_KEY.to_string(), key.clone());
+        }
+    }
+
+    /// Whether no tag is set.
+    pub fn is_empty(&self) -> bool {
+        self.cron_job.is_none() && self.api_key.is_none()
+    }
+
+    /// Every scope a run by `agent_id` with these tags is billed to.
+    pub fn scopes(&self, agent_id: AgentId) -> Vec<SpendScope> {
+        let mut scopes = vec![SpendScope::Agent(agent_id.to_string())];
+        scopes.extend(self.cron_job.clone().map(SpendScope::CronJob));
+        scopes.extend(self.api_key.clone().map(SpendScope::ApiKey));
+        scopes
+    }
+}
+
+/// A ledger account that spend accumulates against.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum SpendScope {
+    Agent(String),
+    CronJob(String),
+    ApiKey(String),
+}
+
+impl SpendScope {
+    /// Stable name of the scope kind, as stored in the ledger.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Agent(_) => "agent",
+            Self::CronJob(_) => "cron_job",
+            Self::ApiKey(_) => "api_key",
+        }
+    }
+
+    /// The scope's identifier.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Agent(id) | Self::CronJob(id) | Self::ApiKey(id) => id,
+        }
+    }
+
+    fn from_parts(kind: &str, id: String) -> Option<Self> {
+        match kind {
+            "agent" => Some(Self::Agent(id)),
+            "cron_job" => Some(Self::CronJob(id)),
+            "api_key" => Some(Self::ApiKey(id)),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SpendScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.kind(), self.id())
+    }
+}
+
+/// One priced inference step, billed to one scope.
+#[derive(Debug, Clone)]
+pub struct SpendRecord {
+    pub scope: SpendScope,
+    pub model: String,
+    pub usage: Usage,
+    pub cost_usd: f64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Accumulated spend of one scope over a period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScopeSpend {
+    #[serde(flatten)]
+    pub scope: SpendScope,
+    pub cost_usd: f64,
+    pub prompt_tokens: u64,
+    pub cached_prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl ScopeSpend {
+    fn empty(scope: SpendScope) -> Self {
+        Self {
+            scope,
+            cost_usd: 0.0,
+            prompt_tokens: 0,
+            cached_prompt_tokens: 0,
+            completion_tokens: 0,
+        }
+    }
+
+    fn add(&mut self, record: &SpendRecord) {
+        self.cost_usd += record.cost_usd;
+        self.prompt_tokens += u64::from(record.usage.prompt_tokens);
+        self.cached_prompt_tokens += u64::from(record.usage.cached_prompt_tokens);
+        self.completion_tokens += u64::from(record.usage.completion_tokens);
+    }
+}
+
+/// Trait for spend ledger backends.
+#[async_trait::async_trait]
+pub trait SpendStore: Send + Sync {
+    /// Append a priced step to the ledger.
+    async fn record(&self, record: &SpendRecord) -> Result<(), SpendError>;
+
+    /// Total USD billed to `scope` at or after `since`.
+    async fn spent_since(
+        &self,
+        scope: &SpendScope,
+        since: DateTime<Utc>,
+    ) -> Result<f64, SpendError>;
+
+    /// Per-scope totals for every scope billed at or after `since`, most
+    /// expensive first.
+    async fn totals_since(&self, since: DateTime<Utc>) -> Result<Vec<ScopeSpend>, SpendError>;
+}
+
+/// In-memory spend ledger for testing and lightweight use.
+#[derive(Default)]
+pub struct MemorySpendStore {
+    records: Mutex<Vec<SpendRecord>>,
+}
+
+impl MemorySpendStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl SpendStore for MemorySpendStore {
+    async fn record(&self, record: &SpendRecord) -> Result<(), SpendError> {
+        self.records.lock().await.push(record.clone());
+        Ok(())
+    }
+
+    async fn spent_since(
+        &self,
+        scope: &SpendScope,
+        since: DateTime<Utc>,
+    ) -> Result<f64, SpendError> {
+        let records = self.records.lock().await;
+        Ok(records
+            .iter()
+            .filter(|r| &r.scope == scope && r.recorded_at >= since)
+            .map(|r| r.cost_usd)
+            .sum())
+    }
+
+    async fn totals_since(&self, since: DateTime<Utc>) -> Result<Vec<ScopeSpend>, SpendError> {
+        let records = self.records.lock().await;
+        let mut totals: HashMap<&SpendScope, ScopeSpend> = HashMap::new();
+        for record in records.iter().filter(|r| r.recorded_at >= since) {
+            totals
+                .entry(&record.scope)
+                .or_insert_with(|| ScopeSpend::empty(record.scope.clone()))
+                .add(record);
+        }
+        let mut totals: Vec<ScopeSpend> = totals.into_values().collect();
+        totals.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+        Ok(totals)
+    }
+}
+
+/// SQLite-backed spend ledger. One row per scope per priced step,
+/// indexed by `(scope_kind, scope_id, recorded_at)` so budget checks are
+/// index range scans. The database runs in WAL mode so `symbi status` can
+/// read it while the runtime writes.
+#[cfg(feature = "cron")]
+pub struct SqliteSpendStore {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+#[cfg(feature = "cron")]
+impl SqliteSpendStore {
+    /// Open (or create) the spend database at the given path.
+    pub fn open(path: &std::path::Path) -> Result<Self, SpendError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| SpendError::StoreFailed(format!("create dir: {e}")))?;
+        }
+        let conn =
+            rusqlite::Connection::open(path).map_err(|e| SpendError::StoreFailed(e.to_string()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")
+            .map_err(|e| SpendError::StoreFailed(e.to_string()))?;
+
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Open an in-memory spend database (useful for tests).
+    pub fn open_in_memory() -> Result<Self, SpendError> {
+        let conn = rusqlite::Connection::open_in_memory()
+            .map_err(|e| SpendError::StoreFailed(e.to_string()))?;
+
+        Self::init_schema(&conn)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Default database path: `$XDG_DATA_HOME/symbi/spend.db`
+    pub fn default_path() -> std::path::PathBuf {
+        let base = dirs::data_dir().unwrap_or_else(|| std::path::PathBuf::from("."));
+        base.join("symbi").join("spend.db")
+    }
+
+    fn init_schema(conn: &rusqlite::Connection) -> Result<(), SpendError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS spend_ledger (
+                scope_kind            TEXT NOT NULL,
+                scope_id              TEXT NOT NULL,
+                model                 TEXT NOT NULL,
+                prompt_tokens         INTEGER NOT NULL,
+                cached_prompt_tokens  INTEGER NOT NULL,
+                completion_tokens     INTEGER NOT NULL,
+                cost_usd              REAL NOT NULL,
+                recorded_at_ms        INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_spend_ledger_scope
+                ON spend_ledger(scope_kind, scope_id, recorded_at_ms);
+            CREATE INDEX IF NOT EXISTS idx_spend_ledger_recorded
+                ON spend_ledger(recorded_at_ms);",
+        )
+        .map_err(|e| SpendError::StoreFailed(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "cron")]
+#[async_trait::async_trait]
+impl SpendStore for SqliteSpendStore {
+    async fn record(&self, record: &SpendRecord) -> Result<(), SpendError> {
+        let conn = self.conn.lock().await;
+        conn.execute(
+            "INSERT INTO spend_ledger (scope_kind, scope_id, model, prompt_tokens,
+                 cached_prompt_tokens, completion_tokens, cost_usd, recorded_at_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                record.scope.kind(),
+                record.scope.id(),
+                record.model,
+                record.usage.prompt_tokens,
+                record.usage.cached_prompt_tokens,
+                record.usage.completion_tokens,
+                record.cost_usd,
+                record.recorded_at.timestamp_millis(),
+            ],
+        )
+        .map_err(|e| SpendError::StoreFailed(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn spent_since(
+        &self,
+        scope: &SpendScope,
+        since: DateTime<Utc>,
+    ) -> Result<f64, SpendError> {
+        let conn = self.conn.lock().await;
+        conn.query_row(
+            "SELECT COALESCE(SUM(cost_usd), 0.0) FROM spend_ledger
+             WHERE scope_kind = ?1 AND scope_id = ?2 AND recorded_at_ms >= ?3",
+            rusqlite::params![scope.kind(), scope.id(), since.timestamp_millis()],
+            |row| row.get(0),
+        )
+        .map_err(|e| SpendError::StoreFailed(e.to_string()))
+    }
+
+    async fn totals_since(&self, since: DateTime<Utc>) -> Result<Vec<ScopeSpend>, SpendError> {
+        let conn = self.conn.lock().await;
+        let mut stmt = conn
+            .prepare(
+                "SELECT scope_kind, scope_id, SUM(cost_usd), SUM(prompt_tokens),
+                        SUM(cached_prompt_tokens), SUM(completion_tokens)
+                 FROM spend_ledger
+                 WHERE recorded_at_ms >= ?1
+                 GROUP BY scope_kind, scope_id
+                 ORDER BY SUM(cost_usd) DESC",
+            )
+            .map_err(|e| SpendError::StoreFailed(e.to_string()))?;
+        let rows = stmt
+            .query_map(rusqlite::params![since.timestamp_millis()], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                ))
+            })
+            .map_err(|e| SpendError::StoreFailed(e.to_string()))?;
+
+        let mut totals = Vec::new();
+        for row in rows {
+            let (kind, id, cost_usd, prompt, cached, completion) =
+                row.map_err(|e| SpendError::StoreFailed(e.to_string()))?;
+            let Some(scope) = SpendScope::from_parts(&kind, id) else {
+                continue;
+            };
+            totals.push(ScopeSpend {
+                scope,
+                cost_usd,
+                prompt_tokens: prompt as u64,
+                cached_prompt_tokens: cached as u64,
+                completion_tokens: completion as u64,
+            });
+        }
+        Ok(totals)
+    }
+}
+
+/// The window a spend limit covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Run,
+    Day,
+    Month,
+}
+
+impl std::fmt::Display for BudgetPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Run => "run",
+            Self::Day => "day",
+            Self::Month => "month",
+        })
+    }
+}
+
+/// A limit that has been reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetBreach {
+    /// `run`, or the ledger scope (`agent:<id>`, `cron_job:<id>`,
+    /// `api_key:<id>`) whose limit was reached.
+    pub scope: String,
+    pub period: BudgetPeriod,
+    pub limit_usd: f64,
+    pub spent_usd: f64,
+}
+
+/// Outcome of a budget check before an inference call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetStatus {
+    WithinBudget,
+    /// Spend is near a limit; route the call to this cheaper model.
+    Downgrade {
+        model: String,
+    },
+    Exceeded(BudgetBreach),
+}
+
+/// Spend totals for the current UTC day or month.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeriodSpend {
+    pub since: DateTime<Utc>,
+    /// Total across all agents. Cron job and API key spend is a subset of
+    /// this, so it is not added again.
+    pub total_usd: f64,
+    pub scopes: Vec<ScopeSpend>,
+}
+
+/// Spend snapshot served under `spend` in `/api/v1/metrics`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendSummary {
+    pub day: PeriodSpend,
+    pub month: PeriodSpend,
+    pub limits: SpendLimits,
+}
+
+/// Prices inference steps, writes them to a [`SpendStore`] and enforces
+/// [`SpendLimits`]. Shared by every reasoning loop in the process.
+pub struct SpendTracker {
+    pricing: PricingTable,
+    limits: SpendLimits,
+    downgrade_model: Option<String>,
+    downgrade_at: f64,
+    store: Arc<dyn SpendStore>,
+}
+
+impl SpendTracker {
+    /// A tracker over `store` with the built-in prices and no limits.
+    pub fn new(store: Arc<dyn SpendStore>) -> Self {
+        Self {
+            pricing: PricingTable::default(),
+            limits: SpendLimits::default(),
+            downgrade_model: None,
+            downgrade_at: default_downgrade_at(),
+            store,
+        }
+    }
+
+    /// Build a tracker from the `[spend]` config section. The ledger is the
+    /// SQLite database at `db_path` when built with the `cron` feature, and
+    /// in-memory (lost on restart) otherwise.
+    pub fn from_config(config: &SpendConfig) -> Result<Self, SpendError> {
+        if !(config.downgrade_at > 0.0 && config.downgrade_at <= 1.0) {
+            return Err(SpendError::ConfigError(format!(
+                "downgrade_at must be in (0, 1], got {}",
+                config.downgrade_at
+            )));
+        }
+        let limits = [
+            config.limits.per_run_usd,
+            config.limits.per_day_usd,
+            config.limits.per_month_usd,
+        ];
+        if limits.iter().flatten().any(|limit| *limit < 0.0) {
+            return Err(SpendError::ConfigError(
+                "spend limits must not be negative".into(),
+            ));
+        }
+        if let Some((model, _)) = config.pricing.iter().find(|(_, p)| {
+            p.input < 0.0 || p.output < 0.0 || p.cached_input.is_some_and(|c| c < 0.0)
+        }) {
+            return Err(SpendError::ConfigError(format!(
+                "price for model '{model}' must not be negative"
+            )));
+        }
+
+        #[cfg(feature = "cron")]
+        let store: Arc<dyn SpendStore> = {
+            let path = config
+                .db_path
+                .clone()
+                .unwrap_or_else(SqliteSpendStore::default_path);
+            Arc::new(SqliteSpendStore::open(&path)?)
+        };
+        #[cfg(not(feature = "cron"))]
+        let store: Arc<dyn SpendStore> = {
+            if config.db_path.is_some() {
+                tracing::warn!(
+                    "spend.db_path is ignored: built without the 'cron' feature, \
+                     so spend is kept in memory only"
+                );
+            }
+            Arc::new(MemorySpendStore::new())
+        };
+
+        let pricing = config
+            .pricing
+            .iter()
+            .fold(PricingTable::default(), |table, (model, price)| {
+                table.with_price(model.clone(), *price)
+            });
+        let mut tracker = Self::new(store)
+            .with_pricing(pricing)
+            .with_limits(config.limits.clone());
+        if let Some(model) = &config.downgrade_model {
+            tracker = tracker.with_downgrade(model.clone(), config.downgrade_at);
+        }
+        Ok(tracker)
+    }
+
+    /// Replace the pricing table.
+    pub fn with_pricing(mut self, pricing: PricingTable) -> Self {
+        self.pricing = pricing;
+        self
+    }
+
+    /// Set the spend limits.
+    pub fn with_limits(mut self, limits: SpendLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Route runs to `model` once spend reaches `at` (a fraction) of any limit.
+    pub fn with_downgrade(mut self, model: impl Into<String>, at: f64) -> Self {
+        self.downgrade_model = Some(model.into());
+        self.downgrade_at = at;
+        self
+    }
+
+    pub fn pricing(&self) -> &PricingTable {
+        &self.pricing
+    }
+
+    pub fn limits(&self) -> &SpendLimits {
+        &self.limits
+    }
+
+    /// Price one inference step and bill it to every scope of the run.
+    /// Returns the step's cost. Ledger write failures are logged, not
+    /// raised: losing a ledger row must not fail the agent's turn.
+    pub async fn record(
+        &self,
+        agent_id: AgentId,
+        tags: &SpendTags,
+        model: &str,
+        usage: &Usage,
+    ) -> f64 {
+        let cost_usd = self.pricing.cost(model, usage).unwrap_or_else(|| {
+            tracing::warn!(
+                model,
+                "No price configured for model; its usage is recorded at $0"
+            );
+            0.0
+        });
+        let recorded_at = Utc::now();
+        for scope in tags.scopes(agent_id) {
+            let record = SpendRecord {
+                scope,
+                model: model.to_string(),
+                usage: usage.clone(),
+                cost_usd,
+                recorded_at,
+            };
+            if let Err(e) = self.store.record(&record).await {
+                tracing::warn!("Failed to record spend for {}: {}", record.scope, e);
+            }
+        }
+        cost_usd
+    }
+
+    /// Check a run that has spent `run_spent_usd` so far against the limits.
+    /// A scope whose ledger cannot be read is skipped with a warning, so a
+    /// broken ledger does not halt every agent.
+    pub async fn check(
+        &self,
+        agent_id: AgentId,
+        tags: &SpendTags,
+        run_spent_usd: f64,
+    ) -> BudgetStatus {
+        let mut peak = 0.0_f64;
+        if let Some(limit) = self.limits.per_run_usd {
+            if run_spent_usd >= limit {
+                return BudgetStatus::Exceeded(BudgetBreach {
+                    scope: "run".into(),
+                    period: BudgetPeriod::Run,
+                    limit_usd: limit,
+                    spent_usd: run_spent_usd,
+                });
+            }
+            peak = peak.max(run_spent_usd / limit);
+        }
+
+        let now = Utc::now();
+        let windows = [
+            (BudgetPeriod::Day, self.limits.per_day_usd, day_start(now)),
+            (
+                BudgetPeriod::Month,
+                self.limits.per_month_usd,
+                month_start(now),
+            ),
+        ];
+        for scope in tags.scopes(agent_id) {
+            for (period, limit, since) in windows {
+                let Some(limit) = limit else { continue };
+                let spent = match self.store.spent_since(&scope, since).await {
+                    Ok(spent) => spent,
+                    Err(e) => {
+                        tracing::warn!("Spend check skipped for {}: {}", scope, e);
+                        continue;
+                    }
+                };
+                if spent >= limit {
+                    return BudgetStatus::Exceeded(BudgetBreach {
+                        scope: scope.to_string(),
+                        period,
+                        limit_usd: limit,
+                        spent_usd: spent,
+                    });
+                }
+                peak = peak.max(spent / limit);
+            }
+        }
+
+        match &self.downgrade_model {
+            Some(model) if peak >= self.downgrade_at => BudgetStatus::Downgrade {
+                model: model.clone(),
+            },
+            _ => BudgetStatus::WithinBudget,
+        }
+    }
+
+    /// Spend for the current UTC day and month.
+    pub async fn summary(&self) -> Result<SpendSummary, SpendError> {
+        let now = Utc::now();
+        Ok(SpendSummary {
+            day: period_spend(self.store.as_ref(), day_start(now)).await?,
+            month: period_spend(self.store.as_ref(), month_start(now)).await?,
+            limits: self.limits.clone(),
+        })
+    }
+}
+
+/// Totals from `store` since `since`, as reported by `SpendTracker::summary`.
+pub async fn period_spend(
+    store: &dyn SpendStore,
+    since: DateTime<Utc>,
+) -> Result<PeriodSpend, SpendError> {
+    let scopes = store.totals_since(since).await?;
+    let total_usd = scopes
+        .iter()
+        .filter(|s| matches!(s.scope, SpendScope::Agent(_)))
+        .map(|s| s.cost_usd)
+        .sum();
+    Ok(PeriodSpend {
+        since,
+        total_usd,
+        scopes,
+    })
+}
+
+/// Midnight UTC at the start of `now`'s day.
+pub fn day_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&now.date_naive().and_hms_opt(0, 0, 0).unwrap_or_default())
+}
+
+/// Midnight UTC on the first of `now`'s month.
+pub fn month_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    day_start(now.with_day(1).unwrap_or(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(prompt: u32, cached: u32, completion: u32) -> Usage {
+        Usage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+            cached_prompt_tokens: cached,
+        }
+    }
+
+    fn tracker(limits: SpendLimits) -> SpendTracker {
+        SpendTracker::new(Arc::new(MemorySpendStore::new()))
+            .with_pricing(PricingTable::empty().with_price("m", ModelPrice::new(1.0, 2.0, 0.5)))
+            .with_limits(limits)
+    }
+
+    #[test]
+    fn prices_cached_input_separately() {
+        let price = ModelPrice::new(3.0, 15.0, 0.3);
+        let cost = price.cost(&usage(1_000_000, 400_000, 100_000));
+        // 600k uncached * $3 + 400k cached * $0.30 + 100k output * $15
+        assert!((cost - (1.8 + 0.12 + 1.5)).abs() < 1e-9);
+
+        let uncached_rate = ModelPrice {
+            input: 2.0,
+            output: 0.0,
+            cached_input: None,
+        };
+        assert!((uncached_rate.cost(&usage(1_000_000, 500_000, 0)) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pricing_matches_longest_prefix_and_strips_vendor() {
+        let table = PricingTable::default();
+        assert_eq!(
+            table.price_for("claude-sonnet-4-20250514"),
+            Some(&ModelPrice::new(3.0, 15.0, 0.3))
+        );
+        assert_eq!(
+            table.price_for("openai/gpt-4o-mini"),
+            Some(&ModelPrice::new(0.15, 0.6, 0.075))
+        );
+        assert_eq!(
+            table.price_for("claude-opus-4-5-20251101"),
+            Some(&ModelPrice::new(5.0, 25.0, 0.5))
+        );
+        assert!(table.price_for("llama-3").is_none());
+    }
+
+    #[test]
+    fn spend_config_parses_from_toml() {
+        let config: SpendConfig = toml::from_str(
+            r#"
+            downgrade_model = "cheap"
+
+            [limits]
+            per_day_usd = 5.0
+
+            [pricing.custom]
+            input = 1.0
+            output = 4.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.downgrade_at, 0.8);
+        assert_eq!(config.limits.per_day_usd, Some(5.0));
+        assert_eq!(config.limits.per_run_usd, None);
+        assert_eq!(config.pricing["custom"].cached_input, None);
+
+        let bad = SpendConfig {
+            downgrade_at: 1.5,
+            ..Default::default()
+        };
+        assert!(matches!(
+            SpendTracker::from_config(&bad),
+            Err(SpendError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn records_against_every_scope() {
+        let tracker = tracker(SpendLimits::default());
+        let agent = AgentId::new();
+        let tags = SpendTags {
+            cron_job: Some("nightly".into()),
+            api_key: Some("ci".into()),
+        };
+        let cost = tracker
+            .record(agent, &tags, "m", &usage(1_000_000, 0, 500_000))
+            .await;
+        assert!((cost - 2.0).abs() < 1e-9);
+        tracker
+            .record(agent, &SpendTags::default(), "m", &usage(1_000_000, 0, 0))
+            .await;
+
+        let summary = tracker.summary().await.unwrap();
+        assert!((summary.day.total_usd - 3.0).abs() < 1e-9);
+        let cost_of = |scope: SpendScope| {
+            summary
+                .day
+                .scopes
+                .iter()
+                .find(|s| s.scope == scope)
+                .map(|s| s.cost_usd)
+        };
+        assert_eq!(cost_of(SpendScope::Agent(agent.to_string())), Some(3.0));
+        assert_eq!(cost_of(SpendScope::CronJob("nightly".into())), Some(2.0));
+        assert_eq!(cost_of(SpendScope::ApiKey("ci".into())), Some(2.0));
+    }
+
+    #[tokio::test]
+    async fn cron_job_budget_blocks_further_runs_of_the_job() {
+        let tracker = tracker(SpendLimits {
+            per_day_usd: Some(1.0),
+            ..Default::default()
+        });
+        let mut metadata = HashMap::new();
+        SpendTags::for_cron_job("nightly").write_metadata(&mut metadata);
+        let tags = SpendTags::from_metadata(&metadata);
+        tracker
+            .record(AgentId::new(), &tags, "m", &usage(1_000_000, 0, 0))
+            .await;
+
+        // A different agent run by the same job is over the job's budget.
+        match tracker.check(AgentId::new(), &tags, 0.0).await {
+            BudgetStatus::Exceeded(breach) => {
+                assert_eq!(breach.scope, "cron_job:nightly");
+                assert_eq!(breach.period, BudgetPeriod::Day);
+            }
+            other => panic!("expected a cron job breach, got {other:?}"),
+        }
+        assert_eq!(
+            tracker
+                .check(AgentId::new(), &SpendTags::for_cron_job("hourly"), 0.0)
+                .await,
+            BudgetStatus::WithinBudget
+        );
+    }
+
+    #[tokio::test]
+    async fn check_enforces_run_and_daily_limits() {
+        let tracker = tracker(SpendLimits {
+            per_run_usd: Some(1.0),
+            per_day_usd: Some(3.0),
+            per_month_usd: None,
+        });
+        let agent = AgentId::new();
+        let tags = SpendTags {
+            api_key: Some("ci".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            tracker.check(agent, &tags, 0.5).await,
+            BudgetStatus::WithinBudget
+        );
+        match tracker.check(agent, &tags, 1.0).await {
+            BudgetStatus::Exceeded(breach) => assert_eq!(breach.period, BudgetPeriod::Run),
+            other => panic!("expected a run breach, got {other:?}"),
+        }
+
+        // Another agent spending on the same key exhausts the key's day.
+        tracker
+            .record(AgentId::new(), &tags, "m", &usage(3_000_000, 0, 0))
+            .await;
+        match tracker.check(agent, &tags, 0.0).await {
+            BudgetStatus::Exceeded(breach) => {
+                assert_eq!(breach.scope, "api_key:ci");
+                assert_eq!(breach.period, BudgetPeriod::Day);
+                assert!((breach.spent_usd - 3.0).abs() < 1e-9);
+            }
+            other => panic!("expected a daily breach, got {other:?}"),
+        }
+        assert_eq!(
+            tracker.check(agent, &SpendTags::default(), 0.0).await,
+            BudgetStatus::WithinBudget
+        );
+    }
+
+    #[tokio::test]
+    async fn check_downgrades_near_a_limit() {
+        let tracker = tracker(SpendLimits {
+            per_run_usd: Some(1.0),
+            ..Default::default()
+        })
+        .with_downgrade("cheap", 0.8);
+        let agent = AgentId::new();
+        assert_eq!(
+            tracker.check(agent, &SpendTags::default(), 0.5).await,
+            BudgetStatus::WithinBudget
+        );
+        assert_eq!(
+            tracker.check(agent, &SpendTags::default(), 0.85).await,
+            BudgetStatus::Downgrade {
+                model: "cheap".into()
+            }
+        );
+    }
+
+    #[test]
+    fn period_starts_are_utc_midnights() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 17, 15, 4, 5).unwrap();
+        assert_eq!(
+            day_start(now),
+            Utc.with_ymd_and_hms(2026, 3, 17, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            month_start(now),
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[cfg(feature = "cron")]
+    #[tokio::test]
+    async fn sqlite_store_sums_by_scope_and_window() {
+        let store = SqliteSpendStore::open_in_memory().unwrap();
+        let agent = SpendScope::Agent("a".into());
+        let record = |scope: SpendScope, cost_usd: f64, recorded_at: DateTime<Utc>| SpendRecord {
+            scope,
+            model: "m".into(),
+            usage: usage(100, 40, 10),
+            cost_usd,
+            recorded_at,
+        };
+        let now = Utc::now();
+        let yesterday = now - chrono::Duration::days(1);
+        store
+            .record(&record(agent.clone(), 1.0, now))
+            .await
+            .unwrap();
+        store
+            .record(&record(agent.clone(), 2.0, yesterday))
+            .await
+            .unwrap();
+        store
+            .record(&record(SpendScope::CronJob("j".into()), 0.5, now))
+            .await
+            .unwrap();
+
+        let today = day_start(now);
+        assert!((store.spent_since(&agent, today).await.unwrap() - 1.0).abs() < 1e-9);
+        assert!((store.spent_since(&agent, yesterday).await.unwrap() - 3.0).abs() < 1e-9);
+
+        let totals = store.totals_since(today).await.unwrap();
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].scope, agent);
+        assert_eq!(totals[0].prompt_tokens, 100);
+        assert_eq!(totals[0].cached_prompt_tokens, 40);
+        assert_eq!(totals[1].scope, SpendScope::CronJob("j".into()));
+    }
+}
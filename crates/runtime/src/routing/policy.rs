@@ -274,9 +274,9 @@ impl PolicyEvaluator {
 
     /// Parse sandbox tier string into SandboxTier enum.
     ///
-    /// All four execution-tier values (`docker`, `gvisor`, `firecracker`,
-    /// `e2b`) ship with OSS runner implementations and are valid policy
-    /// targets. `none` is intentionally rejected here — host-only execution
+    /// All execution-tier values (`docker`, `gvisor`, `firecracker`, `e2b`,
+    /// `namespace`, `wasm`) ship with OSS runner implementations and are valid
+    /// policy targets. `none` is intentionally rejected here — host-only execution
     /// must come from explicit `SandboxTier::None` configuration that passes
     /// the production guard, not from an action extension.
     fn parse_sandbox_tier(&self, sandbox_str: &str) -> Result<SandboxTier, RoutingError> {
@@ -285,10 +285,13 @@ impl PolicyEvaluator {
             "gvisor" => Ok(SandboxTier::GVisor),
             "firecracker" => Ok(SandboxTier::Firecracker),
             "e2b" => Ok(SandboxTier::E2B),
+            "namespace" => Ok(SandboxTier::Namespace),
+            "wasm" | "wasi" => Ok(SandboxTier::Wasm),
             _ => Err(RoutingError::ConfigurationError {
                 key: "action_extension.sandbox".to_string(),
                 reason: format!(
-                    "Invalid sandbox tier: {}. Valid options are: docker, gvisor, firecracker, e2b",
+                    "Invalid sandbox tier: {}. Valid options are: docker, gvisor, firecracker, \
+                     e2b, namespace, wasm",
                     sandbox_str
                 ),
             }),
@@ -0,0 +1,557 @@
+//! File staging and artifact collection for sandbox executions
+//!
+//! An [`ExecutionRequest`] extends the plain `code` + `env` pair accepted by
+//! [`SandboxRunner::execute`](super::SandboxRunner::execute) with input files
+//! to place in the sandbox working directory before the code runs, and an
+//! [`ArtifactSpec`] naming the files to bring back afterwards. Collected files
+//! come back as [`Artifact`]s on the `ExecutionResult`, each with its size and
+//! SHA-256 digest so they can be chained into an evidence record.
+//!
+//! Paths on both sides are relative to the sandbox working directory. Staged
+//! paths may not be absolute or climb out with `..`. Both directions resolve
+//! every path beneath a handle on the working directory (`openat` with
+//! `O_NOFOLLOW` on the final component), so a workload that plants or swaps
+//! in a symlink — even while a session is live — cannot make the host read
+//! or write files outside its own staging directory.
+
+use cap_fs_ext::{FollowSymlinks, OpenOptionsFollowExt};
+use cap_std::fs::{Dir, OpenOptions};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Default per-file cap for collected artifacts (10 MiB).
+const DEFAULT_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Default cap on the combined size of collected artifacts (50 MiB).
+const DEFAULT_MAX_TOTAL_BYTES: u64 = 50 * 1024 * 1024;
+/// Default cap on the number of collected artifacts.
+const DEFAULT_MAX_FILES: usize = 100;
+
+/// A code execution with files staged in and artifacts collected out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionRequest {
+    /// The code to execute in the sandbox
+    pub code: String,
+    /// Environment variables to set in the sandbox
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Files written into the working directory before execution
+    #[serde(default)]
+    pub files: Vec<StagedFile>,
+    /// Files collected from the working directory after execution
+    #[serde(default)]
+    pub artifacts: Option<ArtifactSpec>,
+}
+
+impl ExecutionRequest {
+    /// Create a request that runs `code` with no env, files or artifacts
+    pub fn new(code: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the environment variables
+    pub fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Stage a file into the working directory
+    pub fn with_file(mut self, file: StagedFile) -> Self {
+        self.files.push(file);
+        self
+    }
+
+    /// Collect artifacts matching `spec` after execution
+    pub fn collect(mut self, spec: ArtifactSpec) -> Self {
+        self.artifacts = Some(spec);
+        self
+    }
+
+    /// Whether the request stages files or collects artifacts
+    pub fn uses_files(&self) -> bool {
+        !self.files.is_empty() || self.artifacts.is_some()
+    }
+
+    /// Check every staged path before anything touches the filesystem.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        for file in &self.files {
+            relative_path(&file.path)?;
+        }
+        if let Some(spec) = &self.artifacts {
+            spec.compile()?;
+        }
+        Ok(())
+    }
+}
+
+/// A file placed in the sandbox working directory before execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StagedFile {
+    /// Destination path, relative to the working directory
+    pub path: String,
+    /// Where the file contents come from
+    pub source: FileSource,
+}
+
+impl StagedFile {
+    /// Stage literal bytes
+    pub fn bytes(path: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            path: path.into(),
+            source: FileSource::Bytes(data.into()),
+        }
+    }
+
+    /// Stage a copy of a file on the host
+    pub fn host_path(path: impl Into<String>, host: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            source: FileSource::HostPath(host.into()),
+        }
+    }
+
+    /// Read the file contents from their source.
+    pub async fn read(&self) -> Result<Vec<u8>, anyhow::Error> {
+        match &self.source {
+            FileSource::Bytes(data) => Ok(data.clone()),
+            FileSource::HostPath(host) => {
+                let meta = tokio::fs::metadata(host)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("cannot stage {}: {}", host.display(), e))?;
+                if !meta.is_file() {
+                    anyhow::bail!("cannot stage {}: not a regular file", host.display());
+                }
+                Ok(tokio::fs::read(host).await?)
+            }
+        }
+    }
+}
+
+/// Source of a staged file's contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileSource {
+    /// Literal contents (base64 in serialized form)
+    Bytes(#[serde(with = "base64_bytes")] Vec<u8>),
+    /// A file on the host, copied in at execution time
+    HostPath(PathBuf),
+}
+
+/// Which files to collect after execution, and how much of them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactSpec {
+    /// Glob patterns relative to the working directory (`*.csv`, `out/**/*.png`)
+    pub patterns: Vec<String>,
+    /// Files larger than this are skipped
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Collection stops once the collected files would exceed this total
+    #[serde(default = "default_max_total_bytes")]
+    pub max_total_bytes: u64,
+    /// Collection stops after this many files
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+fn default_max_file_bytes() -> u64 {
+    DEFAULT_MAX_FILE_BYTES
+}
+fn default_max_total_bytes() -> u64 {
+    DEFAULT_MAX_TOTAL_BYTES
+}
+fn default_max_files() -> usize {
+    DEFAULT_MAX_FILES
+}
+
+impl ArtifactSpec {
+    /// Collect files matching any of `patterns` with the default caps
+    pub fn new<S: Into<String>>(patterns: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_total_bytes: DEFAULT_MAX_TOTAL_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+        }
+    }
+
+    /// Set the per-file size cap
+    pub fn with_max_file_bytes(mut self, bytes: u64) -> Self {
+        self.max_file_bytes = bytes;
+        self
+    }
+
+    /// Set the combined size cap
+    pub fn with_max_total_bytes(mut self, bytes: u64) -> Self {
+        self.max_total_bytes = bytes;
+        self
+    }
+
+    /// Set the file count cap
+    pub fn with_max_files(mut self, count: usize) -> Self {
+        self.max_files = count;
+        self
+    }
+
+    fn compile(&self) -> Result<Vec<glob::Pattern>, anyhow::Error> {
+        self.patterns
+            .iter()
+            .map(|p| {
+                relative_path(p)?;
+                glob::Pattern::new(p)
+                    .map_err(|e| anyhow::anyhow!("invalid artifact pattern {:?}: {}", p, e))
+            })
+            .collect()
+    }
+}
+
+/// A file collected from the sandbox after execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// Path relative to the working directory, `/`-separated
+    pub path: String,
+    /// Size in bytes
+    pub size_bytes: u64,
+    /// Hex-encoded SHA-256 of the contents
+    pub sha256: String,
+    /// File contents (base64 in serialized form)
+    #[serde(with = "base64_bytes")]
+    pub content: Vec<u8>,
+}
+
+impl Artifact {
+    /// Build an artifact, hashing its contents
+    pub fn new(path: impl Into<String>, content: Vec<u8>) -> Self {
+        Self {
+            path: path.into(),
+            size_bytes: content.len() as u64,
+            sha256: hex::encode(Sha256::digest(&content)),
+            content,
+        }
+    }
+}
+
+/// Applies an [`ArtifactSpec`]'s patterns and caps to candidate files.
+pub(crate) struct ArtifactCollector {
+    patterns: Vec<glob::Pattern>,
+    spec: ArtifactSpec,
+    artifacts: Vec<Artifact>,
+    total_bytes: u64,
+    truncated: bool,
+}
+
+impl ArtifactCollector {
+    pub(crate) fn new(spec: &ArtifactSpec) -> Result<Self, anyhow::Error> {
+        Ok(Self {
+            patterns: spec.compile()?,
+            spec: spec.clone(),
+            artifacts: Vec::new(),
+            total_bytes: 0,
+            truncated: false,
+        })
+    }
+
+    /// Whether a file at `path` of `size` bytes should be read and collected.
+    /// Files that match but exceed a cap mark the collection truncated.
+    pub(crate) fn admits(&mut self, path: &str, size: u64) -> bool {
+        let options = glob::MatchOptions {
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+            case_sensitive: true,
+        };
+        if !self.patterns.iter().any(|p| p.matches_with(path, options)) {
+            return false;
+        }
+        if size > self.spec.max_file_bytes
+            || self.artifacts.len() >= self.spec.max_files
+            || self.total_bytes + size > self.spec.max_total_bytes
+        {
+            tracing::warn!("Artifact {} ({} bytes) exceeds collection caps", path, size);
+            self.truncated = true;
+            return false;
+        }
+        true
+    }
+
+    /// Record a file that [`admits`](Self::admits) accepted. Contents that
+    /// grew past the per-file cap since the size check are dropped.
+    pub(crate) fn push(&mut self, path: &str, content: Vec<u8>) {
+        let size = content.len() as u64;
+        if size > self.spec.max_file_bytes || self.total_bytes + size > self.spec.max_total_bytes {
+            self.truncated = true;
+            return;
+        }
+        self.total_bytes += size;
+        self.artifacts.push(Artifact::new(path, content));
+    }
+
+    /// Collected artifacts and whether any cap was hit.
+    pub(crate) fn finish(self) -> (Vec<Artifact>, bool) {
+        (self.artifacts, self.truncated)
+    }
+
+    /// Walk `root` and collect matching regular files. Symlinks are never
+    /// followed: each file is opened without following its last component
+    /// and checked through the open handle, so a file swapped for a symlink
+    /// after the directory listing is refused rather than read. Entries are
+    /// visited in sorted order so caps drop the same files on every run.
+    pub(crate) async fn collect_dir(
+        self,
+        root: &Path,
+    ) -> Result<(Vec<Artifact>, bool), anyhow::Error> {
+        let root = root.to_path_buf();
+        tokio::task::spawn_blocking(move || self.collect_dir_blocking(&root)).await?
+    }
+
+    /// Blocking form of [`collect_dir`](Self::collect_dir), for callers that
+    /// run outside an async context.
+    pub(crate) fn collect_dir_blocking(
+        mut self,
+        root: &Path,
+    ) -> Result<(Vec<Artifact>, bool), anyhow::Error> {
+        let workspace = Dir::open_ambient_dir(root, cap_std::ambient_authority())?;
+        let mut no_follow = OpenOptions::new();
+        no_follow.read(true).follow(FollowSymlinks::No);
+
+        let mut pending = vec![PathBuf::new()];
+        while let Some(rel_dir) = pending.pop() {
+            let listing = if rel_dir.as_os_str().is_empty() {
+                workspace.entries()?
+            } else {
+                workspace.read_dir(&rel_dir)?
+            };
+            let mut entries = listing.collect::<Result<Vec<_>, _>>()?;
+            entries.sort_by_key(|e| e.file_name());
+            // Subdirectories are pushed in reverse so they pop in order.
+            let mut subdirs = Vec::new();
+            for entry in entries {
+                let rel = rel_dir.join(entry.file_name());
+                let file_type = entry.file_type()?;
+                if file_type.is_dir() {
+                    subdirs.push(rel);
+                } else if file_type.is_file() {
+                    let Some(path) = rel.to_str().map(|p| p.replace('\\', "/")) else {
+                        continue;
+                    };
+                    let Ok(mut file) = workspace.open_with(&rel, &no_follow) else {
+                        tracing::warn!("Artifact {} changed while collecting; skipped", path);
+                        continue;
+                    };
+                    let metadata = file.metadata()?;
+                    if metadata.is_file() && self.admits(&path, metadata.len()) {
+                        let mut content = Vec::new();
+                        (&mut file)
+                            .take(self.spec.max_file_bytes + 1)
+                            .read_to_end(&mut content)?;
+                        self.push(&path, content);
+                    }
+                }
+            }
+            pending.extend(subdirs.into_iter().rev());
+        }
+        Ok(self.finish())
+    }
+}
+
+/// Write staged files under `root`, creating parent directories.
+///
+/// `root` may be the live workspace of a session whose container has
+/// planted symlinks in it. Paths are resolved beneath a handle on `root`,
+/// so a symlinked directory cannot lead outside it, and the file itself is
+/// opened without following a symlink.
+pub(crate) async fn stage_files(root: &Path, files: &[StagedFile]) -> Result<(), anyhow::Error> {
+    let mut staged = Vec::with_capacity(files.len());
+    for file in files {
+        staged.push((relative_path(&file.path)?.to_path_buf(), file.read().await?));
+    }
+    let root = root.to_path_buf();
+    tokio::task::spawn_blocking(move || -> Result<(), anyhow::Error> {
+        let workspace = Dir::open_ambient_dir(&root, cap_std::ambient_authority())?;
+        let mut create = OpenOptions::new();
+        create
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .follow(FollowSymlinks::No);
+        for (rel, content) in staged {
+            if let Some(parent) = rel.parent().filter(|p| !p.as_os_str().is_empty()) {
+                workspace
+                    .create_dir_all(parent)
+                    .map_err(|e| anyhow::anyhow!("cannot stage {}: {}", rel.display(), e))?;
+            }
+            let mut dest = workspace
+                .open_with(&rel, &create)
+                .map_err(|e| anyhow::anyhow!("cannot stage {}: {}", rel.display(), e))?;
+            dest.write_all(&content)?;
+        }
+        Ok(())
+    })
+    .await?
+}
+
+/// Reject absolute paths and `..` segments so a path stays inside the
+/// directory it is joined onto.
+fn relative_path(path: &str) -> Result<&Path, anyhow::Error> {
+    let p = Path::new(path);
+    if path.is_empty()
+        || !p
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        anyhow::bail!(
+            "sandbox file path {:?} must be relative without '..' segments",
+            path
+        );
+    }
+    Ok(p)
+}
+
+mod base64_bytes {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(d)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staged_paths_must_stay_relative() {
+        for bad in ["", "/etc/passwd", "../escape", "a/../../b"] {
+            let req = ExecutionRequest::new("true").with_file(StagedFile::bytes(bad, "x"));
+            assert!(req.validate().is_err(), "{:?} should be rejected", bad);
+        }
+        let req = ExecutionRequest::new("true")
+            .with_file(StagedFile::bytes("data/input.csv", "x"))
+            .collect(ArtifactSpec::new(["out/*.txt"]));
+        assert!(req.validate().is_ok());
+        assert!(ArtifactSpec::new(["/abs/*"]).compile().is_err());
+    }
+
+    #[test]
+    fn artifact_carries_size_and_sha256() {
+        let artifact = Artifact::new("hello.txt", b"hello".to_vec());
+        assert_eq!(artifact.size_bytes, 5);
+        assert_eq!(
+            artifact.sha256,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        let json = serde_json::to_value(&artifact).unwrap();
+        assert_eq!(json["content"], "aGVsbG8=");
+        let back: Artifact = serde_json::from_value(json).unwrap();
+        assert_eq!(back, artifact);
+    }
+
+    #[tokio::test]
+    async fn stages_files_and_collects_matching_artifacts_within_caps() {
+        let dir = tempfile::tempdir().unwrap();
+        let host = dir.path().join("host-input.bin");
+        std::fs::write(&host, b"from host").unwrap();
+        let work = dir.path().join("work");
+        std::fs::create_dir(&work).unwrap();
+
+        stage_files(
+            &work,
+            &[
+                StagedFile::bytes("in/a.txt", "inline"),
+                StagedFile::host_path("in/b.bin", &host),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read(work.join("in/a.txt")).unwrap(), b"inline");
+        assert_eq!(std::fs::read(work.join("in/b.bin")).unwrap(), b"from host");
+
+        std::fs::create_dir_all(work.join("out/nested")).unwrap();
+        std::fs::write(work.join("out/one.txt"), "1").unwrap();
+        std::fs::write(work.join("out/two.txt"), "22").unwrap();
+        std::fs::write(work.join("out/big.txt"), "x".repeat(64)).unwrap();
+        std::fs::write(work.join("out/nested/deep.txt"), "3").unwrap();
+        std::fs::write(work.join("out/skip.log"), "no").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&host, work.join("out/link.txt")).unwrap();
+
+        let spec = ArtifactSpec::new(["out/*.txt", "out/**/deep.txt"]).with_max_file_bytes(16);
+        let (artifacts, truncated) = ArtifactCollector::new(&spec)
+            .unwrap()
+            .collect_dir(&work)
+            .await
+            .unwrap();
+        let paths: Vec<_> = artifacts.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(paths, ["out/one.txt", "out/two.txt", "out/nested/deep.txt"]);
+        assert!(truncated, "big.txt exceeds the per-file cap");
+
+        let spec = ArtifactSpec::new(["out/*.txt"]).with_max_files(1);
+        let (artifacts, truncated) = ArtifactCollector::new(&spec)
+            .unwrap()
+            .collect_dir(&work)
+            .await
+            .unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert!(truncated);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn staging_never_writes_through_planted_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let outside = dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        std::fs::write(outside.join("target.txt"), "host file").unwrap();
+        let work = dir.path().join("work");
+        std::fs::create_dir(&work).unwrap();
+        // What a session container could leave in its bind-mounted workspace.
+        std::os::unix::fs::symlink(&outside, work.join("escape")).unwrap();
+        std::os::unix::fs::symlink(outside.join("target.txt"), work.join("clobber.txt")).unwrap();
+
+        for path in [
+            "escape/planted.txt",
+            "escape/sub/planted.txt",
+            "clobber.txt",
+        ] {
+            let staged = stage_files(&work, &[StagedFile::bytes(path, "from sandbox")]).await;
+            assert!(staged.is_err(), "{} should be refused", path);
+        }
+        assert!(!outside.join("planted.txt").exists());
+        assert!(!outside.join("sub").exists());
+        assert_eq!(
+            std::fs::read_to_string(outside.join("target.txt")).unwrap(),
+            "host file"
+        );
+
+        // Collection does not read through them either.
+        let (artifacts, _) = ArtifactCollector::new(&ArtifactSpec::new(["**/*.txt"]))
+            .unwrap()
+            .collect_dir(&work)
+            .await
+            .unwrap();
+        assert!(artifacts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn staging_a_directory_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = stage_files(
+            dir.path(),
+            &[StagedFile::host_path("x", dir.path().to_path_buf())],
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("not a regular file"));
+    }
+}
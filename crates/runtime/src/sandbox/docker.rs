@@ -3,16 +3,28 @@
 //! Executes code inside Docker containers for isolated agent execution.
 //! Uses the `docker` CLI rather than a Rust Docker client library to
 //! minimize dependencies and match the pattern of the native runner.
+//!
+//! With [`DockerConfig::egress`] set, containers join an `--internal`
+//! network with no route off the host. The only peer they can reach is an
+//! [`EgressProxy`] the runner serves on that network's gateway address, so
+//! a program that ignores the proxy variables has no way out at all.
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
+use tokio::sync::OnceCell;
 use tokio::time::{timeout, Duration};
 
-use super::{ExecutionResult, SandboxRunner};
+use super::artifacts::{stage_files, ArtifactCollector, ArtifactSpec, ExecutionRequest};
+use super::egress::{EgressAudit, EgressPolicy, EgressProxy, EgressProxyHandle};
+use super::{ExecutionResult, SandboxRunner, SandboxSession};
+use crate::config::{NetworkAccessMode, NetworkPolicy};
 
 /// Default maximum output size in bytes (10 MB)
 const DEFAULT_MAX_OUTPUT_BYTES: usize = 10 * 1024 * 1024;
@@ -44,6 +56,13 @@ pub struct DockerConfig {
     pub max_output_bytes: usize,
     /// Extra docker run flags
     pub extra_flags: Vec<String>,
+    /// Destination allowlist for outbound connections. When set, the
+    /// runner creates an internal network for its containers and serves an
+    /// egress proxy on its gateway; the `HTTP_PROXY`/`HTTPS_PROXY`/
+    /// `ALL_PROXY` variables point at it. Requires `network_mode = "none"`,
+    /// and the proxy must run on the Docker daemon's host.
+    #[serde(default)]
+    pub egress: Option<EgressPolicy>,
 }
 
 impl Default for DockerConfig {
@@ -61,6 +80,7 @@ impl Default for DockerConfig {
             docker_binary: "docker".to_string(),
             max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
             extra_flags: Vec::new(),
+            egress: None,
         }
     }
 }
@@ -92,6 +112,20 @@ impl DockerConfig {
         self
     }
 
+    /// Apply a sandbox profile's network policy. `Full` uses the default
+    /// bridge network, `None` no network, and `Restricted` enforces
+    /// `allowed_destinations` through the egress proxy.
+    pub fn with_network_policy(mut self, policy: &NetworkPolicy) -> Self {
+        self.network_mode = match policy.access_mode {
+            NetworkAccessMode::Full => "bridge",
+            NetworkAccessMode::None | NetworkAccessMode::Restricted => "none",
+        }
+        .to_string();
+        self.egress = matches!(policy.access_mode, NetworkAccessMode::Restricted)
+            .then(|| EgressPolicy::from_network_policy(policy));
+        self
+    }
+
     /// Add a volume mount.
     ///
     /// Validates the host-side path against an obvious-danger blocklist
@@ -125,6 +159,12 @@ impl DockerConfig {
             }
             tracing::warn!("SECURITY: Docker host network mode provides no network isolation");
         }
+        if self.egress.is_some() && self.network_mode != "none" {
+            anyhow::bail!(
+                "egress filtering puts containers on their own internal network; \
+                 leave network_mode as \"none\""
+            );
+        }
         Ok(())
     }
 }
@@ -195,9 +235,150 @@ fn validate_volume_mount(mount: &str) -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+/// Write `env` to a 0600 env-file for `docker run`/`docker exec --env-file`.
+/// The handle must outlive the spawned docker process.
+fn write_env_file(
+    env: &HashMap<String, String>,
+) -> Result<Option<tempfile::NamedTempFile>, anyhow::Error> {
+    if env.is_empty() {
+        return Ok(None);
+    }
+    for key in env.keys() {
+        if key.contains('=') || key.contains('\n') || key.contains('\r') || key.contains('\0') {
+            anyhow::bail!(
+                "invalid env key '{}' — contains '=', newline, or NUL (env-file smuggling vector)",
+                key
+            );
+        }
+    }
+    for value in env.values() {
+        if value.contains('\n') || value.contains('\r') || value.contains('\0') {
+            anyhow::bail!(
+                "invalid env value — contains newline or NUL (env-file smuggling vector)"
+            );
+        }
+    }
+
+    let mut tmp = tempfile::NamedTempFile::new()
+        .map_err(|e| anyhow::anyhow!("failed to create env tempfile: {e}"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(tmp.path(), std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| anyhow::anyhow!("failed to chmod env-file: {e}"))?;
+    }
+    {
+        use std::io::Write;
+        for (key, value) in env {
+            writeln!(tmp, "{}={}", key, value)
+                .map_err(|e| anyhow::anyhow!("failed to write env-file: {e}"))?;
+        }
+        tmp.flush()
+            .map_err(|e| anyhow::anyhow!("failed to flush env-file: {e}"))?;
+    }
+    Ok(Some(tmp))
+}
+
+/// An `--internal` Docker network whose only reachable peer is the runner's
+/// egress proxy, listening on the network's gateway address. The network is
+/// removed when the last runner or session using it drops.
+struct EgressNetwork {
+    docker_binary: String,
+    name: String,
+    proxy_url: String,
+    _proxy: EgressProxyHandle,
+}
+
+impl EgressNetwork {
+    async fn create(docker_binary: &str, proxy: EgressProxy) -> Result<Self, anyhow::Error> {
+        let name = format!("symbi-egress-{}", uuid::Uuid::new_v4().simple());
+        let created = Command::new(docker_binary)
+            .args(["network", "create", "--internal", &name])
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !created.status.success() {
+            anyhow::bail!(
+                "Failed to create egress network: {}",
+                String::from_utf8_lossy(&created.stderr).trim()
+            );
+        }
+
+        match Self::serve_on_gateway(docker_binary, &name, proxy).await {
+            Ok(handle) => {
+                let proxy_url = format!("http://{}", handle.local_addr());
+                tracing::info!(network = %name, proxy = %proxy_url, "Docker egress network ready");
+                Ok(Self {
+                    docker_binary: docker_binary.to_string(),
+                    name,
+                    proxy_url,
+                    _proxy: handle,
+                })
+            }
+            Err(e) => {
+                remove_network(docker_binary, &name);
+                Err(e)
+            }
+        }
+    }
+
+    /// Listen on the network's IPv4 gateway, the host side of its bridge.
+    async fn serve_on_gateway(
+        docker_binary: &str,
+        name: &str,
+        proxy: EgressProxy,
+    ) -> Result<EgressProxyHandle, anyhow::Error> {
+        let inspect = Command::new(docker_binary)
+            .args([
+                "network",
+                "inspect",
+                "--format",
+                "{{range .IPAM.Config}}{{.Gateway}} {{end}}",
+                name,
+            ])
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        let gateway = String::from_utf8_lossy(&inspect.stdout)
+            .split_whitespace()
+            .filter_map(|g| g.parse::<IpAddr>().ok())
+            .find(IpAddr::is_ipv4)
+            .ok_or_else(|| anyhow::anyhow!("egress network {} has no IPv4 gateway", name))?;
+        proxy
+            .listen(SocketAddr::new(gateway, 0))
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Cannot serve the egress proxy on {} (is the Docker daemon on this host?): {}",
+                    gateway,
+                    e
+                )
+            })
+    }
+}
+
+impl Drop for EgressNetwork {
+    fn drop(&mut self) {
+        remove_network(&self.docker_binary, &self.name);
+    }
+}
+
+fn remove_network(docker_binary: &str, name: &str) {
+    let _ = std::process::Command::new(docker_binary)
+        .args(["network", "rm", name])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+}
+
 /// Docker sandbox runner
+#[derive(Clone)]
 pub struct DockerRunner {
     config: DockerConfig,
+    egress: Option<EgressProxy>,
+    /// Created on first use, shared with sessions so it outlives the runner
+    egress_network: Arc<OnceCell<EgressNetwork>>,
 }
 
 impl DockerRunner {
@@ -230,21 +411,58 @@ impl DockerRunner {
             config.cpu_limit
         );
 
-        Ok(Self { config })
+        Ok(Self::with_config(config))
+    }
+
+    fn with_config(config: DockerConfig) -> Self {
+        Self {
+            egress: config.egress.clone().map(EgressProxy::new),
+            egress_network: Arc::new(OnceCell::new()),
+            config,
+        }
+    }
+
+    /// Record egress decisions through `audit`. No effect without
+    /// [`DockerConfig::egress`].
+    pub fn with_egress_audit(mut self, audit: Arc<dyn EgressAudit>) -> Self {
+        self.egress = self.egress.map(|proxy| proxy.with_audit(audit));
+        self
+    }
+
+    /// Set up the egress network if this runner filters egress and has not
+    /// done so yet. Must run before `build_command`.
+    async fn prepare_network(&self) -> Result<(), anyhow::Error> {
+        if let Some(proxy) = &self.egress {
+            self.egress_network
+                .get_or_try_init(|| {
+                    EgressNetwork::create(&self.config.docker_binary, proxy.clone())
+                })
+                .await?;
+        }
+        Ok(())
     }
 
     /// Build the `docker run` command with all configuration applied.
     /// Returns the command plus an optional env-file handle that MUST be kept
     /// alive until the child process has been spawned (so the tempfile is not
-    /// deleted before `docker run` reads it).
+    /// deleted before `docker run` reads it). A `workspace` directory is
+    /// bind-mounted read-write over the working directory, and `detach_as`
+    /// starts a named background container instead of running `code` to exit.
     fn build_command(
         &self,
         code: &str,
         env: &HashMap<String, String>,
+        workspace: Option<&std::path::Path>,
+        detach_as: Option<&str>,
     ) -> Result<(Command, Option<tempfile::NamedTempFile>), anyhow::Error> {
         let mut cmd = Command::new(&self.config.docker_binary);
         cmd.arg("run");
 
+        // Session containers run detached under a known name for `docker exec`
+        if let Some(name) = detach_as {
+            cmd.arg("--detach").arg("--name").arg(name);
+        }
+
         // Auto-remove container after execution
         if self.config.auto_remove {
             cmd.arg("--rm");
@@ -260,8 +478,30 @@ impl DockerRunner {
             cmd.arg("--cpus").arg(cpu.to_string());
         }
 
-        // Network isolation
-        cmd.arg("--network").arg(&self.config.network_mode);
+        // Network isolation. With egress filtering the container joins the
+        // internal network, where the proxy is the only peer it can reach.
+        match self.egress_network.get() {
+            Some(network) => {
+                cmd.arg("--network").arg(&network.name);
+                for var in [
+                    "HTTP_PROXY",
+                    "HTTPS_PROXY",
+                    "ALL_PROXY",
+                    "http_proxy",
+                    "https_proxy",
+                    "all_proxy",
+                ] {
+                    cmd.arg("--env")
+                        .arg(format!("{}={}", var, network.proxy_url));
+                }
+            }
+            None if self.egress.is_some() => {
+                anyhow::bail!("egress network has not been set up");
+            }
+            None => {
+                cmd.arg("--network").arg(&self.config.network_mode);
+            }
+        }
 
         // Working directory
         cmd.arg("--workdir").arg(&self.config.working_dir);
@@ -279,54 +519,19 @@ impl DockerRunner {
 
         // Environment variables: write to a 0600 tempfile and pass via --env-file
         // so values never appear on the command line (process listings, audit logs).
-        let env_file_handle = if env.is_empty() {
-            None
-        } else {
-            for key in env.keys() {
-                if key.contains('=')
-                    || key.contains('\n')
-                    || key.contains('\r')
-                    || key.contains('\0')
-                {
-                    anyhow::bail!(
-                        "invalid env key '{}' — contains '=', newline, or NUL (env-file smuggling vector)",
-                        key
-                    );
-                }
-            }
-            for value in env.values() {
-                if value.contains('\n') || value.contains('\r') || value.contains('\0') {
-                    anyhow::bail!(
-                        "invalid env value — contains newline or NUL (env-file smuggling vector)"
-                    );
-                }
-            }
-
-            let mut tmp = tempfile::NamedTempFile::new()
-                .map_err(|e| anyhow::anyhow!("failed to create env tempfile: {e}"))?;
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                std::fs::set_permissions(tmp.path(), std::fs::Permissions::from_mode(0o600))
-                    .map_err(|e| anyhow::anyhow!("failed to chmod env-file: {e}"))?;
-            }
-            {
-                use std::io::Write;
-                for (key, value) in env {
-                    writeln!(tmp, "{}={}", key, value)
-                        .map_err(|e| anyhow::anyhow!("failed to write env-file: {e}"))?;
-                }
-                tmp.flush()
-                    .map_err(|e| anyhow::anyhow!("failed to flush env-file: {e}"))?;
-            }
+        let env_file_handle = write_env_file(env)?;
+        if let Some(ref tmp) = env_file_handle {
             cmd.arg("--env-file").arg(tmp.path());
-            Some(tmp)
-        };
+        }
 
         // Volume mounts
         for vol in &self.config.volumes {
             cmd.arg("-v").arg(vol);
         }
+        if let Some(dir) = workspace {
+            cmd.arg("-v")
+                .arg(format!("{}:{}:rw", dir.display(), self.config.working_dir));
+        }
 
         // Extra flags
         for flag in &self.config.extra_flags {
@@ -379,28 +584,20 @@ impl DockerRunner {
             (output, false)
         }
     }
-}
 
-#[async_trait]
-impl SandboxRunner for DockerRunner {
-    async fn execute(
+    /// Spawn a prepared `docker run`/`docker exec` command and capture its
+    /// output within the configured limits, then collect artifacts from
+    /// `workspace`. Returns `Ok(None)` if the execution timed out; the docker
+    /// client has been killed by then.
+    async fn run_command(
         &self,
-        code: &str,
-        env: HashMap<String, String>,
-    ) -> Result<ExecutionResult, anyhow::Error> {
-        tracing::info!(
-            "Docker sandbox executing: image={}, code_len={}, env_count={}",
-            self.config.image,
-            code.len(),
-            env.len()
-        );
-
-        let (mut command, _env_file) = self.build_command(code, &env)?;
+        mut command: Command,
+        workspace: Option<&std::path::Path>,
+        artifacts: Option<&ArtifactSpec>,
+    ) -> Result<Option<ExecutionResult>, anyhow::Error> {
         let start = std::time::Instant::now();
         let max_output = self.config.max_output_bytes;
 
-        // _env_file must outlive spawn() so docker can read the env-file before
-        // the tempfile is unlinked on drop.
         let mut child = command.spawn().map_err(|e| {
             anyhow::anyhow!("Failed to spawn docker process: {}. Is Docker running?", e)
         })?;
@@ -446,7 +643,14 @@ impl SandboxRunner for DockerRunner {
                     execution_time
                 );
 
-                Ok(ExecutionResult {
+                let (artifacts, artifacts_truncated) = match (artifacts, workspace) {
+                    (Some(spec), Some(dir)) => {
+                        ArtifactCollector::new(spec)?.collect_dir(dir).await?
+                    }
+                    _ => (Vec::new(), false),
+                };
+
+                Ok(Some(ExecutionResult {
                     stdout,
                     stderr,
                     exit_code,
@@ -454,7 +658,9 @@ impl SandboxRunner for DockerRunner {
                     execution_time_ms: execution_time.as_millis() as u64,
                     stdout_truncated,
                     stderr_truncated,
-                })
+                    artifacts,
+                    artifacts_truncated,
+                }))
             }
             Ok((_, _, _, _, Err(e))) => {
                 tracing::error!("Docker execution failed: {}", e);
@@ -467,13 +673,215 @@ impl SandboxRunner for DockerRunner {
                     "Docker execution timed out after {:?}",
                     self.config.max_execution_time
                 );
-                Err(anyhow::anyhow!(
-                    "Docker execution timed out after {:?}",
-                    self.config.max_execution_time
-                ))
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SandboxRunner for DockerRunner {
+    async fn execute(
+        &self,
+        code: &str,
+        env: HashMap<String, String>,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        self.execute_request(ExecutionRequest::new(code).with_env(env))
+            .await
+    }
+
+    async fn execute_request(
+        &self,
+        request: ExecutionRequest,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        tracing::info!(
+            "Docker sandbox executing: image={}, code_len={}, env_count={}, files={}",
+            self.config.image,
+            request.code.len(),
+            request.env.len(),
+            request.files.len()
+        );
+        request.validate()?;
+        self.prepare_network().await?;
+
+        // Input files and artifacts live in a host directory mounted over the
+        // working directory; it is removed when `workspace` drops.
+        let workspace = if request.uses_files() {
+            let dir = tempfile::tempdir()
+                .map_err(|e| anyhow::anyhow!("failed to create staging dir: {e}"))?;
+            stage_files(dir.path(), &request.files).await?;
+            Some(dir)
+        } else {
+            None
+        };
+
+        let (command, _env_file) = self.build_command(
+            &request.code,
+            &request.env,
+            workspace.as_ref().map(|d| d.path()),
+            None,
+        )?;
+        // _env_file must outlive spawn() so docker can read the env-file before
+        // the tempfile is unlinked on drop.
+        self.run_command(
+            command,
+            workspace.as_ref().map(|d| d.path()),
+            request.artifacts.as_ref(),
+        )
+        .await?
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Docker execution timed out after {:?}",
+                self.config.max_execution_time
+            )
+        })
+    }
+
+    async fn open_session(&self) -> Result<Box<dyn SandboxSession>, anyhow::Error> {
+        self.prepare_network().await?;
+        let workspace = tempfile::tempdir()
+            .map_err(|e| anyhow::anyhow!("failed to create session workspace: {e}"))?;
+        let container = format!("symbi-session-{}", uuid::Uuid::new_v4());
+        let (mut command, _env_file) = self.build_command(
+            SESSION_KEEPALIVE,
+            &HashMap::new(),
+            Some(workspace.path()),
+            Some(&container),
+        )?;
+        let output = command.output().await.map_err(|e| {
+            anyhow::anyhow!("Failed to spawn docker process: {}. Is Docker running?", e)
+        })?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to start session container: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        tracing::info!(
+            "Docker session container started: container={}, image={}",
+            container,
+            self.config.image
+        );
+
+        Ok(Box::new(DockerSession {
+            runner: self.clone(),
+            container,
+            workspace,
+            closed: AtomicBool::new(false),
+        }))
+    }
+}
+
+/// Keeps a session container running until it is stopped.
+const SESSION_KEEPALIVE: &str = "trap 'exit 0' TERM; while :; do sleep 3600 & wait $!; done";
+
+/// A detached container that runs each execution with `docker exec`.
+///
+/// The host workspace is bind-mounted over the working directory for the
+/// container's whole life, so files written by one execution are visible to
+/// the next and staged inputs and artifacts go through the same directory.
+pub struct DockerSession {
+    runner: DockerRunner,
+    container: String,
+    workspace: tempfile::TempDir,
+    closed: AtomicBool,
+}
+
+impl DockerSession {
+    /// Name of the session container
+    pub fn container(&self) -> &str {
+        &self.container
+    }
+
+    async fn remove_container(&self) -> Result<(), anyhow::Error> {
+        let output = Command::new(&self.runner.config.docker_binary)
+            .arg("rm")
+            .arg("--force")
+            .arg(&self.container)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Failed to remove session container {}: {}",
+                self.container,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SandboxSession for DockerSession {
+    async fn execute(&self, request: ExecutionRequest) -> Result<ExecutionResult, anyhow::Error> {
+        if self.closed.load(Ordering::SeqCst) {
+            anyhow::bail!("session container {} is closed", self.container);
+        }
+        request.validate()?;
+        stage_files(self.workspace.path(), &request.files).await?;
+
+        let config = &self.runner.config;
+        let env_file = write_env_file(&request.env)?;
+        let mut command = Command::new(&config.docker_binary);
+        command.arg("exec");
+        if let Some(ref tmp) = env_file {
+            command.arg("--env-file").arg(tmp.path());
+        }
+        command
+            .arg("--workdir")
+            .arg(&config.working_dir)
+            .arg(&self.container)
+            .arg(&config.shell)
+            .arg("-c")
+            .arg(&request.code)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        match self
+            .runner
+            .run_command(
+                command,
+                Some(self.workspace.path()),
+                request.artifacts.as_ref(),
+            )
+            .await?
+        {
+            Some(result) => Ok(result),
+            None => {
+                // Killing the exec client leaves the process running in the
+                // container, so a timeout costs the whole session.
+                self.close().await?;
+                anyhow::bail!(
+                    "Docker execution timed out after {:?}; session container closed",
+                    config.max_execution_time
+                )
             }
         }
     }
+
+    async fn close(&self) -> Result<(), anyhow::Error> {
+        if self.closed.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.remove_container().await
+    }
+}
+
+impl Drop for DockerSession {
+    fn drop(&mut self) {
+        if !self.closed.load(Ordering::SeqCst) {
+            let _ = std::process::Command::new(&self.runner.config.docker_binary)
+                .arg("rm")
+                .arg("--force")
+                .arg(&self.container)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -564,19 +972,120 @@ mod tests {
     #[test]
     fn test_command_building() {
         let config = DockerConfig::default();
-        let runner = DockerRunner { config };
+        let runner = DockerRunner::with_config(config);
 
         let mut env = HashMap::new();
         env.insert("FOO".to_string(), "bar".to_string());
 
         let cmd = runner
-            .build_command("echo hello", &env)
+            .build_command("echo hello", &env, None, None)
             .expect("build_command must succeed for valid env");
         // Verify the command is constructed (we can't easily inspect it,
         // but it shouldn't panic)
         let _ = cmd;
     }
 
+    #[test]
+    fn test_network_policy_maps_to_network_and_egress() {
+        use crate::config::SandboxProfile;
+
+        let mut profile = SandboxProfile::secure_default();
+        profile.network.access_mode = NetworkAccessMode::Full;
+        let full = DockerConfig::default().with_network_policy(&profile.network);
+        assert_eq!(full.network_mode, "bridge");
+        assert!(full.egress.is_none());
+
+        let restricted = DockerConfig::default()
+            .with_network_policy(&SandboxProfile::standard_default().network);
+        assert_eq!(restricted.network_mode, "none");
+        assert!(restricted.egress.is_some());
+        restricted.validate().unwrap();
+
+        // Egress replaces the network mode rather than riding on top of it.
+        assert!(restricted.with_network().validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_egress_runs_containers_on_the_internal_network() {
+        let config = DockerConfig {
+            docker_binary: "true".to_string(),
+            ..DockerConfig::default()
+                .with_network_policy(&crate::config::SandboxProfile::standard_default().network)
+        };
+        let runner = DockerRunner::with_config(config);
+        // Commands are refused until the network exists.
+        assert!(runner
+            .build_command("true", &HashMap::new(), None, None)
+            .is_err());
+
+        let proxy = runner
+            .egress
+            .clone()
+            .unwrap()
+            .listen("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let proxy_url = format!("http://{}", proxy.local_addr());
+        assert!(runner
+            .egress_network
+            .set(EgressNetwork {
+                docker_binary: "true".to_string(),
+                name: "symbi-egress-test".to_string(),
+                proxy_url: proxy_url.clone(),
+                _proxy: proxy,
+            })
+            .is_ok());
+
+        let (cmd, _) = runner
+            .build_command("true", &HashMap::new(), None, None)
+            .unwrap();
+        let args: Vec<String> = cmd
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        let network = args.iter().position(|a| a == "--network").unwrap();
+        assert_eq!(args[network + 1], "symbi-egress-test");
+        assert!(args.contains(&format!("HTTPS_PROXY={}", proxy_url)));
+        assert!(!args.iter().any(|a| a.contains("host-gateway")));
+    }
+
+    #[test]
+    fn test_workspace_is_mounted_over_working_dir() {
+        let runner = DockerRunner::with_config(DockerConfig::default());
+        let dir = std::path::Path::new("/tmp/symbi-stage");
+        let (cmd, _) = runner
+            .build_command("true", &HashMap::new(), Some(dir), None)
+            .unwrap();
+        let args: Vec<String> = cmd
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert!(args.contains(&"/tmp/symbi-stage:/workspace:rw".to_string()));
+    }
+
+    #[test]
+    fn test_session_container_is_detached_and_named() {
+        let runner = DockerRunner::with_config(DockerConfig::default());
+        let (cmd, _) = runner
+            .build_command(
+                SESSION_KEEPALIVE,
+                &HashMap::new(),
+                None,
+                Some("symbi-session-x"),
+            )
+            .unwrap();
+        let args: Vec<String> = cmd
+            .as_std()
+            .get_args()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(args[..4], ["run", "--detach", "--name", "symbi-session-x"]);
+        assert!(args.contains(&"--read-only".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some(SESSION_KEEPALIVE));
+    }
+
     // Integration tests that require Docker are below.
     // They're ignored by default since CI may not have Docker.
 
@@ -655,6 +1164,40 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[ignore] // Requires Docker
+    async fn test_docker_egress_network_has_no_direct_route() {
+        use crate::config::NetworkDestination;
+
+        let policy = NetworkPolicy {
+            access_mode: NetworkAccessMode::Restricted,
+            allowed_destinations: vec![NetworkDestination {
+                host: "example.com".to_string(),
+                port: Some(443),
+                protocol: None,
+            }],
+            max_bandwidth_mbps: None,
+        };
+        let config = DockerConfig::for_image("alpine:latest").with_network_policy(&policy);
+        let runner = match DockerRunner::new(config) {
+            Ok(r) => r,
+            Err(_) => return,
+        };
+
+        // Bypassing the proxy variables gets nowhere on the internal network.
+        let result = runner
+            .execute(
+                "nc -w 3 1.1.1.1 53 </dev/null && echo direct open || echo direct blocked; \
+                 echo $HTTPS_PROXY",
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+        let lines: Vec<&str> = result.stdout.lines().collect();
+        assert_eq!(lines[0], "direct blocked");
+        assert!(lines[1].starts_with("http://"), "{}", result.stdout);
+    }
+
     #[tokio::test]
     #[ignore] // Requires Docker
     async fn test_docker_timeout() {
@@ -671,4 +1214,48 @@ mod tests {
         let result = runner.execute("sleep 30", HashMap::new()).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    #[ignore] // Requires Docker
+    async fn test_docker_stages_files_and_collects_artifacts() {
+        use crate::sandbox::{ArtifactSpec, StagedFile};
+
+        let runner = match DockerRunner::new(DockerConfig::for_image("alpine:latest")) {
+            Ok(r) => r,
+            Err(_) => return, // Docker not available
+        };
+        let request = ExecutionRequest::new("mkdir -p out && tr a-z A-Z < in.txt > out/upper.txt")
+            .with_file(StagedFile::bytes("in.txt", "hello"))
+            .collect(ArtifactSpec::new(["out/*.txt"]));
+
+        let result = runner.execute_request(request).await.unwrap();
+        assert!(result.success, "stderr: {}", result.stderr);
+        assert_eq!(result.artifacts.len(), 1);
+        assert_eq!(result.artifacts[0].path, "out/upper.txt");
+        assert_eq!(result.artifacts[0].content, b"HELLO");
+    }
+
+    #[tokio::test]
+    #[ignore] // Requires Docker
+    async fn test_docker_session_keeps_filesystem_between_executions() {
+        let runner = match DockerRunner::new(DockerConfig::for_image("alpine:latest")) {
+            Ok(r) => r,
+            Err(_) => return, // Docker not available
+        };
+        let session = runner.open_session().await.unwrap();
+        session
+            .execute(ExecutionRequest::new("echo 41 > state.txt"))
+            .await
+            .unwrap();
+        let result = session
+            .execute(ExecutionRequest::new("echo $(( $(cat state.txt) + 1 ))"))
+            .await
+            .unwrap();
+        assert_eq!(result.stdout.trim(), "42");
+        session.close().await.unwrap();
+        assert!(session
+            .execute(ExecutionRequest::new("true"))
+            .await
+            .is_err());
+    }
 }
@@ -2,8 +2,10 @@
 //!
 //! Provides integration with E2B.dev cloud sandboxing service for secure code execution.
 
-use super::{ExecutionResult, SandboxRunner};
+use super::artifacts::{ArtifactCollector, ArtifactSpec, ExecutionRequest};
+use super::{Artifact, ExecutionResult, SandboxRunner};
 use async_trait::async_trait;
+use base64::Engine;
 use std::collections::HashMap;
 
 /// E2B sandbox implementation for cloud-based code execution
@@ -77,6 +79,62 @@ impl E2BSandbox {
             host
         ))
     }
+
+    /// Build the execute payload. Staged files travel inline as base64 and
+    /// the artifact spec asks the service to return matching files.
+    async fn request_payload(
+        request: &ExecutionRequest,
+    ) -> Result<serde_json::Value, anyhow::Error> {
+        let mut payload = serde_json::json!({
+            "code": request.code,
+            "environment": request.env,
+            "timeout": 30000, // 30 seconds in milliseconds
+            "language": "python" // Default to Python, could be configurable
+        });
+        if !request.files.is_empty() {
+            let mut files = Vec::with_capacity(request.files.len());
+            for file in &request.files {
+                files.push(serde_json::json!({
+                    "path": file.path,
+                    "content": base64::engine::general_purpose::STANDARD.encode(file.read().await?),
+                }));
+            }
+            payload["files"] = serde_json::Value::Array(files);
+        }
+        if let Some(spec) = &request.artifacts {
+            payload["artifacts"] = serde_json::to_value(spec)?;
+        }
+        Ok(payload)
+    }
+
+    /// Decode the `artifacts` array of an execute response, re-applying the
+    /// spec's patterns and caps rather than trusting the service to.
+    fn parse_artifacts(
+        response: &serde_json::Value,
+        spec: &ArtifactSpec,
+    ) -> Result<(Vec<Artifact>, bool), anyhow::Error> {
+        let mut collector = ArtifactCollector::new(spec)?;
+        let entries = response
+            .get("artifacts")
+            .and_then(|v| v.as_array())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        for entry in entries {
+            let (Some(path), Some(content)) = (
+                entry.get("path").and_then(|v| v.as_str()),
+                entry.get("content").and_then(|v| v.as_str()),
+            ) else {
+                anyhow::bail!("E2B artifact entry is missing `path` or `content`");
+            };
+            let content = base64::engine::general_purpose::STANDARD
+                .decode(content)
+                .map_err(|e| anyhow::anyhow!("E2B artifact {:?} is not base64: {}", path, e))?;
+            if collector.admits(path, content.len() as u64) {
+                collector.push(path, content);
+            }
+        }
+        Ok(collector.finish())
+    }
 }
 
 #[async_trait]
@@ -85,12 +143,22 @@ impl SandboxRunner for E2BSandbox {
         &self,
         code: &str,
         env: HashMap<String, String>,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        self.execute_request(ExecutionRequest::new(code).with_env(env))
+            .await
+    }
+
+    async fn execute_request(
+        &self,
+        request: ExecutionRequest,
     ) -> Result<ExecutionResult, anyhow::Error> {
         tracing::debug!(
-            "E2B sandbox execution requested for {} chars of code with {} env vars",
-            code.len(),
-            env.len()
+            "E2B sandbox execution requested for {} chars of code with {} env vars and {} files",
+            request.code.len(),
+            request.env.len(),
+            request.files.len()
         );
+        request.validate()?;
 
         // Refuse to ship code + the bearer API key to an untrusted endpoint.
         Self::validate_endpoint(&self.endpoint)
@@ -103,12 +171,7 @@ impl SandboxRunner for E2BSandbox {
             .map_err(|e| anyhow::anyhow!("Failed to create HTTP client: {}", e))?;
 
         // Prepare execution request payload
-        let execution_request = serde_json::json!({
-            "code": code,
-            "environment": env,
-            "timeout": 30000, // 30 seconds in milliseconds
-            "language": "python" // Default to Python, could be configurable
-        });
+        let execution_request = Self::request_payload(&request).await?;
 
         let execution_url = format!("{}/v1/sandboxes/execute", self.endpoint);
 
@@ -171,15 +234,16 @@ impl SandboxRunner for E2BSandbox {
             success
         );
 
-        if success {
-            Ok(ExecutionResult::success(stdout, execution_duration))
+        let mut result = if success {
+            ExecutionResult::success(stdout, execution_duration)
         } else {
-            Ok(ExecutionResult::failure(
-                exit_code,
-                stderr,
-                execution_duration,
-            ))
+            ExecutionResult::failure(exit_code, stderr, execution_duration)
+        };
+        if let Some(spec) = &request.artifacts {
+            (result.artifacts, result.artifacts_truncated) =
+                Self::parse_artifacts(&response_json, spec)?;
         }
+        Ok(result)
     }
 }
 
@@ -230,6 +294,47 @@ mod tests {
         assert!(err.to_string().contains("Refusing E2B execution"));
     }
 
+    #[tokio::test]
+    async fn test_request_payload_carries_files_and_artifact_spec() {
+        use crate::sandbox::StagedFile;
+
+        let plain = E2BSandbox::request_payload(&ExecutionRequest::new("print(1)"))
+            .await
+            .unwrap();
+        assert!(plain.get("files").is_none());
+        assert!(plain.get("artifacts").is_none());
+
+        let request = ExecutionRequest::new("print(1)")
+            .with_file(StagedFile::bytes("data.csv", "a,b"))
+            .collect(ArtifactSpec::new(["*.png"]).with_max_files(3));
+        let payload = E2BSandbox::request_payload(&request).await.unwrap();
+        assert_eq!(payload["files"][0]["path"], "data.csv");
+        assert_eq!(payload["files"][0]["content"], "YSxi");
+        assert_eq!(payload["artifacts"]["patterns"][0], "*.png");
+        assert_eq!(payload["artifacts"]["max_files"], 3);
+    }
+
+    #[test]
+    fn test_parse_artifacts_reapplies_spec() {
+        let response = serde_json::json!({
+            "success": true,
+            "artifacts": [
+                {"path": "plot.png", "content": "cG5n"},
+                {"path": "notes.txt", "content": "aWdub3JlZA=="},
+                {"path": "big.png", "content": "AAAAAAAAAAAA"},
+            ]
+        });
+        let spec = ArtifactSpec::new(["*.png"]).with_max_file_bytes(4);
+        let (artifacts, truncated) = E2BSandbox::parse_artifacts(&response, &spec).unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].path, "plot.png");
+        assert_eq!(artifacts[0].content, b"png");
+        assert!(truncated, "big.png exceeds the per-file cap");
+
+        let bad = serde_json::json!({"artifacts": [{"path": "x.png", "content": "%%"}]});
+        assert!(E2BSandbox::parse_artifacts(&bad, &spec).is_err());
+    }
+
     #[tokio::test]
     #[ignore = "requires live E2B API endpoint"]
     async fn test_e2b_sandbox_execute() {
@@ -0,0 +1,992 @@
+//! Egress filtering proxy for sandboxed code
+//!
+//! Enforces a [`NetworkPolicy`] destination allowlist for sandboxes whose
+//! only route out is this proxy. One listener speaks three dialects:
+//!
+//! - HTTP `CONNECT host:port` tunnels. The first bytes the client sends are
+//!   inspected: a TLS ClientHello makes the tunnel `HTTPS` (and its SNI must
+//!   name the CONNECT host), anything else makes it `TCP`;
+//! - plain HTTP forwarding (`GET http://host/path`), one request per
+//!   connection;
+//! - transparent TLS: a connection that opens with a ClientHello is routed
+//!   to its SNI host on [`EgressProxy::with_transparent_tls_port`].
+//!
+//! Destinations must match an allowed host (exact, or `*.example.com` for
+//! subdomains), port and protocol. Resolved addresses are filtered through
+//! [`is_non_public_ip`] so an allowed name cannot be pointed at loopback,
+//! private or metadata addresses. Every allowed, denied and failed
+//! connection is recorded through [`EgressAudit`] and `tracing`.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, Duration};
+
+use crate::config::{NetworkAccessMode, NetworkPolicy, NetworkProtocol};
+use crate::net_guard::is_non_public_ip;
+
+/// Largest request head accepted from a client.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Largest TLS record read while looking for the ClientHello.
+const MAX_TLS_RECORD: usize = 16 * 1024 + 2048;
+
+/// How long a CONNECT tunnel waits for the client to speak first before it
+/// is classified as plain TCP.
+const FIRST_BYTES_WAIT: Duration = Duration::from_secs(2);
+
+/// Upstream DNS and connect timeout.
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Destination allowlist enforced by the proxy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EgressPolicy {
+    /// Access mode and allowed destinations. `None` denies everything,
+    /// `Full` allows any public destination.
+    pub network: NetworkPolicy,
+    /// Skip the non-public address check (on-prem services, tests).
+    #[serde(default)]
+    pub allow_private_destinations: bool,
+}
+
+impl EgressPolicy {
+    /// Enforce `policy` with the non-public address check enabled.
+    pub fn from_network_policy(policy: &NetworkPolicy) -> Self {
+        Self {
+            network: policy.clone(),
+            allow_private_destinations: false,
+        }
+    }
+
+    /// Whether `protocol` traffic to `host:port` is allowed.
+    pub fn permits(&self, host: &str, port: u16, protocol: NetworkProtocol) -> bool {
+        match self.network.access_mode {
+            NetworkAccessMode::None => false,
+            NetworkAccessMode::Full => protocol != NetworkProtocol::UDP,
+            NetworkAccessMode::Restricted => self.network.allowed_destinations.iter().any(|dest| {
+                host_matches(&dest.host, host)
+                    && dest.port.is_none_or(|p| p == port)
+                    && protocol_matches(dest.protocol, protocol)
+            }),
+        }
+    }
+
+    /// Whether a CONNECT tunnel to `host:port` could carry allowed traffic.
+    fn permits_tunnel(&self, host: &str, port: u16) -> bool {
+        self.permits(host, port, NetworkProtocol::TCP)
+            || self.permits(host, port, NetworkProtocol::HTTPS)
+    }
+}
+
+/// `pattern` is a host name, IP literal, or `*.suffix` for any subdomain.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .strip_suffix(suffix)
+            .is_some_and(|rest| rest.ends_with('.') && rest.len() > 1),
+        None => pattern == host,
+    }
+}
+
+/// A `TCP` rule covers every TCP-based protocol; the others match exactly.
+fn protocol_matches(rule: Option<NetworkProtocol>, actual: NetworkProtocol) -> bool {
+    match rule {
+        None => actual != NetworkProtocol::UDP,
+        Some(NetworkProtocol::TCP) => actual != NetworkProtocol::UDP,
+        Some(rule) => rule == actual,
+    }
+}
+
+/// Outcome of a proxied connection attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EgressDecision {
+    /// The destination was allowed and the upstream connection opened.
+    Allowed,
+    /// The policy or the non-public address check refused the destination.
+    Denied,
+    /// The destination was allowed but could not be reached.
+    Failed,
+}
+
+/// One audited egress connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EgressAuditEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Requested host name or IP literal.
+    pub host: String,
+    pub port: u16,
+    /// Classified protocol; `None` when the request was refused before the
+    /// client sent any payload.
+    pub protocol: Option<NetworkProtocol>,
+    /// Upstream address actually connected to.
+    pub upstream: Option<IpAddr>,
+    pub decision: EgressDecision,
+    /// Why the connection was denied or failed.
+    pub reason: Option<String>,
+}
+
+/// Sink that receives an event for every connection the proxy handles.
+#[async_trait]
+pub trait EgressAudit: Send + Sync {
+    async fn record(&self, event: EgressAuditEvent);
+}
+
+/// Appends egress events to a file as JSON lines.
+pub struct JsonlEgressAudit {
+    path: PathBuf,
+}
+
+impl JsonlEgressAudit {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Default audit log: `<data_dir>/symbi/egress_audit.jsonl`.
+    pub fn default_path() -> PathBuf {
+        let base = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
+        base.join("symbi").join("egress_audit.jsonl")
+    }
+}
+
+#[async_trait]
+impl EgressAudit for JsonlEgressAudit {
+    async fn record(&self, event: EgressAuditEvent) {
+        let line = match serde_json::to_string(&event) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::warn!("Failed to serialize egress audit event: {}", e);
+                return;
+            }
+        };
+        let write = async {
+            if let Some(parent) = self.path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .await?;
+            file.write_all(format!("{}\n", line).as_bytes()).await?;
+            file.flush().await
+        };
+        if let Err(e) = write.await {
+            tracing::warn!(path = %self.path.display(), "Failed to write egress audit event: {}", e);
+        }
+    }
+}
+
+/// Filtering forward proxy. Cheap to clone; clones share the audit sink.
+#[derive(Clone)]
+pub struct EgressProxy {
+    policy: EgressPolicy,
+    audit: Option<Arc<dyn EgressAudit>>,
+    transparent_tls_port: u16,
+}
+
+impl std::fmt::Debug for EgressProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EgressProxy")
+            .field("policy", &self.policy)
+            .field("audit", &self.audit.is_some())
+            .field("transparent_tls_port", &self.transparent_tls_port)
+            .finish()
+    }
+}
+
+/// A running proxy listener; stops accepting when dropped.
+#[derive(Debug)]
+pub struct EgressProxyHandle {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl EgressProxyHandle {
+    /// Address the proxy is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for EgressProxyHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Parsed client request.
+enum ProxyRequest {
+    Connect {
+        host: String,
+        port: u16,
+    },
+    Forward {
+        host: String,
+        port: u16,
+        head: Vec<u8>,
+    },
+}
+
+impl EgressProxy {
+    pub fn new(policy: EgressPolicy) -> Self {
+        Self {
+            policy,
+            audit: None,
+            transparent_tls_port: 443,
+        }
+    }
+
+    /// Attach an audit sink. Returns `self` for builder-style construction.
+    pub fn with_audit(mut self, audit: Arc<dyn EgressAudit>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Upstream port for transparent TLS connections (default 443).
+    pub fn with_transparent_tls_port(mut self, port: u16) -> Self {
+        self.transparent_tls_port = port;
+        self
+    }
+
+    pub fn policy(&self) -> &EgressPolicy {
+        &self.policy
+    }
+
+    /// Bind `addr` and serve connections until the handle is dropped.
+    pub async fn listen(self, addr: SocketAddr) -> io::Result<EgressProxyHandle> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        tracing::info!(%local_addr, "Egress proxy listening");
+        Ok(EgressProxyHandle {
+            local_addr,
+            task: self.serve(listener),
+        })
+    }
+
+    /// Serve connections from an already-bound listener.
+    pub fn serve(self, listener: TcpListener) -> JoinHandle<()> {
+        let proxy = Arc::new(self);
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let proxy = proxy.clone();
+                        tokio::spawn(async move { proxy.handle_connection(stream).await });
+                    }
+                    Err(e) => {
+                        tracing::warn!("Egress proxy accept failed: {}", e);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Handle one client connection to completion.
+    pub async fn handle_connection(&self, mut client: TcpStream) {
+        let mut buf = Vec::new();
+        match read_some(&mut client, &mut buf).await {
+            Ok(n) if n > 0 => {}
+            _ => return,
+        }
+        if buf[0] == TLS_HANDSHAKE {
+            self.handle_transparent_tls(client, buf).await;
+            return;
+        }
+
+        let (request, rest) = match read_request(&mut client, buf).await {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                let _ = respond(&mut client, "400 Bad Request", &e.to_string()).await;
+                return;
+            }
+        };
+        match request {
+            ProxyRequest::Connect { host, port } => {
+                self.handle_connect(client, host, port, rest).await
+            }
+            ProxyRequest::Forward { host, port, head } => {
+                if !self.policy.permits(&host, port, NetworkProtocol::HTTP) {
+                    let reason = format!("{}:{} (http) is not an allowed destination", host, port);
+                    let _ = respond(&mut client, "403 Forbidden", &reason).await;
+                    self.deny(&host, port, Some(NetworkProtocol::HTTP), reason)
+                        .await;
+                    return;
+                }
+                let mut first = head;
+                first.extend_from_slice(&rest);
+                self.tunnel(client, &host, port, NetworkProtocol::HTTP, first, true)
+                    .await
+            }
+        }
+    }
+
+    async fn handle_connect(&self, mut client: TcpStream, host: String, port: u16, rest: Vec<u8>) {
+        if !self.policy.permits_tunnel(&host, port) {
+            let reason = format!("{}:{} is not an allowed destination", host, port);
+            let _ = respond(&mut client, "403 Forbidden", &reason).await;
+            self.deny(&host, port, None, reason).await;
+            return;
+        }
+        if client
+            .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+            .await
+            .is_err()
+        {
+            return;
+        }
+
+        let mut first = rest;
+        if first.is_empty() {
+            match timeout(FIRST_BYTES_WAIT, read_some(&mut client, &mut first)).await {
+                Ok(Ok(0)) | Ok(Err(_)) => return,
+                Ok(Ok(_)) | Err(_) => {}
+            }
+        }
+        let protocol = if first.first() == Some(&TLS_HANDSHAKE) {
+            if read_tls_record(&mut client, &mut first).await.is_err() {
+                return;
+            }
+            if let Some(sni) = client_hello_sni(&first) {
+                if !sni.eq_ignore_ascii_case(host.trim_end_matches('.')) {
+                    let reason = format!("TLS SNI '{}' does not match CONNECT host", sni);
+                    self.deny(&host, port, Some(NetworkProtocol::HTTPS), reason)
+                        .await;
+                    return;
+                }
+            }
+            NetworkProtocol::HTTPS
+        } else {
+            NetworkProtocol::TCP
+        };
+        if !self.policy.permits(&host, port, protocol) {
+            let reason = format!(
+                "{}:{} ({:?}) is not an allowed destination",
+                host, port, protocol
+            );
+            self.deny(&host, port, Some(protocol), reason).await;
+            return;
+        }
+        self.tunnel(client, &host, port, protocol, first, false)
+            .await
+    }
+
+    async fn handle_transparent_tls(&self, mut client: TcpStream, mut first: Vec<u8>) {
+        let port = self.transparent_tls_port;
+        if read_tls_record(&mut client, &mut first).await.is_err() {
+            return;
+        }
+        let Some(host) = client_hello_sni(&first) else {
+            self.deny(
+                "",
+                port,
+                Some(NetworkProtocol::HTTPS),
+                "TLS ClientHello without SNI".to_string(),
+            )
+            .await;
+            return;
+        };
+        if !self.policy.permits(&host, port, NetworkProtocol::HTTPS) {
+            let reason = format!("{}:{} (https) is not an allowed destination", host, port);
+            self.deny(&host, port, Some(NetworkProtocol::HTTPS), reason)
+                .await;
+            return;
+        }
+        self.tunnel(client, &host, port, NetworkProtocol::HTTPS, first, false)
+            .await
+    }
+
+    /// Connect upstream, replay `first`, then relay both directions.
+    async fn tunnel(
+        &self,
+        mut client: TcpStream,
+        host: &str,
+        port: u16,
+        protocol: NetworkProtocol,
+        first: Vec<u8>,
+        http_errors: bool,
+    ) {
+        let (mut upstream, ip) = match self.open_upstream(host, port).await {
+            Ok(connected) => connected,
+            Err((decision, reason)) => {
+                if http_errors {
+                    let status = match decision {
+                        EgressDecision::Denied => "403 Forbidden",
+                        _ => "502 Bad Gateway",
+                    };
+                    let _ = respond(&mut client, status, &reason).await;
+                }
+                self.audit(host, port, Some(protocol), None, decision, Some(reason))
+                    .await;
+                return;
+            }
+        };
+        self.audit(
+            host,
+            port,
+            Some(protocol),
+            Some(ip),
+            EgressDecision::Allowed,
+            None,
+        )
+        .await;
+        if upstream.write_all(&first).await.is_err() {
+            return;
+        }
+        let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+    }
+
+    /// Resolve `host`, drop non-public addresses, and connect.
+    async fn open_upstream(
+        &self,
+        host: &str,
+        port: u16,
+    ) -> Result<(TcpStream, IpAddr), (EgressDecision, String)> {
+        let resolved = timeout(UPSTREAM_TIMEOUT, tokio::net::lookup_host((host, port)))
+            .await
+            .map_err(|_| {
+                (
+                    EgressDecision::Failed,
+                    format!("resolving {} timed out", host),
+                )
+            })?
+            .map_err(|e| (EgressDecision::Failed, format!("resolving {}: {}", host, e)))?;
+        let candidates: Vec<SocketAddr> = resolved
+            .filter(|addr| self.policy.allow_private_destinations || !non_public(addr.ip()))
+            .collect();
+        if candidates.is_empty() {
+            return Err((
+                EgressDecision::Denied,
+                format!("SSRF: {} resolves only to non-public addresses", host),
+            ));
+        }
+        let mut last_error = String::new();
+        for addr in candidates {
+            match timeout(UPSTREAM_TIMEOUT, TcpStream::connect(addr)).await {
+                Ok(Ok(stream)) => return Ok((stream, addr.ip())),
+                Ok(Err(e)) => last_error = e.to_string(),
+                Err(_) => last_error = "connect timed out".to_string(),
+            }
+        }
+        Err((
+            EgressDecision::Failed,
+            format!("connecting to {}:{}: {}", host, port, last_error),
+        ))
+    }
+
+    async fn deny(&self, host: &str, port: u16, protocol: Option<NetworkProtocol>, reason: String) {
+        self.audit(
+            host,
+            port,
+            protocol,
+            None,
+            EgressDecision::Denied,
+            Some(reason),
+        )
+        .await;
+    }
+
+    async fn audit(
+        &self,
+        host: &str,
+        port: u16,
+        protocol: Option<NetworkProtocol>,
+        upstream: Option<IpAddr>,
+        decision: EgressDecision,
+        reason: Option<String>,
+    ) {
+        match decision {
+            EgressDecision::Allowed => {
+                tracing::info!(host, port, ?protocol, ?upstream, "Egress allowed")
+            }
+            _ => tracing::warn!(host, port, ?protocol, ?decision, ?reason, "Egress refused"),
+        }
+        if let Some(audit) = &self.audit {
+            audit
+                .record(EgressAuditEvent {
+                    timestamp: Utc::now(),
+                    host: host.to_string(),
+                    port,
+                    protocol,
+                    upstream,
+                    decision,
+                    reason,
+                })
+                .await;
+        }
+    }
+}
+
+/// [`is_non_public_ip`], also seeing through IPv4-mapped IPv6 addresses.
+fn non_public(ip: IpAddr) -> bool {
+    let ip = match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        v4 => v4,
+    };
+    is_non_public_ip(ip)
+}
+
+async fn read_some(stream: &mut TcpStream, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let mut chunk = [0u8; 4096];
+    let n = stream.read(&mut chunk).await?;
+    buf.extend_from_slice(&chunk[..n]);
+    Ok(n)
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Read and parse the request head. Returns the request and any bytes the
+/// client sent after the head.
+async fn read_request(
+    stream: &mut TcpStream,
+    mut buf: Vec<u8>,
+) -> io::Result<(ProxyRequest, Vec<u8>)> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+    let end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() >= MAX_HEAD_BYTES {
+            return Err(invalid("request head too large"));
+        }
+        if read_some(stream, &mut buf).await? == 0 {
+            return Err(invalid("connection closed mid-request"));
+        }
+    };
+    let rest = buf.split_off(end);
+    let head = std::str::from_utf8(&buf).map_err(|_| invalid("request head is not UTF-8"))?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (
+        request_line.next(),
+        request_line.next(),
+        request_line.next(),
+    ) else {
+        return Err(invalid("malformed request line"));
+    };
+
+    if method.eq_ignore_ascii_case("CONNECT") {
+        let (host, port) =
+            split_authority(target).ok_or_else(|| invalid("CONNECT target must be host:port"))?;
+        return Ok((ProxyRequest::Connect { host, port }, rest));
+    }
+
+    let url = url::Url::parse(target)
+        .ok()
+        .filter(|u| u.scheme() == "http")
+        .ok_or_else(|| invalid("only absolute http:// URLs can be forwarded"))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| invalid("URL has no host"))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+    let mut origin = url.path().to_string();
+    if let Some(query) = url.query() {
+        origin.push('?');
+        origin.push_str(query);
+    }
+
+    // Origin-form request line, hop-by-hop proxy headers dropped, and one
+    // request per connection so a keep-alive cannot switch hosts.
+    let mut out = format!("{} {} {}\r\n", method, origin, version);
+    for line in lines.filter(|l| !l.is_empty()) {
+        let name = line.split(':').next().unwrap_or_default().trim();
+        if [
+            "proxy-connection",
+            "proxy-authorization",
+            "connection",
+            "keep-alive",
+        ]
+        .iter()
+        .any(|h| name.eq_ignore_ascii_case(h))
+        {
+            continue;
+        }
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    out.push_str("Connection: close\r\n\r\n");
+    Ok((
+        ProxyRequest::Forward {
+            host,
+            port,
+            head: out.into_bytes(),
+        },
+        rest,
+    ))
+}
+
+/// Split `host:port` or `[v6]:port`.
+fn split_authority(authority: &str) -> Option<(String, u16)> {
+    let (host, port) = authority.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return None;
+    }
+    Some((host.to_string(), port.parse().ok()?))
+}
+
+const TLS_HANDSHAKE: u8 = 0x16;
+
+/// Read until `buf` holds the complete first TLS record.
+async fn read_tls_record(stream: &mut TcpStream, buf: &mut Vec<u8>) -> io::Result<()> {
+    loop {
+        if buf.len() >= 5 {
+            let len = u16::from_be_bytes([buf[3], buf[4]]) as usize;
+            if len > MAX_TLS_RECORD {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "oversized TLS record",
+                ));
+            }
+            if buf.len() >= 5 + len {
+                return Ok(());
+            }
+        }
+        if read_some(stream, buf).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+    }
+}
+
+/// Server name from the ClientHello in the first TLS record, if any.
+fn client_hello_sni(record: &[u8]) -> Option<String> {
+    struct Reader<'a>(&'a [u8]);
+    impl<'a> Reader<'a> {
+        fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+            if self.0.len() < n {
+                return None;
+            }
+            let (head, tail) = self.0.split_at(n);
+            self.0 = tail;
+            Some(head)
+        }
+        fn u8(&mut self) -> Option<usize> {
+            self.take(1).map(|b| b[0] as usize)
+        }
+        fn u16(&mut self) -> Option<usize> {
+            self.take(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+        }
+        fn vec8(&mut self) -> Option<&'a [u8]> {
+            let n = self.u8()?;
+            self.take(n)
+        }
+        fn vec16(&mut self) -> Option<&'a [u8]> {
+            let n = self.u16()?;
+            self.take(n)
+        }
+    }
+
+    let mut r = Reader(record);
+    if r.u8()? != TLS_HANDSHAKE as usize {
+        return None;
+    }
+    r.take(2)?; // record version
+    let mut r = Reader(r.vec16()?);
+    if r.u8()? != 1 {
+        return None; // not a ClientHello
+    }
+    r.take(3)?; // handshake length
+    r.take(2 + 32)?; // client version, random
+    r.vec8()?; // session id
+    r.vec16()?; // cipher suites
+    r.vec8()?; // compression methods
+    let mut extensions = Reader(r.vec16()?);
+    while !extensions.0.is_empty() {
+        let kind = extensions.u16()?;
+        let data = extensions.vec16()?;
+        if kind != 0 {
+            continue;
+        }
+        let mut names = Reader(Reader(data).vec16()?);
+        while !names.0.is_empty() {
+            let name_type = names.u8()?;
+            let name = names.vec16()?;
+            if name_type == 0 {
+                return std::str::from_utf8(name).ok().map(str::to_string);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NetworkDestination;
+    use tokio::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<EgressAuditEvent>>);
+
+    #[async_trait]
+    impl EgressAudit for Recorder {
+        async fn record(&self, event: EgressAuditEvent) {
+            self.0.lock().await.push(event);
+        }
+    }
+
+    fn restricted(destinations: Vec<NetworkDestination>) -> EgressPolicy {
+        EgressPolicy::from_network_policy(&NetworkPolicy {
+            access_mode: NetworkAccessMode::Restricted,
+            allowed_destinations: destinations,
+            max_bandwidth_mbps: None,
+        })
+    }
+
+    fn dest(
+        host: &str,
+        port: Option<u16>,
+        protocol: Option<NetworkProtocol>,
+    ) -> NetworkDestination {
+        NetworkDestination {
+            host: host.to_string(),
+            port,
+            protocol,
+        }
+    }
+
+    fn client_hello(sni: &str) -> Vec<u8> {
+        let name = sni.as_bytes();
+        let mut list = vec![0u8];
+        list.extend((name.len() as u16).to_be_bytes());
+        list.extend(name);
+        let mut ext = vec![0u8, 0];
+        ext.extend(((list.len() + 2) as u16).to_be_bytes());
+        ext.extend((list.len() as u16).to_be_bytes());
+        ext.extend(list);
+        let mut body = vec![0x03, 0x03];
+        body.extend([0u8; 32]);
+        body.extend([0, 0, 2, 0x13, 0x01, 1, 0]);
+        body.extend((ext.len() as u16).to_be_bytes());
+        body.extend(ext);
+        let mut handshake = vec![1, 0];
+        handshake.extend((body.len() as u16).to_be_bytes());
+        handshake.extend(body);
+        let mut record = vec![TLS_HANDSHAKE, 0x03, 0x01];
+        record.extend((handshake.len() as u16).to_be_bytes());
+        record.extend(handshake);
+        record
+    }
+
+    /// Upstream on loopback that echoes what it receives.
+    async fn echo_upstream() -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let (mut r, mut w) = stream.split();
+                    let _ = tokio::io::copy(&mut r, &mut w).await;
+                });
+            }
+        });
+        addr
+    }
+
+    async fn start(policy: EgressPolicy) -> (EgressProxyHandle, Arc<Recorder>) {
+        let recorder = Arc::new(Recorder::default());
+        let handle = EgressProxy::new(policy)
+            .with_audit(recorder.clone())
+            .listen("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        (handle, recorder)
+    }
+
+    async fn connect(proxy: SocketAddr, target: SocketAddr) -> (TcpStream, String) {
+        let mut stream = TcpStream::connect(proxy).await.unwrap();
+        stream
+            .write_all(
+                format!("CONNECT {} HTTP/1.1\r\nHost: {}\r\n\r\n", target, target).as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut buf = Vec::new();
+        while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            if read_some(&mut stream, &mut buf).await.unwrap() == 0 {
+                break;
+            }
+        }
+        let status = String::from_utf8_lossy(&buf)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        (stream, status)
+    }
+
+    async fn events(recorder: &Recorder) -> Vec<EgressAuditEvent> {
+        // Audit records land just after the relay starts or the refusal is sent.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        recorder.0.lock().await.clone()
+    }
+
+    #[test]
+    fn policy_matches_host_port_and_protocol() {
+        let policy = restricted(vec![
+            dest("api.example.com", Some(443), Some(NetworkProtocol::HTTPS)),
+            dest("*.internal.example", None, Some(NetworkProtocol::TCP)),
+            dest("Mirror.Example.org.", None, None),
+        ]);
+        assert!(policy.permits("api.example.com", 443, NetworkProtocol::HTTPS));
+        assert!(!policy.permits("api.example.com", 443, NetworkProtocol::TCP));
+        assert!(!policy.permits("api.example.com", 80, NetworkProtocol::HTTPS));
+        assert!(!policy.permits("evil.api.example.com", 443, NetworkProtocol::HTTPS));
+        assert!(policy.permits("db.internal.example", 5432, NetworkProtocol::TCP));
+        assert!(policy.permits("db.internal.example", 443, NetworkProtocol::HTTPS));
+        assert!(!policy.permits("internal.example", 5432, NetworkProtocol::TCP));
+        assert!(policy.permits("mirror.example.org", 80, NetworkProtocol::HTTP));
+        assert!(!policy.permits("mirror.example.org", 53, NetworkProtocol::UDP));
+
+        let mut none = policy.clone();
+        none.network.access_mode = NetworkAccessMode::None;
+        assert!(!none.permits("api.example.com", 443, NetworkProtocol::HTTPS));
+        let mut full = policy;
+        full.network.access_mode = NetworkAccessMode::Full;
+        assert!(full.permits("anything.example", 22, NetworkProtocol::TCP));
+    }
+
+    #[test]
+    fn sni_is_read_from_client_hello() {
+        assert_eq!(
+            client_hello_sni(&client_hello("api.example.com")).as_deref(),
+            Some("api.example.com")
+        );
+        assert_eq!(client_hello_sni(b"\x16\x03\x01\x00\x02\x01\x00"), None);
+        assert_eq!(client_hello_sni(b"GET / HTTP/1.1\r\n"), None);
+    }
+
+    #[tokio::test]
+    async fn connect_tunnel_to_allowed_destination_is_relayed_and_audited() {
+        let upstream = echo_upstream().await;
+        let mut policy = restricted(vec![dest("127.0.0.1", Some(upstream.port()), None)]);
+        policy.allow_private_destinations = true;
+        let (proxy, recorder) = start(policy).await;
+
+        let (mut stream, status) = connect(proxy.local_addr(), upstream).await;
+        assert_eq!(status, "HTTP/1.1 200 Connection established");
+        stream.write_all(b"ping").await.unwrap();
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply).await.unwrap();
+        assert_eq!(&reply, b"ping");
+
+        let events = events(&recorder).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].decision, EgressDecision::Allowed);
+        assert_eq!(events[0].protocol, Some(NetworkProtocol::TCP));
+        assert_eq!(events[0].upstream, Some(upstream.ip()));
+    }
+
+    #[tokio::test]
+    async fn unlisted_destination_is_refused() {
+        let upstream = echo_upstream().await;
+        let mut policy = restricted(vec![dest("127.0.0.1", Some(1), None)]);
+        policy.allow_private_destinations = true;
+        let (proxy, recorder) = start(policy).await;
+
+        let (_, status) = connect(proxy.local_addr(), upstream).await;
+        assert!(status.starts_with("HTTP/1.1 403"), "{}", status);
+        let events = events(&recorder).await;
+        assert_eq!(events[0].decision, EgressDecision::Denied);
+        assert_eq!(events[0].port, upstream.port());
+    }
+
+    #[tokio::test]
+    async fn allowed_name_resolving_to_loopback_is_refused() {
+        let upstream = echo_upstream().await;
+        let (proxy, recorder) = start(restricted(vec![dest(
+            "localhost",
+            Some(upstream.port()),
+            None,
+        )]))
+        .await;
+
+        let mut stream = TcpStream::connect(proxy.local_addr()).await.unwrap();
+        let request = format!(
+            "GET http://localhost:{}/ HTTP/1.1\r\nHost: localhost\r\n\r\n",
+            upstream.port()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 403"), "{}", response);
+        let events = events(&recorder).await;
+        assert_eq!(events[0].decision, EgressDecision::Denied);
+        assert!(events[0].reason.as_deref().unwrap().contains("SSRF"));
+    }
+
+    #[tokio::test]
+    async fn plain_http_is_forwarded_in_origin_form() {
+        let upstream = echo_upstream().await;
+        let mut policy = restricted(vec![dest(
+            "127.0.0.1",
+            Some(upstream.port()),
+            Some(NetworkProtocol::HTTP),
+        )]);
+        policy.allow_private_destinations = true;
+        let (proxy, _) = start(policy).await;
+
+        let mut stream = TcpStream::connect(proxy.local_addr()).await.unwrap();
+        let request = format!(
+            "GET http://{}/data?q=1 HTTP/1.1\r\nHost: {}\r\nProxy-Connection: keep-alive\r\n\r\n",
+            upstream, upstream
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut echoed = Vec::new();
+        while !echoed.windows(4).any(|w| w == b"\r\n\r\n") {
+            read_some(&mut stream, &mut echoed).await.unwrap();
+        }
+        let echoed = String::from_utf8(echoed).unwrap();
+        assert!(
+            echoed.starts_with("GET /data?q=1 HTTP/1.1\r\n"),
+            "{}",
+            echoed
+        );
+        assert!(echoed.contains("Connection: close"));
+        assert!(!echoed.contains("Proxy-Connection"));
+    }
+
+    #[tokio::test]
+    async fn tls_sni_must_match_connect_host() {
+        let upstream = echo_upstream().await;
+        let mut policy = restricted(vec![dest("127.0.0.1", Some(upstream.port()), None)]);
+        policy.allow_private_destinations = true;
+        let (proxy, recorder) = start(policy).await;
+
+        let (mut stream, status) = connect(proxy.local_addr(), upstream).await;
+        assert!(status.contains("200"));
+        stream
+            .write_all(&client_hello("blocked.example"))
+            .await
+            .unwrap();
+        let mut buf = Vec::new();
+        let _ = stream.read_to_end(&mut buf).await;
+        assert!(buf.is_empty());
+        let events = events(&recorder).await;
+        assert_eq!(events[0].decision, EgressDecision::Denied);
+        assert!(events[0].reason.as_deref().unwrap().contains("SNI"));
+    }
+}
@@ -16,6 +16,12 @@
 //! drop directory, then execs `firecracker --no-api --config-file <path>`.
 //! Stdout/stderr come back over the VM's serial console.
 //!
+//! Requests that stage files write them under `inputs/` in the same drop
+//! directory, and artifacts are collected from `outputs/` once the VM has
+//! exited. The rootfs init is expected to expose both through the host share
+//! and run the code with `inputs/` as its working directory, copying results
+//! into `outputs/`.
+//!
 //! # Failure modes
 //!
 //! If the operator hasn't configured `[sandbox.firecracker]` in
@@ -32,6 +38,7 @@ use tokio::io::AsyncReadExt;
 use tokio::process::Command;
 use tokio::time::{timeout, Duration};
 
+use super::artifacts::{stage_files, ArtifactCollector, ExecutionRequest};
 use super::{ExecutionResult, SandboxRunner};
 
 /// Default per-execution memory budget (MiB).
@@ -233,6 +240,22 @@ impl SandboxRunner for FirecrackerRunner {
         code: &str,
         env: HashMap<String, String>,
     ) -> Result<ExecutionResult, anyhow::Error> {
+        self.execute_request(ExecutionRequest::new(code).with_env(env))
+            .await
+    }
+
+    async fn execute_request(
+        &self,
+        request: ExecutionRequest,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        request.validate()?;
+        let ExecutionRequest {
+            code,
+            env,
+            files,
+            artifacts: artifact_spec,
+        } = request;
+
         // Per-execution work directory holds the VM config and serial log.
         let work_root = self
             .config
@@ -254,6 +277,13 @@ impl SandboxRunner for FirecrackerRunner {
         // from /proc/cmdline if needed.
         tokio::fs::write(&code_path, code).await?;
 
+        // Staged inputs and the artifact drop share the same host directory.
+        let inputs_dir = work_dir.join("inputs");
+        let outputs_dir = work_dir.join("outputs");
+        tokio::fs::create_dir_all(&inputs_dir).await?;
+        tokio::fs::create_dir_all(&outputs_dir).await?;
+        stage_files(&inputs_dir, &files).await?;
+
         // Encode env for the in-VM init to consume.
         let env_json = serde_json::to_string(&env)?;
         tokio::fs::write(work_dir.join("env.json"), env_json).await?;
@@ -306,7 +336,15 @@ impl SandboxRunner for FirecrackerRunner {
         match result {
             Ok((stdout, stdout_trunc, stderr, stderr_trunc, Ok(status))) => {
                 let exit_code = status.code().unwrap_or(-1);
+                let collected = match &artifact_spec {
+                    Some(spec) => match ArtifactCollector::new(spec) {
+                        Ok(collector) => collector.collect_dir(&outputs_dir).await,
+                        Err(e) => Err(e),
+                    },
+                    None => Ok((Vec::new(), false)),
+                };
                 let _ = tokio::fs::remove_dir_all(&work_dir).await;
+                let (artifacts, artifacts_truncated) = collected?;
                 Ok(ExecutionResult {
                     exit_code,
                     stdout,
@@ -315,6 +353,8 @@ impl SandboxRunner for FirecrackerRunner {
                     success: status.success(),
                     stdout_truncated: stdout_trunc,
                     stderr_truncated: stderr_trunc,
+                    artifacts,
+                    artifacts_truncated,
                 })
             }
             Ok((stdout, stdout_trunc, stderr, stderr_trunc, Err(e))) => {
@@ -327,6 +367,8 @@ impl SandboxRunner for FirecrackerRunner {
                     success: false,
                     stdout_truncated: stdout_trunc,
                     stderr_truncated: stderr_trunc,
+                    artifacts: Vec::new(),
+                    artifacts_truncated: false,
                 })
             }
             Err(_) => {
@@ -405,4 +447,40 @@ mod tests {
         let err = cfg.validate().expect_err("vcpus=0 should be rejected");
         assert!(err.to_string().contains("vcpus"));
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn inputs_are_staged_and_outputs_collected() {
+        use crate::sandbox::{ArtifactSpec, StagedFile};
+        use std::os::unix::fs::PermissionsExt;
+
+        // Stand-in for the VM: works on the drop directory the way a rootfs
+        // init would through its host share.
+        let dir = tempfile::tempdir().unwrap();
+        let fake = dir.path().join("firecracker");
+        std::fs::write(
+            &fake,
+            "#!/bin/sh\nd=$(dirname \"$3\")\ntr a-z A-Z < \"$d/inputs/in/msg.txt\" > \"$d/outputs/msg.txt\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&fake, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let runner = FirecrackerRunner {
+            config: FirecrackerConfig {
+                firecracker_binary: fake.to_string_lossy().into_owned(),
+                work_dir: Some(dir.path().to_path_buf()),
+                ..Default::default()
+            },
+        };
+        let request = ExecutionRequest::new("cat in/msg.txt")
+            .with_file(StagedFile::bytes("in/msg.txt", "staged"))
+            .collect(ArtifactSpec::new(["*.txt"]));
+        let result = runner.execute_request(request).await.unwrap();
+
+        assert!(result.success, "stderr: {}", result.stderr);
+        assert_eq!(result.artifacts.len(), 1);
+        assert_eq!(result.artifacts[0].path, "msg.txt");
+        assert_eq!(result.artifacts[0].content, b"STAGED");
+        assert!(!result.artifacts_truncated);
+    }
 }
@@ -19,7 +19,8 @@ use std::collections::HashMap;
 use std::process::Stdio;
 
 use super::docker::{DockerConfig, DockerRunner};
-use super::{ExecutionResult, SandboxRunner};
+use super::egress::EgressAudit;
+use super::{ExecutionRequest, ExecutionResult, SandboxRunner, SandboxSession};
 
 /// Configuration for gVisor sandbox execution. Layers on top of
 /// `DockerConfig` since gVisor runs as a Docker OCI runtime.
@@ -97,6 +98,13 @@ impl GVisorRunner {
         );
         Ok(Self { inner })
     }
+
+    /// Record egress decisions through `audit`. No effect without
+    /// `docker.egress`.
+    pub fn with_egress_audit(mut self, audit: std::sync::Arc<dyn EgressAudit>) -> Self {
+        self.inner = self.inner.with_egress_audit(audit);
+        self
+    }
 }
 
 #[async_trait]
@@ -108,6 +116,17 @@ impl SandboxRunner for GVisorRunner {
     ) -> Result<ExecutionResult, anyhow::Error> {
         self.inner.execute(code, env).await
     }
+
+    async fn execute_request(
+        &self,
+        request: ExecutionRequest,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        self.inner.execute_request(request).await
+    }
+
+    async fn open_session(&self) -> Result<Box<dyn SandboxSession>, anyhow::Error> {
+        self.inner.open_session().await
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,537 @@
+//! Stateful interpreter kernels
+//!
+//! A kernel keeps one Python or Node.js interpreter alive inside a
+//! [`SandboxSessionManager`] session, so variables, imports and loaded data
+//! survive between code cells instead of being re-serialized into every
+//! script. A small driver (`kernel/driver.py`, `kernel/driver.js`) is
+//! written into the session's working directory and started in the
+//! background; each cell is handed to it through a file under
+//! `.symbi-kernel/<language>/` and its outputs come back Jupyter-style:
+//! stream text, results and displays as MIME bundles, and errors.
+//!
+//! The sandbox image must provide the interpreter (`python3` or `node`) and
+//! a POSIX shell with `base64`.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::artifacts::ExecutionRequest;
+use super::session::{SandboxSessionId, SandboxSessionManager, SessionBinding};
+
+/// Directory under the session's working directory that holds kernel state
+const KERNEL_DIR: &str = ".symbi-kernel";
+
+/// How often the in-sandbox shell polls for the driver's answer
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Extra time the shell waits past `cell_timeout` before giving up on a
+/// driver that did not enforce the limit itself
+const TIMEOUT_GRACE: Duration = Duration::from_secs(5);
+
+/// Interpreter a kernel runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KernelLanguage {
+    Python,
+    Node,
+}
+
+impl KernelLanguage {
+    fn name(self) -> &'static str {
+        match self {
+            KernelLanguage::Python => "python",
+            KernelLanguage::Node => "node",
+        }
+    }
+
+    fn interpreter(self) -> &'static str {
+        match self {
+            KernelLanguage::Python => "python3",
+            KernelLanguage::Node => "node",
+        }
+    }
+
+    fn driver(self) -> (&'static str, &'static str) {
+        match self {
+            KernelLanguage::Python => ("driver.py", include_str!("kernel/driver.py")),
+            KernelLanguage::Node => ("driver.js", include_str!("kernel/driver.js")),
+        }
+    }
+}
+
+impl std::fmt::Display for KernelLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Kernel language and time limits
+#[derive(Debug, Clone)]
+pub struct KernelConfig {
+    /// Interpreter to run
+    pub language: KernelLanguage,
+    /// Longest a single cell may run before the driver interrupts it. Keep
+    /// this below the runner's own execution timeout.
+    pub cell_timeout: Duration,
+    /// Longest to wait for the interpreter to come up
+    pub startup_timeout: Duration,
+}
+
+impl KernelConfig {
+    pub fn new(language: KernelLanguage) -> Self {
+        Self {
+            language,
+            cell_timeout: Duration::from_secs(120),
+            startup_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Default for KernelConfig {
+    fn default() -> Self {
+        Self::new(KernelLanguage::Python)
+    }
+}
+
+/// Representations of one value keyed by MIME type (`text/plain`,
+/// `image/png` as base64, `application/json`,
+/// `application/vnd.dataresource+json` for tables, ...)
+pub type MimeBundle = BTreeMap<String, serde_json::Value>;
+
+/// One output of a code cell, in the shape Jupyter uses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "output_type", rename_all = "snake_case")]
+pub enum CellOutput {
+    /// Text written to stdout or stderr
+    Stream { name: String, text: String },
+    /// Value of the cell's final expression
+    ExecuteResult {
+        execution_count: u64,
+        data: MimeBundle,
+    },
+    /// Value passed to `display()` or a plot the cell produced
+    DisplayData { data: MimeBundle },
+    /// Exception that ended the cell
+    Error {
+        ename: String,
+        evalue: String,
+        traceback: Vec<String>,
+    },
+}
+
+/// Outputs of one executed cell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellResult {
+    /// Position of the cell in the kernel's history, starting at 1
+    pub execution_count: u64,
+    pub outputs: Vec<CellOutput>,
+    pub duration: Duration,
+}
+
+impl CellResult {
+    /// Whether the cell ran to completion without raising
+    pub fn success(&self) -> bool {
+        !self
+            .outputs
+            .iter()
+            .any(|o| matches!(o, CellOutput::Error { .. }))
+    }
+
+    /// Plain-text rendering of the outputs: streams verbatim, results and
+    /// displays by their `text/plain` form, errors with their traceback.
+    pub fn text(&self) -> String {
+        let mut parts = Vec::new();
+        for output in &self.outputs {
+            match output {
+                CellOutput::Stream { text, .. } => {
+                    parts.push(text.trim_end_matches('\n').to_string())
+                }
+                CellOutput::ExecuteResult {
+                    execution_count,
+                    data,
+                } => parts.push(format!("Out[{}]: {}", execution_count, plain_text(data))),
+                CellOutput::DisplayData { data } => parts.push(plain_text(data)),
+                CellOutput::Error {
+                    ename,
+                    evalue,
+                    traceback,
+                } => {
+                    let trace: Vec<&str> = traceback
+                        .iter()
+                        .map(|line| line.trim_end_matches('\n'))
+                        .collect();
+                    if trace.is_empty() {
+                        parts.push(format!("{}: {}", ename, evalue));
+                    } else {
+                        parts.push(trace.join("\n"));
+                    }
+                }
+            }
+        }
+        parts.join("\n")
+    }
+}
+
+fn plain_text(data: &MimeBundle) -> String {
+    let rich: Vec<&str> = data
+        .keys()
+        .map(String::as_str)
+        .filter(|mime| *mime != "text/plain")
+        .collect();
+    let text = data
+        .get("text/plain")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default();
+    if rich.is_empty() {
+        text.to_string()
+    } else {
+        format!("{} [{}]", text, rich.join(", "))
+    }
+}
+
+/// A persistent interpreter running inside a sandbox session
+pub struct Kernel {
+    sessions: Arc<SandboxSessionManager>,
+    session: SessionBinding,
+    config: KernelConfig,
+    execution_count: AtomicU64,
+}
+
+impl Kernel {
+    /// Start an interpreter in an open session on behalf of its owner.
+    /// Fails if the sandbox lacks the interpreter or it does not come up
+    /// within `startup_timeout`.
+    pub async fn start(
+        sessions: Arc<SandboxSessionManager>,
+        session: SessionBinding,
+        config: KernelConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let kernel = Self {
+            sessions,
+            session,
+            config,
+            execution_count: AtomicU64::new(0),
+        };
+        kernel.launch().await?;
+        Ok(kernel)
+    }
+
+    /// Session the kernel runs in
+    pub fn session(&self) -> SandboxSessionId {
+        self.session.id
+    }
+
+    pub fn language(&self) -> KernelLanguage {
+        self.config.language
+    }
+
+    /// Kill the interpreter and start a fresh one. All kernel state is lost;
+    /// files in the session are kept.
+    pub async fn restart(&self) -> Result<(), anyhow::Error> {
+        self.launch().await
+    }
+
+    /// Run one cell and return its outputs. Errors raised by the code are
+    /// outputs, not `Err`; `Err` means the kernel itself failed.
+    pub async fn run_cell(&self, code: &str) -> Result<CellResult, anyhow::Error> {
+        let count = self.execution_count.fetch_add(1, Ordering::SeqCst) + 1;
+        let cell = serde_json::json!({
+            "code": code,
+            "timeout_secs": self.config.cell_timeout.as_secs_f64(),
+        });
+        let script = format!(
+            r#"d={dir}
+printf '%s' '{cell}' | base64 -d > "$d/in/.{n}.tmp"
+mv "$d/in/.{n}.tmp" "$d/in/{n}.cell"
+i=0
+while [ ! -f "$d/out/{n}.json" ]; do
+  if ! kill -0 "$(cat "$d/pid")" 2>/dev/null; then echo "kernel exited" >&2; tail -n 20 "$d/kernel.log" >&2; exit 70; fi
+  i=$((i+1)); if [ "$i" -gt {polls} ]; then echo "kernel did not answer within the cell timeout" >&2; exit 124; fi
+  sleep {interval}
+done
+cat "$d/out/{n}.json"
+rm -f "$d/out/{n}.json""#,
+            dir = self.dir(),
+            cell = encode(cell.to_string().as_bytes()),
+            n = count,
+            polls = polls(self.config.cell_timeout + TIMEOUT_GRACE),
+            interval = POLL_INTERVAL.as_secs_f64(),
+        );
+
+        let start = Instant::now();
+        let result = self
+            .sessions
+            .execute(
+                self.session.id,
+                self.session.owner,
+                ExecutionRequest::new(script),
+            )
+            .await?;
+        if !result.success {
+            anyhow::bail!(
+                "{} kernel failed running cell {}: {}",
+                self.config.language,
+                count,
+                result.stderr.trim()
+            );
+        }
+        if result.stdout_truncated {
+            anyhow::bail!(
+                "cell {} produced more output than the sandbox returns",
+                count
+            );
+        }
+        let outputs: Vec<CellOutput> = serde_json::from_str(&result.stdout)
+            .map_err(|e| anyhow::anyhow!("unreadable output from cell {}: {}", count, e))?;
+        Ok(CellResult {
+            execution_count: count,
+            outputs,
+            duration: start.elapsed(),
+        })
+    }
+
+    /// Stop the interpreter. Closing the session stops it too.
+    pub async fn shutdown(&self) -> Result<(), anyhow::Error> {
+        let script = format!(
+            r#"d={dir}
+[ -f "$d/pid" ] && kill "$(cat "$d/pid")" 2>/dev/null
+rm -f "$d/pid" "$d/ready""#,
+            dir = self.dir()
+        );
+        self.sessions
+            .execute(
+                self.session.id,
+                self.session.owner,
+                ExecutionRequest::new(script),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn launch(&self) -> Result<(), anyhow::Error> {
+        let language = self.config.language;
+        let (file, driver) = language.driver();
+        let script = format!(
+            r#"d={dir}
+if [ -f "$d/pid" ]; then kill "$(cat "$d/pid")" 2>/dev/null; fi
+rm -rf "$d/in" "$d/out" "$d/ready"
+mkdir -p "$d/in" "$d/out"
+command -v {interp} >/dev/null 2>&1 || {{ echo "{interp} is not installed in the sandbox" >&2; exit 127; }}
+printf '%s' '{driver}' | base64 -d > "$d/{file}"
+nohup {interp} "$d/{file}" "$d" > "$d/kernel.log" 2>&1 &
+echo $! > "$d/pid"
+i=0
+while [ ! -f "$d/ready" ]; do
+  if ! kill -0 "$(cat "$d/pid")" 2>/dev/null; then cat "$d/kernel.log" >&2; exit 1; fi
+  i=$((i+1)); if [ "$i" -gt {polls} ]; then echo "kernel did not start" >&2; exit 1; fi
+  sleep {interval}
+done"#,
+            dir = self.dir(),
+            interp = language.interpreter(),
+            driver = encode(driver.as_bytes()),
+            file = file,
+            polls = polls(self.config.startup_timeout),
+            interval = POLL_INTERVAL.as_secs_f64(),
+        );
+        let result = self
+            .sessions
+            .execute(
+                self.session.id,
+                self.session.owner,
+                ExecutionRequest::new(script),
+            )
+            .await?;
+        if !result.success {
+            anyhow::bail!(
+                "failed to start {} kernel: {}",
+                language,
+                result.stderr.trim()
+            );
+        }
+        self.execution_count.store(0, Ordering::SeqCst);
+        tracing::info!(
+            "Started {} kernel in sandbox session {}",
+            language,
+            self.session.id
+        );
+        Ok(())
+    }
+
+    fn dir(&self) -> String {
+        format!("{}/{}", KERNEL_DIR, self.config.language.name())
+    }
+}
+
+fn encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn polls(limit: Duration) -> u128 {
+    limit.as_millis() / POLL_INTERVAL.as_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sandbox::{ExecutionResult, SandboxRunner, SandboxSession, SessionConfig};
+    use crate::types::AgentId;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+
+    /// Session that runs scripts with the host shell in a scratch directory
+    struct HostShellSession(tempfile::TempDir);
+
+    #[async_trait]
+    impl SandboxSession for HostShellSession {
+        async fn execute(
+            &self,
+            request: ExecutionRequest,
+        ) -> Result<ExecutionResult, anyhow::Error> {
+            let output = tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg(&request.code)
+                .current_dir(self.0.path())
+                .output()
+                .await?;
+            let mut result =
+                ExecutionResult::success(String::from_utf8_lossy(&output.stdout).into_owned(), 0);
+            result.stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+            result.exit_code = output.status.code().unwrap_or(-1);
+            result.success = output.status.success();
+            Ok(result)
+        }
+
+        async fn close(&self) -> Result<(), anyhow::Error> {
+            Ok(())
+        }
+    }
+
+    struct HostShellRunner;
+
+    #[async_trait]
+    impl SandboxRunner for HostShellRunner {
+        async fn execute(
+            &self,
+            _code: &str,
+            _env: HashMap<String, String>,
+        ) -> Result<ExecutionResult, anyhow::Error> {
+            unreachable!("sessions only")
+        }
+
+        async fn open_session(&self) -> Result<Box<dyn SandboxSession>, anyhow::Error> {
+            Ok(Box::new(HostShellSession(tempfile::tempdir()?)))
+        }
+    }
+
+    async fn start(language: KernelLanguage) -> Option<Kernel> {
+        if std::process::Command::new(language.interpreter())
+            .arg("--version")
+            .output()
+            .is_err()
+        {
+            eprintln!("skipping: {} not available", language.interpreter());
+            return None;
+        }
+        let sessions = Arc::new(SandboxSessionManager::new(
+            Arc::new(HostShellRunner),
+            SessionConfig::default(),
+        ));
+        let owner = AgentId::new();
+        let id = sessions.open(owner).await.unwrap();
+        let session = SessionBinding { id, owner };
+        let config = KernelConfig {
+            cell_timeout: Duration::from_secs(2),
+            ..KernelConfig::new(language)
+        };
+        Some(Kernel::start(sessions, session, config).await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn python_state_survives_between_cells() {
+        let Some(kernel) = start(KernelLanguage::Python).await else {
+            return;
+        };
+
+        let first = kernel.run_cell("x = 40\nprint('set')").await.unwrap();
+        assert!(first.success());
+        assert_eq!(
+            first.outputs,
+            vec![CellOutput::Stream {
+                name: "stdout".into(),
+                text: "set\n".into()
+            }]
+        );
+
+        let second = kernel.run_cell("x + 2").await.unwrap();
+        assert_eq!(second.execution_count, 2);
+        assert_eq!(second.text(), "Out[2]: 42");
+
+        let rich = kernel
+            .run_cell("display({'a': [1, 2]})\nexit(3)")
+            .await
+            .unwrap();
+        assert!(!rich.success());
+        match &rich.outputs[0] {
+            CellOutput::DisplayData { data } => {
+                assert_eq!(data["application/json"], serde_json::json!({"a": [1, 2]}))
+            }
+            other => panic!("unexpected output {:?}", other),
+        }
+        assert!(
+            matches!(&rich.outputs[1], CellOutput::Error { ename, .. } if ename == "SystemExit")
+        );
+
+        // The cell timeout interrupts runaway code without losing state.
+        let slow = kernel.run_cell("while True: pass").await.unwrap();
+        assert!(
+            matches!(&slow.outputs[0], CellOutput::Error { ename, .. } if ename == "CellTimeout")
+        );
+        assert_eq!(kernel.run_cell("x").await.unwrap().text(), "Out[5]: 40");
+
+        kernel.restart().await.unwrap();
+        let gone = kernel.run_cell("x").await.unwrap();
+        assert_eq!(gone.execution_count, 1);
+        assert!(
+            matches!(&gone.outputs[0], CellOutput::Error { ename, .. } if ename == "NameError")
+        );
+        kernel.shutdown().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn node_returns_tables_and_errors() {
+        let Some(kernel) = start(KernelLanguage::Node).await else {
+            return;
+        };
+
+        kernel
+            .run_cell("const rows = [{n: 1}, {n: 2}]; console.log('ok')")
+            .await
+            .unwrap();
+        let table = kernel.run_cell("rows").await.unwrap();
+        match &table.outputs[0] {
+            CellOutput::ExecuteResult { data, .. } => {
+                let resource = &data["application/vnd.dataresource+json"];
+                assert_eq!(resource["schema"]["fields"][0]["name"], "n");
+                assert_eq!(resource["data"][1]["n"], 2);
+            }
+            other => panic!("unexpected output {:?}", other),
+        }
+
+        let failed = kernel.run_cell("missing.value").await.unwrap();
+        assert!(!failed.success());
+        assert!(
+            matches!(&failed.outputs[0], CellOutput::Error { ename, .. } if ename == "ReferenceError")
+        );
+
+        let awaited = kernel
+            .run_cell("new Promise((r) => setTimeout(() => r(rows.length), 10))")
+            .await
+            .unwrap();
+        assert_eq!(awaited.text(), "Out[4]: 2");
+        kernel.shutdown().await.unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+// Symbiont kernel driver (Node.js).
+//
+// Runs inside a sandbox session and executes code cells against one
+// persistent VM context. Cells arrive as JSON files in <root>/in/<n>.cell;
+// each result is written atomically to <root>/out/<n>.json as a list of
+// Jupyter-style outputs (stream, execute_result, display_data, error).
+
+"use strict";
+
+const fs = require("fs");
+const path = require("path");
+const util = require("util");
+const vm = require("vm");
+
+const ROOT = process.argv[2];
+const INBOX = path.join(ROOT, "in");
+const OUTBOX = path.join(ROOT, "out");
+
+let outputs = [];
+
+function stream(name) {
+  return (...args) => {
+    const text = util.format(...args) + "\n";
+    const last = outputs[outputs.length - 1];
+    if (last && last.output_type === "stream" && last.name === name) {
+      last.text += text;
+    } else {
+      outputs.push({ output_type: "stream", name, text });
+    }
+  };
+}
+
+function bundle(value) {
+  const data = {};
+  if (Buffer.isBuffer(value) && value.length > 8 && value.readUInt32BE(0) === 0x89504e47) {
+    data["image/png"] = value.toString("base64");
+  } else if (
+    Array.isArray(value) &&
+    value.length > 0 &&
+    value.every((row) => row && typeof row === "object" && !Array.isArray(row))
+  ) {
+    const fields = [...new Set(value.flatMap((row) => Object.keys(row)))];
+    data["application/vnd.dataresource+json"] = {
+      schema: { fields: fields.map((name) => ({ name })) },
+      data: value,
+    };
+  }
+  if (value !== null && typeof value === "object" && !Buffer.isBuffer(value)) {
+    try {
+      data["application/json"] = JSON.parse(JSON.stringify(value));
+    } catch (_) {
+      // Cyclic or otherwise unserializable; text/plain still describes it.
+    }
+  }
+  data["text/plain"] = util.inspect(value);
+  return data;
+}
+
+const console_ = {
+  log: stream("stdout"),
+  info: stream("stdout"),
+  debug: stream("stdout"),
+  warn: stream("stderr"),
+  error: stream("stderr"),
+  table: (rows) => outputs.push({ output_type: "display_data", data: bundle(rows) }),
+};
+
+const context = vm.createContext({
+  console: console_,
+  display: (...values) => {
+    for (const value of values) {
+      outputs.push({ output_type: "display_data", data: bundle(value) });
+    }
+  },
+  require,
+  process,
+  Buffer,
+  setTimeout,
+  clearTimeout,
+  setInterval,
+  clearInterval,
+  URL,
+  TextEncoder,
+  TextDecoder,
+});
+
+async function runCell(code, count, timeoutMs) {
+  const options = { filename: `<cell-${count}>` };
+  if (timeoutMs > 0) options.timeout = timeoutMs;
+  let value = vm.runInContext(code, context, options);
+  if (value && typeof value.then === "function") {
+    let timer;
+    const limit =
+      timeoutMs > 0
+        ? new Promise((_, reject) => {
+            timer = setTimeout(() => reject(new Error("cell exceeded its time limit")), timeoutMs);
+          })
+        : new Promise(() => {});
+    try {
+      value = await Promise.race([value, limit]);
+    } finally {
+      clearTimeout(timer);
+    }
+  }
+  if (value !== undefined) {
+    context._ = value;
+    outputs.push({ output_type: "execute_result", execution_count: count, data: bundle(value) });
+  }
+}
+
+async function main() {
+  fs.mkdirSync(INBOX, { recursive: true });
+  fs.mkdirSync(OUTBOX, { recursive: true });
+  fs.writeFileSync(path.join(ROOT, "ready"), String(process.pid));
+  for (;;) {
+    const cells = fs
+      .readdirSync(INBOX)
+      .filter((n) => n.endsWith(".cell"))
+      .sort((a, b) => parseInt(a, 10) - parseInt(b, 10));
+    if (cells.length === 0) {
+      await new Promise((resolve) => setTimeout(resolve, 20));
+      continue;
+    }
+    const name = cells[0];
+    const count = parseInt(name, 10);
+    const file = path.join(INBOX, name);
+    const cell = JSON.parse(fs.readFileSync(file, "utf8"));
+    fs.unlinkSync(file);
+
+    outputs = [];
+    try {
+      await runCell(cell.code, count, Math.round((cell.timeout_secs || 0) * 1000));
+    } catch (e) {
+      // Errors thrown by cell code come from the context's own realm, so
+      // `instanceof Error` is false for them.
+      const err = e && typeof e === "object" && "message" in e ? e : new Error(String(e));
+      outputs.push({
+        output_type: "error",
+        ename: err.name,
+        evalue: err.message,
+        traceback: String(err.stack || err).split("\n"),
+      });
+    }
+
+    const tmp = path.join(OUTBOX, `.${count}.tmp`);
+    fs.writeFileSync(tmp, JSON.stringify(outputs));
+    fs.renameSync(tmp, path.join(OUTBOX, `${count}.json`));
+  }
+}
+
+main();
@@ -0,0 +1,194 @@
+# Symbiont kernel driver (Python).
+#
+# Runs inside a sandbox session and executes code cells against one
+# persistent namespace. Cells arrive as JSON files in <root>/in/<n>.cell;
+# each result is written atomically to <root>/out/<n>.json as a list of
+# Jupyter-style outputs (stream, execute_result, display_data, error).
+
+import ast
+import base64
+import io
+import json
+import os
+import signal
+import sys
+import time
+import traceback
+
+ROOT = sys.argv[1]
+INBOX = os.path.join(ROOT, "in")
+OUTBOX = os.path.join(ROOT, "out")
+
+namespace = {"__name__": "__main__", "__builtins__": __builtins__}
+outputs = []
+
+
+class CellTimeout(Exception):
+    pass
+
+
+def _on_alarm(signum, frame):
+    raise CellTimeout("cell exceeded its time limit")
+
+
+def _bundle(obj):
+    """Build a MIME bundle for a value, richest representations first."""
+    data = {}
+    pd = sys.modules.get("pandas")
+    if pd is not None and isinstance(obj, (pd.DataFrame, pd.Series)):
+        frame = obj.to_frame() if isinstance(obj, pd.Series) else obj
+        try:
+            data["application/vnd.dataresource+json"] = json.loads(
+                frame.to_json(orient="table", date_format="iso", default_handler=str)
+            )
+        except Exception:
+            pass
+    for method, mime in (
+        ("_repr_png_", "image/png"),
+        ("_repr_jpeg_", "image/jpeg"),
+        ("_repr_svg_", "image/svg+xml"),
+        ("_repr_html_", "text/html"),
+        ("_repr_json_", "application/json"),
+    ):
+        fn = getattr(obj, method, None)
+        if not callable(fn):
+            continue
+        try:
+            value = fn()
+        except Exception:
+            continue
+        if value is None:
+            continue
+        if isinstance(value, tuple):
+            value = value[0]
+        if isinstance(value, bytes) and mime.startswith("image/") and mime != "image/svg+xml":
+            value = base64.b64encode(value).decode("ascii")
+        data[mime] = value
+    if isinstance(obj, (dict, list)) and "application/json" not in data:
+        try:
+            json.dumps(obj)
+            data["application/json"] = obj
+        except (TypeError, ValueError):
+            pass
+    data["text/plain"] = repr(obj)
+    return data
+
+
+def display(*objs):
+    for obj in objs:
+        outputs.append({"output_type": "display_data", "data": _bundle(obj)})
+
+
+namespace["display"] = display
+
+
+def _flush_figures():
+    plt = sys.modules.get("matplotlib.pyplot")
+    if plt is None:
+        return
+    for num in plt.get_fignums():
+        buf = io.BytesIO()
+        plt.figure(num).savefig(buf, format="png", bbox_inches="tight")
+        outputs.append(
+            {
+                "output_type": "display_data",
+                "data": {
+                    "image/png": base64.b64encode(buf.getvalue()).decode("ascii"),
+                    "text/plain": "<Figure %d>" % num,
+                },
+            }
+        )
+    plt.close("all")
+
+
+class _Stream(io.TextIOBase):
+    def __init__(self, name):
+        self.name = name
+
+    def writable(self):
+        return True
+
+    def write(self, text):
+        if not text:
+            return 0
+        last = outputs[-1] if outputs else None
+        if last and last.get("output_type") == "stream" and last["name"] == self.name:
+            last["text"] += text
+        else:
+            outputs.append({"output_type": "stream", "name": self.name, "text": text})
+        return len(text)
+
+
+def run_cell(code, count):
+    tree = ast.parse(code, "<cell-%d>" % count, "exec")
+    last = None
+    if tree.body and isinstance(tree.body[-1], ast.Expr):
+        last = ast.Expression(tree.body.pop().value)
+    exec(compile(tree, "<cell-%d>" % count, "exec"), namespace)
+    if last is not None:
+        value = eval(compile(last, "<cell-%d>" % count, "eval"), namespace)
+        if value is not None:
+            namespace["_"] = value
+            outputs.append(
+                {
+                    "output_type": "execute_result",
+                    "execution_count": count,
+                    "data": _bundle(value),
+                }
+            )
+
+
+def main():
+    os.makedirs(INBOX, exist_ok=True)
+    os.makedirs(OUTBOX, exist_ok=True)
+    signal.signal(signal.SIGALRM, _on_alarm)
+    with open(os.path.join(ROOT, "ready"), "w") as f:
+        f.write(str(os.getpid()))
+    real_stdout, real_stderr = sys.stdout, sys.stderr
+    while True:
+        cells = sorted(
+            (n for n in os.listdir(INBOX) if n.endswith(".cell")),
+            key=lambda n: int(n.split(".")[0]),
+        )
+        if not cells:
+            time.sleep(0.02)
+            continue
+        name = cells[0]
+        count = int(name.split(".")[0])
+        path = os.path.join(INBOX, name)
+        with open(path) as f:
+            cell = json.load(f)
+        os.remove(path)
+
+        del outputs[:]
+        sys.stdout, sys.stderr = _Stream("stdout"), _Stream("stderr")
+        signal.setitimer(signal.ITIMER_REAL, max(float(cell.get("timeout_secs", 0)), 0.0))
+        try:
+            run_cell(cell["code"], count)
+        except BaseException as e:
+            # SystemExit included: a cell calling exit() must not take the
+            # kernel and its namespace down with it.
+            outputs.append(
+                {
+                    "output_type": "error",
+                    "ename": type(e).__name__,
+                    "evalue": str(e),
+                    "traceback": traceback.format_exception(type(e), e, e.__traceback__),
+                }
+            )
+        finally:
+            signal.setitimer(signal.ITIMER_REAL, 0)
+            sys.stdout, sys.stderr = real_stdout, real_stderr
+        try:
+            _flush_figures()
+        except Exception:
+            pass
+
+        tmp = os.path.join(OUTBOX, ".%d.tmp" % count)
+        with open(tmp, "w") as f:
+            json.dump(outputs, f, default=str)
+        os.rename(tmp, os.path.join(OUTBOX, "%d.json" % count))
+
+
+if __name__ == "__main__":
+    main()
@@ -1,25 +1,46 @@
 //! Sandbox abstraction layer for multi-tier sandbox execution
 //!
 //! This module provides a unified interface for different sandbox technologies
-//! including Docker, GVisor, Firecracker, E2B.dev, and native (non-isolated) execution.
+//! including Docker, GVisor, Firecracker, E2B.dev, rootless Linux namespaces,
+//! an embedded WASI engine, and native (non-isolated) execution.
 
+pub mod artifacts;
 pub mod docker;
 pub mod e2b;
+pub mod egress;
 pub mod firecracker;
 pub mod gvisor;
+pub mod kernel;
+pub mod namespace;
 #[cfg(feature = "native-sandbox")]
 pub mod native;
+pub mod session;
+#[cfg(feature = "wasm-sandbox")]
+pub mod wasm;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-pub use docker::{DockerConfig, DockerRunner};
+pub use artifacts::{Artifact, ArtifactSpec, ExecutionRequest, FileSource, StagedFile};
+pub use docker::{DockerConfig, DockerRunner, DockerSession};
 pub use e2b::E2BSandbox;
+pub use egress::{
+    EgressAudit, EgressAuditEvent, EgressDecision, EgressPolicy, EgressProxy, EgressProxyHandle,
+    JsonlEgressAudit,
+};
 pub use firecracker::{FirecrackerConfig, FirecrackerRunner};
 pub use gvisor::{GVisorConfig, GVisorRunner};
+pub use kernel::{CellOutput, CellResult, Kernel, KernelConfig, KernelLanguage, MimeBundle};
+pub use namespace::{NamespaceConfig, NamespaceRunner};
 #[cfg(feature = "native-sandbox")]
 pub use native::{NativeConfig, NativeRunner};
+pub use session::{
+    SandboxSession, SandboxSessionId, SandboxSessionManager, SandboxSessionsConfig, SessionBinding,
+    SessionConfig, SessionInfo,
+};
+#[cfg(feature = "wasm-sandbox")]
+pub use wasm::{WasmConfig, WasmInterpreter, WasmPreopen, WasmRunner};
 
 /// Sandbox tier enumeration representing different isolation levels
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -34,6 +55,11 @@ pub enum SandboxTier {
     Firecracker,
     /// E2B.dev cloud sandbox
     E2B,
+    /// Rootless Linux namespaces with seccomp, Landlock and cgroup v2 limits
+    Namespace,
+    /// WASI modules and interpreters in an embedded wasm engine (fuel/epoch
+    /// CPU limits, memory caps, preopened directories only)
+    Wasm,
 }
 
 impl SandboxTier {
@@ -46,7 +72,7 @@ impl SandboxTier {
     /// misconfigured deployments have previously left the unisolated tier
     /// running on real traffic.
     pub fn enforce_production_guard(&self) -> Result<(), String> {
-        // All tiers (Docker, GVisor, Firecracker, E2B) ship with OSS runner
+        // All tiers (Docker, GVisor, Firecracker, E2B, Namespace, Wasm) ship with OSS runner
         // implementations. The only tier the guard refuses unconditionally
         // in production is `None` — direct host execution — unless the
         // operator has explicitly opted in via `SYMBIONT_ALLOW_UNISOLATED=1`.
@@ -93,6 +119,12 @@ pub struct ExecutionResult {
     /// Whether stderr was truncated due to size limits
     #[serde(default)]
     pub stderr_truncated: bool,
+    /// Files collected from the sandbox per the request's `ArtifactSpec`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub artifacts: Vec<Artifact>,
+    /// Whether artifact collection skipped files because of a size or count cap
+    #[serde(default)]
+    pub artifacts_truncated: bool,
 }
 
 impl ExecutionResult {
@@ -106,6 +138,8 @@ impl ExecutionResult {
             success: true,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         }
     }
 
@@ -119,6 +153,8 @@ impl ExecutionResult {
             success: false,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         }
     }
 
@@ -132,6 +168,8 @@ impl ExecutionResult {
             success: false,
             stdout_truncated: false,
             stderr_truncated: false,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
         }
     }
 }
@@ -140,8 +178,12 @@ impl ExecutionResult {
 ///
 /// `profile` carries the per-tier configuration knobs the operator set in
 /// `symbiont.toml` (`[sandbox.docker]`, `[sandbox.gvisor]`,
-/// `[sandbox.firecracker]`). Defaults are applied for any tier the operator
-/// hasn't customised.
+/// `[sandbox.firecracker]`, `[sandbox.namespace]`, `[sandbox.wasm]`). Defaults
+/// are applied for any tier the operator hasn't customised. The `Wasm` tier
+/// needs the `wasm-sandbox` feature.
+///
+/// Docker, gVisor and namespace runners with an egress allowlist record
+/// every egress decision to `profile.egress_audit_log`.
 ///
 /// Returns `Err` if the tier is `SandboxTier::None` — host-only execution
 /// must come from a deliberate code path, not a runner factory call.
@@ -149,17 +191,30 @@ pub fn build_runner(
     tier: SandboxTier,
     profile: &SandboxRunnerProfile,
 ) -> Result<Box<dyn SandboxRunner>, anyhow::Error> {
+    let egress_audit = || -> std::sync::Arc<dyn EgressAudit> {
+        std::sync::Arc::new(JsonlEgressAudit::new(
+            profile
+                .egress_audit_log
+                .clone()
+                .unwrap_or_else(JsonlEgressAudit::default_path),
+        ))
+    };
     match tier {
         SandboxTier::None => Err(anyhow::anyhow!(
-            "SandboxTier::None has no runner; agents must use docker, gvisor, firecracker, or e2b"
+            "SandboxTier::None has no runner; agents must use docker, gvisor, firecracker, e2b, \
+             namespace, or wasm"
         )),
         SandboxTier::Docker => {
             let cfg = profile.docker.clone().unwrap_or_default();
-            Ok(Box::new(DockerRunner::new(cfg)?))
+            Ok(Box::new(
+                DockerRunner::new(cfg)?.with_egress_audit(egress_audit()),
+            ))
         }
         SandboxTier::GVisor => {
             let cfg = profile.gvisor.clone().unwrap_or_default();
-            Ok(Box::new(GVisorRunner::new(cfg)?))
+            Ok(Box::new(
+                GVisorRunner::new(cfg)?.with_egress_audit(egress_audit()),
+            ))
         }
         SandboxTier::Firecracker => {
             let cfg = profile.firecracker.clone().ok_or_else(|| {
@@ -175,6 +230,21 @@ pub fn build_runner(
                 .map_err(|_| anyhow::anyhow!("E2B tier selected but E2B_API_KEY is not set"))?;
             Ok(Box::new(E2BSandbox::new_with_default_endpoint(api_key)))
         }
+        SandboxTier::Namespace => {
+            let cfg = profile.namespace.clone().unwrap_or_default();
+            Ok(Box::new(
+                NamespaceRunner::new(cfg)?.with_egress_audit(egress_audit()),
+            ))
+        }
+        #[cfg(feature = "wasm-sandbox")]
+        SandboxTier::Wasm => {
+            let cfg = profile.wasm.clone().unwrap_or_default();
+            Ok(Box::new(WasmRunner::new(cfg)?))
+        }
+        #[cfg(not(feature = "wasm-sandbox"))]
+        SandboxTier::Wasm => Err(anyhow::anyhow!(
+            "Wasm tier selected but this build lacks the `wasm-sandbox` feature"
+        )),
     }
 }
 
@@ -189,6 +259,11 @@ pub struct SandboxRunnerProfile {
     pub docker: Option<DockerConfig>,
     pub gvisor: Option<GVisorConfig>,
     pub firecracker: Option<FirecrackerConfig>,
+    pub namespace: Option<NamespaceConfig>,
+    #[cfg(feature = "wasm-sandbox")]
+    pub wasm: Option<WasmConfig>,
+    /// Egress audit log. Default: `JsonlEgressAudit::default_path()`.
+    pub egress_audit_log: Option<std::path::PathBuf>,
 }
 
 /// Trait for sandbox runners providing code execution capabilities
@@ -207,6 +282,33 @@ pub trait SandboxRunner: Send + Sync {
         code: &str,
         env: HashMap<String, String>,
     ) -> Result<ExecutionResult, anyhow::Error>;
+
+    /// Execute a request that stages input files into the sandbox working
+    /// directory and collects artifacts from it afterwards.
+    ///
+    /// The default runs plain requests through [`execute`](Self::execute)
+    /// and refuses any request with files or an artifact spec; runners that
+    /// can move files in and out override it.
+    async fn execute_request(
+        &self,
+        request: ExecutionRequest,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        if request.uses_files() {
+            anyhow::bail!(
+                "this sandbox runner does not support file staging or artifact collection"
+            );
+        }
+        self.execute(&request.code, request.env).await
+    }
+
+    /// Open a long-lived sandbox whose filesystem persists across
+    /// executions. Most callers go through a [`SandboxSessionManager`],
+    /// which adds idle and lifetime limits and resource accounting.
+    ///
+    /// The default refuses; runners that can keep a sandbox alive override it.
+    async fn open_session(&self) -> Result<Box<dyn SandboxSession>, anyhow::Error> {
+        anyhow::bail!("this sandbox runner does not support persistent sessions")
+    }
 }
 
 #[cfg(test)]
@@ -243,6 +345,8 @@ mod tier_guard_tests {
         assert!(SandboxTier::GVisor.enforce_production_guard().is_ok());
         assert!(SandboxTier::Firecracker.enforce_production_guard().is_ok());
         assert!(SandboxTier::E2B.enforce_production_guard().is_ok());
+        assert!(SandboxTier::Namespace.enforce_production_guard().is_ok());
+        assert!(SandboxTier::Wasm.enforce_production_guard().is_ok());
     }
 
     #[test]
@@ -0,0 +1,1206 @@
+//! Rootless Linux namespace sandbox runner
+//!
+//! Runs code directly on the host, but inside fresh unprivileged user, mount,
+//! PID, IPC, UTS and (by default) network namespaces, with:
+//!
+//! - a seccomp-bpf filter refusing syscalls that reach outside the sandbox
+//!   (mounts, namespace changes, ptrace, module loading, bpf, keyrings, ...);
+//! - Landlock filesystem rules derived from [`FilesystemControls`] — the
+//!   interpreter's system paths are read-only, a per-execution scratch
+//!   directory and `write_paths` are writable, everything else is denied;
+//! - cgroup v2 limits (`memory.max`, `cpu.max`, `pids.max`) derived from
+//!   [`ResourceConstraints`] when a delegated cgroup subtree is writable,
+//!   falling back to rlimits otherwise;
+//! - with [`NamespaceConfig::egress`] set, a loopback-only network whose one
+//!   way out is an [`EgressProxy`] at `127.0.0.1:3128`, listening inside the
+//!   sandbox's network namespace but served by the runtime.
+//!
+//! No daemon or root is required, so this tier gives CI hosts without Docker
+//! real isolation. It needs unprivileged user namespaces and, unless
+//! `require_landlock` is turned off, a kernel with Landlock enabled.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use tokio::time::Duration;
+
+use super::egress::{EgressAudit, EgressPolicy, EgressProxy};
+use super::{ExecutionResult, SandboxRunner};
+use crate::config::{FilesystemControls, NetworkAccessMode, ResourceConstraints, SandboxProfile};
+
+/// Default maximum output size in bytes (10 MB)
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 10 * 1024 * 1024;
+
+/// `cpu.max` period in microseconds.
+const CPU_PERIOD_US: u64 = 100_000;
+
+/// Port the egress proxy listens on inside the sandbox.
+const EGRESS_PROXY_PORT: u16 = 3128;
+
+/// Configuration for the namespace sandbox tier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamespaceConfig {
+    /// Interpreter used to run code (e.g. "python3", "node", "bash").
+    pub executable: String,
+    /// Directory that per-execution scratch directories are created under.
+    /// The scratch directory is the working directory, `HOME` and `TMPDIR`.
+    pub scratch_root: PathBuf,
+    /// Host paths the interpreter needs to start, mounted read-only through
+    /// Landlock (binaries and shared libraries).
+    pub system_paths: Vec<PathBuf>,
+    /// Filesystem rules. Glob patterns are reduced to the directory before
+    /// the first wildcard; `denied_paths` may not sit inside an allowed path
+    /// because Landlock can only grant access, not carve it back out.
+    pub filesystem: FilesystemControls,
+    /// Memory and CPU limits applied through cgroup v2.
+    pub resources: ResourceConstraints,
+    /// Maximum number of processes the executed code may have running,
+    /// itself included (`pids.max`).
+    pub max_processes: u32,
+    /// Nice value for the sandboxed process.
+    pub process_priority: i8,
+    /// Maximum execution time (timeout)
+    pub max_execution_time: Duration,
+    /// Maximum output bytes per stream before truncation (default: 10MB)
+    pub max_output_bytes: usize,
+    /// Keep the host network namespace. When false the sandbox gets its own
+    /// network namespace with no interfaces up.
+    pub share_network: bool,
+    /// Destination allowlist for outbound connections. When set, the
+    /// isolated network namespace gets loopback plus an egress proxy, and
+    /// the `HTTP_PROXY`/`HTTPS_PROXY`/`ALL_PROXY` variables point at it.
+    /// Requires `share_network = false`.
+    #[serde(default)]
+    pub egress: Option<EgressPolicy>,
+    /// Refuse to run when the kernel has no Landlock support instead of
+    /// running with namespaces and seccomp only.
+    pub require_landlock: bool,
+    /// Refuse to run when no delegated cgroup v2 subtree is writable instead
+    /// of falling back to rlimits.
+    pub require_cgroups: bool,
+    /// Delegated cgroup v2 directory to create per-execution cgroups in.
+    /// Detected from `/proc/self/cgroup` when unset.
+    pub cgroup_parent: Option<PathBuf>,
+}
+
+impl Default for NamespaceConfig {
+    fn default() -> Self {
+        Self::from_profile(&SandboxProfile::secure_default())
+    }
+}
+
+impl NamespaceConfig {
+    /// Derive a namespace config from an SLM sandbox profile.
+    ///
+    /// `NetworkAccessMode::Full` shares the host network; `None` and
+    /// `Restricted` both get an isolated network namespace, and `Restricted`
+    /// enforces `allowed_destinations` through the egress proxy.
+    pub fn from_profile(profile: &SandboxProfile) -> Self {
+        Self {
+            executable: "python3".to_string(),
+            scratch_root: std::env::temp_dir().join("symbiont-namespace"),
+            system_paths: ["/usr", "/lib", "/lib64", "/bin", "/sbin"]
+                .iter()
+                .map(PathBuf::from)
+                .collect(),
+            filesystem: profile.filesystem.clone(),
+            resources: profile.resources.clone(),
+            max_processes: profile.process_limits.max_child_processes.saturating_add(1),
+            process_priority: profile.process_limits.process_priority,
+            max_execution_time: Duration::from_secs(
+                profile.process_limits.max_execution_time_seconds,
+            ),
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            share_network: matches!(profile.network.access_mode, NetworkAccessMode::Full),
+            egress: matches!(profile.network.access_mode, NetworkAccessMode::Restricted)
+                .then(|| EgressPolicy::from_network_policy(&profile.network)),
+            require_landlock: true,
+            require_cgroups: false,
+            cgroup_parent: None,
+        }
+    }
+
+    /// `cpu.max` value for the configured core count.
+    fn cpu_max(&self) -> String {
+        if self.resources.max_cpu_cores <= 0.0 {
+            return format!("max {}", CPU_PERIOD_US);
+        }
+        let quota = (self.resources.max_cpu_cores as f64 * CPU_PERIOD_US as f64).round() as u64;
+        format!("{} {}", quota.max(1000), CPU_PERIOD_US)
+    }
+
+    /// Resolve the Landlock read-only and read-write roots.
+    fn landlock_roots(&self) -> Result<(Vec<PathBuf>, Vec<PathBuf>), anyhow::Error> {
+        let denied = glob_roots(&self.filesystem.denied_paths);
+        let mut read = self.system_paths.clone();
+        read.extend(glob_roots(&self.filesystem.read_paths));
+        let write = glob_roots(&self.filesystem.write_paths);
+
+        for d in &denied {
+            if let Some(a) = read
+                .iter()
+                .chain(&write)
+                .find(|a| d.starts_with(a) && d != *a)
+            {
+                anyhow::bail!(
+                    "denied path '{}' lies inside allowed path '{}'; Landlock cannot \
+                     exclude a subtree of an allowed path",
+                    d.display(),
+                    a.display()
+                );
+            }
+        }
+        let allowed = |p: &PathBuf| !denied.iter().any(|d| p.starts_with(d));
+        Ok((
+            read.into_iter().filter(allowed).collect(),
+            write.into_iter().filter(allowed).collect(),
+        ))
+    }
+}
+
+/// Reduce glob patterns to the directory before the first wildcard.
+/// `/tmp/sandbox/*` becomes `/tmp/sandbox`; relative patterns are ignored.
+pub(super) fn glob_roots(patterns: &[String]) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    for pattern in patterns {
+        let mut root = PathBuf::new();
+        for component in Path::new(pattern).components() {
+            match component {
+                Component::RootDir => root.push("/"),
+                Component::Normal(part) => {
+                    let part = part.to_string_lossy();
+                    if part.contains(['*', '?', '[', '{']) {
+                        break;
+                    }
+                    root.push(part.as_ref());
+                }
+                Component::ParentDir | Component::CurDir | Component::Prefix(_) => {
+                    root = PathBuf::new();
+                    break;
+                }
+            }
+        }
+        if root.is_absolute() && !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+/// Sandbox runner executing code in rootless Linux namespaces.
+#[derive(Debug)]
+pub struct NamespaceRunner {
+    config: NamespaceConfig,
+    read_roots: Vec<PathBuf>,
+    write_roots: Vec<PathBuf>,
+    executable: PathBuf,
+    cgroup_parent: Option<PathBuf>,
+    egress: Option<EgressProxy>,
+}
+
+impl NamespaceRunner {
+    /// Validate `config` against the host and create a runner.
+    ///
+    /// Fails when the host cannot provide the requested isolation: user
+    /// namespaces disabled, Landlock missing while `require_landlock` is set,
+    /// or no writable cgroup v2 subtree while `require_cgroups` is set.
+    pub fn new(config: NamespaceConfig) -> Result<Self, anyhow::Error> {
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = config;
+            anyhow::bail!("the namespace sandbox tier is only available on Linux");
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            linux::check_user_namespaces()?;
+            if config.egress.is_some() && config.share_network {
+                anyhow::bail!(
+                    "egress filtering needs an isolated network namespace; unset share_network"
+                );
+            }
+
+            let (read_roots, write_roots) = config.landlock_roots()?;
+            let executable = resolve_executable(&config.executable)?;
+            if !read_roots.iter().any(|r| executable.starts_with(r)) {
+                anyhow::bail!(
+                    "executable '{}' is outside every readable path; add its directory to \
+                     system_paths",
+                    executable.display()
+                );
+            }
+
+            if !linux::landlock_supported() {
+                if config.require_landlock {
+                    anyhow::bail!(
+                        "this kernel has no Landlock support (add `landlock` to the `lsm=` boot \
+                         parameter), and require_landlock is set"
+                    );
+                }
+                tracing::warn!(
+                    "Landlock unavailable: namespace sandbox runs without filesystem rules"
+                );
+            }
+
+            let cgroup_parent = match &config.cgroup_parent {
+                Some(dir) => linux::usable_cgroup_parent(dir).then(|| dir.clone()),
+                None => linux::detect_cgroup_parent(),
+            };
+            if cgroup_parent.is_none() {
+                if config.require_cgroups {
+                    anyhow::bail!(
+                        "no writable cgroup v2 subtree with the memory, cpu and pids controllers \
+                         enabled; set cgroup_parent to a delegated cgroup"
+                    );
+                }
+                tracing::warn!(
+                    "No delegated cgroup v2 subtree: namespace sandbox falls back to rlimits"
+                );
+            }
+
+            std::fs::create_dir_all(&config.scratch_root)?;
+
+            let egress = config.egress.clone().map(EgressProxy::new);
+            Ok(Self {
+                config,
+                read_roots,
+                write_roots,
+                executable,
+                cgroup_parent,
+                egress,
+            })
+        }
+    }
+
+    /// Record egress decisions through `audit`. No effect without
+    /// [`NamespaceConfig::egress`].
+    pub fn with_egress_audit(mut self, audit: std::sync::Arc<dyn EgressAudit>) -> Self {
+        self.egress = self.egress.map(|proxy| proxy.with_audit(audit));
+        self
+    }
+
+    /// Arguments that make the interpreter run `code`.
+    fn code_args(&self, code: &str) -> Vec<String> {
+        let name = self
+            .executable
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let flag = if name.starts_with("node") { "-e" } else { "-c" };
+        vec![flag.to_string(), code.to_string()]
+    }
+}
+
+/// Resolve `executable` against a fixed system `PATH`.
+fn resolve_executable(executable: &str) -> Result<PathBuf, anyhow::Error> {
+    let candidate = Path::new(executable);
+    let found = if candidate.is_absolute() {
+        candidate.is_file().then(|| candidate.to_path_buf())
+    } else {
+        ["/usr/local/bin", "/usr/bin", "/bin"]
+            .iter()
+            .map(|dir| Path::new(dir).join(executable))
+            .find(|p| p.is_file())
+    };
+    let found = found.ok_or_else(|| anyhow::anyhow!("executable '{}' not found", executable))?;
+    Ok(std::fs::canonicalize(&found)?)
+}
+
+#[async_trait]
+impl SandboxRunner for NamespaceRunner {
+    async fn execute(
+        &self,
+        code: &str,
+        env: HashMap<String, String>,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (code, env);
+            anyhow::bail!("the namespace sandbox tier is only available on Linux");
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            linux::execute(self, code, env).await
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    //! Child-side setup. Everything the child needs is prepared in the parent
+    //! (BPF programs, Landlock ruleset fd, uid/gid map contents, cgroup fd) so
+    //! the `pre_exec` hook only issues raw syscalls between fork and exec.
+
+    use super::*;
+    use landlock::{
+        Access, AccessFs, PathBeneath, PathFd, Ruleset, RulesetAttr, RulesetCreatedAttr, ABI,
+    };
+    use seccompiler::{
+        BpfProgram, SeccompAction, SeccompCmpArgLen, SeccompCmpOp, SeccompCondition, SeccompFilter,
+        SeccompRule,
+    };
+    use std::collections::BTreeMap;
+    use std::ffi::{CStr, CString};
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::net::UnixStream;
+    use std::process::Stdio;
+    use tokio::io::AsyncReadExt;
+    use tokio::process::Command;
+
+    const LANDLOCK_ABI: ABI = ABI::V3;
+
+    // From <linux/securebits.h>.
+    const SECBIT_NOROOT: libc::c_ulong = 1 << 0;
+    const SECBIT_NOROOT_LOCKED: libc::c_ulong = 1 << 1;
+    const SECBIT_NO_SETUID_FIXUP: libc::c_ulong = 1 << 2;
+    const SECBIT_NO_SETUID_FIXUP_LOCKED: libc::c_ulong = 1 << 3;
+    const SECBIT_KEEP_CAPS_LOCKED: libc::c_ulong = 1 << 5;
+
+    /// Syscalls refused with `EPERM`.
+    const DENIED_SYSCALLS: &[libc::c_long] = &[
+        libc::SYS_mount,
+        libc::SYS_umount2,
+        libc::SYS_pivot_root,
+        libc::SYS_chroot,
+        libc::SYS_unshare,
+        libc::SYS_setns,
+        libc::SYS_ptrace,
+        libc::SYS_process_vm_readv,
+        libc::SYS_process_vm_writev,
+        libc::SYS_kexec_load,
+        libc::SYS_kexec_file_load,
+        libc::SYS_init_module,
+        libc::SYS_finit_module,
+        libc::SYS_delete_module,
+        libc::SYS_bpf,
+        libc::SYS_perf_event_open,
+        libc::SYS_keyctl,
+        libc::SYS_add_key,
+        libc::SYS_request_key,
+        libc::SYS_reboot,
+        libc::SYS_swapon,
+        libc::SYS_swapoff,
+        libc::SYS_acct,
+        libc::SYS_settimeofday,
+        libc::SYS_clock_settime,
+        libc::SYS_clock_adjtime,
+        libc::SYS_adjtimex,
+        libc::SYS_syslog,
+        libc::SYS_userfaultfd,
+        libc::SYS_open_by_handle_at,
+        libc::SYS_name_to_handle_at,
+        libc::SYS_quotactl,
+        libc::SYS_fsopen,
+        libc::SYS_fsconfig,
+        libc::SYS_fsmount,
+        libc::SYS_fspick,
+        libc::SYS_move_mount,
+        libc::SYS_open_tree,
+        libc::SYS_vhangup,
+    ];
+
+    /// Namespace flags `clone` may not request from inside the sandbox.
+    const CLONE_NAMESPACE_FLAGS: &[libc::c_int] = &[
+        libc::CLONE_NEWUSER,
+        libc::CLONE_NEWNS,
+        libc::CLONE_NEWPID,
+        libc::CLONE_NEWNET,
+        libc::CLONE_NEWIPC,
+        libc::CLONE_NEWUTS,
+        libc::CLONE_NEWCGROUP,
+    ];
+
+    pub(super) fn check_user_namespaces() -> Result<(), anyhow::Error> {
+        let read = |p: &str| {
+            std::fs::read_to_string(p)
+                .ok()
+                .map(|s| s.trim().to_string())
+        };
+        if read("/proc/sys/user/max_user_namespaces").as_deref() == Some("0") {
+            anyhow::bail!("user namespaces are disabled (user.max_user_namespaces = 0)");
+        }
+        if unsafe { libc::geteuid() } != 0 {
+            if read("/proc/sys/kernel/unprivileged_userns_clone").as_deref() == Some("0") {
+                anyhow::bail!(
+                    "unprivileged user namespaces are disabled \
+                     (kernel.unprivileged_userns_clone = 0)"
+                );
+            }
+            if read("/proc/sys/kernel/apparmor_restrict_unprivileged_userns").as_deref()
+                == Some("1")
+            {
+                anyhow::bail!(
+                    "AppArmor restricts unprivileged user namespaces \
+                     (kernel.apparmor_restrict_unprivileged_userns = 1)"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn landlock_supported() -> bool {
+        Ruleset::default()
+            .handle_access(AccessFs::from_all(LANDLOCK_ABI))
+            .and_then(|r| r.create())
+            .map(|created| Option::<OwnedFd>::from(created).is_some())
+            .unwrap_or(false)
+    }
+
+    /// Build a Landlock ruleset granting read access to `read` and full
+    /// access to `write`. Returns `None` when the kernel lacks Landlock.
+    fn landlock_ruleset(
+        read: &[PathBuf],
+        write: &[PathBuf],
+    ) -> Result<Option<OwnedFd>, anyhow::Error> {
+        let mut ruleset = Ruleset::default()
+            .handle_access(AccessFs::from_all(LANDLOCK_ABI))?
+            .create()?;
+        let rules = read
+            .iter()
+            .map(|p| (p, AccessFs::from_read(LANDLOCK_ABI)))
+            .chain(write.iter().map(|p| (p, AccessFs::from_all(LANDLOCK_ABI))));
+        for (path, access) in rules {
+            // Missing paths simply grant nothing.
+            let Ok(fd) = PathFd::new(path) else {
+                tracing::debug!(path = %path.display(), "Skipping missing Landlock path");
+                continue;
+            };
+            // Directory-only rights are invalid on files; `from_file` keeps
+            // the subset that applies.
+            let access = if path.is_dir() {
+                access
+            } else {
+                access & AccessFs::from_file(LANDLOCK_ABI)
+            };
+            ruleset = ruleset.add_rule(PathBeneath::new(fd, access))?;
+        }
+        Ok(ruleset.into())
+    }
+
+    fn seccomp_programs() -> Result<Vec<BpfProgram>, anyhow::Error> {
+        let arch = std::env::consts::ARCH
+            .try_into()
+            .map_err(|e| anyhow::anyhow!("seccomp: {}", e))?;
+
+        let mut denied: BTreeMap<i64, Vec<SeccompRule>> =
+            DENIED_SYSCALLS.iter().map(|nr| (*nr, Vec::new())).collect();
+        let clone_rules = CLONE_NAMESPACE_FLAGS
+            .iter()
+            .map(|flag| {
+                SeccompRule::new(vec![SeccompCondition::new(
+                    0,
+                    SeccompCmpArgLen::Qword,
+                    SeccompCmpOp::MaskedEq(*flag as u64),
+                    *flag as u64,
+                )?])
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        denied.insert(libc::SYS_clone, clone_rules);
+        let deny = SeccompFilter::new(
+            denied,
+            SeccompAction::Allow,
+            SeccompAction::Errno(libc::EPERM as u32),
+            arch,
+        )?;
+
+        // clone3 takes its flags through a pointer the filter cannot inspect;
+        // ENOSYS makes libc fall back to the filtered clone.
+        let clone3 = SeccompFilter::new(
+            [(libc::SYS_clone3, Vec::new())].into_iter().collect(),
+            SeccompAction::Allow,
+            SeccompAction::Errno(libc::ENOSYS as u32),
+            arch,
+        )?;
+
+        Ok(vec![deny.try_into()?, clone3.try_into()?])
+    }
+
+    pub(super) fn usable_cgroup_parent(dir: &Path) -> bool {
+        let controllers =
+            std::fs::read_to_string(dir.join("cgroup.subtree_control")).unwrap_or_default();
+        let enabled = ["memory", "cpu", "pids"]
+            .iter()
+            .all(|c| controllers.split_whitespace().any(|e| e == *c));
+        let Ok(path) = CString::new(dir.as_os_str().as_encoded_bytes()) else {
+            return false;
+        };
+        // SAFETY: `path` is a valid NUL-terminated string.
+        enabled && unsafe { libc::access(path.as_ptr(), libc::W_OK) } == 0
+    }
+
+    /// The current process's cgroup v2 directory, if it is delegated to us.
+    pub(super) fn detect_cgroup_parent() -> Option<PathBuf> {
+        let cgroup = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+        let relative = cgroup.lines().find_map(|l| l.strip_prefix("0::"))?;
+        let mountinfo = std::fs::read_to_string("/proc/self/mountinfo").ok()?;
+        let mount = mountinfo.lines().find_map(|line| {
+            let (pre, post) = line.split_once(" - ")?;
+            (post.split_whitespace().next() == Some("cgroup2"))
+                .then(|| pre.split_whitespace().nth(4).map(PathBuf::from))?
+        })?;
+        let dir = mount.join(relative.trim_start_matches('/'));
+        usable_cgroup_parent(&dir).then_some(dir)
+    }
+
+    /// Per-execution cgroup, removed on drop.
+    struct ExecCgroup {
+        dir: PathBuf,
+        procs: std::fs::File,
+    }
+
+    impl ExecCgroup {
+        fn create(parent: &Path, config: &NamespaceConfig) -> Result<Self, anyhow::Error> {
+            let dir = parent.join(format!("symbi-ns-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir(&dir)?;
+            let cgroup = Self {
+                procs: std::fs::OpenOptions::new()
+                    .write(true)
+                    .open(dir.join("cgroup.procs"))?,
+                dir,
+            };
+            let memory = config.resources.max_memory_mb * 1024 * 1024;
+            cgroup.set("memory.max", &memory.to_string())?;
+            // Absent when swap accounting is off; memory.max still applies.
+            let _ = cgroup.set("memory.swap.max", "0");
+            cgroup.set("cpu.max", &config.cpu_max())?;
+            // One extra task for the PID-namespace reaper.
+            cgroup.set("pids.max", &(config.max_processes.max(1) + 1).to_string())?;
+            Ok(cgroup)
+        }
+
+        fn set(&self, file: &str, value: &str) -> Result<(), anyhow::Error> {
+            std::fs::write(self.dir.join(file), value)
+                .map_err(|e| anyhow::anyhow!("failed to set {}: {}", file, e))
+        }
+    }
+
+    impl Drop for ExecCgroup {
+        fn drop(&mut self) {
+            if std::fs::remove_dir(&self.dir).is_err() {
+                let _ = std::fs::write(self.dir.join("cgroup.kill"), "1");
+                if let Err(e) = std::fs::remove_dir(&self.dir) {
+                    tracing::warn!(cgroup = %self.dir.display(), "Failed to remove cgroup: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Everything the child needs, prepared before fork.
+    struct ChildSetup {
+        cgroup_procs: libc::c_int,
+        landlock: libc::c_int,
+        seccomp: Vec<BpfProgram>,
+        unshare_flags: libc::c_int,
+        uid_map: Vec<u8>,
+        gid_map: Vec<u8>,
+        memory_rlimit: Option<u64>,
+        fsize_rlimit: Option<u64>,
+        priority: libc::c_int,
+        /// Socket the child sends its egress listener over, or -1.
+        egress_socket: libc::c_int,
+    }
+
+    /// Keeps the egress proxy serving for the lifetime of one execution.
+    struct EgressTask(tokio::task::JoinHandle<()>);
+
+    impl Drop for EgressTask {
+        fn drop(&mut self) {
+            self.0.abort();
+        }
+    }
+
+    /// Receive the listening socket the child created in its network
+    /// namespace.
+    fn receive_listener(socket: UnixStream) -> std::io::Result<std::net::TcpListener> {
+        socket.set_read_timeout(Some(std::time::Duration::from_secs(10)))?;
+        let mut byte = 0u8;
+        let mut iov = libc::iovec {
+            iov_base: (&mut byte as *mut u8).cast(),
+            iov_len: 1,
+        };
+        let mut control = [0u64; 4];
+        // SAFETY: msghdr points at live stack buffers for the whole call, and
+        // the control buffer is large enough for one SCM_RIGHTS fd.
+        unsafe {
+            let mut msg: libc::msghdr = std::mem::zeroed();
+            msg.msg_iov = &mut iov;
+            msg.msg_iovlen = 1;
+            msg.msg_control = control.as_mut_ptr().cast();
+            msg.msg_controllen = std::mem::size_of_val(&control) as _;
+            let n = libc::recvmsg(socket.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC);
+            check(n as libc::c_long)?;
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            if cmsg.is_null()
+                || (*cmsg).cmsg_level != libc::SOL_SOCKET
+                || (*cmsg).cmsg_type != libc::SCM_RIGHTS
+            {
+                return Err(std::io::Error::other(
+                    "sandbox did not hand over its egress listener",
+                ));
+            }
+            let fd = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg) as *const libc::c_int);
+            let listener = std::net::TcpListener::from_raw_fd(fd);
+            listener.set_nonblocking(true)?;
+            Ok(listener)
+        }
+    }
+
+    pub(super) async fn execute(
+        runner: &NamespaceRunner,
+        code: &str,
+        env: HashMap<String, String>,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        let config = &runner.config;
+        let scratch = tempfile::Builder::new()
+            .prefix("run-")
+            .tempdir_in(&config.scratch_root)?;
+
+        let cgroup = runner
+            .cgroup_parent
+            .as_deref()
+            .map(|parent| ExecCgroup::create(parent, config))
+            .transpose()?;
+
+        let mut write_roots = runner.write_roots.clone();
+        write_roots.push(scratch.path().to_path_buf());
+        write_roots.push(PathBuf::from("/dev/null"));
+        let mut read_roots = runner.read_roots.clone();
+        read_roots.extend(["/dev/urandom", "/dev/zero"].map(PathBuf::from));
+        let landlock = landlock_ruleset(&read_roots, &write_roots)?;
+        if landlock.is_none() && config.require_landlock {
+            anyhow::bail!("Landlock is unavailable and require_landlock is set");
+        }
+
+        let mut unshare_flags = libc::CLONE_NEWUSER
+            | libc::CLONE_NEWNS
+            | libc::CLONE_NEWPID
+            | libc::CLONE_NEWIPC
+            | libc::CLONE_NEWUTS;
+        if !config.share_network {
+            unshare_flags |= libc::CLONE_NEWNET;
+        }
+
+        let egress_pair = runner
+            .egress
+            .as_ref()
+            .map(|_| UnixStream::pair())
+            .transpose()?;
+
+        // SAFETY: getuid/getgid cannot fail.
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        let setup = ChildSetup {
+            cgroup_procs: cgroup.as_ref().map_or(-1, |c| c.procs.as_raw_fd()),
+            landlock: landlock.as_ref().map_or(-1, |fd| fd.as_raw_fd()),
+            seccomp: seccomp_programs()?,
+            unshare_flags,
+            uid_map: format!("0 {} 1\n", uid).into_bytes(),
+            gid_map: format!("0 {} 1\n", gid).into_bytes(),
+            memory_rlimit: cgroup
+                .is_none()
+                .then(|| config.resources.max_memory_mb * 1024 * 1024),
+            fsize_rlimit: (config.filesystem.max_file_size_mb > 0)
+                .then(|| config.filesystem.max_file_size_mb * 1024 * 1024),
+            priority: config.process_priority as libc::c_int,
+            egress_socket: egress_pair
+                .as_ref()
+                .map_or(-1, |(_, child)| child.as_raw_fd()),
+        };
+
+        let mut command = Command::new(&runner.executable);
+        command.args(runner.code_args(code));
+        command.current_dir(scratch.path());
+        command.env_clear();
+        command.env("PATH", "/usr/local/bin:/usr/bin:/bin");
+        command.env("HOME", scratch.path());
+        if config.filesystem.allow_temp_files {
+            command.env("TMPDIR", scratch.path());
+        }
+        if runner.egress.is_some() {
+            let proxy = format!("http://127.0.0.1:{}", EGRESS_PROXY_PORT);
+            for var in [
+                "HTTP_PROXY",
+                "HTTPS_PROXY",
+                "ALL_PROXY",
+                "http_proxy",
+                "https_proxy",
+                "all_proxy",
+            ] {
+                command.env(var, &proxy);
+            }
+        }
+        command.envs(env);
+        command.stdin(Stdio::null());
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        command.kill_on_drop(true);
+
+        // SAFETY: `enter_sandbox` runs between fork and exec and only issues
+        // raw syscalls on data prepared above; it does not allocate.
+        unsafe {
+            command.pre_exec(move || enter_sandbox(&setup));
+        }
+
+        let start = std::time::Instant::now();
+        let mut child = command.spawn().map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to start namespace sandbox '{}': {}",
+                runner.executable.display(),
+                e
+            )
+        })?;
+        // The child holds its own copies; close ours.
+        drop(landlock);
+
+        let _egress_task = match (egress_pair, &runner.egress) {
+            (Some((parent, child)), Some(proxy)) => {
+                drop(child);
+                let listener = tokio::task::spawn_blocking(move || receive_listener(parent))
+                    .await?
+                    .map_err(|e| anyhow::anyhow!("Failed to set up sandbox egress: {}", e))?;
+                let listener = tokio::net::TcpListener::from_std(listener)?;
+                Some(EgressTask(proxy.clone().serve(listener)))
+            }
+            _ => None,
+        };
+
+        let max_output = config.max_output_bytes;
+        let mut child_stdout = child.stdout.take();
+        let mut child_stderr = child.stderr.take();
+        let output = tokio::time::timeout(config.max_execution_time, async {
+            let stdout = async {
+                match child_stdout.as_mut() {
+                    Some(s) => read_limited(s, max_output).await,
+                    None => (String::new(), false),
+                }
+            };
+            let stderr = async {
+                match child_stderr.as_mut() {
+                    Some(s) => read_limited(s, max_output).await,
+                    None => (String::new(), false),
+                }
+            };
+            let ((stdout, stdout_truncated), (stderr, stderr_truncated)) =
+                tokio::join!(stdout, stderr);
+            let status = child.wait().await;
+            (stdout, stdout_truncated, stderr, stderr_truncated, status)
+        })
+        .await;
+        let execution_time_ms = start.elapsed().as_millis() as u64;
+
+        match output {
+            Ok((stdout, stdout_truncated, stderr, stderr_truncated, Ok(status))) => {
+                let exit_code = status.code().unwrap_or(-1);
+                drop(cgroup);
+                Ok(ExecutionResult {
+                    exit_code,
+                    stdout,
+                    stderr,
+                    execution_time_ms,
+                    success: status.success(),
+                    stdout_truncated,
+                    stderr_truncated,
+                    artifacts: Vec::new(),
+                    artifacts_truncated: false,
+                })
+            }
+            Ok((_, _, _, _, Err(e))) => Err(anyhow::anyhow!("Process execution failed: {}", e)),
+            Err(_) => {
+                if let Some(id) = child.id() {
+                    // SAFETY: the child leads its own process group (setpgid in
+                    // `enter_sandbox`); a stale id just returns ESRCH.
+                    unsafe {
+                        libc::killpg(id as i32, libc::SIGKILL);
+                    }
+                }
+                let _ = child.kill().await;
+                Err(anyhow::anyhow!(
+                    "Execution timed out after {:?}",
+                    config.max_execution_time
+                ))
+            }
+        }
+    }
+
+    async fn read_limited<R: AsyncReadExt + Unpin>(reader: &mut R, max: usize) -> (String, bool) {
+        let mut buf = Vec::new();
+        let _ = (&mut *reader)
+            .take(max as u64 + 1)
+            .read_to_end(&mut buf)
+            .await;
+        if buf.len() <= max {
+            return (String::from_utf8_lossy(&buf).into_owned(), false);
+        }
+        // Drain the rest so the child is not blocked on a full pipe.
+        let _ = tokio::io::copy(reader, &mut tokio::io::sink()).await;
+        buf.truncate(max);
+        let output = String::from_utf8_lossy(&buf);
+        (
+            format!("{}\n... [output truncated at {} bytes]", output, max),
+            true,
+        )
+    }
+
+    fn check(rc: libc::c_long) -> std::io::Result<()> {
+        if rc < 0 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn write_file(path: &CStr, contents: &[u8]) -> std::io::Result<()> {
+        // SAFETY: `path` is NUL-terminated and `contents` outlives the call.
+        unsafe {
+            let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+            check(fd as libc::c_long)?;
+            let written = libc::write(fd, contents.as_ptr().cast(), contents.len());
+            libc::close(fd);
+            check(written as libc::c_long)
+        }
+    }
+
+    /// Bring up loopback in the new network namespace, listen on the egress
+    /// proxy port there, and pass the listener to the parent, which serves it.
+    /// Runs in the forked child; the namespace has no other interface, so the
+    /// proxy is the only way out.
+    fn expose_egress_listener(socket: libc::c_int) -> std::io::Result<()> {
+        // SAFETY: raw syscalls on stack-allocated, fully initialised structs.
+        unsafe {
+            let ctl = libc::socket(libc::AF_INET, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, 0);
+            check(ctl as libc::c_long)?;
+            let mut ifr: libc::ifreq = std::mem::zeroed();
+            ifr.ifr_name[0] = b'l' as libc::c_char;
+            ifr.ifr_name[1] = b'o' as libc::c_char;
+            check(libc::ioctl(ctl, libc::SIOCGIFFLAGS, &mut ifr) as libc::c_long)?;
+            ifr.ifr_ifru.ifru_flags |= (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+            check(libc::ioctl(ctl, libc::SIOCSIFFLAGS, &ifr) as libc::c_long)?;
+            libc::close(ctl);
+
+            let listener = libc::socket(libc::AF_INET, libc::SOCK_STREAM | libc::SOCK_CLOEXEC, 0);
+            check(listener as libc::c_long)?;
+            let addr = libc::sockaddr_in {
+                sin_family: libc::AF_INET as libc::sa_family_t,
+                sin_port: EGRESS_PROXY_PORT.to_be(),
+                sin_addr: libc::in_addr {
+                    s_addr: u32::from_ne_bytes([127, 0, 0, 1]),
+                },
+                sin_zero: [0; 8],
+            };
+            check(libc::bind(
+                listener,
+                (&addr as *const libc::sockaddr_in).cast(),
+                std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+            ) as libc::c_long)?;
+            check(libc::listen(listener, 128) as libc::c_long)?;
+
+            let mut byte = 0u8;
+            let mut iov = libc::iovec {
+                iov_base: (&mut byte as *mut u8).cast(),
+                iov_len: 1,
+            };
+            let mut control = [0u64; 4];
+            let mut msg: libc::msghdr = std::mem::zeroed();
+            msg.msg_iov = &mut iov;
+            msg.msg_iovlen = 1;
+            msg.msg_control = control.as_mut_ptr().cast();
+            msg.msg_controllen = libc::CMSG_SPACE(std::mem::size_of::<libc::c_int>() as u32) as _;
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(std::mem::size_of::<libc::c_int>() as u32) as _;
+            std::ptr::write_unaligned(libc::CMSG_DATA(cmsg) as *mut libc::c_int, listener);
+            check(libc::sendmsg(socket, &msg, 0) as libc::c_long)?;
+            libc::close(listener);
+            libc::close(socket);
+        }
+        Ok(())
+    }
+
+    /// Runs in the forked child before exec.
+    fn enter_sandbox(setup: &ChildSetup) -> std::io::Result<()> {
+        // SAFETY: plain syscalls on pre-built arguments; see `execute`.
+        unsafe {
+            // Own process group so a timeout can kill the whole tree.
+            libc::setpgid(0, 0);
+
+            if setup.cgroup_procs >= 0 {
+                // Writing "0" moves the writing process.
+                check(libc::write(setup.cgroup_procs, b"0".as_ptr().cast(), 1) as libc::c_long)?;
+            }
+            for (resource, limit) in [
+                (libc::RLIMIT_FSIZE, setup.fsize_rlimit),
+                (libc::RLIMIT_AS, setup.memory_rlimit),
+            ] {
+                if let Some(bytes) = limit {
+                    let rlim = libc::rlimit {
+                        rlim_cur: bytes,
+                        rlim_max: bytes,
+                    };
+                    check(libc::setrlimit(resource, &rlim) as libc::c_long)?;
+                }
+            }
+            libc::setpriority(libc::PRIO_PROCESS, 0, setup.priority);
+
+            check(libc::unshare(setup.unshare_flags) as libc::c_long)?;
+            write_file(c"/proc/self/setgroups", b"deny")?;
+            write_file(c"/proc/self/uid_map", &setup.uid_map)?;
+            write_file(c"/proc/self/gid_map", &setup.gid_map)?;
+            if setup.egress_socket >= 0 {
+                expose_egress_listener(setup.egress_socket)?;
+            }
+
+            // unshare(CLONE_NEWPID) only applies to children: fork once more so
+            // the executed program is PID 1 of the new namespace, and have this
+            // process mirror its exit status.
+            let pid = libc::fork();
+            check(pid as libc::c_long)?;
+            if pid > 0 {
+                // Release the pipes (including std's exec-status pipe) so the
+                // parent sees EOF as soon as the program exits or execs.
+                libc::syscall(libc::SYS_close_range, 0u32, u32::MAX, 0u32);
+                let mut status = 0;
+                while libc::waitpid(pid, &mut status, 0) < 0 {
+                    if *libc::__errno_location() != libc::EINTR {
+                        libc::_exit(127);
+                    }
+                }
+                if libc::WIFSIGNALED(status) {
+                    libc::_exit(128 + libc::WTERMSIG(status));
+                }
+                libc::_exit(libc::WEXITSTATUS(status));
+            }
+
+            libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+            check(libc::mount(
+                std::ptr::null(),
+                c"/".as_ptr(),
+                std::ptr::null(),
+                libc::MS_REC | libc::MS_PRIVATE,
+                std::ptr::null(),
+            ) as libc::c_long)?;
+            // A fresh /proc shows only the sandbox's processes. Hosts that
+            // lock /proc (nested containers) refuse the mount; Landlock still
+            // keeps the host's /proc unreadable in that case.
+            libc::mount(
+                c"proc".as_ptr(),
+                c"/proc".as_ptr(),
+                c"proc".as_ptr(),
+                libc::MS_NOSUID | libc::MS_NODEV | libc::MS_NOEXEC,
+                std::ptr::null(),
+            );
+
+            // Root inside the user namespace, but exec grants no capabilities.
+            check(libc::prctl(
+                libc::PR_SET_SECUREBITS,
+                SECBIT_NOROOT
+                    | SECBIT_NOROOT_LOCKED
+                    | SECBIT_NO_SETUID_FIXUP
+                    | SECBIT_NO_SETUID_FIXUP_LOCKED
+                    | SECBIT_KEEP_CAPS_LOCKED,
+            ) as libc::c_long)?;
+            check(libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) as libc::c_long)?;
+
+            if setup.landlock >= 0 {
+                check(libc::syscall(
+                    libc::SYS_landlock_restrict_self,
+                    setup.landlock,
+                    0u32,
+                ))?;
+            }
+            for program in &setup.seccomp {
+                seccompiler::apply_filter(program).map_err(|_| std::io::Error::last_os_error())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile_config() -> NamespaceConfig {
+        let mut config = NamespaceConfig::default();
+        config.filesystem.read_paths = vec!["/srv/data/**/*.csv".to_string()];
+        config.filesystem.write_paths = vec!["/srv/out/*".to_string(), "relative/*".to_string()];
+        config.filesystem.denied_paths = vec!["/etc/*".to_string()];
+        config
+    }
+
+    #[test]
+    fn glob_patterns_reduce_to_directories() {
+        let (read, write) = profile_config().landlock_roots().unwrap();
+        assert!(read.contains(&PathBuf::from("/srv/data")));
+        assert!(read.contains(&PathBuf::from("/usr")));
+        assert_eq!(write, vec![PathBuf::from("/srv/out")]);
+    }
+
+    #[test]
+    fn denied_path_inside_allowed_path_is_rejected() {
+        let mut config = profile_config();
+        config.filesystem.denied_paths = vec!["/srv/data/secret/*".to_string()];
+        let err = config.landlock_roots().unwrap_err().to_string();
+        assert!(err.contains("/srv/data/secret"), "{}", err);
+
+        // An allowed path under a denied one is dropped instead.
+        config.filesystem.denied_paths = vec!["/srv/*".to_string()];
+        let (read, write) = config.landlock_roots().unwrap();
+        assert!(!read.iter().any(|p| p.starts_with("/srv")));
+        assert!(write.is_empty());
+    }
+
+    #[test]
+    fn profile_limits_map_to_cgroup_values() {
+        let mut profile = SandboxProfile::secure_default();
+        profile.resources.max_cpu_cores = 1.5;
+        profile.process_limits.max_child_processes = 4;
+        profile.network.access_mode = NetworkAccessMode::Full;
+        let config = NamespaceConfig::from_profile(&profile);
+        assert_eq!(config.cpu_max(), "150000 100000");
+        assert_eq!(config.max_processes, 5);
+        assert!(config.share_network);
+        assert!(!NamespaceConfig::default().share_network);
+        assert!(config.egress.is_none());
+        let restricted = NamespaceConfig::from_profile(&SandboxProfile::standard_default());
+        assert!(!restricted.share_network);
+        assert!(restricted.egress.is_some());
+    }
+
+    /// Config for execution tests: hosts without Landlock or a delegated
+    /// cgroup (most CI containers) still exercise namespaces and seccomp.
+    #[cfg(target_os = "linux")]
+    fn runner() -> Option<NamespaceRunner> {
+        let scratch = tempfile::tempdir().unwrap().keep();
+        let config = NamespaceConfig {
+            executable: "sh".to_string(),
+            scratch_root: scratch,
+            max_processes: 16,
+            max_execution_time: Duration::from_secs(10),
+            require_landlock: false,
+            ..Default::default()
+        };
+        match NamespaceRunner::new(config) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                eprintln!("skipping: namespace sandbox unavailable: {}", e);
+                None
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn runs_as_pid_one_without_host_environment() {
+        let Some(runner) = runner() else { return };
+        std::env::set_var("SYMBI_NS_TEST_LEAK", "visible");
+        let mut env = HashMap::new();
+        env.insert("GREETING".to_string(), "hello".to_string());
+        let result = runner
+            .execute(
+                "echo \"$$ $GREETING ${SYMBI_NS_TEST_LEAK:-hidden} $(id -u)\"",
+                env,
+            )
+            .await
+            .unwrap();
+        assert!(result.success, "stderr: {}", result.stderr);
+        assert_eq!(result.stdout.trim(), "1 hello hidden 0");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn seccomp_blocks_namespace_escape() {
+        let Some(runner) = runner() else { return };
+        if !Path::new("/usr/bin/unshare").exists() {
+            return;
+        }
+        let result = runner
+            .execute("/usr/bin/unshare -U true; echo rc=$?", HashMap::new())
+            .await
+            .unwrap();
+        assert!(result.stdout.contains("rc=1"), "stdout: {}", result.stdout);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn timeout_kills_the_sandbox() {
+        let Some(mut runner) = runner() else { return };
+        runner.config.max_execution_time = Duration::from_millis(300);
+        let err = runner.execute("sleep 5", HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn egress_proxy_is_the_only_way_out() {
+        use crate::config::{NetworkDestination, NetworkPolicy};
+        use tokio::io::AsyncWriteExt;
+
+        let upstream = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = upstream.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = upstream.accept().await {
+                let _ = stream.write_all(b"hello from upstream").await;
+            }
+        });
+        let mut egress = EgressPolicy::from_network_policy(&NetworkPolicy {
+            access_mode: NetworkAccessMode::Restricted,
+            allowed_destinations: vec![NetworkDestination {
+                host: "127.0.0.1".to_string(),
+                port: Some(port),
+                protocol: None,
+            }],
+            max_bandwidth_mbps: None,
+        });
+        egress.allow_private_destinations = true;
+        let config = NamespaceConfig {
+            executable: "python3".to_string(),
+            scratch_root: tempfile::tempdir().unwrap().keep(),
+            max_processes: 16,
+            max_execution_time: Duration::from_secs(20),
+            require_landlock: false,
+            egress: Some(egress),
+            ..Default::default()
+        };
+        let runner = match NamespaceRunner::new(config) {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("skipping: namespace sandbox unavailable: {}", e);
+                return;
+            }
+        };
+
+        let code = format!(
+            r#"
+import os, socket
+s = socket.create_connection(("127.0.0.1", 3128))
+s.sendall(b"CONNECT 127.0.0.1:{port} HTTP/1.1\r\n\r\n")
+data = b""
+while b"upstream" not in data:
+    chunk = s.recv(1024)
+    if not chunk:
+        break
+    data += chunk
+print(data.split(b"\r\n")[0].decode(), b"hello from upstream" in data)
+try:
+    socket.create_connection(("1.1.1.1", 53), timeout=1)
+    print("direct open")
+except OSError:
+    print("direct blocked")
+print(os.environ["HTTPS_PROXY"])
+"#
+        );
+        let result = runner.execute(&code, HashMap::new()).await.unwrap();
+        assert!(result.success, "stderr: {}", result.stderr);
+        let lines: Vec<&str> = result.stdout.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "HTTP/1.1 200 Connection established True",
+                "direct blocked",
+                "http://127.0.0.1:3128",
+            ]
+        );
+    }
+}
@@ -511,6 +511,8 @@ impl SandboxRunner for NativeRunner {
                     execution_time_ms: execution_time.as_millis() as u64,
                     stdout_truncated,
                     stderr_truncated,
+                    artifacts: Vec::new(),
+                    artifacts_truncated: false,
                 })
             }
             Ok((_, _, _, _, Err(e))) => {
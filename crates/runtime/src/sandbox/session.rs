@@ -0,0 +1,898 @@
+//! Persistent sandbox sessions
+//!
+//! A session is a long-lived sandbox that keeps its filesystem between
+//! executions, so an agent can build state across several tool calls
+//! instead of paying a fresh container start for each one. Runners that can
+//! host one return it from [`SandboxRunner::open_session`]; the
+//! [`SandboxSessionManager`] owns open sessions, closes them on an idle
+//! timeout or a maximum lifetime, and accounts each one with the
+//! [`ResourceManager`] as its own allocation while adding the time spent in
+//! executions to the usage of the agent that opened it. Only that agent may
+//! run executions in a session.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
+
+use super::artifacts::ExecutionRequest;
+use super::kernel::{KernelConfig, KernelLanguage};
+use super::{
+    build_runner, ExecutionResult, GVisorConfig, SandboxRunner, SandboxRunnerProfile, SandboxTier,
+};
+use crate::config::NetworkPolicy;
+use crate::resource::ResourceManager;
+use crate::types::{AgentId, ResourceRequirements};
+
+/// Unique identifier for a sandbox session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SandboxSessionId(pub uuid::Uuid);
+
+impl SandboxSessionId {
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+}
+
+impl Default for SandboxSessionId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Display for SandboxSessionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An open session and the agent it belongs to, as carried by the code that
+/// runs executions on that agent's behalf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionBinding {
+    pub id: SandboxSessionId,
+    pub owner: AgentId,
+}
+
+/// A live sandbox that runs several executions against the same state
+#[async_trait]
+pub trait SandboxSession: Send + Sync {
+    /// Run one execution inside the session
+    async fn execute(&self, request: ExecutionRequest) -> Result<ExecutionResult, anyhow::Error>;
+
+    /// Tear the sandbox down. Further executions fail.
+    async fn close(&self) -> Result<(), anyhow::Error>;
+}
+
+/// Lifetime limits and resource reservation for managed sessions
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    /// Close a session after this long without an execution
+    pub idle_timeout: Duration,
+    /// Close a session this long after it opened, however busy it is
+    pub max_lifetime: Duration,
+    /// Maximum number of sessions open at once
+    pub max_sessions: usize,
+    /// Resources reserved with the `ResourceManager` for each session
+    pub requirements: ResourceRequirements,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::from_secs(600),
+            max_lifetime: Duration::from_secs(3600),
+            max_sessions: 16,
+            requirements: ResourceRequirements::default(),
+        }
+    }
+}
+
+/// `[sandbox_sessions]` section of `symbiont.toml`. When present, `symbi up`
+/// gives every governed reasoning run a session on this tier and offers the
+/// model the `sandbox_exec` tool.
+///
+/// ```toml
+/// [sandbox_sessions]
+/// tier = "Docker"              # or "GVisor"
+/// image = "python:3.12-slim"
+/// idle_timeout_seconds = 600
+/// max_lifetime_seconds = 3600
+/// max_sessions = 16
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxSessionsConfig {
+    /// Tier hosting the sessions. It must support persistent sessions.
+    #[serde(default = "default_session_tier")]
+    pub tier: SandboxTier,
+    /// Container image for the Docker and gVisor tiers. Unset: the tier's
+    /// default image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default = "default_idle_timeout_seconds")]
+    pub idle_timeout_seconds: u64,
+    #[serde(default = "default_max_lifetime_seconds")]
+    pub max_lifetime_seconds: u64,
+    #[serde(default = "default_max_sessions")]
+    pub max_sessions: usize,
+    /// How often expired sessions are reaped.
+    #[serde(default = "default_reap_interval_seconds")]
+    pub reap_interval_seconds: u64,
+    /// Network access for session containers. `Restricted` confines them to
+    /// an internal network whose only way out is the egress proxy. Unset:
+    /// no network.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkPolicy>,
+    /// Interpreter kernel started in each session on first use and offered
+    /// to the model as the `run_cell` tool. Unset: `sandbox_exec` only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kernel: Option<KernelLanguage>,
+}
+
+fn default_session_tier() -> SandboxTier {
+    SandboxTier::Docker
+}
+
+fn default_idle_timeout_seconds() -> u64 {
+    600
+}
+
+fn default_max_lifetime_seconds() -> u64 {
+    3600
+}
+
+fn default_max_sessions() -> usize {
+    16
+}
+
+fn default_reap_interval_seconds() -> u64 {
+    60
+}
+
+impl Default for SandboxSessionsConfig {
+    fn default() -> Self {
+        Self {
+            tier: default_session_tier(),
+            image: None,
+            idle_timeout_seconds: default_idle_timeout_seconds(),
+            max_lifetime_seconds: default_max_lifetime_seconds(),
+            max_sessions: default_max_sessions(),
+            reap_interval_seconds: default_reap_interval_seconds(),
+            network: None,
+            kernel: None,
+        }
+    }
+}
+
+impl SandboxSessionsConfig {
+    /// Lifetime limits for the manager
+    pub fn session_config(&self) -> SessionConfig {
+        SessionConfig {
+            idle_timeout: Duration::from_secs(self.idle_timeout_seconds),
+            max_lifetime: Duration::from_secs(self.max_lifetime_seconds),
+            max_sessions: self.max_sessions,
+            ..SessionConfig::default()
+        }
+    }
+
+    /// Runner settings for `build_runner`
+    pub fn runner_profile(&self) -> SandboxRunnerProfile {
+        if self.image.is_none() && self.network.is_none() {
+            return SandboxRunnerProfile::default();
+        }
+        let mut gvisor = match &self.image {
+            Some(image) => GVisorConfig::for_image(image),
+            None => GVisorConfig::default(),
+        };
+        if let Some(network) = &self.network {
+            gvisor.docker = gvisor.docker.with_network_policy(network);
+        }
+        SandboxRunnerProfile {
+            docker: Some(gvisor.docker.clone()),
+            gvisor: Some(gvisor),
+            ..SandboxRunnerProfile::default()
+        }
+    }
+
+    /// Kernel settings for `SandboxSessionExecutor::with_kernel`
+    pub fn kernel_config(&self) -> Option<KernelConfig> {
+        self.kernel.map(KernelConfig::new)
+    }
+
+    pub fn reap_interval(&self) -> Duration {
+        Duration::from_secs(self.reap_interval_seconds)
+    }
+}
+
+/// Snapshot of a managed session's accounting
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub id: SandboxSessionId,
+    /// Agent that opened the session. It alone may run executions, and
+    /// their time is added to its usage.
+    pub owner: AgentId,
+    /// Allocation reserving the session's resources in the `ResourceManager`
+    pub account: AgentId,
+    pub executions: u64,
+    /// Total time spent inside executions
+    pub busy_time: Duration,
+    pub age: Duration,
+    pub idle: Duration,
+}
+
+struct SessionStats {
+    last_used: Instant,
+    executions: u64,
+    busy_time: Duration,
+}
+
+struct ManagedSession {
+    session: Box<dyn SandboxSession>,
+    owner: AgentId,
+    account: AgentId,
+    opened_at: Instant,
+    stats: Mutex<SessionStats>,
+}
+
+impl ManagedSession {
+    async fn info(&self, id: SandboxSessionId) -> SessionInfo {
+        let stats = self.stats.lock().await;
+        SessionInfo {
+            id,
+            owner: self.owner,
+            account: self.account,
+            executions: stats.executions,
+            busy_time: stats.busy_time,
+            age: self.opened_at.elapsed(),
+            idle: stats.last_used.elapsed(),
+        }
+    }
+
+    /// Why the session should be closed, if it has outlived a limit.
+    async fn expiry(&self, config: &SessionConfig) -> Option<&'static str> {
+        if self.opened_at.elapsed() >= config.max_lifetime {
+            return Some("max lifetime");
+        }
+        if self.stats.lock().await.last_used.elapsed() >= config.idle_timeout {
+            return Some("idle timeout");
+        }
+        None
+    }
+}
+
+/// Opens, tracks and expires sandbox sessions for one runner
+pub struct SandboxSessionManager {
+    runner: Arc<dyn SandboxRunner>,
+    config: SessionConfig,
+    resources: Option<Arc<dyn ResourceManager + Send + Sync>>,
+    sessions: Mutex<HashMap<SandboxSessionId, Arc<ManagedSession>>>,
+    /// Slots held by `open` calls still starting their sandbox
+    opening: AtomicUsize,
+    /// Serialises read-modify-write updates of owners' usage records
+    usage_lock: Mutex<()>,
+}
+
+/// A session slot claimed by an in-flight `open`; released on drop so a
+/// failed or cancelled open gives the slot back.
+struct SlotReservation<'a>(&'a AtomicUsize);
+
+impl Drop for SlotReservation<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl SandboxSessionManager {
+    /// Create a manager that opens sessions on `runner`
+    pub fn new(runner: Arc<dyn SandboxRunner>, config: SessionConfig) -> Self {
+        Self {
+            runner,
+            config,
+            resources: None,
+            sessions: Mutex::new(HashMap::new()),
+            opening: AtomicUsize::new(0),
+            usage_lock: Mutex::new(()),
+        }
+    }
+
+    /// Create a manager whose runner is built from `config`. Refuses the
+    /// unisolated tier in production, like every other runner factory call.
+    pub fn from_config(config: &SandboxSessionsConfig) -> Result<Self, anyhow::Error> {
+        config
+            .tier
+            .enforce_production_guard()
+            .map_err(anyhow::Error::msg)?;
+        let runner = build_runner(config.tier.clone(), &config.runner_profile())?;
+        Ok(Self::new(Arc::from(runner), config.session_config()))
+    }
+
+    /// Account every session as an allocation in `resources`
+    pub fn with_resource_manager(
+        mut self,
+        resources: Arc<dyn ResourceManager + Send + Sync>,
+    ) -> Self {
+        self.resources = Some(resources);
+        self
+    }
+
+    /// Open a session on behalf of `owner`.
+    pub async fn open(&self, owner: AgentId) -> Result<SandboxSessionId, anyhow::Error> {
+        self.reap_expired().await;
+        let slot = self.reserve_slot().await?;
+
+        // Reserve resources before starting anything so a refusal is cheap.
+        let account = AgentId::new();
+        if let Some(resources) = &self.resources {
+            resources
+                .allocate_resources(account, self.config.requirements.clone())
+                .await
+                .map_err(|e| anyhow::anyhow!("sandbox session allocation refused: {}", e))?;
+        }
+        let session = match self.runner.open_session().await {
+            Ok(session) => session,
+            Err(e) => {
+                self.release(account).await;
+                return Err(e);
+            }
+        };
+
+        let id = SandboxSessionId::new();
+        let now = Instant::now();
+        // Hand the slot over to the map under its lock so a concurrent open
+        // never sees the session counted in neither.
+        let mut sessions = self.sessions.lock().await;
+        sessions.insert(
+            id,
+            Arc::new(ManagedSession {
+                session,
+                owner,
+                account,
+                opened_at: now,
+                stats: Mutex::new(SessionStats {
+                    last_used: now,
+                    executions: 0,
+                    busy_time: Duration::ZERO,
+                }),
+            }),
+        );
+        drop(slot);
+        drop(sessions);
+        tracing::info!("Opened sandbox session {} for agent {}", id, owner);
+        Ok(id)
+    }
+
+    /// Run an execution in an open session on behalf of `caller`, which
+    /// must be the agent that opened it. Executions in one session run one
+    /// at a time; an expired session is closed instead.
+    pub async fn execute(
+        &self,
+        id: SandboxSessionId,
+        caller: AgentId,
+        request: ExecutionRequest,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        let managed = self
+            .sessions
+            .lock()
+            .await
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("sandbox session {} is not open", id))?;
+        if managed.owner != caller {
+            tracing::warn!(
+                "Agent {} tried to use sandbox session {} owned by {}",
+                caller,
+                id,
+                managed.owner
+            );
+            anyhow::bail!("sandbox session {} belongs to another agent", id);
+        }
+        if let Some(reason) = managed.expiry(&self.config).await {
+            self.close(id).await?;
+            anyhow::bail!("sandbox session {} closed ({})", id, reason);
+        }
+
+        // Holding the stats lock across the run serialises executions and
+        // keeps the idle clock from expiring a busy session.
+        let mut stats = managed.stats.lock().await;
+        let remaining = self
+            .config
+            .max_lifetime
+            .saturating_sub(managed.opened_at.elapsed());
+        let start = Instant::now();
+        let outcome = tokio::time::timeout(remaining, managed.session.execute(request)).await;
+        let elapsed = start.elapsed();
+        stats.busy_time += elapsed;
+        stats.executions += 1;
+        stats.last_used = Instant::now();
+        drop(stats);
+
+        if let Some(resources) = &self.resources {
+            // Add this execution to the owner's record rather than replace
+            // what the agent has used elsewhere.
+            let _guard = self.usage_lock.lock().await;
+            let mut usage = resources.get_usage(managed.owner).await.unwrap_or_default();
+            usage.uptime += elapsed;
+            if let Err(e) = resources.update_usage(managed.owner, usage).await {
+                tracing::warn!("Failed to record usage for sandbox session {}: {}", id, e);
+            }
+        }
+
+        match outcome {
+            Ok(result) => result,
+            Err(_) => {
+                self.close(id).await?;
+                anyhow::bail!("sandbox session {} closed (max lifetime)", id)
+            }
+        }
+    }
+
+    /// Close a session and release its allocation. Closing an unknown
+    /// session is a no-op.
+    pub async fn close(&self, id: SandboxSessionId) -> Result<(), anyhow::Error> {
+        let Some(managed) = self.sessions.lock().await.remove(&id) else {
+            return Ok(());
+        };
+        let closed = managed.session.close().await;
+        self.release(managed.account).await;
+        tracing::info!("Closed sandbox session {}", id);
+        closed
+    }
+
+    /// Accounting for an open session
+    pub async fn info(&self, id: SandboxSessionId) -> Option<SessionInfo> {
+        let managed = self.sessions.lock().await.get(&id).cloned()?;
+        Some(managed.info(id).await)
+    }
+
+    /// Accounting for every open session
+    pub async fn list(&self) -> Vec<SessionInfo> {
+        let sessions: Vec<_> = self
+            .sessions
+            .lock()
+            .await
+            .iter()
+            .map(|(id, s)| (*id, s.clone()))
+            .collect();
+        let mut infos = Vec::with_capacity(sessions.len());
+        for (id, managed) in sessions {
+            infos.push(managed.info(id).await);
+        }
+        infos
+    }
+
+    /// Close every session past its idle timeout or maximum lifetime.
+    /// Returns how many were closed.
+    pub async fn reap_expired(&self) -> usize {
+        let sessions: Vec<_> = self
+            .sessions
+            .lock()
+            .await
+            .iter()
+            .map(|(id, s)| (*id, s.clone()))
+            .collect();
+        let mut reaped = 0;
+        for (id, managed) in sessions {
+            // A session mid-execution holds its stats lock; skip it rather
+            // than wait for it to finish.
+            let Ok(stats) = managed.stats.try_lock() else {
+                continue;
+            };
+            drop(stats);
+            if let Some(reason) = managed.expiry(&self.config).await {
+                tracing::info!("Reaping sandbox session {} ({})", id, reason);
+                if let Err(e) = self.close(id).await {
+                    tracing::warn!("Failed to close sandbox session {}: {}", id, e);
+                }
+                reaped += 1;
+            }
+        }
+        reaped
+    }
+
+    /// Close every open session
+    pub async fn close_all(&self) {
+        let ids: Vec<_> = self.sessions.lock().await.keys().copied().collect();
+        for id in ids {
+            if let Err(e) = self.close(id).await {
+                tracing::warn!("Failed to close sandbox session {}: {}", id, e);
+            }
+        }
+    }
+
+    /// Reap expired sessions every `every` until the manager is dropped.
+    pub fn spawn_reaper(self: &Arc<Self>, every: Duration) -> tokio::task::JoinHandle<()> {
+        let manager: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                match manager.upgrade() {
+                    Some(manager) => {
+                        manager.reap_expired().await;
+                    }
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// Claim one of the `max_sessions` slots, counting both open sessions
+    /// and opens still in flight.
+    async fn reserve_slot(&self) -> Result<SlotReservation<'_>, anyhow::Error> {
+        let sessions = self.sessions.lock().await;
+        if sessions.len() + self.opening.load(Ordering::SeqCst) >= self.config.max_sessions {
+            anyhow::bail!(
+                "sandbox session limit reached ({} open)",
+                self.config.max_sessions
+            );
+        }
+        self.opening.fetch_add(1, Ordering::SeqCst);
+        Ok(SlotReservation(&self.opening))
+    }
+
+    async fn release(&self, account: AgentId) {
+        if let Some(resources) = &self.resources {
+            if let Err(e) = resources.deallocate_resources(account).await {
+                tracing::warn!("Failed to release sandbox session allocation: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::{ResourceSystemStatus, ResourceViolation};
+    use crate::types::{
+        ComponentHealth, ResourceAllocation, ResourceError, ResourceLimits, ResourceUsage,
+    };
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Session whose "filesystem" is a counter that survives executions.
+    struct CounterSession {
+        counter: AtomicUsize,
+        closed: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl SandboxSession for CounterSession {
+        async fn execute(
+            &self,
+            request: ExecutionRequest,
+        ) -> Result<ExecutionResult, anyhow::Error> {
+            if request.code == "sleep" {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            let n = self.counter.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(ExecutionResult::success(n.to_string(), 0))
+        }
+
+        async fn close(&self) -> Result<(), anyhow::Error> {
+            self.closed.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct CounterRunner {
+        closed: Arc<AtomicBool>,
+        open_delay: Duration,
+    }
+
+    #[async_trait]
+    impl SandboxRunner for CounterRunner {
+        async fn execute(
+            &self,
+            _code: &str,
+            _env: HashMap<String, String>,
+        ) -> Result<ExecutionResult, anyhow::Error> {
+            Ok(ExecutionResult::success(String::new(), 0))
+        }
+
+        async fn open_session(&self) -> Result<Box<dyn SandboxSession>, anyhow::Error> {
+            tokio::time::sleep(self.open_delay).await;
+            Ok(Box::new(CounterSession {
+                counter: AtomicUsize::new(0),
+                closed: self.closed.clone(),
+            }))
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingResources {
+        allocated: parking_lot::Mutex<Vec<AgentId>>,
+        released: parking_lot::Mutex<Vec<AgentId>>,
+        usage: parking_lot::Mutex<Vec<(AgentId, ResourceUsage)>>,
+    }
+
+    #[async_trait]
+    impl ResourceManager for RecordingResources {
+        async fn allocate_resources(
+            &self,
+            agent_id: AgentId,
+            _requirements: ResourceRequirements,
+        ) -> Result<ResourceAllocation, ResourceError> {
+            self.allocated.lock().push(agent_id);
+            Ok(ResourceAllocation {
+                agent_id,
+                allocated_memory: 0,
+                allocated_cpu_cores: 0.0,
+                allocated_disk_io: 0,
+                allocated_network_io: 0,
+                allocation_time: std::time::SystemTime::now(),
+            })
+        }
+        async fn deallocate_resources(&self, agent_id: AgentId) -> Result<(), ResourceError> {
+            self.released.lock().push(agent_id);
+            Ok(())
+        }
+        async fn update_usage(
+            &self,
+            agent_id: AgentId,
+            usage: ResourceUsage,
+        ) -> Result<(), ResourceError> {
+            self.usage.lock().push((agent_id, usage));
+            Ok(())
+        }
+        async fn get_usage(&self, agent_id: AgentId) -> Result<ResourceUsage, ResourceError> {
+            self.usage
+                .lock()
+                .iter()
+                .rev()
+                .find(|(a, _)| *a == agent_id)
+                .map(|(_, usage)| usage.clone())
+                .ok_or(ResourceError::AgentNotFound { agent_id })
+        }
+        async fn get_system_status(&self) -> ResourceSystemStatus {
+            unimplemented!()
+        }
+        async fn set_limits(
+            &self,
+            _agent_id: AgentId,
+            _limits: ResourceLimits,
+        ) -> Result<(), ResourceError> {
+            Ok(())
+        }
+        async fn check_limits(&self, _agent_id: AgentId) -> Result<bool, ResourceError> {
+            Ok(true)
+        }
+        async fn check_resource_violations(
+            &self,
+            _agent_id: AgentId,
+        ) -> Result<Vec<ResourceViolation>, ResourceError> {
+            Ok(Vec::new())
+        }
+        async fn shutdown(&self) -> Result<(), ResourceError> {
+            Ok(())
+        }
+        async fn check_health(&self) -> Result<ComponentHealth, ResourceError> {
+            Ok(ComponentHealth::healthy(None))
+        }
+    }
+
+    #[tokio::test]
+    async fn executions_share_session_state_and_are_accounted() {
+        let runner = Arc::new(CounterRunner::default());
+        let resources = Arc::new(RecordingResources::default());
+        let manager = SandboxSessionManager::new(runner.clone(), SessionConfig::default())
+            .with_resource_manager(resources.clone());
+
+        let owner = AgentId::new();
+        let id = manager.open(owner).await.unwrap();
+        for expected in ["1", "2", "3"] {
+            let result = manager
+                .execute(id, owner, ExecutionRequest::new("tick"))
+                .await
+                .unwrap();
+            assert_eq!(result.stdout, expected);
+        }
+
+        let info = manager.info(id).await.unwrap();
+        assert_eq!(info.owner, owner);
+        assert_eq!(info.executions, 3);
+        assert_eq!(resources.allocated.lock().as_slice(), &[info.account]);
+        // Executions are charged to the owning agent, not the reservation.
+        let charged: Vec<AgentId> = resources.usage.lock().iter().map(|(a, _)| *a).collect();
+        assert_eq!(charged, vec![owner; 3]);
+
+        // Another agent cannot run in the session.
+        let err = manager
+            .execute(id, AgentId::new(), ExecutionRequest::new("tick"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("another agent"), "{}", err);
+        assert_eq!(manager.info(id).await.unwrap().executions, 3);
+
+        manager.close(id).await.unwrap();
+        assert!(runner.closed.load(Ordering::SeqCst));
+        assert_eq!(resources.released.lock().as_slice(), &[info.account]);
+        assert!(manager
+            .execute(id, owner, ExecutionRequest::new("tick"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn executions_add_to_the_owners_existing_usage() {
+        let runner = Arc::new(CounterRunner::default());
+        let resources = Arc::new(RecordingResources::default());
+        let manager = SandboxSessionManager::new(runner, SessionConfig::default())
+            .with_resource_manager(resources.clone());
+
+        let owner = AgentId::new();
+        let earlier = ResourceUsage {
+            memory_used: 4096,
+            uptime: Duration::from_secs(10),
+            ..ResourceUsage::default()
+        };
+        resources.usage.lock().push((owner, earlier));
+
+        let id = manager.open(owner).await.unwrap();
+        for _ in 0..2 {
+            manager
+                .execute(id, owner, ExecutionRequest::new("tick"))
+                .await
+                .unwrap();
+        }
+
+        let usage = resources.get_usage(owner).await.unwrap();
+        assert_eq!(usage.memory_used, 4096);
+        assert!(usage.uptime >= Duration::from_secs(10));
+        let uptimes: Vec<Duration> = resources
+            .usage
+            .lock()
+            .iter()
+            .map(|(_, u)| u.uptime)
+            .collect();
+        assert!(uptimes.windows(2).all(|w| w[0] <= w[1]), "{:?}", uptimes);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_and_lifetime_limits_close_sessions() {
+        let config = SessionConfig {
+            idle_timeout: Duration::from_secs(60),
+            max_lifetime: Duration::from_secs(100),
+            ..Default::default()
+        };
+        let manager = SandboxSessionManager::new(Arc::new(CounterRunner::default()), config);
+        let agent = AgentId::new();
+
+        let idle = manager.open(agent).await.unwrap();
+        tokio::time::advance(Duration::from_secs(61)).await;
+        let err = manager
+            .execute(idle, agent, ExecutionRequest::new("tick"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("idle timeout"), "{}", err);
+        assert!(manager.info(idle).await.is_none());
+
+        let busy = manager.open(agent).await.unwrap();
+        for _ in 0..3 {
+            tokio::time::advance(Duration::from_secs(30)).await;
+            manager
+                .execute(busy, agent, ExecutionRequest::new("tick"))
+                .await
+                .unwrap();
+        }
+        tokio::time::advance(Duration::from_secs(30)).await;
+        assert_eq!(manager.reap_expired().await, 1);
+        assert!(manager.list().await.is_empty());
+
+        // An execution cannot outrun the remaining lifetime either.
+        let slow = manager.open(agent).await.unwrap();
+        tokio::time::advance(Duration::from_secs(50)).await;
+        manager
+            .execute(slow, agent, ExecutionRequest::new("tick"))
+            .await
+            .unwrap();
+        tokio::time::advance(Duration::from_secs(40)).await;
+        let err = manager
+            .execute(slow, agent, ExecutionRequest::new("sleep"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("max lifetime"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn session_limit_and_unsupported_runners_are_refused() {
+        let config = SessionConfig {
+            max_sessions: 1,
+            ..Default::default()
+        };
+        let manager = SandboxSessionManager::new(Arc::new(CounterRunner::default()), config);
+        manager.open(AgentId::new()).await.unwrap();
+        assert!(manager.open(AgentId::new()).await.is_err());
+        manager.close_all().await;
+        assert!(manager.open(AgentId::new()).await.is_ok());
+
+        struct Stateless;
+        #[async_trait]
+        impl SandboxRunner for Stateless {
+            async fn execute(
+                &self,
+                _code: &str,
+                _env: HashMap<String, String>,
+            ) -> Result<ExecutionResult, anyhow::Error> {
+                Ok(ExecutionResult::success(String::new(), 0))
+            }
+        }
+        let resources = Arc::new(RecordingResources::default());
+        let manager = SandboxSessionManager::new(Arc::new(Stateless), SessionConfig::default())
+            .with_resource_manager(resources.clone());
+        assert!(manager.open(AgentId::new()).await.is_err());
+        // The reservation made before the failed start is released.
+        assert_eq!(*resources.allocated.lock(), *resources.released.lock());
+
+        // So is the session slot: a second failure is the runner's, not the limit's.
+        let manager = SandboxSessionManager::new(
+            Arc::new(Stateless),
+            SessionConfig {
+                max_sessions: 1,
+                ..Default::default()
+            },
+        );
+        assert!(manager.open(AgentId::new()).await.is_err());
+        let err = manager.open(AgentId::new()).await.unwrap_err();
+        assert!(err.to_string().contains("persistent sessions"), "{}", err);
+    }
+
+    #[test]
+    fn sessions_config_fills_in_defaults() {
+        let cfg: SandboxSessionsConfig = toml::from_str(
+            r#"
+            tier = "GVisor"
+            image = "node:20-alpine"
+            max_sessions = 4
+            "#,
+        )
+        .unwrap();
+        let session = cfg.session_config();
+        assert_eq!(session.max_sessions, 4);
+        assert_eq!(session.idle_timeout, Duration::from_secs(600));
+        assert_eq!(cfg.reap_interval(), Duration::from_secs(60));
+        let profile = cfg.runner_profile();
+        assert_eq!(profile.gvisor.unwrap().docker.image, "node:20-alpine");
+
+        let cfg: SandboxSessionsConfig = toml::from_str(
+            r#"
+            [network]
+            access_mode = "Restricted"
+            allowed_destinations = [{ host = "pypi.org", port = 443 }]
+            "#,
+        )
+        .unwrap();
+        let docker = cfg.runner_profile().docker.unwrap();
+        assert_eq!(docker.network_mode, "none");
+        assert!(docker.egress.is_some());
+
+        let cfg: SandboxSessionsConfig = toml::from_str("").unwrap();
+        assert_eq!(cfg.tier, SandboxTier::Docker);
+        assert!(cfg.runner_profile().docker.is_none());
+        assert!(cfg.kernel_config().is_none());
+
+        let cfg: SandboxSessionsConfig = toml::from_str(r#"kernel = "node""#).unwrap();
+        let kernel = cfg.kernel_config().unwrap();
+        assert_eq!(kernel.language, KernelLanguage::Node);
+        assert!(toml::from_str::<SandboxSessionsConfig>(r#"kernel = "ruby""#).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_opens_respect_the_session_limit() {
+        let runner = CounterRunner {
+            open_delay: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let config = SessionConfig {
+            max_sessions: 2,
+            ..Default::default()
+        };
+        let manager = SandboxSessionManager::new(Arc::new(runner), config);
+
+        let opens = futures::future::join_all((0..4).map(|_| manager.open(AgentId::new()))).await;
+        assert_eq!(opens.iter().filter(|r| r.is_ok()).count(), 2);
+        assert_eq!(manager.list().await.len(), 2);
+    }
+}
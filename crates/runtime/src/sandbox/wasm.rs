@@ -0,0 +1,658 @@
+//! WebAssembly (WASI) sandbox runner
+//!
+//! Executes code inside an embedded wasmtime engine instead of a process.
+//! The guest sees only what WASI hands it: the arguments, the environment
+//! passed to [`SandboxRunner::execute`], captured stdout/stderr, and the
+//! directories preopened from [`WasmConfig::preopened_dirs`] plus a
+//! per-execution scratch directory at `/work`. There is no network access
+//! and no other view of the host filesystem.
+//!
+//! Two kinds of code are accepted:
+//!
+//! - with [`WasmConfig::interpreter`] set, `code` is source text handed to a
+//!   WASI build of an interpreter (e.g. CPython's `python.wasm` with `-c`,
+//!   or QuickJS's `qjs.wasm` with `-e`), which is compiled once up front;
+//! - otherwise `code` is a WASI command module, either as WAT text or as a
+//!   base64-encoded `.wasm` binary, compiled per execution.
+//!
+//! CPU time is bounded by an epoch deadline (wall clock, checked every
+//! [`EPOCH_TICK`]) and optionally by fuel; linear memory is capped through
+//! store limits. Startup is a few milliseconds, which makes this tier a
+//! good fit for small, pure-compute transforms.
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::time::Duration;
+use wasmtime::{
+    Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder, Trap, UpdateDeadline,
+};
+use wasmtime_wasi::preview1::{self, WasiP1Ctx};
+use wasmtime_wasi::{
+    DirPerms, FilePerms, I32Exit, OutputStream, Pollable, StdoutStream, StreamError, StreamResult,
+    WasiCtxBuilder,
+};
+
+use super::namespace::glob_roots;
+use super::{ExecutionResult, SandboxRunner};
+use crate::config::SandboxProfile;
+
+/// Default maximum output size in bytes (10 MB)
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 10 * 1024 * 1024;
+
+/// Interval at which the engine epoch advances.
+const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+/// Guest path of the per-execution scratch directory.
+const SCRATCH_GUEST_PATH: &str = "/work";
+
+/// A WASI interpreter module that runs source code.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmInterpreter {
+    /// Path to the interpreter's `.wasm` module.
+    pub module: PathBuf,
+    /// Arguments placed before the code, e.g. `["-c"]` for Python or
+    /// `["-e"]` for QuickJS.
+    pub args: Vec<String>,
+}
+
+/// A host directory exposed to the guest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmPreopen {
+    /// Directory on the host.
+    pub host_path: PathBuf,
+    /// Path the guest sees it under.
+    pub guest_path: String,
+    /// Whether the guest may create, modify and delete files in it.
+    pub writable: bool,
+}
+
+/// Configuration for the WASI sandbox tier.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmConfig {
+    /// Interpreter that runs `code` as source text. When unset, `code` must
+    /// be a WASI module (WAT text or base64 `.wasm`).
+    pub interpreter: Option<WasmInterpreter>,
+    /// Host directories visible to the guest. Nothing else is.
+    pub preopened_dirs: Vec<WasmPreopen>,
+    /// Directory that per-execution scratch directories are created under.
+    /// The scratch directory is preopened read-write at `/work`.
+    pub scratch_root: PathBuf,
+    /// Maximum linear memory per execution in megabytes. `memory.grow`
+    /// past it fails inside the guest.
+    pub max_memory_mb: u64,
+    /// Instruction budget per execution. `None` disables fuel metering and
+    /// leaves only the wall-clock deadline.
+    pub max_fuel: Option<u64>,
+    /// Maximum execution time (timeout)
+    pub max_execution_time: Duration,
+    /// Maximum output bytes per stream before truncation (default: 10MB)
+    pub max_output_bytes: usize,
+}
+
+impl Default for WasmConfig {
+    fn default() -> Self {
+        let profile = SandboxProfile::secure_default();
+        // The secure default never nests a denied path inside an allowed
+        // one; should that change, fail closed with no preopens.
+        Self::with_preopens(&profile, preopens(&profile).unwrap_or_default())
+    }
+}
+
+impl WasmConfig {
+    /// Derive a WASI config from an SLM sandbox profile.
+    ///
+    /// `read_paths` and `write_paths` become preopened directories at the
+    /// same guest path (glob patterns reduced to their directory); anything
+    /// under `denied_paths` is left out. Fails when a denied path lies
+    /// inside an allowed one, since a preopen exposes its whole subtree.
+    pub fn from_profile(profile: &SandboxProfile) -> Result<Self, anyhow::Error> {
+        Ok(Self::with_preopens(profile, preopens(profile)?))
+    }
+
+    fn with_preopens(profile: &SandboxProfile, preopened_dirs: Vec<WasmPreopen>) -> Self {
+        Self {
+            interpreter: None,
+            preopened_dirs,
+            scratch_root: std::env::temp_dir().join("symbiont-wasm"),
+            max_memory_mb: profile.resources.max_memory_mb,
+            max_fuel: None,
+            max_execution_time: Duration::from_secs(
+                profile.process_limits.max_execution_time_seconds,
+            ),
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+        }
+    }
+}
+
+/// Resolve the preopened directories for `profile`.
+fn preopens(profile: &SandboxProfile) -> Result<Vec<WasmPreopen>, anyhow::Error> {
+    let denied = glob_roots(&profile.filesystem.denied_paths);
+    let write = glob_roots(&profile.filesystem.write_paths);
+    let read: Vec<_> = glob_roots(&profile.filesystem.read_paths)
+        .into_iter()
+        .filter(|p| !write.contains(p))
+        .collect();
+
+    for d in &denied {
+        if let Some(a) = read
+            .iter()
+            .chain(&write)
+            .find(|a| d.starts_with(a) && d != *a)
+        {
+            anyhow::bail!(
+                "denied path '{}' lies inside allowed path '{}'; a WASI preopen cannot \
+                 exclude a subtree of its directory",
+                d.display(),
+                a.display()
+            );
+        }
+    }
+    let allowed = |p: &PathBuf| !denied.iter().any(|d| p.starts_with(d));
+    Ok(read
+        .into_iter()
+        .map(|p| (p, false))
+        .chain(write.into_iter().map(|p| (p, true)))
+        .filter(|(p, _)| allowed(p))
+        .map(|(p, writable)| WasmPreopen {
+            guest_path: p.to_string_lossy().into_owned(),
+            host_path: p,
+            writable,
+        })
+        .collect())
+}
+
+/// Per-store state: the WASI context and the memory limiter.
+struct WasmState {
+    wasi: WasiP1Ctx,
+    limits: StoreLimits,
+}
+
+/// Advances the engine epoch until dropped.
+struct EpochTicker {
+    stop: Arc<AtomicBool>,
+}
+
+impl EpochTicker {
+    fn start(engine: Engine) -> Result<Self, anyhow::Error> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        std::thread::Builder::new()
+            .name("symbi-wasm-epoch".to_string())
+            .spawn(move || {
+                while !flag.load(Ordering::Relaxed) {
+                    std::thread::sleep(EPOCH_TICK);
+                    engine.increment_epoch();
+                }
+            })
+            .map_err(|e| anyhow::anyhow!("failed to spawn wasm epoch thread: {}", e))?;
+        Ok(Self { stop })
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Sandbox runner executing code in an embedded WASI engine.
+pub struct WasmRunner {
+    config: WasmConfig,
+    engine: Engine,
+    linker: Linker<WasmState>,
+    interpreter: Option<(Module, Vec<String>)>,
+    _ticker: EpochTicker,
+}
+
+impl std::fmt::Debug for WasmRunner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmRunner")
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmRunner {
+    /// Create the engine and compile the interpreter module, if any.
+    ///
+    /// Fails when the interpreter module cannot be read or compiled, or a
+    /// preopened directory does not exist.
+    pub fn new(config: WasmConfig) -> Result<Self, anyhow::Error> {
+        let mut engine_config = Config::new();
+        engine_config
+            .async_support(true)
+            .epoch_interruption(true)
+            .consume_fuel(config.max_fuel.is_some());
+        let engine = Engine::new(&engine_config)?;
+
+        let mut linker = Linker::new(&engine);
+        preview1::add_to_linker_async(&mut linker, |state: &mut WasmState| &mut state.wasi)?;
+
+        let interpreter = config
+            .interpreter
+            .as_ref()
+            .map(|interp| {
+                let module = Module::from_file(&engine, &interp.module).map_err(|e| {
+                    anyhow::anyhow!(
+                        "failed to load WASI interpreter '{}': {}",
+                        interp.module.display(),
+                        e
+                    )
+                })?;
+                let argv0 = interp
+                    .module
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "interpreter".to_string());
+                let mut args = vec![argv0];
+                args.extend(interp.args.iter().cloned());
+                Ok::<_, anyhow::Error>((module, args))
+            })
+            .transpose()?;
+
+        for dir in &config.preopened_dirs {
+            if !dir.host_path.is_dir() {
+                anyhow::bail!(
+                    "preopened directory '{}' does not exist",
+                    dir.host_path.display()
+                );
+            }
+        }
+        std::fs::create_dir_all(&config.scratch_root)?;
+
+        let ticker = EpochTicker::start(engine.clone())?;
+        Ok(Self {
+            config,
+            engine,
+            linker,
+            interpreter,
+            _ticker: ticker,
+        })
+    }
+
+    /// The module to run and its argv for `code`.
+    fn module_for(&self, code: &str) -> Result<(Module, Vec<String>), anyhow::Error> {
+        if let Some((module, args)) = &self.interpreter {
+            let mut args = args.clone();
+            args.push(code.to_string());
+            return Ok((module.clone(), args));
+        }
+
+        let trimmed = code.trim();
+        let bytes = if trimmed.starts_with('(') {
+            trimmed.as_bytes().to_vec()
+        } else {
+            base64::engine::general_purpose::STANDARD
+                .decode(trimmed)
+                .map_err(|_| {
+                    anyhow::anyhow!(
+                        "no WASI interpreter is configured, so code must be a module in WAT \
+                         text or base64-encoded .wasm"
+                    )
+                })?
+        };
+        let module = Module::new(&self.engine, bytes)
+            .map_err(|e| anyhow::anyhow!("invalid WASI module: {}", e))?;
+        Ok((module, vec!["main.wasm".to_string()]))
+    }
+}
+
+#[async_trait]
+impl SandboxRunner for WasmRunner {
+    async fn execute(
+        &self,
+        code: &str,
+        env: HashMap<String, String>,
+    ) -> Result<ExecutionResult, anyhow::Error> {
+        let config = &self.config;
+        let (module, args) = self.module_for(code)?;
+        let scratch = tempfile::Builder::new()
+            .prefix("run-")
+            .tempdir_in(&config.scratch_root)?;
+
+        let stdout = CappedOutput::new(config.max_output_bytes);
+        let stderr = CappedOutput::new(config.max_output_bytes);
+        let mut wasi = WasiCtxBuilder::new();
+        wasi.args(&args)
+            .env("HOME", SCRATCH_GUEST_PATH)
+            .env("PWD", SCRATCH_GUEST_PATH)
+            .envs(&env.iter().collect::<Vec<_>>())
+            .stdout(stdout.clone())
+            .stderr(stderr.clone())
+            .preopened_dir(
+                scratch.path(),
+                SCRATCH_GUEST_PATH,
+                DirPerms::all(),
+                FilePerms::all(),
+            )?;
+        for dir in &config.preopened_dirs {
+            let (dir_perms, file_perms) = if dir.writable {
+                (DirPerms::all(), FilePerms::all())
+            } else {
+                (DirPerms::READ, FilePerms::READ)
+            };
+            wasi.preopened_dir(&dir.host_path, &dir.guest_path, dir_perms, file_perms)?;
+        }
+
+        let limits = StoreLimitsBuilder::new()
+            .memory_size((config.max_memory_mb as usize).saturating_mul(1024 * 1024))
+            .build();
+        let mut store = Store::new(
+            &self.engine,
+            WasmState {
+                wasi: wasi.build_p1(),
+                limits,
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        if let Some(fuel) = config.max_fuel {
+            store.set_fuel(fuel)?;
+        }
+        // Yield to the executor on every tick so a long computation does not
+        // starve other tasks, and trap once the deadline has passed.
+        let deadline = Instant::now() + config.max_execution_time;
+        store.set_epoch_deadline(1);
+        store.epoch_deadline_callback(move |_| {
+            if Instant::now() >= deadline {
+                Err(Trap::Interrupt.into())
+            } else {
+                Ok(UpdateDeadline::Yield(1))
+            }
+        });
+
+        let start = Instant::now();
+        // The outer timeout covers time spent blocked in host calls (e.g.
+        // `poll_oneoff` sleeps), where epoch checks do not run.
+        let run = tokio::time::timeout(config.max_execution_time, async {
+            let instance = self.linker.instantiate_async(&mut store, &module).await?;
+            let start_fn = instance.get_typed_func::<(), ()>(&mut store, "_start")?;
+            start_fn.call_async(&mut store, ()).await
+        })
+        .await;
+        let execution_time_ms = start.elapsed().as_millis() as u64;
+
+        let outcome = match run {
+            Ok(outcome) => outcome,
+            Err(_) => {
+                return Err(anyhow::anyhow!(
+                    "Execution timed out after {:?}",
+                    config.max_execution_time
+                ))
+            }
+        };
+        let (exit_code, trap) = match outcome {
+            Ok(()) => (0, None),
+            Err(e) => {
+                if let Some(exit) = e.downcast_ref::<I32Exit>() {
+                    (exit.0, None)
+                } else {
+                    match e.downcast_ref::<Trap>() {
+                        Some(Trap::Interrupt) => {
+                            return Err(anyhow::anyhow!(
+                                "Execution timed out after {:?}",
+                                config.max_execution_time
+                            ))
+                        }
+                        Some(Trap::OutOfFuel) => {
+                            return Err(anyhow::anyhow!(
+                                "Execution exhausted its fuel budget of {}",
+                                config.max_fuel.unwrap_or_default()
+                            ))
+                        }
+                        _ => (-1, Some(format!("{:#}", e))),
+                    }
+                }
+            }
+        };
+
+        let (stdout, stdout_truncated) = stdout.take();
+        let (mut stderr, stderr_truncated) = stderr.take();
+        if let Some(trap) = trap {
+            if !stderr.is_empty() && !stderr.ends_with('\n') {
+                stderr.push('\n');
+            }
+            stderr.push_str(&trap);
+        }
+        Ok(ExecutionResult {
+            exit_code,
+            stdout,
+            stderr,
+            execution_time_ms,
+            success: exit_code == 0,
+            stdout_truncated,
+            stderr_truncated,
+            artifacts: Vec::new(),
+            artifacts_truncated: false,
+        })
+    }
+}
+
+/// In-memory guest output stream that keeps the first `max` bytes and
+/// discards the rest, so a chatty guest is truncated rather than trapped.
+#[derive(Clone)]
+struct CappedOutput {
+    max: usize,
+    buffer: Arc<Mutex<(Vec<u8>, bool)>>,
+}
+
+impl CappedOutput {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            buffer: Arc::new(Mutex::new((Vec::new(), false))),
+        }
+    }
+
+    fn take(&self) -> (String, bool) {
+        let guard = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        let (buf, truncated) = &*guard;
+        let output = String::from_utf8_lossy(buf);
+        if *truncated {
+            (
+                format!("{}\n... [output truncated at {} bytes]", output, self.max),
+                true,
+            )
+        } else {
+            (output.into_owned(), false)
+        }
+    }
+}
+
+impl StdoutStream for CappedOutput {
+    fn stream(&self) -> Box<dyn OutputStream> {
+        Box::new(self.clone())
+    }
+
+    fn isatty(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl OutputStream for CappedOutput {
+    fn write(&mut self, bytes: Bytes) -> StreamResult<()> {
+        let mut guard = self.buffer.lock().unwrap_or_else(|e| e.into_inner());
+        let (buf, truncated) = &mut *guard;
+        let room = self.max.saturating_sub(buf.len());
+        if bytes.len() > room {
+            *truncated = true;
+        }
+        buf.extend_from_slice(&bytes[..bytes.len().min(room)]);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> StreamResult<()> {
+        Ok(())
+    }
+
+    fn check_write(&mut self) -> Result<usize, StreamError> {
+        Ok(64 * 1024)
+    }
+}
+
+#[async_trait]
+impl Pollable for CappedOutput {
+    async fn ready(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Prints `hello` and exits with status 3.
+    const HELLO_WAT: &str = r#"(module
+      (import "wasi_snapshot_preview1" "fd_write"
+        (func $fd_write (param i32 i32 i32 i32) (result i32)))
+      (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+      (memory (export "memory") 1)
+      (data (i32.const 16) "hello\n")
+      (func (export "_start")
+        (i32.store (i32.const 0) (i32.const 16))
+        (i32.store (i32.const 4) (i32.const 6))
+        (drop (call $fd_write (i32.const 1) (i32.const 0) (i32.const 1) (i32.const 8)))
+        (call $exit (i32.const 3))))"#;
+
+    const SPIN_WAT: &str = r#"(module
+      (memory (export "memory") 1)
+      (func (export "_start") (loop $l (br $l))))"#;
+
+    fn runner(config: WasmConfig) -> WasmRunner {
+        WasmRunner::new(WasmConfig {
+            scratch_root: tempfile::tempdir().unwrap().keep(),
+            preopened_dirs: Vec::new(),
+            max_execution_time: Duration::from_secs(10),
+            ..config
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn runs_wat_module_and_reports_exit_code() {
+        let runner = runner(WasmConfig::default());
+        let result = runner.execute(HELLO_WAT, HashMap::new()).await.unwrap();
+        assert_eq!(result.stdout, "hello\n");
+        assert_eq!(result.exit_code, 3);
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn accepts_base64_binary_modules() {
+        // (module (func (export "_start")))
+        let binary: &[u8] = &[
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+            0x03, 0x02, 0x01, 0x00, 0x07, 0x0a, 0x01, 0x06, b'_', b's', b't', b'a', b'r', b't',
+            0x00, 0x00, 0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b,
+        ];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(binary);
+        let runner = runner(WasmConfig::default());
+        let result = runner.execute(&encoded, HashMap::new()).await.unwrap();
+        assert!(result.success, "stderr: {}", result.stderr);
+
+        let err = runner
+            .execute("print('hi')", HashMap::new())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no WASI interpreter"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn output_is_truncated_not_trapped() {
+        let runner = runner(WasmConfig {
+            max_output_bytes: 3,
+            ..WasmConfig::default()
+        });
+        let result = runner.execute(HELLO_WAT, HashMap::new()).await.unwrap();
+        assert!(result.stdout_truncated);
+        assert!(result.stdout.starts_with("hel\n"), "{}", result.stdout);
+        assert_eq!(result.exit_code, 3);
+    }
+
+    #[tokio::test]
+    async fn fuel_budget_stops_runaway_code() {
+        let runner = runner(WasmConfig {
+            max_fuel: Some(1_000_000),
+            ..WasmConfig::default()
+        });
+        let err = runner.execute(SPIN_WAT, HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("fuel"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn epoch_deadline_stops_runaway_code() {
+        let mut runner = runner(WasmConfig::default());
+        runner.config.max_execution_time = Duration::from_millis(200);
+        let err = runner.execute(SPIN_WAT, HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn memory_growth_past_the_cap_fails() {
+        // Exits 1 when growing to ~6.5 MB fails, 0 when it succeeds.
+        let wat = r#"(module
+          (import "wasi_snapshot_preview1" "proc_exit" (func $exit (param i32)))
+          (memory (export "memory") 1)
+          (func (export "_start")
+            (call $exit (i32.eq (memory.grow (i32.const 100)) (i32.const -1)))))"#;
+        let capped = runner(WasmConfig {
+            max_memory_mb: 1,
+            ..WasmConfig::default()
+        });
+        assert_eq!(
+            capped.execute(wat, HashMap::new()).await.unwrap().exit_code,
+            1
+        );
+        let roomy = runner(WasmConfig {
+            max_memory_mb: 64,
+            ..WasmConfig::default()
+        });
+        assert_eq!(
+            roomy.execute(wat, HashMap::new()).await.unwrap().exit_code,
+            0
+        );
+    }
+
+    #[test]
+    fn profile_paths_become_preopens() {
+        let mut profile = SandboxProfile::secure_default();
+        profile.filesystem.read_paths = vec!["/srv/data/*.csv".to_string(), "/etc/*".to_string()];
+        profile.filesystem.write_paths = vec!["/srv/out/**".to_string()];
+        profile.filesystem.denied_paths = vec!["/etc/*".to_string()];
+        let config = WasmConfig::from_profile(&profile).unwrap();
+        let preopens: Vec<_> = config
+            .preopened_dirs
+            .iter()
+            .map(|p| (p.guest_path.as_str(), p.writable))
+            .collect();
+        assert_eq!(preopens, vec![("/srv/data", false), ("/srv/out", true)]);
+    }
+
+    #[test]
+    fn denied_path_inside_preopen_is_rejected() {
+        let mut profile = SandboxProfile::secure_default();
+        profile.filesystem.read_paths = vec!["/srv/data/*".to_string()];
+        profile.filesystem.write_paths = vec!["/srv/out/*".to_string()];
+        profile.filesystem.denied_paths = vec!["/srv/data/secrets/*".to_string()];
+        let err = WasmConfig::from_profile(&profile).unwrap_err();
+        assert!(err.to_string().contains("/srv/data/secrets"));
+
+        profile.filesystem.denied_paths = vec!["/srv/out/keys".to_string()];
+        assert!(WasmConfig::from_profile(&profile).is_err());
+    }
+
+    #[test]
+    fn default_config_preopens_the_secure_profile() {
+        let guests: Vec<_> = WasmConfig::default()
+            .preopened_dirs
+            .into_iter()
+            .map(|p| p.guest_path)
+            .collect();
+        assert_eq!(guests, vec!["/tmp/sandbox", "/tmp/sandbox/output"]);
+    }
+}
@@ -28,6 +28,7 @@ use super::cron_types::*;
 use super::job_store::{JobStore, JobStoreError, SqliteJobStore};
 use super::policy_gate::{PolicyGate, ScheduleContext, SchedulePolicyDecision};
 use super::{AgentScheduler, DefaultAgentScheduler};
+use crate::reasoning::spend::SpendTags;
 use crate::types::ExecutionMode;
 
 /// Configuration for the CronScheduler.
@@ -421,6 +422,36 @@ impl CronScheduler {
         Ok(runs)
     }
 
+    /// Ephemeral agent config for one run of `job`, tagged with the trigger,
+    /// the session and the cron job the run's spend is billed to.
+    fn run_config(
+        job: &CronJobDefinition,
+        trigger: &str,
+        session_id: uuid::Uuid,
+    ) -> crate::types::AgentConfig {
+        let mut run_config = job.agent_config.clone();
+        run_config.execution_mode = ExecutionMode::CronScheduled {
+            cron_expression: job.cron_expression.clone(),
+            timezone: job.timezone.clone(),
+        };
+        run_config
+            .metadata
+            .insert("trigger".to_string(), trigger.to_string());
+        run_config
+            .metadata
+            .insert("cron_job_id".to_string(), job.job_id.to_string());
+        SpendTags::for_cron_job(&job.name).write_metadata(&mut run_config.metadata);
+        // Session isolation metadata
+        run_config
+            .metadata
+            .insert("session_id".to_string(), session_id.to_string());
+        run_config.metadata.insert(
+            "session_mode".to_string(),
+            format!("{:?}", job.session_mode),
+        );
+        run_config
+    }
+
     /// Force-trigger a job immediately, regardless of its schedule.
     pub async fn trigger_now(&self, job_id: CronJobId) -> Result<(), CronSchedulerError> {
         let job = self
@@ -491,25 +522,7 @@ impl CronScheduler {
             return Err(CronSchedulerError::PolicyDenied(job_id, reason));
         }
 
-        let mut run_config = job.agent_config.clone();
-        run_config.execution_mode = ExecutionMode::CronScheduled {
-            cron_expression: job.cron_expression.clone(),
-            timezone: job.timezone.clone(),
-        };
-        run_config
-            .metadata
-            .insert("trigger".to_string(), "cron_manual".to_string());
-        run_config
-            .metadata
-            .insert("cron_job_id".to_string(), job.job_id.to_string());
-        // Session isolation metadata
-        run_config
-            .metadata
-            .insert("session_id".to_string(), uuid::Uuid::new_v4().to_string());
-        run_config.metadata.insert(
-            "session_mode".to_string(),
-            format!("{:?}", job.session_mode),
-        );
+        let run_config = Self::run_config(&job, "cron_manual", uuid::Uuid::new_v4());
 
         let started_at = Utc::now();
         let run_id = uuid::Uuid::new_v4();
@@ -852,32 +865,12 @@ impl CronScheduler {
                                 let session_id = uuid::Uuid::new_v4();
 
                                 // Build an ephemeral agent config for the run.
-                                let mut run_config = job.agent_config.clone();
-                                run_config.execution_mode = ExecutionMode::CronScheduled {
-                                    cron_expression: job.cron_expression.clone(),
-                                    timezone: job.timezone.clone(),
-                                };
-                                run_config.metadata.insert(
-                                    "trigger".to_string(),
-                                    "cron".to_string(),
-                                );
-                                run_config.metadata.insert(
-                                    "cron_job_id".to_string(),
-                                    job.job_id.to_string(),
-                                );
+                                let mut run_config =
+                                    Self::run_config(&job, "cron", session_id);
                                 run_config.metadata.insert(
                                     "cron_expression".to_string(),
                                     job.cron_expression.clone(),
                                 );
-                                // Session isolation metadata
-                                run_config.metadata.insert(
-                                    "session_id".to_string(),
-                                    session_id.to_string(),
-                                );
-                                run_config.metadata.insert(
-                                    "session_mode".to_string(),
-                                    format!("{:?}", job.session_mode),
-                                );
 
                                 let result: Result<crate::types::AgentId, crate::types::SchedulerError> =
                                     sched_c.schedule_agent(run_config).await;
@@ -1986,6 +1979,27 @@ mod tests {
         cron.shutdown().await;
     }
 
+    #[test]
+    fn run_config_bills_spend_to_the_cron_job() {
+        let job = CronJobDefinition::new(
+            "nightly-report".to_string(),
+            "0 0 3 * * * *".to_string(),
+            "UTC".to_string(),
+            test_agent_config(),
+        );
+        let session = uuid::Uuid::new_v4();
+        let run_config = CronScheduler::run_config(&job, "cron", session);
+
+        let tags = SpendTags::from_metadata(&run_config.metadata);
+        assert_eq!(tags, SpendTags::for_cron_job("nightly-report"));
+        assert_eq!(run_config.metadata["trigger"], "cron");
+        assert_eq!(run_config.metadata["session_id"], session.to_string());
+        assert!(matches!(
+            run_config.execution_mode,
+            ExecutionMode::CronScheduled { .. }
+        ));
+    }
+
     #[tokio::test]
     async fn tick_loop_refuses_run_when_policy_denies() {
         let (cron, _sched) = make_scheduler().await;
@@ -123,8 +123,20 @@ pub enum DeliveryChannel {
         smtp_port: u16,
         to: Vec<String>,
         from: String,
+        /// Subject line. `{job_name}`, `{status}` and `{run_time}` (or any other
+        /// top-level payload field) are substituted from the payload.
         #[serde(default)]
         subject_template: Option<String>,
+        /// Transport security; STARTTLS unless configured otherwise.
+        #[serde(default)]
+        tls: SmtpTlsMode,
+        /// SMTP AUTH user name. No authentication is attempted when unset.
+        #[serde(default)]
+        username: Option<String>,
+        /// Key of the SMTP AUTH password in the router's secret store. Falls
+        /// back to the `SYMBIONT_SMTP_PASSWORD` environment variable.
+        #[serde(default)]
+        password_secret: Option<String>,
     },
     /// Delegate to a named custom handler registered at runtime.
     Custom {
@@ -141,6 +153,20 @@ pub enum DeliveryChannel {
     },
 }
 
+/// How the SMTP connection for `DeliveryChannel::Email` is secured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTlsMode {
+    /// Connect in plaintext and upgrade with STARTTLS (usually port 587).
+    /// The upgrade is required; servers that don't offer it are refused.
+    #[default]
+    Starttls,
+    /// TLS from the first byte (SMTPS, usually port 465).
+    Implicit,
+    /// No encryption. Only for local relays and tests.
+    None,
+}
+
 fn default_webhook_method() -> String {
     "POST".to_string()
 }
@@ -5,11 +5,21 @@
 
 use async_trait::async_trait;
 use chrono::Utc;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
 use std::collections::HashMap;
 use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
-use super::cron_types::{DeliveryChannel, DeliveryConfig, DeliveryReceipt};
+use super::cron_types::{DeliveryChannel, DeliveryConfig, DeliveryReceipt, SmtpTlsMode};
+use crate::secrets::{resolve_secret_or_env, SecretStore};
+
+/// Subject used for email delivery when no `subject_template` is configured.
+const DEFAULT_EMAIL_SUBJECT: &str = "Scheduled job {job_name}: {status}";
+
+/// Environment fallback for the SMTP password when no secret store has it.
+const SMTP_PASSWORD_ENV: &str = "SYMBIONT_SMTP_PASSWORD";
 
 /// Result of delivering to all configured channels.
 #[derive(Debug, Clone)]
@@ -51,6 +61,8 @@ pub struct DefaultDeliveryRouter {
     /// Allowlisted base directory for `LogFile` delivery. When `None`, log-file
     /// delivery is refused (fail-closed). Initialized from `SYMBIONT_LOG_DIR`.
     log_base_dir: Option<PathBuf>,
+    /// Store that SMTP passwords are resolved from.
+    secret_store: Option<Arc<dyn SecretStore + Send + Sync>>,
 }
 
 impl DefaultDeliveryRouter {
@@ -58,6 +70,7 @@ impl DefaultDeliveryRouter {
         Self {
             custom_handlers: HashMap::new(),
             log_base_dir: std::env::var_os("SYMBIONT_LOG_DIR").map(PathBuf::from),
+            secret_store: None,
         }
     }
 
@@ -68,6 +81,16 @@ impl DefaultDeliveryRouter {
         self
     }
 
+    /// Resolve SMTP passwords (`password_secret`) through `store`.
+    ///
+    /// `symbi up` does not build a delivery router yet, so whoever constructs
+    /// one must attach the store here; without it only `SYMBIONT_SMTP_PASSWORD`
+    /// is consulted.
+    pub fn with_secret_store(mut self, store: Arc<dyn SecretStore + Send + Sync>) -> Self {
+        self.secret_store = Some(store);
+        self
+    }
+
     /// Register a custom delivery handler.
     #[deprecated(
         since = "1.19.0",
@@ -170,6 +193,9 @@ impl DefaultDeliveryRouter {
                 to,
                 from,
                 subject_template,
+                tls,
+                username,
+                password_secret,
             } => {
                 self.deliver_email(
                     payload,
@@ -178,6 +204,9 @@ impl DefaultDeliveryRouter {
                     to,
                     from,
                     subject_template.as_deref(),
+                    *tls,
+                    username.as_deref(),
+                    password_secret.as_deref(),
                 )
                 .await
             }
@@ -402,31 +431,81 @@ impl DefaultDeliveryRouter {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn deliver_email(
         &self,
-        _payload: &serde_json::Value,
+        payload: &serde_json::Value,
         smtp_host: &str,
         smtp_port: u16,
         to: &[String],
         from: &str,
         subject_template: Option<&str>,
+        tls: SmtpTlsMode,
+        username: Option<&str>,
+        password_secret: Option<&str>,
     ) -> DeliveryReceipt {
-        // Email delivery requires a full SMTP client (lettre crate).
-        // For now, log the intent and return a placeholder receipt.
-        tracing::info!(
-            "Email delivery requested: from={}, to={:?}, host={}:{}, subject={:?}",
-            from,
-            to,
-            smtp_host,
-            smtp_port,
-            subject_template,
-        );
-        DeliveryReceipt {
-            channel_description: format!("email:{}:{}", smtp_host, smtp_port),
+        let channel_description = format!("email:{}:{}", smtp_host, smtp_port);
+        let failed = |status_code: Option<u16>, error: String| DeliveryReceipt {
+            channel_description: channel_description.clone(),
             delivered_at: Utc::now(),
             success: false,
-            status_code: None,
-            error: Some("SMTP delivery not yet implemented; add lettre dependency".to_string()),
+            status_code,
+            error: Some(error),
+        };
+
+        let subject = render_subject(subject_template.unwrap_or(DEFAULT_EMAIL_SUBJECT), payload);
+        let message = match build_email(payload, from, to, &subject) {
+            Ok(m) => m,
+            Err(e) => return failed(None, e),
+        };
+
+        let builder = match tls {
+            SmtpTlsMode::Starttls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(smtp_host)
+            }
+            SmtpTlsMode::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(smtp_host),
+            SmtpTlsMode::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                smtp_host,
+            )),
+        };
+        let mut builder = match builder {
+            Ok(b) => b
+                .port(smtp_port)
+                .timeout(Some(std::time::Duration::from_secs(30))),
+            Err(e) => return failed(None, format!("SMTP TLS setup failed: {}", e)),
+        };
+
+        if let Some(user) = username {
+            let password = resolve_secret_or_env(
+                SMTP_PASSWORD_ENV,
+                password_secret,
+                self.secret_store.as_deref(),
+            )
+            .await;
+            let Some(password) = password else {
+                return failed(
+                    None,
+                    format!(
+                        "no SMTP password for '{}': set password_secret or {}",
+                        user, SMTP_PASSWORD_ENV
+                    ),
+                );
+            };
+            builder = builder.credentials(Credentials::new(user.to_string(), password));
+        }
+
+        match builder.build().send(message).await {
+            Ok(response) => DeliveryReceipt {
+                channel_description,
+                delivered_at: Utc::now(),
+                success: true,
+                status_code: Some(response.code().into()),
+                error: None,
+            },
+            Err(e) => failed(
+                e.status().map(u16::from),
+                format!("SMTP delivery failed: {}", e),
+            ),
         }
     }
 
@@ -493,6 +572,98 @@ impl DefaultDeliveryRouter {
     }
 }
 
+/// Fill `{placeholder}` references in an email subject from top-level payload
+/// fields. `job_name` falls back to `name`, and `run_time` to `completed_at`
+/// then `started_at`. Unknown placeholders are left as written.
+fn render_subject(template: &str, payload: &serde_json::Value) -> String {
+    let lookup = |key: &str| -> Option<String> {
+        let candidates: &[&str] = match key {
+            "job_name" => &["job_name", "name"],
+            "run_time" => &["run_time", "completed_at", "started_at"],
+            other => return scalar_field(payload, other),
+        };
+        candidates.iter().find_map(|k| scalar_field(payload, k))
+    };
+
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) => {
+                let key = &after[..end];
+                match lookup(key) {
+                    Some(value) => out.push_str(&value),
+                    None => out.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    // Header values must stay on one line.
+    out.replace(['\r', '\n'], " ")
+}
+
+fn scalar_field(payload: &serde_json::Value, key: &str) -> Option<String> {
+    match payload.get(key)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Build a multipart/alternative message carrying the payload as plain text
+/// and as escaped HTML.
+fn build_email(
+    payload: &serde_json::Value,
+    from: &str,
+    to: &[String],
+    subject: &str,
+) -> Result<Message, String> {
+    let from: Mailbox = from
+        .parse()
+        .map_err(|e| format!("invalid from address '{}': {}", from, e))?;
+    let mut builder = Message::builder().from(from).subject(subject);
+    for recipient in to {
+        let mailbox: Mailbox = recipient
+            .parse()
+            .map_err(|e| format!("invalid recipient '{}': {}", recipient, e))?;
+        builder = builder.to(mailbox);
+    }
+
+    let text = serde_json::to_string_pretty(payload).unwrap_or_else(|_| payload.to_string());
+    let html = format!(
+        "<html><body><h3>{}</h3><pre>{}</pre></body></html>",
+        escape_html(subject),
+        escape_html(&text)
+    );
+    builder
+        .multipart(MultiPart::alternative_plain_html(text, html))
+        .map_err(|e| format!("failed to build email: {}", e))
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
 impl Default for DefaultDeliveryRouter {
     fn default() -> Self {
         Self::new()
@@ -612,6 +783,7 @@ mod tests {
         let router = DefaultDeliveryRouter {
             custom_handlers: HashMap::new(),
             log_base_dir: None,
+            secret_store: None,
         };
         let payload = serde_json::json!({"x": 1});
         let config = DeliveryConfig {
@@ -726,4 +898,196 @@ mod tests {
         assert_eq!(parsed.channels.len(), 2);
         assert!(parsed.fail_fast);
     }
+
+    struct StaticSecretStore;
+
+    #[async_trait]
+    impl SecretStore for StaticSecretStore {
+        async fn get_secret(
+            &self,
+            key: &str,
+        ) -> Result<crate::secrets::Secret, crate::secrets::SecretError> {
+            assert_eq!(key, "smtp/password");
+            Ok(crate::secrets::Secret::new(
+                key.to_string(),
+                "hunter2".to_string(),
+            ))
+        }
+
+        async fn list_secrets(&self) -> Result<Vec<String>, crate::secrets::SecretError> {
+            Ok(vec!["smtp/password".to_string()])
+        }
+    }
+
+    /// In-process SMTP stand-in: serves one session, answers `RCPT` with
+    /// `rcpt_reply`, and returns every line it received (including the DATA body).
+    async fn smtp_stand_in(
+        ehlo_reply: &'static str,
+        rcpt_reply: &'static str,
+    ) -> (u16, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let mut transcript = Vec::new();
+            write.write_all(b"220 stand-in ESMTP\r\n").await.unwrap();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let verb = line.split(' ').next().unwrap_or("").to_ascii_uppercase();
+                transcript.push(line);
+                let reply = match verb.as_str() {
+                    "EHLO" => ehlo_reply,
+                    "AUTH" => "235 2.7.0 accepted\r\n",
+                    "MAIL" | "RSET" => "250 2.1.0 ok\r\n",
+                    "RCPT" => rcpt_reply,
+                    "DATA" => {
+                        write.write_all(b"354 go ahead\r\n").await.unwrap();
+                        while let Ok(Some(body)) = lines.next_line().await {
+                            if body == "." {
+                                break;
+                            }
+                            transcript.push(body);
+                        }
+                        "250 2.0.0 queued\r\n"
+                    }
+                    "QUIT" => "221 2.0.0 bye\r\n",
+                    _ => "502 5.5.2 unrecognized\r\n",
+                };
+                if write.write_all(reply.as_bytes()).await.is_err() || verb == "QUIT" {
+                    break;
+                }
+            }
+            transcript
+        });
+        (port, handle)
+    }
+
+    const EHLO_PLAIN: &str = "250-stand-in\r\n250 AUTH PLAIN LOGIN\r\n";
+
+    fn email_channel(port: u16, tls: SmtpTlsMode) -> DeliveryChannel {
+        DeliveryChannel::Email {
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_port: port,
+            to: vec!["ops@example.com".to_string()],
+            from: "Symbiont <scheduler@example.com>".to_string(),
+            subject_template: Some("{job_name} {status} at {run_time}".to_string()),
+            tls,
+            username: Some("scheduler".to_string()),
+            password_secret: Some("smtp/password".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn email_delivery_sends_multipart_message() {
+        use base64::Engine;
+
+        let (port, server) = smtp_stand_in(EHLO_PLAIN, "250 2.1.5 ok\r\n").await;
+        let router = DefaultDeliveryRouter::new().with_secret_store(Arc::new(StaticSecretStore));
+        let payload = serde_json::json!({
+            "job_name": "nightly-report",
+            "status": "succeeded",
+            "run_time": "2026-01-05T02:00:00Z",
+            "summary": "<b>3 findings</b>",
+        });
+        let config = DeliveryConfig {
+            channels: vec![email_channel(port, SmtpTlsMode::None)],
+            fail_fast: false,
+        };
+
+        let result = router.deliver(&payload, &config).await;
+        let receipt = &result.receipts[0];
+        assert!(receipt.success, "{:?}", receipt.error);
+        assert_eq!(receipt.status_code, Some(250));
+        assert_eq!(
+            receipt.channel_description,
+            format!("email:127.0.0.1:{}", port)
+        );
+
+        let transcript = server.await.unwrap();
+        let auth = base64::engine::general_purpose::STANDARD.encode("\0scheduler\0hunter2");
+        assert!(transcript.contains(&format!("AUTH PLAIN {}", auth)));
+        assert!(transcript.contains(&"RCPT TO:<ops@example.com>".to_string()));
+        let data = transcript.join("\n");
+        assert!(data.contains("Subject: nightly-report succeeded at 2026-01-05T02:00:00Z"));
+        assert!(data.contains("multipart/alternative"));
+        assert!(data.contains("Content-Type: text/plain; charset=utf-8"));
+        assert!(data.contains("Content-Type: text/html; charset=utf-8"));
+        assert!(data.contains("&lt;b&gt;3 findings&lt;/b&gt;"));
+    }
+
+    #[tokio::test]
+    async fn email_rejection_is_recorded_in_receipt() {
+        let (port, server) = smtp_stand_in(EHLO_PLAIN, "550 5.1.1 no such user\r\n").await;
+        let router = DefaultDeliveryRouter::new().with_secret_store(Arc::new(StaticSecretStore));
+        let config = DeliveryConfig {
+            channels: vec![email_channel(port, SmtpTlsMode::None)],
+            fail_fast: false,
+        };
+
+        let result = router.deliver(&serde_json::json!({}), &config).await;
+        assert!(!result.all_succeeded);
+        let receipt = &result.receipts[0];
+        assert_eq!(receipt.status_code, Some(550));
+        assert!(receipt.error.as_deref().unwrap().contains("no such user"));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn email_starttls_refuses_server_without_upgrade() {
+        let (port, server) = smtp_stand_in(EHLO_PLAIN, "250 2.1.5 ok\r\n").await;
+        let router = DefaultDeliveryRouter::new().with_secret_store(Arc::new(StaticSecretStore));
+        let config = DeliveryConfig {
+            channels: vec![email_channel(port, SmtpTlsMode::Starttls)],
+            fail_fast: false,
+        };
+
+        let result = router.deliver(&serde_json::json!({}), &config).await;
+        assert!(!result.receipts[0].success);
+
+        let transcript = server.await.unwrap();
+        assert!(!transcript.iter().any(|l| l.starts_with("AUTH")));
+        assert!(!transcript.iter().any(|l| l.starts_with("MAIL")));
+    }
+
+    #[tokio::test]
+    async fn email_without_password_fails_before_connecting() {
+        let router = DefaultDeliveryRouter::new();
+        let config = DeliveryConfig {
+            channels: vec![email_channel(1, SmtpTlsMode::None)],
+            fail_fast: false,
+        };
+
+        let result = router.deliver(&serde_json::json!({}), &config).await;
+        let receipt = &result.receipts[0];
+        assert!(!receipt.success);
+        assert!(receipt
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("password_secret"));
+    }
+
+    #[test]
+    fn subject_template_falls_back_and_keeps_unknown_placeholders() {
+        let payload = serde_json::json!({
+            "name": "digest",
+            "status": "failed",
+            "started_at": "2026-01-05T02:00:00Z",
+            "attempt": 2,
+        });
+        assert_eq!(
+            render_subject(
+                "{job_name} {status} ({run_time}) #{attempt} {missing}",
+                &payload
+            ),
+            "digest failed (2026-01-05T02:00:00Z) #2 {missing}"
+        );
+        assert_eq!(
+            render_subject(DEFAULT_EMAIL_SUBJECT, &payload),
+            "Scheduled job digest: failed"
+        );
+    }
 }
@@ -0,0 +1,480 @@
+//! Distributed tracing for agent runs.
+//!
+//! Reasoning loops, inference calls, policy checks and tool calls are
+//! recorded as `tracing` spans named and attributed after the OpenTelemetry
+//! GenAI semantic conventions (`invoke_agent`, `chat {model}`,
+//! `execute_tool {name}` with `gen_ai.*` attributes). The helpers here build
+//! those spans so every call site attributes them the same way.
+//!
+//! With the `otel-tracing` feature, [`otlp_layer`] exports the spans to an
+//! OTLP collector and installs the W3C trace-context propagator: inbound
+//! `traceparent` headers parent the server span opened by
+//! [`trace_context_middleware`], and [`current_trace_headers`] carries the
+//! active trace on outbound calls, so a multi-agent run is one distributed
+//! trace. Without the feature the spans still reach any `tracing` subscriber
+//! and propagation is a no-op.
+
+use std::collections::HashMap;
+
+use tracing::field::Empty;
+use tracing::Span;
+
+use crate::metrics::{OtlpConfig, OtlpProtocol};
+use crate::reasoning::inference::Usage;
+use crate::reasoning::loop_types::{Observation, TerminationReason};
+use crate::types::AgentId;
+
+/// Service name reported when `OTEL_SERVICE_NAME` is unset.
+pub const DEFAULT_SERVICE_NAME: &str = "symbiont";
+
+/// Errors raised while setting up trace export.
+#[derive(Debug, thiserror::Error)]
+pub enum TelemetryError {
+    #[error("tracing configuration error: {0}")]
+    ConfigError(String),
+}
+
+/// Where and how spans are exported.
+#[derive(Debug, Clone)]
+pub struct TracingConfig {
+    /// Collector endpoint and transport.
+    pub otlp: OtlpConfig,
+    /// `service.name` resource attribute.
+    pub service_name: String,
+}
+
+impl TracingConfig {
+    /// Read the standard OpenTelemetry environment variables.
+    ///
+    /// Returns `None` unless `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` or
+    /// `OTEL_EXPORTER_OTLP_ENDPOINT` is set, so export stays off by default.
+    /// `OTEL_EXPORTER_OTLP_PROTOCOL` selects `grpc` (default),
+    /// `http/protobuf` or `http/json`. Exporter headers are read from
+    /// `OTEL_EXPORTER_OTLP_HEADERS` by the exporter itself.
+    pub fn from_env() -> Option<Self> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let non_empty = |key: &str| get(key).filter(|v| !v.trim().is_empty());
+
+        let protocol = match non_empty("OTEL_EXPORTER_OTLP_PROTOCOL").as_deref() {
+            Some("http/protobuf") => OtlpProtocol::HttpBinary,
+            Some("http/json") => OtlpProtocol::HttpJson,
+            _ => OtlpProtocol::Grpc,
+        };
+        // A signal-specific endpoint is used as-is; the generic one is a
+        // base URL, to which HTTP exporters append the traces path.
+        let endpoint = match non_empty("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT") {
+            Some(endpoint) => endpoint,
+            None => {
+                let base = non_empty("OTEL_EXPORTER_OTLP_ENDPOINT")?;
+                match protocol {
+                    OtlpProtocol::Grpc => base,
+                    OtlpProtocol::HttpBinary | OtlpProtocol::HttpJson => {
+                        format!("{}/v1/traces", base.trim_end_matches('/'))
+                    }
+                }
+            }
+        };
+
+        Some(Self {
+            otlp: OtlpConfig {
+                endpoint,
+                protocol,
+                timeout_seconds: 10,
+                headers: HashMap::new(),
+            },
+            service_name: non_empty("OTEL_SERVICE_NAME")
+                .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string()),
+        })
+    }
+}
+
+/// Flushes and shuts down the tracer provider when dropped. Hold it for the
+/// life of the process.
+#[cfg(feature = "otel-tracing")]
+pub struct TracingGuard {
+    provider: opentelemetry_sdk::trace::SdkTracerProvider,
+}
+
+#[cfg(feature = "otel-tracing")]
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            tracing::warn!("OTLP tracer provider shutdown failed: {}", e);
+        }
+    }
+}
+
+/// Build a `tracing-subscriber` layer exporting spans over OTLP.
+///
+/// Also installs the tracer provider and the W3C trace-context propagator
+/// globally, which [`current_trace_headers`] and
+/// [`set_parent_from_headers`] rely on.
+#[cfg(feature = "otel-tracing")]
+pub fn otlp_layer<S>(
+    config: &TracingConfig,
+) -> Result<
+    (
+        tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::SdkTracer>,
+        TracingGuard,
+    ),
+    TelemetryError,
+>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_otlp::{Protocol, SpanExporter, WithExportConfig, WithHttpConfig};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use opentelemetry_sdk::Resource;
+    use std::time::Duration;
+
+    let timeout = Duration::from_secs(config.otlp.timeout_seconds);
+
+    let exporter = match config.otlp.protocol {
+        OtlpProtocol::Grpc => SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(&config.otlp.endpoint)
+            .with_timeout(timeout)
+            .build()
+            .map_err(|e| {
+                TelemetryError::ConfigError(format!("Failed to build gRPC OTLP exporter: {}", e))
+            })?,
+        OtlpProtocol::HttpBinary | OtlpProtocol::HttpJson => {
+            let protocol = match config.otlp.protocol {
+                OtlpProtocol::HttpJson => Protocol::HttpJson,
+                _ => Protocol::HttpBinary,
+            };
+            SpanExporter::builder()
+                .with_http()
+                .with_protocol(protocol)
+                .with_endpoint(&config.otlp.endpoint)
+                .with_timeout(timeout)
+                .with_headers(config.otlp.headers.clone())
+                .build()
+                .map_err(|e| {
+                    TelemetryError::ConfigError(format!(
+                        "Failed to build HTTP OTLP exporter: {}",
+                        e
+                    ))
+                })?
+        }
+    };
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build();
+    let tracer = provider.tracer("symbiont");
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    let layer = tracing_opentelemetry::layer().with_tracer(tracer);
+    Ok((layer, TracingGuard { provider }))
+}
+
+/// W3C trace-context headers (`traceparent`, `tracestate`) for the current
+/// span, to attach to an outbound request. Empty when nothing is exported.
+pub fn current_trace_headers() -> HashMap<String, String> {
+    #[allow(unused_mut)]
+    let mut headers = HashMap::new();
+    #[cfg(feature = "otel-tracing")]
+    {
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let cx = Span::current().context();
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&cx, &mut headers)
+        });
+    }
+    headers
+}
+
+/// Continue the trace carried by `headers` (lower-case names) in `span`.
+/// A missing or malformed `traceparent` leaves `span` a trace root.
+pub fn set_parent_from_headers(span: &Span, headers: &HashMap<String, String>) {
+    #[cfg(feature = "otel-tracing")]
+    {
+        use opentelemetry::trace::TraceContextExt;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let cx = opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.extract(headers)
+        });
+        if cx.span().span_context().is_valid() {
+            let _ = span.set_parent(cx);
+        }
+    }
+    #[cfg(not(feature = "otel-tracing"))]
+    let _ = (span, headers);
+}
+
+/// Root span of one reasoning loop run (`invoke_agent`).
+pub fn agent_span(agent_id: &AgentId, provider: &str, model: &str) -> Span {
+    tracing::info_span!(
+        "invoke_agent",
+        otel.kind = "internal",
+        otel.status_code = Empty,
+        gen_ai.operation.name = "invoke_agent",
+        gen_ai.agent.id = %agent_id,
+        gen_ai.provider.name = provider,
+        gen_ai.request.model = model,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+        symbi.iterations = Empty,
+        symbi.termination_reason = Empty,
+        error.type = Empty,
+    )
+}
+
+/// Record a finished run's totals and outcome on its [`agent_span`].
+pub fn record_agent_result(
+    span: &Span,
+    iterations: u32,
+    usage: &Usage,
+    termination: &TerminationReason,
+) {
+    record_usage(span, usage);
+    span.record("symbi.iterations", iterations);
+    let (reason, error) = match termination {
+        TerminationReason::Completed => ("completed", None),
+        TerminationReason::MaxIterations => ("max_iterations", None),
+        TerminationReason::MaxTokens => ("max_tokens", None),
+        TerminationReason::Timeout => ("timeout", Some("timeout")),
+        TerminationReason::PolicyDenial { .. } => ("policy_denial", Some("policy_denial")),
+        TerminationReason::Error { .. } => ("error", Some("error")),
+        TerminationReason::BudgetExceeded { .. } => ("budget_exceeded", Some("budget_exceeded")),
+    };
+    span.record("symbi.termination_reason", reason);
+    if let Some(error) = error {
+        span.record("error.type", error);
+        span.record("otel.status_code", "ERROR");
+    }
+}
+
+/// One inference call (`chat {model}`).
+pub fn chat_span(provider: &str, model: &str, iteration: u32) -> Span {
+    tracing::info_span!(
+        "chat",
+        otel.name = %format!("chat {}", model),
+        otel.kind = "client",
+        gen_ai.operation.name = "chat",
+        gen_ai.provider.name = provider,
+        gen_ai.request.model = model,
+        gen_ai.usage.input_tokens = Empty,
+        gen_ai.usage.output_tokens = Empty,
+        symbi.iteration = iteration,
+    )
+}
+
+/// Record token counts on an [`agent_span`] or [`chat_span`].
+pub fn record_usage(span: &Span, usage: &Usage) {
+    span.record("gen_ai.usage.input_tokens", usage.prompt_tokens);
+    span.record("gen_ai.usage.output_tokens", usage.completion_tokens);
+}
+
+/// Policy evaluation of one iteration's proposed actions.
+pub fn policy_span(iteration: u32) -> Span {
+    tracing::info_span!(
+        "policy_check",
+        symbi.iteration = iteration,
+        symbi.policy.decision = Empty,
+        symbi.policy.actions = Empty,
+        symbi.policy.denied = Empty,
+    )
+}
+
+/// Record the gate's verdict on a [`policy_span`]: `allow` when nothing was
+/// denied, `deny` when everything was, `partial` otherwise.
+pub fn record_policy_decision(span: &Span, action_count: usize, denied_count: usize) {
+    span.record(
+        "symbi.policy.decision",
+        policy_decision(action_count, denied_count),
+    );
+    span.record("symbi.policy.actions", action_count as u64);
+    span.record("symbi.policy.denied", denied_count as u64);
+}
+
+fn policy_decision(action_count: usize, denied_count: usize) -> &'static str {
+    if denied_count == 0 {
+        "allow"
+    } else if denied_count >= action_count {
+        "deny"
+    } else {
+        "partial"
+    }
+}
+
+/// One tool call (`execute_tool {name}`).
+pub fn tool_span(name: &str, call_id: &str) -> Span {
+    tracing::info_span!(
+        "execute_tool",
+        otel.name = %format!("execute_tool {}", name),
+        otel.status_code = Empty,
+        gen_ai.operation.name = "execute_tool",
+        gen_ai.tool.name = name,
+        gen_ai.tool.call.id = call_id,
+        error.type = Empty,
+    )
+}
+
+/// Mark a [`tool_span`] failed when its observation is an error, using the
+/// observation's `error_type` metadata when present.
+pub fn record_tool_result(span: &Span, observation: &Observation) {
+    if observation.is_error {
+        let error_type = observation
+            .metadata
+            .get("error_type")
+            .map(String::as_str)
+            .unwrap_or("tool_error");
+        span.record("error.type", error_type);
+        span.record("otel.status_code", "ERROR");
+    }
+}
+
+/// Hand-off to a delegated agent; the target's `invoke_agent` span nests
+/// under it.
+pub fn delegation_span(target: &str, depth: u32) -> Span {
+    tracing::info_span!(
+        "delegate",
+        otel.name = %format!("delegate {}", target),
+        gen_ai.agent.name = target,
+        symbi.delegation.depth = depth,
+    )
+}
+
+/// Axum middleware opening the server span for a request and parenting it
+/// on the caller's `traceparent`, so handlers (and the agent runs they
+/// start inline) join the caller's trace.
+#[cfg(any(feature = "http-api", feature = "http-input"))]
+pub async fn trace_context_middleware(
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use tracing::Instrument;
+
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|p| p.as_str().to_string());
+    let span = tracing::info_span!(
+        "http.request",
+        otel.name = %match &route {
+            Some(route) => format!("{} {}", method, route),
+            None => method.clone(),
+        },
+        otel.kind = "server",
+        otel.status_code = Empty,
+        http.request.method = %method,
+        http.route = route.as_deref(),
+        http.response.status_code = Empty,
+    );
+
+    let carrier: HashMap<String, String> = ["traceparent", "tracestate"]
+        .into_iter()
+        .filter_map(|name| {
+            let value = request.headers().get(name)?.to_str().ok()?;
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect();
+    set_parent_from_headers(&span, &carrier);
+
+    let response = next.run(request).instrument(span.clone()).await;
+    let status = response.status();
+    span.record("http.response.status_code", status.as_u16());
+    if status.is_server_error() {
+        span.record("otel.status_code", "ERROR");
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> Option<TracingConfig> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        TracingConfig::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn export_is_off_without_an_endpoint() {
+        assert!(config(&[]).is_none());
+        assert!(config(&[("OTEL_SERVICE_NAME", "agents")]).is_none());
+    }
+
+    #[test]
+    fn reads_endpoint_protocol_and_service_name() {
+        let grpc = config(&[("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4317")]).unwrap();
+        assert_eq!(grpc.otlp.endpoint, "http://collector:4317");
+        assert!(matches!(grpc.otlp.protocol, OtlpProtocol::Grpc));
+        assert_eq!(grpc.service_name, DEFAULT_SERVICE_NAME);
+
+        let http = config(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318/"),
+            ("OTEL_EXPORTER_OTLP_PROTOCOL", "http/json"),
+            ("OTEL_SERVICE_NAME", "agents"),
+        ])
+        .unwrap();
+        assert_eq!(http.otlp.endpoint, "http://collector:4318/v1/traces");
+        assert!(matches!(http.otlp.protocol, OtlpProtocol::HttpJson));
+        assert_eq!(http.service_name, "agents");
+
+        let traces = config(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://collector:4318"),
+            ("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT", "http://traces:4318/x"),
+            ("OTEL_EXPORTER_OTLP_PROTOCOL", "http/protobuf"),
+        ])
+        .unwrap();
+        assert_eq!(traces.otlp.endpoint, "http://traces:4318/x");
+        assert!(matches!(traces.otlp.protocol, OtlpProtocol::HttpBinary));
+    }
+
+    #[test]
+    fn policy_decision_summarises_denials() {
+        assert_eq!(policy_decision(2, 0), "allow");
+        assert_eq!(policy_decision(2, 1), "partial");
+        assert_eq!(policy_decision(2, 2), "deny");
+        assert_eq!(policy_decision(0, 0), "allow");
+    }
+
+    #[cfg(feature = "otel-tracing")]
+    #[test]
+    fn trace_context_round_trips_through_headers() {
+        use opentelemetry::trace::{TraceContextExt, TracerProvider};
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        opentelemetry::global::set_text_map_propagator(
+            opentelemetry_sdk::propagation::TraceContextPropagator::new(),
+        );
+        let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let caller = tool_span("search", "call-1");
+            let headers = caller.in_scope(current_trace_headers);
+            let traceparent = headers.get("traceparent").expect("traceparent injected");
+            let caller_trace = caller.context().span().span_context().trace_id();
+            assert!(traceparent.contains(&caller_trace.to_string()));
+
+            let callee = tracing::info_span!("http.request");
+            set_parent_from_headers(&callee, &headers);
+            let callee_trace = callee.context().span().span_context().trace_id();
+            assert_eq!(callee_trace, caller_trace);
+        });
+    }
+}
@@ -111,6 +111,7 @@ mod tests {
                 parser: None,
                 envelope: true,
                 schema: serde_json::json!({}),
+                artifacts: None,
             },
             http: None,
             mcp: None,
@@ -12,6 +12,7 @@ use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::process::Stdio;
 use std::time::Duration;
+use tracing::Instrument;
 
 use super::manifest::Manifest;
 use super::validator;
@@ -19,6 +20,8 @@ use crate::reasoning::circuit_breaker::CircuitBreakerRegistry;
 use crate::reasoning::executor::ActionExecutor;
 use crate::reasoning::inference::ToolDefinition;
 use crate::reasoning::loop_types::{LoopConfig, Observation, ProposedAction};
+use crate::sandbox::artifacts::ArtifactCollector;
+use crate::sandbox::ExecutionResult;
 
 use super::manifest::ArgDef;
 
@@ -38,6 +41,10 @@ pub struct ToolCladExecutor {
     /// running (fail-closed by default). Disable only for local dev via
     /// [`Self::with_mcp_verification`].
     enforce_mcp_verification: bool,
+    /// Long-lived MCP connections; without one, each MCP-backed call
+    /// connects to its server afresh.
+    #[cfg(feature = "mcp-client")]
+    mcp_pool: Option<std::sync::Arc<crate::integrations::mcp::pool::McpConnectionPool>>,
 }
 
 impl ToolCladExecutor {
@@ -82,6 +89,8 @@ impl ToolCladExecutor {
             session_executor,
             browser_executor,
             enforce_mcp_verification: true,
+            #[cfg(feature = "mcp-client")]
+            mcp_pool: None,
         }
     }
 
@@ -92,6 +101,17 @@ impl ToolCladExecutor {
         self
     }
 
+    /// Route MCP-backed tool calls through `pool`'s long-lived connections
+    /// instead of connecting per call.
+    #[cfg(feature = "mcp-client")]
+    pub fn with_mcp_pool(
+        mut self,
+        pool: std::sync::Arc<crate::integrations::mcp::pool::McpConnectionPool>,
+    ) -> Self {
+        self.mcp_pool = Some(pool);
+        self
+    }
+
     /// Check if this executor handles a given tool name.
     /// Matches both direct tool names and session/browser sub-commands
     /// (e.g., "msfconsole_session" or "msfconsole_session.run").
@@ -215,7 +235,22 @@ impl ToolCladExecutor {
         let (program, args) = argv
             .split_first()
             .ok_or_else(|| "Empty command after template interpolation".to_string())?;
-        let output = run_shell_with_timeout(name, program, args, timeout)?;
+        // Tools that collect artifacts run in a fresh working directory so
+        // only files they wrote there are picked up.
+        let workdir = manifest
+            .output
+            .artifacts
+            .as_ref()
+            .map(|_| tempfile::tempdir())
+            .transpose()
+            .map_err(|e| format!("Failed to create working directory for '{}': {}", name, e))?;
+        let output = run_shell_with_timeout(
+            name,
+            program,
+            args,
+            timeout,
+            workdir.as_ref().map(|d| d.path()),
+        )?;
 
         let duration_ms = start.elapsed().as_millis() as u64;
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -272,6 +307,24 @@ impl ToolCladExecutor {
             }
         }
 
+        if let (Some(spec), Some(dir)) = (&manifest.output.artifacts, &workdir) {
+            let (artifacts, artifacts_truncated) = ArtifactCollector::new(spec)
+                .and_then(|collector| collector.collect_dir_blocking(dir.path()))
+                .map_err(|e| format!("Failed to collect artifacts for '{}': {}", name, e))?;
+            let result = ExecutionResult {
+                exit_code: output.status.code().unwrap_or(-1),
+                success: output.status.success(),
+                stdout,
+                stderr,
+                execution_time_ms: duration_ms,
+                stdout_truncated: false,
+                stderr_truncated: false,
+                artifacts,
+                artifacts_truncated,
+            };
+            attach_artifacts(&mut envelope, &result);
+        }
+
         // Attach schema warnings if any
         if !schema_warnings.is_empty() {
             if let Some(obj) = envelope.as_object_mut() {
@@ -392,8 +445,8 @@ impl ToolCladExecutor {
     }
 
     /// Execute an MCP proxy backend tool by loading the on-disk server
-    /// registry (`./mcp-config.toml`) and dispatching to the real stdio MCP
-    /// client. See [`Self::execute_mcp_backend_async_with_registry`] for the
+    /// registry (`./mcp-config.toml`) and dispatching to the real MCP client
+    /// (the connection pool when set, else a one-shot stdio or HTTP call). See [`Self::execute_mcp_backend_async_with_registry`] for the
     /// injectable-registry variant used by tests.
     #[cfg(feature = "mcp-client")]
     async fn execute_mcp_backend_async(
@@ -419,6 +472,10 @@ impl ToolCladExecutor {
         manifest: &Manifest,
         validated: &HashMap<String, String>,
     ) -> Result<serde_json::Value, String> {
+        use crate::integrations::mcp::http_client::RmcpHttpClient;
+        use crate::integrations::mcp::registry::McpServerSpec;
+        use crate::integrations::mcp::stdio_client::RmcpStdioClient;
+
         let mcp = manifest
             .mcp
             .as_ref()
@@ -434,13 +491,20 @@ impl ToolCladExecutor {
         // Map validated args to upstream tool's expected format
         let upstream_args = map_upstream_args(mcp, &manifest.args, validated);
 
-        let result = crate::integrations::mcp::stdio_client::RmcpStdioClient::verified_invoke(
-            spec,
-            &mcp.tool,
-            upstream_args,
-            self.enforce_mcp_verification,
-        )
-        .await?;
+        let enforce = self.enforce_mcp_verification;
+        let result = match (&self.mcp_pool, spec) {
+            (Some(pool), _) => {
+                pool.verified_invoke(&mcp.server, spec, &mcp.tool, upstream_args, enforce)
+                    .await?
+            }
+            (None, McpServerSpec::Stdio(spec)) => {
+                RmcpStdioClient::verified_invoke(spec, &mcp.tool, upstream_args, enforce).await?
+            }
+            (None, McpServerSpec::Http(spec)) => {
+                RmcpHttpClient::verified_invoke(spec, None, &mcp.tool, upstream_args, enforce)
+                    .await?
+            }
+        };
 
         // Real evidence envelope (replaces the fabricated "delegated" one).
         Ok(serde_json::json!({
@@ -583,6 +647,7 @@ impl ActionExecutor for ToolCladExecutor {
                     continue; // Not a ToolClad tool — skip
                 }
 
+                let span = crate::telemetry::tool_span(name, call_id);
                 let is_mcp_tool = self
                     .manifests
                     .get(name.as_str())
@@ -593,46 +658,50 @@ impl ActionExecutor for ToolCladExecutor {
                 // tools are awaited directly here rather than going through
                 // the sync `execute_tool` -> `block_on` bridge, since this
                 // loop already runs on an async runtime.
-                let result = if self.session_executor.handles(name) {
-                    self.session_executor
-                        .execute_session_command(name, arguments)
-                } else if self.browser_executor.handles(name) {
-                    self.browser_executor
-                        .execute_browser_command(name, arguments)
-                } else if is_mcp_tool {
-                    match self.parse_and_validate(name, arguments) {
-                        Ok((manifest, validated)) => {
-                            // Bound the MCP call: it spawns a subprocess and does
-                            // a stdio handshake with no inherent deadline, so a
-                            // hung/slow server would otherwise hang the caller
-                            // indefinitely (the DSL `tool_call()` path has no
-                            // outer timeout). Use the tool's declared
-                            // `timeout_seconds`, capped by `config.tool_timeout`;
-                            // treat 0 as "no per-tool limit" and defer to config.
-                            let manifest_secs = manifest.tool.timeout_seconds;
-                            let call_timeout = if manifest_secs == 0 {
-                                config.tool_timeout
-                            } else {
-                                Duration::from_secs(manifest_secs).min(config.tool_timeout)
-                            };
-                            match tokio::time::timeout(
-                                call_timeout,
-                                self.execute_mcp_backend_async(name, manifest, &validated),
-                            )
-                            .await
-                            {
-                                Ok(r) => r,
-                                Err(_) => Err(format!(
-                                    "MCP tool '{}' timed out after {:?}",
-                                    name, call_timeout
-                                )),
+                let result = async {
+                    if self.session_executor.handles(name) {
+                        self.session_executor
+                            .execute_session_command(name, arguments)
+                    } else if self.browser_executor.handles(name) {
+                        self.browser_executor
+                            .execute_browser_command(name, arguments)
+                    } else if is_mcp_tool {
+                        match self.parse_and_validate(name, arguments) {
+                            Ok((manifest, validated)) => {
+                                // Bound the MCP call: it spawns a subprocess and does
+                                // a stdio handshake with no inherent deadline, so a
+                                // hung/slow server would otherwise hang the caller
+                                // indefinitely (the DSL `tool_call()` path has no
+                                // outer timeout). Use the tool's declared
+                                // `timeout_seconds`, capped by `config.tool_timeout`;
+                                // treat 0 as "no per-tool limit" and defer to config.
+                                let manifest_secs = manifest.tool.timeout_seconds;
+                                let call_timeout = if manifest_secs == 0 {
+                                    config.tool_timeout
+                                } else {
+                                    Duration::from_secs(manifest_secs).min(config.tool_timeout)
+                                };
+                                match tokio::time::timeout(
+                                    call_timeout,
+                                    self.execute_mcp_backend_async(name, manifest, &validated),
+                                )
+                                .await
+                                {
+                                    Ok(r) => r,
+                                    Err(_) => Err(format!(
+                                        "MCP tool '{}' timed out after {:?}",
+                                        name, call_timeout
+                                    )),
+                                }
                             }
+                            Err(e) => Err(e),
                         }
-                        Err(e) => Err(e),
+                    } else {
+                        self.execute_tool(name, arguments)
                     }
-                } else {
-                    self.execute_tool(name, arguments)
-                };
+                }
+                .instrument(span.clone())
+                .await;
 
                 let (content, is_error) = match result {
                     Ok(envelope) => (
@@ -642,13 +711,16 @@ impl ActionExecutor for ToolCladExecutor {
                     Err(e) => (format!("ToolClad error: {}", e), true),
                 };
 
-                observations.push(Observation {
+                let observation = Observation {
                     source: format!("toolclad:{}", name),
                     content,
                     is_error,
                     call_id: Some(call_id.clone()),
                     metadata: HashMap::new(),
-                });
+                    outputs: Vec::new(),
+                };
+                crate::telemetry::record_tool_result(&span, &observation);
+                observations.push(observation);
             }
         }
 
@@ -660,6 +732,36 @@ impl ActionExecutor for ToolCladExecutor {
     }
 }
 
+// ---- Evidence ----
+
+/// Record artifacts collected by a sandbox execution in an evidence envelope.
+///
+/// Each entry carries the path, size and a `sha256:`-prefixed digest in the
+/// same form as `output_hash`; file contents stay out of the envelope.
+pub fn attach_artifacts(envelope: &mut serde_json::Value, result: &ExecutionResult) {
+    let Some(obj) = envelope.as_object_mut() else {
+        return;
+    };
+    if result.artifacts.is_empty() && !result.artifacts_truncated {
+        return;
+    }
+    let artifacts: Vec<serde_json::Value> = result
+        .artifacts
+        .iter()
+        .map(|a| {
+            serde_json::json!({
+                "path": a.path,
+                "size_bytes": a.size_bytes,
+                "sha256": format!("sha256:{}", a.sha256),
+            })
+        })
+        .collect();
+    obj.insert("artifacts".to_string(), serde_json::json!(artifacts));
+    if result.artifacts_truncated {
+        obj.insert("artifacts_truncated".to_string(), serde_json::json!(true));
+    }
+}
+
 // ---- Output Parsing ----
 
 /// Parse raw tool output based on the manifest's `output.format` and `output.parser` fields.
@@ -916,6 +1018,8 @@ fn json_type_name(value: &serde_json::Value) -> &'static str {
 /// input, or one that simply never exits) would otherwise block its caller
 /// forever despite the manifest declaring a `timeout_seconds`.
 ///
+/// `cwd`, when set, becomes the child's working directory.
+///
 /// stdout/stderr are drained on background threads rather than left unread
 /// while polling `try_wait()` in a loop: a child that fills its OS pipe
 /// buffer would block on the next write forever, defeating the timeout no
@@ -936,8 +1040,13 @@ fn run_shell_with_timeout(
     program: &str,
     args: &[String],
     timeout: Duration,
+    cwd: Option<&std::path::Path>,
 ) -> Result<std::process::Output, String> {
-    let mut child = std::process::Command::new(program)
+    let mut command = std::process::Command::new(program);
+    if let Some(cwd) = cwd {
+        command.current_dir(cwd);
+    }
+    let mut child = command
         .args(args)
         // Match `Command::output()`'s implicit default (stdin closed, not
         // inherited from this process) now that we spawn manually.
@@ -1597,6 +1706,74 @@ type = "object"
         assert!(result.get("_note").is_some());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_tool_shell_backend_collects_artifacts_into_envelope() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+[tool]
+name = "touch_test"
+version = "1.0.0"
+binary = "touch"
+description = "Test"
+
+[args.file]
+position = 1
+required = true
+type = "string"
+
+[command]
+template = "touch {file} skipped.log"
+
+[output]
+format = "text"
+
+[output.artifacts]
+patterns = ["*.txt"]
+"#,
+        )
+        .unwrap();
+
+        let executor = ToolCladExecutor::new(vec![("touch_test".to_string(), manifest)]);
+        let result = executor
+            .execute_tool("touch_test", r#"{"file": "report.txt"}"#)
+            .unwrap();
+
+        assert_eq!(result["status"], "success");
+        let artifacts = result["artifacts"].as_array().unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0]["path"], "report.txt");
+        assert_eq!(artifacts[0]["size_bytes"], 0);
+        assert_eq!(
+            artifacts[0]["sha256"],
+            "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert!(result.get("artifacts_truncated").is_none());
+    }
+
+    #[test]
+    fn test_attach_artifacts_records_hashes_without_contents() {
+        let mut result = ExecutionResult::success(String::new(), 5);
+        let mut envelope = serde_json::json!({"status": "success"});
+        attach_artifacts(&mut envelope, &result);
+        assert!(envelope.get("artifacts").is_none());
+
+        result.artifacts = vec![crate::sandbox::Artifact::new(
+            "report.txt",
+            b"hello".to_vec(),
+        )];
+        result.artifacts_truncated = true;
+        attach_artifacts(&mut envelope, &result);
+        assert_eq!(envelope["artifacts"][0]["path"], "report.txt");
+        assert_eq!(envelope["artifacts"][0]["size_bytes"], 5);
+        assert_eq!(
+            envelope["artifacts"][0]["sha256"],
+            "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert!(envelope["artifacts"][0].get("content").is_none());
+        assert_eq!(envelope["artifacts_truncated"], true);
+    }
+
     #[test]
     fn test_parse_output_default_text() {
         let manifest: Manifest = toml::from_str(
@@ -165,6 +165,10 @@ pub struct OutputDef {
     pub envelope: bool,
     #[serde(default)]
     pub schema: serde_json::Value,
+    /// Files to collect from the tool's working directory into the evidence
+    /// envelope. When set, shell tools run in a fresh working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<crate::sandbox::ArtifactSpec>,
 }
 
 // ---- Session Mode Types ----
@@ -17,6 +17,6 @@ pub mod template_vars;
 pub mod validator;
 pub mod watcher;
 
-pub use executor::ToolCladExecutor;
+pub use executor::{attach_artifacts, ToolCladExecutor};
 pub use manifest::{load_custom_types, load_manifest, load_manifests_from_dir, Manifest};
 pub use scope::Scope;
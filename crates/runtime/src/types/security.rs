@@ -50,13 +50,18 @@ impl SecurityTier {
     /// `dsl::SandboxTier::E2B` maps to `Hosted` — it is not a host-
     /// isolation tier. The runner factory still picks the actual E2B
     /// backend; this mapping only affects how the agent's security
-    /// requirements are categorised in policy decisions.
+    /// requirements are categorised in policy decisions. `Namespace` gives
+    /// container-grade isolation (namespaces + seccomp, plus Landlock), so it
+    /// sits on the same rung as Docker. `Wasm` runs code in a wasm engine with
+    /// no host access beyond preopened directories, and also counts as Tier1.
     pub fn from_dsl_sandbox(tier: &dsl::SandboxTier) -> Self {
         match tier {
             dsl::SandboxTier::Docker => SecurityTier::Tier1,
             dsl::SandboxTier::GVisor => SecurityTier::Tier2,
             dsl::SandboxTier::Firecracker => SecurityTier::Tier3,
             dsl::SandboxTier::E2B => SecurityTier::Hosted,
+            dsl::SandboxTier::Namespace => SecurityTier::Tier1,
+            dsl::SandboxTier::Wasm => SecurityTier::Tier1,
         }
     }
 }
@@ -374,6 +379,14 @@ mod from_dsl_sandbox_tests {
             SecurityTier::from_dsl_sandbox(&dsl::SandboxTier::E2B),
             SecurityTier::Hosted
         );
+        assert_eq!(
+            SecurityTier::from_dsl_sandbox(&dsl::SandboxTier::Namespace),
+            SecurityTier::Tier1
+        );
+        assert_eq!(
+            SecurityTier::from_dsl_sandbox(&dsl::SandboxTier::Wasm),
+            SecurityTier::Tier1
+        );
     }
 
     #[test]
@@ -44,6 +44,7 @@ fn text(content: &str) -> InferenceResponse {
             prompt_tokens: 10,
             completion_tokens: 5,
             total_tokens: 15,
+            cached_prompt_tokens: 0,
         },
         model: "mock".into(),
     }
@@ -62,6 +63,7 @@ fn delegate_call(call_id: &str, agent: &str, task: &str) -> InferenceResponse {
             prompt_tokens: 10,
             completion_tokens: 5,
             total_tokens: 15,
+            cached_prompt_tokens: 0,
         },
         model: "mock".into(),
     }
@@ -126,6 +128,8 @@ async fn delegate_tool_call_runs_the_target_and_returns_its_output() {
         circuit_breakers: Arc::new(CircuitBreakerRegistry::default()),
         journal: Arc::new(BufferedJournal::new(100)),
         knowledge_bridge: None,
+        sandbox_sessions: None,
+        spend: None,
         delegation: Some(delegation.clone()),
     };
 
@@ -219,6 +223,8 @@ async fn delegate_tool_call_to_unknown_target_is_an_honest_correlated_error() {
         circuit_breakers: Arc::new(CircuitBreakerRegistry::default()),
         journal: Arc::new(BufferedJournal::new(100)),
         knowledge_bridge: None,
+        sandbox_sessions: None,
+        spend: None,
         delegation: Some(delegation.clone()),
     };
 
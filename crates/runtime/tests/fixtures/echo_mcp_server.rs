@@ -1,9 +1,10 @@
-//! Test-only stdio MCP server exposing a single `echo` tool.
+//! Test-only stdio MCP server exposing an `echo` tool, plus `pid` and
+//! `crash` tools that let pool tests observe process reuse and restarts.
 //!
 //! Built as a `[[bin]]` target of `symbi-runtime`, gated behind the
 //! `mcp-client` feature (`required-features = ["mcp-client"]` in
-//! `Cargo.toml`). Used exclusively by the `mcp_stdio` integration test as a
-//! real subprocess to exercise `RmcpStdioClient` end-to-end over stdio.
+//! `Cargo.toml`). Used by the MCP integration tests as a real subprocess to
+//! exercise `RmcpStdioClient` and `McpConnectionPool` end-to-end over stdio.
 //!
 //! Mirrors the server macro shapes used in `src/mcp_server/mod.rs`
 //! (`#[tool_router]` / `#[tool]` / `#[tool_handler]` / `ServerHandler`).
@@ -43,6 +44,16 @@ impl Echo {
     async fn echo(&self, Parameters(args): Parameters<EchoArgs>) -> String {
         args.text
     }
+
+    #[tool(description = "Return this server's process ID")]
+    async fn pid(&self) -> String {
+        std::process::id().to_string()
+    }
+
+    #[tool(description = "Exit the server process immediately")]
+    async fn crash(&self) -> String {
+        std::process::exit(1)
+    }
 }
 
 #[tool_handler]
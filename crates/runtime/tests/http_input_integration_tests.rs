@@ -39,6 +39,9 @@ fn create_test_config(port: u16) -> HttpInputConfig {
         cors_origins: vec![format!("http://127.0.0.1:{}", port)],
         audit_enabled: true,
         webhook_verify: None,
+        webhook_routes: vec![],
+        agent_memory: vec![],
+        resume_interrupted_runs: false,
     }
 }
 
@@ -231,6 +231,8 @@ fn make_runner(
         circuit_breakers: Arc::new(CircuitBreakerRegistry::default()),
         journal: Arc::new(BufferedJournal::new(1000)),
         knowledge_bridge,
+        sandbox_sessions: None,
+        spend: None,
         delegation: None,
     }
 }
@@ -256,6 +258,7 @@ fn simple_response(content: &str) -> InferenceResponse {
             prompt_tokens: 10,
             completion_tokens: 5,
             total_tokens: 15,
+            cached_prompt_tokens: 0,
         },
         model: "mock".into(),
     }
@@ -274,6 +277,7 @@ fn tool_call_response(call_id: &str, name: &str, arguments: &str) -> InferenceRe
             prompt_tokens: 10,
             completion_tokens: 10,
             total_tokens: 20,
+            cached_prompt_tokens: 0,
         },
         model: "mock".into(),
     }
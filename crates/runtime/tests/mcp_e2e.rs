@@ -60,6 +60,7 @@ fn build_echo_manifest() -> Manifest {
             parser: None,
             envelope: true,
             schema: serde_json::json!({"type": "object"}),
+            artifacts: None,
         },
         http: None,
         mcp: Some(McpProxyDef {
@@ -0,0 +1,108 @@
+//! Integration test: drive the `echo_mcp_server` fixture through
+//! `McpConnectionPool` — calls reuse one long-lived subprocess, a crashed
+//! server is restarted on next use, the health check revives a dead
+//! connection, and enforcement still blocks unverified tools.
+#![cfg(feature = "mcp-client")]
+
+use std::time::Duration;
+
+use symbi_runtime::integrations::mcp::pool::{McpConnectionPool, McpPoolConfig};
+use symbi_runtime::integrations::mcp::registry::{McpServerSpec, StdioServerSpec};
+
+fn echo_spec() -> McpServerSpec {
+    McpServerSpec::Stdio(StdioServerSpec {
+        command: env!("CARGO_BIN_EXE_echo_mcp_server").to_string(),
+        args: vec![],
+        env: Default::default(),
+        public_key_url: None,
+    })
+}
+
+fn pool() -> McpConnectionPool {
+    McpConnectionPool::new(McpPoolConfig {
+        restart_backoff: Duration::from_millis(10),
+        ..Default::default()
+    })
+}
+
+async fn server_pid(pool: &McpConnectionPool, spec: &McpServerSpec) -> String {
+    pool.verified_invoke("echo", spec, "pid", Default::default(), false)
+        .await
+        .expect("pid call")
+        .to_string()
+}
+
+#[tokio::test]
+async fn reuses_one_connection_across_calls() {
+    let pool = pool();
+    let spec = echo_spec();
+
+    let first = server_pid(&pool, &spec).await;
+    let mut args = serde_json::Map::new();
+    args.insert("text".into(), serde_json::json!("hello"));
+    let echoed = pool
+        .verified_invoke("echo", &spec, "echo", args, false)
+        .await
+        .expect("echo call");
+    assert!(echoed.to_string().contains("hello"));
+    assert_eq!(server_pid(&pool, &spec).await, first);
+
+    let tools = pool.list_tools("echo", &spec).await.expect("list");
+    assert!(tools.iter().any(|t| t.name == "crash"));
+    assert_eq!(pool.restarts("echo"), Some(0));
+    pool.shutdown().await;
+}
+
+#[tokio::test]
+async fn restarts_crashed_server_on_next_call() {
+    let pool = pool();
+    let spec = echo_spec();
+
+    let before = server_pid(&pool, &spec).await;
+    let crashed = pool
+        .verified_invoke("echo", &spec, "crash", Default::default(), false)
+        .await;
+    assert!(crashed.is_err(), "a call the server died during must fail");
+
+    let after = server_pid(&pool, &spec).await;
+    assert_ne!(before, after, "a fresh server process should be running");
+    assert_eq!(pool.restarts("echo"), Some(1));
+    pool.shutdown().await;
+}
+
+#[tokio::test]
+async fn health_check_restarts_dead_connection() {
+    let pool = pool();
+    let spec = echo_spec();
+
+    let _ = server_pid(&pool, &spec).await;
+    let _ = pool
+        .verified_invoke("echo", &spec, "crash", Default::default(), false)
+        .await;
+    pool.check_health().await;
+    assert_eq!(pool.restarts("echo"), Some(1));
+    let _ = server_pid(&pool, &spec).await;
+    assert_eq!(
+        pool.restarts("echo"),
+        Some(1),
+        "already revived by the check"
+    );
+    pool.shutdown().await;
+}
+
+#[tokio::test]
+async fn enforce_blocks_unverified_tool_on_pooled_connection() {
+    let pool = pool();
+    let spec = echo_spec();
+    let mut args = serde_json::Map::new();
+    args.insert("text".into(), serde_json::json!("hi"));
+
+    let blocked = pool
+        .verified_invoke("echo", &spec, "echo", args, true)
+        .await;
+    assert!(
+        blocked.is_err(),
+        "unverified tool must be blocked under enforcement"
+    );
+    pool.shutdown().await;
+}
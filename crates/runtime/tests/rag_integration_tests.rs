@@ -3,16 +3,70 @@
 //! These tests verify the end-to-end functionality of the RAG engine
 //! with real context manager and vector database integration.
 
+#![cfg(feature = "vector-lancedb")]
+
+use async_trait::async_trait;
 use symbi_runtime::context::manager::{
     ContextManager, ContextManagerConfig, StandardContextManager,
 };
-use symbi_runtime::context::types::{Knowledge, KnowledgeFact, KnowledgeId, KnowledgeSource};
+use symbi_runtime::context::types::ContextError;
+use symbi_runtime::context::vector_db::EmbeddingService;
+use symbi_runtime::context::vector_db_lance::{LanceDbBackend, LanceDbConfig};
+use symbi_runtime::context::vector_db_trait::{DistanceMetric, VectorDb};
 use symbi_runtime::rag::engine::{RAGEngine, StandardRAGEngine};
 use symbi_runtime::rag::types::*;
 use symbi_runtime::types::AgentId;
 
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+const EMBEDDING_DIMENSION: usize = 384;
+
+/// Deterministic bag-of-words embedding so retrieval is reproducible
+/// without a model server.
+struct WordHashEmbedding;
+
+#[async_trait]
+impl EmbeddingService for WordHashEmbedding {
+    async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>, ContextError> {
+        let mut embedding = vec![0.0; EMBEDDING_DIMENSION];
+        for word in text
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 2)
+        {
+            let bucket = word
+                .bytes()
+                .fold(7usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+            embedding[bucket % EMBEDDING_DIMENSION] += 1.0;
+        }
+        let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            embedding.iter_mut().for_each(|x| *x /= norm);
+        }
+        Ok(embedding)
+    }
+
+    async fn generate_batch_embeddings(
+        &self,
+        texts: Vec<&str>,
+    ) -> Result<Vec<Vec<f32>>, ContextError> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            embeddings.push(self.generate_embedding(text).await?);
+        }
+        Ok(embeddings)
+    }
+
+    fn embedding_dimension(&self) -> usize {
+        EMBEDDING_DIMENSION
+    }
+
+    fn max_text_length(&self) -> usize {
+        8192
+    }
+}
 
 /// Create a test context manager with vector database integration
 async fn create_test_context_manager() -> Arc<dyn ContextManager> {
@@ -28,6 +82,31 @@ async fn create_test_context_manager() -> Arc<dyn ContextManager> {
     Arc::new(manager)
 }
 
+/// Create a RAG engine backed by LanceDB in a temporary directory. The
+/// directory must outlive the engine.
+async fn create_test_rag_engine(
+    context_manager: Arc<dyn ContextManager>,
+) -> (StandardRAGEngine, TempDir) {
+    let data_dir = TempDir::new().expect("Failed to create temp dir");
+    let vector_db = LanceDbBackend::new(LanceDbConfig {
+        data_path: data_dir.path().to_path_buf(),
+        collection_name: "rag_integration".to_string(),
+        vector_dimension: EMBEDDING_DIMENSION,
+        distance_metric: DistanceMetric::Cosine,
+    })
+    .await
+    .expect("Failed to create LanceDB backend");
+    vector_db
+        .initialize()
+        .await
+        .expect("Failed to initialize LanceDB backend");
+
+    let engine = StandardRAGEngine::new(context_manager)
+        .with_vector_db(Arc::new(vector_db))
+        .with_embedding_service(Arc::new(WordHashEmbedding));
+    (engine, data_dir)
+}
+
 /// Create a test RAG request
 fn create_test_rag_request(agent_id: AgentId, query: &str) -> RAGRequest {
     RAGRequest {
@@ -50,66 +129,61 @@ fn create_test_rag_request(agent_id: AgentId, query: &str) -> RAGRequest {
     }
 }
 
-/// Populate context manager with test knowledge
-async fn populate_test_knowledge(
-    context_manager: &Arc<dyn ContextManager>,
-    agent_id: AgentId,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Create session for the agent (using the manager's create_session method)
-
-    // Add some test knowledge facts
-    let facts = vec![
-        KnowledgeFact {
-            id: KnowledgeId::new(),
-            subject: "machine learning".to_string(),
-            predicate: "is".to_string(),
-            object: "a subset of artificial intelligence that focuses on algorithms that learn from data".to_string(),
-            confidence: 0.9,
-            source: KnowledgeSource::UserProvided,
-            created_at: SystemTime::now(),
-            verified: true,
-        },
-        KnowledgeFact {
-            id: KnowledgeId::new(),
-            subject: "neural networks".to_string(),
-            predicate: "are".to_string(),
-            object: "computing systems inspired by biological neural networks".to_string(),
-            confidence: 0.85,
-            source: KnowledgeSource::UserProvided,
-            created_at: SystemTime::now(),
-            verified: true,
-        },
-        KnowledgeFact {
-            id: KnowledgeId::new(),
-            subject: "deep learning".to_string(),
-            predicate: "uses".to_string(),
-            object: "multiple layers of neural networks to model complex patterns".to_string(),
-            confidence: 0.88,
-            source: KnowledgeSource::UserProvided,
-            created_at: SystemTime::now(),
-            verified: true,
-        },
+/// Ingest the test corpus into the RAG engine
+async fn populate_test_documents(
+    rag_engine: &StandardRAGEngine,
+) -> Result<Vec<DocumentId>, Box<dyn std::error::Error>> {
+    let documents = [
+        (
+            "Machine learning",
+            "Machine learning is a subset of artificial intelligence that focuses on \
+             algorithms that learn from data.",
+        ),
+        (
+            "Neural networks",
+            "Neural networks are computing systems inspired by biological neural networks.\n\n\
+             Neural networks learn by adjusting connection weights.",
+        ),
+        (
+            "Deep learning",
+            "Deep learning uses multiple layers of neural networks to model complex patterns.",
+        ),
     ];
 
-    for fact in facts {
-        context_manager
-            .add_knowledge(agent_id, Knowledge::Fact(fact))
-            .await?;
-    }
-
-    Ok(())
+    let inputs = documents
+        .iter()
+        .map(|(title, content)| DocumentInput {
+            title: title.to_string(),
+            content: content.to_string(),
+            metadata: DocumentMetadata {
+                document_type: DocumentType::Text,
+                author: Some("AI Expert".to_string()),
+                created_at: SystemTime::now(),
+                updated_at: SystemTime::now(),
+                language: "en".to_string(),
+                domain: "technical".to_string(),
+                access_level: AccessLevel::Public,
+                tags: vec!["ml".to_string()],
+                source_url: None,
+                file_path: None,
+            },
+            chunking_strategy: ChunkingStrategy::Paragraph,
+        })
+        .collect();
+
+    Ok(rag_engine.ingest_documents(inputs).await?)
 }
 
 #[tokio::test]
 async fn test_rag_engine_initialization() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager);
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
 
     let config = RAGConfig {
         embedding_model: EmbeddingModelConfig {
             model_name: "mock-model".to_string(),
             model_type: EmbeddingModelType::Local,
-            dimension: 384,
+            dimension: EMBEDDING_DIMENSION,
             max_tokens: 512,
             batch_size: 32,
         },
@@ -149,12 +223,10 @@ async fn test_rag_engine_initialization() {
 #[tokio::test]
 async fn test_rag_pipeline_performance() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager.clone());
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
 
     let agent_id = AgentId::new();
-    populate_test_knowledge(&context_manager, agent_id)
-        .await
-        .unwrap();
+    populate_test_documents(&rag_engine).await.unwrap();
 
     let request = create_test_rag_request(agent_id, "What is machine learning?");
 
@@ -195,7 +267,7 @@ async fn test_rag_pipeline_performance() {
 #[tokio::test]
 async fn test_rag_query_analysis_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager);
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
 
     let test_queries = vec![
         ("How do neural networks work?", QueryIntent::Factual),
@@ -252,12 +324,8 @@ async fn test_rag_query_analysis_integration() {
 #[tokio::test]
 async fn test_rag_document_retrieval_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager.clone());
-
-    let agent_id = AgentId::new();
-    populate_test_knowledge(&context_manager, agent_id)
-        .await
-        .unwrap();
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
+    populate_test_documents(&rag_engine).await.unwrap();
 
     let analyzed_query = AnalyzedQuery {
         original_query: "machine learning algorithms".to_string(),
@@ -309,7 +377,8 @@ async fn test_rag_document_retrieval_integration() {
 #[tokio::test]
 async fn test_rag_ranking_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager);
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
+    populate_test_documents(&rag_engine).await.unwrap();
 
     let analyzed_query = AnalyzedQuery {
         original_query: "neural networks".to_string(),
@@ -372,7 +441,8 @@ async fn test_rag_ranking_integration() {
 #[tokio::test]
 async fn test_rag_context_augmentation_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager);
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
+    populate_test_documents(&rag_engine).await.unwrap();
 
     let analyzed_query = AnalyzedQuery {
         original_query: "deep learning".to_string(),
@@ -434,7 +504,8 @@ async fn test_rag_context_augmentation_integration() {
 #[tokio::test]
 async fn test_rag_response_generation_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager);
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
+    populate_test_documents(&rag_engine).await.unwrap();
 
     // Create a complete augmented context
     let analyzed_query = AnalyzedQuery {
@@ -498,7 +569,7 @@ async fn test_rag_response_generation_integration() {
 #[tokio::test]
 async fn test_rag_response_validation_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager);
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
 
     let test_response = GeneratedResponse {
         content: "Neural networks are computing systems inspired by biological neural networks that constitute animal brains.".to_string(),
@@ -544,12 +615,10 @@ async fn test_rag_response_validation_integration() {
 #[tokio::test]
 async fn test_rag_end_to_end_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager.clone());
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
 
     let agent_id = AgentId::new();
-    populate_test_knowledge(&context_manager, agent_id)
-        .await
-        .unwrap();
+    populate_test_documents(&rag_engine).await.unwrap();
 
     let test_queries = vec![
         "What is machine learning?",
@@ -625,7 +694,7 @@ async fn test_rag_end_to_end_integration() {
 #[tokio::test]
 async fn test_rag_stats_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager);
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
 
     let result = rag_engine.get_stats().await;
     assert!(result.is_ok(), "Getting RAG stats should succeed");
@@ -653,7 +722,7 @@ async fn test_rag_stats_integration() {
 #[tokio::test]
 async fn test_rag_error_handling_integration() {
     let context_manager = create_test_context_manager().await;
-    let rag_engine = StandardRAGEngine::new(context_manager);
+    let (rag_engine, _data_dir) = create_test_rag_engine(context_manager).await;
 
     // Test with very short timeout to trigger timeout error
     let request = RAGRequest {
@@ -150,6 +150,8 @@ fn make_live_runner(
         circuit_breakers,
         journal,
         knowledge_bridge: None,
+        sandbox_sessions: None,
+        spend: None,
         delegation: None,
     })
 }
@@ -69,6 +69,7 @@ impl InferenceProvider for OrgaMockProvider {
                     prompt_tokens: 50,
                     completion_tokens: 20,
                     total_tokens: 70,
+                    cached_prompt_tokens: 0,
                 },
                 model: "mock-model".into(),
             }),
@@ -81,6 +82,7 @@ impl InferenceProvider for OrgaMockProvider {
                     prompt_tokens: 80,
                     completion_tokens: 5,
                     total_tokens: 85,
+                    cached_prompt_tokens: 0,
                 },
                 model: "mock-model".into(),
             }),
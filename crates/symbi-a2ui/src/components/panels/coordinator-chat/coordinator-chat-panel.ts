@@ -137,6 +137,8 @@ export class CoordinatorChatPanel extends LitElement {
   @state() private _isProcessing = false;
   @state() private _connectionState: ConnectionState = 'disconnected';
   @state() private _activePhase: ReasoningPhase = 'idle';
+  /** Text streamed so far for the in-flight model turn. */
+  @state() private _streamingContent = '';
 
   private _ws = WsClient.instance();
   // Tool traces indexed by call_id
@@ -187,7 +189,13 @@ export class CoordinatorChatPanel extends LitElement {
   }
 
   private _onChatChunk(msg: ChatChunk) {
+    if (!msg.done) {
+      this._streamingContent += msg.content;
+      this._scrollToBottom();
+      return;
+    }
     if (msg.done) {
+      this._streamingContent = '';
       // Finalize assistant message
       const toolTraces = Array.from(this._pendingToolTraces.values());
       const policyTraces = [...this._pendingPolicyTraces];
@@ -211,6 +219,9 @@ export class CoordinatorChatPanel extends LitElement {
   }
 
   private _onToolCallStarted(msg: ToolCallStarted) {
+    // Text streamed before a tool call is narration for that turn, not the
+    // final answer; the next turn streams afresh.
+    this._streamingContent = '';
     this._activePhase = 'act';
     this._pendingToolTraces.set(msg.call_id, {
       call_id: msg.call_id,
@@ -248,6 +259,7 @@ export class CoordinatorChatPanel extends LitElement {
     ];
     this._isProcessing = false;
     this._activePhase = 'idle';
+    this._streamingContent = '';
   }
 
   private _onChatSubmit(e: CustomEvent<string>) {
@@ -310,6 +322,11 @@ export class CoordinatorChatPanel extends LitElement {
           : this._messages.map(
               (m) => html`<chat-message .data=${m}></chat-message>`,
             )}
+        ${this._isProcessing && this._streamingContent
+          ? html`<chat-message
+              .data=${{ id: 'streaming', role: 'assistant', content: this._streamingContent }}
+            ></chat-message>`
+          : ''}
         ${this._isProcessing
           ? html`
               <div class="thinking">
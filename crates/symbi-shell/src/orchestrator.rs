@@ -489,6 +489,15 @@ fn describe_termination(reason: &TerminationReason) -> String {
         TerminationReason::Timeout => "timed out".to_string(),
         TerminationReason::PolicyDenial { reason } => format!("policy denied: {}", reason),
         TerminationReason::Error { message } => format!("error: {}", message),
+        TerminationReason::BudgetExceeded {
+            scope,
+            period,
+            limit_usd,
+            spent_usd,
+        } => format!(
+            "spend budget exhausted — {} per-{} limit ${:.2} reached (spent ${:.2})",
+            scope, period, limit_usd, spent_usd
+        ),
     }
 }
 
@@ -87,6 +87,7 @@ impl ActionExecutor for OrchestratorExecutor {
                         is_error,
                         call_id: Some(call_id.clone()),
                         metadata: HashMap::new(),
+                        outputs: Vec::new(),
                     });
                 }
                 ProposedAction::Respond { .. }
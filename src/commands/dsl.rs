@@ -1,5 +1,8 @@
 //! `symbi dsl` subcommand — parse and analyze Symbiont DSL files.
 
+use std::path::Path;
+use symbi_channel_adapter::DlpPolicy;
+
 /// Run the DSL parse-and-analyze command.
 pub fn run(source: &str, filename: Option<&str>) {
     let label = filename.unwrap_or("<inline>");
@@ -19,7 +22,19 @@ pub fn run(source: &str, filename: Option<&str>) {
     let has_errors = root.has_error();
     if has_errors {
         eprintln!("Parse errors in {}:", label);
-        dsl::find_errors(root, source, 1);
+        for diag in dsl::find_errors(root, source, 1) {
+            eprintln!("  {}", diag);
+        }
+        eprintln!();
+    }
+
+    // Resolve cross-references against the other agent files
+    let semantic_errors = semantic_diagnostics(source, filename);
+    if !semantic_errors.is_empty() {
+        eprintln!("Semantic errors in {}:", label);
+        for diag in &semantic_errors {
+            eprintln!("  {}", diag);
+        }
         eprintln!();
     }
 
@@ -90,7 +105,9 @@ pub fn run(source: &str, filename: Option<&str>) {
         _ => {}
     }
 
-    // Extract channel definitions
+    // Extract channel definitions; an invalid data classification policy is
+    // an error, since `symbi up` refuses to start the channel with it.
+    let mut channel_errors = 0;
     match dsl::extract_channel_definitions(&tree, source) {
         Ok(channels) if !channels.is_empty() => {
             println!("Channels: {}", channels.len());
@@ -112,12 +129,24 @@ pub fn run(source: &str, filename: Option<&str>) {
                 if !ch.policy_rules.is_empty() {
                     println!("    policy rules: {}", ch.policy_rules.len());
                 }
+                if let Some(ref profile) = ch.dlp_profile {
+                    println!("    dlp_profile: {}", profile);
+                }
                 if !ch.data_classification.is_empty() {
                     println!(
                         "    data classification rules: {}",
                         ch.data_classification.len()
                     );
                 }
+                if let Err(e) = DlpPolicy::from_rules(
+                    ch.dlp_profile.as_deref(),
+                    ch.data_classification
+                        .iter()
+                        .map(|entry| (entry.category.as_str(), entry.action.as_str())),
+                ) {
+                    eprintln!("Error: channel '{}' in {}: {}", ch.name, label, e);
+                    channel_errors += 1;
+                }
             }
             println!();
         }
@@ -127,11 +156,63 @@ pub fn run(source: &str, filename: Option<&str>) {
         _ => {}
     }
 
+    // Extract memory definitions
+    match dsl::extract_memory_definitions(&tree, source) {
+        Ok(memories) if !memories.is_empty() => {
+            println!("Memory: {}", memories.len());
+            for m in &memories {
+                println!(
+                    "  {} (store: {:?}, path: {}, retention: {}d)",
+                    m.name,
+                    m.store,
+                    m.path.display(),
+                    m.retention.as_secs() / 86400
+                );
+                if let Some(ref search) = m.search {
+                    println!(
+                        "    search: vector_weight {}, keyword_weight {}",
+                        search.vector_weight, search.keyword_weight
+                    );
+                }
+            }
+            println!();
+        }
+        Err(e) => {
+            eprintln!("Warning: failed to extract memory: {}", e);
+        }
+        _ => {}
+    }
+
     // Print AST structure
     println!("AST:");
     dsl::print_ast(root, source, 1);
 
-    if has_errors {
+    if has_errors || !semantic_errors.is_empty() || channel_errors > 0 {
         std::process::exit(1);
     }
 }
+
+/// Run the semantic pass over `source` together with its sibling files: the
+/// file's own directory, or `agents/` for inline content.
+fn semantic_diagnostics(source: &str, filename: Option<&str>) -> Vec<dsl::DslDiagnostic> {
+    let path = Path::new(filename.unwrap_or("<inline>"));
+    let dir = match filename {
+        Some(_) => path.parent().filter(|p| !p.as_os_str().is_empty()),
+        None => Some(Path::new("agents")),
+    }
+    .unwrap_or(Path::new("."));
+
+    let mut workspace = if dir.is_dir() {
+        dsl::semantic::Workspace::from_dir(dir).unwrap_or_default()
+    } else {
+        dsl::semantic::Workspace::new()
+    };
+    // Workspace paths come from `read_dir`, which joins onto `dir`; match that
+    // form so the file replaces its on-disk copy rather than duplicating it.
+    let key = match filename {
+        Some(_) => dir.join(path.file_name().unwrap_or_default()),
+        None => path.to_path_buf(),
+    };
+    workspace.add_file(key.clone(), source);
+    workspace.check_file(&key)
+}
@@ -64,18 +64,48 @@ pub async fn run(matches: &ArgMatches) {
         }
     }
 
-    // Set up inference provider from environment
-    let provider =
-        match symbi_runtime::reasoning::providers::cloud::CloudInferenceProvider::from_env() {
-            Some(p) => {
-                Arc::new(p) as Arc<dyn symbi_runtime::reasoning::inference::InferenceProvider>
-            }
-            None => {
-                eprintln!("✗ No LLM provider configured.");
-                eprintln!("  Set one of: OPENROUTER_API_KEY, OPENAI_API_KEY, or ANTHROPIC_API_KEY");
-                std::process::exit(1);
+    // Set up inference provider from environment, wrapped in a record/replay
+    // cassette when --cassette / SYMBI_CASSETTE is set.
+    let live = symbi_runtime::reasoning::providers::cloud::CloudInferenceProvider::from_env()
+        .map(|p| Arc::new(p) as Arc<dyn symbi_runtime::reasoning::inference::InferenceProvider>);
+    let cassette = symbi_runtime::reasoning::providers::cassette::CassetteConfig::resolve(
+        matches.get_one::<String>("cassette").map(String::as_str),
+        matches
+            .get_one::<String>("cassette-mode")
+            .map(String::as_str),
+    );
+    let provider = match cassette {
+        Ok(Some(config)) => {
+            match symbi_runtime::reasoning::providers::cassette::CassetteProvider::open(
+                &config, live,
+            ) {
+                Ok(p) => {
+                    println!("→ Cassette: {} ({})", config.path.display(), config.mode);
+                    Some(Arc::new(p)
+                        as Arc<
+                            dyn symbi_runtime::reasoning::inference::InferenceProvider,
+                        >)
+                }
+                Err(e) => {
+                    eprintln!("✗ {}", e);
+                    std::process::exit(1);
+                }
             }
-        };
+        }
+        Ok(None) => live,
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            std::process::exit(1);
+        }
+    };
+    let provider = match provider {
+        Some(p) => p,
+        None => {
+            eprintln!("✗ No LLM provider configured.");
+            eprintln!("  Set one of: OPENROUTER_API_KEY, OPENAI_API_KEY, or ANTHROPIC_API_KEY");
+            std::process::exit(1);
+        }
+    };
 
     println!("→ Running agent: {} ({})", agent_name, agent_path.display());
     if !description.is_empty() {
@@ -128,6 +158,8 @@ pub async fn run(matches: &ArgMatches) {
         circuit_breakers: Arc::new(CircuitBreakerRegistry::default()),
         journal: Arc::new(BufferedJournal::new(1000)),
         knowledge_bridge: None,
+        sandbox_sessions: None,
+        spend: None,
         delegation: None,
     };
 
@@ -46,6 +46,10 @@ pub async fn run() {
         println!("  • /swagger-ui → API documentation");
     }
 
+    // Spend ledger
+    #[cfg(feature = "cron")]
+    print_spend().await;
+
     // Resource usage
     println!("\n💾 Resources:");
     if let Some((cpu, mem)) = get_resource_usage() {
@@ -56,6 +60,47 @@ pub async fn run() {
     println!();
 }
 
+/// Print today's and this month's spend from the on-disk ledger, if any.
+#[cfg(feature = "cron")]
+async fn print_spend() {
+    use symbi_runtime::reasoning::spend::{day_start, month_start, period_spend, SqliteSpendStore};
+
+    let db_path = ["symbi.toml", "symbi.quick.toml"]
+        .iter()
+        .filter(|p| Path::new(p).exists())
+        .find_map(|p| symbi_runtime::config::Config::from_file(p).ok())
+        .and_then(|c| c.spend.and_then(|s| s.db_path))
+        .unwrap_or_else(SqliteSpendStore::default_path);
+    if !db_path.exists() {
+        return;
+    }
+    let store = match SqliteSpendStore::open(&db_path) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("\n⚠️  Could not open spend ledger: {}", e);
+            return;
+        }
+    };
+
+    println!("\n💰 Spend:");
+    let now = chrono::Utc::now();
+    for (label, since) in [("Today", day_start(now)), ("Month", month_start(now))] {
+        match period_spend(&store, since).await {
+            Ok(period) => {
+                println!("  • {}: ${:.4}", label, period.total_usd);
+                for scope in period.scopes.iter().take(5) {
+                    println!(
+                        "      {:<40} ${:.4}",
+                        scope.scope.to_string(),
+                        scope.cost_usd
+                    );
+                }
+            }
+            Err(e) => println!("  • {}: unavailable ({})", label, e),
+        }
+    }
+}
+
 fn is_port_listening(port: u16) -> bool {
     std::net::TcpStream::connect(format!("127.0.0.1:{}", port))
         .map(|_| true)
@@ -5,12 +5,12 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use symbi_channel_adapter::{
     AgentInvoker, BasicInteractionLogger, ChannelAdapterManager, ChannelConfig, ChatPlatform,
-    MattermostConfig, PlatformSettings, SlackConfig, TeamsConfig,
+    DlpPolicy, MattermostConfig, PlatformSettings, SlackConfig, TeamsConfig,
 };
 use symbi_runtime::api::server::{HttpApiConfig, HttpApiServer};
 use symbi_runtime::http_input::llm_client::LlmClient;
 use symbi_runtime::http_input::{start_http_input, HttpInputConfig};
-use symbi_runtime::reasoning::policy_bridge::ReasoningPolicyGate;
+use symbi_runtime::reasoning::policy_bridge::{gate_response, ReasoningPolicyGate};
 use symbi_runtime::types::{AgentId, SecurityTier};
 use symbi_runtime::AgentRuntime;
 use symbi_runtime::RuntimeConfig;
@@ -46,14 +46,56 @@ fn resolve_security_tier(source: &str, default_tier: SecurityTier) -> SecurityTi
         .unwrap_or(default_tier)
 }
 
-pub async fn run(matches: &ArgMatches) {
-    // Initialize tracing for structured logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
+#[cfg(feature = "otel-tracing")]
+type TracingGuard = Option<symbi_runtime::telemetry::TracingGuard>;
+#[cfg(not(feature = "otel-tracing"))]
+struct TracingGuard;
+
+/// Install the log subscriber. With the `otel-tracing` feature, spans are
+/// also exported over OTLP when an `OTEL_EXPORTER_OTLP_*` endpoint is set;
+/// the returned guard flushes them on shutdown.
+fn init_tracing() -> TracingGuard {
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let registry = tracing_subscriber::registry()
+        .with(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive(tracing::Level::INFO.into()),
         )
-        .init();
+        .with(tracing_subscriber::fmt::layer());
+
+    #[cfg(feature = "otel-tracing")]
+    {
+        use symbi_runtime::telemetry::{otlp_layer, TracingConfig};
+
+        let Some(config) = TracingConfig::from_env() else {
+            registry.init();
+            return None;
+        };
+        match otlp_layer(&config) {
+            Ok((layer, guard)) => {
+                registry.with(layer).init();
+                tracing::info!("Exporting traces via OTLP to {}", config.otlp.endpoint);
+                Some(guard)
+            }
+            Err(e) => {
+                registry.init();
+                tracing::warn!("OTLP trace export disabled: {}", e);
+                None
+            }
+        }
+    }
+    #[cfg(not(feature = "otel-tracing"))]
+    {
+        registry.init();
+        TracingGuard
+    }
+}
+
+pub async fn run(matches: &ArgMatches) {
+    // Initialize tracing for structured logging
+    let _tracing_guard = init_tracing();
 
     let port = matches
         .get_one::<String>("port")
@@ -70,7 +112,16 @@ pub async fn run(matches: &ArgMatches) {
         .map(|s| s.split(',').map(|o| o.trim().to_string()).collect())
         .unwrap_or_default();
     let http_audit = matches.get_flag("http-audit");
+    let resume_interrupted_runs = matches.get_flag("http-resume-runs");
     let serve_agents_md = matches.get_flag("serve-agents-md");
+    let serve_mcp = matches.get_flag("mcp");
+    let mcp_allowed_hosts: Vec<String> = matches
+        .get_one::<String>("mcp-allowed-hosts")
+        .map(|s| s.split(',').map(|h| h.trim().to_string()).collect())
+        .unwrap_or_default();
+    let api_keys_file = matches
+        .get_one::<String>("api-keys-file")
+        .map(PathBuf::from);
     let insecure_allow_all = matches.get_flag("insecure-allow-all")
         || std::env::var("SYMBI_INSECURE_ALLOW_ALL").as_deref() == Ok("1");
     let preset = matches.get_one::<String>("preset");
@@ -134,6 +185,7 @@ pub async fn run(matches: &ArgMatches) {
 
     // Scan agents directory
     let agents_found = scan_agents_directory();
+    report_semantic_errors(Path::new("agents"));
 
     // Load ToolClad manifests from tools/
     let toolclad_manifests =
@@ -268,6 +320,24 @@ pub async fn run(matches: &ArgMatches) {
         None
     };
 
+    // Turn DSL `webhook` blocks into dedicated HTTP input routes
+    let webhook_routes = load_dsl_webhooks(&loaded_agents, agent_id);
+    if !webhook_routes.is_empty() {
+        println!(
+            "✓ {} webhook route(s) loaded from DSL files",
+            webhook_routes.len()
+        );
+    }
+
+    // Attach DSL `memory` blocks to the agents declared alongside them
+    let agent_memory = load_dsl_memories(&loaded_agents);
+    if !agent_memory.is_empty() {
+        println!(
+            "✓ {} memory store(s) loaded from DSL files",
+            agent_memory.len()
+        );
+    }
+
     let http_config = HttpInputConfig {
         bind_address: http_bind.clone(),
         port: http_port_num,
@@ -283,6 +353,9 @@ pub async fn run(matches: &ArgMatches) {
         forward_headers: vec![],
         jwt_public_key_path: None,
         webhook_verify: None,
+        webhook_routes,
+        agent_memory,
+        resume_interrupted_runs,
     };
 
     // Use environment variable for Vault token, or disable Vault in dev mode
@@ -349,14 +422,86 @@ pub async fn run(matches: &ArgMatches) {
     };
 
     // Build the escalation queue (one shared instance) and read its config.
-    // Escalation config is sourced from symbi.toml / symbi.quick.toml when present;
-    // parse failures fall back to defaults (no approval channels).
-    let escalation_cfg = ["symbi.toml", "symbi.quick.toml"]
+    // Escalation and failover config are sourced from symbi.toml /
+    // symbi.quick.toml when present; parse failures fall back to defaults
+    // (no approval channels, no failover chain).
+    let project_cfg = ["symbi.toml", "symbi.quick.toml"]
         .iter()
         .filter(|p| Path::new(p).exists())
-        .find_map(|p| symbi_runtime::config::Config::from_file(p).ok())
-        .and_then(|c| c.escalation)
+        .find_map(|p| symbi_runtime::config::Config::from_file(p).ok());
+    let escalation_cfg = project_cfg
+        .as_ref()
+        .and_then(|c| c.escalation.clone())
         .unwrap_or_default();
+    let inference_provider =
+        build_inference_provider(project_cfg.as_ref().and_then(|c| c.failover.as_ref()));
+    let spend_tracker = project_cfg
+        .as_ref()
+        .and_then(|c| c.spend.as_ref())
+        .and_then(
+            |cfg| match symbi_runtime::reasoning::spend::SpendTracker::from_config(cfg) {
+                Ok(tracker) => Some(Arc::new(tracker)),
+                Err(e) => {
+                    eprintln!("⚠️  Spend tracking disabled: {}", e);
+                    None
+                }
+            },
+        );
+    // Durable reasoning journal: with `--http.resume-runs`, HTTP-input runs
+    // interrupted by a restart resume from their last checkpoint on the
+    // next `symbi up`.
+    #[cfg(feature = "cron")]
+    let journal_storage: Option<Arc<dyn symbi_runtime::reasoning::journal::JournalStorage>> = {
+        use symbi_runtime::reasoning::journal::SqliteJournalStorage;
+        let path = SqliteJournalStorage::default_path();
+        match SqliteJournalStorage::open(&path) {
+            Ok(storage) => {
+                println!("✓ Reasoning journal: {}", path.display());
+                Some(Arc::new(storage))
+            }
+            Err(e) => {
+                eprintln!("⚠️  Reasoning journal is in-memory only: {}", e);
+                None
+            }
+        }
+    };
+    #[cfg(not(feature = "cron"))]
+    let journal_storage: Option<Arc<dyn symbi_runtime::reasoning::journal::JournalStorage>> = None;
+    // Persistent sandbox sessions: each HTTP-input reasoning run gets its
+    // own session and the `sandbox_exec` tool, accounted with the runtime's
+    // resource manager and reaped once idle or past their lifetime.
+    let sandbox_sessions = project_cfg
+        .as_ref()
+        .and_then(|c| c.sandbox_sessions.as_ref())
+        .and_then(
+            |cfg| match symbi_runtime::sandbox::SandboxSessionManager::from_config(cfg) {
+                Ok(mut manager) => {
+                    if let Some(rt) = &runtime {
+                        manager = manager.with_resource_manager(rt.resource_manager.clone());
+                    }
+                    let manager = Arc::new(manager);
+                    manager.spawn_reaper(cfg.reap_interval());
+                    match cfg.kernel {
+                        Some(kernel) => {
+                            println!("✓ Sandbox sessions: {:?} tier, {} kernel", cfg.tier, kernel)
+                        }
+                        None => println!("✓ Sandbox sessions: {:?} tier", cfg.tier),
+                    }
+                    Some(manager)
+                }
+                Err(e) => {
+                    eprintln!("⚠️  Sandbox sessions disabled: {}", e);
+                    None
+                }
+            },
+        );
+    // The `run_cell` kernel only exists inside a session.
+    let sandbox_kernel = sandbox_sessions.as_ref().and_then(|_| {
+        project_cfg
+            .as_ref()
+            .and_then(|c| c.sandbox_sessions.as_ref())
+            .and_then(|cfg| cfg.kernel_config())
+    });
     let escalation_queue = Arc::new(symbi_runtime::escalation::EscalationQueue::new());
     let escalation_timeout = std::time::Duration::from_secs(
         std::env::var("SYMBIONT_ESCALATION_TIMEOUT")
@@ -425,7 +570,7 @@ pub async fn run(matches: &ArgMatches) {
 
     if any_adapter {
         // Build the AgentInvoker bridge (shared across all adapters)
-        let dsl_sources = scan_agent_dsl_sources();
+        let dsl_sources = Arc::new(scan_agent_dsl_sources());
         let llm_client = LlmClient::from_env().map(Arc::new);
 
         if llm_client.is_none() {
@@ -434,7 +579,7 @@ pub async fn run(matches: &ArgMatches) {
 
         let invoker: Arc<dyn AgentInvoker> = Arc::new(LlmAgentInvoker {
             llm_client,
-            dsl_sources: Arc::new(dsl_sources),
+            dsl_sources: Arc::clone(&dsl_sources),
             // Same gate Coordinator Chat receives below — no separate,
             // possibly-forgotten ladder for the chat path, and the same
             // "coordinator" policy surface, since both govern what the model
@@ -501,10 +646,19 @@ pub async fn run(matches: &ArgMatches) {
                 default_agent: default_agent.clone(),
             };
 
+            let dlp = match load_dsl_channel_dlp(&dsl_sources, ChatPlatform::Slack) {
+                Ok(dlp) => dlp,
+                Err(e) => {
+                    eprintln!("✗ Invalid Slack data classification: {}", e);
+                    return;
+                }
+            };
+
             let channel_config = ChannelConfig {
                 name: "slack".to_string(),
                 platform: ChatPlatform::Slack,
                 settings: PlatformSettings::Slack(slack_config),
+                dlp,
             };
 
             match manager.register_adapter(channel_config).await {
@@ -572,10 +726,19 @@ pub async fn run(matches: &ArgMatches) {
                 skip_jwks_verification: false,
             };
 
+            let dlp = match load_dsl_channel_dlp(&dsl_sources, ChatPlatform::Teams) {
+                Ok(dlp) => dlp,
+                Err(e) => {
+                    eprintln!("✗ Invalid Teams data classification: {}", e);
+                    return;
+                }
+            };
+
             let channel_config = ChannelConfig {
                 name: "teams".to_string(),
                 platform: ChatPlatform::Teams,
                 settings: PlatformSettings::Teams(teams_config),
+                dlp,
             };
 
             match manager.register_adapter(channel_config).await {
@@ -634,10 +797,19 @@ pub async fn run(matches: &ArgMatches) {
                 default_agent: default_agent.clone(),
             };
 
+            let dlp = match load_dsl_channel_dlp(&dsl_sources, ChatPlatform::Mattermost) {
+                Ok(dlp) => dlp,
+                Err(e) => {
+                    eprintln!("✗ Invalid Mattermost data classification: {}", e);
+                    return;
+                }
+            };
+
             let channel_config = ChannelConfig {
                 name: "mattermost".to_string(),
                 platform: ChatPlatform::Mattermost,
                 settings: PlatformSettings::Mattermost(mm_config),
+                dlp,
             };
 
             match manager.register_adapter(channel_config).await {
@@ -700,29 +872,42 @@ pub async fn run(matches: &ArgMatches) {
         enable_cors: true,
         enable_tracing: true,
         enable_rate_limiting: true,
-        api_keys_file: None,
+        api_keys_file,
         serve_agents_md,
     };
 
     let mut api_server =
         HttpApiServer::new(api_config).with_escalation_queue(escalation_queue.clone());
+    if let Some(ref tracker) = spend_tracker {
+        api_server = api_server.with_spend_tracker(tracker.clone());
+        println!("✓ Spend tracking enabled");
+    }
     if let Some(ref rt) = runtime {
         api_server = api_server.with_runtime_provider(rt.clone());
 
+        if serve_mcp {
+            api_server = api_server.with_mcp_router(crate::mcp_server::http_router(
+                rt.clone(),
+                policy_gate.clone(),
+                mcp_allowed_hosts,
+            ));
+            println!("✓ MCP endpoint enabled on /mcp");
+        }
+
         // Wire up Coordinator Chat if an LLM provider is available
-        if let Some(cloud_provider) =
-            symbi_runtime::reasoning::providers::cloud::CloudInferenceProvider::from_env()
-        {
-            let coordinator_state = Arc::new(
-                symbi_runtime::api::coordinator::CoordinatorState::new(
-                    Arc::new(cloud_provider),
-                    policy_gate.clone(),
-                    rt.clone(),
-                )
-                .with_rag("symbi-coordinator")
-                .await
-                .with_delegation(build_delegation_registry()),
-            );
+        if let Some(provider) = inference_provider.clone() {
+            let mut coordinator = symbi_runtime::api::coordinator::CoordinatorState::new(
+                provider,
+                policy_gate.clone(),
+                rt.clone(),
+            )
+            .with_rag("symbi-coordinator")
+            .await
+            .with_delegation(build_delegation_registry());
+            if let Some(ref tracker) = spend_tracker {
+                coordinator = coordinator.with_spend_tracker(tracker.clone());
+            }
+            let coordinator_state = Arc::new(coordinator);
             api_server = api_server.with_coordinator(coordinator_state);
             println!("✓ Coordinator Chat enabled on /ws/chat");
         } else {
@@ -730,16 +915,32 @@ pub async fn run(matches: &ArgMatches) {
         }
     }
 
+    // Resumed runs have no HTTP caller left to answer, so report them here.
+    let resumed_run_reports = resume_interrupted_runs.then(|| {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<serde_json::Value>();
+        tokio::spawn(async move {
+            while let Some(report) = rx.recv().await {
+                println!("✓ Resumed reasoning run: {}", report);
+            }
+        });
+        tx
+    });
+
     tokio::select! {
         result = api_server.start() => {
             if let Err(e) = result {
                 eprintln!("✗ API server error: {}", e);
             }
         },
-        _ = start_http_input(http_config, runtime.clone(), secrets_config, Some(http_input_policy_gate.clone())) => {},
+        _ = start_http_input(http_config, runtime.clone(), secrets_config, Some(http_input_policy_gate.clone()), inference_provider.clone(), spend_tracker.clone(), journal_storage.clone(), sandbox_sessions.clone(), sandbox_kernel, resumed_run_reports) => {},
         _ = tokio::signal::ctrl_c() => {}
     }
 
+    // Tear down session containers rather than leave them for Docker.
+    if let Some(sessions) = &sandbox_sessions {
+        sessions.close_all().await;
+    }
+
     // Shutdown chat adapters. We try to reclaim unique ownership of the manager
     // for the `&mut` shutdown. Note: when approval channels are configured, the
     // escalation queue (still held by the running api_server / coordinator) keeps
@@ -928,6 +1129,196 @@ async fn load_dsl_schedules(cron: &symbi_runtime::CronScheduler) -> usize {
     count
 }
 
+/// Scan DSL files in the agents directory for `webhook` blocks and turn each
+/// into an HTTP input route.
+///
+/// A webhook's `agent` is resolved against the agents loaded into the
+/// registry, by DSL-declared name or filename stem. A webhook without an
+/// `agent` invokes the agent declared in the same file; `default_agent` is
+/// the last resort. Webhooks naming an unknown agent are skipped with a
+/// warning.
+fn load_dsl_webhooks(
+    loaded_agents: &[(String, AgentId)],
+    default_agent: AgentId,
+) -> Vec<symbi_runtime::http_input::WebhookRouteConfig> {
+    use symbi_runtime::http_input::WebhookRouteConfig;
+
+    let sources = scan_agent_dsl_sources();
+    let by_stem: std::collections::HashMap<&str, AgentId> = loaded_agents
+        .iter()
+        .map(|(name, id)| (name.as_str(), *id))
+        .collect();
+
+    // Parse each file once; index agents by their DSL-declared names too.
+    let mut names = std::collections::HashMap::new();
+    let mut parsed = Vec::new();
+    for (filename, source) in &sources {
+        let stem = dsl::strip_symbi_extension(filename).unwrap_or(filename);
+        let tree = match dsl::parse_dsl(source) {
+            Ok(tree) => tree,
+            Err(e) => {
+                eprintln!("  ⚠ DSL parse error in {}: {}", filename, e);
+                continue;
+            }
+        };
+        let file_agent = by_stem.get(stem).copied();
+        if let (Some(name), Some(id)) = (dsl::extract_agent_name(&tree, source), file_agent) {
+            names.insert(name, id);
+        }
+        parsed.push((filename, source, tree, file_agent));
+    }
+
+    let mut routes = Vec::new();
+    for (filename, source, tree, file_agent) in parsed {
+        let webhooks = match dsl::extract_webhook_definitions(&tree, source) {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                eprintln!("  ⚠ Webhook extraction error in {}: {}", filename, e);
+                continue;
+            }
+        };
+        for webhook in webhooks {
+            let agent = match webhook.agent.as_deref() {
+                Some(name) => match names
+                    .get(name)
+                    .copied()
+                    .or_else(|| by_stem.get(name).copied())
+                {
+                    Some(id) => id,
+                    None => {
+                        eprintln!(
+                            "  ⚠ Webhook '{}' in {} targets unknown agent '{}', skipping",
+                            webhook.name, filename, name
+                        );
+                        continue;
+                    }
+                },
+                None => file_agent.unwrap_or(default_agent),
+            };
+            println!(
+                "  → webhook {} {} → {}",
+                webhook.name,
+                webhook.path,
+                webhook.agent.as_deref().unwrap_or(filename)
+            );
+            routes.push(WebhookRouteConfig::from_definition(&webhook, agent));
+        }
+    }
+    routes
+}
+
+/// Scan DSL files in the agents directory for `memory` blocks and attach
+/// each to the agent loaded from the same file.
+///
+/// A file attaches at most one memory block to its agent; extra blocks, and
+/// blocks in files without a loaded agent, are skipped with a warning.
+/// `symbi dsl` reports the same problems as semantic errors.
+fn load_dsl_memories(
+    loaded_agents: &[(String, AgentId)],
+) -> Vec<symbi_runtime::context::AgentMemoryConfig> {
+    use symbi_runtime::context::AgentMemoryConfig;
+
+    let by_stem: std::collections::HashMap<&str, AgentId> = loaded_agents
+        .iter()
+        .map(|(name, id)| (name.as_str(), *id))
+        .collect();
+
+    let mut memories = Vec::new();
+    for (filename, source) in scan_agent_dsl_sources() {
+        let stem = dsl::strip_symbi_extension(&filename).unwrap_or(&filename);
+        let tree = match dsl::parse_dsl(&source) {
+            Ok(tree) => tree,
+            Err(e) => {
+                eprintln!("  ⚠ DSL parse error in {}: {}", filename, e);
+                continue;
+            }
+        };
+        let definitions = match dsl::extract_memory_definitions(&tree, &source) {
+            Ok(definitions) => definitions,
+            Err(e) => {
+                eprintln!("  ⚠ Memory extraction error in {}: {}", filename, e);
+                continue;
+            }
+        };
+        let mut definitions = definitions.into_iter();
+        let Some(memory) = definitions.next() else {
+            continue;
+        };
+        let Some(agent) = by_stem.get(stem).copied() else {
+            eprintln!(
+                "  ⚠ Memory '{}' in {} has no agent in the same file, skipping",
+                memory.name, filename
+            );
+            continue;
+        };
+        for extra in definitions {
+            eprintln!(
+                "  ⚠ Memory '{}' in {} ignored: '{}' is already attached to {}",
+                extra.name, filename, memory.name, stem
+            );
+        }
+        println!(
+            "  → memory {} ({}) → {}",
+            memory.name,
+            memory.path.display(),
+            stem
+        );
+        memories.push(AgentMemoryConfig::from_definition(&memory, agent));
+    }
+    memories
+}
+
+/// Build the data classification policy for `platform` from DSL `channel`
+/// blocks' `dlp_profile` and `data_classification` entries.
+///
+/// The first block for the platform that declares either one is used; later
+/// ones are reported and ignored. Labels without a built-in detector are
+/// skipped with a warning; unknown profiles and invalid actions are errors,
+/// so a typo never silently turns a rule off.
+fn load_dsl_channel_dlp(
+    dsl_sources: &[(String, String)],
+    platform: ChatPlatform,
+) -> Result<Option<DlpPolicy>, String> {
+    let mut selected: Option<(String, DlpPolicy)> = None;
+    for (filename, source) in dsl_sources {
+        // Parse and extraction errors are reported when agents are loaded.
+        let Ok(tree) = dsl::parse_dsl(source) else {
+            continue;
+        };
+        let Ok(channels) = dsl::extract_channel_definitions(&tree, source) else {
+            continue;
+        };
+        for channel in channels {
+            if channel.platform.as_deref().and_then(parse_platform) != Some(platform)
+                || (channel.dlp_profile.is_none() && channel.data_classification.is_empty())
+            {
+                continue;
+            }
+            if let Some((ref first, _)) = selected {
+                eprintln!(
+                    "  ⚠ Channel '{}' in {} ignored: {} data classification comes from channel '{}'",
+                    channel.name, filename, platform, first
+                );
+                continue;
+            }
+            let policy = DlpPolicy::from_rules(
+                channel.dlp_profile.as_deref(),
+                channel
+                    .data_classification
+                    .iter()
+                    .map(|entry| (entry.category.as_str(), entry.action.as_str())),
+            )
+            .map_err(|e| format!("channel '{}' in {}: {}", channel.name, filename, e))?;
+            println!(
+                "  → data classification from channel {} → {}",
+                channel.name, platform
+            );
+            selected = Some((channel.name, policy));
+        }
+    }
+    Ok(selected.map(|(_, policy)| policy))
+}
+
 /// Scan DSL files in the agents directory, parse each one, create an
 /// `AgentConfig`, and register it with the runtime scheduler so that
 /// `/api/v1/agents` lists them and `/api/v1/agents/:id/execute` works.
@@ -998,6 +1389,31 @@ fn scan_agents_directory() -> Vec<String> {
     agents
 }
 
+/// Run the DSL semantic pass over the agents directory and print any
+/// unresolved references or type mismatches. These are warnings: the runtime
+/// still starts, but a schedule naming a missing agent will fail when it fires.
+fn report_semantic_errors(agents_dir: &Path) {
+    if !agents_dir.is_dir() {
+        return;
+    }
+    let workspace = match dsl::semantic::Workspace::from_dir(agents_dir) {
+        Ok(ws) => ws,
+        Err(e) => {
+            eprintln!(
+                "⚠️  Failed to read {} for validation: {}",
+                agents_dir.display(),
+                e
+            );
+            return;
+        }
+    };
+    for (path, diagnostics) in workspace.check() {
+        for diag in diagnostics {
+            eprintln!("⚠️  {}: {}", path.display(), diag);
+        }
+    }
+}
+
 /// Scan agents/ directory and return (filename, content) pairs for DSL files.
 fn scan_agent_dsl_sources() -> Vec<(String, String)> {
     let agents_dir = Path::new("agents");
@@ -1025,6 +1441,30 @@ fn scan_agent_dsl_sources() -> Vec<(String, String)> {
     sources
 }
 
+/// Resolve the inference provider shared by Coordinator Chat and the HTTP
+/// input loop: the `[failover]` chain when one is configured, otherwise the
+/// single provider auto-detected from the environment. A chain that cannot
+/// be built (unknown provider, no credentials) is reported and falls back
+/// to auto-detection.
+fn build_inference_provider(
+    failover: Option<&symbi_runtime::reasoning::providers::failover::FailoverConfig>,
+) -> Option<Arc<dyn symbi_runtime::reasoning::inference::InferenceProvider>> {
+    use symbi_runtime::reasoning::providers::{
+        cloud::CloudInferenceProvider, failover::FailoverInferenceProvider,
+    };
+
+    if let Some(config) = failover {
+        match FailoverInferenceProvider::from_config(config) {
+            Ok(chain) => {
+                println!("✓ Inference failover chain configured");
+                return Some(Arc::new(chain));
+            }
+            Err(e) => eprintln!("⚠️  {} — using the auto-detected provider", e),
+        }
+    }
+    CloudInferenceProvider::from_env().map(|p| Arc::new(p) as _)
+}
+
 /// Build a name -> system-prompt registry for in-process delegation targets,
 /// reusing the same `./agents` DSL scan as `scan_agent_dsl_sources` (no second
 /// directory read). The agent name is derived the same way the DSL declares
@@ -1176,34 +1616,13 @@ async fn gate_chat_response(
     agent_name: &str,
     content: String,
 ) -> Result<String, String> {
-    use symbi_runtime::reasoning::conversation::Conversation;
-    use symbi_runtime::reasoning::loop_types::{LoopDecision, LoopState, ProposedAction};
-
-    let agent_id = AgentId::new();
-    let proposed = ProposedAction::Respond {
-        content: content.clone(),
-    };
-    let state = LoopState::new(
-        agent_id,
-        Conversation::with_system(format!("chat-adapter:{agent_name}")),
-    );
-    match policy_gate
-        .evaluate_action(&agent_id, &proposed, &state)
-        .await
-    {
-        LoopDecision::Allow => Ok(content),
-        LoopDecision::Modify {
-            modified_action, ..
-        } => match *modified_action {
-            ProposedAction::Respond { content } => Ok(content),
-            other => Err(format!(
-                "policy gate replaced this chat response with a non-response action ({other:?}); withholding it"
-            )),
-        },
-        LoopDecision::Deny { reason } => {
-            Err(format!("response withheld by policy gate: {reason}"))
-        }
-    }
+    gate_response(
+        policy_gate.as_ref(),
+        AgentId::new(),
+        &format!("chat-adapter:{agent_name}"),
+        content,
+    )
+    .await
 }
 
 #[cfg(test)]
@@ -64,6 +64,12 @@ async fn main() {
                         .action(ArgAction::SetTrue)
                         .help("Log all HTTP requests to audit log"),
                 )
+                .arg(
+                    Arg::new("http-resume-runs")
+                        .long("http.resume-runs")
+                        .action(ArgAction::SetTrue)
+                        .help("Resume HTTP-input reasoning runs left unfinished by the last shutdown"),
+                )
                 .arg(
                     Arg::new("preset")
                         .long("preset")
@@ -171,6 +177,24 @@ async fn main() {
                         .action(ArgAction::SetTrue)
                         .help("Serve AGENTS.md at /agents.md and /.well-known/agents.md (auth-gated)"),
                 )
+                .arg(
+                    Arg::new("mcp")
+                        .long("mcp")
+                        .action(ArgAction::SetTrue)
+                        .help("Serve the MCP server over Streamable HTTP at /mcp on the API port (auth-gated)"),
+                )
+                .arg(
+                    Arg::new("mcp-allowed-hosts")
+                        .long("mcp.allowed-hosts")
+                        .value_name("HOSTS")
+                        .help("Comma-separated Host header allow-list for /mcp (default: localhost, 127.0.0.1, ::1)"),
+                )
+                .arg(
+                    Arg::new("api-keys-file")
+                        .long("api-keys-file")
+                        .value_name("PATH")
+                        .help("JSON file of hashed per-agent API keys for the runtime API and /mcp"),
+                )
                 .arg(
                     Arg::new("insecure-allow-all")
                         .long("insecure-allow-all")
@@ -306,6 +330,19 @@ async fn main() {
                         .help(
                             "Managed CLI: path to the symbi-claude-code plugin (overrides SYMBIONT_CLAUDE_PLUGIN_DIR / autodetect)",
                         ),
+                )
+                .arg(
+                    Arg::new("cassette")
+                        .long("cassette")
+                        .value_name("PATH")
+                        .help("Record or replay inference calls via this cassette file (overrides SYMBI_CASSETTE)"),
+                )
+                .arg(
+                    Arg::new("cassette-mode")
+                        .long("cassette-mode")
+                        .value_name("MODE")
+                        .value_parser(["record", "replay", "lenient"])
+                        .help("Cassette mode: record, replay (fail on unknown requests) or lenient (fall through to live); default replay"),
                 ),
         )
         .subcommand(
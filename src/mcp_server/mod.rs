@@ -1,8 +1,18 @@
 //! MCP Server implementation for Symbiont.
 //!
-//! Exposes Symbiont agents as MCP tools over stdio transport using the rmcp SDK.
-//! MCP clients (Claude Code, Cursor, etc.) can invoke agents, list available agents,
-//! parse DSL files, read agent definitions, and verify schemas via SchemaPin.
+//! Exposes Symbiont agents as MCP tools using the rmcp SDK, over stdio
+//! (`symbi mcp`) or Streamable HTTP mounted on the `symbi up` API server at
+//! `/mcp`. MCP clients (Claude Code, Cursor, etc.) can invoke agents, list
+//! available agents, parse DSL files, read agent definitions, and verify
+//! schemas via SchemaPin.
+//!
+//! Over HTTP every request carries the API server's bearer authentication;
+//! a request that reaches the server without it is refused. Tools then act
+//! only on agents registered with the runtime and within the caller's API
+//! key `agent_scope`, read agent DSL from the runtime's agent records rather
+//! than the `agents/` directory, and `invoke_agent` answers are checked by
+//! the same policy gate as the REST API and recorded in the agent's
+//! execution history.
 
 use std::future::Future;
 use std::sync::Arc;
@@ -12,12 +22,24 @@ use rmcp::{
     model::*,
     service::RequestContext,
     tool, tool_handler, tool_router,
-    transport::stdio,
+    transport::{
+        stdio,
+        streamable_http_server::{
+            session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+        },
+    },
     ErrorData as McpError, RoleServer, ServerHandler, ServiceExt,
 };
 use schemars::JsonSchema;
 use serde::Deserialize;
+use symbi_runtime::api::api_keys::ValidatedKey;
+use symbi_runtime::api::middleware::LegacyTokenAuth;
+use symbi_runtime::api::routes::strip_sensitive_sections;
+use symbi_runtime::api::RuntimeApiProvider;
 use symbi_runtime::http_input::llm_client::LlmClient;
+use symbi_runtime::reasoning::policy_bridge::{gate_response, ReasoningPolicyGate};
+use symbi_runtime::types::AgentId;
+
 use symbi_runtime::integrations::schemapin::{
     native_client::{NativeSchemaPinClient, SchemaPinClient},
     types::VerifyArgs,
@@ -68,12 +90,22 @@ pub struct SymbiMcpServer {
     llm_client: Option<Arc<LlmClient>>,
     agent_dsl_sources: Arc<Vec<(String, String)>>,
     schema_pin: Arc<NativeSchemaPinClient>,
+    http: Option<HttpBinding>,
     // Used by `#[tool_handler]`-generated code via `self.tool_router.call(...)`.
     // The dead-code pass cannot see the macro-expanded reference.
     #[allow(dead_code)]
     tool_router: ToolRouter<Self>,
 }
 
+/// Runtime services an HTTP-mounted server routes calls through, so remote
+/// callers get the same agent scoping, execution history and policy gate as
+/// the REST API.
+#[derive(Clone)]
+struct HttpBinding {
+    runtime: Arc<dyn RuntimeApiProvider>,
+    policy_gate: Arc<dyn ReasoningPolicyGate>,
+}
+
 // ---------------------------------------------------------------------------
 // Tool definitions
 // ---------------------------------------------------------------------------
@@ -88,17 +120,64 @@ impl SymbiMcpServer {
             llm_client,
             agent_dsl_sources,
             schema_pin,
+            http: None,
             tool_router: Self::tool_router(),
         }
     }
 
+    /// Serve remote callers: resolve agents through `runtime` and gate
+    /// responses with `policy_gate`.
+    pub fn with_runtime(
+        mut self,
+        runtime: Arc<dyn RuntimeApiProvider>,
+        policy_gate: Arc<dyn ReasoningPolicyGate>,
+    ) -> Self {
+        self.http = Some(HttpBinding {
+            runtime,
+            policy_gate,
+        });
+        self
+    }
+
+    /// Resolve `agent` to its runtime ID and check the caller's key is
+    /// scoped to it.
+    async fn authorize_agent(
+        binding: &HttpBinding,
+        context: &RequestContext<RoleServer>,
+        agent: &str,
+    ) -> Result<AgentId, String> {
+        let key = authenticated_caller(context)?;
+        let agents = binding
+            .runtime
+            .list_agents_detailed()
+            .await
+            .map_err(|e| format!("Failed to list agents: {}", e))?;
+        let id = agents
+            .iter()
+            .find(|a| a.name == agent)
+            .map(|a| a.id)
+            .ok_or_else(|| format!("Agent '{}' is not registered with the runtime.", agent))?;
+        if !key.can_access(&id) {
+            return Err(format!("API key is not scoped to agent '{}'.", agent));
+        }
+        Ok(id)
+    }
+
     #[tool(
         description = "Invoke a Symbiont agent with a prompt. Sends the prompt to the named agent, which uses LLM-backed reasoning governed by its DSL definition."
     )]
     async fn invoke_agent(
         &self,
         Parameters(params): Parameters<InvokeAgentParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
+        let registered = match &self.http {
+            Some(binding) => match Self::authorize_agent(binding, &context, &params.agent).await {
+                Ok(id) => Some((binding, id)),
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            },
+            None => None,
+        };
         let llm = match &self.llm_client {
             Some(c) => c.clone(),
             None => {
@@ -108,15 +187,23 @@ impl SymbiMcpServer {
             }
         };
 
-        // Find DSL sources matching the requested agent
-        let agent_sources: Vec<&(String, String)> = self
-            .agent_dsl_sources
-            .iter()
-            .filter(|(filename, _)| {
-                let stem = dsl::strip_symbi_extension(filename).unwrap_or(filename);
-                stem == params.agent
-            })
-            .collect();
+        // Find DSL sources for the requested agent: remote callers get the
+        // source the authorized agent was registered from.
+        let agent_sources: Vec<(String, String)> = match registered {
+            Some((binding, id)) => match binding.runtime.get_agent_dsl(id).await {
+                Ok(source) => vec![(params.agent.clone(), source)],
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+            },
+            None => self
+                .agent_dsl_sources
+                .iter()
+                .filter(|(filename, _)| {
+                    let stem = dsl::strip_symbi_extension(filename).unwrap_or(filename);
+                    stem == params.agent
+                })
+                .cloned()
+                .collect(),
+        };
 
         // Build system prompt from DSL context
         let mut system_parts: Vec<String> = Vec::new();
@@ -157,20 +244,72 @@ impl SymbiMcpServer {
 
         let system_prompt = system_parts.join("\n");
 
-        match llm.chat_completion(&system_prompt, &params.prompt).await {
-            Ok(response) => Ok(CallToolResult::success(vec![Content::text(response)])),
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-                "LLM invocation failed: {}",
-                e
-            ))])),
+        let outcome = match llm.chat_completion(&system_prompt, &params.prompt).await {
+            Ok(response) => match registered {
+                Some((binding, id)) => {
+                    let origin = format!("mcp:{}", params.agent);
+                    gate_response(binding.policy_gate.as_ref(), id, &origin, response)
+                        .await
+                        .map_err(|e| ("mcp_withheld", e))
+                }
+                None => Ok(response),
+            },
+            Err(e) => Err(("mcp_failed", format!("LLM invocation failed: {}", e))),
+        };
+
+        // The answer comes from the completion above, not from a scheduled
+        // run, so the agent's history records that call rather than starting
+        // the agent.
+        if let Some((binding, id)) = registered {
+            let status = match &outcome {
+                Ok(_) => "mcp_completed",
+                Err((status, _)) => status,
+            };
+            if let Err(e) = binding.runtime.record_execution(id, status).await {
+                tracing::warn!("failed to record MCP invocation of agent {}: {}", id, e);
+            }
+        }
+
+        match outcome {
+            Ok(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+            Err((_, e)) => Ok(CallToolResult::error(vec![Content::text(e)])),
         }
     }
 
     #[tool(description = "List available Symbiont agents found in the agents/ directory.")]
-    async fn list_agents(&self) -> Result<CallToolResult, McpError> {
+    async fn list_agents(
+        &self,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        // Scoped keys only see the registered agents they may use.
+        let visible: Option<Vec<String>> = match &self.http {
+            Some(binding) => match authenticated_caller(&context) {
+                Ok(key) if key.is_admin() => None,
+                Ok(key) => Some(
+                    binding
+                        .runtime
+                        .list_agents_detailed()
+                        .await
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|a| key.can_access(&a.id))
+                        .map(|a| a.name)
+                        .collect(),
+                ),
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            },
+            None => None,
+        };
+
         let agents: Vec<serde_json::Value> = self
             .agent_dsl_sources
             .iter()
+            .filter(|(filename, _)| {
+                let name = dsl::strip_symbi_extension(filename).unwrap_or(filename);
+                visible
+                    .as_ref()
+                    .is_none_or(|names| names.iter().any(|n| n == name))
+            })
             .map(|(filename, content)| {
                 let name = dsl::strip_symbi_extension(filename).unwrap_or(filename);
 
@@ -311,7 +450,25 @@ impl SymbiMcpServer {
     async fn get_agent_dsl(
         &self,
         Parameters(params): Parameters<GetAgentDslParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
+        // Remote callers get the source the authorized agent was registered
+        // from, never a file that merely shares its name.
+        if let Some(binding) = &self.http {
+            let source = match Self::authorize_agent(binding, &context, &params.agent).await {
+                Ok(id) => binding
+                    .runtime
+                    .get_agent_dsl(id)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+            return Ok(match source {
+                Ok(source) => CallToolResult::success(vec![Content::text(source)]),
+                Err(e) => CallToolResult::error(vec![Content::text(e)]),
+            });
+        }
+
         // First check pre-scanned sources
         for (filename, content) in self.agent_dsl_sources.iter() {
             let stem = dsl::strip_symbi_extension(filename).unwrap_or(filename);
@@ -351,7 +508,9 @@ impl SymbiMcpServer {
     )]
     async fn get_agents_md(&self) -> Result<CallToolResult, McpError> {
         match tokio::fs::read_to_string("AGENTS.md").await {
-            Ok(content) => Ok(CallToolResult::success(vec![Content::text(content)])),
+            Ok(content) => Ok(CallToolResult::success(vec![Content::text(
+                self.agents_md_view(content),
+            )])),
             Err(_) => Ok(CallToolResult::error(vec![Content::text(
                 "No AGENTS.md found in the working directory. Run 'symbi agents-md generate' to create one.",
             )])),
@@ -364,7 +523,22 @@ impl SymbiMcpServer {
     async fn verify_schema(
         &self,
         Parameters(params): Parameters<VerifySchemaParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
+        // Fetching a caller-chosen URL is a control-plane action, as with
+        // the REST API's admin-only routes.
+        if self.http.is_some() {
+            match authenticated_caller(&context) {
+                Ok(key) if key.is_admin() => {}
+                Ok(_) => {
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        "Scoped API keys may not verify schemas.",
+                    )]))
+                }
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            }
+        }
+
         // Write schema content to a temp file for the native client
         let tmp = match tempfile::NamedTempFile::new() {
             Ok(t) => t,
@@ -471,7 +645,7 @@ impl ServerHandler for SymbiMcpServer {
         if request.uri == "file:///AGENTS.md" {
             match tokio::fs::read_to_string("AGENTS.md").await {
                 Ok(content) => Ok(ReadResourceResult::new(vec![ResourceContents::text(
-                    content,
+                    self.agents_md_view(content),
                     "file:///AGENTS.md",
                 )])),
                 Err(_) => Err(McpError::new(
@@ -494,6 +668,45 @@ impl ServerHandler for SymbiMcpServer {
 // Helpers
 // ---------------------------------------------------------------------------
 
+impl SymbiMcpServer {
+    /// AGENTS.md as this server's callers may see it: remote callers get the
+    /// sensitive sections stripped, as from the REST `/agents.md` route.
+    fn agents_md_view(&self, content: String) -> String {
+        if self.http.is_some() {
+            strip_sensitive_sections(&content)
+        } else {
+            content
+        }
+    }
+}
+
+/// Key ID reported for callers authenticated by the legacy
+/// `SYMBIONT_API_TOKEN`.
+const LEGACY_TOKEN_KEY_ID: &str = "SYMBIONT_API_TOKEN";
+
+/// The API key that authenticated an HTTP request. The legacy
+/// `SYMBIONT_API_TOKEN`, which the REST API treats as admin, is reported as
+/// an unscoped key. `None` over stdio and for HTTP requests the API server
+/// did not authenticate.
+fn caller(context: &RequestContext<RoleServer>) -> Option<ValidatedKey> {
+    let parts = context.extensions.get::<axum::http::request::Parts>()?;
+    parts.extensions.get::<ValidatedKey>().cloned().or_else(|| {
+        parts
+            .extensions
+            .get::<LegacyTokenAuth>()
+            .map(|_| ValidatedKey {
+                key_id: LEGACY_TOKEN_KEY_ID.to_string(),
+                agent_scope: None,
+            })
+    })
+}
+
+/// The caller of an HTTP request, failing closed when the API server did
+/// not authenticate it (e.g. the router was mounted without its auth layer).
+fn authenticated_caller(context: &RequestContext<RoleServer>) -> Result<ValidatedKey, String> {
+    caller(context).ok_or_else(|| "Request was not authenticated by the API server.".to_string())
+}
+
 /// Scan the agents/ directory for `.symbi` (or legacy `.dsl`) files and
 /// return (filename, content) pairs.
 fn scan_agent_dsl_files() -> Vec<(String, String)> {
@@ -546,6 +759,27 @@ fn load_agents_md_context() -> Option<String> {
 // Entry point
 // ---------------------------------------------------------------------------
 
+/// Build the Streamable HTTP endpoint for mounting on the API server with
+/// `HttpApiServer::with_mcp_router`. `allowed_hosts` lists the `Host`
+/// values accepted (DNS-rebinding protection); an empty list keeps rmcp's
+/// loopback-only default.
+pub fn http_router(
+    runtime: Arc<dyn RuntimeApiProvider>,
+    policy_gate: Arc<dyn ReasoningPolicyGate>,
+    allowed_hosts: Vec<String>,
+) -> axum::Router {
+    let mut config = StreamableHttpServerConfig::default();
+    if !allowed_hosts.is_empty() {
+        config = config.with_allowed_hosts(allowed_hosts);
+    }
+    let service = StreamableHttpService::new(
+        move || Ok(SymbiMcpServer::new().with_runtime(runtime.clone(), policy_gate.clone())),
+        Arc::new(LocalSessionManager::default()),
+        config,
+    );
+    axum::Router::new().nest_service("/mcp", service)
+}
+
 /// Start the MCP server over stdio transport.
 pub async fn start_mcp_server() -> Result<(), Box<dyn std::error::Error>> {
     // Direct tracing to stderr — stdout is the MCP transport channel
@@ -562,3 +796,246 @@ pub async fn start_mcp_server() -> Result<(), Box<dyn std::error::Error>> {
     service.waiting().await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::service::RunningService;
+    use rmcp::transport::{
+        streamable_http_client::StreamableHttpClientTransportConfig, StreamableHttpClientTransport,
+    };
+    use rmcp::RoleClient;
+    use symbi_runtime::api::api_keys::{ApiKeyRecord, ApiKeyStore};
+    use symbi_runtime::api::middleware::auth_middleware;
+    use symbi_runtime::reasoning::policy_bridge::DefaultPolicyGate;
+    use symbi_runtime::types::{
+        AgentConfig, Capability, ExecutionMode, Priority, ResourceLimits, SecurityTier,
+    };
+    use symbi_runtime::{AgentRuntime, RuntimeConfig};
+
+    const ADMIN_KEY: &str = "admin.admin-secret";
+    const SCOPED_KEY: &str = "scoped.scoped-secret";
+
+    fn agent_config(name: &str) -> AgentConfig {
+        AgentConfig {
+            id: AgentId::new(),
+            name: name.to_string(),
+            dsl_source: format!("agent {} {{ }}", name),
+            execution_mode: ExecutionMode::Ephemeral,
+            security_tier: SecurityTier::Tier1,
+            resource_limits: ResourceLimits::default(),
+            capabilities: vec![Capability::Computation],
+            policies: vec![],
+            metadata: std::collections::HashMap::new(),
+            priority: Priority::Normal,
+        }
+    }
+
+    fn key_record(key_id: &str, secret: &str, agent_scope: Option<Vec<String>>) -> ApiKeyRecord {
+        ApiKeyRecord {
+            key_id: key_id.to_string(),
+            key_hash: ApiKeyStore::hash_key(secret).unwrap(),
+            agent_scope,
+            description: String::new(),
+            created_at: String::new(),
+            revoked: false,
+        }
+    }
+
+    /// Serve `http_router` on a loopback port with two registered agents,
+    /// `alpha` and `beta`, and a key store holding an admin key and a key
+    /// scoped to `alpha`. Without `authenticate` the router is mounted
+    /// bare, as if the auth layer had been forgotten.
+    async fn serve(authenticate: bool) -> String {
+        let runtime = AgentRuntime::new(RuntimeConfig::default())
+            .await
+            .expect("runtime construction");
+        let alpha = runtime
+            .scheduler
+            .schedule_agent(agent_config("alpha"))
+            .await
+            .unwrap();
+        runtime
+            .scheduler
+            .schedule_agent(agent_config("beta"))
+            .await
+            .unwrap();
+
+        let keys = tempfile::NamedTempFile::new().unwrap();
+        let records = vec![
+            key_record("admin", "admin-secret", None),
+            key_record("scoped", "scoped-secret", Some(vec![alpha.0.to_string()])),
+        ];
+        std::fs::write(keys.path(), serde_json::to_string(&records).unwrap()).unwrap();
+        let store = Arc::new(ApiKeyStore::load_from_file(keys.path()).unwrap());
+
+        let mut router = http_router(
+            Arc::new(runtime),
+            Arc::new(DefaultPolicyGate::new()),
+            vec![],
+        );
+        if authenticate {
+            router = router
+                .layer(axum::middleware::from_fn(auth_middleware))
+                .layer(axum::Extension(store));
+        }
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!(
+            "http://127.0.0.1:{}/mcp",
+            listener.local_addr().unwrap().port()
+        );
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        url
+    }
+
+    async fn connect(
+        url: &str,
+        token: Option<&str>,
+    ) -> Result<RunningService<RoleClient, ()>, String> {
+        let mut config = StreamableHttpClientTransportConfig::with_uri(url);
+        if let Some(token) = token {
+            config = config.auth_header(token);
+        }
+        ().serve(StreamableHttpClientTransport::from_config(config))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Call `tool` and return its text and whether it reported an error.
+    async fn call(
+        client: &RunningService<RoleClient, ()>,
+        tool: &str,
+        args: serde_json::Value,
+    ) -> (String, bool) {
+        let args = args.as_object().cloned().unwrap_or_default();
+        let result = client
+            .call_tool(CallToolRequestParams::new(tool.to_string()).with_arguments(args))
+            .await
+            .expect("tool call");
+        let text = result
+            .content
+            .iter()
+            .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        (text, result.is_error.unwrap_or(false))
+    }
+
+    #[tokio::test]
+    async fn http_router_requires_bearer_auth() {
+        let url = serve(true).await;
+        assert!(connect(&url, None).await.is_err());
+        assert!(connect(&url, Some("admin.wrong-secret")).await.is_err());
+
+        let client = connect(&url, Some(ADMIN_KEY)).await.expect("admin session");
+        let tools = client.list_all_tools().await.unwrap();
+        for name in [
+            "invoke_agent",
+            "get_agent_dsl",
+            "list_agents",
+            "verify_schema",
+        ] {
+            assert!(
+                tools.iter().any(|t| t.name == name),
+                "missing tool {}",
+                name
+            );
+        }
+        let _ = client.cancel().await;
+    }
+
+    #[tokio::test]
+    async fn scoped_keys_reach_only_their_agents() {
+        let url = serve(true).await;
+        let client = connect(&url, Some(SCOPED_KEY))
+            .await
+            .expect("scoped session");
+
+        // The source comes from the agent's record; there is no agents/
+        // directory to read it from.
+        let (text, is_error) = call(
+            &client,
+            "get_agent_dsl",
+            serde_json::json!({"agent": "alpha"}),
+        )
+        .await;
+        assert!(!is_error, "{}", text);
+        assert_eq!(text, "agent alpha { }");
+
+        let (text, is_error) = call(
+            &client,
+            "get_agent_dsl",
+            serde_json::json!({"agent": "beta"}),
+        )
+        .await;
+        assert!(is_error);
+        assert!(text.contains("not scoped to agent 'beta'"), "{}", text);
+
+        let (text, is_error) = call(
+            &client,
+            "invoke_agent",
+            serde_json::json!({"agent": "beta", "prompt": "hi"}),
+        )
+        .await;
+        assert!(is_error);
+        assert!(text.contains("not scoped to agent 'beta'"), "{}", text);
+
+        let (text, is_error) = call(
+            &client,
+            "get_agent_dsl",
+            serde_json::json!({"agent": "gamma"}),
+        )
+        .await;
+        assert!(is_error);
+        assert!(text.contains("not registered"), "{}", text);
+        let _ = client.cancel().await;
+    }
+
+    #[tokio::test]
+    async fn verify_schema_requires_an_admin_key() {
+        let url = serve(true).await;
+        let args = serde_json::json!({
+            "schema": "{}",
+            "public_key_url": "http://127.0.0.1:9/.well-known/schemapin.json",
+        });
+
+        let client = connect(&url, Some(SCOPED_KEY))
+            .await
+            .expect("scoped session");
+        let (text, is_error) = call(&client, "verify_schema", args.clone()).await;
+        assert!(is_error);
+        assert_eq!(text, "Scoped API keys may not verify schemas.");
+        let _ = client.cancel().await;
+
+        // An admin key gets past the gate to the verification itself.
+        let client = connect(&url, Some(ADMIN_KEY)).await.expect("admin session");
+        let (text, _) = call(&client, "verify_schema", args).await;
+        assert_ne!(text, "Scoped API keys may not verify schemas.");
+        let _ = client.cancel().await;
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_http_requests_fail_closed() {
+        let url = serve(false).await;
+        let client = connect(&url, None).await.expect("bare session");
+        for (tool, args) in [
+            ("get_agent_dsl", serde_json::json!({"agent": "alpha"})),
+            (
+                "invoke_agent",
+                serde_json::json!({"agent": "alpha", "prompt": "hi"}),
+            ),
+            ("list_agents", serde_json::json!({})),
+            (
+                "verify_schema",
+                serde_json::json!({"schema": "{}", "public_key_url": "http://127.0.0.1:9/"}),
+            ),
+        ] {
+            let (text, is_error) = call(&client, tool, args).await;
+            assert!(is_error, "{} answered: {}", tool, text);
+            assert_eq!(text, "Request was not authenticated by the API server.");
+        }
+        let _ = client.cancel().await;
+    }
+}
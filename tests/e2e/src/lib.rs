@@ -7,6 +7,10 @@
 //! - spawns the serve loop on a tokio task,
 //! - returns the live base URL and a shutdown handle.
 //!
+//! Tests that drive a reasoning loop take their inference provider from
+//! [`inference_provider`], so they replay a recorded cassette instead of
+//! calling a live LLM.
+//!
 //! The harness is only useful when the crate's `e2e` feature is enabled.
 //! Default `cargo test --workspace` skips the E2E suite entirely so the
 //! main CI stays fast; E2E is opt-in via
@@ -18,6 +22,10 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use symbi_runtime::api::server::{HttpApiConfig, HttpApiServer};
+use symbi_runtime::reasoning::inference::InferenceProvider;
+use symbi_runtime::reasoning::providers::cassette::{
+    CassetteConfig, CassetteMode, CassetteProvider,
+};
 use symbi_runtime::{AgentRuntime, RuntimeConfig};
 use tempfile::TempDir;
 use tokio::net::TcpListener;
@@ -155,3 +163,19 @@ pub fn test_client() -> reqwest::Client {
         .build()
         .expect("reqwest client")
 }
+
+/// Inference provider for tests that drive a reasoning loop.
+///
+/// Replays `default_cassette` strictly, so a request the cassette has no
+/// recording of fails the test instead of reaching the network.
+/// `SYMBI_CASSETTE` / `SYMBI_CASSETTE_MODE` override both the file and the
+/// mode; `record` and `lenient` use `live`.
+pub fn inference_provider(
+    default_cassette: impl Into<PathBuf>,
+    live: Option<Arc<dyn InferenceProvider>>,
+) -> Arc<dyn InferenceProvider> {
+    let config = CassetteConfig::from_env()
+        .expect("cassette env")
+        .unwrap_or_else(|| CassetteConfig::new(default_cassette, CassetteMode::Replay));
+    Arc::new(CassetteProvider::open(&config, live).expect("open cassette"))
+}
@@ -0,0 +1,165 @@
+//! E2E-8: record/replay inference cassettes.
+//!
+//! Records a two-step tool-using reasoning loop against a scripted
+//! provider, then replays it through `symbi_e2e::inference_provider` with
+//! no live provider at all. The replayed run must match the recorded one
+//! exactly, and a run whose requests were never recorded must fail instead
+//! of reaching a live model.
+
+#![cfg(feature = "e2e")]
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use symbi_runtime::reasoning::conversation::{Conversation, ConversationMessage};
+use symbi_runtime::reasoning::executor::DefaultActionExecutor;
+use symbi_runtime::reasoning::inference::{
+    FinishReason, InferenceError, InferenceOptions, InferenceProvider, InferenceResponse,
+    ToolCallRequest, ToolDefinition, Usage,
+};
+use symbi_runtime::reasoning::loop_types::{LoopConfig, LoopResult, TerminationReason};
+use symbi_runtime::reasoning::policy_bridge::DefaultPolicyGate;
+use symbi_runtime::reasoning::providers::cassette::{
+    CassetteConfig, CassetteMode, CassetteProvider,
+};
+use symbi_runtime::reasoning::reasoning_loop::ReasoningLoopRunner;
+use symbi_runtime::types::AgentId;
+use tempfile::tempdir;
+
+/// Stands in for a live LLM: one tool call, then a final answer.
+struct ScriptedProvider {
+    script: Mutex<VecDeque<InferenceResponse>>,
+}
+
+impl ScriptedProvider {
+    fn new() -> Arc<Self> {
+        let usage = Usage {
+            prompt_tokens: 40,
+            completion_tokens: 10,
+            total_tokens: 50,
+            cached_prompt_tokens: 0,
+        };
+        Arc::new(Self {
+            script: Mutex::new(VecDeque::from([
+                InferenceResponse {
+                    content: String::new(),
+                    tool_calls: vec![ToolCallRequest {
+                        id: "call_1".into(),
+                        name: "lookup".into(),
+                        arguments: r#"{"city":"Paris"}"#.into(),
+                    }],
+                    finish_reason: FinishReason::ToolCalls,
+                    usage: usage.clone(),
+                    model: "scripted".into(),
+                },
+                InferenceResponse {
+                    content: "Paris is sunny.".into(),
+                    tool_calls: vec![],
+                    finish_reason: FinishReason::Stop,
+                    usage,
+                    model: "scripted".into(),
+                },
+            ])),
+        })
+    }
+}
+
+#[async_trait]
+impl InferenceProvider for ScriptedProvider {
+    async fn complete(
+        &self,
+        _conversation: &Conversation,
+        _options: &InferenceOptions,
+    ) -> Result<InferenceResponse, InferenceError> {
+        self.script
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| InferenceError::Provider("script exhausted".into()))
+    }
+
+    fn provider_name(&self) -> &str {
+        "scripted"
+    }
+
+    fn default_model(&self) -> &str {
+        "scripted"
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        false
+    }
+}
+
+async fn run_loop(provider: Arc<dyn InferenceProvider>, prompt: &str) -> LoopResult {
+    let runner = ReasoningLoopRunner::builder()
+        .provider(provider)
+        .executor(Arc::new(DefaultActionExecutor::default()))
+        .policy_gate(Arc::new(DefaultPolicyGate::permissive_for_dev_only()))
+        .build();
+    let mut conv = Conversation::with_system("You answer weather questions.");
+    conv.push(ConversationMessage::user(prompt));
+    let config = LoopConfig {
+        max_iterations: 5,
+        tool_definitions: vec![ToolDefinition {
+            name: "lookup".into(),
+            description: "Look up the weather".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }],
+        ..Default::default()
+    };
+    runner.run(AgentId::new(), conv, config).await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn recorded_loop_replays_without_a_live_provider() {
+    let td = tempdir().unwrap();
+    let path = td.path().join("weather.cassette.json");
+
+    let recorder = CassetteProvider::open(
+        &CassetteConfig::new(&path, CassetteMode::Record),
+        Some(ScriptedProvider::new() as Arc<dyn InferenceProvider>),
+    )
+    .expect("open cassette for recording");
+    let recorded = run_loop(Arc::new(recorder), "Weather in Paris?").await;
+    assert!(
+        matches!(recorded.termination_reason, TerminationReason::Completed),
+        "recording run: {:?}",
+        recorded.termination_reason
+    );
+
+    let replayed = run_loop(
+        symbi_e2e::inference_provider(&path, None),
+        "Weather in Paris?",
+    )
+    .await;
+    assert!(matches!(
+        replayed.termination_reason,
+        TerminationReason::Completed
+    ));
+    assert_eq!(replayed.output, recorded.output);
+    assert_eq!(replayed.output, "Paris is sunny.");
+    assert_eq!(replayed.iterations, recorded.iterations);
+    assert_eq!(
+        replayed.total_usage.total_tokens,
+        recorded.total_usage.total_tokens
+    );
+
+    // A request the cassette never saw fails rather than going live.
+    let unrecorded = run_loop(
+        symbi_e2e::inference_provider(&path, None),
+        "Weather in Rome?",
+    )
+    .await;
+    match unrecorded.termination_reason {
+        TerminationReason::Error { message } => {
+            assert!(message.contains("no recording"), "got: {message}")
+        }
+        other => panic!("expected a strict-replay error, got {other:?}"),
+    }
+}
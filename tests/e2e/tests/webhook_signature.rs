@@ -49,6 +49,8 @@ async fn spawn_http_input(secret: &str) -> (String, tokio::task::JoinHandle<()>)
             provider: "github".to_string(),
             secret: secret.to_string(),
         }),
+        webhook_routes: vec![],
+        agent_memory: vec![],
     };
     let server = HttpInputServer::new(config).with_runtime(runtime);
     let handle = tokio::spawn(async move {